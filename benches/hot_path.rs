@@ -0,0 +1,269 @@
+//! 交易热路径基准：指令构建、签名、序列化、报价计算、ATA 推导
+//!
+//! 运行：`cargo bench --bench hot_path`
+
+use base64::Engine;
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use futures::executor::block_on;
+use sol_trade_sdk::common::fast_fn::get_associated_token_address_with_program_id_fast;
+use sol_trade_sdk::common::spl_associated_token_account::get_associated_token_address_with_program_id;
+use sol_trade_sdk::common::{Bps, GasFeeStrategy};
+use sol_trade_sdk::constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use sol_trade_sdk::instruction::pumpfun::PumpFunInstructionBuilder;
+use sol_trade_sdk::instruction::pumpswap::PumpSwapInstructionBuilder;
+use sol_trade_sdk::swqos::TradeType;
+use sol_trade_sdk::trading::InstructionBuilder;
+use sol_trade_sdk::trading::common::{InstructionLayout, assemble_instructions};
+use sol_trade_sdk::trading::core::params::{
+    DexParamEnum, PumpFunParams, PumpSwapParams, SwapParams,
+};
+use sol_trade_sdk::utils::calc::pumpfun::get_buy_token_amount_from_sol_amount;
+use sol_trade_sdk::utils::calc::pumpswap::buy_quote_input_internal;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+
+const INPUT_LAMPORTS: u64 = 100_000_000;
+
+fn swap_params(
+    payer: Arc<Keypair>,
+    trade_type: TradeType,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    protocol_params: DexParamEnum,
+) -> SwapParams {
+    SwapParams {
+        rpc: None,
+        rpc_provider: None,
+        payer,
+        trade_type,
+        input_mint,
+        input_token_program: None,
+        output_mint,
+        output_token_program: None,
+        input_amount: Some(INPUT_LAMPORTS),
+        slippage_basis_points: Some(Bps::new(500)),
+        address_lookup_table_account: None,
+        recent_blockhash: Some(Hash::new_unique()),
+        last_valid_block_height: None,
+        wait_transaction_confirmed: false,
+        protocol_params,
+        open_seed_optimize: false,
+        swqos_clients: vec![],
+        middleware_manager: None,
+        durable_nonce: None,
+        with_tip: true,
+        create_input_mint_ata: true,
+        close_input_mint_ata: true,
+        create_output_mint_ata: true,
+        close_output_mint_ata: false,
+        fixed_output_amount: None,
+        gas_fee_strategy: GasFeeStrategy::new(),
+        simulate: false,
+        on_transaction_signed: None,
+        callback_execution_mode: None,
+        enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_ledger: None,
+        trade_store: None,
+        preflight: Default::default(),
+        instruction_layout: InstructionLayout::default(),
+        multisig: None,
+        fee_payer: None,
+        submission_queue: None,
+        urgency: Default::default(),
+        confirm: Default::default(),
+        cancellation_token: None,
+        fanout: None,
+    }
+}
+
+fn pumpfun_params(mint: Pubkey) -> DexParamEnum {
+    DexParamEnum::PumpFun(
+        PumpFunParams::from_dev_trade(
+            mint,
+            35_000_000_000_000,
+            1_000_000_000,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            None,
+            Pubkey::new_unique(),
+            TOKEN_PROGRAM,
+        )
+        .expect("dev trade params"),
+    )
+}
+
+fn pumpswap_params(mint: Pubkey) -> DexParamEnum {
+    DexParamEnum::PumpSwap(PumpSwapParams::new(
+        Pubkey::new_unique(),
+        mint,
+        WSOL_TOKEN_ACCOUNT,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        200_000_000_000_000,
+        80_000_000_000,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        TOKEN_PROGRAM,
+        TOKEN_PROGRAM,
+        Pubkey::new_unique(),
+    ))
+}
+
+fn bench_instruction_build(c: &mut Criterion) {
+    let payer = Arc::new(Keypair::new());
+    let mint = Pubkey::new_unique();
+    let mut group = c.benchmark_group("build_instructions");
+
+    let buy =
+        swap_params(payer.clone(), TradeType::Buy, WSOL_TOKEN_ACCOUNT, mint, pumpfun_params(mint));
+    group.bench_function(BenchmarkId::new("pumpfun", "buy"), |b| {
+        b.iter(|| block_on(PumpFunInstructionBuilder.build_buy_instructions(black_box(&buy))))
+    });
+    let sell =
+        swap_params(payer.clone(), TradeType::Sell, mint, WSOL_TOKEN_ACCOUNT, pumpfun_params(mint));
+    group.bench_function(BenchmarkId::new("pumpfun", "sell"), |b| {
+        b.iter(|| block_on(PumpFunInstructionBuilder.build_sell_instructions(black_box(&sell))))
+    });
+
+    let buy =
+        swap_params(payer.clone(), TradeType::Buy, WSOL_TOKEN_ACCOUNT, mint, pumpswap_params(mint));
+    group.bench_function(BenchmarkId::new("pumpswap", "buy"), |b| {
+        b.iter(|| block_on(PumpSwapInstructionBuilder.build_buy_instructions(black_box(&buy))))
+    });
+    let sell = swap_params(payer, TradeType::Sell, mint, WSOL_TOKEN_ACCOUNT, pumpswap_params(mint));
+    group.bench_function(BenchmarkId::new("pumpswap", "sell"), |b| {
+        b.iter(|| block_on(PumpSwapInstructionBuilder.build_sell_instructions(black_box(&sell))))
+    });
+    group.finish();
+}
+
+fn bench_sign_and_serialize(c: &mut Criterion) {
+    let payer = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let params = swap_params(
+        Arc::new(payer.insecure_clone()),
+        TradeType::Buy,
+        WSOL_TOKEN_ACCOUNT,
+        mint,
+        pumpfun_params(mint),
+    );
+    let business = block_on(PumpFunInstructionBuilder.build_buy_instructions(&params)).unwrap();
+    let tip_accounts = [Pubkey::new_unique()];
+    let instructions = assemble_instructions(
+        &payer,
+        200_000,
+        1_000_000,
+        business,
+        true,
+        &tip_accounts,
+        0.001,
+        None,
+        false,
+        InstructionLayout::default(),
+    )
+    .unwrap();
+    let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+        &instructions,
+        Some(&payer.pubkey()),
+        &Hash::new_unique(),
+    ));
+    let message_bytes = message.serialize();
+    let tx = VersionedTransaction {
+        signatures: vec![payer.sign_message(&message_bytes)],
+        message: message.clone(),
+    };
+
+    let mut group = c.benchmark_group("transaction");
+    group.bench_function("compile_message", |b| {
+        b.iter(|| {
+            Message::new_with_blockhash(
+                black_box(&instructions),
+                Some(&payer.pubkey()),
+                &Hash::default(),
+            )
+        })
+    });
+    group.bench_function("sign", |b| b.iter(|| payer.sign_message(black_box(&message_bytes))));
+    group.bench_function("serialize_bincode", |b| {
+        b.iter(|| bincode::serialize(black_box(&tx)).unwrap())
+    });
+    group.bench_function("serialize_base64", |b| {
+        b.iter(|| {
+            base64::engine::general_purpose::STANDARD
+                .encode(bincode::serialize(black_box(&tx)).unwrap())
+        })
+    });
+    group.finish();
+}
+
+fn bench_quote_math(c: &mut Criterion) {
+    let creator = Pubkey::new_unique();
+    let mut group = c.benchmark_group("quote");
+    group.bench_function("pumpfun_buy_amount", |b| {
+        b.iter(|| {
+            get_buy_token_amount_from_sol_amount(
+                black_box(1_073_000_000_000_000),
+                black_box(30_000_000_000),
+                black_box(793_100_000_000_000),
+                creator,
+                black_box(INPUT_LAMPORTS),
+            )
+        })
+    });
+    group.bench_function("pumpswap_buy_quote_input", |b| {
+        b.iter(|| {
+            buy_quote_input_internal(
+                black_box(INPUT_LAMPORTS),
+                Bps::new(500),
+                black_box(200_000_000_000_000),
+                black_box(80_000_000_000),
+                &creator,
+            )
+        })
+    });
+    group.finish();
+}
+
+fn bench_ata_derivation(c: &mut Criterion) {
+    let wallet = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let mut group = c.benchmark_group("ata");
+    group.bench_function("fast_fn", |b| {
+        b.iter(|| {
+            get_associated_token_address_with_program_id_fast(
+                black_box(&wallet),
+                black_box(&mint),
+                &TOKEN_PROGRAM,
+            )
+        })
+    });
+    group.bench_function("find_program_address", |b| {
+        b.iter(|| {
+            get_associated_token_address_with_program_id(
+                black_box(&wallet),
+                black_box(&mint),
+                &TOKEN_PROGRAM,
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_instruction_build,
+    bench_sign_and_serialize,
+    bench_quote_math,
+    bench_ata_derivation
+);
+criterion_main!(benches);
@@ -1,3 +1,5 @@
+use sol_trade_sdk::common::keystore;
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::common::address_lookup::fetch_address_lookup_table_account;
 use sol_trade_sdk::common::{GasFeeStrategy, TradeConfig};
 use sol_trade_sdk::{
@@ -11,7 +13,6 @@ use sol_trade_sdk::{
 };
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
 use solana_streamer_sdk::match_event;
 use solana_streamer_sdk::streaming::event_parser::common::filter::EventTypeFilter;
 use solana_streamer_sdk::streaming::event_parser::common::EventType;
@@ -22,10 +23,7 @@ use solana_streamer_sdk::streaming::yellowstone_grpc::{AccountFilter, Transactio
 use solana_streamer_sdk::streaming::YellowstoneGrpc;
 use std::{
     str::FromStr,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 // Global static flag to ensure transaction is executed only once
@@ -103,12 +101,13 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
 /// Initializes a new SolanaTrade client with configuration
 async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     println!("🚀 Initializing SolanaTrade client...");
-    let payer = Keypair::from_base58_string("use_your_payer_keypair_here");
+    // Payer is loaded from an encrypted keystore created by keystore::save_keypair
+    let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
     let rpc_url = "http://127.0.0.1:8899".to_string();
     let commitment = CommitmentConfig::confirmed();
     let swqos_configs: Vec<SwqosConfig> = vec![SwqosConfig::Default(rpc_url.clone())];
     let trade_config = TradeConfig::new(rpc_url, swqos_configs, commitment);
-    let solana_trade = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade = SolanaTrade::from_keystore("wallet.json", &passphrase, trade_config).await?;
     println!("✅ SolanaTrade client initialized successfully!");
     Ok(solana_trade)
 }
@@ -120,7 +119,7 @@ async fn pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> AnyResul
 
     let client = create_solana_trade_client().await?;
     let mint_pubkey = trade_info.mint;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = Some(Bps::new(100));
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let lookup_table_key = Pubkey::from_str("use_your_lookup_table_key_here").unwrap();
@@ -128,7 +127,8 @@ async fn pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> AnyResul
         fetch_address_lookup_table_account(&client.rpc, &lookup_table_key).await.ok();
 
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // Buy tokens
     println!("Buying tokens from PumpFun...");
@@ -140,6 +140,7 @@ async fn pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> AnyResul
         input_token_amount: buy_sol_amount,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpFun(PumpFunParams::from_trade(
             trade_info.bonding_curve,
             trade_info.associated_bonding_curve,
@@ -166,6 +167,15 @@ async fn pumpfun_copy_trade_with_grpc(trade_info: PumpFunTradeEvent) -> AnyResul
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     client.buy(buy_params).await?;
 
@@ -1,3 +1,5 @@
+use sol_trade_sdk::common::keystore;
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
 use sol_trade_sdk::common::TradeConfig;
 use sol_trade_sdk::{
@@ -10,17 +12,13 @@ use sol_trade_sdk::{
     SolanaTrade,
 };
 use solana_commitment_config::CommitmentConfig;
-use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
 use solana_streamer_sdk::streaming::event_parser::common::filter::EventTypeFilter;
 use solana_streamer_sdk::streaming::event_parser::common::EventType;
 use solana_streamer_sdk::streaming::event_parser::protocols::bonk::BonkTradeEvent;
 use solana_streamer_sdk::streaming::event_parser::{Protocol, UnifiedEvent};
 use solana_streamer_sdk::{match_event, streaming::ShredStreamGrpc};
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Atomic flag to ensure the sniper trade is executed only once
 static ALREADY_EXECUTED: AtomicBool = AtomicBool::new(false);
@@ -79,12 +77,13 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
 /// Initializes a new SolanaTrade client with configuration
 async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     println!("🚀 Initializing SolanaTrade client...");
-    let payer = Keypair::from_base58_string("use_your_payer_keypair_here");
+    // Payer is loaded from an encrypted keystore created by keystore::save_keypair
+    let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
     let rpc_url = "http://127.0.0.1:8899".to_string();
     let commitment = CommitmentConfig::confirmed();
     let swqos_configs: Vec<SwqosConfig> = vec![SwqosConfig::Default(rpc_url.clone())];
     let trade_config = TradeConfig::new(rpc_url, swqos_configs, commitment);
-    let solana_trade = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade = SolanaTrade::from_keystore("wallet.json", &passphrase, trade_config).await?;
     println!("✅ SolanaTrade client initialized successfully!");
     Ok(solana_trade)
 }
@@ -96,11 +95,12 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
 
     let client = create_solana_trade_client().await?;
     let mint_pubkey = trade_info.base_token_mint;
-    let slippage_basis_points = Some(300);
+    let slippage_basis_points = Some(Bps::new(300));
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let token_type = if trade_info.quote_token_mint == sol_trade_sdk::constants::USD1_TOKEN_ACCOUNT
     {
@@ -119,6 +119,7 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         input_token_amount: buy_sol_amount,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::Bonk(BonkParams::from_dev_trade(
             trade_info.exact_in,
             trade_info.amount_in,
@@ -144,6 +145,15 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     client.buy(buy_params).await?;
 
@@ -170,6 +180,7 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         input_token_amount: amount_token,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::Bonk(BonkParams::immediate_sell(
             trade_info.base_token_program,
             trade_info.platform_config,
@@ -190,6 +201,15 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     client.sell(sell_params).await?;
 
@@ -1,4 +1,5 @@
 use clap::Parser;
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     common::{
         fast_fn::get_associated_token_address_with_program_id_fast_use_seed,
@@ -631,15 +632,17 @@ async fn handle_buy_pumpfun(
     let sol_lamports = sol_str_to_lamports(sol_amount.to_string().as_str()).unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params = TradeBuyParams {
         dex_type: DexType::PumpFun,
         input_token_type: TradeTokenType::SOL,
         mint: mint_pubkey,
         input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpFun(param),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -653,6 +656,15 @@ async fn handle_buy_pumpfun(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _)) => {
@@ -688,15 +700,17 @@ async fn handle_buy_pumpswap(
     let sol_lamports = sol_str_to_lamports(sol_amount.to_string().as_str()).unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params = TradeBuyParams {
         dex_type: DexType::PumpSwap,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(param),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -710,6 +724,15 @@ async fn handle_buy_pumpswap(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _)) => {
@@ -744,15 +767,17 @@ async fn handle_buy_bonk(
     let sol_lamports = sol_str_to_lamports(sol_amount.to_string().as_str()).unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params = TradeBuyParams {
         dex_type: DexType::Bonk,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::Bonk(param),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -766,6 +791,15 @@ async fn handle_buy_bonk(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _)) => {
@@ -804,15 +838,17 @@ async fn handle_buy_raydium_v4(
     let sol_lamports = sol_str_to_lamports(sol_amount.to_string().as_str()).unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params = TradeBuyParams {
         dex_type: DexType::RaydiumAmmV4,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::RaydiumAmmV4(param),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -826,6 +862,15 @@ async fn handle_buy_raydium_v4(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _)) => {
@@ -864,15 +909,17 @@ async fn handle_buy_raydium_cpmm(
     let sol_lamports = sol_str_to_lamports(sol_amount.to_string().as_str()).unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params = TradeBuyParams {
         dex_type: DexType::RaydiumCpmm,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: sol_lamports,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::RaydiumCpmm(param),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -886,6 +933,15 @@ async fn handle_buy_raydium_cpmm(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _)) => {
@@ -1034,15 +1090,17 @@ async fn handle_sell_pumpfun(
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let sell_params = TradeSellParams {
         dex_type: DexType::PumpFun,
         output_token_type: TradeTokenType::SOL,
         mint: mint_pubkey,
         input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::PumpFun(param),
         address_lookup_table_account: None,
@@ -1057,6 +1115,15 @@ async fn handle_sell_pumpfun(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     match client.sell(sell_params).await {
@@ -1095,15 +1162,17 @@ async fn handle_sell_pumpswap(
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let sell_params = TradeSellParams {
         dex_type: DexType::PumpSwap,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::PumpSwap(param),
         address_lookup_table_account: None,
@@ -1118,6 +1187,15 @@ async fn handle_sell_pumpswap(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _)) => {
@@ -1155,15 +1233,17 @@ async fn handle_sell_bonk(
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let sell_params = TradeSellParams {
         dex_type: DexType::Bonk,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::Bonk(param),
         address_lookup_table_account: None,
@@ -1178,6 +1258,15 @@ async fn handle_sell_bonk(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _)) => {
@@ -1218,15 +1307,17 @@ async fn handle_sell_raydium_v4(
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let sell_params = TradeSellParams {
         dex_type: DexType::RaydiumAmmV4,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::RaydiumAmmV4(param),
         address_lookup_table_account: None,
@@ -1241,6 +1332,15 @@ async fn handle_sell_raydium_v4(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _)) => {
@@ -1281,15 +1381,17 @@ async fn handle_sell_raydium_cpmm(
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let sell_params = TradeSellParams {
         dex_type: DexType::RaydiumCpmm,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
         input_token_amount: amount as u64,
-        slippage_basis_points: slippage,
+        slippage_basis_points: slippage.map(Bps::try_new).transpose()?,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::RaydiumCpmm(param),
         address_lookup_table_account: None,
@@ -1304,6 +1406,15 @@ async fn handle_sell_raydium_cpmm(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _)) => {
@@ -1,5 +1,6 @@
 use sol_trade_sdk::{
     common::gas_fee_strategy::{GasFeeStrategy, HighLowFeeParams},
+    common::{ComputeUnits, FeeSpec, Lamports, MicroLamports, Sol},
     swqos::{SwqosType, TradeType},
 };
 
@@ -12,7 +13,8 @@ async fn main() {
 
     // Set global strategy
     println!("1. Set global strategy");
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // Print all strategies
     println!("\n2. Print all strategies");
@@ -24,13 +26,8 @@ async fn main() {
 
     // Add normal fee strategy for SwqosType::Default
     println!("\n4. Add normal fee strategy for SwqosType::Default");
-    gas_fee_strategy.set_normal_fee_strategy(
-        SwqosType::Default,
-        150000, // cu_limit
-        500000, // cu_price
-        0.0,    // buy_tip
-        0.0,    // sell_tip
-    );
+    let rpc_fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Lamports::ZERO);
+    gas_fee_strategy.set_normal_fees(&[SwqosType::Default], rpc_fees, rpc_fees);
 
     // Add high-low fee strategy for SwqosType::Jito on Buy
     println!("\n5. Add high-low fee strategy for SwqosType::Jito on Buy");
@@ -52,13 +49,8 @@ async fn main() {
 
     // Add normal fee strategy for SwqosType::Jito on Buy (will override previous high-low strategy)
     println!("\n7. Add normal fee strategy for SwqosType::Jito (will override previous high-low strategy)");
-    gas_fee_strategy.set_normal_fee_strategy(
-        SwqosType::Jito,
-        150000, // cu_limit
-        500000, // cu_price
-        0.0001, // buy_tip
-        0.0001, // sell_tip
-    );
+    let jito_fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.0001));
+    gas_fee_strategy.set_normal_fees(&[SwqosType::Jito], jito_fees, jito_fees);
 
     // Print all strategies
     println!("\n8. Print all current strategies");
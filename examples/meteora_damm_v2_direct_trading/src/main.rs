@@ -1,3 +1,5 @@
+use sol_trade_sdk::common::keystore;
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     common::{
         fast_fn::get_associated_token_address_with_program_id_fast_use_seed, AnyResult, TradeConfig,
@@ -10,22 +12,22 @@ use sol_trade_sdk::{
     SolanaTrade, TradeTokenType,
 };
 use solana_commitment_config::CommitmentConfig;
-use solana_sdk::signature::Keypair;
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
-use std::{str::FromStr, sync::Arc};
+use std::str::FromStr;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Testing Metaora Damm V2 trading...");
 
     let client = create_solana_trade_client().await?;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = Some(Bps::new(100));
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
     let pool = Pubkey::from_str("7dVri3qjYD3uobSZL3Zth8vSCgU6r6R2nvFsh7uVfDte").unwrap();
     let mint_pubkey = Pubkey::from_str("PRVT6TB7uss3FrUd2D9xs2zqDBsa3GbMJMwCQsgmeta").unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // Buy tokens
     println!("Buying tokens from Metaora Damm V2...");
@@ -37,6 +39,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         input_token_amount: input_token_amount,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::MeteoraDammV2(
             MeteoraDammV2Params::from_pool_address_by_rpc(&client.rpc, &pool).await?,
         ),
@@ -52,6 +55,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     client.buy(buy_params).await?;
 
@@ -77,6 +89,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         input_token_amount: amount_token,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::MeteoraDammV2(
             MeteoraDammV2Params::from_pool_address_by_rpc(&client.rpc, &pool).await?,
@@ -93,6 +106,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     client.sell(sell_params).await?;
 
@@ -104,12 +126,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// Initializes a new SolanaTrade client with configuration
 async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     println!("🚀 Initializing SolanaTrade client...");
-    let payer = Keypair::from_base58_string("your_payer_keypair_here");
+    // Payer is loaded from an encrypted keystore created by keystore::save_keypair
+    let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
     let rpc_url = "http://127.0.0.1:8899".to_string();
     let commitment = CommitmentConfig::confirmed();
     let swqos_configs: Vec<SwqosConfig> = vec![SwqosConfig::Default(rpc_url.clone())];
     let trade_config = TradeConfig::new(rpc_url, swqos_configs, commitment);
-    let solana_trade = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade = SolanaTrade::from_keystore("wallet.json", &passphrase, trade_config).await?;
     println!("✅ SolanaTrade client initialized successfully!");
     Ok(solana_trade)
 }
@@ -1,4 +1,6 @@
 use anyhow::Result;
+use sol_trade_sdk::common::keystore;
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     common::{AnyResult, TradeConfig},
     swqos::SwqosConfig,
@@ -10,8 +12,8 @@ use sol_trade_sdk::{
     SolanaTrade, TradeTokenType,
 };
 use solana_commitment_config::CommitmentConfig;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair};
-use std::{str::FromStr, sync::Arc};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::str::FromStr;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -59,12 +61,13 @@ impl InstructionMiddleware for CustomMiddleware {
 /// Initializes a new SolanaTrade client with configuration
 async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     println!("🚀 Initializing SolanaTrade client...");
-    let payer = Keypair::from_base58_string("use_your_payer_keypair_here");
+    // Payer is loaded from an encrypted keystore created by keystore::save_keypair
+    let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
     let rpc_url = "http://127.0.0.1:8899".to_string();
     let commitment = CommitmentConfig::confirmed();
     let swqos_configs: Vec<SwqosConfig> = vec![SwqosConfig::Default(rpc_url.clone())];
     let trade_config = TradeConfig::new(rpc_url, swqos_configs, commitment);
-    let solana_trade = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade = SolanaTrade::from_keystore("wallet.json", &passphrase, trade_config).await?;
     println!("✅ SolanaTrade client initialized successfully!");
     Ok(solana_trade)
 }
@@ -77,12 +80,13 @@ async fn test_middleware() -> AnyResult<()> {
     client = client.with_middleware_manager(middleware_manager);
     let mint_pubkey = Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn")?;
     let buy_sol_cost = 100_000;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = Some(Bps::new(100));
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
     let pool_address = Pubkey::from_str("539m4mVWt6iduB6W8rDGPMarzNCMesuqY5eUTiiYHAgR")?;
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params = sol_trade_sdk::TradeBuyParams {
         dex_type: DexType::PumpSwap,
@@ -91,6 +95,7 @@ async fn test_middleware() -> AnyResult<()> {
         input_token_amount: buy_sol_cost,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(&client.rpc, &pool_address).await?,
         ),
@@ -106,8 +111,17 @@ async fn test_middleware() -> AnyResult<()> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
     client.buy(buy_params).await?;
-    println!("tip: This transaction will not succeed because we're using a test account. You can modify the code to initialize the payer with your own private key");
+    println!("tip: wallet.json must be a keystore of your own funded wallet, otherwise this transaction will not succeed");
     Ok(())
 }
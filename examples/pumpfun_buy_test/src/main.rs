@@ -1,8 +1,9 @@
+use sol_trade_sdk::common::{keystore, Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
+    SolanaTrade, TradeBuyParams, TradeTokenType,
     common::{AnyResult, TradeConfig},
     swqos::{SwqosConfig, SwqosRegion},
     trading::{core::params::PumpFunParams, factory::DexType},
-    SolanaTrade, TradeBuyParams, TradeTokenType,
 };
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{
@@ -18,13 +19,16 @@ async fn main() -> AnyResult<()> {
 
     println!("\n🚀 初始化 PumpFun 交易客户端...\n");
 
-    // 生成临时测试密钥对
-    let payer_key = env::var("PAYER_KEYPAIR").unwrap_or_else(|_| "GENERATE_NEW".to_string());
-    let payer = if payer_key == "GENERATE_NEW" {
-        println!("📝 生成临时测试密钥对...");
-        Keypair::new()
-    } else {
-        Keypair::from_base58_string(&payer_key)
+    // 设置 PAYER_KEYSTORE 时从加密密钥文件加载（口令取自 WALLET_PASSPHRASE），否则生成临时测试密钥对
+    let payer = match env::var("PAYER_KEYSTORE") {
+        Ok(path) => {
+            let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
+            keystore::load_keypair(path, &passphrase)?
+        },
+        Err(_) => {
+            println!("📝 生成临时测试密钥对...");
+            Keypair::new()
+        },
     };
     println!("📝 钱包地址: {}", payer.pubkey());
 
@@ -71,13 +75,9 @@ async fn main() -> AnyResult<()> {
 
     // 设置 PumpFun 的 gas 策略
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(
-        200000,  // buy_cu_limit
-        1000000, // sell_cu_limit
-        500000,  // buy_cu_price
-        500000,  // sell_cu_price
-        0.005,   // buy_tip
-        0.01,    // sell_tip
+    gas_fee_strategy.set_global_fees(
+        FeeSpec::new(ComputeUnits(200_000), MicroLamports(500_000), Sol(0.005)),
+        FeeSpec::new(ComputeUnits(1_000_000), MicroLamports(500_000), Sol(0.01)),
     );
 
     println!("✅ 客户端初始化完成\n");
@@ -91,6 +91,7 @@ async fn main() -> AnyResult<()> {
     let slippage = env::var("SLIPPAGE")
         .unwrap_or_else(|_| "1000".to_string())
         .parse::<u64>()
+        .map(Bps::new)
         .expect("Invalid slippage");
 
     println!("🔍 获取最新区块哈希...");
@@ -102,7 +103,7 @@ async fn main() -> AnyResult<()> {
     println!("================================");
     println!("🪙 代币: {}", mint);
     println!("💰 金额: {} lamports", buy_amount);
-    println!("📊 滑点: {} basis points", slippage);
+    println!("📊 滑点: {}", slippage);
     println!("================================\n");
 
     // PumpFun买入参数 (买入不需要特殊参数，使用零值)
@@ -128,6 +129,7 @@ async fn main() -> AnyResult<()> {
         input_token_amount: buy_amount,
         slippage_basis_points: Some(slippage),
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: sol_trade_sdk::trading::core::params::DexParamEnum::PumpFun(params),
         address_lookup_table_account: None,
         wait_transaction_confirmed: false, // 不等待确认，测试最快提交速度
@@ -141,6 +143,15 @@ async fn main() -> AnyResult<()> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     println!("⏱️  开始执行买入流程...");
@@ -1,8 +1,9 @@
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     common::{fast_fn::get_associated_token_address_with_program_id_fast_use_seed, TradeConfig},
     swqos::SwqosConfig,
     trading::{
-        core::params::{DexParamEnum, PumpSwapParams},
+        core::params::DexParamEnum,
         factory::DexType,
     },
     SolanaTrade, TradeTokenType,
@@ -18,13 +19,13 @@ async fn main() -> Result<(), anyhow::Error> {
     println!("Testing PumpSwap trading...");
 
     let client = create_solana_trade_client().await?;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = Some(Bps::new(100));
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
-    let pool = Pubkey::from_str("539m4mVWt6iduB6W8rDGPMarzNCMesuqY5eUTiiYHAgR").unwrap();
     let mint_pubkey = Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn").unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // Buy tokens
     println!("Buying tokens from PumpSwap...");
@@ -36,9 +37,10 @@ async fn main() -> Result<(), anyhow::Error> {
         input_token_amount: buy_sol_amount,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::PumpSwap(
-            PumpSwapParams::from_pool_address_by_rpc(&client.rpc, &pool).await?,
-        ),
+        last_valid_block_height: None,
+        // 首次调用按 mint 查找池子并缓存静态账户，之后只刷新储备
+        extension_params: DexParamEnum::from_mint(&client.rpc, DexType::PumpSwap, &mint_pubkey)
+            .await?,
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
         create_input_token_ata: true,
@@ -51,6 +53,15 @@ async fn main() -> Result<(), anyhow::Error> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success, signatures, trade_error) = client.buy(buy_params).await?;
@@ -84,10 +95,10 @@ async fn main() -> Result<(), anyhow::Error> {
         input_token_amount: amount_token,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
-        extension_params: DexParamEnum::PumpSwap(
-            PumpSwapParams::from_pool_address_by_rpc(&client.rpc, &pool).await?,
-        ),
+        extension_params: DexParamEnum::from_mint(&client.rpc, DexType::PumpSwap, &mint_pubkey)
+            .await?,
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
         create_output_token_ata: true,
@@ -100,6 +111,15 @@ async fn main() -> Result<(), anyhow::Error> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success, signatures, trade_error) = client.sell(sell_params).await?;
@@ -1,3 +1,4 @@
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     common::{fast_fn::get_associated_token_address_with_program_id_fast_use_seed, TradeConfig},
     swqos::SwqosConfig,
@@ -18,7 +19,7 @@ async fn main() -> Result<(), anyhow::Error> {
     println!("Testing PumpSwap trading...");
 
     let client = create_solana_trade_client().await?;
-    let slippage_basis_points = Some(100);
+    let slippage_basis_points = Some(Bps::new(100));
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
     let pool = Pubkey::from_str("2xHRmdXSKURh8CkMERbNhYCiQGHGsjLWMgckEP9bmLKK").unwrap();
     let mint_pubkey = Pubkey::from_str("Ew8KqgSitYucieR5KnSAL2SUFspcwA8AgSuZ5xWspump").unwrap();
@@ -27,7 +28,8 @@ async fn main() -> Result<(), anyhow::Error> {
     // let mint_pubkey = Pubkey::from_str("3iC63FgnB7EhcPaiSaC51UkVweeBDkqu17SaRyy2pump").unwrap();
 
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // Buy tokens
     println!("Buying tokens from PumpSwap...");
@@ -39,6 +41,7 @@ async fn main() -> Result<(), anyhow::Error> {
         input_token_amount: buy_sol_amount,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(&client.rpc, &pool).await?,
         ),
@@ -54,6 +57,15 @@ async fn main() -> Result<(), anyhow::Error> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success, signatures, trade_error) = client.buy(buy_params).await?;
@@ -87,6 +99,7 @@ async fn main() -> Result<(), anyhow::Error> {
         input_token_amount: amount_token,
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(&client.rpc, &pool).await?,
@@ -103,6 +116,15 @@ async fn main() -> Result<(), anyhow::Error> {
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success, signatures, trade_error) = client.sell(sell_params).await?;
@@ -172,12 +194,12 @@ async fn create_solana_trade_client() -> Result<SolanaTrade, anyhow::Error> {
 
 // async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
 //     println!("🚀 Initializing SolanaTrade client...");
-//     let payer = Keypair::from_base58_string("use_your_payer_keypair_here");
+//     let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
 //     let rpc_url = "https://api.mainnet-beta.solana.com".to_string();
 //     let commitment = CommitmentConfig::confirmed();
 //     let swqos_configs: Vec<SwqosConfig> = vec![SwqosConfig::Default(rpc_url.clone())];
 //     let trade_config = TradeConfig::new(rpc_url, swqos_configs, commitment);
-//     let solana_trade = SolanaTrade::new(Arc::new(payer), trade_config).await;
+//     let solana_trade = SolanaTrade::from_keystore("wallet.json", &passphrase, trade_config).await?;
 //     println!("✅ SolanaTrade client initialized successfully!");
 //     Ok(solana_trade)
 // }
@@ -14,12 +14,11 @@
 //! - Shared RPC connection pool and SWQOS clients
 
 use sol_trade_sdk::{
-    common::{CallbackExecutionMode, InfrastructureConfig, TradeConfig},
+    common::{keystore, CallbackExecutionMode, InfrastructureConfig, TradeConfig},
     swqos::{SwqosConfig, SwqosRegion},
     TradingClient, TradingInfrastructure,
 };
 use solana_commitment_config::CommitmentConfig;
-use solana_sdk::signature::Keypair;
 use std::sync::Arc;
 
 #[tokio::main]
@@ -41,16 +40,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Infrastructure created with {} SWQOS clients", infrastructure.swqos_clients.len());
 
     // Step 2: Create multiple TradingClients sharing the same infrastructure (fast)
-    let wallet_keys = vec![
-        "wallet1_base58_private_key_here",
-        "wallet2_base58_private_key_here",
-        "wallet3_base58_private_key_here",
-    ];
+    // Wallets are encrypted keystores created by keystore::save_keypair
+    let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
+    let wallet_files = vec!["wallet1.json", "wallet2.json", "wallet3.json"];
 
     let mut clients = Vec::new();
-    for (i, key) in wallet_keys.iter().enumerate() {
+    for (i, path) in wallet_files.iter().enumerate() {
         println!("Creating client for wallet {}...", i + 1);
-        let payer = Arc::new(Keypair::from_base58_string(key));
+        let payer = Arc::new(keystore::load_keypair(path, &passphrase)?);
 
         // Create TradeConfig for each wallet
         let trade_config =
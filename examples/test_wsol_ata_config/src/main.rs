@@ -1,3 +1,4 @@
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::common::TradeConfig;
 use sol_trade_sdk::TradeTokenType;
 use sol_trade_sdk::{
@@ -231,7 +232,8 @@ async fn execute_buy(
     simulate: bool,
 ) -> AnyResult<()> {
     let gas_fee_strategy = sol_trade_sdk::common::GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150000, 150000, 500000, 500000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let recent_blockhash = client.rpc.get_latest_blockhash().await?;
 
@@ -240,8 +242,9 @@ async fn execute_buy(
         input_token_type: TradeTokenType::WSOL,
         mint: Pubkey::from_str("4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R")?, // 示例 mint
         input_token_amount: 100_000,                                             // 0.0001 SOL
-        slippage_basis_points: Some(100),
+        slippage_basis_points: Some(Bps::new(100)),
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(params.clone()),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -255,6 +258,15 @@ async fn execute_buy(
         on_transaction_signed: None,
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success, signatures, error) = client.buy(buy_params).await?;
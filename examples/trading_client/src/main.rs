@@ -1,11 +1,10 @@
+use sol_trade_sdk::common::keystore;
 use sol_trade_sdk::{
     common::{AnyResult, TradeConfig},
     swqos::{SwqosConfig, SwqosRegion},
     SolanaTrade,
 };
 use solana_commitment_config::CommitmentConfig;
-use solana_sdk::signature::Keypair;
-use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -18,7 +17,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// Initializes a new SolanaTrade client with configuration
 async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
     println!("Creating SolanaTrade client...");
-    let payer = Keypair::from_base58_string("use_your_payer_keypair_here");
+    // Payer is loaded from an encrypted keystore created by keystore::save_keypair
+    let passphrase = keystore::passphrase_from_env("WALLET_PASSPHRASE")?;
     let rpc_url = "http://127.0.0.1:8899".to_string();
     println!("rpc_url: {}", rpc_url);
     let commitment = CommitmentConfig::processed();
@@ -34,7 +34,8 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         SwqosConfig::Astralane("your api_token".to_string(), SwqosRegion::Frankfurt, None),
     ];
     let trade_config = TradeConfig::new(rpc_url, swqos_configs, commitment);
-    let solana_trade_client = SolanaTrade::new(Arc::new(payer), trade_config).await;
+    let solana_trade_client =
+        SolanaTrade::from_keystore("wallet.json", &passphrase, trade_config).await?;
     println!("SolanaTrade client created successfully!");
     Ok(solana_trade_client)
 }
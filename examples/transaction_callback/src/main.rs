@@ -4,12 +4,13 @@
 
 use anyhow::Result;
 use futures::future::BoxFuture;
+use sol_trade_sdk::common::Bps;
 use sol_trade_sdk::{
+    CallbackContext, DexType, SolanaTrade, TradeBuyParams, TradeTokenType,
+    TransactionLifecycleCallback,
     common::TradeConfig,
     swqos::SwqosConfig,
     trading::core::params::{DexParamEnum, PumpSwapParams},
-    CallbackContext, DexType, SolanaTrade, TradeBuyParams, TradeTokenType,
-    TransactionLifecycleCallback,
 };
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
@@ -31,6 +32,17 @@ impl TransactionLifecycleCallback for MyDatabaseCallback {
             println!("  - Timestamp: {}", context_clone.timestamp_ns);
             println!("  - With Tip: {}", context_clone.with_tip);
             println!("  - Tip Amount: {} SOL", context_clone.tip_amount);
+            if let Some(details) = &context_clone.details {
+                println!("  - Dex: {:?}, Mint: {}", details.dex_type, details.mint);
+                println!(
+                    "  - Input: {:?}, Min Output: {:?}, Expected Output: {:?}, Slippage: {:?}",
+                    details.input_amount,
+                    details.output_amount(),
+                    details.expected_output(),
+                    details.slippage_basis_points
+                );
+            }
+            println!("  - Size: {} bytes", context_clone.serialized_transaction().len());
 
             // 示例：使用 SQLx 保存到 PostgreSQL
             // sqlx::query!(
@@ -116,8 +128,9 @@ async fn main() -> Result<()> {
         input_token_type: TradeTokenType::SOL,
         mint: Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn")?,
         input_token_amount: 100_000,
-        slippage_basis_points: Some(100),
+        slippage_basis_points: Some(Bps::new(100)),
         recent_blockhash: Some(client.get_rpc().get_latest_blockhash().await?),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(
                 client.get_rpc(),
@@ -137,6 +150,15 @@ async fn main() -> Result<()> {
         on_transaction_signed: Some(custom_callback),
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     println!("执行买入交易（模拟模式）...");
@@ -154,8 +176,9 @@ async fn main() -> Result<()> {
         input_token_type: TradeTokenType::SOL,
         mint: Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn")?,
         input_token_amount: 100_000,
-        slippage_basis_points: Some(100),
+        slippage_basis_points: Some(Bps::new(100)),
         recent_blockhash: Some(client.get_rpc().get_latest_blockhash().await?),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(
                 client.get_rpc(),
@@ -175,6 +198,15 @@ async fn main() -> Result<()> {
         on_transaction_signed: Some(callback_with_pool),
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     println!("执行买入交易（模拟模式）...");
@@ -190,8 +222,9 @@ async fn main() -> Result<()> {
         input_token_type: TradeTokenType::SOL,
         mint: Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn")?,
         input_token_amount: 100_000,
-        slippage_basis_points: Some(100),
+        slippage_basis_points: Some(Bps::new(100)),
         recent_blockhash: Some(client.get_rpc().get_latest_blockhash().await?),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(
             PumpSwapParams::from_pool_address_by_rpc(
                 client.get_rpc(),
@@ -211,6 +244,15 @@ async fn main() -> Result<()> {
         on_transaction_signed: None, // 不使用回调
         callback_execution_mode: None,
         enable_jito_sandwich_protection: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     println!("执行买入交易（模拟模式，无回调）...");
@@ -0,0 +1,96 @@
+//! 同步 API（`blocking` 特性）
+//!
+//! 为无法方便托管 tokio 运行时的脚本、插件提供同步封装：[`BlockingTradingClient`]
+//! 内部持有一个多线程运行时（租金更新等后台任务在其中运行），`buy` / `sell` / `quote`
+//! 与 WSOL 包装/解包方法阻塞直到异步调用完成。
+//!
+//! 不能在 tokio 运行时内部调用（`block_on` 会 panic），异步代码请直接使用 [`TradingClient`]。
+
+use crate::common::TradeConfig;
+use crate::trading::arbitrage::{ArbVenue, quote_venue};
+use crate::{TradeBuyParams, TradeResult, TradeSellParams, TradingClient};
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// [`TradingClient`] 的同步封装
+pub struct BlockingTradingClient {
+    client: TradingClient,
+    runtime: Runtime,
+}
+
+impl BlockingTradingClient {
+    /// 创建内部运行时（`worker_threads` 个工作线程）并初始化交易客户端
+    pub fn with_worker_threads(
+        payer: Arc<Keypair>,
+        trade_config: TradeConfig,
+        worker_threads: usize,
+    ) -> Result<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(worker_threads.max(1))
+            .thread_name("sol-trade-blocking")
+            .enable_all()
+            .build()?;
+        let client = runtime.block_on(TradingClient::new(payer, trade_config));
+        Ok(Self { client, runtime })
+    }
+
+    /// 使用 2 个工作线程创建
+    pub fn new(payer: Arc<Keypair>, trade_config: TradeConfig) -> Result<Self> {
+        Self::with_worker_threads(payer, trade_config, 2)
+    }
+
+    /// 内部的异步客户端
+    pub fn client(&self) -> &TradingClient {
+        &self.client
+    }
+
+    /// 在内部运行时上阻塞执行任意异步操作
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// 同步买入，见 [`TradingClient::buy_with_result`]
+    pub fn buy(&self, params: TradeBuyParams) -> Result<TradeResult> {
+        self.block_on(self.client.buy_with_result(params))
+    }
+
+    /// 同步卖出，见 [`TradingClient::sell_with_result`]
+    pub fn sell(&self, params: TradeSellParams) -> Result<TradeResult> {
+        self.block_on(self.client.sell_with_result(params))
+    }
+
+    /// 在单个池子上报价：`is_buy` 为 true 时输入 SOL 得到 `mint`，否则输入 `mint` 得到 SOL
+    pub fn quote(
+        &self,
+        venue: &ArbVenue,
+        mint: &Pubkey,
+        amount_in: u64,
+        is_buy: bool,
+    ) -> Result<u64> {
+        self.block_on(quote_venue(&self.client.rpc, venue, mint, amount_in, is_buy))
+    }
+
+    /// 见 [`TradingClient::wrap_sol_to_wsol`]
+    pub fn wrap_sol_to_wsol(&self, amount: u64) -> Result<String> {
+        self.block_on(self.client.wrap_sol_to_wsol(amount))
+    }
+
+    /// 见 [`TradingClient::wrap_wsol_to_sol`]
+    pub fn wrap_wsol_to_sol(&self, amount: u64) -> Result<String> {
+        self.block_on(self.client.wrap_wsol_to_sol(amount))
+    }
+
+    /// 见 [`TradingClient::close_wsol`]
+    pub fn close_wsol(&self) -> Result<String> {
+        self.block_on(self.client.close_wsol())
+    }
+
+    /// 见 [`TradingClient::create_wsol_ata`]
+    pub fn create_wsol_ata(&self) -> Result<String> {
+        self.block_on(self.client.create_wsol_ata())
+    }
+}
@@ -1,7 +1,19 @@
+//! 地址查找表（ALT）读取与使用跟踪
+//!
+//! 策略轮换 ALT 后，旧表仍占用租金。[`LookupTableManager`] 记录每张表的登记时间、最近使用时间、
+//! 使用次数与被策略引用的次数：策略开始使用某张表时 [`retain`](LookupTableManager::retain)，
+//! 停用时 [`release`](LookupTableManager::release)；构建交易时自动 [`record_use`](LookupTableManager::record_use)。
+//! 未被引用且闲置超过阈值的表由
+//! [`TradingClient::deactivate_and_close_stale_tables`](crate::TradingClient::deactivate_and_close_stale_tables)
+//! 先停用、冷却期过后关闭并取回租金。
+
 use crate::common::SolanaRpcClient;
 use anyhow::Result;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use solana_address_lookup_table_interface::state::AddressLookupTable;
 use solana_sdk::{message::AddressLookupTableAccount, pubkey::Pubkey};
+use std::time::{Duration, Instant};
 
 pub async fn fetch_address_lookup_table_account(
     rpc: &SolanaRpcClient,
@@ -15,3 +27,142 @@ pub async fn fetch_address_lookup_table_account(
     };
     Ok(address_lookup_table_account)
 }
+
+/// 单张 ALT 的跟踪信息
+#[derive(Debug, Clone)]
+pub struct TrackedLookupTable {
+    pub address: Pubkey,
+    pub registered_at: Instant,
+    pub last_used: Instant,
+    /// 构建交易时使用的次数
+    pub use_count: u64,
+    /// 引用该表的策略数
+    pub references: usize,
+    /// 已发送停用指令的 slot
+    pub deactivated_at_slot: Option<u64>,
+}
+
+impl TrackedLookupTable {
+    fn new(address: Pubkey) -> Self {
+        let now = Instant::now();
+        Self {
+            address,
+            registered_at: now,
+            last_used: now,
+            use_count: 0,
+            references: 0,
+            deactivated_at_slot: None,
+        }
+    }
+
+    /// 未被引用且闲置不少于 `max_idle`
+    pub fn is_stale(&self, max_idle: Duration) -> bool {
+        self.references == 0 && self.last_used.elapsed() >= max_idle
+    }
+}
+
+/// ALT 使用跟踪
+#[derive(Default)]
+pub struct LookupTableManager {
+    tables: DashMap<Pubkey, TrackedLookupTable>,
+}
+
+static LOOKUP_TABLE_MANAGER: Lazy<LookupTableManager> = Lazy::new(LookupTableManager::new);
+
+/// 全局 ALT 跟踪
+pub fn lookup_table_manager() -> &'static LookupTableManager {
+    &LOOKUP_TABLE_MANAGER
+}
+
+impl LookupTableManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一张表（已登记时不变）
+    pub fn track(&self, address: Pubkey) {
+        self.tables.entry(address).or_insert_with(|| TrackedLookupTable::new(address));
+    }
+
+    pub fn untrack(&self, address: &Pubkey) -> Option<TrackedLookupTable> {
+        self.tables.remove(address).map(|(_, table)| table)
+    }
+
+    /// 策略开始引用该表（未登记时自动登记）
+    pub fn retain(&self, address: Pubkey) {
+        let mut table =
+            self.tables.entry(address).or_insert_with(|| TrackedLookupTable::new(address));
+        table.references += 1;
+        table.last_used = Instant::now();
+    }
+
+    /// 策略不再引用该表
+    pub fn release(&self, address: &Pubkey) {
+        if let Some(mut table) = self.tables.get_mut(address) {
+            table.references = table.references.saturating_sub(1);
+        }
+    }
+
+    /// 记录一次使用；未登记的表忽略
+    #[inline]
+    pub fn record_use(&self, address: &Pubkey) {
+        if let Some(mut table) = self.tables.get_mut(address) {
+            table.use_count += 1;
+            table.last_used = Instant::now();
+        }
+    }
+
+    pub fn mark_deactivated(&self, address: &Pubkey, slot: u64) {
+        if let Some(mut table) = self.tables.get_mut(address) {
+            table.deactivated_at_slot = Some(slot);
+        }
+    }
+
+    pub fn get(&self, address: &Pubkey) -> Option<TrackedLookupTable> {
+        self.tables.get(address).map(|table| table.clone())
+    }
+
+    pub fn tables(&self) -> Vec<TrackedLookupTable> {
+        self.tables.iter().map(|table| table.clone()).collect()
+    }
+
+    /// 未被引用且闲置不少于 `max_idle` 的表
+    pub fn stale_tables(&self, max_idle: Duration) -> Vec<Pubkey> {
+        self.tables
+            .iter()
+            .filter(|table| table.is_stale(max_idle))
+            .map(|table| table.address)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_tables() {
+        let manager = LookupTableManager::new();
+        let (active, idle) = (Pubkey::new_unique(), Pubkey::new_unique());
+        manager.retain(active);
+        manager.track(idle);
+        manager.record_use(&idle);
+        manager.record_use(&Pubkey::new_unique());
+
+        assert_eq!(manager.len(), 2);
+        assert_eq!(manager.get(&idle).unwrap().use_count, 1);
+        assert_eq!(manager.stale_tables(Duration::ZERO), vec![idle]);
+        assert!(manager.stale_tables(Duration::from_secs(3600)).is_empty());
+
+        manager.release(&active);
+        assert_eq!(manager.stale_tables(Duration::ZERO).len(), 2);
+    }
+}
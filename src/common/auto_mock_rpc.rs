@@ -1,8 +1,12 @@
-//! Auto Mock RPC 客户端
+//! RPC 录制 / 重放层
 //!
-//! 智能 Auto 模式：有缓存就用，没缓存就调用 RPC 并保存
+//! [`AutoMockRpcClient`] 将只读 RPC 响应按方法与参数录制到磁盘，之后可确定性地重放：
+//! - Auto（默认）：有缓存就用，没缓存就调用 RPC 并保存
+//! - Record：始终调用 RPC 并覆盖保存
+//! - Replay：只读文件，不访问网络
 //!
-//! 用于 DEX Parser 和 Pool 查询测试
+//! 支持过期时间（TTL）与写入前脱敏。用于 DEX Parser、Pool 查询测试与历史回测；
+//! 所有以 [`PoolRpcClient`] 为参数的查询函数都可以使用。
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -18,6 +22,7 @@ use std::fs;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Pool 查询 RPC 客户端 Trait
 ///
@@ -273,11 +278,53 @@ impl PoolRpcClient for Arc<dyn PoolRpcClient + Send + Sync> {
     }
 }
 
-/// Auto Mock RPC 客户端
+/// 录制 / 重放模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MockMode {
+    /// 有缓存（且未过期）就用，没有就调用 RPC 并保存
+    #[default]
+    Auto,
+    /// 始终调用 RPC 并覆盖保存（重新录制）
+    Record,
+    /// 只从文件加载，缺少数据时报错（确定性测试，不访问网络）
+    Replay,
+}
+
+impl MockMode {
+    /// 解析 `MOCK_MODE` 环境变量的取值：`auto` / `record` / `replay`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Some(MockMode::Auto),
+            "record" => Some(MockMode::Record),
+            "replay" => Some(MockMode::Replay),
+            _ => None,
+        }
+    }
+}
+
+/// Auto Mock RPC 客户端（RPC 录制 / 重放层）
+///
+/// 每个 RPC 响应按 `方法 + 参数` 保存为一个 JSON 文件，之后可确定性地重放：
+/// - [`MockMode::Auto`]（默认）：有缓存数据 → 从文件加载；无缓存数据 → 调用 RPC 并保存
+/// - [`MockMode::Record`]：始终调用 RPC 并覆盖保存
+/// - [`MockMode::Replay`]：只从文件加载，缺少数据时报错
+///
+/// 只读查询（账户、程序账户、余额、交易）会被录制；发送交易、空投、确认与最新 blockhash
+/// 始终直接访问底层 RPC。
 ///
-/// 智能 Auto 模式：
-/// - 有缓存数据 → 从文件加载
-/// - 无缓存数据 → 调用 RPC 并保存
+/// 所有以 [`PoolRpcClient`] 为参数的查询函数（各协议的 `get_pool_by_address`、
+/// `get_pool_by_mint`、`list_pools_by_mint`、余额查询等）都可以直接使用本客户端。
+///
+/// # 过期时间（TTL）
+///
+/// [`AutoMockRpcClient::with_ttl`] 设置后，Auto 模式下修改时间早于 TTL 的文件视为过期并重新录制；
+/// Replay 模式忽略 TTL。
+///
+/// # 脱敏
+///
+/// [`AutoMockRpcClient::with_redaction`] 添加的字符串（API key、私有地址等）在写入文件前替换为
+/// `<redacted>`。缓存键基于原始参数计算，脱敏不影响查找；但被脱敏的值若需要在重放时反序列化
+/// （例如响应中的 Pubkey），重放会失败，应只脱敏与结果无关的敏感信息。
 ///
 /// # Namespace（命名空间）
 ///
@@ -301,6 +348,12 @@ impl PoolRpcClient for Arc<dyn PoolRpcClient + Send + Sync> {
 ///    );
 ///    ```
 ///
+/// ## 环境变量
+///
+/// - `MOCK_DIR`: Mock 数据目录（默认: tests/mock_data）
+/// - `MOCK_NAMESPACE`: Mock 命名空间（仅 [`AutoMockRpcClient::new`] 读取）
+/// - `MOCK_MODE`: `auto` / `record` / `replay`（默认 auto）
+///
 /// ## 文件命名规则
 ///
 /// - 有 namespace: `{method}_{namespace}_{params_hash}.json`
@@ -312,6 +365,12 @@ pub struct AutoMockRpcClient {
     mock_dir: String,
     /// 命名空间（可选，用于隔离不同测试的 mock 数据）
     namespace: Option<String>,
+    /// 录制 / 重放模式
+    mode: MockMode,
+    /// 缓存文件过期时间（仅 Auto 模式生效）
+    ttl: Option<Duration>,
+    /// 写入文件前替换为 `<redacted>` 的字符串
+    redactions: Vec<String>,
 }
 
 impl AutoMockRpcClient {
@@ -323,17 +382,11 @@ impl AutoMockRpcClient {
     /// # 环境变量
     /// - `MOCK_DIR`: Mock 数据目录（默认: tests/mock_data）
     /// - `MOCK_NAMESPACE`: Mock 命名空间（可选，用于隔离不同测试的 mock 数据）
+    /// - `MOCK_MODE`: 录制 / 重放模式（可选）
     pub fn new(rpc_url: String) -> Self {
-        let mock_dir = std::env::var("MOCK_DIR").unwrap_or_else(|_| "tests/mock_data".to_string());
-
         // 从环境变量读取 namespace
         let namespace = std::env::var("MOCK_NAMESPACE").ok();
-
-        Self {
-            inner: Arc::new(RpcClient::new(rpc_url)),
-            mock_dir,
-            namespace,
-        }
+        Self::new_with_namespace(rpc_url, namespace)
     }
 
     /// 创建新的 Auto Mock RPC 客户端（指定命名空间）
@@ -362,12 +415,46 @@ impl AutoMockRpcClient {
     /// ```
     pub fn new_with_namespace(rpc_url: String, namespace: Option<String>) -> Self {
         let mock_dir = std::env::var("MOCK_DIR").unwrap_or_else(|_| "tests/mock_data".to_string());
+        let mode = std::env::var("MOCK_MODE")
+            .ok()
+            .and_then(|mode| MockMode::parse(&mode))
+            .unwrap_or_default();
 
         Self {
             inner: Arc::new(RpcClient::new(rpc_url)),
             mock_dir,
             namespace,
+            mode,
+            ttl: None,
+            redactions: Vec::new(),
+        }
+    }
+
+    /// 设置录制 / 重放模式（覆盖 `MOCK_MODE`）
+    pub fn with_mode(mut self, mode: MockMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置 Mock 数据目录（覆盖 `MOCK_DIR`）
+    pub fn with_mock_dir(mut self, mock_dir: impl Into<String>) -> Self {
+        self.mock_dir = mock_dir.into();
+        self
+    }
+
+    /// 设置缓存文件过期时间（Auto 模式下过期文件会重新录制）
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// 添加需要脱敏的字符串（写入文件前替换为 `<redacted>`）
+    pub fn with_redaction(mut self, secret: impl Into<String>) -> Self {
+        let secret = secret.into();
+        if !secret.is_empty() {
+            self.redactions.push(secret);
         }
+        self
     }
 
     /// 获取当前命名空间
@@ -380,6 +467,11 @@ impl AutoMockRpcClient {
         &self.mock_dir
     }
 
+    /// 获取录制 / 重放模式
+    pub fn mode(&self) -> MockMode {
+        self.mode
+    }
+
     /// 生成文件名
     ///
     /// 格式:
@@ -410,6 +502,22 @@ impl AutoMockRpcClient {
         file_path.exists()
     }
 
+    /// Mock 数据是否存在且未过期（Replay 模式忽略 TTL）
+    fn has_fresh_mock_data(&self, method: &str, params: &Value) -> bool {
+        let file_path = Path::new(&self.mock_dir).join(self.generate_file_name(method, params));
+        let Ok(metadata) = fs::metadata(&file_path) else {
+            return false;
+        };
+        match (self.mode, self.ttl) {
+            (MockMode::Replay, _) | (_, None) => true,
+            (_, Some(ttl)) => metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age <= ttl),
+        }
+    }
+
     /// 保存 Mock 数据到文件
     fn save_mock_data(&self, method: &str, params: &Value, response: &Value) {
         // 确保目录存在
@@ -426,10 +534,13 @@ impl AutoMockRpcClient {
             "response": response
         });
 
-        let json = serde_json::to_string_pretty(&mock_data).unwrap_or_else(|e| {
+        let mut json = serde_json::to_string_pretty(&mock_data).unwrap_or_else(|e| {
             eprintln!("⚠️  序列化失败: {}", e);
             String::new()
         });
+        for secret in &self.redactions {
+            json = json.replace(secret.as_str(), "<redacted>");
+        }
 
         fs::write(&file_path, json).unwrap_or_else(|e| {
             eprintln!("⚠️  保存 Mock 数据失败: {} (path: {:?})", e, file_path);
@@ -458,44 +569,45 @@ impl AutoMockRpcClient {
             .ok_or_else(|| "❌ Mock 数据格式错误: 缺少 response 字段".to_string())
     }
 
-    /// Auto 模式调用核心逻辑
+    /// 录制 / 重放调用核心逻辑
     ///
-    /// 通用方法，处理所有 Auto 模式的 RPC 调用
-    #[allow(dead_code)]
-    async fn auto_call<M, P, R>(&self, method: &str, params: &P, rpc_call: M) -> Result<R, String>
+    /// 通用方法，处理所有可录制的 RPC 调用；`rpc_call` 在阻塞线程池中执行
+    async fn auto_call<R, M>(&self, method: &str, params: Value, rpc_call: M) -> Result<R, String>
     where
-        M: FnOnce() -> Result<R, Box<dyn std::error::Error>>,
-        P: Serialize,
-        R: Serialize + for<'de> Deserialize<'de>,
+        M: FnOnce(&RpcClient) -> Result<R, String> + Send + 'static,
+        R: Serialize + for<'de> Deserialize<'de> + Send + 'static,
     {
-        let params_json = serde_json::json!(params);
-
         // 有缓存就用
-        if self.has_mock_data(method, &params_json) {
-            return self.load_mock_data(method, &params_json);
+        if self.mode != MockMode::Record && self.has_fresh_mock_data(method, &params) {
+            return self.load_mock_data(method, &params);
+        }
+        if self.mode == MockMode::Replay {
+            return Err(format!(
+                "❌ Replay 模式下缺少 Mock 数据: {}",
+                self.generate_file_name(method, &params)
+            ));
         }
 
-        // 没缓存就调用 RPC 并保存
-        let result = rpc_call().map_err(|e| e.to_string())?;
+        // 没缓存就调用 RPC 并保存（使用 tokio::task::spawn_blocking）
+        let inner = self.inner.clone();
+        let result = tokio::task::spawn_blocking(move || rpc_call(&inner))
+            .await
+            .map_err(|e| format!("任务执行失败: {}", e))??;
 
         // 保存到文件
         let result_json =
             serde_json::to_value(&result).map_err(|e| format!("序列化结果失败: {}", e))?;
-        self.save_mock_data(method, &params_json, &result_json);
+        self.save_mock_data(method, &params, &result_json);
 
         Ok(result)
     }
 
-    /// 获取交易（Auto 模式）
-    ///
-    /// 智能模式：有缓存就用，没缓存就调用 RPC 并保存
+    /// 获取交易（录制 / 重放）
     pub async fn get_transaction(
         &self,
         signature: &Signature,
         config: RpcTransactionConfig,
     ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
-        // 在 async 函数内部调用 auto_call，但它需要一个同步闭包
-        // 所以我们使用一个特殊的 wrapper
         let params_json = serde_json::json!((
             signature.to_string(),
             RpcTransactionConfig {
@@ -504,164 +616,70 @@ impl AutoMockRpcClient {
                 max_supported_transaction_version: config.max_supported_transaction_version,
             }
         ));
-
-        // 有缓存就用
-        if self.has_mock_data("get_transaction_with_config", &params_json) {
-            return self.load_mock_data("get_transaction_with_config", &params_json);
-        }
-
-        // 没缓存就调用 RPC 并保存（使用 tokio::task::spawn_blocking）
-        let inner = self.inner.clone();
         let sig = *signature;
-
-        let tx = tokio::task::spawn_blocking(move || {
+        self.auto_call("get_transaction_with_config", params_json, move |inner| {
             inner
                 .get_transaction_with_config(&sig, config)
                 .map_err(|e| format!("RPC 调用失败: {}", e))
         })
         .await
-        .map_err(|e| format!("任务执行失败: {}", e))??;
-
-        // 保存到文件
-        let result_json =
-            serde_json::to_value(&tx).map_err(|e| format!("序列化结果失败: {}", e))?;
-        self.save_mock_data("get_transaction_with_config", &params_json, &result_json);
-
-        Ok(tx)
     }
 
-    /// 获取账户信息（Auto 模式）
-    ///
-    /// 智能 Auto 模式：有缓存就用，没缓存就调用 RPC 并保存
+    /// 获取账户信息（录制 / 重放）
     pub async fn get_account(&self, pubkey: &Pubkey) -> Result<Account, String> {
         let params_json = serde_json::json!((pubkey.to_string(),));
-
-        // 有缓存就用
-        if self.has_mock_data("get_account", &params_json) {
-            return self.load_mock_data("get_account", &params_json);
-        }
-
-        // 没缓存就调用 RPC 并保存
-        let inner = self.inner.clone();
         let pk = *pubkey;
-
-        let account = tokio::task::spawn_blocking(move || {
+        self.auto_call("get_account", params_json, move |inner| {
             inner.get_account(&pk).map_err(|e| format!("RPC 调用失败: {}", e))
         })
         .await
-        .map_err(|e| format!("任务执行失败: {}", e))??;
-
-        // 保存到文件
-        let result_json =
-            serde_json::to_value(&account).map_err(|e| format!("序列化结果失败: {}", e))?;
-        self.save_mock_data("get_account", &params_json, &result_json);
-
-        Ok(account)
     }
 
-    /// 获取程序账户列表（Auto 模式）
-    ///
-    /// 智能 Auto 模式：有缓存就用，没缓存就调用 RPC 并保存
+    /// 获取程序账户列表（录制 / 重放）
     pub async fn get_program_ui_accounts_with_config(
         &self,
         program_id: &Pubkey,
         config: RpcProgramAccountsConfig,
     ) -> Result<Vec<(String, UiAccount)>, String> {
-        // 序列化 config 用于缓存键（需要先克隆，因为后面还要用）
+        // 序列化 config 用于缓存键
         let config_for_json =
             serde_json::to_value(&config).map_err(|e| format!("序列化 config 失败: {}", e))?;
         let params_json = serde_json::json!((program_id.to_string(), config_for_json));
-
-        // 有缓存就用
-        if self.has_mock_data("get_program_ui_accounts_with_config", &params_json) {
-            return self.load_mock_data("get_program_ui_accounts_with_config", &params_json);
-        }
-
-        // 没缓存就调用 RPC 并保存
-        let inner = self.inner.clone();
         let pid = *program_id;
-
-        let accounts = tokio::task::spawn_blocking(move || {
-            inner
+        self.auto_call("get_program_ui_accounts_with_config", params_json, move |inner| {
+            let accounts = inner
                 .get_program_ui_accounts_with_config(&pid, config)
-                .map_err(|e| format!("RPC 调用失败: {}", e))
+                .map_err(|e| format!("RPC 调用失败: {}", e))?;
+            // 将 Pubkey 转换为 String
+            Ok(accounts
+                .into_iter()
+                .map(|(pubkey, account)| (pubkey.to_string(), account))
+                .collect())
         })
         .await
-        .map_err(|e| format!("任务执行失败: {}", e))??;
-
-        // 将 Pubkey 转换为 String
-        let accounts: Vec<(String, UiAccount)> = accounts
-            .into_iter()
-            .map(|(pubkey, account)| (pubkey.to_string(), account))
-            .collect();
-
-        // 保存到文件（保存原始格式，Pubkey 转为 String）
-        let result_json =
-            serde_json::to_value(&accounts).map_err(|e| format!("序列化结果失败: {}", e))?;
-        self.save_mock_data("get_program_ui_accounts_with_config", &params_json, &result_json);
-
-        Ok(accounts)
     }
 
-    /// 获取 Token 账户余额（Auto 模式）
-    ///
-    /// 智能 Auto 模式：有缓存就用，没缓存就调用 RPC 并保存
+    /// 获取 Token 账户余额（录制 / 重放）
     pub async fn get_token_account_balance(
         &self,
         pubkey: &Pubkey,
     ) -> Result<UiTokenAmount, String> {
         let params_json = serde_json::json!((pubkey.to_string(),));
-
-        // 有缓存就用
-        if self.has_mock_data("get_token_account_balance", &params_json) {
-            return self.load_mock_data("get_token_account_balance", &params_json);
-        }
-
-        // 没缓存就调用 RPC 并保存
-        let inner = self.inner.clone();
         let pk = *pubkey;
-
-        let balance = tokio::task::spawn_blocking(move || {
+        self.auto_call("get_token_account_balance", params_json, move |inner| {
             inner.get_token_account_balance(&pk).map_err(|e| format!("RPC 调用失败: {}", e))
         })
         .await
-        .map_err(|e| format!("任务执行失败: {}", e))??;
-
-        // 保存到文件
-        let result_json =
-            serde_json::to_value(&balance).map_err(|e| format!("序列化结果失败: {}", e))?;
-        self.save_mock_data("get_token_account_balance", &params_json, &result_json);
-
-        Ok(balance)
     }
 
-    /// 获取 SOL 余额（Auto 模式）
-    ///
-    /// 智能 Auto 模式：有缓存就用，没缓存就调用 RPC 并保存
+    /// 获取 SOL 余额（录制 / 重放）
     pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, String> {
         let params_json = serde_json::json!((pubkey.to_string(),));
-
-        // 有缓存就用
-        if self.has_mock_data("get_balance", &params_json) {
-            return self.load_mock_data("get_balance", &params_json);
-        }
-
-        // 没缓存就调用 RPC 并保存
-        let inner = self.inner.clone();
         let pk = *pubkey;
-
-        let balance = tokio::task::spawn_blocking(move || {
+        self.auto_call("get_balance", params_json, move |inner| {
             inner.get_balance(&pk).map_err(|e| format!("RPC 调用失败: {}", e))
         })
         .await
-        .map_err(|e| format!("任务执行失败: {}", e))??;
-
-        // 保存到文件
-        let result_json =
-            serde_json::to_value(balance).map_err(|e| format!("序列化结果失败: {}", e))?;
-        self.save_mock_data("get_balance", &params_json, &result_json);
-
-        Ok(balance)
     }
 }
 
@@ -746,3 +764,50 @@ impl PoolRpcClient for AutoMockRpcClient {
         Ok(response.value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(dir: &Path, mode: MockMode) -> AutoMockRpcClient {
+        AutoMockRpcClient::new_with_namespace("http://127.0.0.1:1".to_string(), None)
+            .with_mock_dir(dir.to_string_lossy())
+            .with_mode(mode)
+    }
+
+    #[tokio::test]
+    async fn test_replay_and_redaction() {
+        let dir = std::env::temp_dir().join(format!("auto_mock_{}", Pubkey::new_unique()));
+        let pubkey = Pubkey::new_unique();
+        let replay = client(&dir, MockMode::Replay).with_redaction("secret-token");
+
+        assert!(replay.get_balance(&pubkey).await.unwrap_err().contains("Replay"));
+
+        let params = serde_json::json!((pubkey.to_string(),));
+        replay.save_mock_data("get_balance", &params, &serde_json::json!(42));
+        assert_eq!(replay.get_balance(&pubkey).await.unwrap(), 42);
+
+        let secret_params = serde_json::json!(("secret-token",));
+        replay.save_mock_data("get_balance", &secret_params, &serde_json::json!(1));
+        let file = dir.join(replay.generate_file_name("get_balance", &secret_params));
+        let content = fs::read_to_string(file).unwrap();
+        assert!(!content.contains("secret-token"));
+        assert!(content.contains("<redacted>"));
+
+        // Auto 模式下过期的文件视为缺失，Replay 模式忽略 TTL
+        let expired = client(&dir, MockMode::Auto).with_ttl(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(!expired.has_fresh_mock_data("get_balance", &params));
+        let replay = replay.with_ttl(Duration::ZERO);
+        assert!(replay.has_fresh_mock_data("get_balance", &params));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_mock_mode_parse() {
+        assert_eq!(MockMode::parse("Replay"), Some(MockMode::Replay));
+        assert_eq!(MockMode::parse("record"), Some(MockMode::Record));
+        assert_eq!(MockMode::parse("live"), None);
+    }
+}
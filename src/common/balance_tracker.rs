@@ -0,0 +1,330 @@
+//! 余额跟踪器
+//!
+//! 在后台维护 payer 的 SOL 余额与已跟踪代币的 ATA 余额，交易热路径通过同步方法直接读取，
+//! 卖出数量计算、风控检查不必再发起阻塞的 RPC 请求。
+//!
+//! - 配置了 `ws_url` 时通过 WebSocket `accountSubscribe` 实时更新；
+//! - WebSocket 未连接（未配置、断线重连中）时按 `poll_interval` 通过 RPC 轮询兜底；
+//! - 新增跟踪的代币会立即通过 RPC 读取一次余额，并重建订阅。
+
+use crate::common::SolanaRpcClient;
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
+use anyhow::Result;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use dashmap::DashMap;
+use futures::StreamExt;
+use futures::stream::select_all;
+use parking_lot::Mutex;
+use solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_rpc_client_api::config::RpcAccountInfoConfig;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::solana_program::program_pack::Pack;
+use spl_token::state::Account as SplTokenAccount;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// 代币 ATA 中 amount 字段的偏移
+const TOKEN_AMOUNT_OFFSET: usize = 64;
+/// WebSocket 断线后的重连间隔
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// 余额跟踪器配置
+#[derive(Debug, Clone)]
+pub struct BalanceTrackerConfig {
+    /// WebSocket 地址（例如 `wss://...`）；为 None 时仅使用 RPC 轮询
+    pub ws_url: Option<String>,
+    /// RPC 轮询间隔（WebSocket 未连接时生效）
+    pub poll_interval: Duration,
+    /// 是否使用 seed 优化推导 ATA（需与 `TradeConfig::use_seed_optimize` 一致）
+    pub use_seed_optimize: bool,
+}
+
+impl Default for BalanceTrackerConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: None,
+            poll_interval: Duration::from_secs(5),
+            use_seed_optimize: false,
+        }
+    }
+}
+
+impl BalanceTrackerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = Some(ws_url.into());
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_seed_optimize(mut self, use_seed_optimize: bool) -> Self {
+        self.use_seed_optimize = use_seed_optimize;
+        self
+    }
+}
+
+/// 一个代币余额快照
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub ata: Pubkey,
+    pub token_program: Pubkey,
+    /// 余额（最小单位）；ATA 不存在时为 0
+    pub amount: u64,
+    /// 最近一次更新时间；尚未取得余额时为 None
+    pub updated_at: Option<Instant>,
+}
+
+/// 后台余额跟踪器
+pub struct BalanceTracker {
+    rpc: Arc<SolanaRpcClient>,
+    owner: Pubkey,
+    config: BalanceTrackerConfig,
+    sol_balance: AtomicU64,
+    sol_updated_at: Mutex<Option<Instant>>,
+    /// mint -> 余额
+    tokens: DashMap<Pubkey, TokenBalance>,
+    ws_connected: AtomicBool,
+    resubscribe: Notify,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl BalanceTracker {
+    pub fn new(rpc: Arc<SolanaRpcClient>, owner: Pubkey, config: BalanceTrackerConfig) -> Self {
+        Self {
+            rpc,
+            owner,
+            config,
+            sol_balance: AtomicU64::new(0),
+            sol_updated_at: Mutex::new(None),
+            tokens: DashMap::new(),
+            ws_connected: AtomicBool::new(false),
+            resubscribe: Notify::new(),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+
+    /// 启动后台任务：先同步刷新一次全部余额，再启动轮询与（可选的）WebSocket 订阅
+    pub async fn start(self: &Arc<Self>) -> Result<()> {
+        self.refresh_all().await?;
+        let mut tasks = self.tasks.lock();
+        if !tasks.is_empty() {
+            return Ok(());
+        }
+        let tracker = self.clone();
+        tasks.push(tokio::spawn(async move { tracker.poll_loop().await }));
+        if let Some(ws_url) = self.config.ws_url.clone() {
+            let tracker = self.clone();
+            tasks.push(tokio::spawn(async move { tracker.subscribe_loop(ws_url).await }));
+        }
+        Ok(())
+    }
+
+    /// 停止后台任务（已缓存的余额仍可读取，但不再更新）
+    pub fn stop(&self) {
+        for task in self.tasks.lock().drain(..) {
+            task.abort();
+        }
+        self.ws_connected.store(false, Ordering::Relaxed);
+    }
+
+    /// 开始跟踪一个代币的 ATA 余额，并立即通过 RPC 读取一次
+    pub async fn track_token(&self, mint: Pubkey, token_program: Pubkey) -> Result<()> {
+        let ata = get_associated_token_address_with_program_id_fast_use_seed(
+            &self.owner,
+            &mint,
+            &token_program,
+            self.config.use_seed_optimize,
+        );
+        self.tokens
+            .insert(mint, TokenBalance { ata, token_program, amount: 0, updated_at: None });
+        self.refresh_token(&mint, &ata).await?;
+        self.resubscribe.notify_one();
+        Ok(())
+    }
+
+    /// 停止跟踪一个代币
+    pub fn untrack_token(&self, mint: &Pubkey) {
+        if self.tokens.remove(mint).is_some() {
+            self.resubscribe.notify_one();
+        }
+    }
+
+    /// 缓存的 SOL 余额（lamports）；尚未取得余额时为 None
+    pub fn sol_balance(&self) -> Option<u64> {
+        self.sol_updated_at.lock().map(|_| self.sol_balance.load(Ordering::Relaxed))
+    }
+
+    /// 缓存的代币余额（最小单位）；未跟踪或尚未取得余额时为 None
+    pub fn token_balance(&self, mint: &Pubkey) -> Option<u64> {
+        self.tokens.get(mint).filter(|b| b.updated_at.is_some()).map(|b| b.amount)
+    }
+
+    /// 缓存的代币余额快照
+    pub fn token_snapshot(&self, mint: &Pubkey) -> Option<TokenBalance> {
+        self.tokens.get(mint).map(|b| *b)
+    }
+
+    /// WebSocket 订阅当前是否处于连接状态
+    pub fn is_streaming(&self) -> bool {
+        self.ws_connected.load(Ordering::Relaxed)
+    }
+
+    /// 通过 RPC 刷新全部余额
+    pub async fn refresh_all(&self) -> Result<()> {
+        let lamports = self.rpc.get_balance(&self.owner).await?;
+        self.set_sol_balance(lamports);
+        let tracked: Vec<(Pubkey, Pubkey)> =
+            self.tokens.iter().map(|entry| (*entry.key(), entry.ata)).collect();
+        for (mint, ata) in tracked {
+            if let Err(e) = self.refresh_token(&mint, &ata).await {
+                log::warn!("BalanceTracker: failed to refresh {}: {}", mint, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn refresh_token(&self, mint: &Pubkey, ata: &Pubkey) -> Result<()> {
+        let account = self.rpc.get_account_with_commitment(ata, self.rpc.commitment()).await?;
+        let amount = account.value.map_or(Some(0), |account| decode_token_amount(&account.data));
+        if let Some(amount) = amount {
+            self.set_token_balance(mint, amount);
+        }
+        Ok(())
+    }
+
+    fn set_sol_balance(&self, lamports: u64) {
+        self.sol_balance.store(lamports, Ordering::Relaxed);
+        *self.sol_updated_at.lock() = Some(Instant::now());
+    }
+
+    fn set_token_balance(&self, mint: &Pubkey, amount: u64) {
+        if let Some(mut entry) = self.tokens.get_mut(mint) {
+            entry.amount = amount;
+            entry.updated_at = Some(Instant::now());
+        }
+    }
+
+    async fn poll_loop(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.config.poll_interval);
+        loop {
+            interval.tick().await;
+            if self.is_streaming() {
+                continue;
+            }
+            if let Err(e) = self.refresh_all().await {
+                log::warn!("BalanceTracker: RPC refresh failed: {}", e);
+            }
+        }
+    }
+
+    async fn subscribe_loop(self: Arc<Self>, ws_url: String) {
+        loop {
+            if let Err(e) = self.subscribe_once(&ws_url).await {
+                log::warn!("BalanceTracker: subscription error: {}", e);
+            }
+            self.ws_connected.store(false, Ordering::Relaxed);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// 建立一次订阅；连接断开或跟踪列表变化时返回
+    async fn subscribe_once(&self, ws_url: &str) -> Result<()> {
+        let client = PubsubClient::new(ws_url).await?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(self.rpc.commitment()),
+            min_context_slot: None,
+        };
+
+        let mut accounts = vec![(self.owner, None)];
+        accounts.extend(self.tokens.iter().map(|entry| (entry.ata, Some(*entry.key()))));
+        let mut streams = Vec::with_capacity(accounts.len());
+        for (address, mint) in accounts {
+            let (stream, _unsubscribe) =
+                client.account_subscribe(&address, Some(config.clone())).await?;
+            streams.push(stream.map(move |response| (mint, response.value)));
+        }
+        let mut updates = select_all(streams);
+        self.ws_connected.store(true, Ordering::Relaxed);
+        // 订阅建立前的变化可能被遗漏，连接后补一次 RPC 刷新
+        self.refresh_all().await?;
+
+        loop {
+            tokio::select! {
+                update = updates.next() => match update {
+                    Some((mint, account)) => self.apply_update(mint, &account),
+                    None => return Ok(()),
+                },
+                _ = self.resubscribe.notified() => return Ok(()),
+            }
+        }
+    }
+
+    fn apply_update(&self, mint: Option<Pubkey>, account: &UiAccount) {
+        match mint {
+            None => self.set_sol_balance(account.lamports),
+            Some(mint) => {
+                let data = match &account.data {
+                    UiAccountData::Binary(base64_str, _) => STANDARD.decode(base64_str).ok(),
+                    _ => None,
+                };
+                // 账户被关闭时推送空数据
+                let amount = match data {
+                    Some(data) if data.is_empty() => Some(0),
+                    Some(data) => decode_token_amount(&data),
+                    None => None,
+                };
+                if let Some(amount) = amount {
+                    self.set_token_balance(&mint, amount);
+                }
+            },
+        }
+    }
+}
+
+/// 解析代币账户余额（SPL Token 与 Token-2022 基础布局相同）
+fn decode_token_amount(data: &[u8]) -> Option<u64> {
+    if data.len() < SplTokenAccount::LEN {
+        return None;
+    }
+    data.get(TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("slice length is 8")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_token_amount() {
+        let mut data = vec![0u8; SplTokenAccount::LEN];
+        data[TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8].copy_from_slice(&42u64.to_le_bytes());
+        assert_eq!(decode_token_amount(&data), Some(42));
+        assert_eq!(decode_token_amount(&data[..100]), None);
+    }
+
+    #[tokio::test]
+    async fn test_reads_before_refresh_are_none() {
+        let rpc = Arc::new(SolanaRpcClient::new("http://127.0.0.1:8899".to_string()));
+        let tracker = BalanceTracker::new(rpc, Pubkey::new_unique(), BalanceTrackerConfig::new());
+        assert_eq!(tracker.sol_balance(), None);
+        assert_eq!(tracker.token_balance(&Pubkey::new_unique()), None);
+        assert!(!tracker.is_streaming());
+    }
+}
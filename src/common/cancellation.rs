@@ -0,0 +1,65 @@
+//! 取消令牌
+//!
+//! 策略引擎在信号失效时通过 [`CancellationToken`] 主动中止买入/卖出、报价与池子发现，
+//! 而不是直接丢弃 future：
+//!
+//! - 发送前（补全参数、各项检查、提交队列排队）取消：不发送交易，返回 [`TradeError::CANCELLED`]
+//! - 发送后取消：停止确认轮询，已提交的签名照常返回（交易仍可能上链）
+
+use std::future::Future;
+
+pub use tokio_util::sync::CancellationToken;
+
+use crate::swqos::common::TradeError;
+
+/// 在令牌下运行 `future`：令牌先被取消时丢弃 `future` 并返回 `None`
+///
+/// `token` 为 `None` 时等价于直接 `.await`。
+pub async fn cancellable<F: Future>(
+    token: Option<&CancellationToken>,
+    future: F,
+) -> Option<F::Output> {
+    match token {
+        Some(token) => token.run_until_cancelled(future).await,
+        None => Some(future.await),
+    }
+}
+
+/// 等待令牌被取消；`token` 为 `None` 时永不完成
+pub(crate) async fn wait_cancelled(token: Option<&CancellationToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 令牌已被取消时返回 [`TradeError::CANCELLED`] 错误
+pub fn cancelled_error(token: Option<&CancellationToken>, stage: &str) -> Option<TradeError> {
+    token
+        .filter(|token| token.is_cancelled())
+        .map(|_| TradeError::cancelled(format!("cancelled {}", stage)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancellable() {
+        assert_eq!(cancellable(None, async { 1 }).await, Some(1));
+
+        let token = CancellationToken::new();
+        assert_eq!(cancellable(Some(&token), async { 2 }).await, Some(2));
+        assert!(cancelled_error(Some(&token), "before send").is_none());
+
+        let child = token.child_token();
+        token.cancel();
+        let pending = tokio::time::sleep(Duration::from_secs(60));
+        assert_eq!(cancellable(Some(&child), pending).await, None);
+
+        let error = cancelled_error(Some(&child), "before send").unwrap();
+        assert!(error.is_cancelled());
+        assert!(cancelled_error(None, "before send").is_none());
+    }
+}
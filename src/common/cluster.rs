@@ -0,0 +1,172 @@
+//! 集群（mainnet / devnet / testnet）与协议程序 ID 映射
+//!
+//! 指令构建使用各协议的主网程序 ID。选择非主网集群后，执行前会把指令中的主网程序 ID
+//! （`program_id` 与账户列表中的程序账户）替换为该集群上的部署地址；协议在该集群没有部署时
+//! 直接返回错误，而不是把交易发往不存在的程序。
+//!
+//! 注意：
+//! - 只替换程序地址。由程序 ID 派生的 PDA（如 Raydium CPMM 的 authority）不会重新推导，
+//!   池子相关账户应从链上池状态读取（`*_by_rpc` 构造函数）。
+//! - 各 SWQOS 的小费账户只在主网有效，非主网集群不附加小费指令。
+//! - 非主网没有内置的 WSOL-USD 锚定池，USD 计价需通过 `PricingConfig::with_anchor_pools` 指定。
+//!
+//! 集群为进程级共享，以最后创建的 `TradingClient` 配置为准。
+
+use crate::constants::USDC_MINT;
+use crate::instruction::utils::raydium_clmm::DEFAULT_WSOL_USDT_CLMM_POOL;
+use crate::instruction::utils::{
+    bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_clmm, raydium_cpmm,
+};
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use parking_lot::RwLock;
+use solana_sdk::{instruction::Instruction, pubkey, pubkey::Pubkey};
+
+/// Raydium AMM V4 devnet 程序
+pub const RAYDIUM_AMM_V4_DEVNET: Pubkey = pubkey!("DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav");
+/// Raydium CLMM devnet 程序
+pub const RAYDIUM_CLMM_DEVNET: Pubkey = pubkey!("DRayAUgENGQBKVaX8owNhgzkEDyoHTGVEGHVJT1E9pfH");
+/// Raydium CPMM devnet 程序
+pub const RAYDIUM_CPMM_DEVNET: Pubkey =
+    crate::instruction::utils::raydium_launchlab::accounts::CPMM_PROGRAM_DEVNET;
+/// Circle devnet USDC
+pub const USDC_MINT_DEVNET: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+
+/// Solana 集群
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Cluster {
+    #[default]
+    Mainnet,
+    Devnet,
+    Testnet,
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cluster::Mainnet => write!(f, "mainnet"),
+            Cluster::Devnet => write!(f, "devnet"),
+            Cluster::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+impl Cluster {
+    pub fn is_mainnet(&self) -> bool {
+        *self == Cluster::Mainnet
+    }
+
+    /// 协议的主网程序 ID
+    pub fn mainnet_program_id(dex_type: &DexType) -> Pubkey {
+        match dex_type {
+            DexType::PumpFun => pumpfun::accounts::PUMPFUN,
+            DexType::PumpSwap => pumpswap::accounts::AMM_PROGRAM,
+            DexType::Bonk => bonk::accounts::BONK,
+            DexType::RaydiumCpmm => raydium_cpmm::accounts::RAYDIUM_CPMM,
+            DexType::RaydiumAmmV4 => raydium_amm_v4::accounts::RAYDIUM_AMM_V4,
+            DexType::RaydiumClmm => raydium_clmm::accounts::RAYDIUM_CLMM,
+            DexType::MeteoraDammV2 => meteora_damm_v2::accounts::METEORA_DAMM_V2,
+        }
+    }
+
+    /// 协议在该集群上的程序 ID（没有部署时返回错误）
+    pub fn program_id(&self, dex_type: &DexType) -> Result<Pubkey> {
+        let program_id = match (*self, dex_type) {
+            (Cluster::Mainnet, _) => Some(Self::mainnet_program_id(dex_type)),
+            // PumpFun / PumpSwap / Meteora 在 devnet 上使用与主网相同的地址
+            (Cluster::Devnet, DexType::PumpFun | DexType::PumpSwap | DexType::MeteoraDammV2) => {
+                Some(Self::mainnet_program_id(dex_type))
+            },
+            (Cluster::Devnet, DexType::RaydiumCpmm) => Some(RAYDIUM_CPMM_DEVNET),
+            (Cluster::Devnet, DexType::RaydiumAmmV4) => Some(RAYDIUM_AMM_V4_DEVNET),
+            (Cluster::Devnet, DexType::RaydiumClmm) => Some(RAYDIUM_CLMM_DEVNET),
+            _ => None,
+        };
+        program_id.ok_or_else(|| anyhow!("{:?} has no {} deployment", dex_type, self))
+    }
+
+    /// 把指令中的主网程序 ID 替换为该集群的部署地址
+    pub fn remap_instructions(
+        &self,
+        dex_type: &DexType,
+        instructions: &mut [Instruction],
+    ) -> Result<()> {
+        if self.is_mainnet() {
+            return Ok(());
+        }
+        let mainnet = Self::mainnet_program_id(dex_type);
+        let target = self.program_id(dex_type)?;
+        if target == mainnet {
+            return Ok(());
+        }
+        for instruction in instructions.iter_mut() {
+            if instruction.program_id == mainnet {
+                instruction.program_id = target;
+            }
+            for meta in instruction.accounts.iter_mut().filter(|meta| meta.pubkey == mainnet) {
+                meta.pubkey = target;
+            }
+        }
+        Ok(())
+    }
+
+    /// 是否附加 SWQOS 小费（小费账户仅主网有效）
+    pub fn supports_tips(&self) -> bool {
+        self.is_mainnet()
+    }
+
+    /// 内置的 WSOL-USD 锚定池（非主网为空）
+    pub fn default_anchor_pools(&self) -> Vec<Pubkey> {
+        match self {
+            Cluster::Mainnet => vec![DEFAULT_WSOL_USDT_CLMM_POOL],
+            Cluster::Devnet | Cluster::Testnet => vec![],
+        }
+    }
+
+    /// 该集群上的 USDC mint
+    pub fn usdc_mint(&self) -> Option<Pubkey> {
+        match self {
+            Cluster::Mainnet => Some(USDC_MINT),
+            Cluster::Devnet => Some(USDC_MINT_DEVNET),
+            Cluster::Testnet => None,
+        }
+    }
+}
+
+static CLUSTER: RwLock<Cluster> = RwLock::new(Cluster::Mainnet);
+
+/// 当前生效的集群
+pub fn current_cluster() -> Cluster {
+    *CLUSTER.read()
+}
+
+/// 切换集群
+pub fn set_cluster(cluster: Cluster) {
+    *CLUSTER.write() = cluster;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[test]
+    fn test_devnet_program_mapping() {
+        let devnet = Cluster::Devnet;
+        assert_eq!(devnet.program_id(&DexType::RaydiumCpmm).unwrap(), RAYDIUM_CPMM_DEVNET);
+        assert!(devnet.program_id(&DexType::Bonk).unwrap_err().to_string().contains("devnet"));
+        assert!(Cluster::Testnet.program_id(&DexType::RaydiumClmm).is_err());
+
+        let mainnet = raydium_clmm::accounts::RAYDIUM_CLMM;
+        let mut instructions = vec![Instruction::new_with_bytes(
+            mainnet,
+            &[],
+            vec![AccountMeta::new_readonly(mainnet, false)],
+        )];
+        devnet.remap_instructions(&DexType::RaydiumClmm, &mut instructions).unwrap();
+        assert_eq!(instructions[0].program_id, RAYDIUM_CLMM_DEVNET);
+        assert_eq!(instructions[0].accounts[0].pubkey, RAYDIUM_CLMM_DEVNET);
+        assert!(devnet.remap_instructions(&DexType::Bonk, &mut instructions).is_err());
+        assert!(!devnet.supports_tips());
+    }
+}
@@ -22,6 +22,12 @@ pub fn clear_all_pool_caches() {
 
     // Meteora DAMM V2
     crate::instruction::utils::meteora_damm_v2::clear_pool_cache();
+
+    // Token-2022 transfer hook
+    crate::common::transfer_hook::clear_transfer_hook_cache();
+
+    // 按 mint 缓存的协议参数
+    crate::trading::core::params_cache::params_cache().clear();
 }
 
 #[cfg(test)]
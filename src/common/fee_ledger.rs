@@ -0,0 +1,254 @@
+//! 手续费支出统计
+//!
+//! 按 SWQOS 与自然日（UTC）累计优先费（compute unit price × limit）与小费支出，
+//! 同时记录每个 provider 的发送次数与上链次数，便于评估小费水平与上链率是否匹配。
+//!
+//! 只有在等待确认（`wait_transaction_confirmed = true`）时才能确定交易是否上链，
+//! 因此支出金额只在确认后计入；未等待确认的交易仅计入发送次数。
+
+use crate::swqos::SwqosType;
+use chrono::{NaiveDate, Utc};
+use parking_lot::Mutex;
+use solana_sdk::native_token::sol_str_to_lamports;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// 最近上链交易的手续费明细保留条数
+const RECENT_FEES_CAPACITY: usize = 1024;
+
+/// 单笔交易的手续费明细
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxFee {
+    pub signature: Signature,
+    pub swqos_type: SwqosType,
+    /// 优先费（lamports）
+    pub priority_fee_lamports: u64,
+    /// 小费（lamports）；交易执行失败时小费转账被回滚，记为 0
+    pub tip_lamports: u64,
+}
+
+impl TxFee {
+    /// 根据 gas 策略计算交易上链后实际支付的手续费
+    pub fn new(
+        signature: Signature,
+        swqos_type: SwqosType,
+        cu_limit: u32,
+        cu_price: u64,
+        tip_sol: f64,
+        success: bool,
+    ) -> Self {
+        Self {
+            signature,
+            swqos_type,
+            priority_fee_lamports: priority_fee_lamports(cu_limit, cu_price),
+            tip_lamports: if success { sol_to_lamports(tip_sol) } else { 0 },
+        }
+    }
+
+    pub fn total_lamports(&self) -> u64 {
+        self.priority_fee_lamports + self.tip_lamports
+    }
+}
+
+/// 优先费：cu_price 单位为 micro-lamports / CU，向上取整
+#[inline]
+pub fn priority_fee_lamports(cu_limit: u32, cu_price: u64) -> u64 {
+    (cu_limit as u128 * cu_price as u128).div_ceil(1_000_000) as u64
+}
+
+/// 与交易构建时的小费换算保持一致
+#[inline]
+pub(crate) fn sol_to_lamports(sol: f64) -> u64 {
+    sol_str_to_lamports(&sol.to_string()).unwrap_or(0)
+}
+
+/// 单个 provider 的累计统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProviderFeeStats {
+    /// 发送次数
+    pub submitted: u64,
+    /// 确认上链次数（含执行失败但已上链的交易）
+    pub landed: u64,
+    /// 上链且执行成功次数
+    pub succeeded: u64,
+    pub priority_fee_lamports: u64,
+    pub tip_lamports: u64,
+}
+
+impl ProviderFeeStats {
+    pub fn total_lamports(&self) -> u64 {
+        self.priority_fee_lamports + self.tip_lamports
+    }
+
+    /// 上链率（landed / submitted）
+    pub fn landing_rate(&self) -> f64 {
+        if self.submitted == 0 { 0.0 } else { self.landed as f64 / self.submitted as f64 }
+    }
+
+    /// 每笔上链交易的平均支出（lamports）
+    pub fn lamports_per_landed(&self) -> Option<u64> {
+        (self.landed > 0).then(|| self.total_lamports() / self.landed)
+    }
+
+    fn merge(&mut self, other: &ProviderFeeStats) {
+        self.submitted += other.submitted;
+        self.landed += other.landed;
+        self.succeeded += other.succeeded;
+        self.priority_fee_lamports += other.priority_fee_lamports;
+        self.tip_lamports += other.tip_lamports;
+    }
+}
+
+/// 手续费报告快照
+#[derive(Debug, Clone, Default)]
+pub struct FeeReport {
+    /// 按自然日（UTC）与 provider 分组的统计
+    pub daily: BTreeMap<NaiveDate, HashMap<SwqosType, ProviderFeeStats>>,
+}
+
+impl FeeReport {
+    /// 按 provider 汇总全部日期
+    pub fn by_provider(&self) -> HashMap<SwqosType, ProviderFeeStats> {
+        let mut totals: HashMap<SwqosType, ProviderFeeStats> = HashMap::new();
+        for providers in self.daily.values() {
+            for (swqos_type, stats) in providers {
+                totals.entry(*swqos_type).or_default().merge(stats);
+            }
+        }
+        totals
+    }
+
+    /// 按自然日汇总全部 provider
+    pub fn by_day(&self) -> BTreeMap<NaiveDate, ProviderFeeStats> {
+        self.daily
+            .iter()
+            .map(|(day, providers)| {
+                let mut total = ProviderFeeStats::default();
+                providers.values().for_each(|stats| total.merge(stats));
+                (*day, total)
+            })
+            .collect()
+    }
+
+    /// 全部支出汇总
+    pub fn total(&self) -> ProviderFeeStats {
+        let mut total = ProviderFeeStats::default();
+        self.by_day().values().for_each(|stats| total.merge(stats));
+        total
+    }
+}
+
+#[derive(Default)]
+struct LedgerState {
+    daily: BTreeMap<NaiveDate, HashMap<SwqosType, ProviderFeeStats>>,
+    recent: VecDeque<TxFee>,
+}
+
+/// 手续费账本（线程安全，由 `TradingClient` 持有）
+#[derive(Default)]
+pub struct FeeLedger {
+    state: Mutex<LedgerState>,
+}
+
+impl FeeLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次发送
+    pub fn record_submitted(&self, swqos_type: SwqosType) {
+        self.record_submitted_on(Utc::now().date_naive(), swqos_type);
+    }
+
+    /// 记录一笔已确认上链的交易
+    pub fn record_landed(&self, fee: TxFee, success: bool) {
+        self.record_landed_on(Utc::now().date_naive(), fee, success);
+    }
+
+    fn record_submitted_on(&self, day: NaiveDate, swqos_type: SwqosType) {
+        let mut state = self.state.lock();
+        state.daily.entry(day).or_default().entry(swqos_type).or_default().submitted += 1;
+    }
+
+    fn record_landed_on(&self, day: NaiveDate, fee: TxFee, success: bool) {
+        let mut state = self.state.lock();
+        let stats = state.daily.entry(day).or_default().entry(fee.swqos_type).or_default();
+        stats.landed += 1;
+        if success {
+            stats.succeeded += 1;
+        }
+        stats.priority_fee_lamports += fee.priority_fee_lamports;
+        stats.tip_lamports += fee.tip_lamports;
+
+        if state.recent.len() >= RECENT_FEES_CAPACITY {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(fee);
+    }
+
+    /// 查询指定签名的手续费明细（仅包含已确认上链的交易）
+    pub fn fees_for(&self, signatures: &[Signature]) -> Vec<TxFee> {
+        let state = self.state.lock();
+        state
+            .recent
+            .iter()
+            .filter(|fee| signatures.contains(&fee.signature))
+            .copied()
+            .collect()
+    }
+
+    /// 当前统计快照
+    pub fn report(&self) -> FeeReport {
+        FeeReport { daily: self.state.lock().daily.clone() }
+    }
+
+    /// 清空所有统计
+    pub fn reset(&self) {
+        *self.state.lock() = LedgerState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_fee_rounds_up() {
+        assert_eq!(priority_fee_lamports(200_000, 1_000_000), 200_000);
+        assert_eq!(priority_fee_lamports(1, 1), 1);
+        assert_eq!(priority_fee_lamports(0, 5), 0);
+    }
+
+    #[test]
+    fn test_ledger_groups_by_day_and_provider() {
+        let ledger = FeeLedger::new();
+        let day1 = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+
+        ledger.record_submitted_on(day1, SwqosType::Jito);
+        ledger.record_submitted_on(day1, SwqosType::Jito);
+        ledger.record_submitted_on(day2, SwqosType::NextBlock);
+
+        let landed =
+            TxFee::new(Signature::default(), SwqosType::Jito, 100_000, 10_000, 0.001, true);
+        ledger.record_landed_on(day1, landed, true);
+        let failed =
+            TxFee::new(Signature::default(), SwqosType::NextBlock, 100_000, 0, 0.01, false);
+        ledger.record_landed_on(day2, failed, false);
+
+        let report = ledger.report();
+        let jito = report.by_provider()[&SwqosType::Jito];
+        assert_eq!(jito.submitted, 2);
+        assert_eq!(jito.landed, 1);
+        assert_eq!(jito.tip_lamports, 1_000_000);
+        assert_eq!(jito.priority_fee_lamports, 1_000);
+        assert_eq!(jito.landing_rate(), 0.5);
+        assert_eq!(report.by_provider()[&SwqosType::NextBlock].tip_lamports, 0);
+        assert_eq!(report.by_day().len(), 2);
+        assert_eq!(report.total().total_lamports(), 1_001_000);
+        assert_eq!(ledger.fees_for(&[Signature::default()]).len(), 2);
+
+        ledger.reset();
+        assert!(ledger.report().daily.is_empty());
+    }
+}
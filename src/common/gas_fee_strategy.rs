@@ -1,7 +1,16 @@
+use crate::common::fee_ledger::{priority_fee_lamports, sol_to_lamports};
+use crate::common::units::{ComputeUnits, FeeSpec, MicroLamports, Sol};
 use crate::swqos::{SwqosType, TradeType};
+use anyhow::{Result, anyhow};
 use arc_swap::ArcSwap;
-use std::collections::HashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 小时预算的统计窗口
+const HOURLY_BUDGET_WINDOW: Duration = Duration::from_secs(3600);
 
 /// Gas 策略的键类型：由 (SWQOS 类型, 交易类型, 策略类型) 组成
 type GasStrategyKey = (SwqosType, TradeType, GasFeeStrategyType);
@@ -22,7 +31,7 @@ pub struct HighLowFeeParams {
     pub high_tip: f64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GasFeeStrategyType {
     Normal,
     LowTipHighCuPrice,
@@ -35,16 +44,114 @@ impl GasFeeStrategyType {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct GasFeeStrategyValue {
     pub cu_limit: u32,
     pub cu_price: u64,
     pub tip: f64,
 }
 
-#[derive(Clone)]
+/// 手续费上限（全部为 `None` 时不做限制）
+///
+/// - `max_priority_fee_lamports`：单笔交易优先费上限（cu_price × cu_limit / 1e6）；
+/// - `max_tip`：单笔交易小费上限（SOL）；
+/// - `hourly_budget_lamports`：最近一小时内优先费与小费合计上限，耗尽后拒绝新交易。
+///
+/// 超出上限的交易在构建阶段直接返回错误，不会发送。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GasFeeCaps {
+    pub max_priority_fee_lamports: Option<u64>,
+    pub max_tip: Option<f64>,
+    pub hourly_budget_lamports: Option<u64>,
+}
+
+impl GasFeeCaps {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_priority_fee_lamports(mut self, lamports: u64) -> Self {
+        self.max_priority_fee_lamports = Some(lamports);
+        self
+    }
+
+    pub fn with_max_tip(mut self, tip: f64) -> Self {
+        self.max_tip = Some(tip);
+        self
+    }
+
+    pub fn with_hourly_budget_lamports(mut self, lamports: u64) -> Self {
+        self.hourly_budget_lamports = Some(lamports);
+        self
+    }
+
+    /// 检查单条策略是否超出单笔上限
+    pub fn check(&self, swqos_type: SwqosType, value: &GasFeeStrategyValue) -> Result<()> {
+        if let Some(max) = self.max_priority_fee_lamports {
+            let fee = priority_fee_lamports(value.cu_limit, value.cu_price);
+            if fee > max {
+                return Err(anyhow!(
+                    "{:?} priority fee {} lamports (cu_price {} × cu_limit {}) exceeds cap {}",
+                    swqos_type,
+                    fee,
+                    value.cu_price,
+                    value.cu_limit,
+                    max
+                ));
+            }
+        }
+        if let Some(max) = self.max_tip
+            && value.tip > max
+        {
+            return Err(anyhow!("{:?} tip {} SOL exceeds cap {} SOL", swqos_type, value.tip, max));
+        }
+        Ok(())
+    }
+}
+
+/// 单条策略（用于序列化与比较）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GasFeeStrategyEntry {
+    pub swqos_type: SwqosType,
+    pub trade_type: TradeType,
+    pub strategy_type: GasFeeStrategyType,
+    pub value: GasFeeStrategyValue,
+}
+
+/// 两个策略之间的一处差异
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GasFeeStrategyChange {
+    /// 仅存在于新策略中
+    Added(GasFeeStrategyEntry),
+    /// 仅存在于旧策略中
+    Removed(GasFeeStrategyEntry),
+    /// 同一 (SwqosType, TradeType, GasFeeStrategyType) 的参数发生变化
+    Changed { before: GasFeeStrategyEntry, after: GasFeeStrategyEntry },
+}
+
+/// [`GasFeeStrategy`] 的序列化形式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GasFeeStrategySnapshot {
+    strategies: Vec<GasFeeStrategyEntry>,
+    #[serde(default)]
+    tip_splits: Vec<(SwqosType, usize)>,
+    #[serde(default)]
+    caps: GasFeeCaps,
+}
+
+/// Gas 费率策略
+///
+/// 可通过 serde 序列化为 `{ "strategies": [...], "tip_splits": [...] }`，便于写入配置文件后重新加载。
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "GasFeeStrategySnapshot", from = "GasFeeStrategySnapshot")]
 pub struct GasFeeStrategy {
     strategies: GasStrategyStorage,
+    /// 每个 SWQOS 的小费拆分份数（未设置时为 1，即单笔转账）
+    tip_splits: Arc<ArcSwap<HashMap<SwqosType, usize>>>,
+    /// 手续费上限
+    caps: Arc<ArcSwap<GasFeeCaps>>,
+    /// 最近一小时已预留的手续费（时间, lamports），所有克隆共享
+    hourly_spend: Arc<Mutex<VecDeque<(Instant, u64)>>>,
 }
 
 impl Default for GasFeeStrategy {
@@ -53,13 +160,119 @@ impl Default for GasFeeStrategy {
     }
 }
 
+impl From<GasFeeStrategy> for GasFeeStrategySnapshot {
+    fn from(strategy: GasFeeStrategy) -> Self {
+        let mut tip_splits: Vec<(SwqosType, usize)> =
+            strategy.tip_splits.load().iter().map(|(k, v)| (*k, *v)).collect();
+        tip_splits.sort_by_key(|(swqos_type, _)| swqos_order(swqos_type));
+        Self {
+            strategies: strategy.entries(),
+            tip_splits,
+            caps: strategy.fee_caps(),
+        }
+    }
+}
+
+impl From<GasFeeStrategySnapshot> for GasFeeStrategy {
+    fn from(snapshot: GasFeeStrategySnapshot) -> Self {
+        let strategies = snapshot
+            .strategies
+            .into_iter()
+            .map(|e| ((e.swqos_type, e.trade_type, e.strategy_type), e.value))
+            .collect();
+        Self {
+            strategies: Arc::new(ArcSwap::from_pointee(strategies)),
+            tip_splits: Arc::new(ArcSwap::from_pointee(snapshot.tip_splits.into_iter().collect())),
+            caps: Arc::new(ArcSwap::from_pointee(snapshot.caps)),
+            hourly_spend: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+}
+
+/// 用于排序输出的 SwqosType 序号
+fn swqos_order(swqos_type: &SwqosType) -> usize {
+    SwqosType::values().iter().position(|t| t == swqos_type).unwrap_or(usize::MAX)
+}
+
+fn trade_type_order(trade_type: &TradeType) -> usize {
+    match trade_type {
+        TradeType::Create => 0,
+        TradeType::CreateAndBuy => 1,
+        TradeType::Buy => 2,
+        TradeType::Sell => 3,
+    }
+}
+
 impl GasFeeStrategy {
     pub fn new() -> Self {
-        Self { strategies: Arc::new(ArcSwap::from_pointee(HashMap::new())) }
+        Self {
+            strategies: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            tip_splits: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            caps: Arc::new(ArcSwap::from_pointee(GasFeeCaps::default())),
+            hourly_spend: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// 保守预设：低优先费、低小费，适合不抢时效的日常交易
+    ///
+    /// | 方向 | cu_limit | cu_price (micro-lamports) | tip (SOL) |
+    /// |------|----------|---------------------------|-----------|
+    /// | 买入 | 150_000  | 100_000                   | 0.0001    |
+    /// | 卖出 | 150_000  | 100_000                   | 0.0001    |
+    pub fn conservative() -> Self {
+        let strategy = Self::new();
+        let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(100_000), Sol(0.0001));
+        strategy.set_global_fees(fees, fees);
+        strategy
+    }
+
+    /// 狙击预设：买入使用高优先费与高小费争取同区块落地，卖出保持常规
+    ///
+    /// | 方向 | cu_limit | cu_price (micro-lamports) | tip (SOL) |
+    /// |------|----------|---------------------------|-----------|
+    /// | 买入 | 200_000  | 2_000_000                 | 0.005     |
+    /// | 卖出 | 150_000  | 500_000                   | 0.001     |
+    pub fn aggressive_snipe() -> Self {
+        let strategy = Self::new();
+        strategy.set_global_fees(
+            FeeSpec::new(ComputeUnits(200_000), MicroLamports(2_000_000), Sol(0.005)),
+            FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001)),
+        );
+        strategy
+    }
+
+    /// 离场预设：卖出优先落地（止损 / 清仓），买入保持常规
+    ///
+    /// | 方向 | cu_limit | cu_price (micro-lamports) | tip (SOL) |
+    /// |------|----------|---------------------------|-----------|
+    /// | 买入 | 150_000  | 500_000                   | 0.001     |
+    /// | 卖出 | 200_000  | 3_000_000                 | 0.005     |
+    pub fn exit_priority() -> Self {
+        let strategy = Self::new();
+        strategy.set_global_fees(
+            FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001)),
+            FeeSpec::new(ComputeUnits(200_000), MicroLamports(3_000_000), Sol(0.005)),
+        );
+        strategy
+    }
+
+    /// 设置全局费率策略：所有 SWQOS 的买入使用 `buy`、卖出使用 `sell`，Default（RPC）不带小费
+    /// Set global fee strategy with typed units
+    pub fn set_global_fees(&self, buy: FeeSpec, sell: FeeSpec) {
+        for swqos_type in SwqosType::values() {
+            let (buy, sell) = if swqos_type == SwqosType::Default {
+                (buy.without_tip(), sell.without_tip())
+            } else {
+                (buy, sell)
+            };
+            self.set_fee(swqos_type, TradeType::Buy, GasFeeStrategyType::Normal, buy);
+            self.set_fee(swqos_type, TradeType::Sell, GasFeeStrategyType::Normal, sell);
+        }
     }
 
     /// 设置全局费率策略
     /// Set global fee strategy
+    #[deprecated(note = "Use set_global_fees with FeeSpec instead")]
     pub fn set_global_fee_strategy(
         &self,
         buy_cu_limit: u32,
@@ -69,42 +282,9 @@ impl GasFeeStrategy {
         buy_tip: f64,
         sell_tip: f64,
     ) {
-        for swqos_type in SwqosType::values() {
-            if swqos_type.eq(&SwqosType::Default) {
-                continue;
-            }
-            self.set(
-                swqos_type,
-                TradeType::Buy,
-                GasFeeStrategyType::Normal,
-                buy_cu_limit,
-                buy_cu_price,
-                buy_tip,
-            );
-            self.set(
-                swqos_type,
-                TradeType::Sell,
-                GasFeeStrategyType::Normal,
-                sell_cu_limit,
-                sell_cu_price,
-                sell_tip,
-            );
-        }
-        self.set(
-            SwqosType::Default,
-            TradeType::Buy,
-            GasFeeStrategyType::Normal,
-            buy_cu_limit,
-            buy_cu_price,
-            0.0,
-        );
-        self.set(
-            SwqosType::Default,
-            TradeType::Sell,
-            GasFeeStrategyType::Normal,
-            sell_cu_limit,
-            sell_cu_price,
-            0.0,
+        self.set_global_fees(
+            FeeSpec::new(ComputeUnits(buy_cu_limit), MicroLamports(buy_cu_price), Sol(buy_tip)),
+            FeeSpec::new(ComputeUnits(sell_cu_limit), MicroLamports(sell_cu_price), Sol(sell_tip)),
         );
     }
 
@@ -171,8 +351,20 @@ impl GasFeeStrategy {
         );
     }
 
+    /// 为多个服务类型添加标准费率策略，会移除(SwqosType,TradeType)的高低价策略。
+    /// Add normal fee strategies with typed units, Will remove the high-low strategies of (SwqosType,TradeType)
+    pub fn set_normal_fees(&self, swqos_types: &[SwqosType], buy: FeeSpec, sell: FeeSpec) {
+        for swqos_type in swqos_types {
+            self.del_all(*swqos_type, TradeType::Buy);
+            self.del_all(*swqos_type, TradeType::Sell);
+            self.set_fee(*swqos_type, TradeType::Buy, GasFeeStrategyType::Normal, buy);
+            self.set_fee(*swqos_type, TradeType::Sell, GasFeeStrategyType::Normal, sell);
+        }
+    }
+
     /// 为多个服务类型添加标准费率策略，会移除(SwqosType,TradeType)的高低价策略。
     /// Add normal fee strategies for multiple service types, Will remove the high-low strategies of (SwqosType,TradeType)
+    #[deprecated(note = "Use set_normal_fees with FeeSpec instead")]
     pub fn set_normal_fee_strategies(
         &self,
         swqos_types: &[SwqosType],
@@ -181,28 +373,14 @@ impl GasFeeStrategy {
         buy_tip: f64,
         sell_tip: f64,
     ) {
-        for swqos_type in swqos_types {
-            self.del_all(*swqos_type, TradeType::Buy);
-            self.del_all(*swqos_type, TradeType::Sell);
-            self.set(
-                *swqos_type,
-                TradeType::Buy,
-                GasFeeStrategyType::Normal,
-                cu_limit,
-                cu_price,
-                buy_tip,
-            );
-            self.set(
-                *swqos_type,
-                TradeType::Sell,
-                GasFeeStrategyType::Normal,
-                cu_limit,
-                cu_price,
-                sell_tip,
-            );
-        }
+        self.set_normal_fees(
+            swqos_types,
+            FeeSpec::new(ComputeUnits(cu_limit), MicroLamports(cu_price), Sol(buy_tip)),
+            FeeSpec::new(ComputeUnits(cu_limit), MicroLamports(cu_price), Sol(sell_tip)),
+        );
     }
 
+    #[deprecated(note = "Use set_normal_fees with FeeSpec instead")]
     pub fn set_normal_fee_strategy(
         &self,
         swqos_type: SwqosType,
@@ -211,26 +389,25 @@ impl GasFeeStrategy {
         buy_tip: f64,
         sell_tip: f64,
     ) {
-        self.del_all(swqos_type, TradeType::Buy);
-        self.del_all(swqos_type, TradeType::Sell);
-        self.set(
-            swqos_type,
-            TradeType::Buy,
-            GasFeeStrategyType::Normal,
-            cu_limit,
-            cu_price,
-            buy_tip,
-        );
-        self.set(
-            swqos_type,
-            TradeType::Sell,
-            GasFeeStrategyType::Normal,
-            cu_limit,
-            cu_price,
-            sell_tip,
+        self.set_normal_fees(
+            &[swqos_type],
+            FeeSpec::new(ComputeUnits(cu_limit), MicroLamports(cu_price), Sol(buy_tip)),
+            FeeSpec::new(ComputeUnits(cu_limit), MicroLamports(cu_price), Sol(sell_tip)),
         );
     }
 
+    /// 设置单条策略（带单位）
+    pub fn set_fee(
+        &self,
+        swqos_type: SwqosType,
+        trade_type: TradeType,
+        strategy_type: GasFeeStrategyType,
+        spec: FeeSpec,
+    ) {
+        let GasFeeStrategyValue { cu_limit, cu_price, tip } = spec.into();
+        self.set(swqos_type, trade_type, strategy_type, cu_limit, cu_price, tip);
+    }
+
     pub fn set(
         &self,
         swqos_type: SwqosType,
@@ -315,32 +492,33 @@ impl GasFeeStrategy {
         self.strategies.store(Arc::new(HashMap::new()));
     }
 
-    /// 动态更新买入小费（保持其他参数不变）
-    /// Dynamically update buy tip (keep other parameters unchanged)
-    pub fn update_buy_tip(&self, buy_tip: f64) {
+    /// 动态更新某一方向全部策略的小费（保持其他参数不变）；`Sol` 原样保存，不经 lamports 取整
+    /// Dynamically update the tip of all strategies for `trade_type`
+    pub fn update_tip(&self, trade_type: TradeType, tip: impl Into<Sol>) {
+        let tip = tip.into().0;
         self.strategies.rcu(|current_map| {
             let mut new_map = (**current_map).clone();
-            for ((_swqos_type, trade_type, _strategy_type), value) in new_map.iter_mut() {
-                if *trade_type == TradeType::Buy {
-                    value.tip = buy_tip;
+            for ((_swqos_type, strategy_trade_type, _strategy_type), value) in new_map.iter_mut() {
+                if *strategy_trade_type == trade_type {
+                    value.tip = tip;
                 }
             }
             Arc::new(new_map)
         });
     }
 
+    /// 动态更新买入小费（保持其他参数不变）
+    /// Dynamically update buy tip (keep other parameters unchanged)
+    #[deprecated(note = "Use update_tip(TradeType::Buy, ..) with Lamports or Sol instead")]
+    pub fn update_buy_tip(&self, buy_tip: f64) {
+        self.update_tip(TradeType::Buy, Sol(buy_tip));
+    }
+
     /// 动态更新卖出小费（保持其他参数不变）
     /// Dynamically update sell tip (keep other parameters unchanged)
+    #[deprecated(note = "Use update_tip(TradeType::Sell, ..) with Lamports or Sol instead")]
     pub fn update_sell_tip(&self, sell_tip: f64) {
-        self.strategies.rcu(|current_map| {
-            let mut new_map = (**current_map).clone();
-            for ((_swqos_type, trade_type, _strategy_type), value) in new_map.iter_mut() {
-                if *trade_type == TradeType::Sell {
-                    value.tip = sell_tip;
-                }
-            }
-            Arc::new(new_map)
-        });
+        self.update_tip(TradeType::Sell, Sol(sell_tip));
     }
 
     /// 动态更新买入优先费（保持其他参数不变）
@@ -371,6 +549,192 @@ impl GasFeeStrategy {
         });
     }
 
+    /// 设置小费拆分份数：将小费平均拆分为 `count` 笔，转入随机选取的 `count` 个小费账户。
+    /// 部分服务对同一 bundle 内分散到多个小费账户的小费有更好的落地表现。
+    /// 单份会低于该服务最低小费时自动减少份数，见 [`Self::tip_split_for`]。
+    /// Split the tip across `count` randomly selected tip accounts of the provider
+    pub fn set_tip_split(&self, swqos_type: SwqosType, count: usize) {
+        self.tip_splits.rcu(|current_map| {
+            let mut new_map = (**current_map).clone();
+            if count <= 1 {
+                new_map.remove(&swqos_type);
+            } else {
+                new_map.insert(swqos_type, count);
+            }
+            Arc::new(new_map)
+        });
+    }
+
+    /// 为所有服务类型设置小费拆分份数（Default 不使用小费，忽略）
+    /// Set tip split count for all service types
+    pub fn set_tip_split_all(&self, count: usize) {
+        for swqos_type in SwqosType::values() {
+            if swqos_type.eq(&SwqosType::Default) {
+                continue;
+            }
+            self.set_tip_split(swqos_type, count);
+        }
+    }
+
+    /// 获取小费拆分份数（至少为 1）
+    /// Get tip split count (at least 1)
+    pub fn get_tip_split(&self, swqos_type: SwqosType) -> usize {
+        self.tip_splits.load().get(&swqos_type).copied().unwrap_or(1).max(1)
+    }
+
+    /// 实际使用的小费拆分份数：拆分后每份不低于该服务的最低小费，
+    /// 小费不足以按 [`Self::get_tip_split`] 拆分时减少份数（至少为 1）
+    /// Tip split count clamped so that every share stays at or above the provider's minimum tip
+    pub fn tip_split_for(&self, swqos_type: SwqosType, tip: f64) -> usize {
+        let requested = self.get_tip_split(swqos_type);
+        let min_tip = sol_to_lamports(swqos_type.min_tip());
+        if requested <= 1 || min_tip == 0 {
+            return requested;
+        }
+        let shares = sol_to_lamports(tip) / min_tip;
+        usize::try_from(shares).unwrap_or(usize::MAX).clamp(1, requested)
+    }
+
+    /// 设置手续费上限
+    /// Set hard caps on priority fee, tip and hourly spend
+    pub fn set_fee_caps(&self, caps: GasFeeCaps) {
+        self.caps.store(Arc::new(caps));
+    }
+
+    /// 当前手续费上限
+    pub fn fee_caps(&self) -> GasFeeCaps {
+        **self.caps.load()
+    }
+
+    /// 各策略的 cu_limit 乘以 `factor`（不超过 `max`）的副本，用于多腿交易按腿数放大 compute budget
+    ///
+    /// 小费拆分、费用上限与小时预算仍与原策略共享。
+    pub(crate) fn with_scaled_cu_limit(&self, factor: u32, max: u32) -> Self {
+        let scaled: GasStrategyMap = self
+            .strategies
+            .load()
+            .iter()
+            .map(|(key, value)| {
+                let cu_limit = value.cu_limit.saturating_mul(factor).min(max);
+                (*key, GasFeeStrategyValue { cu_limit, ..*value })
+            })
+            .collect();
+        Self {
+            strategies: Arc::new(ArcSwap::from_pointee(scaled)),
+            tip_splits: self.tip_splits.clone(),
+            caps: self.caps.clone(),
+            hourly_spend: self.hourly_spend.clone(),
+        }
+    }
+
+    /// 最近一小时已预留的手续费（lamports）
+    pub fn hourly_spent_lamports(&self) -> u64 {
+        let mut spend = self.hourly_spend.lock();
+        Self::evict_expired(&mut spend, Instant::now());
+        spend.iter().map(|(_, lamports)| lamports).sum()
+    }
+
+    fn evict_expired(spend: &mut VecDeque<(Instant, u64)>, now: Instant) {
+        while let Some((at, _)) = spend.front() {
+            if now.duration_since(*at) < HOURLY_BUDGET_WINDOW {
+                break;
+            }
+            spend.pop_front();
+        }
+    }
+
+    /// 在发送前检查本次交易将使用的策略：单笔上限逐条检查，再按最坏情况预留小时预算
+    ///
+    /// `exclusive = true` 表示各 SWQOS 的交易共用 durable nonce、最多一笔上链，只预留最高的一笔；
+    /// 否则（如未使用 durable nonce 的卖出）每笔都可能上链，预留全部花费之和。
+    /// 预留在发送前完成且不退还，交易未上链时预算也会被占用。
+    pub fn enforce_caps(
+        &self,
+        configs: &[(SwqosType, GasFeeStrategyType, GasFeeStrategyValue)],
+        with_tip: bool,
+        exclusive: bool,
+    ) -> Result<()> {
+        let caps = self.fee_caps();
+        let mut max_spend = 0u64;
+        let mut total_spend = 0u64;
+        for (swqos_type, _, value) in configs {
+            caps.check(*swqos_type, value)?;
+            let tip = if with_tip && *swqos_type != SwqosType::Default { value.tip } else { 0.0 };
+            let spend =
+                priority_fee_lamports(value.cu_limit, value.cu_price) + sol_to_lamports(tip);
+            max_spend = max_spend.max(spend);
+            total_spend = total_spend.saturating_add(spend);
+        }
+        let max_spend = if exclusive { max_spend } else { total_spend };
+        if let Some(budget) = caps.hourly_budget_lamports {
+            let now = Instant::now();
+            let mut spend = self.hourly_spend.lock();
+            Self::evict_expired(&mut spend, now);
+            let spent: u64 = spend.iter().map(|(_, lamports)| lamports).sum();
+            if spent + max_spend > budget {
+                return Err(anyhow!(
+                    "hourly fee budget exhausted: spent {} + {} lamports exceeds budget {}",
+                    spent,
+                    max_spend,
+                    budget
+                ));
+            }
+            spend.push_back((now, max_spend));
+        }
+        Ok(())
+    }
+
+    /// 所有策略（按 SwqosType、TradeType、GasFeeStrategyType 排序）
+    /// All strategies as structured entries, in a stable order
+    pub fn entries(&self) -> Vec<GasFeeStrategyEntry> {
+        let mut entries: Vec<GasFeeStrategyEntry> = self
+            .strategies
+            .load()
+            .iter()
+            .map(|((swqos_type, trade_type, strategy_type), value)| GasFeeStrategyEntry {
+                swqos_type: *swqos_type,
+                trade_type: *trade_type,
+                strategy_type: *strategy_type,
+                value: *value,
+            })
+            .collect();
+        entries.sort_by_key(|e| {
+            (
+                swqos_order(&e.swqos_type),
+                trade_type_order(&e.trade_type),
+                GasFeeStrategyType::values().iter().position(|t| *t == e.strategy_type),
+            )
+        });
+        entries
+    }
+
+    /// 与另一策略比较，返回 `other` 相对 `self` 的变化
+    /// Compare with another strategy; changes describe how to go from `self` to `other`
+    pub fn diff(&self, other: &GasFeeStrategy) -> Vec<GasFeeStrategyChange> {
+        let before = self.strategies.load();
+        let after = other.strategies.load();
+        let mut changes = Vec::new();
+        for entry in self.entries() {
+            let key = (entry.swqos_type, entry.trade_type, entry.strategy_type);
+            match after.get(&key) {
+                None => changes.push(GasFeeStrategyChange::Removed(entry)),
+                Some(value) if *value != entry.value => {
+                    changes.push(GasFeeStrategyChange::Changed {
+                        before: entry,
+                        after: GasFeeStrategyEntry { value: *value, ..entry },
+                    })
+                },
+                Some(_) => {},
+            }
+        }
+        for entry in other.entries() {
+            if !before.contains_key(&(entry.swqos_type, entry.trade_type, entry.strategy_type)) {
+                changes.push(GasFeeStrategyChange::Added(entry));
+            }
+        }
+        changes
+    }
+
     /// 打印所有策略。
     /// Print all strategies
     pub fn print_all_strategies(&self) {
@@ -382,3 +746,143 @@ impl GasFeeStrategy {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tip_split_defaults_and_picks_distinct_accounts() {
+        let strategy = GasFeeStrategy::new();
+        assert_eq!(strategy.get_tip_split(SwqosType::Jito), 1);
+
+        strategy.set_tip_split(SwqosType::Jito, 3);
+        assert_eq!(strategy.get_tip_split(SwqosType::Jito), 3);
+        assert_eq!(strategy.clone().get_tip_split(SwqosType::Jito), 3);
+
+        let primary = SwqosType::Jito.tip_accounts()[0];
+        let picked = SwqosType::Jito.pick_tip_accounts(primary, 3);
+        assert_eq!(picked.len(), 3);
+        assert_eq!(picked[0], primary);
+        let unique: std::collections::HashSet<_> = picked.iter().collect();
+        assert_eq!(unique.len(), 3);
+
+        strategy.set_tip_split(SwqosType::Jito, 1);
+        assert_eq!(strategy.get_tip_split(SwqosType::Jito), 1);
+    }
+
+    #[test]
+    fn test_tip_split_respects_min_tip() {
+        let strategy = GasFeeStrategy::new();
+        strategy.set_tip_split(SwqosType::Jito, 3);
+        let min_tip = SwqosType::Jito.min_tip();
+        assert_eq!(strategy.tip_split_for(SwqosType::Jito, min_tip * 10.0), 3);
+        assert_eq!(strategy.tip_split_for(SwqosType::Jito, min_tip * 2.5), 2);
+        assert_eq!(strategy.tip_split_for(SwqosType::Jito, min_tip), 1);
+        assert_eq!(strategy.tip_split_for(SwqosType::Jito, 0.0), 1);
+        assert_eq!(strategy.tip_split_for(SwqosType::NextBlock, min_tip * 10.0), 1);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_update_tip_keeps_exact_sol_value() {
+        let strategy = GasFeeStrategy::new();
+        strategy.set(
+            SwqosType::Jito,
+            TradeType::Buy,
+            GasFeeStrategyType::Normal,
+            200_000,
+            1,
+            0.001,
+        );
+        strategy.set(
+            SwqosType::Jito,
+            TradeType::Sell,
+            GasFeeStrategyType::Normal,
+            200_000,
+            1,
+            0.001,
+        );
+
+        strategy.update_buy_tip(0.0000000015);
+        assert_eq!(strategy.get_strategies(TradeType::Buy)[0].2.tip, 0.0000000015);
+        strategy.update_tip(TradeType::Sell, crate::common::units::Lamports(1_500));
+        assert_eq!(strategy.get_strategies(TradeType::Sell)[0].2.tip, 0.0000015);
+        assert_eq!(strategy.get_strategies(TradeType::Buy)[0].2.tip, 0.0000000015);
+    }
+
+    #[test]
+    fn test_presets_serde_round_trip_and_diff() {
+        let conservative = GasFeeStrategy::conservative();
+        conservative.set_tip_split(SwqosType::Jito, 2);
+        let json = serde_json::to_string(&conservative).unwrap();
+        let restored: GasFeeStrategy = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.entries(), conservative.entries());
+        assert_eq!(restored.get_tip_split(SwqosType::Jito), 2);
+        assert!(conservative.diff(&restored).is_empty());
+
+        let snipe = GasFeeStrategy::aggressive_snipe();
+        let changes = conservative.diff(&snipe);
+        assert!(!changes.is_empty());
+        assert!(changes.iter().all(|c| matches!(c, GasFeeStrategyChange::Changed { .. })));
+
+        let empty = GasFeeStrategy::new();
+        assert!(empty.diff(&snipe).iter().all(|c| matches!(c, GasFeeStrategyChange::Added(_))));
+        assert_eq!(empty.fee_caps(), GasFeeCaps::default());
+    }
+
+    #[test]
+    fn test_fee_caps_and_hourly_budget() {
+        let strategy = GasFeeStrategy::new();
+        let value = GasFeeStrategyValue { cu_limit: 200_000, cu_price: 500_000, tip: 0.001 };
+        let configs = [(SwqosType::Jito, GasFeeStrategyType::Normal, value)];
+        // 200_000 × 500_000 / 1e6 = 100_000 lamports 优先费 + 1_000_000 lamports 小费
+        strategy.set_fee_caps(GasFeeCaps::new().with_max_priority_fee_lamports(50_000));
+        assert!(strategy.enforce_caps(&configs, true, true).is_err());
+
+        strategy.set_fee_caps(GasFeeCaps::new().with_max_tip(0.0005));
+        assert!(strategy.enforce_caps(&configs, true, true).is_err());
+
+        strategy.set_fee_caps(GasFeeCaps::new().with_hourly_budget_lamports(2_500_000));
+        assert!(strategy.enforce_caps(&configs, true, true).is_ok());
+        assert!(strategy.clone().enforce_caps(&configs, true, true).is_ok());
+        assert_eq!(strategy.hourly_spent_lamports(), 2_200_000);
+        assert!(strategy.enforce_caps(&configs, true, true).is_err());
+        assert_eq!(strategy.hourly_spent_lamports(), 2_200_000);
+    }
+
+    #[test]
+    fn test_hourly_budget_reserves_sum_when_every_provider_can_land() {
+        let strategy = GasFeeStrategy::new();
+        let value = GasFeeStrategyValue { cu_limit: 200_000, cu_price: 500_000, tip: 0.001 };
+        let configs = [
+            (SwqosType::Jito, GasFeeStrategyType::Normal, value),
+            (SwqosType::Bloxroute, GasFeeStrategyType::Normal, value),
+        ];
+        strategy.set_fee_caps(GasFeeCaps::new().with_hourly_budget_lamports(3_000_000));
+        // 共用 durable nonce：只预留最高的一笔
+        assert!(strategy.enforce_caps(&configs, true, true).is_ok());
+        assert_eq!(strategy.hourly_spent_lamports(), 1_100_000);
+        // 各笔均可上链：预留两笔之和，超出剩余预算
+        assert!(strategy.enforce_caps(&configs, true, false).is_err());
+        assert!(strategy.clone().enforce_caps(&configs[..1], true, false).is_ok());
+        assert_eq!(strategy.hourly_spent_lamports(), 2_200_000);
+    }
+
+    #[test]
+    fn test_scaled_cu_limit_shares_caps() {
+        let strategy = GasFeeStrategy::new();
+        strategy.set_normal_fee_strategy(SwqosType::Jito, 300_000, 1_000, 0.001, 0.001);
+        strategy.set_fee_caps(GasFeeCaps::new().with_max_tip(0.01));
+
+        let scaled = strategy.with_scaled_cu_limit(3, 800_000);
+        let (_, _, value) = scaled.get_strategies(TradeType::Buy)[0];
+        assert_eq!(value.cu_limit, 800_000);
+        assert_eq!(value.cu_price, 1_000);
+        let (_, _, original) = strategy.get_strategies(TradeType::Buy)[0];
+        assert_eq!(original.cu_limit, 300_000);
+
+        strategy.set_fee_caps(GasFeeCaps::new().with_max_tip(0.02));
+        assert_eq!(scaled.fee_caps().max_tip, Some(0.02));
+    }
+}
@@ -0,0 +1,236 @@
+//! 加密的密钥存储
+//!
+//! 以口令加密保存 / 读取 Keypair，避免在源码或配置中保存明文 base58 私钥：
+//! - 密钥派生：PBKDF2-HMAC-SHA256（随机 16 字节 salt）
+//! - 加密：AES-256-GCM（随机 12 字节 nonce）
+//!
+//! 派生出的密钥、明文私钥与口令均以 [`Zeroizing`] 包装，释放时清零。
+//!
+//! 文件为 JSON 格式，可与配置一同纳入版本管理。口令可直接传入，或通过
+//! [`passphrase_from_env`] 从环境变量读取。
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Result, anyhow};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use solana_sdk::signature::Keypair;
+use std::io::Write;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// 当前文件格式版本
+pub const KEYSTORE_VERSION: u32 = 1;
+/// 默认 PBKDF2 迭代次数
+pub const DEFAULT_KDF_ITERATIONS: u32 = 600_000;
+/// 允许的最小 PBKDF2 迭代次数
+pub const MIN_KDF_ITERATIONS: u32 = 1_000;
+/// 允许的最大 PBKDF2 迭代次数（防止篡改后的文件让解密耗时失控）
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// 加密后的密钥文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedKeypair {
+    pub version: u32,
+    /// 密钥派生算法，目前固定为 `pbkdf2-sha256`
+    pub kdf: String,
+    pub iterations: u32,
+    /// hex 编码
+    pub salt: String,
+    /// hex 编码
+    pub nonce: String,
+    /// hex 编码（含 GCM 认证标签）
+    pub ciphertext: String,
+    /// 公钥（明文，便于识别文件对应的钱包）
+    pub pubkey: String,
+}
+
+impl EncryptedKeypair {
+    /// 用口令加密 Keypair
+    pub fn encrypt(keypair: &Keypair, passphrase: &str, iterations: u32) -> Result<Self> {
+        check_iterations(iterations)?;
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut salt);
+        rand::rng().fill_bytes(&mut nonce);
+        let cipher = cipher(passphrase, &salt, iterations);
+        let secret = Zeroizing::new(keypair.to_bytes());
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), secret.as_slice())
+            .map_err(|_| anyhow!("Failed to encrypt keypair"))?;
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            kdf: "pbkdf2-sha256".to_string(),
+            iterations,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+            pubkey: solana_sdk::signer::Signer::pubkey(keypair).to_string(),
+        })
+    }
+
+    /// 用口令解密；口令错误或文件被篡改时返回错误
+    pub fn decrypt(&self, passphrase: &str) -> Result<Keypair> {
+        if self.version != KEYSTORE_VERSION || self.kdf != "pbkdf2-sha256" {
+            return Err(anyhow!(
+                "Unsupported keystore format: version {} kdf {}",
+                self.version,
+                self.kdf
+            ));
+        }
+        check_iterations(self.iterations)?;
+        let salt = hex::decode(&self.salt)?;
+        let nonce = hex::decode(&self.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(anyhow!("Invalid keystore nonce length {}", nonce.len()));
+        }
+        let ciphertext = hex::decode(&self.ciphertext)?;
+        let plaintext = cipher(passphrase, &salt, self.iterations)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map(Zeroizing::new)
+            .map_err(|_| {
+                anyhow!("Failed to decrypt keystore: wrong passphrase or corrupted file")
+            })?;
+        Keypair::try_from(plaintext.as_slice())
+            .map_err(|e| anyhow!("Invalid keypair in keystore: {}", e))
+    }
+}
+
+fn check_iterations(iterations: u32) -> Result<()> {
+    if !(MIN_KDF_ITERATIONS..=MAX_KDF_ITERATIONS).contains(&iterations) {
+        return Err(anyhow!(
+            "Keystore KDF iterations {} out of range [{}, {}]",
+            iterations,
+            MIN_KDF_ITERATIONS,
+            MAX_KDF_ITERATIONS
+        ));
+    }
+    Ok(())
+}
+
+fn cipher(passphrase: &str, salt: &[u8], iterations: u32) -> Aes256Gcm {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, key.as_mut());
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()))
+}
+
+/// 加密保存 Keypair
+///
+/// 先写入同目录下以 0600 权限新建的临时文件并 fsync，再重命名覆盖目标文件；
+/// 写入中途失败不会破坏已有的密钥文件。
+pub fn save_keypair(path: impl AsRef<Path>, keypair: &Keypair, passphrase: &str) -> Result<()> {
+    let encrypted = EncryptedKeypair::encrypt(keypair, passphrase, DEFAULT_KDF_ITERATIONS)?;
+    write_keystore(path.as_ref(), &encrypted)
+}
+
+fn write_keystore(path: &Path, encrypted: &EncryptedKeypair) -> Result<()> {
+    let data = serde_json::to_vec_pretty(encrypted)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid keystore path {}", path.display()))?;
+    let mut suffix = [0u8; 8];
+    rand::rng().fill_bytes(&mut suffix);
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        hex::encode(suffix)
+    ));
+    let result = write_owner_only(&tmp, &data).and_then(|()| Ok(std::fs::rename(&tmp, path)?));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// 以 0600 权限新建文件（已存在时报错），写入并 fsync
+fn write_owner_only(path: &Path, data: &[u8]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// 读取并解密 Keypair
+pub fn load_keypair(path: impl AsRef<Path>, passphrase: &str) -> Result<Keypair> {
+    let path = path.as_ref();
+    let data = std::fs::read(path)
+        .map_err(|e| anyhow!("Failed to read keystore {}: {}", path.display(), e))?;
+    let encrypted: EncryptedKeypair = serde_json::from_slice(&data)
+        .map_err(|e| anyhow!("Invalid keystore {}: {}", path.display(), e))?;
+    encrypted.decrypt(passphrase)
+}
+
+/// 从环境变量读取口令（释放时清零）
+pub fn passphrase_from_env(var: &str) -> Result<Zeroizing<String>> {
+    std::env::var(var)
+        .map(Zeroizing::new)
+        .map_err(|_| anyhow!("Keystore passphrase env var {} is not set", var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let keypair = Keypair::new();
+        let encrypted = EncryptedKeypair::encrypt(&keypair, "correct horse", 1_000).unwrap();
+        assert_eq!(encrypted.pubkey, keypair.pubkey().to_string());
+        assert!(!encrypted.ciphertext.contains(&hex::encode(keypair.to_bytes())));
+
+        let decrypted = encrypted.decrypt("correct horse").unwrap();
+        assert_eq!(decrypted.pubkey(), keypair.pubkey());
+        assert!(encrypted.decrypt("wrong").is_err());
+
+        let mut ciphertext = hex::decode(&encrypted.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        let tampered =
+            EncryptedKeypair { ciphertext: hex::encode(ciphertext), ..encrypted.clone() };
+        assert!(tampered.decrypt("correct horse").is_err());
+
+        // 篡改迭代次数：超出范围时直接拒绝，不执行 KDF
+        let huge = EncryptedKeypair { iterations: u32::MAX, ..encrypted };
+        let err = huge.decrypt("correct horse").unwrap_err();
+        assert!(err.to_string().contains("out of range"));
+        assert!(EncryptedKeypair::encrypt(&keypair, "correct horse", 1).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_keypair_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("keystore-{}.json", Keypair::new().pubkey()));
+        std::fs::write(&path, b"{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let keypair = Keypair::new();
+        let encrypted = EncryptedKeypair::encrypt(&keypair, "pass", MIN_KDF_ITERATIONS).unwrap();
+        write_keystore(&path, &encrypted).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(load_keypair(&path, "pass").unwrap().pubkey(), keypair.pubkey());
+        // 临时文件已重命名为目标文件，不会残留
+        let prefix = format!(".{}.", path.file_name().unwrap().to_string_lossy());
+        assert!(
+            !std::fs::read_dir(path.parent().unwrap()).unwrap().any(|entry| entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with(&prefix))
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,20 +1,46 @@
 pub mod address_lookup;
 pub mod auto_mock_rpc;
+pub mod balance_tracker;
 pub mod bonding_curve;
+pub mod cancellation;
+pub mod cluster;
 pub mod dex_pool_cache;
 pub mod fast_fn;
 pub mod fast_timing;
+pub mod fee_ledger;
 pub mod gas_fee_strategy;
 pub mod global;
+pub mod keystore;
 pub mod nonce_cache;
+pub mod pool_watcher;
+pub mod pricing;
+pub mod program_error;
+pub mod program_probe;
+pub mod rent_cache;
 pub mod rpc_client_wrapper;
+pub mod rpc_provider;
+pub mod rpc_retry;
 pub mod seed;
+pub mod shared_pool_cache;
+pub mod single_flight;
 pub mod spl_associated_token_account;
 pub mod spl_token;
 pub mod spl_token_2022;
 pub mod subscription_handle;
+pub mod transfer_hook;
 pub mod types;
+pub mod units;
 
-pub use auto_mock_rpc::{AutoMockRpcClient, PoolRpcClient};
+pub use auto_mock_rpc::{AutoMockRpcClient, MockMode, PoolRpcClient};
+pub use balance_tracker::{BalanceTracker, BalanceTrackerConfig, TokenBalance};
+pub use cancellation::{CancellationToken, cancellable};
+pub use cluster::Cluster;
 pub use gas_fee_strategy::*;
+pub use pool_watcher::{AmmV4PoolWatcher, PoolWatcherConfig, ReserveDeltaEvent, ReserveMove};
+pub use pricing::PricingConfig;
+pub use program_error::{ProgramError, ProgramErrorKind, decode_program_error};
+pub use program_probe::{ProbeMode, ProbeReport, ProgramProbeConfig};
+pub use rpc_provider::{RpcProvider, RpcProviderRef};
+pub use rpc_retry::RpcRetryPolicy;
 pub use types::*;
+pub use units::{Bps, ComputeUnits, FeeSpec, Lamports, MicroLamports, Sol};
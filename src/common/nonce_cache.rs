@@ -1,4 +1,6 @@
 use crate::common::SolanaRpcClient;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use solana_hash::Hash;
 use solana_nonce::state::State;
 use solana_nonce::versions::Versions;
@@ -38,3 +40,91 @@ pub async fn fetch_nonce_info(
     }
     None
 }
+
+/// 已登记的 nonce 账户及其最近读取的 nonce 值
+///
+/// [`TradingClient::create_nonce_accounts`](crate::TradingClient::create_nonce_accounts) 创建的账户
+/// 自动登记；nonce 每次使用后都会推进，发送前通过 [`NonceCache::refresh`] 读取当前值。
+pub struct NonceCache {
+    accounts: DashMap<Pubkey, DurableNonceInfo>,
+}
+
+static NONCE_CACHE: Lazy<NonceCache> = Lazy::new(NonceCache::new);
+
+/// 全局 nonce 账户缓存
+pub fn nonce_cache() -> &'static NonceCache {
+    &NONCE_CACHE
+}
+
+impl Default for NonceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonceCache {
+    pub fn new() -> Self {
+        Self { accounts: DashMap::new() }
+    }
+
+    /// 登记（或更新）一个 nonce 账户；`nonce_account` 为空时忽略
+    pub fn insert(&self, info: DurableNonceInfo) {
+        if let Some(nonce_account) = info.nonce_account {
+            self.accounts.insert(nonce_account, info);
+        }
+    }
+
+    pub fn remove(&self, nonce_account: &Pubkey) -> Option<DurableNonceInfo> {
+        self.accounts.remove(nonce_account).map(|(_, info)| info)
+    }
+
+    /// 最近一次读取的 nonce 信息（不访问 RPC，可能已被使用）
+    pub fn get(&self, nonce_account: &Pubkey) -> Option<DurableNonceInfo> {
+        self.accounts.get(nonce_account).map(|info| info.clone())
+    }
+
+    /// 已登记的 nonce 账户
+    pub fn accounts(&self) -> Vec<Pubkey> {
+        self.accounts.iter().map(|entry| *entry.key()).collect()
+    }
+
+    /// 通过 RPC 读取 nonce 账户的当前值并更新缓存
+    pub async fn refresh(
+        &self,
+        rpc: &SolanaRpcClient,
+        nonce_account: Pubkey,
+    ) -> Option<DurableNonceInfo> {
+        let info = fetch_nonce_info(rpc, nonce_account).await?;
+        self.insert(info.clone());
+        Some(info)
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonce_cache_registry() {
+        let cache = NonceCache::new();
+        cache.insert(DurableNonceInfo { nonce_account: None, current_nonce: None });
+        assert!(cache.is_empty());
+
+        let account = Pubkey::new_unique();
+        let nonce = Hash::new_from_array([7; 32]);
+        cache.insert(DurableNonceInfo { nonce_account: Some(account), current_nonce: Some(nonce) });
+        assert_eq!(cache.accounts(), vec![account]);
+        assert_eq!(cache.get(&account).unwrap().current_nonce, Some(nonce));
+
+        assert!(cache.remove(&account).is_some());
+        assert!(cache.get(&account).is_none());
+    }
+}
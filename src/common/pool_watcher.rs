@@ -0,0 +1,447 @@
+//! Raydium AMM V4 池子储备监控
+//!
+//! 与 [`crate::common::BalanceTracker`] 相同的订阅模型：配置了 `ws_url` 时通过 WebSocket
+//! `accountSubscribe` 订阅池子的两个 vault，断线期间按 `poll_interval` 通过 RPC 轮询兜底。
+//!
+//! 一次 swap 会分别推送 coin vault 与 pc vault 的变化。两侧都相对基线变化后视为一笔成交完成：
+//! 变化幅度超过 `threshold_bps` 时产生 [`ReserveDeltaEvent`]（包含价格变化），并以当前储备为新基线。
+//!
+//! 储备使用 vault 余额，未扣除 `need_take_pnl`，对大额成交检测足够。
+
+use crate::common::{Bps, SolanaRpcClient};
+use crate::instruction::utils::raydium_amm_v4::get_pool_by_address;
+use anyhow::{Result, anyhow};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use dashmap::DashMap;
+use futures::StreamExt;
+use futures::stream::select_all;
+use parking_lot::Mutex;
+use solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_rpc_client_api::config::RpcAccountInfoConfig;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::solana_program::program_pack::Pack;
+use spl_token::state::Account as SplTokenAccount;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// 代币账户中 amount 字段的偏移
+const TOKEN_AMOUNT_OFFSET: usize = 64;
+/// WebSocket 断线后的重连间隔
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// 池子监控配置
+#[derive(Debug, Clone)]
+pub struct PoolWatcherConfig {
+    /// WebSocket 地址（例如 `wss://...`）；为 None 时仅使用 RPC 轮询
+    pub ws_url: Option<String>,
+    /// RPC 轮询间隔（WebSocket 未连接时生效）
+    pub poll_interval: Duration,
+    /// 触发事件的储备变化阈值（基点，按 coin / pc 两侧中变化较大的一侧计算）
+    pub threshold_bps: Bps,
+}
+
+impl Default for PoolWatcherConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: None,
+            poll_interval: Duration::from_secs(2),
+            threshold_bps: Bps::new(100),
+        }
+    }
+}
+
+impl PoolWatcherConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = Some(ws_url.into());
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_threshold_bps(mut self, threshold_bps: Bps) -> Self {
+        self.threshold_bps = threshold_bps;
+        self
+    }
+}
+
+/// 储备变化方向（以 coin 代币为标的）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReserveMove {
+    /// coin 被买走：coin 储备减少、pc 储备增加，价格上涨
+    Buy,
+    /// coin 被卖入：coin 储备增加、pc 储备减少，价格下跌
+    Sell,
+    /// 两侧同向变化（加减流动性）
+    Liquidity,
+}
+
+/// 储备大幅变化事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReserveDeltaEvent {
+    pub pool: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub direction: ReserveMove,
+    pub coin_reserve_before: u64,
+    pub coin_reserve_after: u64,
+    pub pc_reserve_before: u64,
+    pub pc_reserve_after: u64,
+    /// 变化前价格（每个 coin 值多少 pc，已按精度换算）
+    pub price_before: f64,
+    /// 变化后价格
+    pub price_after: f64,
+    /// 价格变化（基点，上涨为正）
+    pub price_change_bps: f64,
+    pub detected_at: Instant,
+}
+
+/// 事件回调
+pub type ReserveDeltaCallback = Arc<dyn Fn(ReserveDeltaEvent) + Send + Sync>;
+
+/// 单个池子的监控状态
+#[derive(Debug, Clone)]
+struct WatchedPool {
+    coin_mint: Pubkey,
+    pc_mint: Pubkey,
+    coin_vault: Pubkey,
+    pc_vault: Pubkey,
+    coin_decimals: u8,
+    pc_decimals: u8,
+    /// 当前储备
+    coin_reserve: u64,
+    pc_reserve: u64,
+    /// 上一笔完整成交后的储备
+    baseline: (u64, u64),
+}
+
+impl WatchedPool {
+    fn price(&self, coin_reserve: u64, pc_reserve: u64) -> f64 {
+        if coin_reserve == 0 {
+            return 0.0;
+        }
+        let coin = coin_reserve as f64 / 10f64.powi(self.coin_decimals as i32);
+        let pc = pc_reserve as f64 / 10f64.powi(self.pc_decimals as i32);
+        pc / coin
+    }
+
+    /// 两侧都已相对基线变化时结算一次：超过阈值返回事件，并重置基线
+    fn settle(&mut self, pool: &Pubkey, threshold_bps: Bps) -> Option<ReserveDeltaEvent> {
+        let (coin_before, pc_before) = self.baseline;
+        if self.coin_reserve == coin_before || self.pc_reserve == pc_before {
+            return None;
+        }
+        self.baseline = (self.coin_reserve, self.pc_reserve);
+        let moved = |before: u64, after: u64| {
+            before == 0 || before.abs_diff(after) >= threshold_bps.of(before)
+        };
+        if !moved(coin_before, self.coin_reserve) && !moved(pc_before, self.pc_reserve) {
+            return None;
+        }
+
+        let direction = match (self.coin_reserve < coin_before, self.pc_reserve > pc_before) {
+            (true, true) => ReserveMove::Buy,
+            (false, false) => ReserveMove::Sell,
+            _ => ReserveMove::Liquidity,
+        };
+        let price_before = self.price(coin_before, pc_before);
+        let price_after = self.price(self.coin_reserve, self.pc_reserve);
+        let price_change_bps = if price_before > 0.0 {
+            (price_after - price_before) / price_before * 10_000.0
+        } else {
+            0.0
+        };
+        Some(ReserveDeltaEvent {
+            pool: *pool,
+            coin_mint: self.coin_mint,
+            pc_mint: self.pc_mint,
+            direction,
+            coin_reserve_before: coin_before,
+            coin_reserve_after: self.coin_reserve,
+            pc_reserve_before: pc_before,
+            pc_reserve_after: self.pc_reserve,
+            price_before,
+            price_after,
+            price_change_bps,
+            detected_at: Instant::now(),
+        })
+    }
+}
+
+/// Raydium AMM V4 池子储备监控器
+pub struct AmmV4PoolWatcher {
+    rpc: Arc<SolanaRpcClient>,
+    config: PoolWatcherConfig,
+    on_event: ReserveDeltaCallback,
+    /// pool -> 状态
+    pools: DashMap<Pubkey, WatchedPool>,
+    ws_connected: AtomicBool,
+    resubscribe: Notify,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl AmmV4PoolWatcher {
+    pub fn new(
+        rpc: Arc<SolanaRpcClient>,
+        config: PoolWatcherConfig,
+        on_event: ReserveDeltaCallback,
+    ) -> Self {
+        Self {
+            rpc,
+            config,
+            on_event,
+            pools: DashMap::new(),
+            ws_connected: AtomicBool::new(false),
+            resubscribe: Notify::new(),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 启动后台任务：轮询与（可选的）WebSocket 订阅
+    pub fn start(self: &Arc<Self>) {
+        let mut tasks = self.tasks.lock();
+        if !tasks.is_empty() {
+            return;
+        }
+        let watcher = self.clone();
+        tasks.push(tokio::spawn(async move { watcher.poll_loop().await }));
+        if let Some(ws_url) = self.config.ws_url.clone() {
+            let watcher = self.clone();
+            tasks.push(tokio::spawn(async move { watcher.subscribe_loop(ws_url).await }));
+        }
+    }
+
+    /// 停止后台任务
+    pub fn stop(&self) {
+        for task in self.tasks.lock().drain(..) {
+            task.abort();
+        }
+        self.ws_connected.store(false, Ordering::Relaxed);
+    }
+
+    /// 开始监控一个 AMM V4 池子，并立即通过 RPC 读取一次储备作为基线
+    pub async fn watch_pool(&self, pool: Pubkey) -> Result<()> {
+        let amm_info = get_pool_by_address(self.rpc.as_ref(), &pool).await?;
+        let mut watched = WatchedPool {
+            coin_mint: amm_info.coin_mint,
+            pc_mint: amm_info.pc_mint,
+            coin_vault: amm_info.token_coin,
+            pc_vault: amm_info.token_pc,
+            coin_decimals: amm_info.coin_decimals as u8,
+            pc_decimals: amm_info.pc_decimals as u8,
+            coin_reserve: 0,
+            pc_reserve: 0,
+            baseline: (0, 0),
+        };
+        let (coin_reserve, pc_reserve) =
+            self.fetch_reserves(&watched.coin_vault, &watched.pc_vault).await?;
+        watched.coin_reserve = coin_reserve;
+        watched.pc_reserve = pc_reserve;
+        watched.baseline = (coin_reserve, pc_reserve);
+        self.pools.insert(pool, watched);
+        self.resubscribe.notify_one();
+        Ok(())
+    }
+
+    /// 停止监控一个池子
+    pub fn unwatch_pool(&self, pool: &Pubkey) {
+        if self.pools.remove(pool).is_some() {
+            self.resubscribe.notify_one();
+        }
+    }
+
+    /// 当前储备 (coin, pc)；未监控时为 None
+    pub fn reserves(&self, pool: &Pubkey) -> Option<(u64, u64)> {
+        self.pools.get(pool).map(|p| (p.coin_reserve, p.pc_reserve))
+    }
+
+    /// WebSocket 订阅当前是否处于连接状态
+    pub fn is_streaming(&self) -> bool {
+        self.ws_connected.load(Ordering::Relaxed)
+    }
+
+    async fn fetch_reserves(&self, coin_vault: &Pubkey, pc_vault: &Pubkey) -> Result<(u64, u64)> {
+        let accounts = self.rpc.get_multiple_accounts(&[*coin_vault, *pc_vault]).await?;
+        let amount = |index: usize| {
+            accounts
+                .get(index)
+                .and_then(|account| account.as_ref())
+                .and_then(|account| decode_token_amount(&account.data))
+                .ok_or_else(|| anyhow!("Failed to read vault balance"))
+        };
+        Ok((amount(0)?, amount(1)?))
+    }
+
+    /// 通过 RPC 刷新全部池子储备
+    pub async fn refresh_all(&self) {
+        let watched: Vec<(Pubkey, Pubkey, Pubkey)> =
+            self.pools.iter().map(|p| (*p.key(), p.coin_vault, p.pc_vault)).collect();
+        for (pool, coin_vault, pc_vault) in watched {
+            match self.fetch_reserves(&coin_vault, &pc_vault).await {
+                Ok((coin_reserve, pc_reserve)) => {
+                    self.apply_reserve(&pool, Some(coin_reserve), Some(pc_reserve))
+                },
+                Err(e) => log::warn!("AmmV4PoolWatcher: failed to refresh {}: {}", pool, e),
+            }
+        }
+    }
+
+    fn apply_reserve(&self, pool: &Pubkey, coin_reserve: Option<u64>, pc_reserve: Option<u64>) {
+        let event = {
+            let Some(mut watched) = self.pools.get_mut(pool) else {
+                return;
+            };
+            if let Some(coin_reserve) = coin_reserve {
+                watched.coin_reserve = coin_reserve;
+            }
+            if let Some(pc_reserve) = pc_reserve {
+                watched.pc_reserve = pc_reserve;
+            }
+            watched.settle(pool, self.config.threshold_bps)
+        };
+        // 回调在释放 DashMap 锁之后执行，允许回调中调用 watch/unwatch
+        if let Some(event) = event {
+            (self.on_event)(event);
+        }
+    }
+
+    async fn poll_loop(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.config.poll_interval);
+        loop {
+            interval.tick().await;
+            if self.is_streaming() {
+                continue;
+            }
+            self.refresh_all().await;
+        }
+    }
+
+    async fn subscribe_loop(self: Arc<Self>, ws_url: String) {
+        loop {
+            if let Err(e) = self.subscribe_once(&ws_url).await {
+                log::warn!("AmmV4PoolWatcher: subscription error: {}", e);
+            }
+            self.ws_connected.store(false, Ordering::Relaxed);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// 建立一次订阅；连接断开或监控列表变化时返回
+    async fn subscribe_once(&self, ws_url: &str) -> Result<()> {
+        let client = PubsubClient::new(ws_url).await?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(self.rpc.commitment()),
+            min_context_slot: None,
+        };
+
+        // (vault, pool, 是否为 coin vault)
+        let vaults: Vec<(Pubkey, Pubkey, bool)> = self
+            .pools
+            .iter()
+            .flat_map(|p| [(p.coin_vault, *p.key(), true), (p.pc_vault, *p.key(), false)])
+            .collect();
+        if vaults.is_empty() {
+            self.resubscribe.notified().await;
+            return Ok(());
+        }
+        let mut streams = Vec::with_capacity(vaults.len());
+        for (vault, pool, is_coin) in vaults {
+            let (stream, _unsubscribe) =
+                client.account_subscribe(&vault, Some(config.clone())).await?;
+            streams.push(stream.map(move |response| (pool, is_coin, response.value)));
+        }
+        let mut updates = select_all(streams);
+        self.ws_connected.store(true, Ordering::Relaxed);
+        // 订阅建立前的变化可能被遗漏，连接后补一次 RPC 刷新
+        self.refresh_all().await;
+
+        loop {
+            tokio::select! {
+                update = updates.next() => match update {
+                    Some((pool, is_coin, account)) => self.apply_update(&pool, is_coin, &account),
+                    None => return Ok(()),
+                },
+                _ = self.resubscribe.notified() => return Ok(()),
+            }
+        }
+    }
+
+    fn apply_update(&self, pool: &Pubkey, is_coin: bool, account: &UiAccount) {
+        let amount = match &account.data {
+            UiAccountData::Binary(base64_str, _) => {
+                STANDARD.decode(base64_str).ok().and_then(|data| decode_token_amount(&data))
+            },
+            _ => None,
+        };
+        if let Some(amount) = amount {
+            if is_coin {
+                self.apply_reserve(pool, Some(amount), None);
+            } else {
+                self.apply_reserve(pool, None, Some(amount));
+            }
+        }
+    }
+}
+
+/// 解析代币账户余额（SPL Token 与 Token-2022 基础布局相同）
+fn decode_token_amount(data: &[u8]) -> Option<u64> {
+    if data.len() < SplTokenAccount::LEN {
+        return None;
+    }
+    data.get(TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("slice length is 8")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watched(coin_reserve: u64, pc_reserve: u64) -> WatchedPool {
+        WatchedPool {
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            coin_vault: Pubkey::new_unique(),
+            pc_vault: Pubkey::new_unique(),
+            coin_decimals: 6,
+            pc_decimals: 9,
+            coin_reserve,
+            pc_reserve,
+            baseline: (coin_reserve, pc_reserve),
+        }
+    }
+
+    #[test]
+    fn test_settle_detects_large_buy() {
+        let pool = Pubkey::new_unique();
+        let mut state = watched(1_000_000_000, 100_000_000_000);
+
+        // 只有一侧变化：等待另一侧
+        state.coin_reserve = 950_000_000;
+        assert!(state.settle(&pool, Bps::new(100)).is_none());
+
+        state.pc_reserve = 105_300_000_000;
+        let event = state.settle(&pool, Bps::new(100)).expect("5% move exceeds 1%");
+        assert_eq!(event.direction, ReserveMove::Buy);
+        assert_eq!(event.coin_reserve_before, 1_000_000_000);
+        assert!(event.price_change_bps > 0.0);
+        assert_eq!(state.baseline, (950_000_000, 105_300_000_000));
+
+        // 小额卖出低于阈值：不产生事件，但基线前移
+        state.coin_reserve = 950_100_000;
+        state.pc_reserve = 105_290_000_000;
+        assert!(state.settle(&pool, Bps::new(100)).is_none());
+        assert_eq!(state.baseline, (950_100_000, 105_290_000_000));
+    }
+}
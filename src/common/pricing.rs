@@ -0,0 +1,117 @@
+//! USD 计价配置
+//!
+//! 各协议的 `get_token_price_in_usd*` 都按 Token X -> WSOL -> USD 的路径计价，
+//! WSOL 的 USD 价格来自 Raydium CLMM 上的 WSOL-稳定币锚定池。锚定池、视为 1 USD 的稳定币
+//! 以及 WSOL 价格的缓存时长由 [`PricingConfig`] 决定。
+//!
+//! 配置为进程级共享：通过 `TradeConfig::with_pricing_config` 设置时以最后创建的
+//! `TradingClient` 为准，也可以直接调用 [`set_pricing_config`]。调用方显式传入锚定池地址时
+//! 仍使用该地址，不读缓存。
+
+use crate::constants::{USDC_MINT, USDT_MINT};
+use crate::instruction::utils::raydium_clmm::DEFAULT_WSOL_USDT_CLMM_POOL;
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// USD 计价配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PricingConfig {
+    /// WSOL-USDC/USDT 的 Raydium CLMM 锚定池，按顺序尝试，第一个成功的为准
+    pub anchor_pools: Vec<Pubkey>,
+    /// 视为 1 USD 的稳定币（锚定池的另一侧必须是其中之一）
+    pub stablecoins: Vec<Pubkey>,
+    /// WSOL 价格的缓存时长，`Duration::ZERO` 表示每次都从链上读取（默认）
+    pub refresh_interval: Duration,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            anchor_pools: vec![DEFAULT_WSOL_USDT_CLMM_POOL],
+            stablecoins: vec![USDC_MINT, USDT_MINT],
+            refresh_interval: Duration::ZERO,
+        }
+    }
+}
+
+impl PricingConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置锚定池（按优先级排列）
+    pub fn with_anchor_pools(mut self, anchor_pools: Vec<Pubkey>) -> Self {
+        self.anchor_pools = anchor_pools;
+        self
+    }
+
+    /// 设置视为 1 USD 的稳定币
+    pub fn with_stablecoins(mut self, stablecoins: Vec<Pubkey>) -> Self {
+        self.stablecoins = stablecoins;
+        self
+    }
+
+    /// 设置 WSOL 价格的缓存时长
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    pub fn is_stablecoin(&self, mint: &Pubkey) -> bool {
+        self.stablecoins.contains(mint)
+    }
+}
+
+static PRICING_CONFIG: Lazy<ArcSwap<PricingConfig>> =
+    Lazy::new(|| ArcSwap::from_pointee(PricingConfig::default()));
+
+/// 最近一次从锚定池读到的 WSOL 价格
+static WSOL_USD_PRICE: Lazy<Mutex<Option<(Instant, f64)>>> = Lazy::new(|| Mutex::new(None));
+
+/// 当前生效的计价配置
+pub fn pricing_config() -> Arc<PricingConfig> {
+    PRICING_CONFIG.load_full()
+}
+
+/// 替换计价配置（同时清空 WSOL 价格缓存）
+pub fn set_pricing_config(config: PricingConfig) {
+    PRICING_CONFIG.store(Arc::new(config));
+    *WSOL_USD_PRICE.lock() = None;
+}
+
+/// 缓存未过期时返回 WSOL 价格
+pub(crate) fn cached_wsol_price(refresh_interval: Duration) -> Option<f64> {
+    let cached = *WSOL_USD_PRICE.lock();
+    cached.filter(|(at, _)| at.elapsed() < refresh_interval).map(|(_, price)| price)
+}
+
+pub(crate) fn cache_wsol_price(price: f64) {
+    *WSOL_USD_PRICE.lock() = Some((Instant::now(), price));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_config() {
+        let config = PricingConfig::new();
+        assert_eq!(config.anchor_pools, vec![DEFAULT_WSOL_USDT_CLMM_POOL]);
+        assert!(config.is_stablecoin(&USDC_MINT));
+
+        let usd1 = Pubkey::new_unique();
+        let config = config
+            .with_stablecoins(vec![usd1])
+            .with_refresh_interval(Duration::from_secs(30));
+        assert!(config.is_stablecoin(&usd1));
+        assert!(!config.is_stablecoin(&USDT_MINT));
+
+        cache_wsol_price(150.0);
+        assert_eq!(cached_wsol_price(Duration::from_secs(30)), Some(150.0));
+        assert_eq!(cached_wsol_price(Duration::ZERO), None);
+    }
+}
@@ -0,0 +1,238 @@
+//! 协议自定义错误码解码
+//!
+//! 交易上链失败时，链上返回的是各协议程序的原始错误码（如 `custom program error: 0x1774`）。
+//! 本模块按程序 ID 查表，将错误码映射为统一的 [`ProgramErrorKind`] 与可读的错误信息，
+//! 便于上层按错误类型（滑点、曲线已完成、池已迁移等）分别处理。
+//!
+//! 错误表来源于各协议 IDL / 源码，仅收录交易路径上常见的错误；未收录的错误码返回 `None`。
+
+use crate::instruction::utils::{
+    bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_clmm, raydium_cpmm,
+    raydium_launchlab,
+};
+use crate::trading::factory::DexType;
+use solana_sdk::pubkey::Pubkey;
+
+/// 统一的错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProgramErrorKind {
+    /// 滑点超出容忍范围
+    SlippageToleranceExceeded,
+    /// 绑定曲线已完成（已迁移至 AMM）
+    CurveComplete,
+    /// 池已迁移
+    PoolMigrated,
+    /// 池已禁用或状态不允许交易
+    PoolDisabled,
+    /// 流动性/储备不足
+    InsufficientLiquidity,
+    /// 交易数量为 0 或过小
+    ZeroAmount,
+    /// 数学运算溢出
+    MathOverflow,
+    /// 无权限
+    Unauthorized,
+    /// 账户不匹配或无效
+    InvalidAccount,
+    /// 参数无效
+    InvalidInput,
+    /// 交易已过期
+    TransactionTooOld,
+    /// 其他已知错误
+    Other,
+}
+
+/// 解码后的协议错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramError {
+    /// 所属协议
+    pub dex_type: DexType,
+    /// 原始错误码
+    pub code: u32,
+    /// 统一错误类型
+    pub kind: ProgramErrorKind,
+    /// 协议 IDL 中的错误名
+    pub name: &'static str,
+    /// 可读的错误信息
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} {} ({}): {}", self.dex_type, self.name, self.code, self.message)
+    }
+}
+
+type ErrorTable = &'static [(u32, ProgramErrorKind, &'static str, &'static str)];
+
+use ProgramErrorKind::*;
+
+const PUMPFUN_ERRORS: ErrorTable = &[
+    (6000, Unauthorized, "NotAuthorized", "The given account is not authorized"),
+    (6001, Other, "AlreadyInitialized", "The program is already initialized"),
+    (6002, SlippageToleranceExceeded, "TooMuchSolRequired", "Too much SOL required to buy"),
+    (6003, SlippageToleranceExceeded, "TooLittleSolReceived", "Too little SOL received to sell"),
+    (6004, InvalidAccount, "MintDoesNotMatchBondingCurve", "Mint does not match bonding curve"),
+    (6005, CurveComplete, "BondingCurveComplete", "Bonding curve complete, migrated to AMM"),
+    (6006, Other, "BondingCurveNotComplete", "Bonding curve not complete"),
+    (6007, Other, "NotInitialized", "The program is not initialized"),
+    (6020, ZeroAmount, "BuyZeroAmount", "Buy amount is zero"),
+    (6021, InsufficientLiquidity, "NotEnoughTokensToBuy", "Not enough tokens to buy"),
+    (6022, ZeroAmount, "SellZeroAmount", "Sell amount is zero"),
+    (6023, InsufficientLiquidity, "NotEnoughTokensToSell", "Not enough tokens to sell"),
+    (6024, MathOverflow, "Overflow", "Overflow"),
+];
+
+const PUMPSWAP_ERRORS: ErrorTable = &[
+    (6001, ZeroAmount, "ZeroBaseAmount", "Base amount is zero"),
+    (6002, ZeroAmount, "ZeroQuoteAmount", "Quote amount is zero"),
+    (6003, InsufficientLiquidity, "TooLittlePoolTokenLiquidity", "Too little pool liquidity"),
+    (6004, SlippageToleranceExceeded, "ExceededSlippage", "Exceeded slippage"),
+    (6005, Unauthorized, "InvalidAdmin", "Invalid admin"),
+    (6008, InvalidAccount, "InvalidBaseMint", "Invalid base mint"),
+    (6009, InvalidAccount, "InvalidQuoteMint", "Invalid quote mint"),
+    (6014, InvalidAccount, "InvalidPoolBaseTokenAccount", "Invalid pool base token account"),
+    (6015, InvalidAccount, "InvalidPoolQuoteTokenAccount", "Invalid pool quote token account"),
+    (6016, InsufficientLiquidity, "BuyMoreBaseAmountThanPoolReserves", "Buy exceeds reserves"),
+    (6020, PoolDisabled, "DisabledBuy", "Buy is disabled"),
+    (6021, PoolDisabled, "DisabledSell", "Sell is disabled"),
+    (6023, MathOverflow, "Overflow", "Overflow"),
+];
+
+const LAUNCHLAB_ERRORS: ErrorTable = &[
+    (6000, Unauthorized, "NotApproved", "Not approved"),
+    (6001, InvalidAccount, "InvalidOwner", "Input account owner is not the program address"),
+    (6002, InvalidInput, "InvalidInput", "Invalid input"),
+    (6003, InvalidInput, "InputNotMatchCurveConfig", "Input does not match curve config"),
+    (6004, SlippageToleranceExceeded, "ExceededSlippage", "Exceeds desired slippage limit"),
+    (6005, PoolMigrated, "PoolMigrated", "Pool migrated"),
+    (6007, MathOverflow, "MathOverflow", "Math overflow"),
+];
+
+const RAYDIUM_AMM_V4_ERRORS: ErrorTable = &[
+    (22, PoolDisabled, "InvalidStatus", "Pool status does not allow swaps"),
+    (24, InvalidAccount, "WrongAccountsNumber", "Wrong number of accounts"),
+    (29, InvalidInput, "InvalidInput", "Invalid input"),
+    (30, SlippageToleranceExceeded, "ExceededSlippage", "Exceeds desired slippage limit"),
+    (40, InsufficientLiquidity, "InsufficientFunds", "Insufficient funds"),
+];
+
+const RAYDIUM_CPMM_ERRORS: ErrorTable = &[
+    (6000, Unauthorized, "NotApproved", "Not approved"),
+    (6001, InvalidAccount, "InvalidOwner", "Input account owner is not the program address"),
+    (6002, InsufficientLiquidity, "EmptySupply", "Input token account empty"),
+    (6003, InvalidInput, "InvalidInput", "Invalid input"),
+    (6005, SlippageToleranceExceeded, "ExceededSlippage", "Exceeds desired slippage limit"),
+    (6006, ZeroAmount, "ZeroTradingTokens", "Given amount results in zero trading tokens"),
+    (6008, InvalidAccount, "InvalidVault", "Invalid vault"),
+    (6011, MathOverflow, "MathOverflow", "Math overflow"),
+    (6012, InsufficientLiquidity, "InsufficientVault", "Insufficient vault"),
+];
+
+const RAYDIUM_CLMM_ERRORS: ErrorTable = &[
+    (6001, Unauthorized, "NotApproved", "Not approved"),
+    (6011, InvalidAccount, "InvalidTickArray", "Invalid tick array account"),
+    (6013, InvalidInput, "SqrtPriceLimitOverflow", "Square root price limit overflow"),
+    (6019, InsufficientLiquidity, "LiquidityInsufficient", "Liquidity insufficient"),
+    (6020, TransactionTooOld, "TransactionTooOld", "Transaction too old"),
+    (6021, SlippageToleranceExceeded, "PriceSlippageCheck", "Price slippage check"),
+    (6022, SlippageToleranceExceeded, "TooLittleOutputReceived", "Too little output received"),
+    (6023, SlippageToleranceExceeded, "TooMuchInputPaid", "Too much input paid"),
+    (6024, ZeroAmount, "ZeroAmountSpecified", "Swap amount can not be zero"),
+    (6025, InvalidAccount, "InvalidInputPoolVault", "Input pool vault is invalid"),
+    (6026, ZeroAmount, "TooSmallInputOrOutputAmount", "Swap input or output amount is too small"),
+    (6027, InvalidAccount, "NotEnoughTickArrayAccount", "Not enough tick array accounts"),
+    (6028, InvalidAccount, "InvalidFirstTickArrayAccount", "Invalid first tick array account"),
+    (6040, InvalidAccount, "MissingTickArrayBitmapExtensionAccount", "Missing bitmap extension"),
+    (6041, InsufficientLiquidity, "InsufficientLiquidityForDirection", "Insufficient liquidity"),
+    (6043, MathOverflow, "CalculateOverflow", "Calculate overflow"),
+];
+
+const METEORA_DAMM_V2_ERRORS: ErrorTable = &[
+    (6000, MathOverflow, "MathOverflow", "Math operation overflow"),
+    (6001, InvalidInput, "InvalidFee", "Invalid fee setup"),
+    (6002, SlippageToleranceExceeded, "ExceededSlippage", "Exceeded slippage tolerance"),
+    (6003, PoolDisabled, "PoolDisabled", "Pool disabled"),
+    (6005, Unauthorized, "InvalidAdmin", "Invalid admin"),
+    (6006, ZeroAmount, "AmountIsZero", "Amount is zero"),
+];
+
+/// 根据程序 ID 查找协议与错误表
+fn error_table(program_id: &Pubkey) -> Option<(DexType, ErrorTable)> {
+    Some(match *program_id {
+        id if id == pumpfun::accounts::PUMPFUN => (DexType::PumpFun, PUMPFUN_ERRORS),
+        id if id == pumpswap::accounts::AMM_PROGRAM => (DexType::PumpSwap, PUMPSWAP_ERRORS),
+        id if id == bonk::accounts::BONK
+            || id == raydium_launchlab::accounts::LAUNCHLAB_PROGRAM =>
+        {
+            (DexType::Bonk, LAUNCHLAB_ERRORS)
+        },
+        id if id == raydium_amm_v4::accounts::RAYDIUM_AMM_V4 => {
+            (DexType::RaydiumAmmV4, RAYDIUM_AMM_V4_ERRORS)
+        },
+        id if id == raydium_cpmm::accounts::RAYDIUM_CPMM => {
+            (DexType::RaydiumCpmm, RAYDIUM_CPMM_ERRORS)
+        },
+        id if id == raydium_clmm::accounts::RAYDIUM_CLMM => {
+            (DexType::RaydiumClmm, RAYDIUM_CLMM_ERRORS)
+        },
+        id if id == meteora_damm_v2::accounts::METEORA_DAMM_V2 => {
+            (DexType::MeteoraDammV2, METEORA_DAMM_V2_ERRORS)
+        },
+        _ => return None,
+    })
+}
+
+/// 解码指定程序的自定义错误码
+pub fn decode_program_error(program_id: &Pubkey, code: u32) -> Option<ProgramError> {
+    let (dex_type, table) = error_table(program_id)?;
+    table
+        .iter()
+        .find(|(c, ..)| *c == code)
+        .map(|&(code, kind, name, message)| ProgramError { dex_type, code, kind, name, message })
+}
+
+/// 从交易日志中找出失败的程序并解码其错误码
+///
+/// 匹配形如 `Program <id> failed: custom program error: 0x1774` 的日志行。
+pub fn decode_from_logs<S: AsRef<str>>(logs: &[S]) -> Option<ProgramError> {
+    logs.iter().rev().find_map(|log| {
+        let rest = log.as_ref().strip_prefix("Program ")?;
+        let (program_id, rest) = rest.split_once(' ')?;
+        let hex = rest.strip_prefix("failed: custom program error: 0x")?;
+        let code = u32::from_str_radix(hex.trim(), 16).ok()?;
+        decode_program_error(&program_id.parse().ok()?, code)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_known_codes() {
+        let err = decode_program_error(&pumpfun::accounts::PUMPFUN, 6005).unwrap();
+        assert_eq!(err.kind, ProgramErrorKind::CurveComplete);
+        assert_eq!(err.dex_type, DexType::PumpFun);
+
+        let err = decode_program_error(&raydium_amm_v4::accounts::RAYDIUM_AMM_V4, 30).unwrap();
+        assert_eq!(err.kind, ProgramErrorKind::SlippageToleranceExceeded);
+
+        assert!(decode_program_error(&pumpswap::accounts::AMM_PROGRAM, 9999).is_none());
+        assert!(decode_program_error(&Pubkey::new_unique(), 6004).is_none());
+    }
+
+    #[test]
+    fn test_decode_from_logs() {
+        let logs = vec![
+            "Program pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA invoke [1]".to_string(),
+            "Program log: AnchorError occurred. Error Code: ExceededSlippage.".to_string(),
+            "Program pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA failed: custom program error: 0x1774"
+                .to_string(),
+        ];
+        let err = decode_from_logs(&logs).unwrap();
+        assert_eq!(err.dex_type, DexType::PumpSwap);
+        assert_eq!(err.name, "ExceededSlippage");
+        assert!(decode_from_logs(&["Program log: hello"]).is_none());
+    }
+}
@@ -0,0 +1,315 @@
+//! 已部署协议程序的版本探测
+//!
+//! 读取各协议程序的 ProgramData 账户，计算可执行文件的 sha256 与最近部署 slot，
+//! 与固定的基线比较。程序升级后指令编码可能失效（账户顺序、参数布局变化），
+//! 探测到变化时按 [`ProbeMode`] 打印警告或返回错误，提醒在交易前核对 SDK 版本。
+//!
+//! 基线来源：
+//! - [`ProgramProbeConfig::with_expected_hash`] 显式固定的哈希；
+//! - `baseline_path` 指向的 JSON 文件。文件不存在时用本次探测结果创建，之后只补充新协议，
+//!   检测到变化时不会覆盖（确认兼容后删除文件或更新哈希即可接受新版本）。
+
+use crate::common::SolanaRpcClient;
+use crate::common::cluster::current_cluster;
+use crate::constants::registry::registry;
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use solana_sdk::{hash::Hash, pubkey, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// BPF Upgradeable Loader
+pub const BPF_UPGRADEABLE_LOADER: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// ProgramData 账户头：tag(4) + slot(8) + Option<upgrade_authority>(1 + 32)
+const PROGRAM_DATA_HEADER_SIZE: usize = 45;
+
+/// 探测到变化时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProbeMode {
+    /// 打印警告后继续（默认）
+    #[default]
+    Warn,
+    /// 返回错误；在 `TradingClient::new` 中会导致启动失败
+    Error,
+}
+
+/// 程序版本探测配置
+#[derive(Debug, Clone)]
+pub struct ProgramProbeConfig {
+    /// 需要探测的协议（默认：全部）
+    pub protocols: Vec<DexType>,
+    /// 显式固定的可执行文件哈希，优先于基线文件
+    pub expected: HashMap<DexType, Hash>,
+    /// 基线 JSON 文件路径
+    pub baseline_path: Option<PathBuf>,
+    pub mode: ProbeMode,
+}
+
+impl Default for ProgramProbeConfig {
+    fn default() -> Self {
+        Self {
+            protocols: vec![
+                DexType::PumpFun,
+                DexType::PumpSwap,
+                DexType::Bonk,
+                DexType::RaydiumCpmm,
+                DexType::RaydiumAmmV4,
+                DexType::RaydiumClmm,
+                DexType::MeteoraDammV2,
+            ],
+            expected: HashMap::new(),
+            baseline_path: None,
+            mode: ProbeMode::Warn,
+        }
+    }
+}
+
+impl ProgramProbeConfig {
+    pub fn new(mode: ProbeMode) -> Self {
+        Self { mode, ..Default::default() }
+    }
+
+    pub fn with_protocols(mut self, protocols: Vec<DexType>) -> Self {
+        self.protocols = protocols;
+        self
+    }
+
+    pub fn with_expected_hash(mut self, dex_type: DexType, hash: Hash) -> Self {
+        self.expected.insert(dex_type, hash);
+        self
+    }
+
+    pub fn with_baseline_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.baseline_path = Some(path.into());
+        self
+    }
+}
+
+/// 已部署程序的指纹
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramFingerprint {
+    pub program_id: Pubkey,
+    /// 可执行文件 sha256（去除 ProgramData 尾部的零填充）
+    pub executable_hash: Hash,
+    /// 最近一次部署的 slot（不可升级程序为 None）
+    pub last_deploy_slot: Option<u64>,
+    /// 是否仍可升级（存在 upgrade authority）
+    pub upgradeable: bool,
+}
+
+/// 与基线不一致的程序
+#[derive(Debug, Clone)]
+pub struct ProgramMismatch {
+    pub dex_type: DexType,
+    pub expected: Hash,
+    pub actual: ProgramFingerprint,
+}
+
+/// 探测结果
+#[derive(Debug, Clone, Default)]
+pub struct ProbeReport {
+    pub fingerprints: Vec<(DexType, ProgramFingerprint)>,
+    pub mismatches: Vec<ProgramMismatch>,
+    /// 当前集群没有部署而跳过的协议
+    pub skipped: Vec<DexType>,
+}
+
+impl ProbeReport {
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// 按模式处理不一致：Warn 打印警告后返回 Ok，Error 返回错误
+    pub fn enforce(&self, mode: ProbeMode) -> Result<()> {
+        if self.is_clean() {
+            return Ok(());
+        }
+        let details: Vec<String> = self
+            .mismatches
+            .iter()
+            .map(|m| {
+                format!(
+                    "{:?} ({}) expected {} got {} (deployed at slot {:?})",
+                    m.dex_type,
+                    m.actual.program_id,
+                    m.expected,
+                    m.actual.executable_hash,
+                    m.actual.last_deploy_slot
+                )
+            })
+            .collect();
+        match mode {
+            ProbeMode::Warn => {
+                for detail in &details {
+                    eprintln!("⚠️  程序已升级，SDK 指令编码可能过期: {}", detail);
+                }
+                Ok(())
+            },
+            ProbeMode::Error => {
+                Err(anyhow!("Deployed programs changed since baseline: {}", details.join("; ")))
+            },
+        }
+    }
+}
+
+/// 从程序账户与 ProgramData 账户数据计算指纹
+///
+/// `program_data` 为 None 时 `program_account_data` 视为不可升级 loader 的可执行文件本身。
+pub fn fingerprint_from_accounts(
+    program_id: Pubkey,
+    program_account_data: &[u8],
+    program_data: Option<&[u8]>,
+) -> Result<ProgramFingerprint> {
+    let (elf, last_deploy_slot, upgradeable) = match program_data {
+        Some(data) => {
+            if data.len() < PROGRAM_DATA_HEADER_SIZE || data[..4] != [3, 0, 0, 0] {
+                return Err(anyhow!("Invalid ProgramData account for {}", program_id));
+            }
+            let slot = u64::from_le_bytes(data[4..12].try_into()?);
+            (&data[PROGRAM_DATA_HEADER_SIZE..], Some(slot), data[12] == 1)
+        },
+        None => (program_account_data, None, false),
+    };
+    let end = elf.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    let digest: [u8; 32] = Sha256::digest(&elf[..end]).into();
+    Ok(ProgramFingerprint {
+        program_id,
+        executable_hash: Hash::new_from_array(digest),
+        last_deploy_slot,
+        upgradeable,
+    })
+}
+
+/// 读取程序指纹（支持 Upgradeable Loader 与不可升级 loader）
+pub async fn fingerprint_program(
+    rpc: &SolanaRpcClient,
+    program_id: &Pubkey,
+) -> Result<ProgramFingerprint> {
+    let account = rpc.get_account(program_id).await?;
+    if !account.executable {
+        return Err(anyhow!("{} is not an executable program", program_id));
+    }
+    if account.owner != BPF_UPGRADEABLE_LOADER {
+        return fingerprint_from_accounts(*program_id, &account.data, None);
+    }
+    // UpgradeableLoaderState::Program { programdata_address }
+    if account.data.len() < 36 || account.data[..4] != [2, 0, 0, 0] {
+        return Err(anyhow!("Invalid upgradeable program account {}", program_id));
+    }
+    let programdata_address = Pubkey::try_from(&account.data[4..36])?;
+    let program_data = rpc.get_account(&programdata_address).await?;
+    fingerprint_from_accounts(*program_id, &account.data, Some(&program_data.data))
+}
+
+fn load_baseline(path: &PathBuf) -> Result<HashMap<String, Hash>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let value: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let object = value.as_object().ok_or_else(|| anyhow!("Invalid probe baseline file"))?;
+    object
+        .iter()
+        .map(|(name, hash)| {
+            let hash = hash.as_str().ok_or_else(|| anyhow!("Invalid hash for {}", name))?;
+            Ok((name.clone(), Hash::from_str(hash)?))
+        })
+        .collect()
+}
+
+fn save_baseline(path: &PathBuf, baseline: &HashMap<String, Hash>) -> Result<()> {
+    let mut names: Vec<&String> = baseline.keys().collect();
+    names.sort();
+    let object: Map<String, Value> = names
+        .into_iter()
+        .map(|name| (name.clone(), Value::String(baseline[name].to_string())))
+        .collect();
+    std::fs::write(path, serde_json::to_string_pretty(&Value::Object(object))?)?;
+    Ok(())
+}
+
+/// 探测配置中的协议，与基线比较
+///
+/// 程序 ID 按当前集群与 [`Registry`](crate::constants::registry::Registry) 解析。
+pub async fn probe_programs(
+    rpc: &SolanaRpcClient,
+    config: &ProgramProbeConfig,
+) -> Result<ProbeReport> {
+    let mut baseline = match &config.baseline_path {
+        Some(path) => load_baseline(path)?,
+        None => HashMap::new(),
+    };
+    let registry = registry();
+    let cluster = current_cluster();
+    let mut report = ProbeReport::default();
+    let mut baseline_changed = false;
+
+    for dex_type in &config.protocols {
+        let Ok(program_id) = registry.program_id(cluster, dex_type) else {
+            report.skipped.push(dex_type.clone());
+            continue;
+        };
+        let fingerprint = fingerprint_program(rpc, &program_id).await?;
+        let name = format!("{:?}", dex_type);
+        let expected = config.expected.get(dex_type).or_else(|| baseline.get(&name)).copied();
+        match expected {
+            Some(expected) if expected != fingerprint.executable_hash => {
+                report.mismatches.push(ProgramMismatch {
+                    dex_type: dex_type.clone(),
+                    expected,
+                    actual: fingerprint.clone(),
+                });
+            },
+            Some(_) => {},
+            None => {
+                baseline.insert(name, fingerprint.executable_hash);
+                baseline_changed = true;
+            },
+        }
+        report.fingerprints.push((dex_type.clone(), fingerprint));
+    }
+
+    if baseline_changed && let Some(path) = &config.baseline_path {
+        save_baseline(path, &baseline)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_trims_padding_and_reads_slot() {
+        let program_id = Pubkey::new_unique();
+        let elf = b"\x7fELF-program-bytes";
+        let mut program_data = vec![3, 0, 0, 0];
+        program_data.extend_from_slice(&42u64.to_le_bytes());
+        program_data.push(1);
+        program_data.extend_from_slice(Pubkey::new_unique().as_ref());
+        program_data.extend_from_slice(elf);
+        let unpadded = fingerprint_from_accounts(program_id, &[], Some(&program_data)).unwrap();
+        program_data.extend_from_slice(&[0u8; 64]);
+        let padded = fingerprint_from_accounts(program_id, &[], Some(&program_data)).unwrap();
+        assert_eq!(unpadded, padded);
+        assert_eq!(padded.last_deploy_slot, Some(42));
+        assert!(padded.upgradeable);
+        let digest: [u8; 32] = Sha256::digest(elf).into();
+        assert_eq!(padded.executable_hash, Hash::new_from_array(digest));
+        assert!(fingerprint_from_accounts(program_id, &[], Some(&[2, 0, 0, 0])).is_err());
+
+        let report = ProbeReport {
+            mismatches: vec![ProgramMismatch {
+                dex_type: DexType::PumpFun,
+                expected: Hash::default(),
+                actual: padded,
+            }],
+            ..Default::default()
+        };
+        assert!(report.enforce(ProbeMode::Warn).is_ok());
+        assert!(report.enforce(ProbeMode::Error).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! 免租金额缓存
+//!
+//! 免租金额与账户大小成线性关系：`minimum_balance(space) = base + space * per_byte`。
+//! 启动时（`TradingClient::new` / `TradingInfrastructure::new`）通过 RPC 查询 0 字节与
+//! 165 字节（代币账户）的免租金额求出两个系数，之后每小时后台刷新一次，
+//! 创建账户的指令构建直接读取缓存，不再访问 RPC。
+//!
+//! 集群调整租金参数后可调用 [`refresh_now`] 立即刷新。
+
+use crate::common::SolanaRpcClient;
+use anyhow::{Result, anyhow};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::time::{Duration, sleep};
+
+/// SPL Token / Token-2022（无扩展）代币账户大小
+pub const TOKEN_ACCOUNT_SPACE: usize = 165;
+
+/// 后台刷新间隔
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// u64::MAX 表示未初始化
+static BASE: AtomicU64 = AtomicU64::new(u64::MAX);
+static PER_BYTE: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// `space` 字节账户的免租金额（lamports）；缓存未初始化时为 None
+#[inline]
+pub fn get(space: usize) -> Option<u64> {
+    // Acquire 与 store 的 Release 配对，保证读到的两个系数来自同一次刷新之后
+    let per_byte = PER_BYTE.load(Ordering::Acquire);
+    let base = BASE.load(Ordering::Acquire);
+    if base == u64::MAX || per_byte == u64::MAX {
+        return None;
+    }
+    Some(base + space as u64 * per_byte)
+}
+
+/// 与 [`get`] 相同，缓存未初始化时返回错误
+#[inline]
+pub fn require(space: usize) -> Result<u64> {
+    get(space).ok_or_else(|| anyhow!("Rent not initialized"))
+}
+
+/// 代币账户的免租金额
+#[inline]
+pub fn token_account() -> Option<u64> {
+    get(TOKEN_ACCOUNT_SPACE)
+}
+
+/// 直接设置系数（用于测试或已知租金参数的离线环境）
+pub fn set(base: u64, per_byte: u64) {
+    BASE.store(base, Ordering::Release);
+    PER_BYTE.store(per_byte, Ordering::Release);
+}
+
+/// 立即通过 RPC 刷新缓存
+pub async fn refresh_now(rpc: &SolanaRpcClient) -> Result<()> {
+    let base = rpc.get_minimum_balance_for_rent_exemption(0).await?;
+    let token = rpc.get_minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_SPACE).await?;
+    let per_byte = token.saturating_sub(base) / TOKEN_ACCOUNT_SPACE as u64;
+    set(base, per_byte);
+    Ok(())
+}
+
+/// 启动后台刷新任务（每 [`REFRESH_INTERVAL`] 一次）
+pub fn start_updater(rpc: Arc<SolanaRpcClient>) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = refresh_now(&rpc).await {
+                log::debug!("rent cache refresh failed: {}", e);
+            }
+            sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rent_cache_get() {
+        // 主网参数：3480 lamports/byte-year，2 年免租
+        set(890_880, 6_960);
+        assert_eq!(get(0), Some(890_880));
+        assert_eq!(token_account(), Some(2_039_280));
+        assert_eq!(get(82), Some(1_461_600));
+    }
+}
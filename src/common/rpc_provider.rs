@@ -0,0 +1,166 @@
+//! RPC 传输抽象
+//!
+//! [`RpcProvider`] 覆盖 SDK 交易路径上用到的 RPC 方法（账户读取、余额、blockhash、发送与模拟），
+//! 让调用方可以接入自定义传输（基于 gRPC 的 RPC、缓存代理、测试替身等），
+//! 而不局限于 `solana_client` 的非阻塞客户端。
+//!
+//! 标准的 `SolanaRpcClient` 已实现该 trait；通过 `TradingClient::with_rpc_provider`
+//! 设置自定义实现后，发送前模拟与模拟模式会走该实现。
+
+use crate::common::SolanaRpcClient;
+use anyhow::Result;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_client::rpc_response::{RpcSimulateTransactionResult, UiTokenAmount};
+use solana_hash::Hash;
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+};
+use std::sync::Arc;
+
+/// 共享的 RPC 传输实现
+pub type RpcProviderRef = Arc<dyn RpcProvider>;
+
+/// SDK 使用的 RPC 方法集合
+#[async_trait::async_trait]
+pub trait RpcProvider: Send + Sync {
+    /// 读取账户（不存在时返回错误）
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Account>;
+
+    /// 批量读取账户，结果与 `pubkeys` 一一对应，不存在的账户为 `None`
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>>;
+
+    /// 读取 Token 账户余额
+    async fn get_token_account_balance(&self, pubkey: &Pubkey) -> Result<UiTokenAmount>;
+
+    /// 获取最新 blockhash
+    async fn get_latest_blockhash(&self) -> Result<Hash>;
+
+    /// 发送已签名交易
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature>;
+
+    /// 模拟交易
+    async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<RpcSimulateTransactionResult>;
+}
+
+#[async_trait::async_trait]
+impl RpcProvider for SolanaRpcClient {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Account> {
+        Ok(self.get_account(pubkey).await?)
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        Ok(self.get_multiple_accounts(pubkeys).await?)
+    }
+
+    async fn get_token_account_balance(&self, pubkey: &Pubkey) -> Result<UiTokenAmount> {
+        Ok(self.get_token_account_balance(pubkey).await?)
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.get_latest_blockhash().await?)
+    }
+
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        Ok(self.send_transaction(transaction).await?)
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<RpcSimulateTransactionResult> {
+        Ok(self.simulate_transaction_with_config(transaction, config).await?.value)
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: RpcProvider + ?Sized> RpcProvider for Arc<P> {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Account> {
+        self.as_ref().get_account(pubkey).await
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        self.as_ref().get_multiple_accounts(pubkeys).await
+    }
+
+    async fn get_token_account_balance(&self, pubkey: &Pubkey) -> Result<UiTokenAmount> {
+        self.as_ref().get_token_account_balance(pubkey).await
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash> {
+        self.as_ref().get_latest_blockhash().await
+    }
+
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        self.as_ref().send_transaction(transaction).await
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<RpcSimulateTransactionResult> {
+        self.as_ref().simulate_transaction(transaction, config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::collections::HashMap;
+
+    /// 内存中的测试替身
+    struct StaticProvider {
+        accounts: HashMap<Pubkey, Account>,
+    }
+
+    #[async_trait::async_trait]
+    impl RpcProvider for StaticProvider {
+        async fn get_account(&self, pubkey: &Pubkey) -> Result<Account> {
+            self.accounts.get(pubkey).cloned().ok_or_else(|| anyhow!("AccountNotFound"))
+        }
+
+        async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+            Ok(pubkeys.iter().map(|pubkey| self.accounts.get(pubkey).cloned()).collect())
+        }
+
+        async fn get_token_account_balance(&self, _pubkey: &Pubkey) -> Result<UiTokenAmount> {
+            Err(anyhow!("unsupported"))
+        }
+
+        async fn get_latest_blockhash(&self) -> Result<Hash> {
+            Ok(Hash::default())
+        }
+
+        async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+            Ok(transaction.signatures.first().copied().unwrap_or_default())
+        }
+
+        async fn simulate_transaction(
+            &self,
+            _transaction: &VersionedTransaction,
+            _config: RpcSimulateTransactionConfig,
+        ) -> Result<RpcSimulateTransactionResult> {
+            Err(anyhow!("unsupported"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_provider_behind_shared_ref() {
+        let known = Pubkey::new_unique();
+        let provider: RpcProviderRef = Arc::new(StaticProvider {
+            accounts: HashMap::from([(known, Account { lamports: 7, ..Account::default() })]),
+        });
+        let missing = Pubkey::new_unique();
+        let accounts = provider.get_multiple_accounts(&[known, missing]).await.unwrap();
+        assert_eq!(accounts[0].as_ref().map(|a| a.lamports), Some(7));
+        assert!(accounts[1].is_none());
+        assert!(provider.get_account(&missing).await.is_err());
+        assert_eq!(provider.get_latest_blockhash().await.unwrap(), Hash::default());
+    }
+}
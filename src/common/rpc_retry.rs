@@ -0,0 +1,232 @@
+//! RPC 重试策略
+//!
+//! 构建交易时的 RPC 读取（池状态、tick array、余额等）遇到 429、5xx、超时或连接错误时，
+//! 按 [`RpcRetryPolicy`] 做指数退避重试，而不是让整笔交易构建失败。
+//! 重试在 [`RetrySender`] 中完成，对 `SolanaRpcClient` 的所有调用透明生效。
+//!
+//! `sendTransaction` 默认不重试：发送失败由 SWQOS 层处理，重复提交应由调用方决定。
+
+use async_trait::async_trait;
+use rand::Rng;
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::http_sender::HttpSender;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client::rpc_client::RpcClientConfig;
+use solana_rpc_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client_api::client_error::{
+    Error as ClientError, ErrorKind as ClientErrorKind, Result as ClientResult,
+};
+use solana_rpc_client_api::request::{RpcError, RpcRequest};
+use std::time::Duration;
+
+/// 节点落后 / 不健康（`NodeUnhealthy`），换个时间再请求通常即可成功
+const JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY: i64 = -32005;
+
+/// RPC 重试策略
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RpcRetryPolicy {
+    /// 最大尝试次数（含首次请求），1 表示不重试
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间
+    pub initial_backoff: Duration,
+    /// 单次等待时间上限
+    pub max_backoff: Duration,
+    /// 每次重试后等待时间的倍数
+    pub multiplier: u32,
+    /// 是否加入随机抖动（在 `[backoff / 2, backoff]` 内取值），避免多个客户端同时重试
+    pub jitter: bool,
+    /// 是否重试 `sendTransaction`（默认：false）
+    pub retry_send_transaction: bool,
+}
+
+impl Default for RpcRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            multiplier: 2,
+            jitter: true,
+            retry_send_transaction: false,
+        }
+    }
+}
+
+impl RpcRetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 不重试
+    pub fn disabled() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// 设置最大尝试次数（含首次请求）
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// 设置首次重试前的等待时间与单次等待上限
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// 设置退避倍数
+    pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier.max(1);
+        self
+    }
+
+    /// 启用 / 关闭随机抖动
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// 是否重试 `sendTransaction`
+    pub fn with_retry_send_transaction(mut self, retry: bool) -> Self {
+        self.retry_send_transaction = retry;
+        self
+    }
+
+    /// 第 `retry` 次重试（从 0 开始）前的等待时间（未加抖动）
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(retry);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// 加入抖动后的等待时间
+    fn backoff_with_jitter(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        if !self.jitter || backoff.is_zero() {
+            return backoff;
+        }
+        let half = backoff / 2;
+        half + half.mul_f64(rand::rng().random::<f64>())
+    }
+
+    /// 该请求是否允许重试
+    pub fn retries_request(&self, request: &RpcRequest) -> bool {
+        self.max_attempts > 1
+            && (self.retry_send_transaction || !matches!(request, RpcRequest::SendTransaction))
+    }
+}
+
+/// 错误是否为暂时性错误（429、5xx、超时、连接错误、节点不健康）
+pub fn is_retryable_error(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status()
+                    .is_some_and(|status| status.as_u16() == 429 || status.is_server_error())
+        },
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+        },
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(message))
+        | ClientErrorKind::Custom(message) => is_retryable_message(message),
+        _ => false,
+    }
+}
+
+fn is_retryable_message(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("429")
+        || message.contains("too many requests")
+        || message.contains("timed out")
+}
+
+/// 按 [`RpcRetryPolicy`] 重试的 HTTP RPC 发送器
+pub struct RetrySender {
+    inner: HttpSender,
+    policy: RpcRetryPolicy,
+}
+
+impl RetrySender {
+    pub fn new(url: impl ToString, policy: RpcRetryPolicy) -> Self {
+        Self { inner: HttpSender::new(url.to_string()), policy }
+    }
+}
+
+#[async_trait]
+impl RpcSender for RetrySender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let max_attempts =
+            if self.policy.retries_request(&request) { self.policy.max_attempts } else { 1 };
+        let mut retry = 0;
+        loop {
+            match self.inner.send(request, params.clone()).await {
+                Err(e) if retry + 1 < max_attempts && is_retryable_error(&e) => {
+                    tokio::time::sleep(self.policy.backoff_with_jitter(retry)).await;
+                    retry += 1;
+                },
+                result => return result,
+            }
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// 创建按 `policy` 重试的 RPC 客户端
+pub fn new_rpc_client_with_retry(
+    url: String,
+    commitment: CommitmentConfig,
+    policy: RpcRetryPolicy,
+) -> RpcClient {
+    RpcClient::new_sender(
+        RetrySender::new(url, policy),
+        RpcClientConfig::with_commitment(commitment),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_and_classification() {
+        let policy = RpcRetryPolicy::new()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+        let jittered = policy.clone().with_jitter(true).backoff_with_jitter(1);
+        assert!(jittered >= Duration::from_millis(100) && jittered <= Duration::from_millis(200));
+
+        assert!(policy.retries_request(&RpcRequest::GetAccountInfo));
+        assert!(!policy.retries_request(&RpcRequest::SendTransaction));
+        assert!(!RpcRetryPolicy::disabled().retries_request(&RpcRequest::GetAccountInfo));
+
+        let custom = |message: &str| ClientError::from(ClientErrorKind::Custom(message.into()));
+        assert!(is_retryable_error(&custom("HTTP status client error (429 Too Many Requests)")));
+        assert!(!is_retryable_error(&custom("AccountNotFound")));
+        let rpc_error = |code| {
+            ClientError::from(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code,
+                message: String::new(),
+                data: solana_rpc_client_api::request::RpcResponseErrorData::Empty,
+            }))
+        };
+        assert!(is_retryable_error(&rpc_error(JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY)));
+        assert!(!is_retryable_error(&rpc_error(-32602)));
+    }
+}
@@ -1,44 +1,19 @@
 use crate::common::SolanaRpcClient;
-use anyhow::anyhow;
+use crate::common::rent_cache::{self, TOKEN_ACCOUNT_SPACE};
 use fnv::FnvHasher;
-use once_cell::sync::Lazy;
 use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
 use solana_system_interface::instruction::create_account_with_seed;
 use std::hash::Hasher;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use tokio::time::{Duration, sleep};
 
-// 🚀 优化：使用 AtomicU64 替代 RwLock，性能提升 5-10x
-// u64::MAX 表示未初始化状态
-static SPL_TOKEN_RENT: Lazy<AtomicU64> = Lazy::new(|| AtomicU64::new(u64::MAX));
-static SPL_TOKEN_2022_RENT: Lazy<AtomicU64> = Lazy::new(|| AtomicU64::new(u64::MAX));
-
-/// 更新租金缓存（后台任务调用）
+/// 更新租金缓存，见 [`rent_cache::refresh_now`]
 pub async fn update_rents(client: &SolanaRpcClient) -> Result<(), anyhow::Error> {
-    let rent = fetch_rent_for_token_account(client, false).await?;
-    SPL_TOKEN_RENT.store(rent, Ordering::Release); // Release 确保其他线程可见
-
-    let rent = fetch_rent_for_token_account(client, true).await?;
-    SPL_TOKEN_2022_RENT.store(rent, Ordering::Release);
-
-    Ok(())
+    rent_cache::refresh_now(client).await
 }
 
+/// 启动租金缓存后台刷新，见 [`rent_cache::start_updater`]
 pub fn start_rent_updater(client: Arc<SolanaRpcClient>) {
-    tokio::spawn(async move {
-        loop {
-            if let Err(_e) = update_rents(&client).await {}
-            sleep(Duration::from_secs(60 * 60)).await;
-        }
-    });
-}
-
-async fn fetch_rent_for_token_account(
-    client: &SolanaRpcClient,
-    _is_2022_token: bool,
-) -> Result<u64, anyhow::Error> {
-    Ok(client.get_minimum_balance_for_rent_exemption(165).await?)
+    rent_cache::start_updater(client);
 }
 
 pub fn create_associated_token_account_use_seed(
@@ -50,20 +25,7 @@ pub fn create_associated_token_account_use_seed(
     let is_2022_token = token_program == &crate::constants::TOKEN_PROGRAM_2022;
 
     // 🚀 优化：原子读取租金缓存
-    // Relaxed: 租金值不变，无需同步；Release/Acquire 在 update_rents 保证初始化可见性
-    let rent = if is_2022_token {
-        let v = SPL_TOKEN_2022_RENT.load(Ordering::Relaxed);
-        if v == u64::MAX {
-            return Err(anyhow!("Rent not initialized"));
-        }
-        v
-    } else {
-        let v = SPL_TOKEN_RENT.load(Ordering::Relaxed);
-        if v == u64::MAX {
-            return Err(anyhow!("Rent not initialized"));
-        }
-        v
-    };
+    let rent = rent_cache::require(TOKEN_ACCOUNT_SPACE)?;
 
     let mut buf = [0u8; 8];
     let mut hasher = FnvHasher::default();
@@ -82,7 +44,7 @@ pub fn create_associated_token_account_use_seed(
     // 买入和卖出只要都使用事件中的 token_program，地址自然一致
     let ata_like = Pubkey::create_with_seed(payer, seed, token_program)?;
 
-    let len = 165;
+    let len = TOKEN_ACCOUNT_SPACE as u64;
     // 但账户的 owner 仍然使用正确的 token_program（Token 或 Token-2022）
     let create_acc =
         create_account_with_seed(payer, &ata_like, owner, seed, rent, len, token_program);
@@ -0,0 +1,476 @@
+//! 跨进程共享的池参数 / 代币精度缓存（内存映射文件）
+//!
+//! 多进程架构下由一个发现进程加载池子并写入缓存，多个执行进程映射同一文件直接读取，
+//! 避免每个进程重复请求 RPC。文件由定长槽位组成，每个槽位带 seqlock 序号：
+//! 写入时序号为奇数，读取方在序号前后一致且为偶数时才采用读到的数据。
+//!
+//! - 单写多读：只有通过 [`SharedPoolCache::create`] 打开的进程可以写入；
+//! - 条目只会被覆盖，不会删除；写入进程重启时 `create` 原地清空文件（不截断）并递增头部代数，
+//!   已打开的读取方发现代数变化后自动重新映射；
+//! - [`install_shared_pool_cache`] 安装为全局后端后，[`params_cache`](crate::trading::core::params_cache::params_cache)
+//!   与代币精度缓存在本地未命中时自动读取，写入方在加载后自动发布。
+
+use crate::perf::zero_copy_io::SharedMappedFile;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use arc_swap::ArcSwap;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{Ordering, fence};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MAGIC: u64 = u64::from_le_bytes(*b"SOLPOOL1");
+const HEADER_SIZE: usize = 64;
+/// 文件头偏移：magic(8) | capacity(8) | slot_size(8) | generation(8)
+///
+/// generation 每次 [`SharedPoolCache::create`] 递增，重建期间为奇数
+const HEADER_MAGIC: usize = 0;
+const HEADER_CAPACITY: usize = 8;
+const HEADER_SLOT_SIZE: usize = 16;
+const HEADER_GENERATION: usize = 24;
+/// 槽位内偏移：seq(8) | kind(1) dex(1) pad(6) | mint(32) | updated_at_ms(8) | len(4) pad(4) | payload
+const SLOT_KIND: usize = 8;
+const SLOT_DEX: usize = 9;
+const SLOT_MINT: usize = 16;
+const SLOT_UPDATED_AT: usize = 48;
+const SLOT_LEN: usize = 56;
+const SLOT_PAYLOAD: usize = 64;
+/// 单个条目序列化后的最大字节数
+pub const MAX_PAYLOAD_SIZE: usize = 1024;
+const SLOT_SIZE: usize = SLOT_PAYLOAD + MAX_PAYLOAD_SIZE;
+/// 读取方遇到正在写入的槽位时的最大重试次数
+const MAX_READ_RETRIES: usize = 1024;
+
+const KIND_EMPTY: u8 = 0;
+const KIND_PARAMS: u8 = 1;
+const KIND_DECIMALS: u8 = 2;
+
+/// 从共享缓存读取的池参数
+#[derive(Clone)]
+pub struct SharedParams {
+    /// 池地址（与 [`ParamsCache`](crate::trading::core::params_cache::ParamsCache) 的约定一致）
+    pub pool: Pubkey,
+    pub params: DexParamEnum,
+    /// 写入方发布该条目时的 Unix 时间（毫秒）
+    pub updated_at_ms: u64,
+}
+
+impl SharedParams {
+    /// 距发布时的时长
+    pub fn age(&self) -> Duration {
+        Duration::from_millis(now_ms().saturating_sub(self.updated_at_ms))
+    }
+}
+
+/// 基于内存映射文件的跨进程缓存
+pub struct SharedPoolCache {
+    path: PathBuf,
+    /// 当前映射；读取方发现写入方重建文件后替换为新映射
+    mapping: ArcSwap<Mapping>,
+    /// 只有写入方持有；同一进程内的多个写入串行执行
+    writer: Option<Mutex<()>>,
+}
+
+/// 某一代缓存文件的映射
+struct Mapping {
+    file: SharedMappedFile,
+    capacity: usize,
+    generation: u64,
+}
+
+/// 槽位中读出的键与数据
+struct SlotSnapshot {
+    kind: u8,
+    dex: u8,
+    mint: Pubkey,
+    updated_at_ms: u64,
+    payload: Vec<u8>,
+}
+
+impl SharedPoolCache {
+    /// 以写入方身份创建缓存文件（已存在时原地清空），可容纳 `capacity` 个条目
+    ///
+    /// 文件不会被截断：已打开的读取方在重建期间读不到条目，完成后按新的代数重新映射。
+    pub fn create(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(anyhow!("Shared pool cache capacity must be positive"));
+        }
+        let file =
+            SharedMappedFile::open_or_create(path.as_ref(), HEADER_SIZE + capacity * SLOT_SIZE)?;
+        // 代数先置为奇数，读取方据此停止使用旧布局
+        let previous = if file.atomic_u64(HEADER_MAGIC).load(Ordering::Acquire) == MAGIC {
+            file.atomic_u64(HEADER_GENERATION).load(Ordering::Relaxed)
+        } else {
+            0
+        };
+        let rebuilding = previous | 1;
+        file.atomic_u64(HEADER_GENERATION).store(rebuilding, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        file.atomic_u64(HEADER_CAPACITY).store(capacity as u64, Ordering::Relaxed);
+        file.atomic_u64(HEADER_SLOT_SIZE).store(SLOT_SIZE as u64, Ordering::Relaxed);
+        let mapping = Mapping { file, capacity, generation: rebuilding + 1 };
+        for index in 0..capacity {
+            mapping.clear_slot(index);
+        }
+        mapping.file.atomic_u64(HEADER_MAGIC).store(MAGIC, Ordering::Release);
+        mapping
+            .file
+            .atomic_u64(HEADER_GENERATION)
+            .store(mapping.generation, Ordering::Release);
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            mapping: ArcSwap::from_pointee(mapping),
+            writer: Some(Mutex::new(())),
+        })
+    }
+
+    /// 以只读方身份映射写入方创建的缓存文件
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let mapping = Mapping::open(path.as_ref())?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            mapping: ArcSwap::from_pointee(mapping),
+            writer: None,
+        })
+    }
+
+    /// 是否为写入方
+    pub fn is_writer(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.current().map(|mapping| mapping.capacity).unwrap_or(0)
+    }
+
+    /// 已占用的槽位数
+    pub fn len(&self) -> usize {
+        self.current()
+            .map(|mapping| {
+                (0..mapping.capacity).filter(|i| mapping.read_slot(*i).is_some()).count()
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 发布池参数（覆盖同一 `(dex_type, mint)` 的旧条目）
+    pub fn put_params(
+        &self,
+        dex_type: &DexType,
+        mint: &Pubkey,
+        pool: &Pubkey,
+        params: &DexParamEnum,
+    ) -> Result<()> {
+        let payload = bincode::serialize(&(pool, params))?;
+        self.put(KIND_PARAMS, dex_code(dex_type), mint, &payload)
+    }
+
+    /// 读取池参数
+    pub fn get_params(&self, dex_type: &DexType, mint: &Pubkey) -> Option<SharedParams> {
+        let slot = self.find(KIND_PARAMS, dex_code(dex_type), mint)?;
+        let (pool, params) = bincode::deserialize(&slot.payload).ok()?;
+        Some(SharedParams { pool, params, updated_at_ms: slot.updated_at_ms })
+    }
+
+    /// 发布代币精度
+    pub fn put_decimals(&self, mint: &Pubkey, decimals: u8) -> Result<()> {
+        self.put(KIND_DECIMALS, 0, mint, &[decimals])
+    }
+
+    /// 读取代币精度
+    pub fn get_decimals(&self, mint: &Pubkey) -> Option<u8> {
+        self.find(KIND_DECIMALS, 0, mint)?.payload.first().copied()
+    }
+
+    /// 当前代的映射：文件代数变化时读取方重新映射；重建期间返回 None
+    fn current(&self) -> Option<Arc<Mapping>> {
+        let mapping = self.mapping.load_full();
+        let generation = mapping.file.atomic_u64(HEADER_GENERATION).load(Ordering::Acquire);
+        if generation == mapping.generation {
+            return Some(mapping);
+        }
+        // 写入方的映射只会被另一个写入方重建，不再可用
+        if self.is_writer() || generation % 2 == 1 {
+            return None;
+        }
+        match Mapping::open(&self.path) {
+            Ok(mapping) => {
+                let mapping = Arc::new(mapping);
+                self.mapping.store(mapping.clone());
+                Some(mapping)
+            },
+            Err(e) => {
+                log::warn!("Failed to remap shared pool cache {}: {}", self.path.display(), e);
+                None
+            },
+        }
+    }
+
+    fn put(&self, kind: u8, dex: u8, mint: &Pubkey, payload: &[u8]) -> Result<()> {
+        let _guard = self
+            .writer
+            .as_ref()
+            .ok_or_else(|| anyhow!("Shared pool cache is opened read-only"))?
+            .lock();
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            return Err(anyhow!(
+                "Shared cache entry is {} bytes, exceeds {}",
+                payload.len(),
+                MAX_PAYLOAD_SIZE
+            ));
+        }
+        let mapping = self
+            .current()
+            .ok_or_else(|| anyhow!("Shared pool cache was re-created by another writer"))?;
+        // 写入方独占写权限：槽位不会在探测期间被其他进程改变
+        let index = mapping
+            .probe(kind, dex, mint)
+            .find(|i| match mapping.read_slot(*i) {
+                None => true,
+                Some(slot) => slot.kind == kind && slot.dex == dex && slot.mint == *mint,
+            })
+            .ok_or_else(|| anyhow!("Shared pool cache is full ({} entries)", mapping.capacity))?;
+
+        let base = mapping.slot_offset(index);
+        let seq = mapping.file.atomic_u64(base);
+        let start = seq.load(Ordering::Relaxed);
+        seq.store(start + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        unsafe {
+            let ptr = mapping.file.as_ptr().add(base);
+            *ptr.add(SLOT_KIND) = kind;
+            *ptr.add(SLOT_DEX) = dex;
+            std::ptr::copy_nonoverlapping(mint.as_ref().as_ptr(), ptr.add(SLOT_MINT), 32);
+            std::ptr::copy_nonoverlapping(
+                now_ms().to_le_bytes().as_ptr(),
+                ptr.add(SLOT_UPDATED_AT),
+                8,
+            );
+            std::ptr::copy_nonoverlapping(
+                (payload.len() as u32).to_le_bytes().as_ptr(),
+                ptr.add(SLOT_LEN),
+                4,
+            );
+            std::ptr::copy_nonoverlapping(payload.as_ptr(), ptr.add(SLOT_PAYLOAD), payload.len());
+        }
+        seq.store(start + 2, Ordering::Release);
+        Ok(())
+    }
+
+    fn find(&self, kind: u8, dex: u8, mint: &Pubkey) -> Option<SlotSnapshot> {
+        let mapping = self.current()?;
+        for index in mapping.probe(kind, dex, mint) {
+            let slot = mapping.read_slot(index)?;
+            if slot.kind == kind && slot.dex == dex && slot.mint == *mint {
+                return Some(slot);
+            }
+        }
+        None
+    }
+}
+
+impl Mapping {
+    /// 映射写入方已初始化完成的缓存文件
+    fn open(path: &Path) -> Result<Self> {
+        let file = SharedMappedFile::open(path)?;
+        if file.len() < HEADER_SIZE
+            || file.atomic_u64(HEADER_MAGIC).load(Ordering::Acquire) != MAGIC
+        {
+            return Err(anyhow!("Not a shared pool cache file"));
+        }
+        let generation = file.atomic_u64(HEADER_GENERATION).load(Ordering::Acquire);
+        if generation % 2 == 1 {
+            return Err(anyhow!("Shared pool cache is being re-created"));
+        }
+        let capacity = file.atomic_u64(HEADER_CAPACITY).load(Ordering::Relaxed) as usize;
+        let slot_size = file.atomic_u64(HEADER_SLOT_SIZE).load(Ordering::Relaxed) as usize;
+        if slot_size != SLOT_SIZE || file.len() < HEADER_SIZE + capacity * SLOT_SIZE {
+            return Err(anyhow!("Incompatible shared pool cache layout"));
+        }
+        Ok(Self { file, capacity, generation })
+    }
+
+    /// 线性探测顺序（条目从不删除，遇到空槽即可停止）
+    fn probe(&self, kind: u8, dex: u8, mint: &Pubkey) -> impl Iterator<Item = usize> {
+        let bytes = mint.to_bytes();
+        let hash = u64::from_le_bytes(bytes[..8].try_into().unwrap())
+            ^ ((kind as u64) << 56)
+            ^ ((dex as u64) << 48);
+        let start = (hash % self.capacity as u64) as usize;
+        let capacity = self.capacity;
+        (0..capacity).map(move |i| (start + i) % capacity)
+    }
+
+    fn slot_offset(&self, index: usize) -> usize {
+        HEADER_SIZE + index * SLOT_SIZE
+    }
+
+    /// 清空槽位；序号保持递增，仍在读取旧内容的进程通过序号发现变化
+    fn clear_slot(&self, index: usize) {
+        let base = self.slot_offset(index);
+        let seq = self.file.atomic_u64(base);
+        // 上一个写入方可能在写入中途退出，序号停在奇数
+        let busy = seq.load(Ordering::Relaxed) | 1;
+        seq.store(busy, Ordering::Relaxed);
+        fence(Ordering::Release);
+        unsafe { std::ptr::write_bytes(self.file.as_ptr().add(base + 8), 0, SLOT_SIZE - 8) };
+        seq.store(busy + 1, Ordering::Release);
+    }
+
+    /// seqlock 读取槽位；空槽或持续处于写入中时返回 None
+    fn read_slot(&self, index: usize) -> Option<SlotSnapshot> {
+        let base = self.slot_offset(index);
+        let seq = self.file.atomic_u64(base);
+        for _ in 0..MAX_READ_RETRIES {
+            let before = seq.load(Ordering::Acquire);
+            if before == 0 {
+                return None;
+            }
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let snapshot = unsafe { self.copy_slot(base) };
+            fence(Ordering::Acquire);
+            if seq.load(Ordering::Relaxed) == before {
+                return snapshot.filter(|slot| slot.kind != KIND_EMPTY);
+            }
+        }
+        None
+    }
+
+    /// 拷贝槽位内容；写入中的数据可能不一致，由调用方通过序号校验
+    unsafe fn copy_slot(&self, base: usize) -> Option<SlotSnapshot> {
+        let ptr = unsafe { self.file.as_ptr().add(base) as *const u8 };
+        let read = |offset: usize, len: usize| {
+            let mut buf = vec![0u8; len];
+            unsafe { std::ptr::copy_nonoverlapping(ptr.add(offset), buf.as_mut_ptr(), len) };
+            buf
+        };
+        let len = u32::from_le_bytes(read(SLOT_LEN, 4).try_into().ok()?) as usize;
+        if len > MAX_PAYLOAD_SIZE {
+            return None;
+        }
+        Some(SlotSnapshot {
+            kind: read(SLOT_KIND, 1)[0],
+            dex: read(SLOT_DEX, 1)[0],
+            mint: Pubkey::try_from(read(SLOT_MINT, 32).as_slice()).ok()?,
+            updated_at_ms: u64::from_le_bytes(read(SLOT_UPDATED_AT, 8).try_into().ok()?),
+            payload: read(SLOT_PAYLOAD, len),
+        })
+    }
+}
+
+static SHARED_POOL_CACHE: OnceCell<Arc<SharedPoolCache>> = OnceCell::new();
+
+/// 安装全局共享缓存（进程内只能安装一次）
+pub fn install_shared_pool_cache(cache: Arc<SharedPoolCache>) -> Result<()> {
+    SHARED_POOL_CACHE
+        .set(cache)
+        .map_err(|_| anyhow!("Shared pool cache is already installed"))
+}
+
+/// 已安装的全局共享缓存
+pub fn shared_pool_cache() -> Option<&'static SharedPoolCache> {
+    SHARED_POOL_CACHE.get().map(|cache| cache.as_ref())
+}
+
+fn dex_code(dex_type: &DexType) -> u8 {
+    match dex_type {
+        DexType::PumpFun => 1,
+        DexType::PumpSwap => 2,
+        DexType::Bonk => 3,
+        DexType::RaydiumCpmm => 4,
+        DexType::RaydiumAmmV4 => 5,
+        DexType::RaydiumClmm => 6,
+        DexType::MeteoraDammV2 => 7,
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::BonkParams;
+
+    #[test]
+    fn test_writer_and_reader_share_entries() {
+        let path = std::env::temp_dir().join(format!("shared_pool_{}.bin", std::process::id()));
+        let writer = SharedPoolCache::create(&path, 4).unwrap();
+        let reader = SharedPoolCache::open(&path).unwrap();
+        assert!(writer.is_writer() && !reader.is_writer());
+        assert_eq!(reader.capacity(), 4);
+
+        let (mint, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(reader.get_decimals(&mint).is_none());
+        writer.put_decimals(&mint, 6).unwrap();
+        assert_eq!(reader.get_decimals(&mint), Some(6));
+
+        let params = BonkParams { real_base: 7, ..Default::default() };
+        writer
+            .put_params(&DexType::Bonk, &mint, &pool, &DexParamEnum::Bonk(params))
+            .unwrap();
+        let shared = reader.get_params(&DexType::Bonk, &mint).unwrap();
+        assert_eq!(shared.pool, pool);
+        assert!(matches!(&shared.params, DexParamEnum::Bonk(p) if p.real_base == 7));
+        assert!(reader.get_params(&DexType::PumpSwap, &mint).is_none());
+
+        // 覆盖同一个键不占用新槽位
+        writer.put_decimals(&mint, 9).unwrap();
+        assert_eq!(reader.get_decimals(&mint), Some(9));
+        assert_eq!(reader.len(), 2);
+        assert!(reader.put_decimals(&mint, 6).is_err());
+
+        for _ in 0..2 {
+            writer.put_decimals(&Pubkey::new_unique(), 6).unwrap();
+        }
+        assert!(writer.put_decimals(&Pubkey::new_unique(), 6).is_err());
+
+        drop((writer, reader));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reader_remaps_after_writer_recreates() {
+        let path =
+            std::env::temp_dir().join(format!("shared_pool_recreate_{}.bin", std::process::id()));
+        let writer = SharedPoolCache::create(&path, 2).unwrap();
+        let reader = SharedPoolCache::open(&path).unwrap();
+        let mint = Pubkey::new_unique();
+        writer.put_decimals(&mint, 6).unwrap();
+        assert_eq!(reader.get_decimals(&mint), Some(6));
+
+        // 写入方重启：文件原地重建，旧写入方失效，读取方切换到新布局
+        let restarted = SharedPoolCache::create(&path, 8).unwrap();
+        assert!(writer.put_decimals(&mint, 9).is_err());
+        assert!(reader.get_decimals(&mint).is_none());
+        assert_eq!(reader.capacity(), 8);
+        restarted.put_decimals(&mint, 9).unwrap();
+        assert_eq!(reader.get_decimals(&mint), Some(9));
+
+        // 以更小的容量重建不截断文件，旧映射仍可安全访问
+        let len = std::fs::metadata(&path).unwrap().len();
+        let shrunk = SharedPoolCache::create(&path, 1).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+        assert!(restarted.put_decimals(&mint, 6).is_err());
+        assert!(reader.get_decimals(&mint).is_none());
+        assert_eq!(reader.capacity(), 1);
+
+        drop((writer, reader, restarted, shrunk));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
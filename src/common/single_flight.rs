@@ -0,0 +1,78 @@
+//! 并发账户读取合并（single-flight）
+//!
+//! 多笔交易并发指向同一个 mint 时，会同时读取同一个 Pool 账户。这里按
+//! `(账户地址, slot 时间桶)` 合并同时发起的读取：同一时间桶内只有第一个调用方真正发起 RPC，
+//! 其余调用方等待并共享其结果。请求完成后立即移除，不做结果缓存（缓存由各协议的 Pool 缓存负责）。
+//!
+//! 领头的调用方被取消时，等待中的调用方会接替发起请求。
+
+use crate::common::auto_mock_rpc::PoolRpcClient;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::OnceCell;
+
+/// slot 时间桶长度（毫秒），与出块间隔一致：跨桶的调用方不会拿到上一个 slot 发起的结果
+pub const SLOT_BUCKET_MS: u64 = 400;
+
+type FlightKey = (Pubkey, u64);
+type FlightCell = Arc<OnceCell<Result<Account, String>>>;
+
+static IN_FLIGHT: Lazy<DashMap<FlightKey, FlightCell>> = Lazy::new(DashMap::new);
+
+fn current_bucket() -> u64 {
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    (now_ms / SLOT_BUCKET_MS as u128) as u64
+}
+
+/// 读取账户，合并同一时间桶内对同一地址的并发请求
+pub async fn get_account_coalesced<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pubkey: &Pubkey,
+) -> Result<Account, String> {
+    coalesce(pubkey, || rpc.get_account(pubkey)).await
+}
+
+async fn coalesce<F, Fut>(pubkey: &Pubkey, fetch: F) -> Result<Account, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Account, String>>,
+{
+    let key = (*pubkey, current_bucket());
+    let cell = IN_FLIGHT.entry(key).or_default().clone();
+    let result = cell.get_or_init(fetch).await.clone();
+    IN_FLIGHT.remove_if(&key, |_, current| Arc::ptr_eq(current, &cell));
+    result
+}
+
+/// 当前进行中的合并请求数
+pub fn in_flight_count() -> usize {
+    IN_FLIGHT.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_concurrent_reads_are_coalesced() {
+        let calls = AtomicUsize::new(0);
+        let pool = Pubkey::new_unique();
+        let fetch = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            Ok(Account { lamports: 42, ..Account::default() })
+        };
+        let (a, b, c) =
+            tokio::join!(coalesce(&pool, fetch), coalesce(&pool, fetch), coalesce(&pool, fetch));
+        assert_eq!(a.unwrap().lamports, 42);
+        assert_eq!(b.unwrap().lamports, 42);
+        assert_eq!(c.unwrap().lamports, 42);
+        // 恰好跨过时间桶边界时最多发起两次
+        assert!(calls.load(Ordering::SeqCst) <= 2);
+        assert!(!IN_FLIGHT.iter().any(|entry| entry.key().0 == pool));
+    }
+}
@@ -54,6 +54,31 @@ pub fn transfer(
     Ok(Instruction { program_id: *token_program_id, accounts, data })
 }
 
+pub fn transfer_checked(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    // TransferChecked
+    let mut data = Vec::with_capacity(10);
+    data.push(12); // Instruction discriminator for TransferChecked
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+
+    let accounts = vec![
+        AccountMeta::new(*source_pubkey, false),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new_readonly(*owner_pubkey, true),
+    ];
+
+    Ok(Instruction { program_id: *token_program_id, accounts, data })
+}
+
 pub fn initialize_account3(
     token_program_id: &Pubkey,
     account_pubkey: &Pubkey,
@@ -0,0 +1,324 @@
+//! Token-2022 Transfer Hook 额外账户解析
+//!
+//! 带 TransferHook 扩展的 mint 在每次 `transfer_checked` 时都会 CPI 调用 hook 程序，
+//! 外层指令必须携带 hook 程序、校验账户（`["extra-account-metas", mint]` PDA）以及
+//! 校验账户中 `ExtraAccountMetaList` 声明的全部额外账户，否则交易会因缺少账户而失败。
+//!
+//! 这里直接解析 TLV 数据，支持字面量地址与 PDA（字面量 / 指令数据 / 账户地址种子）；
+//! 依赖账户数据的种子需要额外 RPC 读取，暂不支持，解析时返回错误。
+
+use crate::common::auto_mock_rpc::PoolRpcClient;
+use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+use std::sync::Arc;
+
+/// `spl-transfer-hook-interface:execute` 指令 discriminator
+pub const EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+/// 校验账户 PDA 种子
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// 单个 ExtraAccountMeta 的序列化长度：discriminator + address_config + is_signer + is_writable
+const EXTRA_ACCOUNT_META_SIZE: usize = 1 + 32 + 1 + 1;
+/// Execute 指令中固定账户数：source, mint, destination, authority, validation
+const EXECUTE_FIXED_ACCOUNTS: usize = 5;
+
+/// 全局 hook 信息缓存（mint -> hook 信息，`None` 表示该 mint 没有 hook）
+static TRANSFER_HOOK_CACHE: Lazy<DashMap<Pubkey, Option<Arc<TransferHookInfo>>>> =
+    Lazy::new(DashMap::new);
+
+/// 校验账户中声明的一个额外账户
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtraAccountMetaEntry {
+    /// 0 = 固定地址；1 = hook 程序下的 PDA；128 + i = 第 i 个账户所属程序下的 PDA
+    pub discriminator: u8,
+    pub address_config: [u8; 32],
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// mint 的 transfer hook 配置
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferHookInfo {
+    /// hook 程序
+    pub program_id: Pubkey,
+    /// `["extra-account-metas", mint]` 校验账户
+    pub validation_account: Pubkey,
+    /// 校验账户声明的额外账户
+    pub extra_metas: Vec<ExtraAccountMetaEntry>,
+}
+
+impl TransferHookInfo {
+    /// 解析一次转账需要附加到外层指令的账户：额外账户、hook 程序、校验账户
+    ///
+    /// `amount` 仅在种子引用指令数据时使用；输出侧转账数量在构建时未知，传入最小输出即可。
+    pub fn resolve_execute_accounts(
+        &self,
+        source: &Pubkey,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> Result<Vec<AccountMeta>> {
+        let mut instruction_data = [0u8; 16];
+        instruction_data[..8].copy_from_slice(&EXECUTE_DISCRIMINATOR);
+        instruction_data[8..].copy_from_slice(&amount.to_le_bytes());
+
+        let mut keys = Vec::with_capacity(EXECUTE_FIXED_ACCOUNTS + self.extra_metas.len());
+        keys.extend([*source, *mint, *destination, *authority, self.validation_account]);
+
+        let mut metas = Vec::with_capacity(self.extra_metas.len() + 2);
+        for entry in &self.extra_metas {
+            let pubkey = match entry.discriminator {
+                0 => Pubkey::new_from_array(entry.address_config),
+                1 => self.derive_pda(&self.program_id, entry, &keys, &instruction_data)?,
+                d if d >= 128 => {
+                    let index = (d - 128) as usize;
+                    let program_id = *keys.get(index).ok_or_else(|| {
+                        anyhow!("extra account meta references missing account {}", index)
+                    })?;
+                    self.derive_pda(&program_id, entry, &keys, &instruction_data)?
+                },
+                d => return Err(anyhow!("unsupported extra account meta discriminator {}", d)),
+            };
+            keys.push(pubkey);
+            metas.push(if entry.is_writable {
+                AccountMeta::new(pubkey, entry.is_signer)
+            } else {
+                AccountMeta::new_readonly(pubkey, entry.is_signer)
+            });
+        }
+        metas.push(AccountMeta::new_readonly(self.program_id, false));
+        metas.push(AccountMeta::new_readonly(self.validation_account, false));
+        Ok(metas)
+    }
+
+    /// 与转账双方无关的账户：固定地址的额外账户、hook 程序与校验账户
+    ///
+    /// 以 PDA 声明的额外账户依赖转账双方，不包含在内。
+    pub fn static_accounts(&self) -> Vec<Pubkey> {
+        let mut accounts: Vec<Pubkey> = self
+            .extra_metas
+            .iter()
+            .filter(|entry| entry.discriminator == 0)
+            .map(|entry| Pubkey::new_from_array(entry.address_config))
+            .collect();
+        accounts.push(self.program_id);
+        accounts.push(self.validation_account);
+        accounts
+    }
+
+    fn derive_pda(
+        &self,
+        program_id: &Pubkey,
+        entry: &ExtraAccountMetaEntry,
+        keys: &[Pubkey],
+        instruction_data: &[u8],
+    ) -> Result<Pubkey> {
+        let config = &entry.address_config;
+        let mut seeds: Vec<&[u8]> = Vec::new();
+        let mut i = 0;
+        while i < config.len() {
+            match config[i] {
+                0 => break,
+                // Literal { length, bytes }
+                1 => {
+                    let len = *config.get(i + 1).ok_or_else(|| anyhow!("truncated seed"))? as usize;
+                    let bytes = config
+                        .get(i + 2..i + 2 + len)
+                        .ok_or_else(|| anyhow!("truncated literal seed"))?;
+                    seeds.push(bytes);
+                    i += 2 + len;
+                },
+                // InstructionData { index, length }
+                2 => {
+                    let index =
+                        *config.get(i + 1).ok_or_else(|| anyhow!("truncated seed"))? as usize;
+                    let len = *config.get(i + 2).ok_or_else(|| anyhow!("truncated seed"))? as usize;
+                    let bytes = instruction_data
+                        .get(index..index + len)
+                        .ok_or_else(|| anyhow!("instruction data seed out of range"))?;
+                    seeds.push(bytes);
+                    i += 3;
+                },
+                // AccountKey { index }
+                3 => {
+                    let index =
+                        *config.get(i + 1).ok_or_else(|| anyhow!("truncated seed"))? as usize;
+                    let key = keys
+                        .get(index)
+                        .ok_or_else(|| anyhow!("account key seed references missing account"))?;
+                    seeds.push(key.as_ref());
+                    i += 2;
+                },
+                4 => {
+                    return Err(anyhow!("account data seeds are not supported for transfer hooks"));
+                },
+                t => return Err(anyhow!("unknown seed type {}", t)),
+            }
+        }
+        Ok(Pubkey::find_program_address(&seeds, program_id).0)
+    }
+}
+
+/// 计算校验账户地址
+pub fn get_extra_account_metas_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()], program_id).0
+}
+
+/// 解析校验账户数据（TLV：discriminator + u32 长度 + PodSlice<ExtraAccountMeta>）
+pub fn parse_extra_account_meta_list(data: &[u8]) -> Result<Vec<ExtraAccountMetaEntry>> {
+    if data.len() < 16 {
+        return Err(anyhow!("extra account meta list is too short: {} bytes", data.len()));
+    }
+    if data[..8] != EXECUTE_DISCRIMINATOR {
+        return Err(anyhow!("extra account meta list has an unexpected discriminator"));
+    }
+    let value_len = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+    let value = data
+        .get(12..12 + value_len)
+        .ok_or_else(|| anyhow!("extra account meta list length {} exceeds data", value_len))?;
+    if value.len() < 4 {
+        return Err(anyhow!("extra account meta list is missing its count"));
+    }
+    let count = u32::from_le_bytes(value[..4].try_into().unwrap()) as usize;
+    let items = value
+        .get(4..4 + count * EXTRA_ACCOUNT_META_SIZE)
+        .ok_or_else(|| anyhow!("extra account meta list count {} exceeds data", count))?;
+    Ok(items
+        .chunks_exact(EXTRA_ACCOUNT_META_SIZE)
+        .map(|item| ExtraAccountMetaEntry {
+            discriminator: item[0],
+            address_config: item[1..33].try_into().unwrap(),
+            is_signer: item[33] != 0,
+            is_writable: item[34] != 0,
+        })
+        .collect())
+}
+
+/// 从 Token-2022 mint 数据中读取 hook 程序（未配置时返回 `None`）
+pub fn transfer_hook_program_id(mint_data: &[u8]) -> Option<Pubkey> {
+    use spl_token_2022::extension::transfer_hook::TransferHook;
+    use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+    use spl_token_2022::state::Mint as Mint2022;
+    let mint = StateWithExtensions::<Mint2022>::unpack(mint_data).ok()?;
+    let extension = mint.get_extension::<TransferHook>().ok()?;
+    Option::<Pubkey>::from(extension.program_id)
+}
+
+/// 获取 mint 的 transfer hook 配置（带缓存）
+///
+/// 非 Token-2022 或未配置 hook 的 mint 返回 `None`。
+pub async fn fetch_transfer_hook_info<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    mint: &Pubkey,
+) -> Result<Option<Arc<TransferHookInfo>>> {
+    if let Some(cached) = TRANSFER_HOOK_CACHE.get(mint) {
+        return Ok(cached.clone());
+    }
+    let account = rpc.get_account(mint).await.map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+    let program_id = if account.owner == spl_token_2022::ID {
+        transfer_hook_program_id(&account.data)
+    } else {
+        None
+    };
+    let info = match program_id {
+        Some(program_id) => {
+            let validation_account = get_extra_account_metas_address(mint, &program_id);
+            let validation = rpc
+                .get_account(&validation_account)
+                .await
+                .map_err(|e| anyhow!("failed to load transfer hook validation account: {}", e))?;
+            Some(Arc::new(TransferHookInfo {
+                program_id,
+                validation_account,
+                extra_metas: parse_extra_account_meta_list(&validation.data)?,
+            }))
+        },
+        None => None,
+    };
+    TRANSFER_HOOK_CACHE.insert(*mint, info.clone());
+    Ok(info)
+}
+
+/// 清除 hook 缓存（hook 权限方更新额外账户后调用）
+pub fn clear_transfer_hook_cache() {
+    TRANSFER_HOOK_CACHE.clear();
+}
+
+/// 把一次转账的 hook 账户追加到外层指令账户列表（无 hook 时不做任何事）
+pub fn append_transfer_hook_accounts(
+    accounts: &mut Vec<AccountMeta>,
+    hook: Option<&TransferHookInfo>,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    if let Some(hook) = hook {
+        for meta in hook.resolve_execute_accounts(source, mint, destination, authority, amount)? {
+            if !accounts.iter().any(|existing| existing.pubkey == meta.pubkey) {
+                accounts.push(meta);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(discriminator: u8, config: &[u8], is_writable: bool) -> Vec<u8> {
+        let mut item = vec![discriminator];
+        let mut address_config = [0u8; 32];
+        address_config[..config.len()].copy_from_slice(config);
+        item.extend_from_slice(&address_config);
+        item.extend_from_slice(&[0, is_writable as u8]);
+        item
+    }
+
+    #[test]
+    fn test_parse_and_resolve_extra_account_metas() {
+        let fixed = Pubkey::new_unique();
+        let mut items = entry(0, fixed.as_ref(), false);
+        // PDA: ["counter", source]
+        items.extend(entry(1, &[1, 7, b'c', b'o', b'u', b'n', b't', b'e', b'r', 3, 0], true));
+
+        let mut data = EXECUTE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&((4 + items.len()) as u32).to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&items);
+
+        let extra_metas = parse_extra_account_meta_list(&data).unwrap();
+        assert_eq!(extra_metas.len(), 2);
+
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let hook = TransferHookInfo {
+            program_id,
+            validation_account: get_extra_account_metas_address(&mint, &program_id),
+            extra_metas,
+        };
+        let source = Pubkey::new_unique();
+        let metas = hook
+            .resolve_execute_accounts(
+                &source,
+                &mint,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                1,
+            )
+            .unwrap();
+        let counter = Pubkey::find_program_address(&[b"counter", source.as_ref()], &program_id).0;
+        assert_eq!(metas[0], AccountMeta::new_readonly(fixed, false));
+        assert_eq!(metas[1], AccountMeta::new(counter, false));
+        assert_eq!(metas[2].pubkey, program_id);
+        assert_eq!(metas[3].pubkey, hook.validation_account);
+
+        assert!(parse_extra_account_meta_list(&data[..12]).is_err());
+    }
+}
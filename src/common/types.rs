@@ -1,6 +1,21 @@
+use crate::common::cluster::Cluster;
+use crate::common::pricing::PricingConfig;
+use crate::common::program_probe::ProgramProbeConfig;
+use crate::common::rpc_retry::RpcRetryPolicy;
 use crate::swqos::SwqosConfig;
+use crate::swqos::fanout::SwqosFanout;
+use crate::trading::common::InstructionLayout;
+use crate::trading::core::transaction_pool::PerformanceConfig;
+use crate::trading::pool_guard::PoolStateCheckConfig;
+use crate::trading::rate_limit::WalletRateLimitConfig;
+use crate::trading::sandwich_guard::SandwichGuardConfig;
+use crate::trading::sol_reserve::SolReserveConfig;
+use crate::trading::submission_queue::SubmissionQueueConfig;
 use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Infrastructure-only configuration (wallet-independent)
 /// Can be shared across multiple wallets using the same RPC/SWQOS setup
@@ -9,6 +24,7 @@ pub struct InfrastructureConfig {
     pub rpc_url: String,
     pub swqos_configs: Vec<SwqosConfig>,
     pub commitment: CommitmentConfig,
+    pub rpc_retry: RpcRetryPolicy,
 }
 
 impl InfrastructureConfig {
@@ -17,7 +33,12 @@ impl InfrastructureConfig {
         swqos_configs: Vec<SwqosConfig>,
         commitment: CommitmentConfig,
     ) -> Self {
-        Self { rpc_url, swqos_configs, commitment }
+        Self {
+            rpc_url,
+            swqos_configs,
+            commitment,
+            rpc_retry: RpcRetryPolicy::default(),
+        }
     }
 
     /// Create from TradeConfig (extract infrastructure-only settings)
@@ -26,9 +47,16 @@ impl InfrastructureConfig {
             rpc_url: config.rpc_url.clone(),
             swqos_configs: config.swqos_configs.clone(),
             commitment: config.commitment,
+            rpc_retry: config.rpc_retry.clone(),
         }
     }
 
+    /// 设置 RPC 重试策略
+    pub fn with_rpc_retry_policy(mut self, rpc_retry: RpcRetryPolicy) -> Self {
+        self.rpc_retry = rpc_retry;
+        self
+    }
+
     /// Generate a cache key for this infrastructure configuration
     pub fn cache_key(&self) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -45,6 +73,7 @@ impl Hash for InfrastructureConfig {
         self.swqos_configs.hash(state);
         // Hash commitment level as string since CommitmentConfig doesn't impl Hash
         format!("{:?}", self.commitment).hash(state);
+        self.rpc_retry.hash(state);
     }
 }
 
@@ -53,6 +82,7 @@ impl PartialEq for InfrastructureConfig {
         self.rpc_url == other.rpc_url
             && self.swqos_configs == other.swqos_configs
             && self.commitment == other.commitment
+            && self.rpc_retry == other.rpc_retry
     }
 }
 
@@ -89,6 +119,22 @@ pub enum CallbackExecutionMode {
     Sync,
 }
 
+/// 发送前模拟策略
+///
+/// 在向 SWQOS 广播之前，先通过 RPC 的 `simulateTransaction` 模拟一次交易，
+/// 避免为明显会失败的交易（滑点超限、余额不足、池已迁移等）支付小费。
+/// 模拟会额外增加一次 RPC 往返延迟；模拟请求本身失败（网络错误等）时只记录日志并继续发送。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PreflightPolicy {
+    /// 不模拟，直接发送（默认）
+    #[default]
+    Skip,
+    /// 模拟失败时中止发送并返回错误
+    SimulateAndAbortOnError,
+    /// 模拟失败时仅告警，仍然发送
+    SimulateAndWarn,
+}
+
 #[derive(Debug, Clone)]
 pub struct TradeConfig {
     pub rpc_url: String,
@@ -190,6 +236,48 @@ pub struct TradeConfig {
     /// 3. **账户不需要存在**: `jitodontfront` 账户只需是有效的 Pubkey，不需要在链上存在
     /// 4. **标记为只读**: 优化执行速度，不消耗额外的 Compute Unit
     pub enable_jito_sandwich_protection: bool,
+    /// 发送前模拟策略（默认：Skip）
+    pub preflight: PreflightPolicy,
+    /// 协议参数含 `Pubkey::default()` 占位时是否通过 RPC 自动补全（默认：false）
+    pub auto_fill_params: bool,
+    /// 卖出前是否检查持仓账户冻结 / mint 永久委托（默认：false）
+    pub check_sell_accounts: bool,
+    /// 交易指令排列策略（默认：小费 → compute budget → 业务指令）
+    pub instruction_layout: InstructionLayout,
+    /// 专用签名线程数，0 表示在发送任务上直接签名（默认：0）
+    pub signing_threads: usize,
+    /// USD 计价配置（锚定池、稳定币、WSOL 价格缓存时长）
+    pub pricing: PricingConfig,
+    /// RPC 读取的重试策略（默认：最多 3 次，100ms 起指数退避，带抖动）
+    pub rpc_retry: RpcRetryPolicy,
+    /// 目标集群（默认：Mainnet），非主网时自动映射协议程序 ID
+    pub cluster: Cluster,
+    /// `transfer_sol_to` / `transfer_token` 的收款地址白名单（默认：None，不限制）
+    pub transfer_allow_list: Option<Vec<Pubkey>>,
+    /// 交易构建器池 / 序列化缓冲池的容量与预热配置（默认：1000/100 个构建器，10_000 个 256KB 缓冲区）
+    pub performance: PerformanceConfig,
+    /// 启动时探测已部署协议程序是否升级（默认：None，不探测）
+    pub program_probe: Option<ProgramProbeConfig>,
+    /// 经公开中继发送前的三明治风险检查（默认：None，不检查）
+    pub sandwich_guard: Option<SandwichGuardConfig>,
+    /// 钱包交易频率限制（默认：None，不限制）
+    pub wallet_rate_limit: Option<WalletRateLimitConfig>,
+    /// 付款钱包始终保留的 SOL，买入不会动用（默认：None，不保留）
+    pub sol_reserve: Option<SolReserveConfig>,
+    /// 构建交易前检查池子状态是否可交易、储备是否一致（默认：None，不检查）
+    pub pool_state_check: Option<PoolStateCheckConfig>,
+    /// 等待确认的超时时间，超时返回 `TradeError::CONFIRMATION_TIMED_OUT`（默认：15 秒）
+    pub confirm_timeout: Duration,
+    /// 等待确认时 `getSignatureStatuses` 的轮询间隔（默认：1 秒）
+    pub confirm_poll_interval: Duration,
+    /// 按紧急程度排队的提交队列（默认：None，不排队）
+    pub submission_queue: Option<SubmissionQueueConfig>,
+    /// 各 SWQOS provider 接收交易的顺序与间隔（默认：None，同时发送给所有 provider）
+    pub swqos_fanout: Option<SwqosFanout>,
+    /// SWQOS provider 配置文件（JSON），启动时加载并在修改或收到 SIGHUP 后热加载（默认：None）
+    ///
+    /// 设置后文件中的 provider 取代 `swqos_configs`，格式见 `swqos::registry`。
+    pub swqos_config_file: Option<PathBuf>,
 }
 
 impl TradeConfig {
@@ -202,6 +290,28 @@ impl TradeConfig {
         println!("🔧 TradeConfig use_seed_optimize default value: false");
         println!("🔧 TradeConfig callback_execution_mode default value: Async");
         println!("🔧 TradeConfig enable_jito_sandwich_protection default value: false");
+        println!("🔧 TradeConfig preflight default value: Skip");
+        println!("🔧 TradeConfig auto_fill_params default value: false");
+        println!("🔧 TradeConfig check_sell_accounts default value: false");
+        println!("🔧 TradeConfig instruction_layout default value: tip before compute budget");
+        println!("🔧 TradeConfig signing_threads default value: 0");
+        println!("🔧 TradeConfig pricing default value: Raydium CLMM WSOL-USDT anchor, no cache");
+        println!("🔧 TradeConfig rpc_retry default value: 3 attempts, 100ms-2s backoff, jitter");
+        println!("🔧 TradeConfig cluster default value: Mainnet");
+        println!("🔧 TradeConfig transfer_allow_list default value: None");
+        println!(
+            "🔧 TradeConfig performance default value: 1000 builders (100 pre-warmed), 10000 x 256KB buffers"
+        );
+        println!("🔧 TradeConfig program_probe default value: None");
+        println!("🔧 TradeConfig sandwich_guard default value: None");
+        println!("🔧 TradeConfig wallet_rate_limit default value: None");
+        println!("🔧 TradeConfig sol_reserve default value: None");
+        println!("🔧 TradeConfig pool_state_check default value: None");
+        println!("🔧 TradeConfig confirm_timeout default value: 15s");
+        println!("🔧 TradeConfig confirm_poll_interval default value: 1s");
+        println!("🔧 TradeConfig submission_queue default value: None");
+        println!("🔧 TradeConfig swqos_fanout default value: None");
+        println!("🔧 TradeConfig swqos_config_file default value: None");
         Self {
             rpc_url,
             swqos_configs,
@@ -210,6 +320,26 @@ impl TradeConfig {
             use_seed_optimize: false,         // 默认：禁用seed优化
             callback_execution_mode: CallbackExecutionMode::Async, // 默认：异步模式
             enable_jito_sandwich_protection: false, // 默认：禁用三明治防护（大多数场景不需要）
+            preflight: PreflightPolicy::Skip, // 默认：不模拟
+            auto_fill_params: false,          // 默认：不补全，缺失字段直接报错
+            check_sell_accounts: false,       // 默认：不检查，节省 RPC 往返
+            instruction_layout: InstructionLayout::default(),
+            signing_threads: 0, // 默认：不启用签名线程池
+            pricing: PricingConfig::default(),
+            rpc_retry: RpcRetryPolicy::default(),
+            cluster: Cluster::Mainnet,
+            transfer_allow_list: None,
+            performance: PerformanceConfig::default(),
+            program_probe: None,
+            sandwich_guard: None,
+            wallet_rate_limit: None,
+            sol_reserve: None,
+            pool_state_check: None,
+            confirm_timeout: Duration::from_secs(15),
+            confirm_poll_interval: Duration::from_secs(1),
+            submission_queue: None,
+            swqos_fanout: None,
+            swqos_config_file: None,
         }
     }
 
@@ -270,6 +400,166 @@ impl TradeConfig {
         self.enable_jito_sandwich_protection = enabled;
         self
     }
+
+    /// 设置发送前模拟策略
+    ///
+    /// # 参数
+    /// * `preflight` - Skip / SimulateAndAbortOnError / SimulateAndWarn
+    pub fn with_preflight(mut self, preflight: PreflightPolicy) -> Self {
+        self.preflight = preflight;
+        self
+    }
+
+    /// 设置是否自动补全协议参数
+    ///
+    /// 开启后，`PumpFunParams` / `RaydiumClmmParams` 等参数中校验不通过的占位字段
+    /// 会在交易执行前通过 RPC 从链上补全（会增加一次或多次 RPC 往返）。
+    pub fn with_auto_fill_params(mut self, enabled: bool) -> Self {
+        self.auto_fill_params = enabled;
+        self
+    }
+
+    /// 设置卖出前是否检查账户状态
+    ///
+    /// 开启后，每次卖出前读取 mint 与持仓账户（两次 RPC 往返）：持仓账户被冻结或 mint
+    /// 存在他人的永久委托时，直接返回 `TradeError::ACCOUNT_FROZEN`，不发送交易。
+    pub fn with_check_sell_accounts(mut self, enabled: bool) -> Self {
+        self.check_sell_accounts = enabled;
+        self
+    }
+
+    /// 设置交易指令排列策略
+    ///
+    /// 例如部分中继要求小费转账位于交易最后：
+    /// `InstructionLayout::new().with_tip(TipPlacement::Last)`
+    pub fn with_instruction_layout(mut self, layout: InstructionLayout) -> Self {
+        self.instruction_layout = layout;
+        self
+    }
+
+    /// 设置专用签名线程数
+    ///
+    /// 多个 SWQOS 并发发送时，各变体的签名在绑核的专用线程上并行执行，
+    /// 不占用 tokio 工作线程。签名池为进程级共享，以最后创建的 `TradingClient` 配置为准。
+    pub fn with_signing_threads(mut self, threads: usize) -> Self {
+        self.signing_threads = threads;
+        self
+    }
+
+    /// 设置 USD 计价配置
+    ///
+    /// 所有 `get_token_price_in_usd*` 在未显式传入锚定池时读取此配置。
+    /// 配置为进程级共享，以最后创建的 `TradingClient` 配置为准。
+    pub fn with_pricing_config(mut self, pricing: PricingConfig) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// 设置 RPC 重试策略
+    ///
+    /// 作用于 `TradingClient` 创建的 RPC 客户端：429、5xx、超时与连接错误按指数退避重试，
+    /// `sendTransaction` 默认不重试。传入 [`RpcRetryPolicy::disabled`] 可关闭重试。
+    pub fn with_rpc_retry_policy(mut self, rpc_retry: RpcRetryPolicy) -> Self {
+        self.rpc_retry = rpc_retry;
+        self
+    }
+
+    /// 设置目标集群
+    ///
+    /// 非主网时，各协议指令中的程序 ID 在执行前替换为该集群的部署地址，
+    /// 协议在该集群没有部署时交易直接报错；SWQOS 小费只在主网附加。
+    /// 若 USD 计价仍使用内置的主网锚定池，则一并替换为该集群的内置锚定池（非主网为空）。
+    /// 集群为进程级共享，以最后创建的 `TradingClient` 配置为准。
+    pub fn with_cluster(mut self, cluster: Cluster) -> Self {
+        if self.pricing.anchor_pools == self.cluster.default_anchor_pools() {
+            self.pricing.anchor_pools = cluster.default_anchor_pools();
+        }
+        self.cluster = cluster;
+        self
+    }
+
+    /// 设置转账收款地址白名单
+    ///
+    /// 设置后 `transfer_sol_to` / `transfer_token` 只允许转给名单内的地址（代币转账校验收款钱包，
+    /// 而非其 ATA）；传入空列表则禁止所有转账。
+    pub fn with_transfer_allow_list(mut self, allow_list: Vec<Pubkey>) -> Self {
+        self.transfer_allow_list = Some(allow_list);
+        self
+    }
+
+    /// 设置对象池容量与预热数量
+    ///
+    /// 池为进程级共享，以最后创建的 `TradingClient` 配置为准；配置变化时会重建池。
+    /// 高频部署可调大容量避免运行时分配，低频部署可用 `PerformanceConfig::low_memory()` 节省内存。
+    pub fn with_performance(mut self, performance: PerformanceConfig) -> Self {
+        self.performance = performance;
+        self
+    }
+
+    /// 启动时探测协议程序是否在基线之后升级过
+    ///
+    /// `ProbeMode::Error` 下检测到变化会使 `TradingClient::new` 启动失败。
+    pub fn with_program_probe(mut self, program_probe: ProgramProbeConfig) -> Self {
+        self.program_probe = Some(program_probe);
+        self
+    }
+
+    /// 发送前估算抢跑者可榨取的价值，超过阈值时警告或拒绝
+    ///
+    /// 只检查能按储备估算的协议；所有 SWQOS 均为 Jito 且启用三明治防护时跳过。
+    pub fn with_sandwich_guard(mut self, sandwich_guard: SandwichGuardConfig) -> Self {
+        self.sandwich_guard = Some(sandwich_guard);
+        self
+    }
+
+    /// 限制钱包的交易频率，避免触发中继 / 验证者的垃圾交易过滤
+    pub fn with_wallet_rate_limit(mut self, wallet_rate_limit: WalletRateLimitConfig) -> Self {
+        self.wallet_rate_limit = Some(wallet_rate_limit);
+        self
+    }
+
+    /// 始终保留一部分 SOL 用于支付卖出手续费，避免买入把钱包耗尽后无法退出持仓
+    pub fn with_sol_reserve(mut self, sol_reserve: SolReserveConfig) -> Self {
+        self.sol_reserve = Some(sol_reserve);
+        self
+    }
+
+    /// 构建交易前读取池子账户与 vault，池子不可交易、储备为空或状态过旧时直接返回
+    /// `TradeError::POOL_STATE_INVALID`，不发送注定失败的交易
+    pub fn with_pool_state_check(mut self, pool_state_check: PoolStateCheckConfig) -> Self {
+        self.pool_state_check = Some(pool_state_check);
+        self
+    }
+
+    /// 等待确认的超时时间；超时表示尚未观察到上链，而不是交易失败
+    pub fn with_confirm_timeout(mut self, confirm_timeout: Duration) -> Self {
+        self.confirm_timeout = confirm_timeout;
+        self
+    }
+
+    /// 等待确认时的轮询间隔
+    pub fn with_confirm_poll_interval(mut self, confirm_poll_interval: Duration) -> Self {
+        self.confirm_poll_interval = confirm_poll_interval;
+        self
+    }
+
+    /// 多笔交易同时触发时按紧急程度（Snipe > Exit > Normal）排队构建与提交
+    pub fn with_submission_queue(mut self, submission_queue: SubmissionQueueConfig) -> Self {
+        self.submission_queue = Some(submission_queue);
+        self
+    }
+
+    /// 按顺序分批发送给各 provider（例如先 Jito，20ms 后其余），避免无 nonce 的交易被不同中继重复打包
+    pub fn with_swqos_fanout(mut self, swqos_fanout: SwqosFanout) -> Self {
+        self.swqos_fanout = Some(swqos_fanout);
+        self
+    }
+
+    /// 从配置文件加载 SWQOS provider 并热加载：修改 token、区域或黑名单无需重启
+    pub fn with_swqos_config_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.swqos_config_file = Some(path.into());
+        self
+    }
 }
 
 pub type SolanaRpcClient = solana_client::nonblocking::rpc_client::RpcClient;
@@ -0,0 +1,319 @@
+//! 带单位的费用类型
+//!
+//! 费用参数中 CU 数量、CU 价格（micro-lamports）与小费（SOL 浮点数）都是裸数字，
+//! 位置参数写错顺序或把 lamports 当作 SOL 传入时编译器无法发现。这里的新类型只能显式构造，
+//! 不同单位之间不能混用：
+//!
+//! ```
+//! use sol_trade_sdk::common::{ComputeUnits, FeeSpec, GasFeeStrategy, Lamports, MicroLamports, Sol};
+//!
+//! let strategy = GasFeeStrategy::new();
+//! let buy = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+//! let sell = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Lamports(1_000_000));
+//! strategy.set_global_fees(buy, sell);
+//! ```
+//!
+//! 滑点与费率统一使用 [`Bps`]（基点，100 = 1%），避免基点与百分比混用。买入滑点可以超过 100%，
+//! 卖出方向按 100% 截断：
+//!
+//! ```
+//! use sol_trade_sdk::common::Bps;
+//!
+//! let slippage = Bps::new(300);
+//! assert_eq!(slippage, Bps::from_percent(3.0).unwrap());
+//! assert_eq!(slippage.apply_down(1_000_000), 970_000);
+//! assert_eq!(Bps::new(20_000).apply_down(1_000_000), 0);
+//! assert!(Bps::try_new(20_000).is_err());
+//! ```
+//!
+//! 链上账户中以其他分母表示的费率（如 Raydium CPMM / CLMM 的百万分比）保持原始数值，不使用 [`Bps`]。
+
+use crate::common::fee_ledger::{priority_fee_lamports, sol_to_lamports};
+use crate::common::gas_fee_strategy::GasFeeStrategyValue;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// lamports 金额
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Lamports(pub u64);
+
+/// SOL 金额（浮点数，仅用于与旧接口互转和展示）
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Sol(pub f64);
+
+/// 计算单元数量（cu_limit）
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct ComputeUnits(pub u32);
+
+/// 计算单元价格：micro-lamports / CU（cu_price）
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct MicroLamports(pub u64);
+
+impl Lamports {
+    pub const ZERO: Lamports = Lamports(0);
+
+    pub fn to_sol(self) -> Sol {
+        Sol(self.0 as f64 / LAMPORTS_PER_SOL as f64)
+    }
+}
+
+impl Sol {
+    /// 换算为 lamports（按十进制字符串换算，与交易构建时的小费换算一致）
+    pub fn to_lamports(self) -> Lamports {
+        Lamports(sol_to_lamports(self.0))
+    }
+}
+
+impl MicroLamports {
+    /// 该 CU 价格在 `cu_limit` 下的优先费（向上取整）
+    pub fn priority_fee(self, cu_limit: ComputeUnits) -> Lamports {
+        Lamports(priority_fee_lamports(cu_limit.0, self.0))
+    }
+}
+
+impl From<Sol> for Lamports {
+    fn from(sol: Sol) -> Self {
+        sol.to_lamports()
+    }
+}
+
+impl From<Lamports> for Sol {
+    fn from(lamports: Lamports) -> Self {
+        lamports.to_sol()
+    }
+}
+
+impl fmt::Display for Lamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lamports", self.0)
+    }
+}
+
+impl fmt::Display for Sol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} SOL", self.0)
+    }
+}
+
+impl fmt::Display for ComputeUnits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} CU", self.0)
+    }
+}
+
+impl fmt::Display for MicroLamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} micro-lamports/CU", self.0)
+    }
+}
+
+/// 基点：1 bp = 0.01%
+///
+/// 不限制上限：买入滑点允许超过 100%。只在 0..=100% 有意义的计算（卖出最小输出、`1 - bps`）
+/// 通过 [`Bps::capped`] 截断到 [`Bps::HUNDRED_PERCENT`]；需要拒绝越界输入时使用 [`Bps::try_new`]。
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "u64", into = "u64")]
+pub struct Bps(u64);
+
+impl Bps {
+    pub const ZERO: Bps = Bps(0);
+    /// 100%
+    pub const HUNDRED_PERCENT: Bps = Bps(10_000);
+    pub const DENOMINATOR: u64 = 10_000;
+
+    pub const fn new(bps: u64) -> Self {
+        Self(bps)
+    }
+
+    /// 构造不超过 100% 的基点（费率、卖出滑点等），超出时返回错误
+    pub fn try_new(bps: u64) -> Result<Self> {
+        if bps > Self::DENOMINATOR {
+            return Err(anyhow!("Basis points {} out of range 0..={}", bps, Self::DENOMINATOR));
+        }
+        Ok(Self(bps))
+    }
+
+    /// 超出 100% 时截断为 [`Bps::HUNDRED_PERCENT`]
+    pub const fn saturating(bps: u64) -> Self {
+        Self(bps).capped()
+    }
+
+    /// 截断到 100%，用于 `1 - bps` 类的计算
+    pub const fn capped(self) -> Self {
+        if self.0 > Self::DENOMINATOR { Self::HUNDRED_PERCENT } else { self }
+    }
+
+    /// 由百分比构造（`1.5` = 1.5% = 150 bps），四舍五入到整数基点
+    pub fn from_percent(percent: f64) -> Result<Self> {
+        if !percent.is_finite() || percent < 0.0 {
+            return Err(anyhow!("Invalid percent: {}", percent));
+        }
+        Ok(Self((percent * 100.0).round() as u64))
+    }
+
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub const fn as_u128(self) -> u128 {
+        self.0 as u128
+    }
+
+    /// 百分比（150 bps = 1.5）
+    pub fn to_percent(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// 比例（150 bps = 0.015）
+    pub fn to_fraction(self) -> f64 {
+        self.0 as f64 / Self::DENOMINATOR as f64
+    }
+
+    /// `amount * (1 + bps)`，向下取整，溢出时饱和
+    pub const fn apply_up(self, amount: u64) -> u64 {
+        let factor = Self::DENOMINATOR as u128 + self.0 as u128;
+        saturate((amount as u128).saturating_mul(factor) / Self::DENOMINATOR as u128)
+    }
+
+    /// `amount * (1 - bps)`，向下取整；超过 100% 按 100% 计算
+    pub const fn apply_down(self, amount: u64) -> u64 {
+        let factor = Self::DENOMINATOR - self.capped().0;
+        (amount as u128 * factor as u128 / Self::DENOMINATOR as u128) as u64
+    }
+
+    /// `amount * bps`，向下取整，溢出时饱和
+    pub const fn of(self, amount: u64) -> u64 {
+        saturate((amount as u128).saturating_mul(self.0 as u128) / Self::DENOMINATOR as u128)
+    }
+}
+
+const fn saturate(value: u128) -> u64 {
+    if value > u64::MAX as u128 { u64::MAX } else { value as u64 }
+}
+
+impl From<u64> for Bps {
+    fn from(bps: u64) -> Self {
+        Self(bps)
+    }
+}
+
+impl From<Bps> for u64 {
+    fn from(bps: Bps) -> Self {
+        bps.as_u64()
+    }
+}
+
+impl fmt::Display for Bps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bps", self.0)
+    }
+}
+
+/// 单个方向（买入或卖出）的费用设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeeSpec {
+    pub cu_limit: ComputeUnits,
+    pub cu_price: MicroLamports,
+    pub tip: Lamports,
+}
+
+impl FeeSpec {
+    /// `tip` 可以传 [`Lamports`] 或 [`Sol`]
+    pub fn new(cu_limit: ComputeUnits, cu_price: MicroLamports, tip: impl Into<Lamports>) -> Self {
+        Self { cu_limit, cu_price, tip: tip.into() }
+    }
+
+    /// 相同 CU 设置、不带小费
+    pub fn without_tip(self) -> Self {
+        Self { tip: Lamports::ZERO, ..self }
+    }
+
+    /// 优先费（不含小费）
+    pub fn priority_fee(&self) -> Lamports {
+        self.cu_price.priority_fee(self.cu_limit)
+    }
+
+    /// 优先费 + 小费
+    pub fn total_fee(&self) -> Lamports {
+        Lamports(self.priority_fee().0 + self.tip.0)
+    }
+}
+
+impl From<FeeSpec> for GasFeeStrategyValue {
+    fn from(spec: FeeSpec) -> Self {
+        Self {
+            cu_limit: spec.cu_limit.0,
+            cu_price: spec.cu_price.0,
+            tip: spec.tip.to_sol().0,
+        }
+    }
+}
+
+impl From<GasFeeStrategyValue> for FeeSpec {
+    fn from(value: GasFeeStrategyValue) -> Self {
+        Self {
+            cu_limit: ComputeUnits(value.cu_limit),
+            cu_price: MicroLamports(value.cu_price),
+            tip: Sol(value.tip).to_lamports(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_spec_round_trip() {
+        assert_eq!(Sol(0.001).to_lamports(), Lamports(1_000_000));
+        assert_eq!(Lamports(123_456_789).to_sol().to_lamports(), Lamports(123_456_789));
+        let spec = FeeSpec::new(ComputeUnits(200_000), MicroLamports(2_500_000), Sol(0.005));
+        assert_eq!(spec.priority_fee(), Lamports(500_000));
+        assert_eq!(spec.total_fee(), Lamports(5_500_000));
+        let value = GasFeeStrategyValue::from(spec);
+        assert_eq!(value.tip, 0.005);
+        assert_eq!(FeeSpec::from(value), spec);
+        assert_eq!(spec.without_tip().tip, Lamports::ZERO);
+    }
+
+    #[test]
+    fn test_bps_range_and_conversions() {
+        assert_eq!(Bps::try_new(10_000).unwrap(), Bps::HUNDRED_PERCENT);
+        assert!(Bps::try_new(10_001).is_err());
+        assert_eq!(Bps::saturating(50_000), Bps::HUNDRED_PERCENT);
+        assert_eq!(Bps::from_percent(1.5).unwrap(), Bps::new(150));
+        assert!(Bps::from_percent(-1.0).is_err());
+        assert_eq!(Bps::from_percent(150.0).unwrap(), Bps::new(15_000));
+        assert_eq!(Bps::new(250).to_percent(), 2.5);
+        assert_eq!(Bps::new(100).apply_up(1_000), 1_010);
+        assert_eq!(Bps::HUNDRED_PERCENT.apply_up(u64::MAX / 2), u64::MAX - 1);
+        assert_eq!(Bps::new(100).apply_down(1_000), 990);
+        assert_eq!(Bps::new(25).of(1_000_000), 2_500);
+
+        // 买入滑点可以超过 100%，卖出方向截断到 100%
+        assert_eq!(Bps::new(20_000).apply_up(1_000), 3_000);
+        assert_eq!(Bps::new(20_000).apply_down(1_000), 0);
+        assert_eq!(Bps::new(20_000).capped(), Bps::HUNDRED_PERCENT);
+        assert_eq!(Bps::new(u64::MAX).apply_up(2), u64::MAX);
+
+        let json = serde_json::to_string(&Bps::new(300)).unwrap();
+        assert_eq!(json, "300");
+        assert_eq!(serde_json::from_str::<Bps>("300").unwrap(), Bps::new(300));
+        assert_eq!(serde_json::from_str::<Bps>("20000").unwrap(), Bps::new(20_000));
+    }
+}
@@ -1,6 +1,7 @@
 pub mod accounts;
 pub mod decimals;
 pub mod platforms;
+pub mod registry;
 pub mod swqos;
 pub mod tokens;
 pub mod trade_consts;
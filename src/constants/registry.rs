@@ -0,0 +1,186 @@
+//! 常量注册表：运行时可覆盖的程序 ID、内置账户（手续费接收账户等）与 SWQOS 小费账户
+//!
+//! 指令构建仍使用 `constants` / `instruction::utils::*::accounts` 中的硬编码地址，
+//! 执行器在发送前通过 [`Registry::remap_instructions`] 统一替换（在集群映射之后生效），
+//! 因此 fork 部署、devnet 或程序升级后的新地址无需修改构建器即可使用。
+//!
+//! 注册表为进程级共享，通过 [`set_registry`] 整体替换；默认为空（全部使用内置地址）。
+
+use crate::common::cluster::{Cluster, current_cluster};
+use crate::swqos::SwqosType;
+use crate::trading::factory::DexType;
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 运行时常量覆盖
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    /// 协议程序 ID 覆盖（优先于集群映射）
+    programs: HashMap<DexType, Pubkey>,
+    /// 内置账户 → 替换账户（手续费接收账户、全局配置等）
+    accounts: HashMap<Pubkey, Pubkey>,
+    /// SWQOS 小费账户覆盖
+    tip_accounts: HashMap<SwqosType, Vec<Pubkey>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 覆盖协议的程序 ID
+    pub fn with_program(mut self, dex_type: DexType, program_id: Pubkey) -> Self {
+        self.programs.insert(dex_type, program_id);
+        self
+    }
+
+    /// 把指令中出现的内置账户 `builtin` 替换为 `replacement`
+    ///
+    /// 例如 `with_account(pumpfun::global_constants::FEE_RECIPIENT, new_recipient)`。
+    pub fn with_account(mut self, builtin: Pubkey, replacement: Pubkey) -> Self {
+        self.accounts.insert(builtin, replacement);
+        self
+    }
+
+    /// 覆盖 SWQOS 的小费账户列表（空列表等同于不覆盖）
+    pub fn with_tip_accounts(mut self, swqos_type: SwqosType, accounts: Vec<Pubkey>) -> Self {
+        if accounts.is_empty() {
+            self.tip_accounts.remove(&swqos_type);
+        } else {
+            self.tip_accounts.insert(swqos_type, accounts);
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty() && self.accounts.is_empty() && self.tip_accounts.is_empty()
+    }
+
+    /// 协议在集群上生效的程序 ID：覆盖值优先，否则为集群映射
+    pub fn program_id(&self, cluster: Cluster, dex_type: &DexType) -> Result<Pubkey> {
+        match self.programs.get(dex_type) {
+            Some(program_id) => Ok(*program_id),
+            None => cluster.program_id(dex_type),
+        }
+    }
+
+    /// 内置账户的生效地址
+    pub fn account(&self, builtin: &Pubkey) -> Pubkey {
+        self.accounts.get(builtin).copied().unwrap_or(*builtin)
+    }
+
+    /// SWQOS 的小费账户（未覆盖时为内置列表）
+    pub fn tip_accounts(&self, swqos_type: SwqosType) -> &[Pubkey] {
+        self.tip_accounts
+            .get(&swqos_type)
+            .map(Vec::as_slice)
+            .unwrap_or(swqos_type.tip_accounts())
+    }
+
+    /// 是否覆盖了该 SWQOS 的小费账户
+    pub fn overrides_tip_accounts(&self, swqos_type: SwqosType) -> bool {
+        self.tip_accounts.contains_key(&swqos_type)
+    }
+
+    /// 把指令中的主网程序 ID 与内置账户替换为生效地址
+    pub fn remap_instructions(
+        &self,
+        cluster: Cluster,
+        dex_type: &DexType,
+        instructions: &mut [Instruction],
+    ) -> Result<()> {
+        match self.programs.get(dex_type) {
+            Some(target) => {
+                let mainnet = Cluster::mainnet_program_id(dex_type);
+                if *target != mainnet {
+                    for instruction in instructions.iter_mut() {
+                        if instruction.program_id == mainnet {
+                            instruction.program_id = *target;
+                        }
+                        for meta in
+                            instruction.accounts.iter_mut().filter(|meta| meta.pubkey == mainnet)
+                        {
+                            meta.pubkey = *target;
+                        }
+                    }
+                }
+            },
+            None => cluster.remap_instructions(dex_type, instructions)?,
+        }
+        if !self.accounts.is_empty() {
+            for instruction in instructions.iter_mut() {
+                instruction.program_id = self.account(&instruction.program_id);
+                for meta in instruction.accounts.iter_mut() {
+                    meta.pubkey = self.account(&meta.pubkey);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+static REGISTRY: Lazy<ArcSwap<Registry>> = Lazy::new(|| ArcSwap::from_pointee(Registry::new()));
+
+/// 当前生效的注册表
+pub fn registry() -> Arc<Registry> {
+    REGISTRY.load_full()
+}
+
+/// 替换注册表
+pub fn set_registry(registry: Registry) {
+    REGISTRY.store(Arc::new(registry));
+}
+
+/// 按当前集群与注册表替换指令中的程序 ID 与内置账户
+pub fn remap_instructions(dex_type: &DexType, instructions: &mut [Instruction]) -> Result<()> {
+    REGISTRY.load().remap_instructions(current_cluster(), dex_type, instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::utils::{pumpfun, raydium_cpmm};
+    use solana_sdk::instruction::AccountMeta;
+
+    #[test]
+    fn test_registry_overrides() {
+        let fork = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let registry = Registry::new()
+            .with_program(DexType::PumpFun, fork)
+            .with_account(pumpfun::global_constants::FEE_RECIPIENT, recipient);
+        let mainnet = pumpfun::accounts::PUMPFUN;
+        let mut instructions = vec![Instruction::new_with_bytes(
+            mainnet,
+            &[],
+            vec![
+                AccountMeta::new(pumpfun::global_constants::FEE_RECIPIENT, false),
+                AccountMeta::new_readonly(mainnet, false),
+            ],
+        )];
+        // 程序覆盖优先于集群映射：PumpFun 在 testnet 没有部署，但指定了 fork 地址
+        registry
+            .remap_instructions(Cluster::Testnet, &DexType::PumpFun, &mut instructions)
+            .unwrap();
+        assert_eq!(instructions[0].program_id, fork);
+        assert_eq!(instructions[0].accounts[0].pubkey, recipient);
+        assert_eq!(instructions[0].accounts[1].pubkey, fork);
+        assert_eq!(registry.program_id(Cluster::Testnet, &DexType::PumpFun).unwrap(), fork);
+        assert_eq!(
+            registry.program_id(Cluster::Mainnet, &DexType::RaydiumCpmm).unwrap(),
+            raydium_cpmm::accounts::RAYDIUM_CPMM
+        );
+
+        let tip = Pubkey::new_unique();
+        let registry = registry.with_tip_accounts(SwqosType::Jito, vec![tip]);
+        assert_eq!(registry.tip_accounts(SwqosType::Jito), &[tip]);
+        assert_eq!(
+            registry.tip_accounts(SwqosType::NextBlock),
+            SwqosType::NextBlock.tip_accounts()
+        );
+    }
+}
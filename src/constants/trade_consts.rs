@@ -1,7 +1,11 @@
-pub const DEFAULT_SLIPPAGE: u64 = 1000; // 10%
+use crate::common::Bps;
+
+pub const DEFAULT_SLIPPAGE: Bps = Bps::new(1000); // 10%
 pub const DEFAULT_TIP_UNIT_LIMIT: u32 = 150000;
 pub const DEFAULT_TIP_UNIT_PRICE: u64 = 500000;
 pub const DEFAULT_BUY_TIP_FEE: f64 = 0.0006;
 pub const DEFAULT_SELL_TIP_FEE: f64 = 0.0001;
 pub const DEFAULT_RPC_UNIT_LIMIT: u32 = 150000;
 pub const DEFAULT_RPC_UNIT_PRICE: u64 = 500000;
+/// Solana 单笔交易序列化后的最大字节数（PACKET_DATA_SIZE）
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
@@ -1,3 +1,4 @@
+use crate::trading::lifecycle::record_quote;
 use crate::{
     constants::trade_consts::DEFAULT_SLIPPAGE,
     instruction::utils::bonk::{
@@ -7,7 +8,7 @@ use crate::{
     trading::{
         common::utils::get_token_balance,
         core::{
-            params::{BonkParams, SwapParams},
+            params::{BonkParams, DexParamEnum, SwapParams},
             traits::InstructionBuilder,
         },
     },
@@ -82,9 +83,10 @@ impl InstructionBuilder for BonkInstructionBuilder {
                 protocol_params.virtual_quote,
                 protocol_params.real_base,
                 protocol_params.real_quote,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
             ),
         };
+        record_quote(None, minimum_amount_out);
 
         let user_base_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -243,9 +245,10 @@ impl InstructionBuilder for BonkInstructionBuilder {
                 protocol_params.virtual_quote,
                 protocol_params.real_base,
                 protocol_params.real_quote,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE) as u128,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
             ),
         };
+        record_quote(None, minimum_amount_out);
 
         let user_base_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -334,4 +337,55 @@ impl InstructionBuilder for BonkInstructionBuilder {
 
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<BonkParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for Bonk"))?;
+        let usd1_pool = protocol_params.global_config == accounts::USD1_GLOBAL_CONFIG;
+        let quote_mint = if usd1_pool {
+            crate::constants::USD1_TOKEN_ACCOUNT
+        } else {
+            crate::constants::WSOL_TOKEN_ACCOUNT
+        };
+        let global_config =
+            if usd1_pool { accounts::USD1_GLOBAL_CONFIG } else { accounts::GLOBAL_CONFIG };
+        let pool_state = if protocol_params.pool_state == Pubkey::default() {
+            get_pool_pda(mint, &quote_mint).ok_or_else(|| anyhow!("Bonk pool PDA not found"))?
+        } else {
+            protocol_params.pool_state
+        };
+        let base_vault = if protocol_params.base_vault == Pubkey::default() {
+            get_vault_pda(&pool_state, mint).ok_or_else(|| anyhow!("Bonk vault PDA not found"))?
+        } else {
+            protocol_params.base_vault
+        };
+        let quote_vault = if protocol_params.quote_vault == Pubkey::default() {
+            get_vault_pda(&pool_state, &quote_mint)
+                .ok_or_else(|| anyhow!("Bonk vault PDA not found"))?
+        } else {
+            protocol_params.quote_vault
+        };
+        Ok(vec![
+            accounts::AUTHORITY,
+            global_config,
+            protocol_params.platform_config,
+            pool_state,
+            base_vault,
+            quote_vault,
+            *mint,
+            quote_mint,
+            protocol_params.mint_token_program,
+            crate::constants::TOKEN_PROGRAM,
+            accounts::EVENT_AUTHORITY,
+            crate::constants::SYSTEM_PROGRAM,
+            protocol_params.platform_associated_account,
+            protocol_params.creator_associated_account,
+        ])
+    }
 }
@@ -1,13 +1,15 @@
+use crate::trading::lifecycle::record_quote;
 use crate::{
     instruction::utils::meteora_damm_v2::{SWAP_DISCRIMINATOR, accounts, get_event_authority_pda},
     trading::core::{
-        params::{MeteoraDammV2Params, SwapParams},
+        params::{DexParamEnum, MeteoraDammV2Params, SwapParams},
         traits::InstructionBuilder,
     },
 };
 use anyhow::{Result, anyhow};
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
     signer::Signer,
 };
 
@@ -52,6 +54,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             Some(fixed) => fixed,
             None => return Err(anyhow!("fixed_output_amount must be set for MeteoraDammV2 swap")),
         };
+        record_quote(None, minimum_amount_out);
 
         let input_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -167,6 +170,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             Some(fixed) => fixed,
             None => return Err(anyhow!("fixed_output_amount must be set for MeteoraDammV2 swap")),
         };
+        record_quote(None, minimum_amount_out);
 
         let input_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -248,4 +252,26 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
 
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        _mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<MeteoraDammV2Params>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for MeteoraDammV2"))?;
+        Ok(vec![
+            accounts::AUTHORITY,
+            protocol_params.pool,
+            protocol_params.token_a_vault,
+            protocol_params.token_b_vault,
+            protocol_params.token_a_mint,
+            protocol_params.token_b_mint,
+            protocol_params.token_a_program,
+            protocol_params.token_b_program,
+            get_event_authority_pda(),
+        ])
+    }
 }
@@ -4,5 +4,6 @@ pub mod pumpfun;
 pub mod pumpswap;
 pub mod raydium_amm_v4;
 pub mod raydium_clmm;
+pub mod raydium_clmm_position;
 pub mod raydium_cpmm;
 pub mod utils;
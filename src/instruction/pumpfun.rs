@@ -1,8 +1,10 @@
+use crate::trading::lifecycle::record_quote;
 use crate::{
     common::spl_token::close_account,
     constants::{TOKEN_PROGRAM_2022, trade_consts::DEFAULT_SLIPPAGE},
+    perf::arena,
     trading::core::{
-        params::{PumpFunParams, SwapParams},
+        params::{DexParamEnum, PumpFunParams, SwapParams},
         traits::InstructionBuilder,
     },
 };
@@ -66,6 +68,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             input_amount,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
         );
+        record_quote(Some(buy_token_amount), buy_token_amount);
 
         let bonding_curve_addr = if bonding_curve.account == Pubkey::default() {
             get_bonding_curve_pda(&params.output_mint)
@@ -74,10 +77,10 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             bonding_curve.account
         };
 
-        // Determine token program based on mayhem mode
-        let is_mayhem_mode = bonding_curve.is_mayhem_mode;
-        let token_program = protocol_params.token_program;
-        let token_program_meta = if protocol_params.token_program == TOKEN_PROGRAM_2022 {
+        // Determine token program based on mayhem mode (Mayhem curves are always Token-2022)
+        let is_mayhem_mode = protocol_params.is_mayhem_mode();
+        let token_program = protocol_params.effective_token_program();
+        let token_program_meta = if token_program == TOKEN_PROGRAM_2022 {
             crate::constants::TOKEN_PROGRAM_2022_META
         } else {
             crate::constants::TOKEN_PROGRAM_META
@@ -108,7 +111,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(2);
+        let mut instructions = arena::instruction_vec(2);
 
         // Create associated token account
         if params.create_output_mint_ata {
@@ -154,11 +157,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             accounts::FEE_PROGRAM_META,
         ];
 
-        instructions.push(Instruction::new_with_bytes(
-            accounts::PUMPFUN,
-            &buy_data,
-            accounts.to_vec(),
-        ));
+        instructions.push(arena::arena_instruction(accounts::PUMPFUN, &buy_data, &accounts));
 
         Ok(instructions)
     }
@@ -203,6 +202,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
             ),
         };
+        record_quote(Some(sol_amount), min_sol_output);
 
         let bonding_curve_addr = if bonding_curve.account == Pubkey::default() {
             get_bonding_curve_pda(&params.input_mint)
@@ -211,10 +211,10 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             bonding_curve.account
         };
 
-        // Determine token program based on mayhem mode
-        let is_mayhem_mode = bonding_curve.is_mayhem_mode;
-        let token_program = protocol_params.token_program;
-        let token_program_meta = if protocol_params.token_program == TOKEN_PROGRAM_2022 {
+        // Determine token program based on mayhem mode (Mayhem curves are always Token-2022)
+        let is_mayhem_mode = protocol_params.is_mayhem_mode();
+        let token_program = protocol_params.effective_token_program();
+        let token_program_meta = if token_program == TOKEN_PROGRAM_2022 {
             crate::constants::TOKEN_PROGRAM_2022_META
         } else {
             crate::constants::TOKEN_PROGRAM_META
@@ -242,7 +242,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(2);
+        let mut instructions = arena::instruction_vec(2);
 
         let mut sell_data = [0u8; 24];
         sell_data[..8].copy_from_slice(&[51, 230, 133, 164, 1, 127, 131, 173]); // Method ID
@@ -273,11 +273,7 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
             accounts::FEE_PROGRAM_META,
         ];
 
-        instructions.push(Instruction::new_with_bytes(
-            accounts::PUMPFUN,
-            &sell_data,
-            accounts.to_vec(),
-        ));
+        instructions.push(arena::arena_instruction(accounts::PUMPFUN, &sell_data, &accounts));
 
         // Optional: Close token account
         if protocol_params.close_token_account_when_sell.unwrap_or(false)
@@ -294,6 +290,53 @@ impl InstructionBuilder for PumpFunInstructionBuilder {
 
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<PumpFunParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for PumpFun"))?;
+        let token_program = protocol_params.effective_token_program();
+        let bonding_curve = if protocol_params.bonding_curve.account == Pubkey::default() {
+            get_bonding_curve_pda(mint)
+                .ok_or_else(|| anyhow!("Bonding curve PDA not found for mint"))?
+        } else {
+            protocol_params.bonding_curve.account
+        };
+        let associated_bonding_curve =
+            if protocol_params.associated_bonding_curve == Pubkey::default() {
+                crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                    &bonding_curve,
+                    mint,
+                    &token_program,
+                )
+            } else {
+                protocol_params.associated_bonding_curve
+            };
+        let fee_recipient = if protocol_params.is_mayhem_mode() {
+            global_constants::MAYHEM_FEE_RECIPIENT
+        } else {
+            global_constants::FEE_RECIPIENT
+        };
+        Ok(vec![
+            global_constants::GLOBAL_ACCOUNT_META.pubkey,
+            fee_recipient,
+            *mint,
+            bonding_curve,
+            associated_bonding_curve,
+            crate::constants::SYSTEM_PROGRAM,
+            token_program,
+            protocol_params.creator_vault,
+            accounts::EVENT_AUTHORITY_META.pubkey,
+            accounts::GLOBAL_VOLUME_ACCUMULATOR_META.pubkey,
+            accounts::FEE_CONFIG_META.pubkey,
+            accounts::FEE_PROGRAM_META.pubkey,
+        ])
+    }
 }
 
 /// Parameters for creating a new token on PumpFun
@@ -650,4 +693,52 @@ impl PumpFunInstructionBuilder {
 
         Ok(Instruction::new_with_bytes(accounts::PUMPFUN, &data, accounts))
     }
+
+    /// 构建领取创作者手续费指令（collect_creator_fee）
+    ///
+    /// 创作者的所有代币在 bonding curve 上产生的创作者手续费都累积在同一个 creator vault PDA 中，
+    /// 该指令把 vault 中超出免租金额的 lamports 全部转给 `creator`（必须签名）。
+    pub fn build_collect_creator_fee_instruction(creator: &Pubkey) -> Result<Instruction> {
+        use crate::instruction::utils::pumpfun::get_creator_vault_pda;
+
+        let creator_vault = get_creator_vault_pda(creator)
+            .ok_or_else(|| anyhow!("Creator vault PDA not found for creator {}", creator))?;
+        let accounts = [
+            AccountMeta::new(*creator, true), // 0: creator - 创作者（签名者，可写）
+            AccountMeta::new(creator_vault, false), // 1: creator_vault - 创作者手续费金库 PDA（可写）
+            crate::constants::SYSTEM_PROGRAM_META,  // 2: system_program
+            accounts::EVENT_AUTHORITY_META,         // 3: event_authority
+            accounts::PUMPFUN_META,                 // 4: program
+        ];
+        Ok(Instruction::new_with_bytes(
+            accounts::PUMPFUN,
+            &COLLECT_CREATOR_FEE_DISCRIMINATOR,
+            accounts.to_vec(),
+        ))
+    }
+}
+
+/// collect_creator_fee 指令 discriminator
+pub const COLLECT_CREATOR_FEE_DISCRIMINATOR: [u8; 8] = [20, 22, 86, 123, 198, 28, 219, 132];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_creator_fee_instruction_layout() {
+        let creator = Pubkey::new_unique();
+        let ix =
+            PumpFunInstructionBuilder::build_collect_creator_fee_instruction(&creator).unwrap();
+        assert_eq!(ix.program_id, accounts::PUMPFUN);
+        assert_eq!(ix.data, COLLECT_CREATOR_FEE_DISCRIMINATOR);
+        assert_eq!(ix.accounts.len(), 5);
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts[0].pubkey, creator);
+        assert_eq!(
+            Some(ix.accounts[1].pubkey),
+            crate::instruction::utils::pumpfun::get_creator_vault_pda(&creator)
+        );
+        assert!(ix.accounts[1].is_writable);
+    }
 }
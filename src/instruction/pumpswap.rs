@@ -1,18 +1,22 @@
 use crate::{
+    common::transfer_hook::append_transfer_hook_accounts,
     constants::trade_consts::DEFAULT_SLIPPAGE,
     instruction::utils::pumpswap::{
-        BUY_DISCRIMINATOR, SELL_DISCRIMINATOR, accounts, fee_recipient_ata,
-        get_user_volume_accumulator_pda,
+        BUY_DISCRIMINATOR, BUY_EXACT_QUOTE_IN_DISCRIMINATOR, SELL_DISCRIMINATOR, accounts,
+        fee_recipient_ata, get_user_volume_accumulator_pda,
     },
+    perf::arena,
     trading::{
         common::wsol_manager,
         core::{
-            params::{PumpSwapParams, SwapParams},
+            params::{DexParamEnum, PumpSwapParams, SwapParams},
             traits::InstructionBuilder,
         },
+        lifecycle::record_quote,
     },
     utils::calc::pumpswap::{
-        buy_base_input_internal, buy_quote_input_internal, sell_base_input_internal,
+        buy_base_input_internal, buy_exact_quote_input_internal, buy_quote_input_internal,
+        sell_base_input_internal,
     },
 };
 use anyhow::{Result, anyhow};
@@ -25,6 +29,101 @@ use solana_sdk::{
 /// Instruction builder for PumpSwap protocol
 pub struct PumpSwapInstructionBuilder;
 
+/// 池子的计价资产（WSOL 或 USDC），并校验用户选择的计价代币与之一致
+///
+/// `user_mint` 为买入的输入 / 卖出的输出：SOL、WSOL 只能用于 WSOL 池，USDC 只能用于 USDC 池。
+fn resolve_stable_mint(
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+    user_mint: &Pubkey,
+) -> Result<Pubkey> {
+    let is_stable = |mint: &Pubkey| {
+        *mint == crate::constants::WSOL_TOKEN_ACCOUNT
+            || *mint == crate::constants::USDC_TOKEN_ACCOUNT
+    };
+    let stable_mint = if is_stable(quote_mint) {
+        *quote_mint
+    } else if is_stable(base_mint) {
+        *base_mint
+    } else {
+        return Err(anyhow!("Pool must contain WSOL or USDC"));
+    };
+    let user_wants_usdc = if *user_mint == crate::constants::USDC_TOKEN_ACCOUNT {
+        true
+    } else if *user_mint == crate::constants::SOL_TOKEN_ACCOUNT
+        || *user_mint == crate::constants::WSOL_TOKEN_ACCOUNT
+    {
+        false
+    } else {
+        return Err(anyhow!("Unsupported PumpSwap quote token {}", user_mint));
+    };
+    match (stable_mint == crate::constants::USDC_TOKEN_ACCOUNT, user_wants_usdc) {
+        (true, false) => Err(anyhow!("Pool is quoted in USDC, use TradeTokenType::USDC")),
+        (false, true) => {
+            Err(anyhow!("Pool is quoted in WSOL, use TradeTokenType::SOL or TradeTokenType::WSOL"))
+        },
+        _ => Ok(stable_mint),
+    }
+}
+
+/// 追加 Token-2022 transfer hook 账户
+///
+/// `is_buy_instruction` 为 true 时 base 由池转给用户、quote 由用户转入池；否则方向相反。
+/// 池 vault 的转账权限为池账户本身。
+#[allow(clippy::too_many_arguments)]
+fn append_swap_transfer_hooks(
+    accounts: &mut Vec<AccountMeta>,
+    protocol_params: &PumpSwapParams,
+    payer: &Pubkey,
+    user_base_token_account: &Pubkey,
+    user_quote_token_account: &Pubkey,
+    is_buy_instruction: bool,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Result<()> {
+    let pool = &protocol_params.pool;
+    for (hook, mint, user_account, pool_account, amount) in [
+        (
+            protocol_params.base_transfer_hook.as_deref(),
+            &protocol_params.base_mint,
+            user_base_token_account,
+            &protocol_params.pool_base_token_account,
+            base_amount,
+        ),
+        (
+            protocol_params.quote_transfer_hook.as_deref(),
+            &protocol_params.quote_mint,
+            user_quote_token_account,
+            &protocol_params.pool_quote_token_account,
+            quote_amount,
+        ),
+    ] {
+        let pool_to_user = is_buy_instruction == (mint == &protocol_params.base_mint);
+        if pool_to_user {
+            append_transfer_hook_accounts(
+                accounts,
+                hook,
+                pool_account,
+                mint,
+                user_account,
+                pool,
+                amount,
+            )?;
+        } else {
+            append_transfer_hook_accounts(
+                accounts,
+                hook,
+                user_account,
+                mint,
+                pool_account,
+                payer,
+                amount,
+            )?;
+        }
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl InstructionBuilder for PumpSwapInstructionBuilder {
     async fn build_buy_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>> {
@@ -50,8 +149,8 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         let quote_mint = protocol_params.quote_mint;
         let pool_base_token_reserves = protocol_params.pool_base_token_reserves;
         let pool_quote_token_reserves = protocol_params.pool_quote_token_reserves;
-        let params_coin_creator_vault_ata = protocol_params.coin_creator_vault_ata;
-        let params_coin_creator_vault_authority = protocol_params.coin_creator_vault_authority;
+        let (params_coin_creator_vault_ata, params_coin_creator_vault_authority) =
+            protocol_params.resolved_coin_creator_vault();
         let create_wsol_ata = params.create_input_mint_ata;
         let close_wsol_ata = params.close_input_mint_ata;
         let base_token_program = protocol_params.base_token_program;
@@ -59,28 +158,26 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         let pool_base_token_account = protocol_params.pool_base_token_account;
         let pool_quote_token_account = protocol_params.pool_quote_token_account;
 
-        let is_wsol = (base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::USDC_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-                && base_mint != crate::constants::USDC_TOKEN_ACCOUNT);
-        let is_usdc = (base_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::WSOL_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::USDC_TOKEN_ACCOUNT
-                && base_mint != crate::constants::WSOL_TOKEN_ACCOUNT);
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
-        }
+        // USDC 池直接使用用户的 USDC ATA，不做 WSOL 包装
+        let stable_mint = resolve_stable_mint(&base_mint, &quote_mint, &params.input_mint)?;
+        let is_usdc = stable_mint == crate::constants::USDC_TOKEN_ACCOUNT;
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let quote_is_wsol_or_usdc = quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let quote_is_wsol_or_usdc = quote_mint == stable_mint;
+        let stable_token_program =
+            if quote_is_wsol_or_usdc { quote_token_program } else { base_token_program };
         let mut creator = Pubkey::default();
         if params_coin_creator_vault_authority != accounts::DEFAULT_COIN_CREATOR_VAULT_AUTHORITY {
             creator = params_coin_creator_vault_authority;
         }
 
+        // 含手续费的固定总支出：quote 为 WSOL / USDC 且未指定固定输出时生效
+        let exact_quote_in = protocol_params.exact_quote_in
+            && quote_is_wsol_or_usdc
+            && params.fixed_output_amount.is_none();
+
         // 如果设置了 fixed_output_amount，使用逆向计算
         let (token_amount, sol_amount) = if let Some(fixed_output) = params.fixed_output_amount {
             // 逆向计算：给定目标 base 数量，计算需要的 quote 数量
@@ -92,8 +189,22 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 &creator,
             )
             .unwrap();
+            record_quote(Some(fixed_output), fixed_output);
             // base_amount_out (fixed), max_quote_amount_in
             (fixed_output, result.max_quote)
+        } else if exact_quote_in {
+            let spendable_quote_in = params.input_amount.unwrap_or(0);
+            let result = buy_exact_quote_input_internal(
+                spendable_quote_in,
+                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                pool_base_token_reserves,
+                pool_quote_token_reserves,
+                &creator,
+            )
+            .map_err(|e| anyhow!(e))?;
+            record_quote(Some(result.base), result.min_base);
+            // min_base_amount_out, spendable_quote_in
+            (result.min_base, spendable_quote_in)
         } else if quote_is_wsol_or_usdc {
             let result = buy_quote_input_internal(
                 params.input_amount.unwrap_or(0),
@@ -103,6 +214,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 &creator,
             )
             .unwrap();
+            record_quote(Some(result.base), result.base);
             // base_amount_out, max_quote_amount_in
             (result.base, result.max_quote)
         } else {
@@ -114,6 +226,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 &creator,
             )
             .unwrap();
+            record_quote(Some(result.ui_quote), result.min_quote);
             // min_quote_amount_out, base_amount_in
             (result.min_quote, params.input_amount.unwrap_or(0))
         };
@@ -147,11 +260,25 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(6);
+        let mut instructions = arena::instruction_vec(6);
 
         if create_wsol_ata {
-            instructions
-                .extend(crate::trading::common::handle_wsol(&params.payer.pubkey(), sol_amount));
+            if is_usdc {
+                instructions.extend(
+                    crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed(
+                        &params.payer.pubkey(),
+                        &params.payer.pubkey(),
+                        &stable_mint,
+                        &stable_token_program,
+                        params.open_seed_optimize,
+                    ),
+                );
+            } else {
+                instructions.extend(crate::trading::common::handle_wsol(
+                    &params.payer.pubkey(),
+                    sol_amount,
+                ));
+            }
         }
 
         if params.create_output_mint_ata {
@@ -167,7 +294,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         }
 
         // Create buy instruction
-        let mut accounts = Vec::with_capacity(23);
+        let mut accounts = arena::account_metas(23);
         accounts.extend([
             AccountMeta::new(pool, false),                          // pool_id
             AccountMeta::new(params.payer.pubkey(), true),          // user (signer)
@@ -198,10 +325,26 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         }
         accounts.push(accounts::FEE_CONFIG_META);
         accounts.push(accounts::FEE_PROGRAM_META);
+        append_swap_transfer_hooks(
+            &mut accounts,
+            protocol_params,
+            &params.payer.pubkey(),
+            &user_base_token_account,
+            &user_quote_token_account,
+            quote_is_wsol_or_usdc,
+            if quote_is_wsol_or_usdc { token_amount } else { sol_amount },
+            if quote_is_wsol_or_usdc { sol_amount } else { token_amount },
+        )?;
 
         // Create instruction data
         let mut data = [0u8; 24];
-        if quote_is_wsol_or_usdc {
+        if exact_quote_in {
+            data[..8].copy_from_slice(&BUY_EXACT_QUOTE_IN_DISCRIMINATOR);
+            // spendable_quote_in
+            data[8..16].copy_from_slice(&sol_amount.to_le_bytes());
+            // min_base_amount_out
+            data[16..24].copy_from_slice(&token_amount.to_le_bytes());
+        } else if quote_is_wsol_or_usdc {
             data[..8].copy_from_slice(&BUY_DISCRIMINATOR);
             // base_amount_out
             data[8..16].copy_from_slice(&token_amount.to_le_bytes());
@@ -217,12 +360,13 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
 
         let buy_instruction = Instruction {
             program_id: accounts::AMM_PROGRAM,
-            accounts: accounts.clone(),
-            data: data.to_vec(),
+            accounts,
+            data: arena::instruction_data(&data),
         };
 
         instructions.push(buy_instruction);
-        if close_wsol_ata {
+        // USDC ATA 保留剩余余额，不关闭
+        if close_wsol_ata && !is_usdc {
             // Close wSOL ATA account, reclaim rent
             instructions.extend(crate::trading::common::close_wsol(&params.payer.pubkey()));
         }
@@ -246,40 +390,33 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         let pool_quote_token_reserves = protocol_params.pool_quote_token_reserves;
         let pool_base_token_account = protocol_params.pool_base_token_account;
         let pool_quote_token_account = protocol_params.pool_quote_token_account;
-        let params_coin_creator_vault_ata = protocol_params.coin_creator_vault_ata;
-        let params_coin_creator_vault_authority = protocol_params.coin_creator_vault_authority;
+        let (params_coin_creator_vault_ata, params_coin_creator_vault_authority) =
+            protocol_params.resolved_coin_creator_vault();
         let create_wsol_ata = params.create_output_mint_ata;
         let close_wsol_ata = params.close_output_mint_ata;
         let base_token_program = protocol_params.base_token_program;
         let quote_token_program = protocol_params.quote_token_program;
 
-        let is_wsol = (base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::USDC_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-                && base_mint != crate::constants::USDC_TOKEN_ACCOUNT);
-        let is_usdc = (base_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            && quote_mint != crate::constants::WSOL_TOKEN_ACCOUNT)
-            || (quote_mint == crate::constants::USDC_TOKEN_ACCOUNT
-                && base_mint != crate::constants::WSOL_TOKEN_ACCOUNT);
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
-        }
+        // USDC 池直接卖出到用户的 USDC ATA，不做 WSOL 处理
+        let stable_mint = resolve_stable_mint(&base_mint, &quote_mint, &params.output_mint)?;
+        let is_usdc = stable_mint == crate::constants::USDC_TOKEN_ACCOUNT;
 
-        if params.input_amount.is_none() {
+        if params.input_amount.is_none_or(|amount| amount == 0) {
             return Err(anyhow!("Token amount is not set"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let quote_is_wsol_or_usdc = quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let quote_is_wsol_or_usdc = quote_mint == stable_mint;
+        let stable_token_program =
+            if quote_is_wsol_or_usdc { quote_token_program } else { base_token_program };
         let mut creator = Pubkey::default();
         if params_coin_creator_vault_authority != accounts::DEFAULT_COIN_CREATOR_VAULT_AUTHORITY {
             creator = params_coin_creator_vault_authority;
         }
 
-        let (token_amount, mut sol_amount) = if quote_is_wsol_or_usdc {
+        let (token_amount, mut sol_amount, expected_output) = if quote_is_wsol_or_usdc {
             let result = sell_base_input_internal(
                 params.input_amount.unwrap(),
                 params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
@@ -289,7 +426,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             )
             .unwrap();
             // base_amount_in, min_quote_amount_out
-            (params.input_amount.unwrap(), result.min_quote)
+            (params.input_amount.unwrap(), result.min_quote, result.ui_quote)
         } else {
             let result = buy_quote_input_internal(
                 params.input_amount.unwrap(),
@@ -300,12 +437,13 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             )
             .unwrap();
             // max_quote_amount_in, base_amount_out
-            (result.max_quote, result.base)
+            (result.max_quote, result.base, result.base)
         };
 
         if params.fixed_output_amount.is_some() {
             sol_amount = params.fixed_output_amount.unwrap();
         }
+        record_quote(Some(expected_output), sol_amount);
 
         // Determine fee recipient based on mayhem mode
         let is_mayhem_mode = protocol_params.is_mayhem_mode;
@@ -336,14 +474,26 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(3);
+        let mut instructions = arena::instruction_vec(3);
 
         if create_wsol_ata {
-            instructions.extend(wsol_manager::create_wsol_ata(&params.payer.pubkey()));
+            if is_usdc {
+                instructions.extend(
+                    crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed(
+                        &params.payer.pubkey(),
+                        &params.payer.pubkey(),
+                        &stable_mint,
+                        &stable_token_program,
+                        params.open_seed_optimize,
+                    ),
+                );
+            } else {
+                instructions.extend(wsol_manager::create_wsol_ata(&params.payer.pubkey()));
+            }
         }
 
         // Create sell instruction
-        let mut accounts = Vec::with_capacity(23);
+        let mut accounts = arena::account_metas(23);
         accounts.extend([
             AccountMeta::new(pool, false),                          // pool_id
             AccountMeta::new(params.payer.pubkey(), true),          // user (signer)
@@ -375,6 +525,16 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
 
         accounts.push(accounts::FEE_CONFIG_META);
         accounts.push(accounts::FEE_PROGRAM_META);
+        append_swap_transfer_hooks(
+            &mut accounts,
+            protocol_params,
+            &params.payer.pubkey(),
+            &user_base_token_account,
+            &user_quote_token_account,
+            quote_is_wsol_or_usdc,
+            if quote_is_wsol_or_usdc { token_amount } else { sol_amount },
+            if quote_is_wsol_or_usdc { sol_amount } else { token_amount },
+        )?;
 
         // Create instruction data
         let mut data = [0u8; 24];
@@ -394,13 +554,14 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
 
         let sell_instruction = Instruction {
             program_id: accounts::AMM_PROGRAM,
-            accounts: accounts.clone(),
-            data: data.to_vec(),
+            accounts,
+            data: arena::instruction_data(&data),
         };
 
         instructions.push(sell_instruction);
 
-        if close_wsol_ata {
+        // 卖出所得的 USDC 留在 ATA 中，不关闭
+        if close_wsol_ata && !is_usdc {
             instructions.extend(crate::trading::common::close_wsol(&params.payer.pubkey()));
         }
         if params.close_input_mint_ata {
@@ -418,4 +579,108 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         }
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        _mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<PumpSwapParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for PumpSwap"))?;
+        let (coin_creator_vault_ata, coin_creator_vault_authority) =
+            protocol_params.resolved_coin_creator_vault();
+        let fee_recipient = if protocol_params.is_mayhem_mode {
+            accounts::MAYHEM_FEE_RECIPIENT
+        } else {
+            accounts::FEE_RECIPIENT
+        };
+        let mut static_accounts = vec![
+            protocol_params.pool,
+            accounts::GLOBAL_ACCOUNT_META.pubkey,
+            protocol_params.base_mint,
+            protocol_params.quote_mint,
+            protocol_params.pool_base_token_account,
+            protocol_params.pool_quote_token_account,
+            fee_recipient,
+            fee_recipient_ata(fee_recipient, protocol_params.quote_mint),
+            protocol_params.base_token_program,
+            protocol_params.quote_token_program,
+            crate::constants::SYSTEM_PROGRAM,
+            accounts::ASSOCIATED_TOKEN_PROGRAM_META.pubkey,
+            accounts::EVENT_AUTHORITY_META.pubkey,
+            coin_creator_vault_ata,
+            coin_creator_vault_authority,
+            accounts::GLOBAL_VOLUME_ACCUMULATOR_META.pubkey,
+            accounts::FEE_CONFIG_META.pubkey,
+            accounts::FEE_PROGRAM_META.pubkey,
+        ];
+        for hook in [&protocol_params.base_transfer_hook, &protocol_params.quote_transfer_hook]
+            .into_iter()
+            .flatten()
+        {
+            static_accounts.extend(hook.static_accounts());
+        }
+        Ok(static_accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{SOL_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+
+    #[test]
+    fn test_resolve_stable_mint_matches_pool_quote() {
+        let token = Pubkey::new_unique();
+        assert_eq!(
+            resolve_stable_mint(&token, &USDC_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT).unwrap(),
+            USDC_TOKEN_ACCOUNT
+        );
+        assert_eq!(
+            resolve_stable_mint(&token, &WSOL_TOKEN_ACCOUNT, &SOL_TOKEN_ACCOUNT).unwrap(),
+            WSOL_TOKEN_ACCOUNT
+        );
+        // 计价资产在 base 侧
+        assert_eq!(
+            resolve_stable_mint(&WSOL_TOKEN_ACCOUNT, &token, &WSOL_TOKEN_ACCOUNT).unwrap(),
+            WSOL_TOKEN_ACCOUNT
+        );
+        // 代币类型与池子不一致
+        let err = resolve_stable_mint(&token, &USDC_TOKEN_ACCOUNT, &SOL_TOKEN_ACCOUNT).unwrap_err();
+        assert!(err.to_string().contains("USDC"));
+        assert!(resolve_stable_mint(&token, &WSOL_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT).is_err());
+        assert!(resolve_stable_mint(&token, &Pubkey::new_unique(), &SOL_TOKEN_ACCOUNT).is_err());
+    }
+
+    #[test]
+    fn test_static_accounts_cover_pool_accounts() {
+        let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let params = DexParamEnum::PumpSwap(PumpSwapParams::new(
+            pool,
+            mint,
+            WSOL_TOKEN_ACCOUNT,
+            base_vault,
+            quote_vault,
+            0,
+            0,
+            Pubkey::default(),
+            Pubkey::default(),
+            crate::constants::TOKEN_PROGRAM,
+            crate::constants::TOKEN_PROGRAM,
+            accounts::FEE_RECIPIENT,
+        ));
+        let static_accounts = params.static_accounts(&mint).unwrap();
+        for account in [pool, mint, base_vault, quote_vault, accounts::FEE_CONFIG_META.pubkey] {
+            assert!(static_accounts.contains(&account));
+        }
+        // 去重，且不含被调用的 DEX 程序
+        let mut deduped = static_accounts.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(deduped.len(), static_accounts.len());
+        assert!(!static_accounts.contains(&accounts::AMM_PROGRAM));
+    }
 }
@@ -1,15 +1,20 @@
+use crate::trading::lifecycle::record_quote;
 use crate::{
+    common::Bps,
     constants::trade_consts::DEFAULT_SLIPPAGE,
-    instruction::utils::raydium_amm_v4::{SWAP_BASE_IN_DISCRIMINATOR, accounts},
+    instruction::utils::raydium_amm_v4::{
+        SWAP_BASE_IN_DISCRIMINATOR, SWAP_BASE_OUT_DISCRIMINATOR, accounts,
+    },
     trading::core::{
-        params::{RaydiumAmmV4Params, SwapParams},
+        params::{DexParamEnum, RaydiumAmmV4Params, SwapMode, SwapParams},
         traits::InstructionBuilder,
     },
-    utils::calc::raydium_amm_v4::compute_swap_amount,
+    utils::calc::raydium_amm_v4::{compute_swap_amount, compute_swap_amount_base_out},
 };
 use anyhow::{Result, anyhow};
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
     signer::Signer,
 };
 
@@ -55,17 +60,13 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             || protocol_params.coin_mint == crate::constants::USDC_TOKEN_ACCOUNT;
         // 🔧 修复：使用已经解包的 input_amount
         let amount_in: u64 = input_amount;
-        let swap_result = compute_swap_amount(
-            protocol_params.coin_reserve,
-            protocol_params.pc_reserve,
+        let data = swap_data(
+            protocol_params,
             is_base_in,
             amount_in,
+            params.fixed_output_amount,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-        );
-        let minimum_amount_out = match params.fixed_output_amount {
-            Some(fixed) => fixed,
-            None => swap_result.min_amount_out,
-        };
+        )?;
 
         let user_source_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -128,12 +129,6 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             AccountMeta::new(user_destination_token_account, false), // User Destination Token Account
             AccountMeta::new(params.payer.pubkey(), true),           // User Source Owner
         ];
-        // Create instruction data
-        let mut data = [0u8; 17];
-        data[..1].copy_from_slice(SWAP_BASE_IN_DISCRIMINATOR);
-        data[1..9].copy_from_slice(&amount_in.to_le_bytes());
-        data[9..17].copy_from_slice(&minimum_amount_out.to_le_bytes());
-
         instructions.push(Instruction::new_with_bytes(
             accounts::RAYDIUM_AMM_V4,
             &data,
@@ -172,23 +167,25 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
         if !is_wsol && !is_usdc {
             return Err(anyhow!("Pool must contain WSOL or USDC"));
         }
+        // 固定输出时不一定卖出全部持仓，账户可能仍有余额
+        if protocol_params.swap_mode == SwapMode::ExactOut && params.close_input_mint_ata {
+            return Err(anyhow!(
+                "Cannot close the token account when selling with SwapMode::ExactOut"
+            ));
+        }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
         let is_base_in = protocol_params.pc_mint == crate::constants::WSOL_TOKEN_ACCOUNT
             || protocol_params.pc_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-        let swap_result = compute_swap_amount(
-            protocol_params.coin_reserve,
-            protocol_params.pc_reserve,
+        let data = swap_data(
+            protocol_params,
             is_base_in,
             params.input_amount.unwrap_or(0),
+            params.fixed_output_amount,
             params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-        );
-        let minimum_amount_out = match params.fixed_output_amount {
-            Some(fixed) => fixed,
-            None => swap_result.min_amount_out,
-        };
+        )?;
 
         let user_source_token_account =
             crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed(
@@ -238,12 +235,6 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             AccountMeta::new(user_destination_token_account, false), // User Destination Token Account
             AccountMeta::new(params.payer.pubkey(), true),           // User Source Owner
         ];
-        // Create instruction data
-        let mut data = [0u8; 17];
-        data[..1].copy_from_slice(SWAP_BASE_IN_DISCRIMINATOR);
-        data[1..9].copy_from_slice(&params.input_amount.unwrap_or(0).to_le_bytes());
-        data[9..17].copy_from_slice(&minimum_amount_out.to_le_bytes());
-
         instructions.push(Instruction::new_with_bytes(
             accounts::RAYDIUM_AMM_V4,
             &data,
@@ -265,4 +256,118 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
 
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        _mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumAmmV4Params>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
+        Ok(vec![
+            crate::constants::TOKEN_PROGRAM,
+            protocol_params.amm,
+            accounts::AUTHORITY,
+            protocol_params.token_coin,
+            protocol_params.token_pc,
+            protocol_params.coin_mint,
+            protocol_params.pc_mint,
+        ])
+    }
+}
+
+/// 按兑换模式计算报价并编码指令数据（discriminator + 两个 u64）
+///
+/// - ExactIn：SwapBaseIn(amount_in, minimum_amount_out)，最小输出优先取 `fixed_output`，否则按滑点从报价计算；
+/// - ExactOut：SwapBaseOut(max_amount_in, amount_out)，`fixed_output` 为目标输出，最大输入按报价加滑点计算，
+///   且不超过 `input_amount`（买入时即包装的 WSOL 数量）。
+fn swap_data(
+    protocol_params: &RaydiumAmmV4Params,
+    is_base_in: bool,
+    input_amount: u64,
+    fixed_output: Option<u64>,
+    slippage: Bps,
+) -> Result<[u8; 17]> {
+    let (discriminator, first, second) = match protocol_params.swap_mode {
+        SwapMode::ExactIn => {
+            let swap_result = compute_swap_amount(
+                protocol_params.coin_reserve,
+                protocol_params.pc_reserve,
+                is_base_in,
+                input_amount,
+                slippage,
+            );
+            let minimum_amount_out = fixed_output.unwrap_or(swap_result.min_amount_out);
+            record_quote(Some(swap_result.amount_out), minimum_amount_out);
+            (SWAP_BASE_IN_DISCRIMINATOR, input_amount, minimum_amount_out)
+        },
+        SwapMode::ExactOut => {
+            let amount_out = fixed_output.ok_or_else(|| {
+                anyhow!("fixed_output_token_amount is required for SwapMode::ExactOut")
+            })?;
+            let quote = compute_swap_amount_base_out(
+                protocol_params.coin_reserve,
+                protocol_params.pc_reserve,
+                is_base_in,
+                amount_out,
+                slippage,
+            )?;
+            if quote.amount_in > input_amount {
+                return Err(anyhow!(
+                    "Input amount {} is below the {} required for output {}",
+                    input_amount,
+                    quote.amount_in,
+                    amount_out
+                ));
+            }
+            record_quote(Some(amount_out), amount_out);
+            (SWAP_BASE_OUT_DISCRIMINATOR, quote.max_amount_in.min(input_amount), amount_out)
+        },
+    };
+    let mut data = [0u8; 17];
+    data[..1].copy_from_slice(discriminator);
+    data[1..9].copy_from_slice(&first.to_le_bytes());
+    data[9..17].copy_from_slice(&second.to_le_bytes());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(swap_mode: SwapMode) -> RaydiumAmmV4Params {
+        RaydiumAmmV4Params::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            crate::constants::WSOL_TOKEN_ACCOUNT,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000_000,
+            50_000_000_000,
+        )
+        .with_swap_mode(swap_mode)
+    }
+
+    #[test]
+    fn test_swap_data_modes() {
+        let exact_in =
+            swap_data(&pool(SwapMode::ExactIn), false, 1_000, Some(900), Bps::ZERO).unwrap();
+        assert_eq!(exact_in[0], SWAP_BASE_IN_DISCRIMINATOR[0]);
+        assert_eq!(u64::from_le_bytes(exact_in[1..9].try_into().unwrap()), 1_000);
+        assert_eq!(u64::from_le_bytes(exact_in[9..17].try_into().unwrap()), 900);
+
+        let exact_out = pool(SwapMode::ExactOut);
+        let data =
+            swap_data(&exact_out, false, 100_000_000, Some(1_000_000_000), Bps::new(100)).unwrap();
+        assert_eq!(data[0], SWAP_BASE_OUT_DISCRIMINATOR[0]);
+        let max_amount_in = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        assert!(max_amount_in > 50_000_000 && max_amount_in <= 100_000_000);
+        assert_eq!(u64::from_le_bytes(data[9..17].try_into().unwrap()), 1_000_000_000);
+
+        // 输入预算不足、缺少目标输出时拒绝
+        assert!(swap_data(&exact_out, false, 1_000, Some(1_000_000_000), Bps::ZERO).is_err());
+        assert!(swap_data(&exact_out, false, 100_000_000, None, Bps::ZERO).is_err());
+    }
 }
@@ -1,9 +1,14 @@
+use crate::trading::lifecycle::record_quote;
 use crate::{
-    common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed,
+    common::{Bps, fast_fn::get_associated_token_address_with_program_id_fast_use_seed},
     constants::trade_consts::DEFAULT_SLIPPAGE,
-    instruction::utils::raydium_clmm::{accounts, get_pool_by_address, get_tick_array_pda},
+    instruction::utils::raydium_clmm::{
+        accounts, estimate_tick_arrays_crossed,
+        get_first_initialized_tick_array_start_index_with_extension, get_pool_by_address,
+        get_tick_array_pda, tick_array_start_indices,
+    },
     trading::core::{
-        params::{RaydiumClmmParams, SwapParams},
+        params::{DexParamEnum, RaydiumClmmParams, SwapMode, SwapParams},
         traits::InstructionBuilder,
     },
     utils::{
@@ -14,6 +19,7 @@ use crate::{
 use anyhow::{Result, anyhow};
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
     signer::Signer,
 };
 
@@ -32,17 +38,65 @@ pub struct RaydiumClmmInstructionBuilder;
 
 /// 滑点计算辅助函数
 /// 根据官方 client 实现移植
-fn amount_with_slippage(amount: u64, slippage_bps: u16, round_up: bool) -> u64 {
-    let slippage_f64 = (slippage_bps as f64) / 10000.0; // 将 BP 转换为小数
+fn amount_with_slippage(amount: u64, slippage_bps: Bps, round_up: bool) -> u64 {
+    let slippage_f64 = slippage_bps.to_fraction();
     if round_up {
         // max in: amount * (1 + slippage), 向上取整
         ((amount as f64) * (1.0 + slippage_f64)).ceil() as u64
     } else {
-        // min out: amount * (1 - slippage), 向下取整
-        ((amount as f64) * (1.0 - slippage_f64)).floor() as u64
+        // min out: amount * (1 - slippage), 向下取整；滑点超过 100% 时按 100% 计算
+        ((amount as f64) * (1.0 - slippage_bps.capped().to_fraction())).floor() as u64
+    }
+}
+
+/// 按兑换模式计算 SwapV2 的 `(amount, other_amount_threshold)`
+///
+/// - ExactIn：`(输入量, 最小输出)`，最小输出优先取 `fixed_output`，否则按滑点从报价计算；
+/// - ExactOut：`(fixed_output, 输入量)`，输入量即愿意支付的上限。
+fn swap_amounts(
+    mode: SwapMode,
+    input_amount: u64,
+    expected_output: u64,
+    fixed_output: Option<u64>,
+    slippage_bps: Bps,
+) -> Result<(u64, u64)> {
+    match mode {
+        SwapMode::ExactIn => Ok((
+            input_amount,
+            fixed_output
+                .unwrap_or_else(|| amount_with_slippage(expected_output, slippage_bps, false)),
+        )),
+        SwapMode::ExactOut => {
+            let amount_out = fixed_output.ok_or_else(|| {
+                anyhow!("fixed_output_token_amount is required for SwapMode::ExactOut")
+            })?;
+            if amount_out == 0 {
+                return Err(anyhow!(
+                    "fixed_output_token_amount cannot be zero for SwapMode::ExactOut"
+                ));
+            }
+            Ok((amount_out, input_amount))
+        },
     }
 }
 
+/// SwapV2 指令数据：discriminator (8) + amount (u64) + other_amount_threshold (u64)
+/// + sqrt_price_limit_x64 (u128) + is_base_input (bool)
+fn encode_swap_v2_data(
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    mode: SwapMode,
+) -> Vec<u8> {
+    let mut data = vec![0u8; 41];
+    data[0..8].copy_from_slice(SWAP_V2_DISCRIMINATOR);
+    data[8..16].copy_from_slice(&amount.to_le_bytes());
+    data[16..24].copy_from_slice(&other_amount_threshold.to_le_bytes());
+    data[24..40].copy_from_slice(&sqrt_price_limit_x64.to_le_bytes());
+    data[40] = mode.is_base_input() as u8;
+    data
+}
+
 /// 简化算法降级方案
 fn fallback_simple_calculation(
     amount_in: u64,
@@ -177,8 +231,9 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
             ));
         }
 
-        // Determine which token is input (for is_base_input flag)
-        let is_token0_in = protocol_params.token0_mint == input_mint;
+        // 兑换方向由输入代币在池内的位置决定，与 is_base_input 无关
+        let direction = protocol_params.swap_direction(&input_mint)?;
+        let is_token0_in = direction.is_zero_for_one();
 
         // Get vaults and programs based on which token is input/output
         let (input_vault, input_token_program) = if is_token0_in {
@@ -226,6 +281,16 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
 
         let fee_rate = amm_config.trade_fee_rate;
 
+        // 沿兑换方向预取/注册的 tick array 数量
+        let tick_array_depth =
+            protocol_params.tick_array_depth.resolve(estimate_tick_arrays_crossed(
+                pool_state.sqrt_price_x64,
+                pool_state.liquidity,
+                amount_in,
+                pool_state.tick_spacing,
+                zero_for_one,
+            ));
+
         // 尝试使用完整的 tick-by-tick 算法
         let expected_output = if pool_state.liquidity > 0 {
             // 计算需要的 tick array start indices
@@ -235,22 +300,13 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
                     pool_state.tick_spacing,
                 );
 
-            // 获取附近的 3 个 tick arrays（当前 + 前后各1个）
-            let tick_spacing_i32 = pool_state.tick_spacing as i32;
-            let ticks_per_array = 60 * tick_spacing_i32;
-
-            let mut tick_array_indices = vec![current_tick_array_start];
-
-            // 添加前一个和后一个 tick array
-            let prev_index = current_tick_array_start - ticks_per_array;
-            let next_index = current_tick_array_start + ticks_per_array;
-
-            if prev_index >= clmm_math::MIN_TICK {
-                tick_array_indices.push(prev_index);
-            }
-            if next_index <= clmm_math::MAX_TICK {
-                tick_array_indices.push(next_index);
-            }
+            // 从当前 array 起沿兑换方向获取 tick_array_depth 个
+            let tick_array_indices = tick_array_start_indices(
+                current_tick_array_start,
+                pool_state.tick_spacing,
+                zero_for_one,
+                tick_array_depth,
+            );
 
             // 从 RPC 获取 tick arrays
             match crate::instruction::utils::raydium_clmm::get_tick_arrays(
@@ -340,16 +396,19 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         };
 
         // Apply slippage using official client logic
-        // For buy (base_in=true): minimum_amount_out = expected_output * (1 - slippage)
         let slippage = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
-        let minimum_amount_out = match params.fixed_output_amount {
-            Some(fixed) => fixed,
-            None => {
-                // 使用官方的 amount_with_slippage 函数
-                // is_base_input=true: 计算 min out，round_up=false
-                amount_with_slippage(expected_output, slippage as u16, false)
-            },
+        let (amount, other_amount_threshold) = swap_amounts(
+            protocol_params.swap_mode,
+            amount_in,
+            expected_output,
+            params.fixed_output_amount,
+            slippage,
+        )?;
+        let minimum_amount_out = match protocol_params.swap_mode {
+            SwapMode::ExactIn => other_amount_threshold,
+            SwapMode::ExactOut => amount,
         };
+        record_quote(Some(expected_output), minimum_amount_out);
 
         let input_token_account = get_associated_token_address_with_program_id_fast_use_seed(
             &params.payer.pubkey(),
@@ -365,24 +424,9 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         );
 
         // Calculate sqrt_price_limit_x64 for slippage protection
-        // Match Raydium SDK V2 logic from constants.ts:
-        // MIN_SQRT_PRICE_X64 = 4295048016
-        // MAX_SQRT_PRICE_X64 = 79226673521066979257578248091
-        // MIN_SQRT_PRICE_X64_ADD_ONE = 4295048017
-        // MAX_SQRT_PRICE_X64_SUB_ONE = 79226673521066979257578248090
-        const MIN_SQRT_PRICE_X64: u128 = 4295048016;
-        const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
-
-        // No price limit specified, use default limits matching SDK
-        // For baseIn (token0 -> token1): use minimum sqrt price + 1
-        // For baseOut (token1 -> token0): use maximum sqrt price - 1
-        let sqrt_price_limit_x64 = if is_token0_in {
-            // Buying (token0 -> token1): use minimum sqrt price + 1
-            MIN_SQRT_PRICE_X64 + 1
-        } else {
-            // Selling (token1 -> token0): use maximum sqrt price - 1
-            MAX_SQRT_PRICE_X64 - 1
-        };
+        // No price limit specified, use default limits matching Raydium SDK V2 (constants.ts):
+        // ZeroForOne -> MIN_SQRT_PRICE_X64_ADD_ONE, OneForZero -> MAX_SQRT_PRICE_X64_SUB_ONE
+        let sqrt_price_limit_x64 = direction.sqrt_price_limit_x64();
 
         // ========================================
         // Build instructions
@@ -407,45 +451,24 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         }
 
         // Calculate tick arrays - CLMM requires multiple tick arrays for swap
-        // 根据官方 client 实现，需要获取多个 tick arrays（最多 5 个）
-        let zero_for_one = is_token0_in;
-        let mut tick_array_start_index =
-            crate::instruction::utils::raydium_clmm::get_first_initialized_tick_array_start_index(
-                &pool_state,
-                zero_for_one,
-            );
-
-        let mut tick_array_pdas = Vec::new();
-        let (first_tick_array_pda, _) =
-            get_tick_array_pda(&protocol_params.pool_state, tick_array_start_index)?;
-        tick_array_pdas.push(first_tick_array_pda);
-
-        // 获取后续的 tick arrays（最多 5 个）
-        let tick_spacing = pool_state.tick_spacing as i32;
-        const TICK_ARRAY_SIZE: i32 = 60; // raydium_amm_v3::states::TICK_ARRAY_SIZE
-        let ticks_per_array = tick_spacing * TICK_ARRAY_SIZE;
-
-        for _ in 0..4 {
-            tick_array_start_index = if zero_for_one {
-                tick_array_start_index - ticks_per_array
-            } else {
-                tick_array_start_index + ticks_per_array
-            };
-
-            // 检查是否超出范围
-            const MIN_TICK: i32 = -443636;
-            const MAX_TICK: i32 = 443636;
-            if (zero_for_one && tick_array_start_index < MIN_TICK)
-                || (!zero_for_one && tick_array_start_index > MAX_TICK)
-            {
-                break;
-            }
-
-            if let Ok((tick_array_pda, _)) =
-                get_tick_array_pda(&protocol_params.pool_state, tick_array_start_index)
-            {
-                tick_array_pdas.push(tick_array_pda);
-            }
+        // 从第一个已初始化的 array 起沿兑换方向注册 tick_array_depth 个
+        // 默认 bitmap 中附近没有已初始化的 array 时读取 bitmap extension
+        let tick_array_start_index = get_first_initialized_tick_array_start_index_with_extension(
+            rpc,
+            &protocol_params.pool_state,
+            &pool_state,
+            zero_for_one,
+        )
+        .await?;
+        let mut tick_array_pdas = Vec::with_capacity(tick_array_depth);
+        for start_index in tick_array_start_indices(
+            tick_array_start_index,
+            pool_state.tick_spacing,
+            zero_for_one,
+            tick_array_depth,
+        ) {
+            let (tick_array_pda, _) = get_tick_array_pda(&protocol_params.pool_state, start_index)?;
+            tick_array_pdas.push(tick_array_pda);
         }
 
         // Get tick array bitmap extension PDA
@@ -506,19 +529,16 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
             });
         }
 
-        // Create instruction data: discriminator (8 bytes) + amount (u64) + other_amount_threshold (u64) + sqrt_price_limit_x64 (u128) + is_base_input (bool)
-        // 使用 SwapV2 指令 discriminator
-        //
         // IMPORTANT: is_base_input 的含义：
         // - true: 指定输入金额，计算输出金额 (amount = input, other_amount_threshold = min output)
         // - false: 指定输出金额，计算输入金额 (amount = output, other_amount_threshold = max input)
-        // 买入场景：输入固定，输出浮动，所以 is_base_input = true
-        let mut data = vec![0u8; 41];
-        data[0..8].copy_from_slice(SWAP_V2_DISCRIMINATOR);
-        data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-        data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
-        data[24..40].copy_from_slice(&sqrt_price_limit_x64.to_le_bytes());
-        data[40] = 1; // is_base_input = true (买入场景：输入固定)
+        // 由 RaydiumClmmParams::swap_mode 决定，与买卖方向无关
+        let data = encode_swap_v2_data(
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            protocol_params.swap_mode,
+        );
 
         instructions.push(Instruction::new_with_bytes(
             accounts::RAYDIUM_CLMM,
@@ -623,8 +643,9 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        // Determine which token is input (for is_base_input flag)
-        let is_token0_in = protocol_params.token0_mint == input_mint;
+        // 兑换方向由输入代币在池内的位置决定，与 is_base_input 无关
+        let direction = protocol_params.swap_direction(&input_mint)?;
+        let is_token0_in = direction.is_zero_for_one();
 
         // Get vaults and programs based on which token is input/output
         let (input_vault, input_token_program) = if is_token0_in {
@@ -659,17 +680,26 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         };
 
         // 获取 AMM config 以获取费率
-        let amm_config = crate::instruction::utils::raydium_clmm::get_amm_config(
-            params.rpc.as_ref().ok_or_else(|| anyhow!("RPC client required"))?,
-            &pool_state.amm_config,
-        )
-        .await?;
+        let rpc = params.rpc.as_ref().ok_or_else(|| anyhow!("RPC client required"))?;
+        let amm_config =
+            crate::instruction::utils::raydium_clmm::get_amm_config(rpc, &pool_state.amm_config)
+                .await?;
 
         let fee_rate = amm_config.trade_fee_rate;
 
         // zero_for_one: token0 -> token1 为 true, token1 -> token0 为 false
         let zero_for_one = is_token0_in;
 
+        // 沿兑换方向预取/注册的 tick array 数量
+        let tick_array_depth =
+            protocol_params.tick_array_depth.resolve(estimate_tick_arrays_crossed(
+                pool_state.sqrt_price_x64,
+                pool_state.liquidity,
+                amount_in,
+                pool_state.tick_spacing,
+                zero_for_one,
+            ));
+
         // 尝试使用完整的 tick-by-tick 算法（与买入指令相同）
         let expected_output = if pool_state.liquidity > 0 {
             // 计算需要的 tick array start indices
@@ -679,22 +709,13 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
                     pool_state.tick_spacing,
                 );
 
-            // 获取附近的 3 个 tick arrays（当前 + 前后各1个）
-            let tick_spacing_i32 = pool_state.tick_spacing as i32;
-            let ticks_per_array = 60 * tick_spacing_i32;
-
-            let mut tick_array_indices = vec![current_tick_array_start];
-
-            // 添加前一个和后一个 tick array
-            let prev_index = current_tick_array_start - ticks_per_array;
-            let next_index = current_tick_array_start + ticks_per_array;
-
-            if prev_index >= clmm_math::MIN_TICK {
-                tick_array_indices.push(prev_index);
-            }
-            if next_index <= clmm_math::MAX_TICK {
-                tick_array_indices.push(next_index);
-            }
+            // 从当前 array 起沿兑换方向获取 tick_array_depth 个
+            let tick_array_indices = tick_array_start_indices(
+                current_tick_array_start,
+                pool_state.tick_spacing,
+                zero_for_one,
+                tick_array_depth,
+            );
 
             // 从 RPC 获取 tick arrays
             match crate::instruction::utils::raydium_clmm::get_tick_arrays(
@@ -785,10 +806,18 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
 
         // Apply slippage
         let slippage = params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
-        let minimum_amount_out = match params.fixed_output_amount {
-            Some(fixed) => fixed,
-            None => ((expected_output as f64) * (1.0 - (slippage as f64) / 10000.0)) as u64,
+        let (amount, other_amount_threshold) = swap_amounts(
+            protocol_params.swap_mode,
+            amount_in,
+            expected_output,
+            params.fixed_output_amount,
+            slippage,
+        )?;
+        let minimum_amount_out = match protocol_params.swap_mode {
+            SwapMode::ExactIn => other_amount_threshold,
+            SwapMode::ExactOut => amount,
         };
+        record_quote(Some(expected_output), minimum_amount_out);
 
         let input_token_account = get_associated_token_address_with_program_id_fast_use_seed(
             &params.payer.pubkey(),
@@ -804,24 +833,9 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         );
 
         // Calculate sqrt_price_limit_x64 for slippage protection
-        // Match Raydium SDK V2 logic from constants.ts:
-        // MIN_SQRT_PRICE_X64 = 4295048016
-        // MAX_SQRT_PRICE_X64 = 79226673521066979257578248091
-        // MIN_SQRT_PRICE_X64_ADD_ONE = 4295048017
-        // MAX_SQRT_PRICE_X64_SUB_ONE = 79226673521066979257578248090
-        const MIN_SQRT_PRICE_X64: u128 = 4295048016;
-        const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
-
-        // No price limit specified, use default limits matching SDK
-        // For baseIn (token0 -> token1): use minimum sqrt price + 1
-        // For baseOut (token1 -> token0): use maximum sqrt price - 1
-        let sqrt_price_limit_x64 = if is_token0_in {
-            // Selling (token0 -> token1): use minimum sqrt price + 1
-            MIN_SQRT_PRICE_X64 + 1
-        } else {
-            // Buying (token1 -> token0): use maximum sqrt price - 1
-            MAX_SQRT_PRICE_X64 - 1
-        };
+        // No price limit specified, use default limits matching Raydium SDK V2 (constants.ts):
+        // ZeroForOne -> MIN_SQRT_PRICE_X64_ADD_ONE, OneForZero -> MAX_SQRT_PRICE_X64_SUB_ONE
+        let sqrt_price_limit_x64 = direction.sqrt_price_limit_x64();
 
         // ========================================
         // Build instructions
@@ -845,45 +859,24 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
         }
 
         // Calculate tick arrays - CLMM requires multiple tick arrays for swap
-        // 根据官方 client 实现，需要获取多个 tick arrays（最多 5 个）
-        let zero_for_one = is_token0_in;
-        let mut tick_array_start_index =
-            crate::instruction::utils::raydium_clmm::get_first_initialized_tick_array_start_index(
-                &pool_state,
-                zero_for_one,
-            );
-
-        let mut tick_array_pdas = Vec::new();
-        let (first_tick_array_pda, _) =
-            get_tick_array_pda(&protocol_params.pool_state, tick_array_start_index)?;
-        tick_array_pdas.push(first_tick_array_pda);
-
-        // 获取后续的 tick arrays（最多 5 个）
-        let tick_spacing = pool_state.tick_spacing as i32;
-        const TICK_ARRAY_SIZE: i32 = 60; // raydium_amm_v3::states::TICK_ARRAY_SIZE
-        let ticks_per_array = tick_spacing * TICK_ARRAY_SIZE;
-
-        for _ in 0..4 {
-            tick_array_start_index = if zero_for_one {
-                tick_array_start_index - ticks_per_array
-            } else {
-                tick_array_start_index + ticks_per_array
-            };
-
-            // 检查是否超出范围
-            const MIN_TICK: i32 = -443636;
-            const MAX_TICK: i32 = 443636;
-            if (zero_for_one && tick_array_start_index < MIN_TICK)
-                || (!zero_for_one && tick_array_start_index > MAX_TICK)
-            {
-                break;
-            }
-
-            if let Ok((tick_array_pda, _)) =
-                get_tick_array_pda(&protocol_params.pool_state, tick_array_start_index)
-            {
-                tick_array_pdas.push(tick_array_pda);
-            }
+        // 从第一个已初始化的 array 起沿兑换方向注册 tick_array_depth 个
+        // 默认 bitmap 中附近没有已初始化的 array 时读取 bitmap extension
+        let tick_array_start_index = get_first_initialized_tick_array_start_index_with_extension(
+            rpc,
+            &protocol_params.pool_state,
+            &pool_state,
+            zero_for_one,
+        )
+        .await?;
+        let mut tick_array_pdas = Vec::with_capacity(tick_array_depth);
+        for start_index in tick_array_start_indices(
+            tick_array_start_index,
+            pool_state.tick_spacing,
+            zero_for_one,
+            tick_array_depth,
+        ) {
+            let (tick_array_pda, _) = get_tick_array_pda(&protocol_params.pool_state, start_index)?;
+            tick_array_pdas.push(tick_array_pda);
         }
 
         // Get tick array bitmap extension PDA (may not exist)
@@ -919,14 +912,13 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
             account_metas.push(AccountMeta::new(tick_array_pdas[i], false));
         }
 
-        // Create instruction data: discriminator (8 bytes) + amount (u64) + other_amount_threshold (u64) + sqrt_price_limit_x64 (u128) + is_base_input (bool)
-        // 使用 SwapV2 指令 discriminator
-        let mut data = vec![0u8; 41];
-        data[0..8].copy_from_slice(SWAP_V2_DISCRIMINATOR);
-        data[8..16].copy_from_slice(&amount_in.to_le_bytes());
-        data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
-        data[24..40].copy_from_slice(&sqrt_price_limit_x64.to_le_bytes());
-        data[40] = if is_token0_in { 1 } else { 0 }; // is_base_input
+        // is_base_input 由 RaydiumClmmParams::swap_mode 决定，与 token0/token1 哪个输入无关
+        let data = encode_swap_v2_data(
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            protocol_params.swap_mode,
+        );
 
         instructions.push(Instruction::new_with_bytes(
             accounts::RAYDIUM_CLMM,
@@ -940,4 +932,134 @@ impl InstructionBuilder for RaydiumClmmInstructionBuilder {
 
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        _mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumClmmParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumClmm"))?;
+        let (tick_array_bitmap_extension, _) =
+            crate::instruction::utils::raydium_clmm::get_tick_array_bitmap_extension_pda(
+                &protocol_params.pool_state,
+            );
+        Ok(vec![
+            protocol_params.amm_config,
+            protocol_params.pool_state,
+            protocol_params.token0_vault,
+            protocol_params.token1_vault,
+            protocol_params.observation_state,
+            crate::constants::TOKEN_PROGRAM,
+            crate::constants::TOKEN_2022_PROGRAM,
+            crate::constants::MEMO_PROGRAM,
+            protocol_params.token0_mint,
+            protocol_params.token1_mint,
+            tick_array_bitmap_extension,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::{SwapDirection, TickArrayDepth};
+
+    #[test]
+    fn test_swap_v2_data_matches_sdk_layout() {
+        // Raydium SDK V2 constants.ts: MIN_SQRT_PRICE_X64_ADD_ONE / MAX_SQRT_PRICE_X64_SUB_ONE
+        assert_eq!(SwapDirection::ZeroForOne.sqrt_price_limit_x64(), 4295048017);
+        assert_eq!(SwapDirection::OneForZero.sqrt_price_limit_x64(), 79226673521066979257578248090);
+
+        for direction in [SwapDirection::ZeroForOne, SwapDirection::OneForZero] {
+            let limit = direction.sqrt_price_limit_x64();
+            let exact_in = encode_swap_v2_data(1_000, 990, limit, SwapMode::ExactIn);
+            assert_eq!(exact_in.len(), 41);
+            assert_eq!(&exact_in[0..8], SWAP_V2_DISCRIMINATOR);
+            assert_eq!(u64::from_le_bytes(exact_in[8..16].try_into().unwrap()), 1_000);
+            assert_eq!(u64::from_le_bytes(exact_in[16..24].try_into().unwrap()), 990);
+            assert_eq!(u128::from_le_bytes(exact_in[24..40].try_into().unwrap()), limit);
+            assert_eq!(exact_in[40], 1);
+            let exact_out = encode_swap_v2_data(500, 1_000, limit, SwapMode::ExactOut);
+            assert_eq!(exact_out[40], 0);
+        }
+
+        // ExactIn: (输入, 最小输出)；ExactOut: (固定输出, 最大输入)
+        assert_eq!(
+            swap_amounts(SwapMode::ExactIn, 1_000, 2_000, None, Bps::new(100)).unwrap(),
+            (1_000, 1_980)
+        );
+        assert_eq!(
+            swap_amounts(SwapMode::ExactIn, 1_000, 2_000, Some(1_500), Bps::new(100)).unwrap(),
+            (1_000, 1_500)
+        );
+        assert_eq!(
+            swap_amounts(SwapMode::ExactOut, 1_000, 2_000, Some(1_500), Bps::new(100)).unwrap(),
+            (1_500, 1_000)
+        );
+        assert!(swap_amounts(SwapMode::ExactOut, 1_000, 2_000, None, Bps::new(100)).is_err());
+        assert!(swap_amounts(SwapMode::ExactOut, 1_000, 2_000, Some(0), Bps::new(100)).is_err());
+    }
+
+    #[test]
+    fn test_exact_in_exact_out_parity() {
+        let sqrt_price_x64 = 1u128 << 64;
+        let liquidity = 1_000_000_000_000u128;
+        let fee_rate = 2_500;
+        let amount_in = 1_000_000_000u64;
+        for direction in [SwapDirection::ZeroForOne, SwapDirection::OneForZero] {
+            let zero_for_one = direction.is_zero_for_one();
+            let target = direction.sqrt_price_limit_x64();
+            let exact_in = clmm_math::compute_swap_step(
+                sqrt_price_x64,
+                target,
+                liquidity,
+                amount_in,
+                fee_rate,
+                true,
+                zero_for_one,
+            )
+            .unwrap();
+            assert_eq!(exact_in.amount_in + exact_in.fee_amount, amount_in);
+
+            // 以 exact-in 的输出作为 exact-out 的目标，所需输入应与原输入一致（仅差舍入）
+            let exact_out = clmm_math::compute_swap_step(
+                sqrt_price_x64,
+                target,
+                liquidity,
+                exact_in.amount_out,
+                fee_rate,
+                false,
+                zero_for_one,
+            )
+            .unwrap();
+            assert_eq!(exact_out.amount_out, exact_in.amount_out);
+            let total_in = exact_out.amount_in + exact_out.fee_amount;
+            assert!(total_in.abs_diff(amount_in) <= amount_in / 10_000, "{:?}", direction);
+        }
+    }
+
+    #[test]
+    fn test_tick_array_depth_scales_with_trade_size() {
+        let sqrt_price_x64 = 1u128 << 64;
+        let liquidity = 1_000_000_000u128;
+        let small = estimate_tick_arrays_crossed(sqrt_price_x64, liquidity, 1_000, 1, true);
+        let large = estimate_tick_arrays_crossed(sqrt_price_x64, liquidity, 500_000_000, 1, false);
+        assert_eq!(small, 3);
+        assert!(large > small);
+        assert_eq!(estimate_tick_arrays_crossed(sqrt_price_x64, 0, 1_000, 1, true), usize::MAX);
+
+        let adaptive = TickArrayDepth::default();
+        assert_eq!(adaptive.resolve(small), 5);
+        assert_eq!(adaptive.resolve(usize::MAX), 10);
+        assert_eq!(TickArrayDepth::Fixed(3).resolve(large), 3);
+        assert_eq!(TickArrayDepth::Adaptive { min: 2, max: 8 }.resolve(large), large.min(8));
+
+        // 沿兑换方向排列，超出 tick 范围时截断
+        assert_eq!(tick_array_start_indices(0, 10, true, 3), vec![0, -600, -1200]);
+        assert_eq!(tick_array_start_indices(0, 10, false, 3), vec![0, 600, 1200]);
+        assert_eq!(tick_array_start_indices(443_400, 10, false, 5), vec![443_400]);
+    }
 }
@@ -0,0 +1,374 @@
+//! Raydium CLMM 流动性仓位指令
+//!
+//! 仓位以 NFT 表示：`open_position_v2` 铸造仓位 NFT 并注入流动性，
+//! `increase_liquidity_v2` / `decrease_liquidity_v2` 调整流动性（decrease 同时领取手续费与奖励），
+//! `close_position` 在流动性清零后销毁 NFT 并回收租金。
+//!
+//! 领取手续费即 `decrease_liquidity_v2` 且 `liquidity = 0`。
+
+use crate::{
+    common::fast_fn::{
+        get_associated_token_address_with_program_id_fast,
+        get_associated_token_address_with_program_id_fast_use_seed,
+    },
+    constants::{
+        ASSOCIATED_TOKEN_PROGRAM_ID, MEMO_PROGRAM, RENT, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM,
+        TOKEN_PROGRAM,
+    },
+    instruction::utils::{
+        raydium_clmm::{
+            accounts, get_personal_position_pda, get_position_metadata_pda,
+            get_protocol_position_pda, get_tick_array_bitmap_extension_pda, get_tick_array_pda,
+            get_tick_array_start_index,
+        },
+        raydium_clmm_types::PersonalPositionState,
+    },
+    trading::core::params::RaydiumClmmParams,
+};
+use anyhow::{Result, anyhow};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+const OPEN_POSITION_V2_DISCRIMINATOR: &[u8] = &[77, 184, 74, 214, 112, 86, 241, 199];
+const INCREASE_LIQUIDITY_V2_DISCRIMINATOR: &[u8] = &[133, 29, 89, 223, 69, 238, 176, 10];
+const DECREASE_LIQUIDITY_V2_DISCRIMINATOR: &[u8] = &[58, 127, 188, 62, 79, 82, 196, 96];
+const CLOSE_POSITION_DISCRIMINATOR: &[u8] = &[123, 134, 81, 0, 49, 68, 98, 98];
+
+/// 价格区间（tick 必须是 tick_spacing 的整数倍）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickRange {
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub tick_spacing: u16,
+}
+
+impl TickRange {
+    pub fn new(tick_lower: i32, tick_upper: i32, tick_spacing: u16) -> Result<Self> {
+        if tick_spacing == 0 {
+            return Err(anyhow!("tick_spacing must be non-zero"));
+        }
+        if tick_lower >= tick_upper {
+            return Err(anyhow!(
+                "tick_lower {} must be below tick_upper {}",
+                tick_lower,
+                tick_upper
+            ));
+        }
+        let spacing = tick_spacing as i32;
+        if tick_lower % spacing != 0 || tick_upper % spacing != 0 {
+            return Err(anyhow!(
+                "ticks [{}, {}] are not multiples of tick_spacing {}",
+                tick_lower,
+                tick_upper,
+                tick_spacing
+            ));
+        }
+        Ok(Self { tick_lower, tick_upper, tick_spacing })
+    }
+
+    fn tick_array_start_indices(&self) -> (i32, i32) {
+        (
+            get_tick_array_start_index(self.tick_lower, self.tick_spacing),
+            get_tick_array_start_index(self.tick_upper, self.tick_spacing),
+        )
+    }
+
+    fn tick_arrays(&self, pool_id: &Pubkey) -> Result<(Pubkey, Pubkey)> {
+        let (lower_start, upper_start) = self.tick_array_start_indices();
+        Ok((
+            get_tick_array_pda(pool_id, lower_start)?.0,
+            get_tick_array_pda(pool_id, upper_start)?.0,
+        ))
+    }
+}
+
+/// 仓位持有者在池两侧代币上的账户
+fn owner_token_accounts(
+    owner: &Pubkey,
+    pool: &RaydiumClmmParams,
+    use_seed: bool,
+) -> (Pubkey, Pubkey) {
+    (
+        get_associated_token_address_with_program_id_fast_use_seed(
+            owner,
+            &pool.token0_mint,
+            &pool.token0_program,
+            use_seed,
+        ),
+        get_associated_token_address_with_program_id_fast_use_seed(
+            owner,
+            &pool.token1_mint,
+            &pool.token1_program,
+            use_seed,
+        ),
+    )
+}
+
+fn position_nft_account(owner: &Pubkey, position_nft_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id_fast(owner, position_nft_mint, &TOKEN_PROGRAM)
+}
+
+/// 开仓：铸造仓位 NFT（`position_nft_mint` 需作为签名者）并注入流动性
+///
+/// `liquidity = 0` 且 `base_flag` 为 Some 时，由程序按 `amount_0_max`（true）
+/// 或 `amount_1_max`（false）计算流动性。
+#[allow(clippy::too_many_arguments)]
+pub fn open_position_v2(
+    payer: &Pubkey,
+    pool: &RaydiumClmmParams,
+    range: &TickRange,
+    position_nft_mint: &Pubkey,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    base_flag: Option<bool>,
+    with_metadata: bool,
+    use_seed: bool,
+) -> Result<Instruction> {
+    let (tick_array_lower_start, tick_array_upper_start) = range.tick_array_start_indices();
+    let (tick_array_lower, tick_array_upper) = range.tick_arrays(&pool.pool_state)?;
+    let (token_account_0, token_account_1) = owner_token_accounts(payer, pool, use_seed);
+
+    let mut data = Vec::with_capacity(8 + 4 * 4 + 16 + 8 * 2 + 1 + 2);
+    data.extend_from_slice(OPEN_POSITION_V2_DISCRIMINATOR);
+    data.extend_from_slice(&range.tick_lower.to_le_bytes());
+    data.extend_from_slice(&range.tick_upper.to_le_bytes());
+    data.extend_from_slice(&tick_array_lower_start.to_le_bytes());
+    data.extend_from_slice(&tick_array_upper_start.to_le_bytes());
+    data.extend_from_slice(&liquidity.to_le_bytes());
+    data.extend_from_slice(&amount_0_max.to_le_bytes());
+    data.extend_from_slice(&amount_1_max.to_le_bytes());
+    data.push(with_metadata as u8);
+    push_option_bool(&mut data, base_flag);
+
+    let accounts = vec![
+        AccountMeta::new(*payer, true),             // 0. Payer
+        AccountMeta::new_readonly(*payer, false),   // 1. Position NFT Owner
+        AccountMeta::new(*position_nft_mint, true), // 2. Position NFT Mint (signer)
+        AccountMeta::new(position_nft_account(payer, position_nft_mint), false), // 3. Position NFT Account
+        AccountMeta::new(get_position_metadata_pda(position_nft_mint), false), // 4. Metadata Account
+        AccountMeta::new(pool.pool_state, false),                              // 5. Pool State
+        AccountMeta::new(
+            get_protocol_position_pda(&pool.pool_state, range.tick_lower, range.tick_upper),
+            false,
+        ), // 6. Protocol Position
+        AccountMeta::new(tick_array_lower, false), // 7. Tick Array Lower
+        AccountMeta::new(tick_array_upper, false), // 8. Tick Array Upper
+        AccountMeta::new(get_personal_position_pda(position_nft_mint), false), // 9. Personal Position
+        AccountMeta::new(token_account_0, false), // 10. Token Account 0
+        AccountMeta::new(token_account_1, false), // 11. Token Account 1
+        AccountMeta::new(pool.token0_vault, false), // 12. Token Vault 0
+        AccountMeta::new(pool.token1_vault, false), // 13. Token Vault 1
+        AccountMeta::new_readonly(RENT, false),   // 14. Rent
+        AccountMeta::new_readonly(SYSTEM_PROGRAM, false), // 15. System Program
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false), // 16. Token Program
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false), // 17. Associated Token Program
+        AccountMeta::new_readonly(accounts::METADATA_PROGRAM, false),  // 18. Metadata Program
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false),          // 19. Token 2022 Program
+        AccountMeta::new_readonly(pool.token0_mint, false),            // 20. Vault 0 Mint
+        AccountMeta::new_readonly(pool.token1_mint, false),            // 21. Vault 1 Mint
+        // remainingAccounts: exTickArrayBitmap（区间超出默认 bitmap 范围时使用）
+        AccountMeta::new(get_tick_array_bitmap_extension_pda(&pool.pool_state).0, false),
+    ];
+    Ok(Instruction { program_id: accounts::RAYDIUM_CLMM, accounts, data })
+}
+
+/// 向已有仓位追加流动性
+#[allow(clippy::too_many_arguments)]
+pub fn increase_liquidity_v2(
+    owner: &Pubkey,
+    pool: &RaydiumClmmParams,
+    position: &PersonalPositionState,
+    tick_spacing: u16,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    base_flag: Option<bool>,
+    use_seed: bool,
+) -> Result<Instruction> {
+    let range = position_range(position, tick_spacing)?;
+    let (tick_array_lower, tick_array_upper) = range.tick_arrays(&pool.pool_state)?;
+    let (token_account_0, token_account_1) = owner_token_accounts(owner, pool, use_seed);
+
+    let mut data = Vec::with_capacity(8 + 16 + 8 * 2 + 2);
+    data.extend_from_slice(INCREASE_LIQUIDITY_V2_DISCRIMINATOR);
+    data.extend_from_slice(&liquidity.to_le_bytes());
+    data.extend_from_slice(&amount_0_max.to_le_bytes());
+    data.extend_from_slice(&amount_1_max.to_le_bytes());
+    push_option_bool(&mut data, base_flag);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner, true), // 0. NFT Owner
+        AccountMeta::new_readonly(position_nft_account(owner, &position.nft_mint), false), // 1. NFT Account
+        AccountMeta::new(pool.pool_state, false), // 2. Pool State
+        AccountMeta::new(
+            get_protocol_position_pda(&pool.pool_state, range.tick_lower, range.tick_upper),
+            false,
+        ), // 3. Protocol Position
+        AccountMeta::new(get_personal_position_pda(&position.nft_mint), false), // 4. Personal Position
+        AccountMeta::new(tick_array_lower, false), // 5. Tick Array Lower
+        AccountMeta::new(tick_array_upper, false), // 6. Tick Array Upper
+        AccountMeta::new(token_account_0, false),  // 7. Token Account 0
+        AccountMeta::new(token_account_1, false),  // 8. Token Account 1
+        AccountMeta::new(pool.token0_vault, false), // 9. Token Vault 0
+        AccountMeta::new(pool.token1_vault, false), // 10. Token Vault 1
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false), // 11. Token Program
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false), // 12. Token 2022 Program
+        AccountMeta::new_readonly(pool.token0_mint, false), // 13. Vault 0 Mint
+        AccountMeta::new_readonly(pool.token1_mint, false), // 14. Vault 1 Mint
+        AccountMeta::new(get_tick_array_bitmap_extension_pda(&pool.pool_state).0, false),
+    ];
+    Ok(Instruction { program_id: accounts::RAYDIUM_CLMM, accounts, data })
+}
+
+/// 减少仓位流动性，同时领取手续费与奖励（`liquidity = 0` 时仅领取）
+///
+/// `rewards` 为池中已初始化的奖励 `(reward_vault, reward_mint)`，
+/// 奖励领取到持有者的 ATA（需预先存在）。
+#[allow(clippy::too_many_arguments)]
+pub fn decrease_liquidity_v2(
+    owner: &Pubkey,
+    pool: &RaydiumClmmParams,
+    position: &PersonalPositionState,
+    tick_spacing: u16,
+    liquidity: u128,
+    amount_0_min: u64,
+    amount_1_min: u64,
+    rewards: &[(Pubkey, Pubkey)],
+    use_seed: bool,
+) -> Result<Instruction> {
+    let range = position_range(position, tick_spacing)?;
+    let (tick_array_lower, tick_array_upper) = range.tick_arrays(&pool.pool_state)?;
+    let (token_account_0, token_account_1) = owner_token_accounts(owner, pool, use_seed);
+
+    let mut data = Vec::with_capacity(8 + 16 + 8 * 2);
+    data.extend_from_slice(DECREASE_LIQUIDITY_V2_DISCRIMINATOR);
+    data.extend_from_slice(&liquidity.to_le_bytes());
+    data.extend_from_slice(&amount_0_min.to_le_bytes());
+    data.extend_from_slice(&amount_1_min.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, true), // 0. NFT Owner
+        AccountMeta::new_readonly(position_nft_account(owner, &position.nft_mint), false), // 1. NFT Account
+        AccountMeta::new(get_personal_position_pda(&position.nft_mint), false), // 2. Personal Position
+        AccountMeta::new(pool.pool_state, false),                               // 3. Pool State
+        AccountMeta::new(
+            get_protocol_position_pda(&pool.pool_state, range.tick_lower, range.tick_upper),
+            false,
+        ), // 4. Protocol Position
+        AccountMeta::new(pool.token0_vault, false),                             // 5. Token Vault 0
+        AccountMeta::new(pool.token1_vault, false),                             // 6. Token Vault 1
+        AccountMeta::new(tick_array_lower, false), // 7. Tick Array Lower
+        AccountMeta::new(tick_array_upper, false), // 8. Tick Array Upper
+        AccountMeta::new(token_account_0, false),  // 9. Recipient Token Account 0
+        AccountMeta::new(token_account_1, false),  // 10. Recipient Token Account 1
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false), // 11. Token Program
+        AccountMeta::new_readonly(TOKEN_2022_PROGRAM, false), // 12. Token 2022 Program
+        AccountMeta::new_readonly(MEMO_PROGRAM, false), // 13. Memo Program
+        AccountMeta::new_readonly(pool.token0_mint, false), // 14. Vault 0 Mint
+        AccountMeta::new_readonly(pool.token1_mint, false), // 15. Vault 1 Mint
+        AccountMeta::new(get_tick_array_bitmap_extension_pda(&pool.pool_state).0, false),
+    ];
+    // remainingAccounts: 每个奖励依次为 (reward vault, 接收账户, reward mint)
+    for (reward_vault, reward_mint) in rewards {
+        accounts.push(AccountMeta::new(*reward_vault, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id_fast(owner, reward_mint, &TOKEN_PROGRAM),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*reward_mint, false));
+    }
+    Ok(Instruction { program_id: accounts::RAYDIUM_CLMM, accounts, data })
+}
+
+/// 关闭仓位（流动性、手续费与奖励均已领取后），销毁 NFT 并回收租金
+pub fn close_position(owner: &Pubkey, position_nft_mint: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*owner, true),              // 0. NFT Owner
+        AccountMeta::new(*position_nft_mint, false), // 1. Position NFT Mint
+        AccountMeta::new(position_nft_account(owner, position_nft_mint), false), // 2. Position NFT Account
+        AccountMeta::new(get_personal_position_pda(position_nft_mint), false), // 3. Personal Position
+        AccountMeta::new_readonly(SYSTEM_PROGRAM, false),                      // 4. System Program
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false),                       // 5. Token Program
+    ];
+    Instruction {
+        program_id: accounts::RAYDIUM_CLMM,
+        accounts,
+        data: CLOSE_POSITION_DISCRIMINATOR.to_vec(),
+    }
+}
+
+fn position_range(position: &PersonalPositionState, tick_spacing: u16) -> Result<TickRange> {
+    TickRange::new(position.tick_lower_index, position.tick_upper_index, tick_spacing)
+}
+
+/// Borsh `Option<bool>` 编码
+fn push_option_bool(data: &mut Vec<u8>, value: Option<bool>) {
+    match value {
+        Some(value) => {
+            data.push(1);
+            data.push(value as u8);
+        },
+        None => data.push(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_range_validation() {
+        assert!(TickRange::new(-120, 120, 60).is_ok());
+        assert!(TickRange::new(-100, 120, 60).is_err());
+        assert!(TickRange::new(120, 120, 60).is_err());
+        assert!(TickRange::new(0, 60, 0).is_err());
+    }
+
+    #[test]
+    fn test_open_position_v2_layout() {
+        let payer = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let pool = RaydiumClmmParams {
+            pool_state: Pubkey::new_unique(),
+            amm_config: Pubkey::new_unique(),
+            token0_mint: Pubkey::new_unique(),
+            token1_mint: Pubkey::new_unique(),
+            token0_vault: Pubkey::new_unique(),
+            token1_vault: Pubkey::new_unique(),
+            observation_state: Pubkey::new_unique(),
+            token0_decimals: 9,
+            token1_decimals: 6,
+            token0_program: TOKEN_PROGRAM,
+            token1_program: TOKEN_PROGRAM,
+            swap_mode: Default::default(),
+            tick_array_depth: Default::default(),
+        };
+        let range = TickRange::new(-600, 600, 60).unwrap();
+        let ix = open_position_v2(
+            &payer,
+            &pool,
+            &range,
+            &nft_mint,
+            0,
+            1_000,
+            2_000,
+            Some(true),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(&ix.data[..8], OPEN_POSITION_V2_DISCRIMINATOR);
+        // disc + 4 * i32 + u128 + 2 * u64 + bool + Option<bool>
+        assert_eq!(ix.data.len(), 8 + 16 + 16 + 16 + 1 + 2);
+        assert_eq!(ix.accounts.len(), 23);
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(ix.accounts[5].pubkey, pool.pool_state);
+
+        let close = close_position(&payer, &nft_mint);
+        assert_eq!(close.data, CLOSE_POSITION_DISCRIMINATOR);
+        assert_eq!(close.accounts[3].pubkey, get_personal_position_pda(&nft_mint));
+    }
+}
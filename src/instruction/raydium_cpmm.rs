@@ -1,12 +1,16 @@
+use crate::trading::lifecycle::record_quote;
 use crate::{
-    common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed,
+    common::{
+        fast_fn::get_associated_token_address_with_program_id_fast_use_seed,
+        transfer_hook::append_transfer_hook_accounts,
+    },
     constants::trade_consts::DEFAULT_SLIPPAGE,
     instruction::utils::raydium_cpmm::{
         SWAP_BASE_IN_DISCRIMINATOR, accounts, get_observation_state_pda, get_pool_pda,
         get_vault_account,
     },
     trading::core::{
-        params::{RaydiumCpmmParams, SwapParams},
+        params::{DexParamEnum, RaydiumCpmmParams, SwapParams},
         traits::InstructionBuilder,
     },
     utils::calc::raydium_cpmm::compute_swap_amount,
@@ -85,6 +89,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             Some(fixed) => fixed,
             None => result.min_amount_out,
         };
+        record_quote(Some(result.amount_out), minimum_amount_out);
 
         let input_token_account = get_associated_token_address_with_program_id_fast_use_seed(
             &params.payer.pubkey(),
@@ -144,7 +149,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         }
 
         // Create buy instruction
-        let accounts: [AccountMeta; 13] = [
+        let mut accounts: Vec<AccountMeta> = vec![
             AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             accounts::AUTHORITY_META,                      // Authority (readonly)
             AccountMeta::new(protocol_params.amm_config, false), // Amm Config (readonly)
@@ -163,17 +168,23 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             AccountMeta::new_readonly(params.output_mint, false), // Output token mint (readonly)
             AccountMeta::new(observation_state_account, false), // Observation State Account
         ];
+        // Token-2022 transfer hook: vault -> user
+        append_transfer_hook_accounts(
+            &mut accounts,
+            protocol_params.transfer_hook_for(&params.output_mint),
+            &output_vault_account,
+            &params.output_mint,
+            &output_token_account,
+            &accounts::AUTHORITY,
+            minimum_amount_out,
+        )?;
         // Create instruction data
         let mut data = [0u8; 24];
         data[..8].copy_from_slice(SWAP_BASE_IN_DISCRIMINATOR);
         data[8..16].copy_from_slice(&amount_in.to_le_bytes());
         data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
 
-        instructions.push(Instruction::new_with_bytes(
-            accounts::RAYDIUM_CPMM,
-            &data,
-            accounts.to_vec(),
-        ));
+        instructions.push(Instruction::new_with_bytes(accounts::RAYDIUM_CPMM, &data, accounts));
 
         if params.close_input_mint_ata {
             // Close wSOL ATA account, reclaim rent
@@ -230,19 +241,18 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             protocol_params.quote_token_program
         };
 
+        let result = compute_swap_amount(
+            protocol_params.base_reserve,
+            protocol_params.quote_reserve,
+            is_quote_out,
+            params.input_amount.unwrap_or(0),
+            params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+        );
         let minimum_amount_out: u64 = match params.fixed_output_amount {
             Some(fixed) => fixed,
-            None => {
-                compute_swap_amount(
-                    protocol_params.base_reserve,
-                    protocol_params.quote_reserve,
-                    is_quote_out,
-                    params.input_amount.unwrap_or(0),
-                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-                )
-                .min_amount_out
-            },
+            None => result.min_amount_out,
         };
+        record_quote(Some(result.amount_out), minimum_amount_out);
 
         let output_token_account = get_associated_token_address_with_program_id_fast_use_seed(
             &params.payer.pubkey(),
@@ -289,7 +299,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         }
 
         // Create sell instruction
-        let accounts: [AccountMeta; 13] = [
+        let mut accounts: Vec<AccountMeta> = vec![
             AccountMeta::new(params.payer.pubkey(), true), // Payer (signer)
             accounts::AUTHORITY_META,                      // Authority (readonly)
             AccountMeta::new(protocol_params.amm_config, false), // Amm Config (readonly)
@@ -308,17 +318,23 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             }, // Output token mint (readonly)
             AccountMeta::new(observation_state_account, false), // Observation State Account
         ];
+        // Token-2022 transfer hook: user -> vault
+        append_transfer_hook_accounts(
+            &mut accounts,
+            protocol_params.transfer_hook_for(&params.input_mint),
+            &input_token_account,
+            &params.input_mint,
+            &input_vault_account,
+            &params.payer.pubkey(),
+            params.input_amount.unwrap_or(0),
+        )?;
         // Create instruction data
         let mut data = [0u8; 24];
         data[..8].copy_from_slice(SWAP_BASE_IN_DISCRIMINATOR);
         data[8..16].copy_from_slice(&params.input_amount.unwrap_or(0).to_le_bytes());
         data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
 
-        instructions.push(Instruction::new_with_bytes(
-            accounts::RAYDIUM_CPMM,
-            &data,
-            accounts.to_vec(),
-        ));
+        instructions.push(Instruction::new_with_bytes(accounts::RAYDIUM_CPMM, &data, accounts));
 
         if params.close_output_mint_ata {
             // Close wSOL ATA account, reclaim rent
@@ -336,4 +352,50 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
 
         Ok(instructions)
     }
+
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        _mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let protocol_params = protocol_params
+            .as_any()
+            .downcast_ref::<RaydiumCpmmParams>()
+            .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumCpmm"))?;
+        let pool_state = if protocol_params.pool_state == Pubkey::default() {
+            get_pool_pda(
+                &protocol_params.amm_config,
+                &protocol_params.base_mint,
+                &protocol_params.quote_mint,
+            )
+            .ok_or_else(|| anyhow!("RaydiumCpmm pool PDA not found"))?
+        } else {
+            protocol_params.pool_state
+        };
+        let observation_state = if protocol_params.observation_state == Pubkey::default() {
+            get_observation_state_pda(&pool_state)
+                .ok_or_else(|| anyhow!("RaydiumCpmm observation state PDA not found"))?
+        } else {
+            protocol_params.observation_state
+        };
+        let mut static_accounts = vec![
+            accounts::AUTHORITY,
+            protocol_params.amm_config,
+            pool_state,
+            get_vault_account(&pool_state, &protocol_params.base_mint, protocol_params),
+            get_vault_account(&pool_state, &protocol_params.quote_mint, protocol_params),
+            protocol_params.base_token_program,
+            protocol_params.quote_token_program,
+            protocol_params.base_mint,
+            protocol_params.quote_mint,
+            observation_state,
+        ];
+        for hook in [&protocol_params.base_transfer_hook, &protocol_params.quote_transfer_hook]
+            .into_iter()
+            .flatten()
+        {
+            static_accounts.extend(hook.static_accounts());
+        }
+        Ok(static_accounts)
+    }
 }
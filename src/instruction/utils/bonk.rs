@@ -1,5 +1,5 @@
 use crate::{
-    common::SolanaRpcClient,
+    common::{Bps, SolanaRpcClient, single_flight::get_account_coalesced},
     instruction::utils::bonk_types::{PoolState, pool_state_decode},
 };
 use anyhow::anyhow;
@@ -94,7 +94,7 @@ pub async fn get_pool_by_address(
         return Ok(pool);
     }
     // 2. RPC 查询
-    let account = rpc.get_account(pool_address).await?;
+    let account = get_account_coalesced(rpc, pool_address).await.map_err(|e| anyhow!(e))?;
     if account.owner != accounts::BONK {
         return Err(anyhow!("Account is not owned by Bonk program"));
     }
@@ -145,12 +145,13 @@ pub fn get_amount_in(
     virtual_quote: u128,
     real_base: u128,
     real_quote: u128,
-    slippage_basis_points: u128,
+    slippage_basis_points: Bps,
 ) -> u64 {
     let amount_out_u128 = amount_out as u128;
 
     // Consider slippage, actual required output amount is higher
-    let amount_out_with_slippage = amount_out_u128 * 10000 / (10000 - slippage_basis_points);
+    let amount_out_with_slippage =
+        amount_out_u128 * 10000 / (10000 - slippage_basis_points.capped().as_u128());
 
     let input_reserve = virtual_quote.checked_add(real_quote).unwrap();
     let output_reserve = virtual_base.checked_sub(real_base).unwrap();
@@ -177,7 +178,7 @@ pub fn get_amount_out(
     virtual_quote: u128,
     real_base: u128,
     real_quote: u128,
-    slippage_basis_points: u128,
+    slippage_basis_points: Bps,
 ) -> u64 {
     let amount_in_u128 = amount_in as u128;
     let protocol_fee = amount_in_u128 * protocol_fee_rate / 10000;
@@ -196,7 +197,7 @@ pub fn get_amount_out(
     let denominator = input_reserve.checked_add(amount_in_net).unwrap();
     let mut amount_out = numerator.checked_div(denominator).unwrap();
 
-    amount_out = amount_out - (amount_out * slippage_basis_points) / 10000;
+    amount_out = amount_out - (amount_out * slippage_basis_points.capped().as_u128()) / 10000;
     amount_out as u64
 }
 
@@ -224,6 +225,17 @@ pub fn get_vault_pda(pool_state: &Pubkey, mint: &Pubkey) -> Option<Pubkey> {
     )
 }
 
+/// 按计价代币选择 GlobalConfig：WSOL → [`accounts::GLOBAL_CONFIG`]，USD1 → [`accounts::USD1_GLOBAL_CONFIG`]
+pub fn resolve_global_config(quote_mint: &Pubkey) -> Option<Pubkey> {
+    if *quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT {
+        Some(accounts::GLOBAL_CONFIG)
+    } else if *quote_mint == crate::constants::USD1_TOKEN_ACCOUNT {
+        Some(accounts::USD1_GLOBAL_CONFIG)
+    } else {
+        None
+    }
+}
+
 pub fn get_platform_associated_account(platform_config: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] =
         &[platform_config.as_ref(), crate::constants::WSOL_TOKEN_ACCOUNT.as_ref()];
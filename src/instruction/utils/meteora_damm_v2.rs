@@ -1,6 +1,6 @@
 use crate::{
-    common::SolanaRpcClient,
-    instruction::utils::meteora_damm_v2_types::{Pool, pool_decode},
+    common::{auto_mock_rpc::PoolRpcClient, single_flight::get_account_coalesced},
+    instruction::utils::meteora_damm_v2_types::{Pool, pool_state_decode},
 };
 use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
@@ -62,8 +62,8 @@ pub(crate) mod meteora_cache {
     }
 }
 
-pub async fn get_pool_by_address(
-    rpc: &SolanaRpcClient,
+pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     pool_address: &Pubkey,
 ) -> Result<Pool, anyhow::Error> {
     // 1. 检查缓存
@@ -71,18 +71,20 @@ pub async fn get_pool_by_address(
         return Ok(pool);
     }
     // 2. RPC 查询
-    let account = rpc.get_account(pool_address).await?;
+    let account = get_account_coalesced(rpc, pool_address)
+        .await
+        .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
     if account.owner != accounts::METEORA_DAMM_V2 {
         return Err(anyhow!("Account is not owned by Meteora Damm V2 program"));
     }
-    let pool = pool_decode(&account.data[8..]).ok_or_else(|| anyhow!("Failed to decode pool"))?;
+    let pool = pool_state_decode(&account.data).ok_or_else(|| anyhow!("Failed to decode pool"))?;
     // 3. 写入缓存
     meteora_cache::cache_pool_by_address(pool_address, &pool);
     Ok(pool)
 }
 
-pub async fn get_pool_by_address_force(
-    rpc: &SolanaRpcClient,
+pub async fn get_pool_by_address_force<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     pool_address: &Pubkey,
 ) -> Result<Pool, anyhow::Error> {
     meteora_cache::POOL_DATA_CACHE.remove(pool_address);
@@ -97,3 +99,143 @@ pub fn clear_pool_cache() {
 pub fn get_event_authority_pda() -> Pubkey {
     Pubkey::find_program_address(&[seeds::EVENT_AUTHORITY_SEED], &accounts::METEORA_DAMM_V2).0
 }
+
+/// 获取池两侧 vault 的实时余额（支持 Auto Mock）
+///
+/// # Returns
+/// 返回 (token_a 余额, token_b 余额, token_a 精度, token_b 精度)，余额为最小单位。
+/// vault 余额包含尚未领取的协议/合作方手续费（`protocol_*_fee`、`partner_*_fee`）。
+pub async fn get_pool_reserves<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pool: &Pool,
+) -> Result<(u64, u64, u8, u8), anyhow::Error> {
+    let (token_a_balance, token_b_balance) = tokio::join!(
+        rpc.get_token_account_balance(&pool.token_a_vault),
+        rpc.get_token_account_balance(&pool.token_b_vault),
+    );
+    let token_a_balance = token_a_balance.map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+    let token_b_balance = token_b_balance.map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+
+    let token_a_amount = token_a_balance
+        .amount
+        .parse::<u64>()
+        .map_err(|e| anyhow!("Failed to parse token_a balance: {}", e))?;
+    let token_b_amount = token_b_balance
+        .amount
+        .parse::<u64>()
+        .map_err(|e| anyhow!("Failed to parse token_b balance: {}", e))?;
+
+    crate::utils::token::cache_token_decimals(pool.token_a_mint, token_a_balance.decimals);
+    crate::utils::token::cache_token_decimals(pool.token_b_mint, token_b_balance.decimals);
+    Ok((token_a_amount, token_b_amount, token_a_balance.decimals, token_b_balance.decimals))
+}
+
+/// 获取池的现价：1 个 token A 可兑换的 token B 数量（已按精度换算）
+///
+/// 强制刷新池状态以读取最新 `sqrt_price`。
+pub async fn get_pool_spot_price<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pool_address: &Pubkey,
+) -> Result<f64, anyhow::Error> {
+    let pool = get_pool_by_address_force(rpc, pool_address).await?;
+    let (_, _, decimals_a, decimals_b) = get_pool_reserves(rpc, &pool).await?;
+    let price = crate::utils::price::meteora_damm_v2::price_token_a_in_token_b(
+        pool.sqrt_price,
+        decimals_a,
+        decimals_b,
+    );
+    if !price.is_finite() || price <= 0.0 {
+        return Err(anyhow!("Invalid spot price for Meteora DAMM V2 pool {}", pool_address));
+    }
+    Ok(price)
+}
+
+/// DAMM V2 费率分母
+pub const FEE_DENOMINATOR: u64 = 1_000_000_000;
+
+/// 单一价格区间内的 exact-in 兑换输出（不含手续费）
+///
+/// `sqrt_price_x64` 为 Q64.64；`a_to_b` 为 true 时输入 token A。超出区间边界的部分不做截断。
+pub fn concentrated_swap_out(
+    liquidity: u128,
+    sqrt_price_x64: u128,
+    amount_in: u64,
+    a_to_b: bool,
+) -> u64 {
+    if liquidity == 0 || sqrt_price_x64 == 0 || amount_in == 0 {
+        return 0;
+    }
+    let l = liquidity as f64;
+    let sqrt_p = sqrt_price_x64 as f64 / 2f64.powi(64);
+    let amount_in = amount_in as f64;
+    let amount_out = if a_to_b {
+        // 1/sqrtP' = 1/sqrtP + Δa/L，Δb = L * (sqrtP - sqrtP')
+        let sqrt_p_next = 1.0 / (1.0 / sqrt_p + amount_in / l);
+        l * (sqrt_p - sqrt_p_next)
+    } else {
+        // sqrtP' = sqrtP + Δb/L，Δa = L * (1/sqrtP - 1/sqrtP')
+        let sqrt_p_next = sqrt_p + amount_in / l;
+        l * (1.0 / sqrt_p - 1.0 / sqrt_p_next)
+    };
+    if amount_out.is_finite() && amount_out > 0.0 { amount_out as u64 } else { 0 }
+}
+
+/// Quote an exact-in swap against a Meteora DAMM V2 pool.
+///
+/// - If `a_to_b=true`: token A -> token B
+/// - If `a_to_b=false`: token B -> token A
+///
+/// 按基础费率（cliff fee）从输入中扣除手续费，不计动态费率与费率调度。
+pub async fn quote_exact_in<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pool_address: &Pubkey,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Result<crate::utils::quote::QuoteExactInResult, anyhow::Error> {
+    let pool = get_pool_by_address_force(rpc, pool_address).await?;
+    let fee_numerator = pool.pool_fees.base_fee.cliff_fee_numerator.min(FEE_DENOMINATOR);
+    let fee_amount =
+        (amount_in as u128 * fee_numerator as u128).div_ceil(FEE_DENOMINATOR as u128) as u64;
+    let amount_out =
+        concentrated_swap_out(pool.liquidity, pool.sqrt_price, amount_in - fee_amount, a_to_b);
+    Ok(crate::utils::quote::QuoteExactInResult {
+        amount_out,
+        fee_amount,
+        price_impact_bps: None,
+        extra_accounts_read: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+    use crate::instruction::utils::meteora_damm_v2_types::{POOL_DISCRIMINATOR, POOL_SIZE};
+
+    #[test]
+    fn test_pool_state_decode_checks_discriminator() {
+        let mut data = POOL_DISCRIMINATOR.to_vec();
+        data.extend(vec![0u8; POOL_SIZE]);
+        let pool = pool_state_decode(&data).unwrap();
+        assert_eq!(pool.token_a_program(), TOKEN_PROGRAM);
+        assert_eq!(Pool { token_b_flag: 1, ..pool }.token_b_program(), TOKEN_PROGRAM_2022);
+
+        data[0] ^= 0xff;
+        assert!(pool_state_decode(&data).is_none());
+        assert!(pool_state_decode(&POOL_DISCRIMINATOR).is_none());
+    }
+
+    #[test]
+    fn test_concentrated_swap_out() {
+        // sqrtP = 1（价格 1:1），L 远大于输入时输出接近输入
+        let one = 1u128 << 64;
+        let out = concentrated_swap_out(1_000_000_000_000, one, 1_000, true);
+        assert!((998..=1_000).contains(&out));
+        // sqrtP = 2（1 A = 4 B）
+        let out = concentrated_swap_out(1_000_000_000_000, 2 * one, 1_000, true);
+        assert!((3_990..=4_000).contains(&out));
+        let out = concentrated_swap_out(1_000_000_000_000, 2 * one, 4_000, false);
+        assert!((990..=1_000).contains(&out));
+        assert_eq!(concentrated_swap_out(0, one, 1_000, true), 0);
+    }
+}
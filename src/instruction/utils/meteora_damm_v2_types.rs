@@ -112,3 +112,30 @@ pub fn pool_decode(data: &[u8]) -> Option<Pool> {
     }
     borsh::from_slice::<Pool>(&data[..POOL_SIZE]).ok()
 }
+
+/// Anchor 账户标识：sha256("account:Pool")[..8]
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// 从完整账户数据解析 Pool（校验 8 字节账户标识）
+pub fn pool_state_decode(account_data: &[u8]) -> Option<Pool> {
+    if account_data.len() < 8 || account_data[..8] != POOL_DISCRIMINATOR {
+        return None;
+    }
+    pool_decode(&account_data[8..])
+}
+
+impl Pool {
+    /// token A 所属代币程序（`token_a_flag`: 0 = SPL Token, 1 = Token-2022）
+    pub fn token_a_program(&self) -> Pubkey {
+        token_program_from_flag(self.token_a_flag)
+    }
+
+    /// token B 所属代币程序
+    pub fn token_b_program(&self) -> Pubkey {
+        token_program_from_flag(self.token_b_flag)
+    }
+}
+
+fn token_program_from_flag(flag: u8) -> Pubkey {
+    if flag == 1 { crate::constants::TOKEN_PROGRAM_2022 } else { crate::constants::TOKEN_PROGRAM }
+}
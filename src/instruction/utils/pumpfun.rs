@@ -238,13 +238,51 @@ pub async fn fetch_bonding_curve_account(
         return Err(anyhow!("Bonding curve not found"));
     }
 
-    let bonding_curve =
-        solana_sdk::borsh1::try_from_slice_unchecked::<BondingCurveAccount>(&account.data[8..])
-            .map_err(|e| anyhow::anyhow!("Failed to deserialize bonding curve account: {}", e))?;
+    let mut bonding_curve = decode_bonding_curve_account(&account.data)?;
+    bonding_curve.account = bonding_curve_pda;
 
     Ok((Arc::new(bonding_curve), bonding_curve_pda))
 }
 
+/// bonding curve 账户中 `creator` 字段的偏移（含 8 字节 discriminator）
+const BONDING_CURVE_CREATOR_OFFSET: usize = 49;
+/// bonding curve 账户中 `is_mayhem_mode` 字段的偏移
+pub const BONDING_CURVE_MAYHEM_OFFSET: usize = 81;
+
+/// 解析 bonding curve 账户数据（含 discriminator）
+///
+/// 兼容旧布局：没有 `creator` 的账户 creator 为默认值，没有 `is_mayhem_mode` 的账户视为非 Mayhem。
+pub fn decode_bonding_curve_account(data: &[u8]) -> Result<BondingCurveAccount, anyhow::Error> {
+    if data.len() < BONDING_CURVE_CREATOR_OFFSET {
+        return Err(anyhow!("Bonding curve account too short: {} bytes", data.len()));
+    }
+    let read_u64 = |offset: usize| {
+        u64::from_le_bytes(data[offset..offset + 8].try_into().expect("slice length is 8"))
+    };
+    let creator = data
+        .get(BONDING_CURVE_CREATOR_OFFSET..BONDING_CURVE_MAYHEM_OFFSET)
+        .map(|bytes| Pubkey::new_from_array(bytes.try_into().expect("slice length is 32")))
+        .unwrap_or_default();
+    Ok(BondingCurveAccount {
+        discriminator: read_u64(0),
+        account: Pubkey::default(),
+        virtual_token_reserves: read_u64(8),
+        virtual_sol_reserves: read_u64(16),
+        real_token_reserves: read_u64(24),
+        real_sol_reserves: read_u64(32),
+        token_total_supply: read_u64(40),
+        complete: data[48] != 0,
+        creator,
+        is_mayhem_mode: is_mayhem_bonding_curve(data),
+    })
+}
+
+/// 根据 bonding curve 账户数据判断是否为 Mayhem 模式（Token-2022，手续费进入 Mayhem 接收地址）
+#[inline]
+pub fn is_mayhem_bonding_curve(data: &[u8]) -> bool {
+    data.get(BONDING_CURVE_MAYHEM_OFFSET).is_some_and(|flag| *flag != 0)
+}
+
 #[inline]
 pub fn get_buy_price(
     amount: u64,
@@ -264,3 +302,31 @@ pub fn get_buy_price(
 
     s_u64.min(real_token_reserves)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_bonding_curve_account_layouts() {
+        let creator = Pubkey::new_unique();
+        let mut data = vec![0u8; 150];
+        data[8..16].copy_from_slice(&1_000u64.to_le_bytes());
+        data[16..24].copy_from_slice(&2_000u64.to_le_bytes());
+        data[BONDING_CURVE_CREATOR_OFFSET..BONDING_CURVE_MAYHEM_OFFSET]
+            .copy_from_slice(creator.as_ref());
+        data[BONDING_CURVE_MAYHEM_OFFSET] = 1;
+
+        let curve = decode_bonding_curve_account(&data).unwrap();
+        assert_eq!(curve.virtual_token_reserves, 1_000);
+        assert_eq!(curve.virtual_sol_reserves, 2_000);
+        assert_eq!(curve.creator, creator);
+        assert!(curve.is_mayhem_mode);
+
+        // 旧布局：没有 mayhem 标志
+        let legacy = decode_bonding_curve_account(&data[..BONDING_CURVE_MAYHEM_OFFSET]).unwrap();
+        assert!(!legacy.is_mayhem_mode);
+        assert_eq!(legacy.creator, creator);
+        assert!(decode_bonding_curve_account(&data[..40]).is_err());
+    }
+}
@@ -1,10 +1,9 @@
 use crate::{
-    common::{
-        SolanaRpcClient, auto_mock_rpc::PoolRpcClient,
-        spl_associated_token_account::get_associated_token_address_with_program_id,
-    },
+    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient, single_flight::get_account_coalesced},
     constants::{TOKEN_PROGRAM, USDC_MINT, USDT_MINT, WSOL_TOKEN_ACCOUNT},
-    instruction::utils::pumpswap_types::{Pool, pool_decode},
+    instruction::utils::pumpswap_types::{
+        Pool, PumpSwapFeeConfig, global_config_fee_decode, pool_decode,
+    },
 };
 use anyhow::anyhow;
 use base64::Engine;
@@ -13,7 +12,7 @@ use solana_account_decoder::{UiAccountData, UiAccountEncoding};
 use solana_sdk::{pubkey, pubkey::Pubkey};
 
 /// Raydium CLMM WSOL-USDT 锚定池（用于 USD 价格计算）
-/// 如果不传入锚定池参数，默认使用此池
+/// `PricingConfig::default()` 的锚定池；可通过 `PricingConfig::with_anchor_pools` 替换
 pub const DEFAULT_WSOL_USDT_CLMM_POOL: Pubkey =
     pubkey!("ExcBWu8fGPdJiaF1b1z3iEef38sjQJks8xvj6M85pPY6");
 
@@ -197,16 +196,6 @@ pub(crate) fn coin_creator_vault_authority(coin_creator: Pubkey) -> Pubkey {
     pump_pool_authority
 }
 
-pub(crate) fn coin_creator_vault_ata(coin_creator: Pubkey, quote_mint: Pubkey) -> Pubkey {
-    let creator_vault_authority = coin_creator_vault_authority(coin_creator);
-
-    get_associated_token_address_with_program_id(
-        &creator_vault_authority,
-        &quote_mint,
-        &TOKEN_PROGRAM,
-    )
-}
-
 pub(crate) fn fee_recipient_ata(fee_recipient: Pubkey, quote_mint: Pubkey) -> Pubkey {
     crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
         &fee_recipient,
@@ -246,8 +235,7 @@ pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
         return Ok(pool);
     }
     // 2. RPC 查询
-    let account = rpc
-        .get_account(pool_address)
+    let account = get_account_coalesced(rpc, pool_address)
         .await
         .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
     if account.owner != accounts::AMM_PROGRAM {
@@ -640,10 +628,19 @@ pub async fn list_pools_by_mint<T: PoolRpcClient + ?Sized>(
 pub async fn get_token_balances<T: PoolRpcClient + ?Sized>(
     pool: &Pool,
     rpc: &T,
+) -> Result<(u64, u64), anyhow::Error> {
+    get_vault_balances(rpc, &pool.pool_base_token_account, &pool.pool_quote_token_account).await
+}
+
+/// 并发读取池子 base / quote token 账户余额（即池子储备）
+pub async fn get_vault_balances<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pool_base_token_account: &Pubkey,
+    pool_quote_token_account: &Pubkey,
 ) -> Result<(u64, u64), anyhow::Error> {
     let (base_balance_result, quote_balance_result) = tokio::join!(
-        rpc.get_token_account_balance(&pool.pool_base_token_account),
-        rpc.get_token_account_balance(&pool.pool_quote_token_account),
+        rpc.get_token_account_balance(pool_base_token_account),
+        rpc.get_token_account_balance(pool_quote_token_account),
     );
 
     let base_balance =
@@ -664,12 +661,30 @@ pub async fn get_token_balances<T: PoolRpcClient + ?Sized>(
     Ok((base_amount, quote_amount))
 }
 
+/// 从 GlobalConfig 账户读取当前费率配置
+pub async fn get_fee_config<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+) -> Result<PumpSwapFeeConfig, anyhow::Error> {
+    let account = rpc
+        .get_account(&accounts::GLOBAL_ACCOUNT)
+        .await
+        .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+    if account.owner != accounts::AMM_PROGRAM {
+        return Err(anyhow!("GlobalConfig is not owned by PumpSwap program"));
+    }
+    account
+        .data
+        .get(8..)
+        .and_then(global_config_fee_decode)
+        .ok_or_else(|| anyhow!("Failed to decode PumpSwap GlobalConfig"))
+}
+
 /// Quote an exact-in swap against a PumpSwap pool.
 ///
 /// - If `is_base_in=true`: base -> quote
 /// - If `is_base_in=false`: quote -> base
-pub async fn quote_exact_in(
-    rpc: &SolanaRpcClient,
+pub async fn quote_exact_in<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     pool_address: &Pubkey,
     amount_in: u64,
     is_base_in: bool,
@@ -681,7 +696,7 @@ pub async fn quote_exact_in(
         // base -> quote
         let r = crate::utils::calc::pumpswap::sell_base_input_internal(
             amount_in,
-            0,
+            crate::common::Bps::ZERO,
             base_reserve,
             quote_reserve,
             &pool.coin_creator,
@@ -692,14 +707,17 @@ pub async fn quote_exact_in(
         Ok(crate::utils::quote::QuoteExactInResult {
             amount_out: r.ui_quote,
             fee_amount: 0,
-            price_impact_bps: None,
+            price_impact_bps: Some(crate::utils::quote::constant_product_impact_bps(
+                base_reserve,
+                amount_in,
+            )),
             extra_accounts_read: 2, // two token accounts
         })
     } else {
         // quote -> base
         let r = crate::utils::calc::pumpswap::buy_quote_input_internal(
             amount_in,
-            0,
+            crate::common::Bps::ZERO,
             base_reserve,
             quote_reserve,
             &pool.coin_creator,
@@ -710,7 +728,10 @@ pub async fn quote_exact_in(
         Ok(crate::utils::quote::QuoteExactInResult {
             amount_out: r.base,
             fee_amount,
-            price_impact_bps: None,
+            price_impact_bps: Some(crate::utils::quote::constant_product_impact_bps(
+                quote_reserve,
+                amount_in,
+            )),
             extra_accounts_read: 2,
         })
     }
@@ -725,12 +746,13 @@ pub async fn get_token_price_in_usd(
     token_mint: &Pubkey,
     wsol_usd_clmm_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_clmm_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
-    use crate::constants::{SOL_MINT, USDC_MINT, USDT_MINT, WSOL_TOKEN_ACCOUNT};
+    use crate::constants::{SOL_MINT, WSOL_TOKEN_ACCOUNT};
     use crate::utils::price::pumpswap::{price_base_in_quote, price_quote_in_base};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
@@ -738,7 +760,7 @@ pub async fn get_token_price_in_usd(
     if *token_mint == SOL_MINT || *token_mint == WSOL_TOKEN_ACCOUNT {
         return crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await;
     }
@@ -784,7 +806,7 @@ pub async fn get_token_price_in_usd(
     let price_wsol_in_usd =
         crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await?;
 
@@ -808,12 +830,13 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     x_wsol_pool_address: &Pubkey,
     wsol_usd_clmm_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_clmm_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
-    use crate::constants::{SOL_MINT, USDC_MINT, USDT_MINT, WSOL_TOKEN_ACCOUNT};
+    use crate::constants::{SOL_MINT, WSOL_TOKEN_ACCOUNT};
     use crate::utils::price::pumpswap::{price_base_in_quote, price_quote_in_base};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
@@ -821,7 +844,7 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     if *token_mint == SOL_MINT || *token_mint == WSOL_TOKEN_ACCOUNT {
         return crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await;
     }
@@ -866,7 +889,7 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     let price_wsol_in_usd =
         crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await?;
 
@@ -904,4 +927,24 @@ mod tests {
         assert_eq!(usdc_price, 1.0);
         assert_eq!(usdt_price, 1.0);
     }
+
+    #[test]
+    fn test_global_config_fee_decode() {
+        let mut data = vec![0u8; 32 + 8 + 8 + 1 + 32 * 8 + 8 + 32];
+        data[32..40].copy_from_slice(&20u64.to_le_bytes());
+        data[40..48].copy_from_slice(&5u64.to_le_bytes());
+        data[32 + 8 + 8 + 1 + 32 * 8..][..8].copy_from_slice(&5u64.to_le_bytes());
+        let fees = global_config_fee_decode(&data).unwrap();
+        assert_eq!(
+            fees,
+            PumpSwapFeeConfig {
+                lp_fee_basis_points: 20,
+                protocol_fee_basis_points: 5,
+                coin_creator_fee_basis_points: 5,
+            }
+        );
+        assert_eq!(fees.total_fee_basis_points(true), 30);
+        assert_eq!(fees.total_fee_basis_points(false), 25);
+        assert!(global_config_fee_decode(&data[..100]).is_none());
+    }
 }
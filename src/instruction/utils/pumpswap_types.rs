@@ -25,3 +25,50 @@ pub fn pool_decode(data: &[u8]) -> Option<Pool> {
     }
     borsh::from_slice::<Pool>(&data[..POOL_SIZE]).ok()
 }
+
+/// PumpSwap GlobalConfig 中与费率相关的字段
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PumpSwapFeeConfig {
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub coin_creator_fee_basis_points: u64,
+}
+
+impl Default for PumpSwapFeeConfig {
+    fn default() -> Self {
+        use crate::instruction::utils::pumpswap::accounts::{
+            COIN_CREATOR_FEE_BASIS_POINTS, LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS,
+        };
+        Self {
+            lp_fee_basis_points: LP_FEE_BASIS_POINTS,
+            protocol_fee_basis_points: PROTOCOL_FEE_BASIS_POINTS,
+            coin_creator_fee_basis_points: COIN_CREATOR_FEE_BASIS_POINTS,
+        }
+    }
+}
+
+impl PumpSwapFeeConfig {
+    /// 总费率；没有 coin creator 的池子不收 creator fee
+    pub fn total_fee_basis_points(&self, has_coin_creator: bool) -> u64 {
+        self.lp_fee_basis_points
+            + self.protocol_fee_basis_points
+            + if has_coin_creator { self.coin_creator_fee_basis_points } else { 0 }
+    }
+}
+
+// GlobalConfig 布局（不含 8 字节 discriminator）：
+// admin(32) | lp_fee_basis_points(8) | protocol_fee_basis_points(8) | disable_flags(1)
+// | protocol_fee_recipients(32 * 8) | coin_creator_fee_basis_points(8) | ...
+const GLOBAL_CONFIG_LP_FEE_OFFSET: usize = 32;
+const GLOBAL_CONFIG_CREATOR_FEE_OFFSET: usize = 32 + 8 + 8 + 1 + 32 * 8;
+
+pub fn global_config_fee_decode(data: &[u8]) -> Option<PumpSwapFeeConfig> {
+    let read_u64 = |offset: usize| {
+        data.get(offset..offset + 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    };
+    Some(PumpSwapFeeConfig {
+        lp_fee_basis_points: read_u64(GLOBAL_CONFIG_LP_FEE_OFFSET)?,
+        protocol_fee_basis_points: read_u64(GLOBAL_CONFIG_LP_FEE_OFFSET + 8)?,
+        coin_creator_fee_basis_points: read_u64(GLOBAL_CONFIG_CREATOR_FEE_OFFSET)?,
+    })
+}
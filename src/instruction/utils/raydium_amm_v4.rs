@@ -2,7 +2,7 @@
 #![allow(clippy::doc_markdown)]
 
 use crate::{
-    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient},
+    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient, single_flight::get_account_coalesced},
     constants::{SOL_MINT, USDC_MINT, USDT_MINT},
     instruction::utils::raydium_amm_v4_types::{AMM_INFO_SIZE, AmmInfo, amm_info_decode},
 };
@@ -14,7 +14,7 @@ use once_cell::sync::Lazy;
 use solana_sdk::{pubkey, pubkey::Pubkey};
 
 /// Raydium CLMM WSOL-USDT 锚定池（用于 USD 价格计算）
-/// 如果不传入锚定池参数，默认使用此池
+/// `PricingConfig::default()` 的锚定池；可通过 `PricingConfig::with_anchor_pools` 替换
 pub const DEFAULT_WSOL_USDT_CLMM_POOL: Pubkey =
     pubkey!("ExcBWu8fGPdJiaF1b1z3iEef38sjQJks8xvj6M85pPY6");
 
@@ -70,6 +70,8 @@ pub(crate) fn get_cached_pool_by_address(pool_address: &Pubkey) -> Option<AmmInf
 
 /// 将 Pool 信息写入缓存
 pub(crate) fn cache_pool_by_address(pool_address: &Pubkey, amm_info: &AmmInfo) {
+    crate::utils::token::cache_token_decimals(amm_info.coin_mint, amm_info.coin_decimals as u8);
+    crate::utils::token::cache_token_decimals(amm_info.pc_mint, amm_info.pc_decimals as u8);
     POOL_DATA_CACHE.insert(*pool_address, amm_info.clone());
 }
 
@@ -120,8 +122,7 @@ pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
     }
 
     // 2. RPC 查询
-    let account = rpc
-        .get_account(pool_address)
+    let account = get_account_coalesced(rpc, pool_address)
         .await
         .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
     if account.owner != accounts::RAYDIUM_AMM_V4 {
@@ -139,8 +140,8 @@ pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
 /// 强制刷新：强制重新查询指定 Pool
 ///
 /// 先从缓存中删除该 Pool，然后重新查询并写入缓存。
-pub async fn get_pool_by_address_force(
-    rpc: &SolanaRpcClient,
+pub async fn get_pool_by_address_force<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     pool_address: &Pubkey,
 ) -> Result<AmmInfo, anyhow::Error> {
     POOL_DATA_CACHE.remove(pool_address);
@@ -547,8 +548,8 @@ pub async fn get_pool_by_mint<T: PoolRpcClient + ?Sized>(
 /// 强制刷新：强制重新查询指定 mint 对应的最优 Pool
 ///
 /// 先从 mint → pool_address 缓存中删除该 mint，然后重新查询并写入缓存。
-pub async fn get_pool_by_mint_force(
-    rpc: &SolanaRpcClient,
+pub async fn get_pool_by_mint_force<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     mint: &Pubkey,
 ) -> Result<(Pubkey, AmmInfo), anyhow::Error> {
     MINT_TO_POOL_CACHE.remove(mint);
@@ -652,20 +653,128 @@ pub async fn list_pools_by_mint<T: PoolRpcClient + ?Sized>(
     Ok(sorted_pools)
 }
 
+/// 两跳计价时最多尝试的中间代币数
+const MAX_HOP_CANDIDATES: usize = 3;
+
+/// 按池子实时余额计算 `mint` 以池中另一侧代币计的价格，返回（价格，另一侧 mint）
+async fn price_in_pair(
+    rpc: &SolanaRpcClient,
+    amm: &AmmInfo,
+    mint: &Pubkey,
+) -> Result<(f64, Pubkey), anyhow::Error> {
+    use crate::utils::price::raydium_amm_v4::{price_base_in_quote, price_quote_in_base};
+
+    let is_coin = amm.coin_mint == *mint;
+    if !is_coin && amm.pc_mint != *mint {
+        return Err(anyhow!("AMM V4 pool does not contain mint {}", mint));
+    }
+    let (coin, pc) = tokio::try_join!(
+        rpc.get_token_account_balance(&amm.token_coin),
+        rpc.get_token_account_balance(&amm.token_pc)
+    )?;
+    let coin_reserve: u64 = coin.amount.parse()?;
+    let pc_reserve: u64 = pc.amount.parse()?;
+    let (price, other_mint) = if is_coin {
+        (price_base_in_quote(coin_reserve, pc_reserve, coin.decimals, pc.decimals), amm.pc_mint)
+    } else {
+        (price_quote_in_base(coin_reserve, pc_reserve, coin.decimals, pc.decimals), amm.coin_mint)
+    };
+    if !(price > 0.0 && price.is_finite()) {
+        return Err(anyhow!("Computed {} price on AMM V4 is invalid: {}", mint, price));
+    }
+    Ok((price, other_mint))
+}
+
+/// 单跳计价：`mint` 本身是稳定币 / WSOL，或其最优池与 WSOL / 稳定币配对
+async fn get_direct_usd_price(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    wsol_usd_clmm_pool_address: Option<&Pubkey>,
+) -> Result<f64, anyhow::Error> {
+    let pricing = crate::common::pricing::pricing_config();
+    if pricing.is_stablecoin(mint) {
+        return Ok(1.0);
+    }
+    let wsol_price = || {
+        crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
+            rpc,
+            wsol_usd_clmm_pool_address,
+        )
+    };
+    if *mint == SOL_MINT {
+        return wsol_price().await;
+    }
+
+    let (_, amm) = get_pool_by_mint(rpc, mint).await?;
+    let (price, other_mint) = price_in_pair(rpc, &amm, mint).await?;
+    if pricing.is_stablecoin(&other_mint) {
+        Ok(price)
+    } else if other_mint == SOL_MINT {
+        Ok(price * wsol_price().await?)
+    } else {
+        Err(anyhow!("Best AMM V4 pool for mint {} is paired with {}", mint, other_mint))
+    }
+}
+
+/// 两跳计价：X -> Y -> WSOL/稳定币 -> USD
+///
+/// 按池子优先级依次尝试 X 的配对代币 Y（最多 [`MAX_HOP_CANDIDATES`] 个），
+/// 第一个能以单跳换算为 USD 的 Y 为准。
+async fn get_token_price_in_usd_two_hop(
+    rpc: &SolanaRpcClient,
+    token_mint: &Pubkey,
+    wsol_usd_clmm_pool_address: Option<&Pubkey>,
+) -> Result<f64, anyhow::Error> {
+    let pools = list_pools_by_mint(rpc, token_mint, true).await?;
+    let mut tried = Vec::with_capacity(MAX_HOP_CANDIDATES);
+    let mut last_error = None;
+    for (pool_address, amm) in &pools {
+        let y_mint = if amm.coin_mint == *token_mint { amm.pc_mint } else { amm.coin_mint };
+        if tried.contains(&y_mint) {
+            continue;
+        }
+        if tried.len() == MAX_HOP_CANDIDATES {
+            break;
+        }
+        tried.push(y_mint);
+
+        let result = async {
+            let amm = get_pool_by_address_force(rpc, pool_address).await?;
+            let (price_x_in_y, _) = price_in_pair(rpc, &amm, token_mint).await?;
+            let price_y_in_usd =
+                get_direct_usd_price(rpc, &y_mint, wsol_usd_clmm_pool_address).await?;
+            Ok::<_, anyhow::Error>(price_x_in_y * price_y_in_usd)
+        }
+        .await;
+        match result {
+            Ok(price) => return Ok(price),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(anyhow!(
+        "No USD route for mint {} within 2 hops on AMM V4 (tried {:?}): {}",
+        token_mint,
+        tried,
+        last_error.map(|e| e.to_string()).unwrap_or_else(|| "no pools".to_string())
+    ))
+}
+
 /// 获取任意 Token 在 Raydium AMM V4 上的 USD 价格（通过 X-WSOL 池 + Raydium CLMM WSOL-USD 锚定池）
 ///
 /// 价格计算路径：Token X -> WSOL -> USD
 /// - 要求：存在一个 X-WSOL 的 AMM V4 池，以及一个 Raydium CLMM 上的 WSOL-USDT/USDC 锚定池
+/// - X 只与其他长尾代币 Y 配对时，按 X -> Y -> WSOL/稳定币 -> USD 两跳计价
 pub async fn get_token_price_in_usd(
     rpc: &SolanaRpcClient,
     token_mint: &Pubkey,
     wsol_usd_clmm_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_clmm_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
     use crate::utils::price::raydium_amm_v4::{price_base_in_quote, price_quote_in_base};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
@@ -673,7 +782,7 @@ pub async fn get_token_price_in_usd(
     if *token_mint == SOL_MINT {
         return crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await;
     }
@@ -703,8 +812,8 @@ pub async fn get_token_price_in_usd(
     // 支持三种池子类型：
     // 1. X-WSOL：需要通过 WSOL-USD 锚定池计算
     // 2. X-USDC/USDT：直接认为稳定币价格 = 1 USD
-    // 3. 其他：暂不支持（需要多跳路由）
-    if other_mint == USDC_MINT || other_mint == USDT_MINT {
+    // 3. 其他：两跳计价（X -> Y -> WSOL/稳定币 -> USD）
+    if pricing.is_stablecoin(&other_mint) {
         // X-稳定币池：直接计算 X 相对稳定币的价格
         let coin_decimals = crate::utils::token::get_token_decimals(rpc, &amm.coin_mint).await?;
         let pc_decimals = crate::utils::token::get_token_decimals(rpc, &amm.pc_mint).await?;
@@ -741,11 +850,8 @@ pub async fn get_token_price_in_usd(
     }
 
     if other_mint != SOL_MINT {
-        return Err(anyhow!(
-            "Best AMM V4 pool for mint {} is paired with {} (not WSOL/USDC/USDT); multi-hop USD pricing is not supported yet",
-            token_mint,
-            other_mint
-        ));
+        // X 只与其他长尾代币配对：两跳计价 X -> Y -> WSOL/稳定币 -> USD
+        return get_token_price_in_usd_two_hop(rpc, token_mint, wsol_usd_clmm_pool_address).await;
     }
 
     // X-WSOL 池：计算 X 相对 WSOL 的价格
@@ -780,7 +886,7 @@ pub async fn get_token_price_in_usd(
     let price_wsol_in_usd =
         crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await?;
 
@@ -804,11 +910,12 @@ pub async fn get_token_price_in_usd_with_pool(
     x_wsol_pool_address: &Pubkey,
     wsol_usd_clmm_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_clmm_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
     use crate::utils::price::raydium_amm_v4::{price_base_in_quote, price_quote_in_base};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
@@ -816,7 +923,7 @@ pub async fn get_token_price_in_usd_with_pool(
     if *token_mint == SOL_MINT {
         return crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await;
     }
@@ -843,8 +950,8 @@ pub async fn get_token_price_in_usd_with_pool(
     // 支持三种池子类型：
     // 1. X-WSOL：需要通过 WSOL-USD 锚定池计算
     // 2. X-USDC/USDT：直接认为稳定币价格 = 1 USD
-    // 3. 其他：暂不支持（需要多跳路由）
-    if other_mint == USDC_MINT || other_mint == USDT_MINT {
+    // 3. 其他：两跳计价（X -> Y -> WSOL/稳定币 -> USD）
+    if pricing.is_stablecoin(&other_mint) {
         // X-稳定币池：直接计算 X 相对稳定币的价格
         let coin_decimals = crate::utils::token::get_token_decimals(rpc, &amm.coin_mint).await?;
         let pc_decimals = crate::utils::token::get_token_decimals(rpc, &amm.pc_mint).await?;
@@ -881,11 +988,19 @@ pub async fn get_token_price_in_usd_with_pool(
     }
 
     if other_mint != SOL_MINT {
-        return Err(anyhow!(
-            "Provided AMM V4 pool {} is paired with {} (not WSOL/USDC/USDT); multi-hop USD pricing is not supported yet",
-            x_wsol_pool_address,
-            other_mint
-        ));
+        // 指定池为 X-Y 对：X 以 Y 计价，再由 Y 的 WSOL/稳定币池换算为 USD
+        let (price_x_in_y, y_mint) = price_in_pair(rpc, &amm, token_mint).await?;
+        let price_y_in_usd = get_direct_usd_price(rpc, &y_mint, wsol_usd_clmm_pool_address)
+            .await
+            .map_err(|e| {
+            anyhow!(
+                "Provided AMM V4 pool {} is paired with {} which has no WSOL/stable route: {}",
+                x_wsol_pool_address,
+                y_mint,
+                e
+            )
+        })?;
+        return Ok(price_x_in_y * price_y_in_usd);
     }
 
     // 3. X-WSOL 池：计算 X 相对 WSOL 的价格
@@ -920,7 +1035,7 @@ pub async fn get_token_price_in_usd_with_pool(
     let price_wsol_in_usd =
         crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await?;
 
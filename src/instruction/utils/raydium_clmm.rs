@@ -6,12 +6,16 @@
 #![allow(unused_assignments)]
 
 use crate::{
-    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient},
+    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient, single_flight::get_account_coalesced},
     constants::{SOL_MINT, USDC_MINT, USDT_MINT},
     instruction::utils::raydium_clmm_types::{
-        AmmConfig, PoolState, TickArrayState, amm_config_decode, pool_state_decode,
+        AmmConfig, ObservationState, PersonalPositionState, PoolState, TickArrayBitmapExtension,
+        TickArrayState, amm_config_decode, observation_state_decode,
+        personal_position_state_decode, pool_state_decode, tick_array_bitmap_extension_decode,
         tick_array_state_decode,
     },
+    utils::calc::raydium_clmm::{MAX_TICK, MIN_TICK},
+    utils::price::raydium_clmm::{ClmmTwap, twap_from_observations},
 };
 use anyhow::anyhow;
 use base64::Engine;
@@ -20,7 +24,7 @@ use solana_account_decoder::UiAccountData;
 use solana_sdk::{pubkey, pubkey::Pubkey};
 
 /// Raydium CLMM WSOL-USDT 锚定池（用于 USD 价格计算）
-/// 如果不传入锚定池参数，默认使用此池
+/// `PricingConfig::default()` 的锚定池；可通过 `PricingConfig::with_anchor_pools` 替换
 pub const DEFAULT_WSOL_USDT_CLMM_POOL: Pubkey =
     pubkey!("ExcBWu8fGPdJiaF1b1z3iEef38sjQJks8xvj6M85pPY6");
 
@@ -28,6 +32,8 @@ pub const DEFAULT_WSOL_USDT_CLMM_POOL: Pubkey =
 pub mod seeds {
     pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
     pub const POOL_TICK_ARRAY_BITMAP_SEED: &[u8] = b"pool_tick_array_bitmap_extension";
+    pub const POSITION_SEED: &[u8] = b"position";
+    pub const METADATA_SEED: &[u8] = b"metadata";
 }
 
 /// Calculate tick array PDA
@@ -88,10 +94,59 @@ pub fn get_tick_array_start_index(tick_current: i32, tick_spacing: u16) -> i32 {
     start_index * ticks_in_array
 }
 
+/// 从 `start_index` 起沿兑换方向的 `count` 个 tick array 起始 tick（超出 tick 范围时截断）
+pub fn tick_array_start_indices(
+    start_index: i32,
+    tick_spacing: u16,
+    zero_for_one: bool,
+    count: usize,
+) -> Vec<i32> {
+    let ticks_per_array = 60 * tick_spacing as i32;
+    let mut indices = Vec::with_capacity(count);
+    let mut index = start_index;
+    for _ in 0..count {
+        if !(MIN_TICK..=MAX_TICK).contains(&index) {
+            break;
+        }
+        indices.push(index);
+        index = if zero_for_one { index - ticks_per_array } else { index + ticks_per_array };
+    }
+    indices
+}
+
+/// 按当前区间流动性估算一笔兑换跨越的 tick array 数（含当前 array，多留一个余量）
+///
+/// 假设流动性在价格区间内不变，只作为预取深度的参考；流动性为 0 时返回 `usize::MAX`。
+pub fn estimate_tick_arrays_crossed(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_in: u64,
+    tick_spacing: u16,
+    zero_for_one: bool,
+) -> usize {
+    if liquidity == 0 || sqrt_price_x64 == 0 {
+        return usize::MAX;
+    }
+    let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    let liquidity = liquidity as f64;
+    let amount = amount_in as f64;
+    // token0 输入：1/√P' = 1/√P + Δx/L；token1 输入：√P' = √P + Δy/L
+    let sqrt_price_next = if zero_for_one {
+        1.0 / (1.0 / sqrt_price + amount / liquidity)
+    } else {
+        sqrt_price + amount / liquidity
+    };
+    let ticks_crossed = (2.0 * (sqrt_price_next / sqrt_price).ln() / 1.0001f64.ln()).abs();
+    let ticks_per_array = 60.0 * tick_spacing.max(1) as f64;
+    ((ticks_crossed / ticks_per_array).ceil() as usize).saturating_add(2)
+}
+
 /// Constants related to program accounts and authorities
 pub mod accounts {
     use solana_sdk::{pubkey, pubkey::Pubkey};
     pub const RAYDIUM_CLMM: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+    /// Metaplex Token Metadata（仓位 NFT 元数据）
+    pub const METADATA_PROGRAM: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 }
 
 /// 判断是否为 Hot Mint（主流桥接资产）
@@ -114,24 +169,272 @@ pub fn get_tick_array_bitmap_extension_pda(pool_id: &Pubkey) -> (Pubkey, u8) {
     )
 }
 
-/// Find first initialized tick array from bitmap
+/// Calculate protocol position PDA（按池与价格区间聚合的仓位）
+pub fn get_protocol_position_pda(pool_id: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seeds::POSITION_SEED,
+            pool_id.as_ref(),
+            &tick_lower.to_be_bytes(),
+            &tick_upper.to_be_bytes(),
+        ],
+        &accounts::RAYDIUM_CLMM,
+    )
+    .0
+}
+
+/// Calculate personal position PDA（由仓位 NFT mint 推导）
+pub fn get_personal_position_pda(position_nft_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::POSITION_SEED, position_nft_mint.as_ref()],
+        &accounts::RAYDIUM_CLMM,
+    )
+    .0
+}
+
+/// Calculate Metaplex metadata PDA of the position NFT
+pub fn get_position_metadata_pda(position_nft_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::METADATA_SEED, accounts::METADATA_PROGRAM.as_ref(), position_nft_mint.as_ref()],
+        &accounts::METADATA_PROGRAM,
+    )
+    .0
+}
+
+/// 获取仓位 NFT 对应的 personal position
+pub async fn get_personal_position<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    position_nft_mint: &Pubkey,
+) -> Result<PersonalPositionState, anyhow::Error> {
+    let address = get_personal_position_pda(position_nft_mint);
+    let account = rpc.get_account(&address).await.map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+    if account.owner != accounts::RAYDIUM_CLMM {
+        return Err(anyhow!("Account is not owned by Raydium CLMM program"));
+    }
+    personal_position_state_decode(&account.data)
+        .ok_or_else(|| anyhow!("Failed to decode personal position"))
+}
+
+/// 一个 tick array 覆盖的 tick 数
+fn tick_count(tick_spacing: u16) -> i32 {
+    60 * tick_spacing as i32
+}
+
+/// 池子默认 bitmap 覆盖 `[-boundary, boundary)`，每个扩展子位图同样覆盖 `boundary` 个 tick
+fn bitmap_tick_span(tick_spacing: u16) -> i32 {
+    tick_count(tick_spacing) * 512
+}
+
+/// 从 `from` 位（含）起沿方向找到的第一个已置位的位
+fn nearest_set_bit(words: &[u64], from: usize, downward: bool) -> Option<usize> {
+    let is_set = |i: &usize| (words[i / 64] >> (i % 64)) & 1 == 1;
+    if downward { (0..=from).rev().find(is_set) } else { (from..words.len() * 64).find(is_set) }
+}
+
+/// 在池子默认 bitmap 中查找 `last_start` 之后（沿方向）的已初始化 array
+///
+/// 未找到时返回默认 bitmap 在该方向上的边界，供扩展位图继续查找。
+fn next_in_default_bitmap(
+    bitmap: &[u64; 16],
+    last_start: i32,
+    tick_spacing: u16,
+    zero_for_one: bool,
+) -> (bool, i32) {
+    let tick_count = tick_count(tick_spacing);
+    let boundary = bitmap_tick_span(tick_spacing);
+    let next = if zero_for_one { last_start - tick_count } else { last_start + tick_count };
+    if next < -boundary || next >= boundary {
+        return (false, last_start);
+    }
+    let bit = (next.div_euclid(tick_count) + 512) as usize;
+    match nearest_set_bit(bitmap, bit, zero_for_one) {
+        Some(found) => (true, (found as i32 - 512) * tick_count),
+        None => (false, if zero_for_one { -boundary } else { boundary - tick_count }),
+    }
+}
+
+/// array 起始 tick 在扩展位图中的子位图与位偏移；位于默认 bitmap 范围内时返回 None
+fn extension_bit(
+    extension: &TickArrayBitmapExtension,
+    start_index: i32,
+    tick_spacing: u16,
+) -> Option<(&[u64; 8], usize)> {
+    let span = bitmap_tick_span(tick_spacing);
+    let abs = start_index.abs();
+    let mut offset = abs / span - 1;
+    if start_index < 0 && abs % span == 0 {
+        offset -= 1;
+    }
+    let bitmaps = if start_index < 0 {
+        &extension.negative_tick_array_bitmap
+    } else {
+        &extension.positive_tick_array_bitmap
+    };
+    let bitmap = bitmaps.get(usize::try_from(offset).ok()?)?;
+    let remainder = abs % span;
+    let mut bit = remainder / tick_count(tick_spacing);
+    if start_index < 0 && remainder != 0 {
+        bit = 512 - bit;
+    }
+    Some((bitmap, bit as usize))
+}
+
+/// 在扩展位图中查找 `last_start` 之后（沿方向）的已初始化 array，只查下一个 array 所在的子位图
+///
+/// 未找到时返回该子位图在方向上的边界。
+fn next_in_extension(
+    extension: &TickArrayBitmapExtension,
+    last_start: i32,
+    tick_spacing: u16,
+    zero_for_one: bool,
+) -> (bool, i32) {
+    let tick_count = tick_count(tick_spacing);
+    let next = if zero_for_one { last_start - tick_count } else { last_start + tick_count };
+    if next < get_tick_array_start_index(MIN_TICK, tick_spacing)
+        || next > get_tick_array_start_index(MAX_TICK, tick_spacing)
+    {
+        return (false, next);
+    }
+    let Some((bitmap, bit)) = extension_bit(extension, next, tick_spacing) else {
+        return (false, next);
+    };
+    let span = bitmap_tick_span(tick_spacing);
+    let mut multiple = next.abs() / span;
+    if next < 0 && next.abs() % span != 0 {
+        multiple += 1;
+    }
+    let (lower, upper) = if next < 0 {
+        (-span * multiple, -span * multiple + span)
+    } else {
+        (span * multiple, span * multiple + span)
+    };
+    match nearest_set_bit(bitmap, bit, zero_for_one) {
+        Some(found) => (true, next + (found as i32 - bit as i32) * tick_count),
+        None => (false, if zero_for_one { lower } else { upper - tick_count }),
+    }
+}
+
+fn is_tick_array_initialized(
+    pool_state: &PoolState,
+    extension: Option<&TickArrayBitmapExtension>,
+    start_index: i32,
+) -> Result<bool, anyhow::Error> {
+    let tick_spacing = pool_state.tick_spacing;
+    let boundary = bitmap_tick_span(tick_spacing);
+    if (-boundary..boundary).contains(&start_index) {
+        let bit = (start_index.div_euclid(tick_count(tick_spacing)) + 512) as usize;
+        return Ok(nearest_set_bit(&pool_state.tick_array_bitmap, bit, false) == Some(bit));
+    }
+    let extension = extension.ok_or_else(|| anyhow!("Tick array bitmap extension is required"))?;
+    Ok(extension_bit(extension, start_index, tick_spacing)
+        .is_some_and(|(bitmap, bit)| nearest_set_bit(bitmap, bit, false) == Some(bit)))
+}
+
+/// 沿兑换方向查找第一个已初始化的 tick array（与链上 `get_first_initialized_tick_array` 一致）
+///
+/// 当前 array 未初始化时先在池子默认 bitmap 中查找，超出默认范围后查 `extension`。
+/// 需要扩展位图但未提供时返回错误；该方向上没有已初始化的 array 时返回 `Ok(None)`。
+pub fn find_first_initialized_tick_array_start_index(
+    pool_state: &PoolState,
+    extension: Option<&TickArrayBitmapExtension>,
+    zero_for_one: bool,
+) -> Result<Option<i32>, anyhow::Error> {
+    let tick_spacing = pool_state.tick_spacing;
+    let current = get_tick_array_start_index(pool_state.tick_current, tick_spacing);
+    if is_tick_array_initialized(pool_state, extension, current)? {
+        return Ok(Some(current));
+    }
+    let mut last_start = current;
+    loop {
+        let (found, start) = next_in_default_bitmap(
+            &pool_state.tick_array_bitmap,
+            last_start,
+            tick_spacing,
+            zero_for_one,
+        );
+        if found {
+            return Ok(Some(start));
+        }
+        last_start = start;
+        let extension =
+            extension.ok_or_else(|| anyhow!("Tick array bitmap extension is required"))?;
+        let (found, start) = next_in_extension(extension, last_start, tick_spacing, zero_for_one);
+        if found {
+            return Ok(Some(start));
+        }
+        last_start = start;
+        if !(MIN_TICK..=MAX_TICK).contains(&last_start) {
+            return Ok(None);
+        }
+    }
+}
+
+/// Find first initialized tick array from the pool's default bitmap
 ///
-/// This is a simplified version. In production, you should use the full bitmap logic
-/// from the pool state's tick_array_bitmap field.
+/// 只使用池子自带的 bitmap；需要 bitmap extension 或未找到时退回当前 tick 所在的 array。
+/// 稀疏池请使用 [`get_first_initialized_tick_array_start_index_with_extension`]。
 ///
 /// # Arguments
 /// * `pool_state` - Pool state
 /// * `zero_for_one` - Swap direction (true = token0 -> token1)
-///
-/// # Returns
-/// First initialized tick array start index, or falls back to current tick's array
 pub fn get_first_initialized_tick_array_start_index(
     pool_state: &PoolState,
-    _zero_for_one: bool,
+    zero_for_one: bool,
 ) -> i32 {
-    // TODO: Implement full bitmap search logic
-    // For now, fall back to current tick's array
-    get_tick_array_start_index(pool_state.tick_current, pool_state.tick_spacing)
+    find_first_initialized_tick_array_start_index(pool_state, None, zero_for_one)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| {
+            get_tick_array_start_index(pool_state.tick_current, pool_state.tick_spacing)
+        })
+}
+
+/// 读取池子的 tick array bitmap extension；账户不存在时返回 None
+pub async fn get_tick_array_bitmap_extension<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pool_id: &Pubkey,
+) -> Result<Option<TickArrayBitmapExtension>, anyhow::Error> {
+    let (address, _) = get_tick_array_bitmap_extension_pda(pool_id);
+    let Ok(account) = rpc.get_account(&address).await else {
+        return Ok(None);
+    };
+    if account.owner != accounts::RAYDIUM_CLMM {
+        return Ok(None);
+    }
+    tick_array_bitmap_extension_decode(&account.data)
+        .map(Some)
+        .ok_or_else(|| anyhow!("Failed to decode tick array bitmap extension"))
+}
+
+/// 沿兑换方向查找第一个已初始化的 tick array
+///
+/// 默认 bitmap 中附近没有已初始化的 array 时才读取 bitmap extension 账户；
+/// 扩展账户不存在或该方向上没有已初始化的 array 时退回当前 tick 所在的 array。
+pub async fn get_first_initialized_tick_array_start_index_with_extension<
+    T: PoolRpcClient + ?Sized,
+>(
+    rpc: &T,
+    pool_id: &Pubkey,
+    pool_state: &PoolState,
+    zero_for_one: bool,
+) -> Result<i32, anyhow::Error> {
+    let start = match find_first_initialized_tick_array_start_index(pool_state, None, zero_for_one)
+    {
+        Ok(start) => start,
+        Err(_) => {
+            let extension = get_tick_array_bitmap_extension(rpc, pool_id).await?;
+            find_first_initialized_tick_array_start_index(
+                pool_state,
+                extension.as_ref(),
+                zero_for_one,
+            )
+            .ok()
+            .flatten()
+        },
+    };
+    Ok(start.unwrap_or_else(|| {
+        get_tick_array_start_index(pool_state.tick_current, pool_state.tick_spacing)
+    }))
 }
 
 // ==================== 缓存模块 ====================
@@ -160,6 +463,8 @@ pub(crate) mod raydium_clmm_cache {
     }
 
     pub(crate) fn cache_pool_by_address(pool_address: &Pubkey, pool: &PoolState) {
+        crate::utils::token::cache_token_decimals(pool.token_mint0, pool.mint_decimals0);
+        crate::utils::token::cache_token_decimals(pool.token_mint1, pool.mint_decimals1);
         POOL_DATA_CACHE.insert(*pool_address, pool.clone());
     }
 
@@ -205,8 +510,7 @@ pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
         return Ok(pool);
     }
     // 2. RPC 查询
-    let account = rpc
-        .get_account(pool_address)
+    let account = get_account_coalesced(rpc, pool_address)
         .await
         .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
     if account.owner != accounts::RAYDIUM_CLMM {
@@ -220,20 +524,62 @@ pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
 }
 
 /// 获取 amm_config 配置
-pub async fn get_amm_config(
-    rpc: &SolanaRpcClient,
+pub async fn get_amm_config<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     amm_config_address: &Pubkey,
 ) -> Result<AmmConfig, anyhow::Error> {
-    let account = rpc.get_account(amm_config_address).await?;
+    let account = rpc
+        .get_account(amm_config_address)
+        .await
+        .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
     if account.owner != accounts::RAYDIUM_CLMM {
         return Err(anyhow!("Account is not owned by Raydium CLMM program"));
     }
     amm_config_decode(&account.data).ok_or_else(|| anyhow!("Failed to decode amm config"))
 }
 
+/// 读取池子的价格预言机账户（`pool_state.observation_key`）
+pub async fn get_observation_state<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    observation_key: &Pubkey,
+) -> Result<ObservationState, anyhow::Error> {
+    let account = rpc
+        .get_account(observation_key)
+        .await
+        .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+    if account.owner != accounts::RAYDIUM_CLMM {
+        return Err(anyhow!("Account is not owned by Raydium CLMM program"));
+    }
+    observation_state_decode(&account.data)
+        .ok_or_else(|| anyhow!("Failed to decode observation state"))
+}
+
+/// 计算池子最近 `window_secs` 秒的 TWAP
+///
+/// 基于链上观测点（约每 15 秒写入一次），单笔交易难以操纵，
+/// 可作为风控与止盈止损的参考价。
+pub async fn get_pool_twap<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    pool_address: &Pubkey,
+    window_secs: u32,
+) -> Result<ClmmTwap, anyhow::Error> {
+    let pool_state = get_pool_by_address(rpc, pool_address).await?;
+    let observation_state = get_observation_state(rpc, &pool_state.observation_key).await?;
+    if observation_state.pool_id != *pool_address {
+        return Err(anyhow!("Observation account does not belong to pool {}", pool_address));
+    }
+    twap_from_observations(
+        &observation_state,
+        window_secs,
+        pool_state.mint_decimals0,
+        pool_state.mint_decimals1,
+    )
+    .ok_or_else(|| anyhow!("Not enough observations to compute TWAP for {}", pool_address))
+}
+
 /// 获取多个 tick arrays
-pub async fn get_tick_arrays(
-    rpc: &SolanaRpcClient,
+pub async fn get_tick_arrays<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     pool_id: &Pubkey,
     start_indices: &[i32],
 ) -> Result<Vec<(i32, TickArrayState)>, anyhow::Error> {
@@ -1017,25 +1363,55 @@ pub async fn quote_exact_in(
 ///
 /// # Arguments
 /// * `rpc` - RPC 客户端（支持 AutoMockRpcClient）
-/// * `wsol_usd_pool_address` - WSOL-USDT/USDC CLMM 池地址（例如你提供的 USDT-WSOL 池）
+/// * `wsol_usd_pool_address` - WSOL-USDT/USDC CLMM 池地址；为 `None` 时按
+///   [`PricingConfig`](crate::common::pricing::PricingConfig) 的锚定池与缓存时长读取
 pub async fn get_wsol_price_in_usd_with_client<T: PoolRpcClient + ?Sized>(
     rpc: &T,
     wsol_usd_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    use crate::utils::price::raydium_clmm::{price_token0_in_token1, price_token1_in_token0};
+    use crate::common::pricing::{cache_wsol_price, cached_wsol_price, pricing_config};
+
+    let config = pricing_config();
+
+    // 显式指定锚定池：直接读取，不使用缓存
+    if let Some(pool) = wsol_usd_pool_address {
+        return get_wsol_price_from_anchor_pool(rpc, pool, &config.stablecoins).await;
+    }
+
+    if let Some(price) = cached_wsol_price(config.refresh_interval) {
+        return Ok(price);
+    }
 
-    let wsol_usd_pool = wsol_usd_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
+    // 按优先级依次尝试配置的锚定池
+    let mut last_error = None;
+    for pool in &config.anchor_pools {
+        match get_wsol_price_from_anchor_pool(rpc, pool, &config.stablecoins).await {
+            Ok(price) => {
+                cache_wsol_price(price);
+                return Ok(price);
+            },
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow!("No WSOL-USD anchor pool configured")))
+}
+
+/// 从单个 WSOL-稳定币锚定池读取 WSOL 的 USD 价格
+async fn get_wsol_price_from_anchor_pool<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    wsol_usd_pool: &Pubkey,
+    stablecoins: &[Pubkey],
+) -> Result<f64, anyhow::Error> {
+    use crate::utils::price::raydium_clmm::{price_token0_in_token1, price_token1_in_token0};
 
     // 强制刷新：每次调用都重新从链上读取池状态，避免价格缓存
     let pool_state = get_pool_by_address_force(rpc, wsol_usd_pool).await?;
 
-    // 只支持 WSOL <-> USDC/USDT 的稳定币池
+    // 只支持 WSOL <-> 稳定币的锚定池
     let is_token0_sol = pool_state.token_mint0 == SOL_MINT;
     let is_token1_sol = pool_state.token_mint1 == SOL_MINT;
-    let is_token0_stable =
-        pool_state.token_mint0 == USDC_MINT || pool_state.token_mint0 == USDT_MINT;
-    let is_token1_stable =
-        pool_state.token_mint1 == USDC_MINT || pool_state.token_mint1 == USDT_MINT;
+    let is_token0_stable = stablecoins.contains(&pool_state.token_mint0);
+    let is_token1_stable = stablecoins.contains(&pool_state.token_mint1);
 
     let price_wsol_in_stable = if is_token0_sol && is_token1_stable {
         // token0 = WSOL, token1 = USDC/USDT
@@ -1053,7 +1429,7 @@ pub async fn get_wsol_price_in_usd_with_client<T: PoolRpcClient + ?Sized>(
         )
     } else {
         return Err(anyhow!(
-            "WSOL-USD anchor pool must be a SOL<->USDC/USDT CLMM pool, got {:?} / {:?}",
+            "WSOL-USD anchor pool must be a SOL<->stablecoin CLMM pool, got {:?} / {:?}",
             pool_state.token_mint0,
             pool_state.token_mint1
         ));
@@ -1063,7 +1439,7 @@ pub async fn get_wsol_price_in_usd_with_client<T: PoolRpcClient + ?Sized>(
         return Err(anyhow!("Invalid WSOL price from anchor pool (<= 0)"));
     }
 
-    // 默认认为 USDC / USDT ~= 1 USD
+    // 稳定币 ~= 1 USD
     Ok(price_wsol_in_stable)
 }
 
@@ -1079,17 +1455,18 @@ pub async fn get_token_price_in_usd<T: PoolRpcClient + ?Sized>(
     token_mint: &Pubkey,
     wsol_usd_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
     use crate::utils::price::raydium_clmm::{price_token0_in_token1, price_token1_in_token0};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
     // WSOL/SOL 的价格直接来自锚定池
     if *token_mint == SOL_MINT {
-        return get_wsol_price_in_usd_with_client(rpc, Some(wsol_usd_pool)).await;
+        return get_wsol_price_in_usd_with_client(rpc, wsol_usd_pool_address).await;
     }
 
     // 1. 先在 CLMM 中找到 Token X 的最优池（优先 X-WSOL/USDC/USDT 对）
@@ -1118,7 +1495,7 @@ pub async fn get_token_price_in_usd<T: PoolRpcClient + ?Sized>(
     // 1. X-WSOL：需要通过 WSOL-USD 锚定池计算
     // 2. X-USDC/USDT：直接认为稳定币价格 = 1 USD
     // 3. 其他：暂不支持（需要多跳路由）
-    if other_mint == USDC_MINT || other_mint == USDT_MINT {
+    if pricing.is_stablecoin(&other_mint) {
         // X-稳定币池：直接计算 X 相对稳定币的价格
         let price_x_in_stable = if is_token0_x {
             price_token0_in_token1(
@@ -1175,7 +1552,7 @@ pub async fn get_token_price_in_usd<T: PoolRpcClient + ?Sized>(
     }
 
     // 5. 计算 WSOL 的 USD 价格
-    let price_wsol_in_usd = get_wsol_price_in_usd_with_client(rpc, Some(wsol_usd_pool)).await?;
+    let price_wsol_in_usd = get_wsol_price_in_usd_with_client(rpc, wsol_usd_pool_address).await?;
 
     Ok(price_x_in_wsol * price_wsol_in_usd)
 }
@@ -1193,24 +1570,25 @@ pub async fn get_token_price_in_usd<T: PoolRpcClient + ?Sized>(
 /// * `rpc` - 实现 PoolRpcClient trait 的 RPC 客户端（支持 AutoMockRpcClient）
 /// * `token_mint` - Token X 的 mint 地址
 /// * `x_wsol_pool_address` - Token X 与 WSOL 配对的 CLMM 池地址
-/// * `wsol_usd_pool_address` - WSOL-USDT/USDC 锚定池地址（可选，默认使用 DEFAULT_WSOL_USDT_CLMM_POOL）
+/// * `wsol_usd_pool_address` - WSOL-USDT/USDC 锚定池地址（可选，默认按 `PricingConfig` 的锚定池）
 pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     rpc: &T,
     token_mint: &Pubkey,
     x_wsol_pool_address: &Pubkey,
     wsol_usd_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
     use crate::utils::price::raydium_clmm::{price_token0_in_token1, price_token1_in_token0};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
     // WSOL/SOL 的价格直接来自锚定池
     if *token_mint == SOL_MINT {
-        return get_wsol_price_in_usd_with_client(rpc, Some(wsol_usd_pool)).await;
+        return get_wsol_price_in_usd_with_client(rpc, wsol_usd_pool_address).await;
     }
 
     // 1. 直接强制刷新指定的 X-WSOL 池（跳过查找步骤）
@@ -1236,7 +1614,7 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     // 1. X-WSOL：需要通过 WSOL-USD 锚定池计算
     // 2. X-USDC/USDT：直接认为稳定币价格 = 1 USD
     // 3. 其他：不支持
-    if other_mint == USDC_MINT || other_mint == USDT_MINT {
+    if pricing.is_stablecoin(&other_mint) {
         // X-稳定币池：直接计算 X 相对稳定币的价格
         let price_x_in_stable = if is_token0_x {
             price_token0_in_token1(
@@ -1293,7 +1671,56 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     }
 
     // 4. 计算 WSOL 的 USD 价格
-    let price_wsol_in_usd = get_wsol_price_in_usd_with_client(rpc, Some(wsol_usd_pool)).await?;
+    let price_wsol_in_usd = get_wsol_price_in_usd_with_client(rpc, wsol_usd_pool_address).await?;
 
     Ok(price_x_in_wsol * price_wsol_in_usd)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_bit(words: &mut [u64], bit: usize) {
+        words[bit / 64] |= 1 << (bit % 64);
+    }
+
+    #[test]
+    fn test_first_initialized_tick_array_with_extension() {
+        // tick_spacing = 10：每个 array 600 个 tick，默认 bitmap 覆盖 [-307200, 307200)
+        let mut pool = PoolState { tick_spacing: 10, tick_current: 5, ..Default::default() };
+        set_bit(&mut pool.tick_array_bitmap, 510); // start -1200
+        assert_eq!(
+            find_first_initialized_tick_array_start_index(&pool, None, true).unwrap(),
+            Some(-1200)
+        );
+        // 向上查找超出默认 bitmap，需要扩展位图
+        assert!(find_first_initialized_tick_array_start_index(&pool, None, false).is_err());
+
+        let mut extension = TickArrayBitmapExtension::default();
+        assert_eq!(
+            find_first_initialized_tick_array_start_index(&pool, Some(&extension), false).unwrap(),
+            None
+        );
+        set_bit(&mut extension.positive_tick_array_bitmap[0], 3); // start 307200 + 3 * 600
+        assert_eq!(
+            find_first_initialized_tick_array_start_index(&pool, Some(&extension), false).unwrap(),
+            Some(309_000)
+        );
+
+        // 负方向：默认 bitmap 为空时进入 negative_tick_array_bitmap
+        pool.tick_array_bitmap = [0; 16];
+        set_bit(&mut extension.negative_tick_array_bitmap[0], 511); // start -307800
+        assert_eq!(
+            find_first_initialized_tick_array_start_index(&pool, Some(&extension), true).unwrap(),
+            Some(-307_800)
+        );
+
+        // 当前 array 已初始化时直接返回
+        set_bit(&mut pool.tick_array_bitmap, 512);
+        assert_eq!(
+            find_first_initialized_tick_array_start_index(&pool, None, false).unwrap(),
+            Some(0)
+        );
+        assert_eq!(get_first_initialized_tick_array_start_index(&pool, true), 0);
+    }
+}
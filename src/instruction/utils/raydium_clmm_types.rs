@@ -118,3 +118,89 @@ pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
     }
     borsh::from_slice::<PoolState>(&data[..POOL_STATE_SIZE]).ok()
 }
+
+/// 池子默认 bitmap（±512 个 tick array）之外的 tick array 初始化位图
+///
+/// 每个子位图 512 位；`positive_tick_array_bitmap[i]` 覆盖
+/// `[(i + 1) * 512, (i + 2) * 512)` 个 tick array，负方向对称。
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
+pub struct TickArrayBitmapExtension {
+    pub pool_id: Pubkey,
+    pub positive_tick_array_bitmap: [[u64; 8]; 14],
+    pub negative_tick_array_bitmap: [[u64; 8]; 14],
+}
+
+pub const TICK_ARRAY_BITMAP_EXTENSION_SIZE: usize = 8 + 32 + 64 * 14 * 2;
+
+pub fn tick_array_bitmap_extension_decode(data: &[u8]) -> Option<TickArrayBitmapExtension> {
+    if data.len() < TICK_ARRAY_BITMAP_EXTENSION_SIZE {
+        return None;
+    }
+    borsh::from_slice::<TickArrayBitmapExtension>(&data[8..TICK_ARRAY_BITMAP_EXTENSION_SIZE]).ok()
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
+pub struct PositionRewardInfo {
+    pub growth_inside_last_x64: u128,
+    pub reward_amount_owed: u64,
+}
+
+/// 流动性仓位（由仓位 NFT 持有者控制）
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
+pub struct PersonalPositionState {
+    pub bump: [u8; 1],
+    pub nft_mint: Pubkey,
+    pub pool_id: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_0_last_x64: u128,
+    pub fee_growth_inside_1_last_x64: u128,
+    pub token_fees_owed_0: u64,
+    pub token_fees_owed_1: u64,
+    pub reward_infos: [PositionRewardInfo; 3],
+    pub recent_epoch: u64,
+    pub padding: [u64; 7],
+}
+
+pub const PERSONAL_POSITION_STATE_SIZE: usize =
+    8 + 1 + 32 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8 + 24 * 3 + 8 + 8 * 7;
+
+pub fn personal_position_state_decode(data: &[u8]) -> Option<PersonalPositionState> {
+    if data.len() < PERSONAL_POSITION_STATE_SIZE {
+        return None;
+    }
+    borsh::from_slice::<PersonalPositionState>(&data[8..PERSONAL_POSITION_STATE_SIZE]).ok()
+}
+
+/// 观测点环形缓冲区长度
+pub const OBSERVATION_NUM: usize = 100;
+
+/// 价格观测点（`tick_cumulative` 为 tick 对时间的累积值）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, BorshDeserialize)]
+pub struct Observation {
+    pub block_timestamp: u32,
+    pub tick_cumulative: i64,
+    pub padding: [u64; 4],
+}
+
+/// 池子的价格预言机账户（`pool_state.observation_key`）
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize)]
+pub struct ObservationState {
+    pub initialized: bool,
+    pub recent_epoch: u64,
+    /// 最近一次写入的观测点下标
+    pub observation_index: u16,
+    pub pool_id: Pubkey,
+    pub observations: [Observation; OBSERVATION_NUM],
+    pub padding: [u64; 4],
+}
+
+pub const OBSERVATION_STATE_SIZE: usize = 8 + 1 + 8 + 2 + 32 + 44 * OBSERVATION_NUM + 32;
+
+pub fn observation_state_decode(data: &[u8]) -> Option<ObservationState> {
+    if data.len() < OBSERVATION_STATE_SIZE {
+        return None;
+    }
+    borsh::from_slice::<ObservationState>(&data[8..OBSERVATION_STATE_SIZE]).ok()
+}
@@ -4,7 +4,7 @@
 #![allow(clippy::unwrap_used)]
 
 use crate::{
-    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient},
+    common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient, single_flight::get_account_coalesced},
     constants::{USDC_MINT, USDT_MINT, WSOL_TOKEN_ACCOUNT},
     instruction::utils::raydium_cpmm_types::{PoolState, pool_state_decode},
     trading::core::params::RaydiumCpmmParams,
@@ -16,7 +16,7 @@ use solana_account_decoder::UiAccountData;
 use solana_sdk::{pubkey, pubkey::Pubkey};
 
 /// Raydium CLMM WSOL-USDT 锚定池（用于 USD 价格计算）
-/// 如果不传入锚定池参数，默认使用此池
+/// `PricingConfig::default()` 的锚定池；可通过 `PricingConfig::with_anchor_pools` 替换
 pub const DEFAULT_WSOL_USDT_CLMM_POOL: Pubkey =
     pubkey!("ExcBWu8fGPdJiaF1b1z3iEef38sjQJks8xvj6M85pPY6");
 
@@ -75,6 +75,8 @@ pub(crate) mod raydium_cpmm_cache {
     }
 
     pub(crate) fn cache_pool_by_address(pool_address: &Pubkey, pool: &PoolState) {
+        crate::utils::token::cache_token_decimals(pool.token0_mint, pool.mint0_decimals);
+        crate::utils::token::cache_token_decimals(pool.token1_mint, pool.mint1_decimals);
         POOL_DATA_CACHE.insert(*pool_address, pool.clone());
     }
 
@@ -133,8 +135,7 @@ pub async fn get_pool_by_address<T: PoolRpcClient + ?Sized>(
         return Ok(pool);
     }
     // 2. RPC 查询
-    let account = rpc
-        .get_account(pool_address)
+    let account = get_account_coalesced(rpc, pool_address)
         .await
         .map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
     if account.owner != accounts::RAYDIUM_CPMM {
@@ -392,8 +393,8 @@ pub async fn get_pool_token_balances<T: PoolRpcClient + ?Sized>(
 ///
 /// - If `is_token0_in=true`: token0 -> token1
 /// - If `is_token0_in=false`: token1 -> token0
-pub async fn quote_exact_in(
-    rpc: &SolanaRpcClient,
+pub async fn quote_exact_in<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
     pool_address: &Pubkey,
     amount_in: u64,
     is_token0_in: bool,
@@ -412,12 +413,15 @@ pub async fn quote_exact_in(
         token1_reserve,
         is_token0_in,
         amount_in,
-        0,
+        crate::common::Bps::ZERO,
     );
+    let reserve_in = if is_token0_in { token0_reserve } else { token1_reserve };
     Ok(crate::utils::quote::QuoteExactInResult {
         amount_out: q.amount_out,
         fee_amount: q.fee,
-        price_impact_bps: None,
+        price_impact_bps: Some(crate::utils::quote::constant_product_impact_bps(
+            reserve_in, amount_in,
+        )),
         extra_accounts_read: 2,
     })
 }
@@ -672,12 +676,13 @@ pub async fn get_token_price_in_usd(
     token_mint: &Pubkey,
     wsol_usd_clmm_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_clmm_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
     use crate::constants::SOL_MINT;
     use crate::utils::price::raydium_cpmm::{price_base_in_quote, price_quote_in_base};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
@@ -686,7 +691,7 @@ pub async fn get_token_price_in_usd(
         // TODO: raydium_clmm::get_wsol_price_in_usd 也需要改为泛型版本
         return crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await;
     }
@@ -717,7 +722,7 @@ pub async fn get_token_price_in_usd(
     // 1. X-WSOL：需要通过 WSOL-USD 锚定池计算
     // 2. X-USDC/USDT：直接认为稳定币价格 = 1 USD
     // 3. 其他：暂不支持（需要多跳路由）
-    if other_mint == USDC_MINT || other_mint == USDT_MINT {
+    if pricing.is_stablecoin(&other_mint) {
         // X-稳定币池：直接计算 X 相对稳定币的价格
         // 获取实时余额
         let (token0_balance, token1_balance) =
@@ -792,7 +797,7 @@ pub async fn get_token_price_in_usd(
     let price_wsol_in_usd =
         crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await?;
 
@@ -816,12 +821,13 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     x_wsol_pool_address: &Pubkey,
     wsol_usd_clmm_pool_address: Option<&Pubkey>,
 ) -> Result<f64, anyhow::Error> {
-    let wsol_usd_pool = wsol_usd_clmm_pool_address.unwrap_or(&DEFAULT_WSOL_USDT_CLMM_POOL);
     use crate::constants::SOL_MINT;
     use crate::utils::price::raydium_cpmm::{price_base_in_quote, price_quote_in_base};
 
+    let pricing = crate::common::pricing::pricing_config();
+
     // 稳定币自身的价格直接认为是 1 USD
-    if *token_mint == USDC_MINT || *token_mint == USDT_MINT {
+    if pricing.is_stablecoin(token_mint) {
         return Ok(1.0);
     }
 
@@ -830,7 +836,7 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
         // TODO: raydium_clmm::get_wsol_price_in_usd 也需要改为泛型版本
         return crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await;
     }
@@ -858,7 +864,7 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     // 1. X-WSOL：需要通过 WSOL-USD 锚定池计算
     // 2. X-USDC/USDT：直接认为稳定币价格 = 1 USD
     // 3. 其他：暂不支持（需要多跳路由）
-    if other_mint == USDC_MINT || other_mint == USDT_MINT {
+    if pricing.is_stablecoin(&other_mint) {
         // X-稳定币池：直接计算 X 相对稳定币的价格
         // 获取实时余额
         let (token0_balance, token1_balance) =
@@ -934,7 +940,7 @@ pub async fn get_token_price_in_usd_with_pool<T: PoolRpcClient + ?Sized>(
     let price_wsol_in_usd =
         crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client(
             rpc,
-            Some(wsol_usd_pool),
+            wsol_usd_clmm_pool_address,
         )
         .await?;
 
@@ -25,6 +25,12 @@ pub mod seeds {
 
     /// Seed for platform config PDAs
     pub const PLATFORM_CONFIG_SEED: &[u8] = b"platform_config";
+
+    /// Seed for the creator fee vault authority PDA
+    pub const CREATOR_FEE_VAULT_AUTH_SEED: &[u8] = b"creator_fee_vault_auth_seed";
+
+    /// Seed for the platform fee vault authority PDA
+    pub const PLATFORM_FEE_VAULT_AUTH_SEED: &[u8] = b"platform_fee_vault_auth_seed";
 }
 
 /// Constants related to program accounts and authorities
@@ -380,6 +386,147 @@ pub async fn fetch_global_config(
     parse_global_config(&account.data)
 }
 
+// ==================== 配置缓存 ====================
+
+/// GlobalConfig / PlatformConfig 缓存
+///
+/// 两类配置几乎不变，缓存后 Bonk 交易构建不必每次都读取 RPC；超过 TTL 的条目在下次访问时重新拉取。
+pub mod config_cache {
+    use super::*;
+    use dashmap::DashMap;
+    use once_cell::sync::Lazy;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, Instant};
+
+    /// 默认缓存有效期
+    pub const DEFAULT_CONFIG_CACHE_TTL: Duration = Duration::from_secs(300);
+
+    static CONFIG_CACHE_TTL_MS: AtomicU64 =
+        AtomicU64::new(DEFAULT_CONFIG_CACHE_TTL.as_millis() as u64);
+
+    static GLOBAL_CONFIG_CACHE: Lazy<DashMap<Pubkey, (GlobalConfig, Instant)>> =
+        Lazy::new(DashMap::new);
+
+    static PLATFORM_CONFIG_CACHE: Lazy<DashMap<Pubkey, (PlatformConfig, Instant)>> =
+        Lazy::new(DashMap::new);
+
+    /// 设置缓存有效期（对已缓存条目同样生效）
+    pub fn set_config_cache_ttl(ttl: Duration) {
+        CONFIG_CACHE_TTL_MS.store(ttl.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub fn config_cache_ttl() -> Duration {
+        Duration::from_millis(CONFIG_CACHE_TTL_MS.load(Ordering::Relaxed))
+    }
+
+    fn fresh<T: Clone>(cache: &DashMap<Pubkey, (T, Instant)>, address: &Pubkey) -> Option<T> {
+        let entry = cache.get(address)?;
+        (entry.1.elapsed() < config_cache_ttl()).then(|| entry.0.clone())
+    }
+
+    /// 带缓存的 [`fetch_global_config`]
+    pub async fn get_global_config_cached(
+        rpc: &SolanaRpcClient,
+        global_config_address: &Pubkey,
+    ) -> Result<GlobalConfig, anyhow::Error> {
+        if let Some(config) = fresh(&GLOBAL_CONFIG_CACHE, global_config_address) {
+            return Ok(config);
+        }
+        let config = fetch_global_config(rpc, global_config_address).await?;
+        GLOBAL_CONFIG_CACHE.insert(*global_config_address, (config.clone(), Instant::now()));
+        Ok(config)
+    }
+
+    /// 带缓存的 [`fetch_platform_config`]
+    pub async fn get_platform_config_cached(
+        rpc: &SolanaRpcClient,
+        platform_config_address: &Pubkey,
+    ) -> Result<PlatformConfig, anyhow::Error> {
+        if let Some(config) = fresh(&PLATFORM_CONFIG_CACHE, platform_config_address) {
+            return Ok(config);
+        }
+        let config = fetch_platform_config(rpc, platform_config_address).await?;
+        PLATFORM_CONFIG_CACHE.insert(*platform_config_address, (config.clone(), Instant::now()));
+        Ok(config)
+    }
+
+    /// 按计价代币解析并读取 GlobalConfig（见 [`resolve_global_config`](crate::instruction::utils::bonk::resolve_global_config)）
+    pub async fn get_global_config_for_quote_mint(
+        rpc: &SolanaRpcClient,
+        quote_mint: &Pubkey,
+    ) -> Result<(Pubkey, GlobalConfig), anyhow::Error> {
+        let address = crate::instruction::utils::bonk::resolve_global_config(quote_mint)
+            .ok_or_else(|| {
+                anyhow::anyhow!("No known global_config for quote mint {}", quote_mint)
+            })?;
+        Ok((address, get_global_config_cached(rpc, &address).await?))
+    }
+
+    /// 预热：一次 `getMultipleAccounts` 拉取 WSOL / USD1 的 GlobalConfig 与给定的 PlatformConfig
+    ///
+    /// 不存在或解析失败的账户会被跳过并记录日志。返回写入缓存的条目数。
+    pub async fn warm_up_config_cache(
+        rpc: &SolanaRpcClient,
+        platform_configs: &[Pubkey],
+    ) -> Result<usize, anyhow::Error> {
+        use crate::instruction::utils::bonk::accounts::{GLOBAL_CONFIG, USD1_GLOBAL_CONFIG};
+
+        let global_configs = [GLOBAL_CONFIG, USD1_GLOBAL_CONFIG];
+        let addresses: Vec<Pubkey> =
+            global_configs.iter().chain(platform_configs.iter()).copied().collect();
+        let now = Instant::now();
+        let mut cached = 0;
+        for chunk in addresses.chunks(100) {
+            let accounts = rpc.get_multiple_accounts(chunk).await?;
+            for (address, account) in chunk.iter().zip(accounts) {
+                let Some(account) = account else {
+                    log::warn!("LaunchLab config {} not found during warm-up", address);
+                    continue;
+                };
+                let result = if global_configs.contains(address) {
+                    parse_global_config(&account.data).map(|config| {
+                        GLOBAL_CONFIG_CACHE.insert(*address, (config, now));
+                    })
+                } else {
+                    parse_platform_config(&account.data).map(|config| {
+                        PLATFORM_CONFIG_CACHE.insert(*address, (config, now));
+                    })
+                };
+                match result {
+                    Ok(_) => cached += 1,
+                    Err(e) => log::warn!("Failed to parse LaunchLab config {}: {}", address, e),
+                }
+            }
+        }
+        Ok(cached)
+    }
+
+    /// 清空配置缓存
+    pub fn clear_config_cache() {
+        GLOBAL_CONFIG_CACHE.clear();
+        PLATFORM_CONFIG_CACHE.clear();
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_fresh_respects_ttl() {
+            let cache: DashMap<Pubkey, (u64, Instant)> = DashMap::new();
+            let address = Pubkey::new_unique();
+            assert_eq!(fresh(&cache, &address), None);
+            cache.insert(address, (7, Instant::now()));
+            assert_eq!(fresh(&cache, &address), Some(7));
+            let stale = Instant::now().checked_sub(config_cache_ttl() + Duration::from_secs(1));
+            if let Some(stale) = stale {
+                cache.insert(address, (7, stale));
+                assert_eq!(fresh(&cache, &address), None);
+            }
+        }
+    }
+}
+
 /// Fetch and parse the bonding curve account for Raydium LaunchLab
 pub async fn fetch_bonding_curve_account(
     rpc: &SolanaRpcClient,
@@ -436,6 +583,12 @@ pub mod discriminators {
 
     /// migrate_to_cpswap discriminator: [136, 92, 200, 103, 28, 218, 144, 140]
     pub const MIGRATE_TO_CPSWAP: [u8; 8] = [136, 92, 200, 103, 28, 218, 144, 140];
+
+    /// claim_creator_fee discriminator: [26, 97, 138, 203, 132, 171, 141, 252]
+    pub const CLAIM_CREATOR_FEE: [u8; 8] = [26, 97, 138, 203, 132, 171, 141, 252];
+
+    /// claim_platform_fee_from_vault discriminator: [117, 241, 198, 168, 248, 218, 80, 29]
+    pub const CLAIM_PLATFORM_FEE_FROM_VAULT: [u8; 8] = [117, 241, 198, 168, 248, 218, 80, 29];
 }
 
 /// Calculate pool state PDA (seeds: ["pool", base_mint, quote_mint])
@@ -509,6 +662,123 @@ pub fn get_creator_fee_vault_pda(
     .ok_or_else(|| anyhow::anyhow!("Failed to find creator fee vault PDA"))
 }
 
+/// Calculate creator fee vault authority PDA (seeds: ["creator_fee_vault_auth_seed"])
+pub fn get_creator_fee_vault_authority_pda() -> Result<(Pubkey, u8), anyhow::Error> {
+    Pubkey::try_find_program_address(
+        &[seeds::CREATOR_FEE_VAULT_AUTH_SEED],
+        &accounts::LAUNCHLAB_PROGRAM,
+    )
+    .ok_or_else(|| anyhow::anyhow!("Failed to find creator fee vault authority PDA"))
+}
+
+/// Calculate platform fee vault authority PDA (seeds: ["platform_fee_vault_auth_seed"])
+pub fn get_platform_fee_vault_authority_pda() -> Result<(Pubkey, u8), anyhow::Error> {
+    Pubkey::try_find_program_address(
+        &[seeds::PLATFORM_FEE_VAULT_AUTH_SEED],
+        &accounts::LAUNCHLAB_PROGRAM,
+    )
+    .ok_or_else(|| anyhow::anyhow!("Failed to find platform fee vault authority PDA"))
+}
+
+/// Build claim_creator_fee instruction
+///
+/// Sweeps the creator fee vault (seeds: [creator, quote_mint]) into the creator's quote token ATA.
+/// The ATA is created by the program if it does not exist yet.
+///
+/// # Arguments
+/// * `creator` - The pool creator (signer, pays ATA rent)
+/// * `quote_mint` - The quote mint the fees are denominated in (WSOL / USD1)
+pub fn build_claim_creator_fee_instruction(
+    creator: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<Instruction, anyhow::Error> {
+    use crate::common::fast_fn::get_associated_token_address_with_program_id_fast;
+    use crate::constants::TOKEN_PROGRAM;
+
+    let (fee_vault_authority, _) = get_creator_fee_vault_authority_pda()?;
+    let (creator_fee_vault, _) = get_creator_fee_vault_pda(creator, quote_mint)?;
+    let recipient_token_account =
+        get_associated_token_address_with_program_id_fast(creator, quote_mint, &TOKEN_PROGRAM);
+
+    let accounts = vec![
+        AccountMeta::new(*creator, true),                      // creator
+        AccountMeta::new_readonly(fee_vault_authority, false), // fee_vault_authority
+        AccountMeta::new(creator_fee_vault, false),            // creator_fee_vault
+        AccountMeta::new(recipient_token_account, false),      // recipient_token_account
+        AccountMeta::new_readonly(*quote_mint, false),         // quote_mint
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false),       // token_program
+        AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false), // system_program
+        AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false), // associated_token_program
+    ];
+
+    Ok(Instruction {
+        program_id: accounts::LAUNCHLAB_PROGRAM,
+        accounts,
+        data: discriminators::CLAIM_CREATOR_FEE.to_vec(),
+    })
+}
+
+/// Build claim_platform_fee_from_vault instruction
+///
+/// Sweeps the platform fee vault (seeds: [platform_config, quote_mint]) into the quote token ATA
+/// of the platform's `fee_wallet` (see [`PlatformConfig::fee_wallet`]), which must sign.
+///
+/// # Arguments
+/// * `platform_fee_wallet` - The platform claim fee wallet (signer, pays ATA rent)
+/// * `platform_config` - The platform config account
+/// * `quote_mint` - The quote mint the fees are denominated in (WSOL / USD1)
+pub fn build_claim_platform_fee_instruction(
+    platform_fee_wallet: &Pubkey,
+    platform_config: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Result<Instruction, anyhow::Error> {
+    use crate::common::fast_fn::get_associated_token_address_with_program_id_fast;
+    use crate::constants::TOKEN_PROGRAM;
+
+    let (fee_vault_authority, _) = get_platform_fee_vault_authority_pda()?;
+    let (platform_fee_vault, _) = get_platform_fee_vault_pda(platform_config, quote_mint)?;
+    let recipient_token_account = get_associated_token_address_with_program_id_fast(
+        platform_fee_wallet,
+        quote_mint,
+        &TOKEN_PROGRAM,
+    );
+
+    let accounts = vec![
+        AccountMeta::new(*platform_fee_wallet, true), // platform_fee_wallet
+        AccountMeta::new_readonly(fee_vault_authority, false), // fee_vault_authority
+        AccountMeta::new_readonly(*platform_config, false), // platform_config
+        AccountMeta::new(platform_fee_vault, false),  // platform_fee_vault
+        AccountMeta::new(recipient_token_account, false), // recipient_token_account
+        AccountMeta::new_readonly(*quote_mint, false), // quote_mint
+        AccountMeta::new_readonly(TOKEN_PROGRAM, false), // token_program
+        AccountMeta::new_readonly(accounts::SYSTEM_PROGRAM, false), // system_program
+        AccountMeta::new_readonly(accounts::ASSOCIATED_TOKEN_PROGRAM, false), // associated_token_program
+    ];
+
+    Ok(Instruction {
+        program_id: accounts::LAUNCHLAB_PROGRAM,
+        accounts,
+        data: discriminators::CLAIM_PLATFORM_FEE_FROM_VAULT.to_vec(),
+    })
+}
+
+/// Read the claimable balance of a LaunchLab fee vault (SPL token account); 0 if not created yet
+pub async fn fetch_fee_vault_balance(
+    rpc: &SolanaRpcClient,
+    fee_vault: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let account = rpc.get_account_with_commitment(fee_vault, rpc.commitment()).await?.value;
+    match account {
+        Some(account) if account.data.len() >= 72 => Ok(u64::from_le_bytes(
+            account.data[64..72]
+                .try_into()
+                .map_err(|e| anyhow::anyhow!("Failed to parse fee vault amount: {}", e))?,
+        )),
+        Some(_) => Err(anyhow::anyhow!("Fee vault {} is not a token account", fee_vault)),
+        None => Ok(0),
+    }
+}
+
 /// Build buy_exact_in instruction
 ///
 /// # Arguments
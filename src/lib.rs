@@ -1,3 +1,5 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod common;
 pub mod constants;
 pub mod instruction;
@@ -6,43 +8,80 @@ pub mod perf;
 pub mod swqos;
 pub mod trading;
 pub mod utils;
+use crate::common::Bps;
 use crate::common::CallbackExecutionMode;
 use crate::common::GasFeeStrategy;
 use crate::common::InfrastructureConfig;
+use crate::common::PreflightPolicy;
+use crate::common::RpcProviderRef;
 use crate::common::TradeConfig;
+pub use crate::common::cancellation::{CancellationToken, cancellable};
+pub use crate::common::fee_ledger::{FeeLedger, FeeReport, ProviderFeeStats, TxFee};
 use crate::common::nonce_cache::DurableNonceInfo;
 use crate::constants::SOL_TOKEN_ACCOUNT;
 use crate::constants::USD1_TOKEN_ACCOUNT;
 use crate::constants::USDC_TOKEN_ACCOUNT;
 use crate::constants::WSOL_TOKEN_ACCOUNT;
+pub use crate::constants::registry::{Registry, set_registry};
 #[cfg(feature = "perf-trace")]
 use crate::constants::trade_consts::DEFAULT_SLIPPAGE;
 use crate::swqos::SwqosClient;
 use crate::swqos::SwqosConfig;
+use crate::swqos::SwqosType;
 use crate::swqos::TradeType;
-use crate::swqos::common::TradeError;
+use crate::swqos::common::{ConfirmConfig, TradeError};
+pub use crate::swqos::fanout::SwqosFanout;
+pub use crate::swqos::registry::{ProviderEntry, ProviderFile, ReloadReport, SwqosRegistry};
+pub use crate::swqos::response::SwqosResponse;
 pub use crate::trading::CallbackContext;
 pub use crate::trading::CallbackRef;
+pub use crate::trading::CallbackRegistry;
 use crate::trading::MiddlewareManager;
 pub use crate::trading::NoopCallback;
 use crate::trading::SwapParams;
 use crate::trading::TradeFactory;
+pub use crate::trading::TradeMetadata;
+use crate::trading::TradeQuote;
+pub use crate::trading::TradeTiming;
 pub use crate::trading::TransactionLifecycleCallback;
-use crate::trading::core::params::BonkParams;
+use crate::trading::common::FeePayerRef;
+use crate::trading::common::InstructionLayout;
+use crate::trading::common::TransactionTemplate;
+pub use crate::trading::core::account_recovery::{
+    MissingAccount, MissingAccountKind, SimulationFailure,
+};
 use crate::trading::core::params::DexParamEnum;
-use crate::trading::core::params::MeteoraDammV2Params;
-use crate::trading::core::params::PumpFunParams;
-use crate::trading::core::params::PumpSwapParams;
-use crate::trading::core::params::{RaydiumAmmV4Params, RaydiumClmmParams, RaydiumCpmmParams};
+pub use crate::trading::core::params_cache::{CachedParams, Freshness, ParamsCache, params_cache};
+pub use crate::trading::core::transaction_pool::{PerformanceConfig, reset_pools};
+pub use crate::trading::core::validation::ParamsValidationError;
 pub use crate::trading::factory::DexType;
+use crate::trading::multisig::SquadsConfig;
+pub use crate::trading::notifier::{TradeEvent, WebhookConfig, WebhookNotifier};
+pub use crate::trading::orders::{OrderId, OrderRegistry, OrderStatus, TradeOrder};
+pub use crate::trading::pool_guard::PoolStateCheckConfig;
+pub use crate::trading::rate_limit::{RateLimitMode, WalletRateLimitConfig, WalletRateLimiter};
+use crate::trading::resubmitter::{
+    NonceResubmitter, ResubmitConfig, ResubmitEntry, ResubmitHandle,
+};
+pub use crate::trading::sandwich_guard::{SandwichGuardConfig, SandwichGuardMode};
+pub use crate::trading::self_check::{CheckItem, CheckStatus, StartupReport};
+pub use crate::trading::sol_reserve::SolReserveConfig;
+pub use crate::trading::store::{TradeStore, TradeStoreRef};
+pub use crate::trading::submission_queue::{
+    ProviderRateLimit, SubmissionQueue, SubmissionQueueConfig, Urgency,
+};
+pub use crate::trading::{DuplicateExecution, ResolvedFill, SlippageViolation};
 use common::SolanaRpcClient;
 use parking_lot::Mutex;
+use parser::{DexParser, ParsedTradeInfo};
 use rustls::crypto::{CryptoProvider, ring::default_provider};
 use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::message::AddressLookupTableAccount;
 use solana_sdk::signer::Signer;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signature};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Type of the token to buy
 #[derive(Clone, PartialEq)]
@@ -83,16 +122,20 @@ impl TradingInfrastructure {
         }
 
         // Create RPC client
-        let rpc = Arc::new(SolanaRpcClient::new_with_commitment(
+        let rpc = Arc::new(common::rpc_retry::new_rpc_client_with_retry(
             config.rpc_url.clone(),
             config.commitment,
+            config.rpc_retry.clone(),
         ));
 
         // Initialize rent cache and start background updater
-        common::seed::update_rents(&rpc)
+        common::rent_cache::refresh_now(&rpc)
             .await
             .expect("Failed to initialize rent cache - this is required for trading operations");
-        common::seed::start_rent_updater(rpc.clone());
+        common::rent_cache::start_updater(rpc.clone());
+
+        // Auto 区域先探测选区
+        crate::swqos::region_probe::probe_auto_regions(&config.swqos_configs, true).await;
 
         // Create SWQOS clients with blacklist checking
         let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
@@ -137,8 +180,16 @@ pub struct TradingClient {
     pub payer: Arc<Keypair>,
     /// RPC client for blockchain interactions
     pub rpc: Arc<SolanaRpcClient>,
-    /// SWQOS (Stake-Weighted Quality of Service) clients for transaction priority and routing
+    /// 自定义 RPC 传输（可选），设置后模拟与发送前模拟走该实现
+    pub rpc_provider: Option<RpcProviderRef>,
+    /// SWQOS (Stake-Weighted Quality of Service) clients created at startup
+    ///
+    /// 仅为启动时的快照，不反映运行时黑名单与热加载；当前生效的客户端见 [`TradingClient::swqos_clients`]。
+    #[deprecated(note = "Startup snapshot only; use swqos_clients() for the live provider set")]
     pub swqos_clients: Vec<Arc<SwqosClient>>,
+    /// SWQOS (Stake-Weighted Quality of Service) provider registry, shared across clones
+    /// 运行时黑名单与热加载见 [`SwqosRegistry`]
+    pub swqos_registry: Arc<SwqosRegistry>,
     /// Optional middleware manager for custom transaction processing
     pub middleware_manager: Option<Arc<MiddlewareManager>>,
     /// Whether to use seed optimization for all ATA operations (default: false)
@@ -170,24 +221,87 @@ pub struct TradingClient {
     /// buy_params.enable_jito_sandwich_protection = Some(true); // 强制启用
     /// ```
     pub enable_jito_sandwich_protection: bool,
+    /// 在途订单登记表（用于 replace_trade / cancel_order）
+    pub orders: Arc<OrderRegistry>,
+    /// 手续费账本：按 SWQOS 与自然日累计优先费与小费支出
+    pub fee_ledger: Arc<FeeLedger>,
+    /// 交易日志存储（可选），在交易生命周期中自动写入
+    pub trade_store: Option<TradeStoreRef>,
+    /// Webhook 通知器（可选），推送每次买入/卖出的执行结果
+    pub notifier: Option<Arc<WebhookNotifier>>,
+    /// 发送前模拟策略（全局默认配置）
+    pub preflight: PreflightPolicy,
+    /// 是否通过 RPC 自动补全协议参数中的占位字段
+    pub auto_fill_params: bool,
+    /// 卖出前是否检查持仓账户冻结 / mint 永久委托
+    pub check_sell_accounts: bool,
+    /// 交易指令排列策略（全局默认配置）
+    pub instruction_layout: InstructionLayout,
+    /// 客户端级签名回调注册表，与交易级 `on_transaction_signed` 合并执行
+    pub callbacks: CallbackRegistry,
+    /// Squads 多签执行模式（可选）
+    pub multisig: Option<SquadsConfig>,
+    /// 转账收款地址白名单（None 表示不限制）
+    pub transfer_allow_list: Option<Vec<Pubkey>>,
+    /// 发送前三明治风险检查（None 表示不检查）
+    pub sandwich_guard: Option<SandwichGuardConfig>,
+    /// 钱包交易频率限制（None 表示不限制），克隆之间共享
+    pub rate_limiter: Option<Arc<WalletRateLimiter>>,
+    /// 付款钱包最低 SOL 保留额（None 表示不保留）
+    pub sol_reserve: Option<SolReserveConfig>,
+    /// 等待确认的默认超时与轮询间隔
+    pub confirm: ConfirmConfig,
+    /// 交易前池子状态检查（None 表示不检查）
+    pub pool_state_check: Option<PoolStateCheckConfig>,
+    /// 按紧急程度排队的提交队列（None 表示不排队），克隆之间共享
+    pub submission_queue: Option<Arc<SubmissionQueue>>,
+    /// SWQOS 分发顺序与间隔（None 表示同时发送）
+    pub swqos_fanout: Option<Arc<SwqosFanout>>,
+    /// 创建时的配置静态检查结果，包含在 [`TradingClient::startup_report`] 中
+    pub config_checks: Arc<Vec<CheckItem>>,
 }
 
 static INSTANCE: Mutex<Option<Arc<TradingClient>>> = Mutex::new(None);
 
+/// SWQOS 配置文件修改检查间隔
+const SWQOS_CONFIG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// 🔄 向后兼容：SolanaTrade 别名
 pub type SolanaTrade = TradingClient;
 
 impl Clone for TradingClient {
+    #[allow(deprecated)]
     fn clone(&self) -> Self {
         Self {
             payer: self.payer.clone(),
             rpc: self.rpc.clone(),
+            rpc_provider: self.rpc_provider.clone(),
             swqos_clients: self.swqos_clients.clone(),
+            swqos_registry: self.swqos_registry.clone(),
             middleware_manager: self.middleware_manager.clone(),
             use_seed_optimize: self.use_seed_optimize,
             callback_execution_mode: self.callback_execution_mode,
             enable_jito_sandwich_protection: self.enable_jito_sandwich_protection,
             infrastructure: self.infrastructure.clone(),
+            orders: self.orders.clone(),
+            fee_ledger: self.fee_ledger.clone(),
+            trade_store: self.trade_store.clone(),
+            notifier: self.notifier.clone(),
+            preflight: self.preflight,
+            auto_fill_params: self.auto_fill_params,
+            check_sell_accounts: self.check_sell_accounts,
+            instruction_layout: self.instruction_layout,
+            callbacks: self.callbacks.clone(),
+            multisig: self.multisig.clone(),
+            transfer_allow_list: self.transfer_allow_list.clone(),
+            sandwich_guard: self.sandwich_guard,
+            rate_limiter: self.rate_limiter.clone(),
+            sol_reserve: self.sol_reserve,
+            confirm: self.confirm,
+            pool_state_check: self.pool_state_check,
+            submission_queue: self.submission_queue.clone(),
+            swqos_fanout: self.swqos_fanout.clone(),
+            config_checks: self.config_checks.clone(),
         }
     }
 }
@@ -207,10 +321,14 @@ pub struct TradeBuyParams {
     pub mint: Pubkey,
     /// Amount of tokens to buy (in smallest token units)
     pub input_token_amount: u64,
-    /// Optional slippage tolerance in basis points (e.g., 100 = 1%)
-    pub slippage_basis_points: Option<u64>,
+    /// Optional slippage tolerance in basis points (e.g., `Bps::new(100)` = 1%)
+    pub slippage_basis_points: Option<Bps>,
     /// Recent blockhash for transaction validity
     pub recent_blockhash: Option<Hash>,
+    /// Last block height at which `recent_blockhash` is valid (optional)
+    ///
+    /// 随交易写入交易日志，重启恢复时据此判断未确认的交易是否已过期；未设置时通过 RPC 校验 blockhash。
+    pub last_valid_block_height: Option<u64>,
     /// Protocol-specific parameters (PumpFun, Raydium, etc.)
     pub extension_params: DexParamEnum,
     // Extended configuration
@@ -251,6 +369,41 @@ pub struct TradeBuyParams {
     ///
     /// 参见 `TradeConfig.enable_jito_sandwich_protection` 字段的详细文档。
     pub enable_jito_sandwich_protection: Option<bool>,
+    /// 是否使用 seed 优化创建/查找代币账户（可选，覆盖全局配置）
+    ///
+    /// - `Some(true)`：使用 seed 账户
+    /// - `Some(false)`：使用标准 ATA（部分 Token-2022 mint 与 seed 账户不兼容）
+    /// - `None`：使用全局配置（TradeConfig.use_seed_optimize）
+    pub use_seed_optimize: Option<bool>,
+    /// 在协议指令之前插入的自定义指令（例如第三方程序 CPI、额外转账）
+    ///
+    /// 交易结构：[nonce, tip, compute_budget, pre_swap..., swap..., post_swap...]
+    /// 构建后会校验交易大小不超过 1232 字节。可包含 SetComputeUnitLimit / SetComputeUnitPrice
+    /// （pre / post 合计每种最多一条），替换 gas 策略生成的对应指令；费用上限仍按 gas 策略校验。
+    pub pre_swap_instructions: Vec<Instruction>,
+    /// 在协议指令之后插入的自定义指令
+    pub post_swap_instructions: Vec<Instruction>,
+    /// 用户自定义交易上下文（策略标识、关联 ID 等），传递给中间件、回调与事件
+    pub metadata: Option<TradeMetadata>,
+    /// 第三方手续费支付者（代付 gas，可为远程签名器），必须与交易钱包不同
+    ///
+    /// 设置后其作为交易的 account[0] 支付基础手续费与优先费，交易钱包仍支付 ATA 租金与小费。
+    pub fee_payer: Option<FeePayerRef>,
+    /// 提交队列中的紧急程度（仅在配置了 `TradeConfig::submission_queue` 时生效）
+    ///
+    /// - `None`：买入为 `Urgency::Normal`，卖出为 `Urgency::Exit`
+    pub urgency: Option<Urgency>,
+    /// 等待确认的超时时间（可选，覆盖全局配置 `TradeConfig.confirm_timeout`）
+    ///
+    /// 超时返回 `TradeError::CONFIRMATION_TIMED_OUT`，交易仍可能上链，可继续跟踪返回的签名。
+    pub confirm_timeout: Option<Duration>,
+    /// 等待确认的轮询间隔（可选，覆盖全局配置 `TradeConfig.confirm_poll_interval`）
+    pub confirm_poll_interval: Option<Duration>,
+    /// 取消令牌（可选）
+    ///
+    /// 发送前取消则不发送交易并返回 `TradeError::CANCELLED`；发送后取消则停止提交与确认轮询，
+    /// 已提交的签名照常返回。
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 /// Parameters for executing sell orders across different DEX protocols
@@ -268,10 +421,14 @@ pub struct TradeSellParams {
     pub mint: Pubkey,
     /// Amount of tokens to sell (in smallest token units)
     pub input_token_amount: u64,
-    /// Optional slippage tolerance in basis points (e.g., 100 = 1%)
-    pub slippage_basis_points: Option<u64>,
+    /// Optional slippage tolerance in basis points (e.g., `Bps::new(100)` = 1%)
+    pub slippage_basis_points: Option<Bps>,
     /// Recent blockhash for transaction validity
     pub recent_blockhash: Option<Hash>,
+    /// Last block height at which `recent_blockhash` is valid (optional)
+    ///
+    /// 随交易写入交易日志，重启恢复时据此判断未确认的交易是否已过期；未设置时通过 RPC 校验 blockhash。
+    pub last_valid_block_height: Option<u64>,
     /// Whether to include tip for transaction priority
     pub with_tip: bool,
     /// Protocol-specific parameters (PumpFun, Raydium, etc.)
@@ -314,6 +471,71 @@ pub struct TradeSellParams {
     ///
     /// 参见 `TradeConfig.enable_jito_sandwich_protection` 字段的详细文档。
     pub enable_jito_sandwich_protection: Option<bool>,
+    /// 是否使用 seed 优化创建/查找代币账户（可选，覆盖全局配置）
+    ///
+    /// - `Some(true)`：使用 seed 账户
+    /// - `Some(false)`：使用标准 ATA（部分 Token-2022 mint 与 seed 账户不兼容）
+    /// - `None`：使用全局配置（TradeConfig.use_seed_optimize）
+    pub use_seed_optimize: Option<bool>,
+    /// 在协议指令之前插入的自定义指令（例如第三方程序 CPI、额外转账）
+    ///
+    /// 交易结构：[nonce, tip, compute_budget, pre_swap..., swap..., post_swap...]
+    /// 构建后会校验交易大小不超过 1232 字节。可包含 SetComputeUnitLimit / SetComputeUnitPrice
+    /// （pre / post 合计每种最多一条），替换 gas 策略生成的对应指令；费用上限仍按 gas 策略校验。
+    pub pre_swap_instructions: Vec<Instruction>,
+    /// 在协议指令之后插入的自定义指令
+    pub post_swap_instructions: Vec<Instruction>,
+    /// 用户自定义交易上下文（策略标识、关联 ID 等），传递给中间件、回调与事件
+    pub metadata: Option<TradeMetadata>,
+    /// 第三方手续费支付者（代付 gas，可为远程签名器），必须与交易钱包不同
+    ///
+    /// 设置后其作为交易的 account[0] 支付基础手续费与优先费，交易钱包仍支付 ATA 租金与小费。
+    pub fee_payer: Option<FeePayerRef>,
+    /// 提交队列中的紧急程度（仅在配置了 `TradeConfig::submission_queue` 时生效）
+    ///
+    /// - `None`：买入为 `Urgency::Normal`，卖出为 `Urgency::Exit`
+    pub urgency: Option<Urgency>,
+    /// 等待确认的超时时间（可选，覆盖全局配置 `TradeConfig.confirm_timeout`）
+    ///
+    /// 超时返回 `TradeError::CONFIRMATION_TIMED_OUT`，交易仍可能上链，可继续跟踪返回的签名。
+    pub confirm_timeout: Option<Duration>,
+    /// 等待确认的轮询间隔（可选，覆盖全局配置 `TradeConfig.confirm_poll_interval`）
+    pub confirm_poll_interval: Option<Duration>,
+    /// 取消令牌（可选）
+    ///
+    /// 发送前取消则不发送交易并返回 `TradeError::CANCELLED`；发送后取消则停止提交与确认轮询，
+    /// 已提交的签名照常返回。
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+/// Result of a buy/sell including the fees actually paid
+///
+/// `fees` 只包含已确认上链的交易（需 `wait_transaction_confirmed = true`），
+/// 未等待确认时为空。`timing` 为本次交易的耗时分解；未等待确认时，
+/// 返回时尚未完成提交的 provider 不会出现在 `timing.providers` 中。
+#[derive(Debug)]
+pub struct TradeResult {
+    /// 是否至少有一个交易成功
+    pub success: bool,
+    /// 所有提交的交易签名（按SWQOS顺序）
+    pub signatures: Vec<Signature>,
+    /// 最后一个错误（如果全部失败）
+    pub error: Option<TradeError>,
+    /// 已上链交易的手续费明细
+    pub fees: Vec<TxFee>,
+    /// 构建、签名、提交、确认各阶段耗时（交易未发送时为 `None`）
+    pub timing: Option<TradeTiming>,
+    /// 各 SWQOS 的原始提交响应（bundle ID、中继 ID、错误响应体、HTTP 状态、Retry-After）
+    pub responses: Vec<SwqosResponse>,
+    /// 从发送到上链经过的 slot 数（仅等待确认且已上链时）
+    pub slots_waited: Option<u64>,
+}
+
+impl TradeResult {
+    /// 本次交易支付的优先费与小费合计（lamports）
+    pub fn fee_lamports(&self) -> u64 {
+        self.fees.iter().map(TxFee::total_lamports).sum()
+    }
 }
 
 impl TradingClient {
@@ -342,26 +564,65 @@ impl TradingClient {
             eprintln!("    Crypto operations may fail. Continuing anyway...");
         }
 
+        if let Err(e) = crate::perf::configure_signing_pool(trade_config.signing_threads) {
+            eprintln!("⚠️  Failed to start signing pool: {e}; signing on send tasks instead");
+        }
+
+        crate::common::pricing::set_pricing_config(trade_config.pricing.clone());
+        crate::common::cluster::set_cluster(trade_config.cluster);
+        crate::trading::core::transaction_pool::set_performance_config(trade_config.performance);
+
+        if let Err(e) = trade_config.validate() {
+            eprintln!("⚠️  {}", e);
+        }
+
         let rpc_url = trade_config.rpc_url.clone();
         let swqos_configs = trade_config.swqos_configs.clone();
         let commitment = trade_config.commitment;
-        let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
+        let swqos_registry = match &trade_config.swqos_config_file {
+            Some(path) => {
+                let registry =
+                    Arc::new(SwqosRegistry::new(rpc_url.clone(), commitment, vec![]).await);
+                if let Err(e) = registry.reload_from_file(path).await {
+                    eprintln!("⚠️  Failed to load SWQOS config file {}: {}", path.display(), e);
+                    if let Err(e) = registry.reload(swqos_configs).await {
+                        eprintln!("{}", e);
+                    }
+                }
+                registry.watch_file(path.clone(), SWQOS_CONFIG_POLL_INTERVAL);
+                #[cfg(unix)]
+                if let Err(e) = registry.reload_on_sighup(path.clone()) {
+                    eprintln!("⚠️  Failed to install SIGHUP handler: {}", e);
+                }
+                registry
+            },
+            None => Arc::new(SwqosRegistry::new(rpc_url.clone(), commitment, swqos_configs).await),
+        };
+        swqos_registry.start_region_probe(crate::swqos::region_probe::PROBE_INTERVAL);
 
-        for swqos in swqos_configs {
-            match SwqosConfig::get_swqos_client(rpc_url.clone(), commitment, swqos.clone()).await {
-                Ok(client) => swqos_clients.push(client),
-                Err(e) => {
-                    eprintln!("Failed to create SWQOS client {:?}: {}", swqos, e);
+        let rpc = Arc::new(common::rpc_retry::new_rpc_client_with_retry(
+            rpc_url.clone(),
+            commitment,
+            trade_config.rpc_retry.clone(),
+        ));
+        common::rent_cache::refresh_now(&rpc)
+            .await
+            .expect("Failed to initialize rent cache - this is required for trading operations");
+        common::rent_cache::start_updater(rpc.clone());
+
+        if let Some(probe) = &trade_config.program_probe {
+            let result = crate::common::program_probe::probe_programs(&rpc, probe)
+                .await
+                .and_then(|report| report.enforce(probe.mode));
+            match result {
+                Err(e) if probe.mode == crate::common::ProbeMode::Error => {
+                    panic!("❌ 协议程序版本探测失败: {}", e);
                 },
+                Err(e) => eprintln!("⚠️  协议程序版本探测失败: {}", e),
+                Ok(()) => {},
             }
         }
 
-        let rpc = Arc::new(SolanaRpcClient::new_with_commitment(rpc_url.clone(), commitment));
-        common::seed::update_rents(&rpc)
-            .await
-            .expect("Failed to initialize rent cache - this is required for trading operations");
-        common::seed::start_rent_updater(rpc.clone());
-
         // 🔧 初始化WSOL ATA：如果配置为启动时创建，则检查并创建
         if trade_config.create_wsol_ata_on_startup {
             // 根据seed配置计算WSOL ATA地址
@@ -428,15 +689,45 @@ impl TradingClient {
             }
         }
 
+        #[allow(deprecated)]
         let instance = Self {
             payer,
             rpc,
-            swqos_clients,
+            rpc_provider: None,
+            swqos_clients: swqos_registry.clients().as_ref().clone(),
+            swqos_registry,
             middleware_manager: None,
             use_seed_optimize: trade_config.use_seed_optimize,
             callback_execution_mode: trade_config.callback_execution_mode,
             enable_jito_sandwich_protection: trade_config.enable_jito_sandwich_protection,
             infrastructure: None,
+            orders: Arc::new(OrderRegistry::new()),
+            fee_ledger: Arc::new(FeeLedger::new()),
+            trade_store: None,
+            notifier: None,
+            preflight: trade_config.preflight,
+            auto_fill_params: trade_config.auto_fill_params,
+            check_sell_accounts: trade_config.check_sell_accounts,
+            instruction_layout: trade_config.instruction_layout,
+            callbacks: CallbackRegistry::new(),
+            multisig: None,
+            transfer_allow_list: trade_config.transfer_allow_list.clone(),
+            sandwich_guard: trade_config.sandwich_guard,
+            rate_limiter: trade_config
+                .wallet_rate_limit
+                .map(|config| Arc::new(WalletRateLimiter::new(config))),
+            sol_reserve: trade_config.sol_reserve,
+            confirm: ConfirmConfig::new(
+                trade_config.confirm_timeout,
+                trade_config.confirm_poll_interval,
+            ),
+            pool_state_check: trade_config.pool_state_check,
+            submission_queue: trade_config
+                .submission_queue
+                .clone()
+                .map(|config| Arc::new(SubmissionQueue::new(config))),
+            swqos_fanout: trade_config.swqos_fanout.clone().map(Arc::new),
+            config_checks: Arc::new(trade_config.check()),
         };
 
         let mut current = INSTANCE.lock();
@@ -445,6 +736,19 @@ impl TradingClient {
         instance
     }
 
+    /// Creates a client whose payer is loaded from an encrypted keystore file
+    ///
+    /// 密钥文件由 [`common::keystore::save_keypair`] 生成；口令可通过
+    /// [`common::keystore::passphrase_from_env`] 从环境变量读取，避免在源码中保存明文私钥。
+    pub async fn from_keystore(
+        path: impl AsRef<std::path::Path>,
+        passphrase: &str,
+        trade_config: TradeConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let payer = common::keystore::load_keypair(path, passphrase)?;
+        Ok(Self::new(Arc::new(payer), trade_config).await)
+    }
+
     /// Adds a middleware manager to the SolanaTrade instance
     ///
     /// Middleware managers can be used to implement custom logic that runs before or after trading operations,
@@ -460,6 +764,135 @@ impl TradingClient {
         self
     }
 
+    /// Plug in a custom RPC transport
+    ///
+    /// 自定义实现（gRPC RPC、缓存代理、测试替身等）用于模拟模式与发送前模拟；
+    /// 其余读取仍使用 `rpc`。
+    pub fn with_rpc_provider(mut self, provider: RpcProviderRef) -> Self {
+        self.rpc_provider = Some(provider);
+        self
+    }
+
+    /// Execute trades through a Squads multisig treasury
+    ///
+    /// 构建好的指令以金库为交易主体，包装为 Squads 提案（`vault_transaction_create` +
+    /// `proposal_create`，可选 `proposal_approve`），由 payer 作为多签成员签名发送；
+    /// 提案达到阈值后在 Squads 中执行。
+    pub fn with_squads_multisig(mut self, config: SquadsConfig) -> Self {
+        self.multisig = Some(config);
+        self
+    }
+
+    /// Register a client-level signed-transaction callback
+    ///
+    /// 可注册多个回调（入库、指标、webhook 等），按 `order` 从小到大执行；
+    /// `mode` 为 `None` 时使用交易级 / 全局的 `callback_execution_mode`。
+    pub fn with_callback(
+        mut self,
+        callback: CallbackRef,
+        order: i32,
+        mode: Option<CallbackExecutionMode>,
+    ) -> Self {
+        self.callbacks.register(callback, order, mode);
+        self
+    }
+
+    /// Attach a trade store that journals every signed/submitted/landed transaction
+    ///
+    /// 待确认记录在发送前写入，其余状态在后台更新；成交明细需调用 [`TradingClient::record_fill`] 写入。
+    pub fn with_trade_store(mut self, trade_store: TradeStoreRef) -> Self {
+        self.trade_store = Some(trade_store);
+        self
+    }
+
+    /// Attach a webhook notifier that receives the result of every buy/sell
+    ///
+    /// 如需同时推送签名事件，可将同一个 notifier 设置为 `on_transaction_signed` 回调。
+    pub fn with_webhook_notifier(mut self, notifier: WebhookNotifier) -> Self {
+        self.notifier = Some(Arc::new(notifier));
+        self
+    }
+
+    /// Parse a landed transaction and record its fills into the attached trade store
+    ///
+    /// 未设置 trade store 时仅返回解析结果。
+    pub async fn record_fill(
+        &self,
+        parser: &DexParser,
+        signature: &Signature,
+    ) -> Result<Vec<ParsedTradeInfo>, anyhow::Error> {
+        let result = parser.parse_transaction(&signature.to_string()).await;
+        if let Some(error) = result.error {
+            return Err(anyhow::anyhow!("Failed to parse {}: {}", signature, error));
+        }
+        if let Some(store) = &self.trade_store {
+            for fill in &result.trades {
+                store.record_fill(fill).await?;
+            }
+        }
+        Ok(result.trades)
+    }
+
+    /// Fetch a landed transaction and resolve its executed amounts, fees and effective price
+    ///
+    /// 使用 crate 自带的解析器解析交易，参见 [`ResolvedFill`]。
+    pub async fn resolve_fill(&self, signature: &Signature) -> Result<ResolvedFill, anyhow::Error> {
+        trading::fill::resolve_fill(&self.rpc, signature).await
+    }
+
+    /// Resolve a landed transaction and check it against the quote used to build it
+    ///
+    /// 实际输出低于报价的最小输出时记录警告日志，并向 webhook 投递
+    /// [`TradeEvent::SlippageViolation`]。报价可从回调上下文的
+    /// [`trading::TradeDetails::quote`] 获取。
+    pub async fn verify_fill(
+        &self,
+        signature: &Signature,
+        quote: &TradeQuote,
+    ) -> Result<(ResolvedFill, Option<SlippageViolation>), anyhow::Error> {
+        let fill = self.resolve_fill(signature).await?;
+        let violation = fill.check_slippage(quote);
+        if let Some(violation) = violation {
+            log::warn!("slippage violation detected: {}", violation.to_json());
+            if let Some(notifier) = &self.notifier {
+                notifier.notify(TradeEvent::SlippageViolation(violation));
+            }
+        }
+        Ok((fill, violation))
+    }
+
+    /// Resolve every signature returned by one buy/sell and detect duplicate landings
+    ///
+    /// 不使用 durable nonce 时，同一笔交易在各 provider 上的签名不同，可能被中继重复打包。
+    /// 传入同一次买入/卖出返回的全部签名：未上链或无法拉取的签名会被忽略，成功上链超过一笔时
+    /// 每笔重复成交记录警告日志并向 webhook 投递 [`TradeEvent::DuplicateExecution`]，
+    /// 调用方可据 [`DuplicateExecution`] 中的数量修正持仓。
+    pub async fn check_duplicate_execution(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<(Vec<ResolvedFill>, Vec<DuplicateExecution>), anyhow::Error> {
+        let mut unique: Vec<Signature> = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            if *signature != Signature::default() && !unique.contains(signature) {
+                unique.push(*signature);
+            }
+        }
+        let fills: Vec<ResolvedFill> =
+            futures::future::join_all(unique.iter().map(|signature| self.resolve_fill(signature)))
+                .await
+                .into_iter()
+                .filter_map(|fill| fill.ok())
+                .collect();
+        let duplicates = trading::detect_duplicates(&fills);
+        for duplicate in &duplicates {
+            log::warn!("duplicate execution detected: {}", duplicate.to_json());
+            if let Some(notifier) = &self.notifier {
+                notifier.notify(TradeEvent::DuplicateExecution(*duplicate));
+            }
+        }
+        Ok((fills, duplicates))
+    }
+
     /// Gets the RPC client instance for direct Solana blockchain interactions
     ///
     /// This provides access to the underlying Solana RPC client that can be used
@@ -471,6 +904,11 @@ impl TradingClient {
         &self.rpc
     }
 
+    /// 当前生效的 SWQOS 客户端快照（黑名单与热加载之后），不复制客户端列表
+    pub fn swqos_clients(&self) -> Arc<Vec<Arc<SwqosClient>>> {
+        self.swqos_registry.clients()
+    }
+
     /// Gets the current globally shared SolanaTrade instance
     ///
     /// This provides access to the singleton instance that was created with `new()`.
@@ -518,6 +956,119 @@ impl TradingClient {
         &self,
         params: TradeBuyParams,
     ) -> Result<(bool, Vec<Signature>, Option<TradeError>), anyhow::Error> {
+        let (dex_type, mint) = (params.dex_type.clone(), params.mint);
+        let metadata = self.notifier.as_ref().and(params.metadata.clone()).map(Arc::new);
+        let result = self.execute_buy(params).await;
+        if let Some(notifier) = &self.notifier {
+            notifier.notify_trade(TradeType::Buy, dex_type, mint, metadata, &result);
+        }
+        result
+    }
+
+    async fn execute_buy(
+        &self,
+        mut params: TradeBuyParams,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>), anyhow::Error> {
+        let token = params.cancellation_token.clone();
+        let Some(rejected) = cancellable(token.as_ref(), self.buy_preflight(&mut params)).await
+        else {
+            return Ok((
+                false,
+                vec![],
+                Some(TradeError::cancelled("buy cancelled before submission")),
+            ));
+        };
+        if let Some(error) = rejected? {
+            return Ok((false, vec![], Some(error)));
+        }
+        let executor = TradeFactory::create_executor(params.dex_type.clone());
+        let buy_params = self.build_buy_swap_params(params)?;
+
+        let swap_result = executor.swap(buy_params).await;
+
+        swap_result.map(|(success, sigs, err)| (success, sigs, err.map(TradeError::from)))
+    }
+
+    /// 买入发送前的参数补全与各项检查，返回 `Some` 表示交易被拒绝
+    pub(crate) async fn buy_preflight(
+        &self,
+        params: &mut TradeBuyParams,
+    ) -> Result<Option<TradeError>, anyhow::Error> {
+        self.fill_protocol_params(
+            &mut params.extension_params,
+            &params.mint,
+            &params.input_token_type,
+        )
+        .await?;
+        if let Some(error) = self.check_pool_state(&params.extension_params, &params.mint).await? {
+            return Ok(Some(error));
+        }
+        if let Some(error) = self.check_sandwich_guard(
+            &params.extension_params,
+            &params.mint,
+            true,
+            params.input_token_amount,
+            params.slippage_basis_points,
+            params.enable_jito_sandwich_protection,
+        ) {
+            return Ok(Some(error));
+        }
+        if let Some(error) = self
+            .check_sol_reserve(
+                &params.input_token_type,
+                &mut params.input_token_amount,
+                &params.gas_fee_strategy,
+                params.simulate,
+            )
+            .await?
+        {
+            return Ok(Some(error));
+        }
+        Ok(self.check_rate_limit(&params.mint, params.simulate).await)
+    }
+
+    /// 开启 `auto_fill_params` 时，从链上补全协议参数中的占位字段
+    async fn fill_protocol_params(
+        &self,
+        extension_params: &mut DexParamEnum,
+        mint: &Pubkey,
+        quote_token_type: &TradeTokenType,
+    ) -> Result<(), anyhow::Error> {
+        if !self.auto_fill_params {
+            return Ok(());
+        }
+        let quote_mint = if *quote_token_type == TradeTokenType::USD1 {
+            USD1_TOKEN_ACCOUNT
+        } else {
+            WSOL_TOKEN_ACCOUNT
+        };
+        if extension_params.fill_missing(&self.rpc, mint, &quote_mint).await? {
+            log::debug!(
+                "auto-filled {:?} params for {} from chain",
+                extension_params.dex_type(),
+                mint
+            );
+        }
+        Ok(())
+    }
+
+    /// 交易级确认超时 / 轮询间隔覆盖全局配置
+    fn confirm_config(
+        &self,
+        timeout: Option<Duration>,
+        poll_interval: Option<Duration>,
+    ) -> ConfirmConfig {
+        ConfirmConfig {
+            timeout: timeout.unwrap_or(self.confirm.timeout),
+            poll_interval: poll_interval.unwrap_or(self.confirm.poll_interval),
+        }
+    }
+
+    /// 将 `TradeBuyParams` 转换为执行器使用的 `SwapParams`（含协议参数校验）
+    pub(crate) fn build_buy_swap_params(
+        &self,
+        params: TradeBuyParams,
+    ) -> Result<SwapParams, anyhow::Error> {
         #[cfg(feature = "perf-trace")]
         if params.slippage_basis_points.is_none() {
             log::debug!(
@@ -539,10 +1090,14 @@ impl TradingClient {
         } else {
             USD1_TOKEN_ACCOUNT
         };
-        let executor = TradeFactory::create_executor(params.dex_type.clone());
         let protocol_params = params.extension_params;
+        protocol_params.validate(&params.dex_type)?;
+        if let Some(fee_payer) = &params.fee_payer {
+            crate::trading::common::fee_payer::validate_fee_payer(fee_payer, &self.payer.pubkey())?;
+        }
         let buy_params = SwapParams {
             rpc: Some(self.rpc.clone()),
+            rpc_provider: self.rpc_provider.clone(),
             payer: self.payer.clone(),
             trade_type: TradeType::Buy,
             input_mint: input_token_mint,
@@ -553,10 +1108,11 @@ impl TradingClient {
             slippage_basis_points: params.slippage_basis_points,
             address_lookup_table_account: params.address_lookup_table_account,
             recent_blockhash: params.recent_blockhash,
+            last_valid_block_height: params.last_valid_block_height,
             wait_transaction_confirmed: params.wait_transaction_confirmed,
-            protocol_params: protocol_params.clone(),
-            open_seed_optimize: self.use_seed_optimize, // 使用全局seed优化配置
-            swqos_clients: self.swqos_clients.clone(),
+            protocol_params,
+            open_seed_optimize: params.use_seed_optimize.unwrap_or(self.use_seed_optimize),
+            swqos_clients: self.swqos_clients().as_ref().clone(),
             middleware_manager: self.middleware_manager.clone(),
             durable_nonce: params.durable_nonce,
             with_tip: true,
@@ -567,43 +1123,30 @@ impl TradingClient {
             fixed_output_amount: params.fixed_output_token_amount,
             gas_fee_strategy: params.gas_fee_strategy,
             simulate: params.simulate,
-            on_transaction_signed: params.on_transaction_signed,
+            on_transaction_signed: self.callbacks.merge_with(params.on_transaction_signed),
             callback_execution_mode: params
                 .callback_execution_mode
                 .or(Some(self.callback_execution_mode)),
             enable_jito_sandwich_protection: params
                 .enable_jito_sandwich_protection
                 .or(Some(self.enable_jito_sandwich_protection)),
+            pre_swap_instructions: params.pre_swap_instructions,
+            post_swap_instructions: params.post_swap_instructions,
+            metadata: params.metadata.map(Arc::new),
+            fee_ledger: Some(self.fee_ledger.clone()),
+            trade_store: self.trade_store.clone(),
+            preflight: self.preflight,
+            instruction_layout: self.instruction_layout,
+            multisig: self.multisig.clone(),
+            fee_payer: params.fee_payer,
+            submission_queue: self.submission_queue.clone(),
+            urgency: params.urgency.unwrap_or(Urgency::Normal),
+            confirm: self.confirm_config(params.confirm_timeout, params.confirm_poll_interval),
+            cancellation_token: params.cancellation_token,
+            fanout: self.swqos_fanout.clone(),
         };
 
-        // Validate protocol params
-        let is_valid_params = match params.dex_type {
-            DexType::PumpFun => protocol_params.as_any().downcast_ref::<PumpFunParams>().is_some(),
-            DexType::PumpSwap => {
-                protocol_params.as_any().downcast_ref::<PumpSwapParams>().is_some()
-            },
-            DexType::Bonk => protocol_params.as_any().downcast_ref::<BonkParams>().is_some(),
-            DexType::RaydiumCpmm => {
-                protocol_params.as_any().downcast_ref::<RaydiumCpmmParams>().is_some()
-            },
-            DexType::RaydiumAmmV4 => {
-                protocol_params.as_any().downcast_ref::<RaydiumAmmV4Params>().is_some()
-            },
-            DexType::RaydiumClmm => {
-                protocol_params.as_any().downcast_ref::<RaydiumClmmParams>().is_some()
-            },
-            DexType::MeteoraDammV2 => {
-                protocol_params.as_any().downcast_ref::<MeteoraDammV2Params>().is_some()
-            },
-        };
-
-        if !is_valid_params {
-            return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
-        }
-
-        let swap_result = executor.swap(buy_params).await;
-
-        swap_result.map(|(success, sigs, err)| (success, sigs, err.map(TradeError::from)))
+        Ok(buy_params)
     }
 
     /// Execute a sell order for a specified token
@@ -636,6 +1179,83 @@ impl TradingClient {
         &self,
         params: TradeSellParams,
     ) -> Result<(bool, Vec<Signature>, Option<TradeError>), anyhow::Error> {
+        let (dex_type, mint) = (params.dex_type.clone(), params.mint);
+        let metadata = self.notifier.as_ref().and(params.metadata.clone()).map(Arc::new);
+        let result = self.execute_sell(params).await;
+        if let Some(notifier) = &self.notifier {
+            notifier.notify_trade(TradeType::Sell, dex_type, mint, metadata, &result);
+        }
+        result
+    }
+
+    async fn execute_sell(
+        &self,
+        mut params: TradeSellParams,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>), anyhow::Error> {
+        let token = params.cancellation_token.clone();
+        let Some(rejected) = cancellable(token.as_ref(), self.sell_preflight(&mut params)).await
+        else {
+            return Ok((
+                false,
+                vec![],
+                Some(TradeError::cancelled("sell cancelled before submission")),
+            ));
+        };
+        if let Some(error) = rejected? {
+            return Ok((false, vec![], Some(error)));
+        }
+        let executor = TradeFactory::create_executor(params.dex_type.clone());
+        let sell_params = self.build_sell_swap_params(params)?;
+
+        // Execute sell based on tip preference
+        let swap_result = executor.swap(sell_params).await;
+
+        swap_result.map(|(success, sigs, err)| (success, sigs, err.map(TradeError::from)))
+    }
+
+    /// 卖出发送前的参数补全与各项检查，返回 `Some` 表示交易被拒绝
+    pub(crate) async fn sell_preflight(
+        &self,
+        params: &mut TradeSellParams,
+    ) -> Result<Option<TradeError>, anyhow::Error> {
+        self.fill_protocol_params(
+            &mut params.extension_params,
+            &params.mint,
+            &params.output_token_type,
+        )
+        .await?;
+        if self.check_sell_accounts
+            && let Some(error) = crate::trading::sell_guard::check_sell_accounts(
+                &self.rpc,
+                &self.payer.pubkey(),
+                &params.mint,
+                params.use_seed_optimize.unwrap_or(self.use_seed_optimize),
+            )
+            .await?
+        {
+            return Ok(Some(error));
+        }
+        if let Some(error) = self.check_pool_state(&params.extension_params, &params.mint).await? {
+            return Ok(Some(error));
+        }
+        if let Some(error) = self.check_sandwich_guard(
+            &params.extension_params,
+            &params.mint,
+            false,
+            params.input_token_amount,
+            params.slippage_basis_points,
+            params.enable_jito_sandwich_protection,
+        ) {
+            return Ok(Some(error));
+        }
+        Ok(self.check_rate_limit(&params.mint, params.simulate).await)
+    }
+
+    /// 将 `TradeSellParams` 转换为执行器使用的 `SwapParams`（含协议参数校验）
+    pub(crate) fn build_sell_swap_params(
+        &self,
+        params: TradeSellParams,
+    ) -> Result<SwapParams, anyhow::Error> {
         #[cfg(feature = "perf-trace")]
         if params.slippage_basis_points.is_none() {
             log::debug!(
@@ -648,8 +1268,11 @@ impl TradingClient {
                 " Current version only support USD1 trading on Bonk protocols"
             ));
         }
-        let executor = TradeFactory::create_executor(params.dex_type.clone());
         let protocol_params = params.extension_params;
+        protocol_params.validate(&params.dex_type)?;
+        if let Some(fee_payer) = &params.fee_payer {
+            crate::trading::common::fee_payer::validate_fee_payer(fee_payer, &self.payer.pubkey())?;
+        }
         let output_token_mint = if params.output_token_type == TradeTokenType::SOL {
             SOL_TOKEN_ACCOUNT
         } else if params.output_token_type == TradeTokenType::WSOL {
@@ -661,6 +1284,7 @@ impl TradingClient {
         };
         let sell_params = SwapParams {
             rpc: Some(self.rpc.clone()),
+            rpc_provider: self.rpc_provider.clone(),
             payer: self.payer.clone(),
             trade_type: TradeType::Sell,
             input_mint: params.mint,
@@ -671,11 +1295,12 @@ impl TradingClient {
             slippage_basis_points: params.slippage_basis_points,
             address_lookup_table_account: params.address_lookup_table_account,
             recent_blockhash: params.recent_blockhash,
+            last_valid_block_height: params.last_valid_block_height,
             wait_transaction_confirmed: params.wait_transaction_confirmed,
-            protocol_params: protocol_params.clone(),
+            protocol_params,
             with_tip: params.with_tip,
-            open_seed_optimize: self.use_seed_optimize, // 使用全局seed优化配置
-            swqos_clients: self.swqos_clients.clone(),
+            open_seed_optimize: params.use_seed_optimize.unwrap_or(self.use_seed_optimize),
+            swqos_clients: self.swqos_clients().as_ref().clone(),
             middleware_manager: self.middleware_manager.clone(),
             durable_nonce: params.durable_nonce,
             create_input_mint_ata: false,
@@ -685,44 +1310,74 @@ impl TradingClient {
             fixed_output_amount: params.fixed_output_token_amount,
             gas_fee_strategy: params.gas_fee_strategy,
             simulate: params.simulate,
-            on_transaction_signed: params.on_transaction_signed,
+            on_transaction_signed: self.callbacks.merge_with(params.on_transaction_signed),
             callback_execution_mode: params
                 .callback_execution_mode
                 .or(Some(self.callback_execution_mode)),
             enable_jito_sandwich_protection: params
                 .enable_jito_sandwich_protection
                 .or(Some(self.enable_jito_sandwich_protection)),
+            pre_swap_instructions: params.pre_swap_instructions,
+            post_swap_instructions: params.post_swap_instructions,
+            metadata: params.metadata.map(Arc::new),
+            fee_ledger: Some(self.fee_ledger.clone()),
+            trade_store: self.trade_store.clone(),
+            preflight: self.preflight,
+            instruction_layout: self.instruction_layout,
+            multisig: self.multisig.clone(),
+            fee_payer: params.fee_payer,
+            submission_queue: self.submission_queue.clone(),
+            urgency: params.urgency.unwrap_or(Urgency::Exit),
+            confirm: self.confirm_config(params.confirm_timeout, params.confirm_poll_interval),
+            cancellation_token: params.cancellation_token,
+            fanout: self.swqos_fanout.clone(),
         };
 
-        // Validate protocol params
-        let is_valid_params = match params.dex_type {
-            DexType::PumpFun => protocol_params.as_any().downcast_ref::<PumpFunParams>().is_some(),
-            DexType::PumpSwap => {
-                protocol_params.as_any().downcast_ref::<PumpSwapParams>().is_some()
-            },
-            DexType::Bonk => protocol_params.as_any().downcast_ref::<BonkParams>().is_some(),
-            DexType::RaydiumCpmm => {
-                protocol_params.as_any().downcast_ref::<RaydiumCpmmParams>().is_some()
-            },
-            DexType::RaydiumAmmV4 => {
-                protocol_params.as_any().downcast_ref::<RaydiumAmmV4Params>().is_some()
-            },
-            DexType::RaydiumClmm => {
-                protocol_params.as_any().downcast_ref::<RaydiumClmmParams>().is_some()
-            },
-            DexType::MeteoraDammV2 => {
-                protocol_params.as_any().downcast_ref::<MeteoraDammV2Params>().is_some()
-            },
-        };
+        Ok(sell_params)
+    }
 
-        if !is_valid_params {
-            return Err(anyhow::anyhow!("Invalid protocol params for Trade"));
-        }
+    /// Execute a buy order and return a [`TradeResult`] with per-trade fee cost
+    pub async fn buy_with_result(
+        &self,
+        params: TradeBuyParams,
+    ) -> Result<TradeResult, anyhow::Error> {
+        let (success, signatures, error) = self.buy(params).await?;
+        Ok(self.trade_result(success, signatures, error))
+    }
 
-        // Execute sell based on tip preference
-        let swap_result = executor.swap(sell_params).await;
+    /// Execute a sell order and return a [`TradeResult`] with per-trade fee cost
+    pub async fn sell_with_result(
+        &self,
+        params: TradeSellParams,
+    ) -> Result<TradeResult, anyhow::Error> {
+        let (success, signatures, error) = self.sell(params).await?;
+        Ok(self.trade_result(success, signatures, error))
+    }
 
-        swap_result.map(|(success, sigs, err)| (success, sigs, err.map(TradeError::from)))
+    fn trade_result(
+        &self,
+        success: bool,
+        signatures: Vec<Signature>,
+        error: Option<TradeError>,
+    ) -> TradeResult {
+        let fees = self.fee_ledger.fees_for(&signatures);
+        let timing = trading::timing::trade_timing(&signatures);
+        let responses = swqos::response::swqos_responses(&signatures);
+        let slots_waited = timing.as_ref().and_then(TradeTiming::slots_waited);
+        TradeResult {
+            success,
+            signatures,
+            error,
+            fees,
+            timing,
+            responses,
+            slots_waited,
+        }
+    }
+
+    /// 累计手续费支出报告（按 SWQOS 与自然日分组，含发送/上链次数）
+    pub fn fee_report(&self) -> FeeReport {
+        self.fee_ledger.report()
     }
 
     /// Execute a sell order for a percentage of the specified token amount
@@ -765,6 +1420,356 @@ impl TradingClient {
         self.sell(params).await
     }
 
+    /// Sell a Token-2022 token that charges transfer fees
+    ///
+    /// 按当前 epoch 的转账费率放宽滑点（或缩放 `fixed_output_token_amount`），
+    /// 使最小输出对应池子实际收到的扣费后数量；若代币程序报告余额不足，
+    /// 按链上最新余额重新计算后重试一次。普通代币的手续费为 0，行为与 [`Self::sell`] 相同。
+    pub async fn sell_with_transfer_fee(
+        &self,
+        params: TradeSellParams,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>), anyhow::Error> {
+        use crate::trading::transfer_fee;
+
+        let sized = |mut params: TradeSellParams, fee: u64| {
+            let amount = params.input_token_amount;
+            let slippage = params
+                .slippage_basis_points
+                .unwrap_or(constants::trade_consts::DEFAULT_SLIPPAGE);
+            params.slippage_basis_points =
+                Some(transfer_fee::slippage_after_fee(slippage, amount, fee));
+            params.fixed_output_token_amount = params
+                .fixed_output_token_amount
+                .map(|output| transfer_fee::output_after_fee(output, amount, fee));
+            params
+        };
+
+        let fee =
+            transfer_fee::fetch_transfer_fee(&self.rpc, &params.mint, params.input_token_amount)
+                .await?;
+        let result = self.sell(sized(params.clone(), fee)).await;
+        let insufficient = match &result {
+            Ok((false, _, Some(error))) => transfer_fee::is_insufficient_funds(error),
+            Err(e) => e.to_string().to_ascii_lowercase().contains("insufficient funds"),
+            _ => false,
+        };
+        if !insufficient {
+            return result;
+        }
+
+        let balance = transfer_fee::fetch_token_balance(
+            &self.rpc,
+            &self.payer.pubkey(),
+            &params.mint,
+            params.use_seed_optimize.unwrap_or(self.use_seed_optimize),
+        )
+        .await?;
+        if balance == 0 || balance >= params.input_token_amount {
+            return result;
+        }
+        log::warn!(
+            "sell of {} reported insufficient funds, retrying with balance {} instead of {}",
+            params.mint,
+            balance,
+            params.input_token_amount
+        );
+        let mut retry = params;
+        if let Some(output) = retry.fixed_output_token_amount {
+            retry.fixed_output_token_amount = Some(transfer_fee::output_after_fee(
+                output,
+                retry.input_token_amount,
+                retry.input_token_amount - balance,
+            ));
+        }
+        retry.input_token_amount = balance;
+        let fee = transfer_fee::fetch_transfer_fee(&self.rpc, &retry.mint, balance).await?;
+        self.sell(sized(retry, fee)).await
+    }
+
+    /// Pre-build a buy transaction template for one SWQOS provider
+    ///
+    /// 账户、指令数据、compute budget 与小费在此时全部确定；触发时调用
+    /// [`TradingClient::fire_template`] 仅需换上最新 blockhash（或 nonce）并重新签名。
+    ///
+    /// # Arguments
+    /// * `params` - Buy trade parameters (`recent_blockhash` / `durable_nonce` are used as placeholders)
+    /// * `swqos_type` - The SWQOS provider the template is built for (tip account and gas strategy)
+    pub async fn build_buy_template(
+        &self,
+        mut params: TradeBuyParams,
+        swqos_type: SwqosType,
+    ) -> Result<TransactionTemplate, anyhow::Error> {
+        self.fill_protocol_params(
+            &mut params.extension_params,
+            &params.mint,
+            &params.input_token_type,
+        )
+        .await?;
+        let dex_type = params.dex_type.clone();
+        let swap_params = self.build_buy_swap_params(params)?;
+        self.build_template(dex_type, swap_params, swqos_type).await
+    }
+
+    /// Pre-build a sell transaction template for one SWQOS provider
+    ///
+    /// 参见 [`TradingClient::build_buy_template`]。
+    pub async fn build_sell_template(
+        &self,
+        mut params: TradeSellParams,
+        swqos_type: SwqosType,
+    ) -> Result<TransactionTemplate, anyhow::Error> {
+        self.fill_protocol_params(
+            &mut params.extension_params,
+            &params.mint,
+            &params.output_token_type,
+        )
+        .await?;
+        let dex_type = params.dex_type.clone();
+        let swap_params = self.build_sell_swap_params(params)?;
+        self.build_template(dex_type, swap_params, swqos_type).await
+    }
+
+    async fn build_template(
+        &self,
+        dex_type: DexType,
+        params: SwapParams,
+        swqos_type: SwqosType,
+    ) -> Result<TransactionTemplate, anyhow::Error> {
+        use crate::trading::common::{assemble_instructions, get_transaction_blockhash};
+        use std::str::FromStr;
+
+        if params.fee_payer.is_some() {
+            return Err(anyhow::anyhow!(
+                "Transaction templates do not support a separate fee_payer"
+            ));
+        }
+        let executor = TradeFactory::create_executor(dex_type);
+        let business_instructions = executor.build_instructions(&params).await?;
+
+        let swqos_client = self
+            .swqos_registry
+            .get(swqos_type)
+            .ok_or_else(|| anyhow::anyhow!("SWQOS {:?} is not configured", swqos_type))?;
+        let (_, _, gas) = params
+            .gas_fee_strategy
+            .get_strategies(params.trade_type)
+            .into_iter()
+            .find(|config| config.0 == swqos_type)
+            .ok_or_else(|| anyhow::anyhow!("No gas fee strategy for {:?}", swqos_type))?;
+        params.gas_fee_strategy.fee_caps().check(swqos_type, &gas)?;
+
+        let tip_account_str = swqos_client.resolve_tip_account()?;
+        let with_tip = params.trade_type == TradeType::Buy || params.with_tip;
+        let use_tip = with_tip && !tip_account_str.is_empty() && swqos_type != SwqosType::Default;
+        let tip_accounts = if use_tip {
+            let tip_account = Pubkey::from_str(&tip_account_str)
+                .map_err(|e| anyhow::anyhow!("无效的小费接收地址 '{}': {}", tip_account_str, e))?;
+            swqos_type.pick_tip_accounts(
+                tip_account,
+                params.gas_fee_strategy.tip_split_for(swqos_type, gas.tip),
+            )
+        } else {
+            vec![]
+        };
+
+        let instructions = assemble_instructions(
+            params.payer.as_ref(),
+            gas.cu_limit,
+            gas.cu_price,
+            business_instructions,
+            use_tip,
+            &tip_accounts,
+            if use_tip { gas.tip } else { 0.0 },
+            params.durable_nonce.clone(),
+            params.enable_jito_sandwich_protection.unwrap_or(false),
+            params.instruction_layout,
+        )?;
+        let is_buy = params.trade_type == TradeType::Buy;
+        let instructions = match &params.middleware_manager {
+            Some(middleware_manager) => middleware_manager
+                .apply_middlewares_process_full_instructions(
+                    instructions,
+                    executor.protocol_name().to_string(),
+                    is_buy,
+                    params.metadata.as_deref(),
+                )?,
+            None => instructions,
+        };
+
+        let placeholder =
+            get_transaction_blockhash(params.recent_blockhash, params.durable_nonce.clone())
+                .unwrap_or_default();
+        TransactionTemplate::new(
+            params.payer.clone(),
+            &instructions,
+            params.address_lookup_table_account,
+            placeholder,
+            swqos_type,
+            params.trade_type,
+            params.durable_nonce.is_some(),
+        )
+    }
+
+    /// Sign a pre-built template with a fresh blockhash and send it to its SWQOS provider
+    ///
+    /// 对基于 nonce 的模板，`blockhash` 应传入 nonce 账户的当前值。
+    ///
+    /// # Returns
+    /// Returns the signature of the submitted transaction
+    pub async fn fire_template(
+        &self,
+        template: &TransactionTemplate,
+        blockhash: Hash,
+        wait_transaction_confirmed: bool,
+    ) -> Result<Signature, anyhow::Error> {
+        let swqos_client = self.swqos_registry.get(template.swqos_type()).ok_or_else(|| {
+            anyhow::anyhow!("SWQOS {:?} is not configured", template.swqos_type())
+        })?;
+        let transaction = template.sign_with_blockhash(&blockhash)?;
+        let signature = transaction.signatures[0];
+        swqos_client
+            .send_transaction(template.trade_type(), &transaction, wait_transaction_confirmed)
+            .await?;
+        Ok(signature)
+    }
+
+    /// Keep rebroadcasting nonce-based templates to their SWQOS providers until one lands
+    ///
+    /// 所有模板使用 `durable_nonce` 的当前值签名后，按 `config.interval` 反复广播。
+    /// 返回的句柄可调用 `cancel()` 推进 nonce，使所有已广播交易失效。
+    pub fn resubmit_templates(
+        &self,
+        templates: &[TransactionTemplate],
+        durable_nonce: DurableNonceInfo,
+        config: ResubmitConfig,
+    ) -> Result<ResubmitHandle, anyhow::Error> {
+        let trade_type = templates
+            .first()
+            .map(|t| t.trade_type())
+            .ok_or_else(|| anyhow::anyhow!("No templates to resubmit"))?;
+        let mut entries = Vec::with_capacity(templates.len());
+        for template in templates {
+            let swqos_client = self.swqos_registry.get(template.swqos_type()).ok_or_else(|| {
+                anyhow::anyhow!("SWQOS {:?} is not configured", template.swqos_type())
+            })?;
+            entries.push(ResubmitEntry {
+                swqos_client: swqos_client.clone(),
+                transaction: template.sign_with_nonce(&durable_nonce)?,
+            });
+        }
+        NonceResubmitter::new(self.rpc.clone(), self.payer.clone()).start(
+            trade_type,
+            entries,
+            durable_nonce,
+            config,
+        )
+    }
+
+    /// Submit a durable-nonce trade and register it as a replaceable in-flight order
+    ///
+    /// 订单必须设置 `durable_nonce`，这样后续 [`TradingClient::replace_trade`] 才能复用同一个
+    /// nonce 使原交易失效。
+    ///
+    /// # Returns
+    /// Returns the order id together with the regular `(success, signatures, error)` result
+    pub async fn place_order(
+        &self,
+        order: TradeOrder,
+    ) -> Result<(OrderId, (bool, Vec<Signature>, Option<TradeError>)), anyhow::Error> {
+        let durable_nonce = order
+            .durable_nonce()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Replaceable orders require durable_nonce to be set"))?;
+        let trade_type = order.trade_type();
+        let result = self.execute_order(order).await?;
+        let order_id = self.orders.insert(trade_type, durable_nonce, result.1.clone());
+        Ok((order_id, result))
+    }
+
+    /// Replace an in-flight order with new parameters reusing the same durable nonce
+    ///
+    /// 新交易与原交易使用同一个 nonce，任意一笔上链后另一笔即失效，适用于原交易卡住、
+    /// 需要调整滑点或费用重新发送的场景。若原交易已经上链，返回错误。
+    ///
+    /// # Returns
+    /// Returns the id of the replacement order together with its send result
+    pub async fn replace_trade(
+        &self,
+        order_id: OrderId,
+        mut new_params: TradeOrder,
+    ) -> Result<(OrderId, (bool, Vec<Signature>, Option<TradeError>)), anyhow::Error> {
+        let order = self
+            .orders
+            .get(order_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown order {}", order_id))?;
+        if order.status != OrderStatus::Pending {
+            return Err(anyhow::anyhow!("Order {} is not pending: {:?}", order_id, order.status));
+        }
+        if self.any_signature_landed(&order.signatures).await? {
+            self.orders.remove(order_id);
+            return Err(anyhow::anyhow!("Order {} has already landed", order_id));
+        }
+
+        new_params.set_durable_nonce(order.durable_nonce.clone());
+        let (new_id, result) = self.place_order(new_params).await?;
+        self.orders.set_status(order_id, OrderStatus::Replaced(new_id));
+        Ok((new_id, result))
+    }
+
+    /// Cancel an in-flight order by advancing its durable nonce
+    ///
+    /// # Returns
+    /// Returns the signature of the nonce-advance transaction
+    pub async fn cancel_order(&self, order_id: OrderId) -> Result<Signature, anyhow::Error> {
+        let order = self
+            .orders
+            .get(order_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown order {}", order_id))?;
+        if order.status != OrderStatus::Pending {
+            return Err(anyhow::anyhow!("Order {} is not pending: {:?}", order_id, order.status));
+        }
+        if self.any_signature_landed(&order.signatures).await? {
+            self.orders.remove(order_id);
+            return Err(anyhow::anyhow!("Order {} has already landed", order_id));
+        }
+        let nonce_account = order
+            .durable_nonce
+            .nonce_account
+            .ok_or_else(|| anyhow::anyhow!("Nonce account not set in DurableNonceInfo"))?;
+        let current_nonce = order
+            .durable_nonce
+            .current_nonce
+            .ok_or_else(|| anyhow::anyhow!("Current nonce not set in DurableNonceInfo"))?;
+        let signature = crate::trading::resubmitter::advance_nonce(
+            &self.rpc,
+            &self.payer,
+            &nonce_account,
+            current_nonce,
+        )
+        .await?;
+        self.orders.set_status(order_id, OrderStatus::Cancelled);
+        Ok(signature)
+    }
+
+    async fn execute_order(
+        &self,
+        order: TradeOrder,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>), anyhow::Error> {
+        match order {
+            TradeOrder::Buy(params) => self.buy(params).await,
+            TradeOrder::Sell(params) => self.sell(params).await,
+        }
+    }
+
+    async fn any_signature_landed(&self, signatures: &[Signature]) -> Result<bool, anyhow::Error> {
+        if signatures.is_empty() {
+            return Ok(false);
+        }
+        let statuses = self.rpc.get_signature_statuses(signatures).await?;
+        // 失败上链同样会消耗 nonce，因此任何状态都视为已上链
+        Ok(statuses.value.iter().any(|status| status.is_some()))
+    }
+
     /// Wraps native SOL into wSOL (Wrapped SOL) for use in SPL token operations
     ///
     /// This function creates a wSOL associated token account (if it doesn't exist),
@@ -1024,4 +2029,125 @@ impl TradingClient {
 
         Ok((mint.pubkey(), signature.to_string()))
     }
+
+    /// 探测已部署协议程序的版本（见 [`common::program_probe`]），不处理不一致，由调用方决定
+    pub async fn probe_programs(
+        &self,
+        config: &common::ProgramProbeConfig,
+    ) -> Result<common::ProbeReport, anyhow::Error> {
+        crate::common::program_probe::probe_programs(&self.rpc, config).await
+    }
+
+    /// PumpFun creator vault 中可领取的创作者手续费（lamports，已扣除 vault 的免租金额）
+    ///
+    /// payer 创建的所有 PumpFun 代币共用一个 creator vault。
+    pub async fn pumpfun_creator_fees_available(&self) -> Result<u64, anyhow::Error> {
+        let creator_vault =
+            crate::instruction::utils::pumpfun::get_creator_vault_pda(&self.payer.pubkey())
+                .ok_or_else(|| anyhow::anyhow!("Creator vault PDA not found for payer"))?;
+        let balance = self.rpc.get_balance(&creator_vault).await?;
+        let rent_exempt = match common::rent_cache::get(0) {
+            Some(rent) => rent,
+            None => self.rpc.get_minimum_balance_for_rent_exemption(0).await?,
+        };
+        Ok(balance.saturating_sub(rent_exempt))
+    }
+
+    /// 领取 payer 作为创作者累积的 PumpFun 创作者手续费
+    ///
+    /// # Returns
+    /// * `Ok((u64, String))` - (领取的 lamports, 交易签名)
+    ///
+    /// 没有可领取的手续费时返回错误，不发送交易。
+    pub async fn claim_pumpfun_creator_fees(&self) -> Result<(u64, String), anyhow::Error> {
+        use crate::instruction::pumpfun::PumpFunInstructionBuilder;
+
+        let claimable = self.pumpfun_creator_fees_available().await?;
+        if claimable == 0 {
+            return Err(anyhow::anyhow!("No PumpFun creator fees to claim"));
+        }
+        let instruction =
+            PumpFunInstructionBuilder::build_collect_creator_fee_instruction(&self.payer.pubkey())?;
+        let signature = self.send_claim_instruction(instruction).await?;
+        Ok((claimable, signature))
+    }
+
+    /// 领取 payer 作为 LaunchLab（Bonk）池创建者累积的创作者手续费
+    ///
+    /// 手续费按计价代币（WSOL / USD1）分 vault 存放，领取到 payer 对应计价代币的 ATA（不会自动解包 WSOL）。
+    ///
+    /// # Returns
+    /// * `Ok((u64, String))` - (领取的计价代币原始数量, 交易签名)
+    pub async fn claim_launchlab_creator_fees(
+        &self,
+        quote_mint: &Pubkey,
+    ) -> Result<(u64, String), anyhow::Error> {
+        use crate::instruction::utils::raydium_launchlab::{
+            build_claim_creator_fee_instruction, fetch_fee_vault_balance, get_creator_fee_vault_pda,
+        };
+
+        let (fee_vault, _) = get_creator_fee_vault_pda(&self.payer.pubkey(), quote_mint)?;
+        let claimable = fetch_fee_vault_balance(&self.rpc, &fee_vault).await?;
+        if claimable == 0 {
+            return Err(anyhow::anyhow!("No LaunchLab creator fees to claim"));
+        }
+        let instruction = build_claim_creator_fee_instruction(&self.payer.pubkey(), quote_mint)?;
+        let signature = self.send_claim_instruction(instruction).await?;
+        Ok((claimable, signature))
+    }
+
+    /// 领取 LaunchLab 平台手续费（payer 必须是平台配置中的 `fee_wallet`）
+    ///
+    /// # Returns
+    /// * `Ok((u64, String))` - (领取的计价代币原始数量, 交易签名)
+    pub async fn claim_launchlab_platform_fees(
+        &self,
+        platform_config: &Pubkey,
+        quote_mint: &Pubkey,
+    ) -> Result<(u64, String), anyhow::Error> {
+        use crate::instruction::utils::raydium_launchlab::{
+            build_claim_platform_fee_instruction, fetch_fee_vault_balance, fetch_platform_config,
+            get_platform_fee_vault_pda,
+        };
+
+        let config = fetch_platform_config(&self.rpc, platform_config).await?;
+        if config.fee_wallet != self.payer.pubkey() {
+            return Err(anyhow::anyhow!(
+                "Payer {} is not the fee wallet {} of platform config {}",
+                self.payer.pubkey(),
+                config.fee_wallet,
+                platform_config
+            ));
+        }
+        let (fee_vault, _) = get_platform_fee_vault_pda(platform_config, quote_mint)?;
+        let claimable = fetch_fee_vault_balance(&self.rpc, &fee_vault).await?;
+        if claimable == 0 {
+            return Err(anyhow::anyhow!("No LaunchLab platform fees to claim"));
+        }
+        let instruction = build_claim_platform_fee_instruction(
+            &self.payer.pubkey(),
+            platform_config,
+            quote_mint,
+        )?;
+        let signature = self.send_claim_instruction(instruction).await?;
+        Ok((claimable, signature))
+    }
+
+    /// 发送单条手续费领取指令（payer 签名并支付）
+    async fn send_claim_instruction(
+        &self,
+        instruction: solana_sdk::instruction::Instruction,
+    ) -> Result<String, anyhow::Error> {
+        use solana_sdk::transaction::Transaction;
+
+        let recent_blockhash = self.rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.payer.pubkey()),
+            &[&*self.payer],
+            recent_blockhash,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&transaction).await?;
+        Ok(signature.to_string())
+    }
 }
@@ -0,0 +1,50 @@
+//! 聚合器路由识别
+//!
+//! 聚合器（如 Jupiter）在一个外部指令中依次 CPI 调用多个 DEX 完成路由。各协议解析器同时解析外层指令与
+//! CPI 调用，并按调用范围切分转账（见 [`TransactionAdapter::get_invocations_with_transfers`]），
+//! 每一段路由解析为一笔独立的 [`ParsedTradeInfo`](super::types::ParsedTradeInfo)，
+//! 其 `aggregator` 字段记录经由的聚合器。
+
+use solana_program::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use super::transaction_adapter::TransactionAdapter;
+
+/// Jupiter v6 / v4
+const JUPITER_PROGRAM_IDS: &[Pubkey] = &[
+    pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"),
+    pubkey!("JUP4Fb2cqiRUcaTHdrPC8h2gNsA5ETXiPDD33WcGuJB"),
+];
+
+/// 支持识别的聚合器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Aggregator {
+    Jupiter,
+}
+
+impl Aggregator {
+    pub const ALL: [Aggregator; 1] = [Aggregator::Jupiter];
+
+    pub fn program_ids(&self) -> &'static [Pubkey] {
+        match self {
+            Aggregator::Jupiter => JUPITER_PROGRAM_IDS,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aggregator::Jupiter => "Jupiter",
+        }
+    }
+
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|aggregator| aggregator.program_ids().contains(program_id))
+    }
+
+    /// 交易经由的聚合器（任一外部指令调用了聚合器程序）
+    pub fn detect(adapter: &TransactionAdapter) -> Option<Self> {
+        adapter.instructions.iter().find_map(|ix| Self::from_program_id(&ix.program_id))
+    }
+}
@@ -42,17 +42,17 @@ pub trait DexParserTrait: Send + Sync {
 
     /// 判断是否可以解析此交易
     ///
+    /// 同时检查外层指令与内部指令（经聚合器 CPI 调用的 DEX 只出现在内部指令中）
+    ///
     /// # 参数
     /// - `adapter`: 交易适配器
     ///
     /// # 返回
     /// 如果可以解析返回 true
     fn can_parse(&self, adapter: &TransactionAdapter) -> bool {
-        let program_id = self.protocol().program_id();
-        adapter
-            .instructions
-            .iter()
-            .any(|instr| instr.program_id.to_string() == program_id)
+        let program_ids = self.protocol().program_ids();
+        adapter.instructions.iter().any(|instr| program_ids.contains(&instr.program_id))
+            || !adapter.get_inner_instructions_by_programs(&program_ids).is_empty()
     }
 }
 
@@ -4,23 +4,46 @@
 
 use solana_client::rpc_config::RpcTransactionConfig;
 use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
 use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::common::SolanaRpcClient;
 use crate::common::rpc_client_wrapper::RpcClientWrapper;
 
 use super::{
+    aggregator::Aggregator,
     base_parser::{DexParserTrait, ParseError},
+    idl::{IdlDecoded, IdlDecoder},
     pumpswap::PumpswapParser,
     raydium::{clmm::RaydiumClmmParser, cpmm::RaydiumCpmmParser, v4::RaydiumV4Parser},
-    transaction_adapter::TransactionAdapter,
-    types::{DexProtocol, ParseResult, ParserConfig},
+    transaction_adapter::{TransactionAdapter, TransferData},
+    transfers::classify_transfers,
+    types::{DexProtocol, ParseResult, ParsedTradeInfo, ParserConfig, WalletActivity},
 };
 
+/// 按签名端到端解析的结果：所有已注册协议解析器识别出的交易，以及交易中的全部代币转账
+#[derive(Debug, Clone)]
+pub struct ParsedTransaction {
+    /// 交易签名
+    pub signature: String,
+    /// 区块槽位
+    pub slot: u64,
+    /// 时间戳
+    pub timestamp: i64,
+    /// 手续费支付者（account[0]）
+    pub fee_payer: Option<Pubkey>,
+    /// 解析出的交易（一笔交易可能经过多个 DEX，例如聚合器路由）
+    pub trades: Vec<ParsedTradeInfo>,
+    /// 所有 transfer / transferChecked / mintTo / burn 动作
+    pub transfers: Vec<TransferData>,
+}
+
 /// DEX 解析器
 ///
 /// 主入口，负责获取交易并分发到对应的协议解析器
@@ -32,6 +55,8 @@ pub struct DexParser {
     rpc_client: RpcClientWrapper,
     /// 已注册的协议解析器（key: program_id）
     pub parsers: HashMap<String, Arc<dyn DexParserTrait>>,
+    /// 已注册的 IDL 解码器（key: program_id），用于没有专用解析器的程序
+    pub idl_decoders: HashMap<Pubkey, Arc<IdlDecoder>>,
 }
 
 impl DexParser {
@@ -64,7 +89,7 @@ impl DexParser {
             Arc::new(RaydiumCpmmParser) as Arc<dyn DexParserTrait>,
         );
 
-        Self { config, rpc_client, parsers }
+        Self { config, rpc_client, parsers, idl_decoders: HashMap::new() }
     }
 
     /// 使用默认配置创建解析器
@@ -113,7 +138,7 @@ impl DexParser {
             Arc::new(RaydiumCpmmParser) as Arc<dyn DexParserTrait>,
         );
 
-        Self { config, rpc_client, parsers }
+        Self { config, rpc_client, parsers, idl_decoders: HashMap::new() }
     }
 
     /// 解析交易
@@ -157,14 +182,26 @@ impl DexParser {
             .await
             .map_err(|e| format!("获取交易失败: {}", e))?;
 
-        let slot = tx.slot;
-        let block_time = tx.block_time;
+        self.parse_encoded_transaction(&tx).await
+    }
 
+    /// 解析已获取的交易（不发起 RPC 请求）
+    ///
+    /// 交易需以 `JsonParsed` 编码获取。无法识别协议时返回空列表。
+    pub async fn parse_encoded_transaction(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<Vec<super::types::ParsedTradeInfo>, Box<dyn std::error::Error + Send + Sync>> {
         // 创建交易适配器并解析
-        let adapter = TransactionAdapter::from_encoded_transaction(&tx, slot, block_time)?;
+        let adapter = TransactionAdapter::from_encoded_transaction(tx, tx.slot, tx.block_time)?;
 
-        // 识别协议并分发到对应的解析器
-        match self.parse_with_correct_parser(&adapter).await {
+        // 识别协议并分发到对应的解析器；聚合器路由可能经过多个 DEX，运行所有协议解析器
+        let parsed = if Aggregator::detect(&adapter).is_some() {
+            self.parse_with_all_parsers(&adapter).await
+        } else {
+            self.parse_with_correct_parser(&adapter).await
+        };
+        match parsed {
             Ok(trades) => Ok(trades),
             Err(ParseError::UnsupportedProtocol(msg)) => {
                 // 如果无法识别协议，返回空结果而不是错误
@@ -177,6 +214,193 @@ impl DexParser {
         }
     }
 
+    /// 通过给定的 RPC 获取交易并完整解析（所有协议的交易 + 全部转账）
+    ///
+    /// 与 [`DexParser::parse_transaction`] 不同，这里会运行所有能识别该交易的协议解析器，
+    /// 而不是只取第一个匹配的；未识别任何协议时 `trades` 为空但仍返回转账。
+    pub async fn parse_signature(
+        &self,
+        rpc: &SolanaRpcClient,
+        signature: &Signature,
+    ) -> Result<ParsedTransaction, Box<dyn std::error::Error + Send + Sync>> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let tx = rpc
+            .get_transaction_with_config(signature, config)
+            .await
+            .map_err(|e| format!("获取交易失败: {}", e))?;
+        self.parse_encoded_transaction_full(&tx).await
+    }
+
+    /// 完整解析已获取的交易（`JsonParsed` 编码），不发起 RPC 请求
+    pub async fn parse_encoded_transaction_full(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<ParsedTransaction, Box<dyn std::error::Error + Send + Sync>> {
+        let adapter = TransactionAdapter::from_encoded_transaction(tx, tx.slot, tx.block_time)?;
+        let trades = self.parse_with_all_parsers(&adapter).await?;
+
+        Ok(ParsedTransaction {
+            signature: adapter.signature.clone(),
+            slot: adapter.slot,
+            timestamp: adapter.timestamp,
+            fee_payer: adapter.fee_payer(),
+            trades,
+            transfers: adapter.get_transfer_actions(),
+        })
+    }
+
+    /// 解析一笔交易中涉及 `wallets` 的全部动作（`JsonParsed` 编码），不发起 RPC 请求
+    ///
+    /// 交易中有这些钱包的 swap 时只返回 swap（其中的代币转账属于 swap 本身）；
+    /// 否则返回 SOL / SPL 转账与 ATA 创建 / 关闭动作。
+    pub async fn parse_wallet_activity(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+        wallets: &HashSet<Pubkey>,
+    ) -> Result<Vec<WalletActivity>, Box<dyn std::error::Error + Send + Sync>> {
+        let adapter = TransactionAdapter::from_encoded_transaction(tx, tx.slot, tx.block_time)?;
+        let trades: Vec<WalletActivity> = self
+            .parse_with_all_parsers(&adapter)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|trade| {
+                wallets.contains(&trade.user)
+                    || trade.fee_payer.is_some_and(|fee_payer| wallets.contains(&fee_payer))
+            })
+            .map(WalletActivity::Trade)
+            .collect();
+        if !trades.is_empty() {
+            return Ok(trades);
+        }
+        Ok(classify_transfers(&adapter, wallets)
+            .into_iter()
+            .map(WalletActivity::Transfer)
+            .collect())
+    }
+
+    /// 回填钱包历史：读取 `wallet` 在 `before` 之前的最多 `limit` 笔交易（新到旧）并逐笔解析
+    ///
+    /// 失败的交易会被跳过；返回最后一个读取的签名，作为下一页的 `before`。
+    pub async fn wallet_history(
+        &self,
+        rpc: &SolanaRpcClient,
+        wallet: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<(Vec<WalletActivity>, Option<Signature>), Box<dyn std::error::Error + Send + Sync>>
+    {
+        let statuses = rpc
+            .get_signatures_for_address_with_config(
+                wallet,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(limit),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await
+            .map_err(|e| format!("获取签名列表失败: {}", e))?;
+        let wallets = HashSet::from([*wallet]);
+        let mut activity = Vec::new();
+        let mut last = None;
+        for status in statuses {
+            let signature =
+                Signature::from_str(&status.signature).map_err(|e| format!("无效签名: {}", e))?;
+            last = Some(signature);
+            if status.err.is_some() {
+                continue;
+            }
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::JsonParsed),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            };
+            let tx = rpc
+                .get_transaction_with_config(&signature, config)
+                .await
+                .map_err(|e| format!("获取交易失败: {}", e))?;
+            activity.extend(self.parse_wallet_activity(&tx, &wallets).await?);
+        }
+        Ok((activity, last))
+    }
+
+    /// 注册 IDL 解码器（需带程序 ID，见 [`IdlDecoder::with_program_id`]）
+    pub fn register_idl(&mut self, decoder: IdlDecoder) -> Result<(), ParseError> {
+        let program_id = decoder.program_id.ok_or_else(|| {
+            ParseError::UnsupportedProtocol(format!("IDL {} 未包含程序地址", decoder.name))
+        })?;
+        self.idl_decoders.insert(program_id, Arc::new(decoder));
+        Ok(())
+    }
+
+    /// 用已注册的 IDL 解码器解码已获取的交易（`JsonParsed` 编码）
+    ///
+    /// 作为专用解析器之外的兜底：返回交易中所有已注册程序的指令与 CPI 事件。
+    pub fn decode_with_idl(
+        &self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<Vec<IdlDecoded>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.idl_decoders.is_empty() {
+            return Ok(vec![]);
+        }
+        let adapter = TransactionAdapter::from_encoded_transaction(tx, tx.slot, tx.block_time)?;
+        let mut decoded: Vec<IdlDecoded> = self
+            .idl_decoders
+            .values()
+            .flat_map(|decoder| decoder.decode_transaction(&adapter))
+            .collect();
+        decoded.sort_by_key(|d| (d.outer_index, d.inner_index.map_or(0, |i| i + 1)));
+        Ok(decoded)
+    }
+
+    /// 运行所有能识别该交易的协议解析器
+    ///
+    /// 聚合器路由中的每一段分别由对应协议解析；某个协议未找到有效交易时跳过，
+    /// 所有协议都失败时返回第一个错误。
+    async fn parse_with_all_parsers(
+        &self,
+        adapter: &TransactionAdapter,
+    ) -> Result<Vec<ParsedTradeInfo>, ParseError> {
+        // 按程序 ID 排序，保证多协议交易的输出顺序稳定
+        let mut parsers: Vec<_> = self.parsers.iter().collect();
+        parsers.sort_by(|a, b| a.0.cmp(b.0));
+        let mut trades = Vec::new();
+        let mut first_error = None;
+        for (program_id, parser) in parsers {
+            if !parser.can_parse(adapter) {
+                continue;
+            }
+            if self.config.verbose {
+                println!("识别到程序 {}，开始解析...", program_id);
+            }
+            match parser.parse(adapter).await {
+                Ok(parsed) => trades.extend(parsed),
+                Err(ParseError::UnsupportedProtocol(_)) => {},
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                },
+            }
+        }
+        if let Some(e) = first_error
+            && trades.is_empty()
+        {
+            return Err(e);
+        }
+
+        if let Some(aggregator) = Aggregator::detect(adapter) {
+            for trade in &mut trades {
+                trade.aggregator = Some(aggregator.name().to_string());
+            }
+        }
+        Ok(trades)
+    }
+
     /// 识别协议并分发到对应的解析器
     #[allow(dead_code)] // 将在后续实现中使用
     async fn parse_with_correct_parser(
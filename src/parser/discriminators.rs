@@ -2,9 +2,27 @@
 //!
 //! 参考 solana-dex-parser 的 discriminator 系统
 //! 使用 8 字节标识符精确识别指令类型
+//!
+//! 内置表覆盖官方程序；PumpFun / Raydium 的分叉程序可在运行时通过
+//! [`DiscriminatorRegistry::register_program`] 挂到已有协议上，指令布局不同的
+//! 再用 [`DiscriminatorRegistry::register_global`] 补充 discriminator，无需发布新版本。
 
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
 
+use super::types::DexProtocol as ParsedDexProtocol;
+
+/// 运行时注册的扩展：额外 discriminator 与分叉程序 ID
+#[derive(Default)]
+struct RuntimeExtensions {
+    discriminators: HashMap<(DexProtocol, [u8; 8]), InstructionType>,
+    programs: HashMap<Pubkey, DexProtocol>,
+}
+
+static RUNTIME_EXTENSIONS: Lazy<RwLock<RuntimeExtensions>> = Lazy::new(Default::default);
+
 /// DEX 协议枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DexProtocol {
@@ -14,6 +32,28 @@ pub enum DexProtocol {
     RaydiumV4,
 }
 
+impl From<ParsedDexProtocol> for DexProtocol {
+    fn from(protocol: ParsedDexProtocol) -> Self {
+        match protocol {
+            ParsedDexProtocol::PumpSwap => DexProtocol::PumpSwap,
+            ParsedDexProtocol::RaydiumV4 => DexProtocol::RaydiumV4,
+            ParsedDexProtocol::RaydiumClmm => DexProtocol::RaydiumClmm,
+            ParsedDexProtocol::RaydiumCpmm => DexProtocol::RaydiumCpmm,
+        }
+    }
+}
+
+impl From<DexProtocol> for ParsedDexProtocol {
+    fn from(protocol: DexProtocol) -> Self {
+        match protocol {
+            DexProtocol::PumpSwap => ParsedDexProtocol::PumpSwap,
+            DexProtocol::RaydiumV4 => ParsedDexProtocol::RaydiumV4,
+            DexProtocol::RaydiumClmm => ParsedDexProtocol::RaydiumClmm,
+            DexProtocol::RaydiumCpmm => ParsedDexProtocol::RaydiumCpmm,
+        }
+    }
+}
+
 /// 指令类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InstructionType {
@@ -61,9 +101,64 @@ impl DiscriminatorRegistry {
         // 注册 Raydium V4 discriminators
         registry.register_raydium_v4();
 
+        // 运行时注册的 discriminator（可覆盖内置项）
+        for (key, instr_type) in &RUNTIME_EXTENSIONS.read().discriminators {
+            registry.discriminators.insert(*key, *instr_type);
+        }
+
         registry
     }
 
+    /// 向当前注册表添加 discriminator（仅影响此实例）
+    pub fn register(
+        &mut self,
+        protocol: DexProtocol,
+        discriminator: [u8; 8],
+        instr_type: InstructionType,
+    ) {
+        self.discriminators.insert((protocol, discriminator), instr_type);
+    }
+
+    /// 全局注册 discriminator，之后创建的注册表（包括各协议解析器内部使用的）都会包含它
+    ///
+    /// Raydium V4 的 1 字节 discriminator 按首字节填充、其余补 0。
+    pub fn register_global(
+        protocol: DexProtocol,
+        discriminator: [u8; 8],
+        instr_type: InstructionType,
+    ) {
+        RUNTIME_EXTENSIONS
+            .write()
+            .discriminators
+            .insert((protocol, discriminator), instr_type);
+    }
+
+    /// 将分叉程序 ID 注册为某个协议的别名，解析器会像对待官方程序一样识别并解析它
+    pub fn register_program(program_id: Pubkey, protocol: DexProtocol) {
+        RUNTIME_EXTENSIONS.write().programs.insert(program_id, protocol);
+    }
+
+    /// 移除已注册的分叉程序，返回其原先对应的协议
+    pub fn unregister_program(program_id: &Pubkey) -> Option<DexProtocol> {
+        RUNTIME_EXTENSIONS.write().programs.remove(program_id)
+    }
+
+    /// 查询运行时注册的程序对应的协议
+    pub fn registered_protocol(program_id: &Pubkey) -> Option<DexProtocol> {
+        RUNTIME_EXTENSIONS.read().programs.get(program_id).copied()
+    }
+
+    /// 某协议在运行时注册的全部分叉程序 ID
+    pub fn registered_programs(protocol: DexProtocol) -> Vec<Pubkey> {
+        RUNTIME_EXTENSIONS
+            .read()
+            .programs
+            .iter()
+            .filter(|(_, p)| **p == protocol)
+            .map(|(program_id, _)| *program_id)
+            .collect()
+    }
+
     /// 注册 Raydium CLMM 的 discriminators
     fn register_raydium_clmm(&mut self) {
         use InstructionType::*;
@@ -204,3 +299,44 @@ impl DiscriminatorRegistry {
         matches!(instr_type, InstructionType::Swap | InstructionType::Buy | InstructionType::Sell)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_registration() {
+        let fork = Pubkey::new_unique();
+        DiscriminatorRegistry::register_program(fork, DexProtocol::RaydiumCpmm);
+        assert_eq!(
+            DiscriminatorRegistry::registered_protocol(&fork),
+            Some(DexProtocol::RaydiumCpmm)
+        );
+        assert!(ParsedDexProtocol::RaydiumCpmm.program_ids().contains(&fork));
+        assert!(ParsedDexProtocol::RaydiumCpmm.matches_program(&fork));
+        assert_eq!(
+            ParsedDexProtocol::from_program_id(&fork.to_string()),
+            Some(ParsedDexProtocol::RaydiumCpmm)
+        );
+        assert!(!ParsedDexProtocol::PumpSwap.matches_program(&fork));
+
+        let disc = [250, 1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(
+            DiscriminatorRegistry::new().identify(DexProtocol::RaydiumCpmm, &disc),
+            InstructionType::Unknown
+        );
+        DiscriminatorRegistry::register_global(
+            DexProtocol::RaydiumCpmm,
+            disc,
+            InstructionType::AddLiquidity,
+        );
+        let registry = DiscriminatorRegistry::default();
+        assert!(registry.is_liquidity_discriminator(DexProtocol::RaydiumCpmm, &disc));
+
+        assert_eq!(
+            DiscriminatorRegistry::unregister_program(&fork),
+            Some(DexProtocol::RaydiumCpmm)
+        );
+        assert!(!ParsedDexProtocol::RaydiumCpmm.matches_program(&fork));
+    }
+}
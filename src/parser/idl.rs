@@ -0,0 +1,694 @@
+//! Anchor IDL 驱动的通用解码器
+//!
+//! 运行时加载 Anchor IDL JSON，把该程序的指令与 CPI 事件（`emit_cpi!`）解码为动态 JSON，
+//! 在还没有专用解析器的新 DEX 上线时作为兜底。同时支持 Anchor 0.30+ 的新格式
+//! （自带 `discriminator`、事件字段定义在 `types` 中）与旧格式（按名称计算 discriminator）。
+//!
+//! 数值映射：64 位及以下整数为 JSON 数字，`u128`/`i128` 为十进制字符串；
+//! `pubkey` 为 base58 字符串；`bytes` 与 `[u8; N]` 为十六进制字符串；
+//! 无字段的枚举变体为变体名，带字段的变体为 `{ 变体名: 字段 }`。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+use super::base_parser::ParseError;
+use super::transaction_adapter::TransactionAdapter;
+
+/// Anchor `emit_cpi!` 事件指令的前缀（`sha256("anchor:event")[..8]` 的小端表示）
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// 类型嵌套深度上限（防止自引用别名导致无限递归）
+const MAX_DEPTH: usize = 64;
+
+/// IDL 字段类型
+#[derive(Debug, Clone, PartialEq)]
+enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+    U64,
+    I64,
+    F64,
+    U128,
+    I128,
+    Bytes,
+    String,
+    Pubkey,
+    Vec(Box<IdlType>),
+    Option(Box<IdlType>),
+    COption(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+/// 结构体 / 枚举变体的字段
+#[derive(Debug, Clone)]
+enum IdlFields {
+    Named(Vec<(String, IdlType)>),
+    Tuple(Vec<IdlType>),
+    None,
+}
+
+/// `types` 中的自定义类型
+#[derive(Debug, Clone)]
+enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<(String, IdlFields)>),
+    Alias(IdlType),
+}
+
+#[derive(Debug, Clone)]
+struct IdlInstruction {
+    name: String,
+    discriminator: Vec<u8>,
+    args: Vec<(String, IdlType)>,
+    /// 展平后的账户名（嵌套账户组用 `组名.账户名`）
+    accounts: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct IdlEvent {
+    name: String,
+    discriminator: Vec<u8>,
+    fields: IdlFields,
+}
+
+/// 解码后的指令
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedInstruction {
+    pub name: String,
+    /// 参数：`{ 参数名: 值 }`
+    pub args: Value,
+    /// 账户：`{ 账户名: 地址 }`，IDL 之外的剩余账户放在 `remaining` 数组中
+    pub accounts: Value,
+}
+
+/// 解码后的事件
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedEvent {
+    pub name: String,
+    pub data: Value,
+}
+
+/// 交易中解码出的一项
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IdlDecodedItem {
+    Instruction(DecodedInstruction),
+    Event(DecodedEvent),
+}
+
+/// 交易中解码出的指令或事件及其位置
+#[derive(Debug, Clone, Serialize)]
+pub struct IdlDecoded {
+    /// 外部指令索引
+    pub outer_index: usize,
+    /// 内部指令索引（外部指令为 `None`）
+    pub inner_index: Option<usize>,
+    pub item: IdlDecodedItem,
+}
+
+/// 由 Anchor IDL 构建的通用解码器
+#[derive(Debug, Clone)]
+pub struct IdlDecoder {
+    /// 程序名（`metadata.name` 或旧格式的 `name`）
+    pub name: String,
+    /// 程序 ID（`address` 或旧格式的 `metadata.address`；可用 [`Self::with_program_id`] 覆盖）
+    pub program_id: Option<Pubkey>,
+    instructions: Vec<IdlInstruction>,
+    events: Vec<IdlEvent>,
+    types: HashMap<String, IdlTypeDef>,
+}
+
+impl IdlDecoder {
+    /// 从 IDL JSON 字符串构建
+    pub fn from_json(json: &str) -> Result<Self, ParseError> {
+        let idl: Value = serde_json::from_str(json)
+            .map_err(|e| ParseError::ParseFailed(format!("无效的 IDL JSON: {}", e)))?;
+        Self::from_value(&idl)
+    }
+
+    /// 从 IDL 文件构建
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ParseError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            ParseError::ParseFailed(format!("读取 IDL 文件 {} 失败: {}", path.display(), e))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// 从已解析的 IDL JSON 构建
+    pub fn from_value(idl: &Value) -> Result<Self, ParseError> {
+        let name = idl["metadata"]["name"].as_str().or(idl["name"].as_str()).unwrap_or_default();
+        let program_id = idl["address"]
+            .as_str()
+            .or(idl["metadata"]["address"].as_str())
+            .map(|address| {
+                address
+                    .parse()
+                    .map_err(|_| ParseError::ParseFailed(format!("无效的程序地址: {}", address)))
+            })
+            .transpose()?;
+
+        let mut types = HashMap::new();
+        for def in as_array(&idl["types"]) {
+            let type_name = field_name(def)?;
+            types.insert(type_name.to_string(), parse_type_def(&def["type"])?);
+        }
+
+        let mut instructions = Vec::new();
+        for ix in as_array(&idl["instructions"]) {
+            let ix_name = field_name(ix)?;
+            let discriminator = match ix.get("discriminator") {
+                Some(disc) => parse_discriminator(disc)?,
+                None => sighash("global", &to_snake_case(ix_name)),
+            };
+            let args = as_array(&ix["args"])
+                .iter()
+                .map(|arg| Ok((field_name(arg)?.to_string(), parse_type(&arg["type"])?)))
+                .collect::<Result<_, ParseError>>()?;
+            let mut accounts = Vec::new();
+            flatten_accounts(&ix["accounts"], "", &mut accounts);
+            instructions.push(IdlInstruction {
+                name: ix_name.to_string(),
+                discriminator,
+                args,
+                accounts,
+            });
+        }
+
+        let mut events = Vec::new();
+        for event in as_array(&idl["events"]) {
+            let event_name = field_name(event)?;
+            let discriminator = match event.get("discriminator") {
+                Some(disc) => parse_discriminator(disc)?,
+                None => sighash("event", event_name),
+            };
+            // 旧格式字段内联在事件中，新格式引用同名类型
+            let fields = match event.get("fields") {
+                Some(fields) => parse_fields(fields)?,
+                None => match types.get(event_name) {
+                    Some(IdlTypeDef::Struct(fields)) => fields.clone(),
+                    _ => IdlFields::None,
+                },
+            };
+            events.push(IdlEvent { name: event_name.to_string(), discriminator, fields });
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            program_id,
+            instructions,
+            events,
+            types,
+        })
+    }
+
+    /// 覆盖程序 ID（IDL 未包含地址，或用于分叉程序）
+    pub fn with_program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = Some(program_id);
+        self
+    }
+
+    /// IDL 中的指令名
+    pub fn instruction_names(&self) -> impl Iterator<Item = &str> {
+        self.instructions.iter().map(|ix| ix.name.as_str())
+    }
+
+    /// IDL 中的事件名
+    pub fn event_names(&self) -> impl Iterator<Item = &str> {
+        self.events.iter().map(|event| event.name.as_str())
+    }
+
+    /// 解码指令数据
+    pub fn decode_instruction(
+        &self,
+        data: &[u8],
+        accounts: &[Pubkey],
+    ) -> Result<DecodedInstruction, ParseError> {
+        let ix = self
+            .instructions
+            .iter()
+            .find(|ix| data.starts_with(&ix.discriminator))
+            .ok_or_else(|| ParseError::ParseFailed("未知的指令 discriminator".to_string()))?;
+        let mut reader = Reader::new(&data[ix.discriminator.len()..]);
+        let mut args = Map::new();
+        for (name, ty) in &ix.args {
+            args.insert(name.clone(), self.decode_type(ty, &mut reader, 0)?);
+        }
+
+        let mut named = Map::new();
+        for (name, account) in ix.accounts.iter().zip(accounts) {
+            named.insert(name.clone(), Value::String(account.to_string()));
+        }
+        if accounts.len() > ix.accounts.len() {
+            let remaining = accounts[ix.accounts.len()..]
+                .iter()
+                .map(|account| Value::String(account.to_string()))
+                .collect();
+            named.insert("remaining".to_string(), Value::Array(remaining));
+        }
+
+        Ok(DecodedInstruction {
+            name: ix.name.clone(),
+            args: Value::Object(args),
+            accounts: Value::Object(named),
+        })
+    }
+
+    /// 解码事件数据
+    ///
+    /// 接受 CPI 事件指令数据（以 [`EVENT_IX_TAG`] 开头）或日志中 `Program data:` 的原始数据。
+    pub fn decode_event(&self, data: &[u8]) -> Result<DecodedEvent, ParseError> {
+        let data = data.strip_prefix(&EVENT_IX_TAG[..]).unwrap_or(data);
+        let event = self
+            .events
+            .iter()
+            .find(|event| data.starts_with(&event.discriminator))
+            .ok_or_else(|| ParseError::ParseFailed("未知的事件 discriminator".to_string()))?;
+        let mut reader = Reader::new(&data[event.discriminator.len()..]);
+        Ok(DecodedEvent {
+            name: event.name.clone(),
+            data: self.decode_fields(&event.fields, &mut reader, 0)?,
+        })
+    }
+
+    /// 解码交易中属于该程序的全部指令与 CPI 事件（按指令顺序；无法解码的指令被跳过）
+    pub fn decode_transaction(&self, adapter: &TransactionAdapter) -> Vec<IdlDecoded> {
+        let Some(program_id) = self.program_id else {
+            return vec![];
+        };
+
+        let mut decoded = Vec::new();
+        for ix in adapter.instructions.iter().filter(|ix| ix.program_id == program_id) {
+            if let Some(item) = self.decode_item(&ix.data, &ix.accounts) {
+                decoded.push(IdlDecoded { outer_index: ix.index, inner_index: None, item });
+            }
+        }
+        for inner in adapter.get_inner_instructions_by_program(&program_id) {
+            let ix = &inner.instruction;
+            if let Some(item) = self.decode_item(&ix.data, &ix.accounts) {
+                decoded.push(IdlDecoded {
+                    outer_index: inner.outer_index,
+                    inner_index: Some(inner.inner_index),
+                    item,
+                });
+            }
+        }
+        decoded.sort_by_key(|d| (d.outer_index, d.inner_index.map_or(0, |i| i + 1)));
+        decoded
+    }
+
+    fn decode_item(&self, data: &[u8], accounts: &[Pubkey]) -> Option<IdlDecodedItem> {
+        if data.starts_with(&EVENT_IX_TAG) {
+            self.decode_event(data).ok().map(IdlDecodedItem::Event)
+        } else {
+            self.decode_instruction(data, accounts).ok().map(IdlDecodedItem::Instruction)
+        }
+    }
+
+    fn decode_fields(
+        &self,
+        fields: &IdlFields,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> Result<Value, ParseError> {
+        match fields {
+            IdlFields::Named(fields) => {
+                let mut map = Map::new();
+                for (name, ty) in fields {
+                    map.insert(name.clone(), self.decode_type(ty, reader, depth + 1)?);
+                }
+                Ok(Value::Object(map))
+            },
+            IdlFields::Tuple(types) => types
+                .iter()
+                .map(|ty| self.decode_type(ty, reader, depth + 1))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            IdlFields::None => Ok(Value::Null),
+        }
+    }
+
+    fn decode_type(
+        &self,
+        ty: &IdlType,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> Result<Value, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(ParseError::ParseFailed("IDL 类型嵌套过深".to_string()));
+        }
+        let value = match ty {
+            IdlType::Bool => Value::Bool(reader.read::<1>()?[0] != 0),
+            IdlType::U8 => reader.read::<1>()?[0].into(),
+            IdlType::I8 => (reader.read::<1>()?[0] as i8).into(),
+            IdlType::U16 => u16::from_le_bytes(reader.read()?).into(),
+            IdlType::I16 => i16::from_le_bytes(reader.read()?).into(),
+            IdlType::U32 => u32::from_le_bytes(reader.read()?).into(),
+            IdlType::I32 => i32::from_le_bytes(reader.read()?).into(),
+            IdlType::F32 => f32::from_le_bytes(reader.read()?).into(),
+            IdlType::U64 => u64::from_le_bytes(reader.read()?).into(),
+            IdlType::I64 => i64::from_le_bytes(reader.read()?).into(),
+            IdlType::F64 => f64::from_le_bytes(reader.read()?).into(),
+            IdlType::U128 => u128::from_le_bytes(reader.read()?).to_string().into(),
+            IdlType::I128 => i128::from_le_bytes(reader.read()?).to_string().into(),
+            IdlType::Bytes => {
+                let len = reader.read_len()?;
+                hex::encode(reader.take(len)?).into()
+            },
+            IdlType::String => {
+                let len = reader.read_len()?;
+                String::from_utf8_lossy(reader.take(len)?).into_owned().into()
+            },
+            IdlType::Pubkey => Pubkey::new_from_array(reader.read()?).to_string().into(),
+            IdlType::Vec(inner) => {
+                let len = reader.read_len()?;
+                if **inner == IdlType::U8 {
+                    hex::encode(reader.take(len)?).into()
+                } else {
+                    (0..len)
+                        .map(|_| self.decode_type(inner, reader, depth + 1))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into()
+                }
+            },
+            IdlType::Option(inner) => match reader.read::<1>()?[0] {
+                0 => Value::Null,
+                _ => self.decode_type(inner, reader, depth + 1)?,
+            },
+            IdlType::COption(inner) => match u32::from_le_bytes(reader.read()?) {
+                0 => Value::Null,
+                _ => self.decode_type(inner, reader, depth + 1)?,
+            },
+            IdlType::Array(inner, len) => {
+                if **inner == IdlType::U8 {
+                    hex::encode(reader.take(*len)?).into()
+                } else {
+                    (0..*len)
+                        .map(|_| self.decode_type(inner, reader, depth + 1))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into()
+                }
+            },
+            IdlType::Defined(name) => match self.types.get(name) {
+                Some(IdlTypeDef::Struct(fields)) => self.decode_fields(fields, reader, depth)?,
+                Some(IdlTypeDef::Alias(alias)) => self.decode_type(alias, reader, depth + 1)?,
+                Some(IdlTypeDef::Enum(variants)) => {
+                    let index = reader.read::<1>()?[0] as usize;
+                    let (variant, fields) = variants.get(index).ok_or_else(|| {
+                        ParseError::ParseFailed(format!("{} 的枚举变体索引越界: {}", name, index))
+                    })?;
+                    match fields {
+                        IdlFields::None => Value::String(variant.clone()),
+                        fields => {
+                            let mut map = Map::new();
+                            map.insert(variant.clone(), self.decode_fields(fields, reader, depth)?);
+                            Value::Object(map)
+                        },
+                    }
+                },
+                None => {
+                    return Err(ParseError::ParseFailed(format!("IDL 中未定义类型: {}", name)));
+                },
+            },
+        };
+        Ok(value)
+    }
+}
+
+/// Borsh 数据读取游标
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        if len > self.data.len() {
+            return Err(ParseError::ParseFailed(format!(
+                "数据不足: 需要 {} 字节，剩余 {} 字节",
+                len,
+                self.data.len()
+            )));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+
+    /// 读取 u32 长度前缀（超出剩余数据视为损坏）
+    fn read_len(&mut self) -> Result<usize, ParseError> {
+        let len = u32::from_le_bytes(self.read()?) as usize;
+        if len > self.data.len() {
+            return Err(ParseError::ParseFailed(format!("长度前缀越界: {}", len)));
+        }
+        Ok(len)
+    }
+}
+
+fn as_array(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn field_name(value: &Value) -> Result<&str, ParseError> {
+    value["name"]
+        .as_str()
+        .ok_or_else(|| ParseError::ParseFailed(format!("IDL 项缺少 name: {}", value)))
+}
+
+fn parse_discriminator(value: &Value) -> Result<Vec<u8>, ParseError> {
+    as_array(value)
+        .iter()
+        .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .filter(|disc| !disc.is_empty())
+        .ok_or_else(|| ParseError::ParseFailed(format!("无效的 discriminator: {}", value)))
+}
+
+/// Anchor 的 discriminator：`sha256("<namespace>:<name>")[..8]`
+fn sighash(namespace: &str, name: &str) -> Vec<u8> {
+    Sha256::digest(format!("{}:{}", namespace, name).as_bytes())[..8].to_vec()
+}
+
+/// camelCase → snake_case（旧格式 IDL 的指令名为 camelCase）
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn flatten_accounts(value: &Value, prefix: &str, out: &mut Vec<String>) {
+    for account in as_array(value) {
+        let name = account["name"].as_str().unwrap_or_default();
+        let full_name =
+            if prefix.is_empty() { name.to_string() } else { format!("{}.{}", prefix, name) };
+        match account.get("accounts") {
+            Some(nested) => flatten_accounts(nested, &full_name, out),
+            None => out.push(full_name),
+        }
+    }
+}
+
+fn parse_fields(value: &Value) -> Result<IdlFields, ParseError> {
+    let fields = as_array(value);
+    if fields.is_empty() {
+        return Ok(IdlFields::None);
+    }
+    if fields
+        .iter()
+        .all(|field| field.get("name").is_some() && field.get("type").is_some())
+    {
+        fields
+            .iter()
+            .map(|field| Ok((field_name(field)?.to_string(), parse_type(&field["type"])?)))
+            .collect::<Result<_, ParseError>>()
+            .map(IdlFields::Named)
+    } else {
+        fields.iter().map(parse_type).collect::<Result<_, _>>().map(IdlFields::Tuple)
+    }
+}
+
+fn parse_type_def(value: &Value) -> Result<IdlTypeDef, ParseError> {
+    match value["kind"].as_str() {
+        Some("struct") => Ok(IdlTypeDef::Struct(parse_fields(&value["fields"])?)),
+        Some("enum") => as_array(&value["variants"])
+            .iter()
+            .map(|variant| {
+                Ok((field_name(variant)?.to_string(), parse_fields(&variant["fields"])?))
+            })
+            .collect::<Result<_, ParseError>>()
+            .map(IdlTypeDef::Enum),
+        Some("type") | Some("alias") => Ok(IdlTypeDef::Alias(parse_type(&value["alias"])?)),
+        _ => Err(ParseError::ParseFailed(format!("不支持的类型定义: {}", value))),
+    }
+}
+
+fn parse_type(value: &Value) -> Result<IdlType, ParseError> {
+    let unsupported = || ParseError::ParseFailed(format!("不支持的 IDL 类型: {}", value));
+    if let Some(name) = value.as_str() {
+        return Ok(match name {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "f32" => IdlType::F32,
+            "u64" => IdlType::U64,
+            "i64" => IdlType::I64,
+            "f64" => IdlType::F64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "bytes" => IdlType::Bytes,
+            "string" => IdlType::String,
+            "pubkey" | "publicKey" => IdlType::Pubkey,
+            _ => return Err(unsupported()),
+        });
+    }
+    if let Some(inner) = value.get("vec") {
+        return Ok(IdlType::Vec(Box::new(parse_type(inner)?)));
+    }
+    if let Some(inner) = value.get("option") {
+        return Ok(IdlType::Option(Box::new(parse_type(inner)?)));
+    }
+    if let Some(inner) = value.get("coption") {
+        return Ok(IdlType::COption(Box::new(parse_type(inner)?)));
+    }
+    if let Some(array) = value.get("array") {
+        let len = array[1].as_u64().ok_or_else(unsupported)? as usize;
+        return Ok(IdlType::Array(Box::new(parse_type(&array[0])?), len));
+    }
+    match value.get("defined") {
+        Some(Value::String(name)) => Ok(IdlType::Defined(name.clone())),
+        Some(defined) => defined["name"]
+            .as_str()
+            .map(|name| IdlType::Defined(name.to_string()))
+            .ok_or_else(unsupported),
+        None => Err(unsupported()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEW_FORMAT_IDL: &str = r#"{
+        "address": "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+        "metadata": { "name": "fork_amm" },
+        "instructions": [{
+            "name": "swap",
+            "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+            "accounts": [
+                { "name": "user" },
+                { "name": "pool", "accounts": [{ "name": "state" }, { "name": "vault" }] }
+            ],
+            "args": [
+                { "name": "amount_in", "type": "u64" },
+                { "name": "min_out", "type": { "option": "u64" } },
+                { "name": "side", "type": { "defined": { "name": "Side" } } }
+            ]
+        }],
+        "events": [{ "name": "SwapEvent", "discriminator": [9, 9, 9, 9, 9, 9, 9, 9] }],
+        "types": [
+            { "name": "Side", "type": { "kind": "enum", "variants": [{ "name": "Buy" }, { "name": "Sell" }] } },
+            { "name": "SwapEvent", "type": { "kind": "struct", "fields": [
+                { "name": "pool", "type": "pubkey" },
+                { "name": "amounts", "type": { "vec": "u64" } },
+                { "name": "sqrt_price", "type": "u128" },
+                { "name": "memo", "type": "string" }
+            ] } }
+        ]
+    }"#;
+
+    #[test]
+    fn test_decode_new_format() {
+        let decoder = IdlDecoder::from_json(NEW_FORMAT_IDL).unwrap();
+        assert_eq!(decoder.name, "fork_amm");
+        assert!(decoder.program_id.is_some());
+
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        data.extend(1_000u64.to_le_bytes());
+        data.push(1);
+        data.extend(990u64.to_le_bytes());
+        data.push(1);
+        let accounts: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let ix = decoder.decode_instruction(&data, &accounts).unwrap();
+        assert_eq!(ix.name, "swap");
+        assert_eq!(ix.args["amount_in"], 1_000);
+        assert_eq!(ix.args["min_out"], 990);
+        assert_eq!(ix.args["side"], "Sell");
+        assert_eq!(ix.accounts["pool.vault"], accounts[2].to_string());
+        assert_eq!(ix.accounts["remaining"][0], accounts[3].to_string());
+        assert!(decoder.decode_instruction(&data[..12], &accounts).is_err());
+
+        let pool = Pubkey::new_unique();
+        let mut event = EVENT_IX_TAG.to_vec();
+        event.extend([9; 8]);
+        event.extend(pool.to_bytes());
+        event.extend(2u32.to_le_bytes());
+        event.extend(5u64.to_le_bytes());
+        event.extend(6u64.to_le_bytes());
+        event.extend(u128::MAX.to_le_bytes());
+        event.extend(2u32.to_le_bytes());
+        event.extend(b"hi");
+        let decoded = decoder.decode_event(&event).unwrap();
+        assert_eq!(decoded.name, "SwapEvent");
+        assert_eq!(decoded.data["pool"], pool.to_string());
+        assert_eq!(decoded.data["amounts"], serde_json::json!([5, 6]));
+        assert_eq!(decoded.data["sqrt_price"], u128::MAX.to_string());
+        assert_eq!(decoded.data["memo"], "hi");
+    }
+
+    #[test]
+    fn test_decode_legacy_format() {
+        let idl = r#"{
+            "name": "legacy",
+            "instructions": [{
+                "name": "swapBaseIn",
+                "accounts": [{ "name": "amm", "isMut": true, "isSigner": false }],
+                "args": [{ "name": "data", "type": { "array": ["u8", 4] } }]
+            }],
+            "events": [{ "name": "Traded", "fields": [{ "name": "ok", "type": "bool", "index": false }] }]
+        }"#;
+        let decoder = IdlDecoder::from_json(idl).unwrap();
+        assert_eq!(decoder.program_id, None);
+
+        let mut data = sighash("global", "swap_base_in");
+        data.extend([0xde, 0xad, 0xbe, 0xef]);
+        let ix = decoder.decode_instruction(&data, &[]).unwrap();
+        assert_eq!(ix.args["data"], "deadbeef");
+
+        let mut event = sighash("event", "Traded");
+        event.push(1);
+        assert_eq!(decoder.decode_event(&event).unwrap().data["ok"], true);
+        assert_eq!(to_snake_case("swapBaseIn"), "swap_base_in");
+    }
+}
@@ -1,21 +1,31 @@
 //! Solana DEX 交易解析模块
 
+pub mod aggregator;
 pub mod base_parser;
 pub mod constants;
 pub mod dex_parser;
 pub mod discriminators;
+pub mod idl;
 pub mod instruction_data_parser;
 pub mod transaction_adapter;
+pub mod transfers;
 pub mod types;
 pub mod utils;
+pub mod wallet_stats;
+pub mod whale;
 
 pub mod pumpswap;
 pub mod raydium;
 
-pub use dex_parser::DexParser;
+pub use aggregator::Aggregator;
+pub use dex_parser::{DexParser, ParsedTransaction};
 pub use discriminators::{DexProtocol, DiscriminatorRegistry, InstructionType};
+pub use idl::{IdlDecoded, IdlDecodedItem, IdlDecoder};
 pub use instruction_data_parser::{
     format_token_amount, parse_u64_from_offset, parse_u128_from_offset,
 };
+pub use transfers::classify_transfers;
 pub use types::*;
 pub use utils::BinaryReader;
+pub use wallet_stats::{WalletStats, WalletStatsAggregator};
+pub use whale::{WhaleFilter, WhaleTradeEvent, whale_trades};
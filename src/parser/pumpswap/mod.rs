@@ -107,6 +107,7 @@ impl PumpswapParser {
 
         Ok(ParsedTradeInfo {
             user: event.user,
+            fee_payer: adapter.fee_payer(),
             trade_type: TradeType::Buy,
             pool: event.pool,
             input_token,
@@ -114,6 +115,7 @@ impl PumpswapParser {
             fee,
             fees: vec![],
             dex: DexProtocol::PumpSwap.name().to_string(),
+            aggregator: None,
             signature: adapter.signature.clone(),
             slot: adapter.slot,
             timestamp: adapter.timestamp,
@@ -194,6 +196,7 @@ impl PumpswapParser {
 
         Ok(ParsedTradeInfo {
             user: event.user,
+            fee_payer: adapter.fee_payer(),
             trade_type: TradeType::Sell,
             pool: event.pool,
             input_token,
@@ -201,6 +204,7 @@ impl PumpswapParser {
             fee,
             fees: vec![],
             dex: DexProtocol::PumpSwap.name().to_string(),
+            aggregator: None,
             signature: adapter.signature.clone(),
             slot: adapter.slot,
             timestamp: adapter.timestamp,
@@ -217,13 +221,10 @@ impl DexParserTrait for PumpswapParser {
         let mut trades = Vec::new();
 
         // 获取 PumpSwap 程序的所有指令
-        let program_id_str = DexProtocol::PumpSwap.program_id();
-        let program_pubkey = program_id_str.parse().map_err(|_| {
-            ParseError::UnsupportedProtocol("Invalid PumpSwap program ID".to_string())
-        })?;
+        let program_ids = DexProtocol::PumpSwap.program_ids();
 
         // 从内部指令中查找事件（事件在 CPI 调用中发出）
-        let inner_instructions = adapter.get_inner_instructions_by_program(&program_pubkey);
+        let inner_instructions = adapter.get_inner_instructions_by_programs(&program_ids);
 
         // 解析每个指令中的事件
         for inner_ix in inner_instructions {
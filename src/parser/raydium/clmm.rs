@@ -9,7 +9,7 @@ use solana_sdk::pubkey::Pubkey;
 use crate::parser::{
     base_parser::{DexParserTrait, ParseError},
     discriminators::{DexProtocol as ParserDexProtocol, DiscriminatorRegistry},
-    transaction_adapter::TransactionAdapter,
+    transaction_adapter::{TransactionAdapter, TransferData},
     types::{DexProtocol, ParsedTradeInfo, TokenInfo, TradeType},
 };
 
@@ -60,14 +60,11 @@ impl RaydiumClmmParser {
         Ok(accounts[2])
     }
 
-    /// 获取指令相关的 Transfer 记录
-    fn get_transfers_for_instruction(
+    /// 将调用范围内的 Transfer 数据转换为 Transfer 记录
+    fn transfer_records(
         &self,
-        adapter: &TransactionAdapter,
-        outer_index: usize,
+        transfer_data_list: Vec<TransferData>,
     ) -> Result<Vec<TransferRecord>, ParseError> {
-        let transfer_data_list = adapter.get_transfers_for_instruction(outer_index);
-
         // 将 TransferData 转换为 TransferRecord
         let mut records = Vec::new();
         for td in transfer_data_list {
@@ -183,22 +180,19 @@ impl DexParserTrait for RaydiumClmmParser {
     ) -> Result<Vec<ParsedTradeInfo>, ParseError> {
         let mut trades = Vec::new();
 
-        let program_id_str = DexProtocol::RaydiumClmm.program_id();
-        let program_pubkey = program_id_str.parse().map_err(|_| {
-            ParseError::UnsupportedProtocol("Invalid Raydium CLMM program ID".to_string())
-        })?;
+        let program_ids = DexProtocol::RaydiumClmm.program_ids();
 
-        // 1. 首先尝试从 inner instructions 中解析 CLMM 指令
-        let inner_instructions = adapter.get_inner_instructions_by_program(&program_pubkey);
+        // 外层指令与 CPI 调用（聚合器路由）均解析，每次调用只取其范围内的转账
+        let invocations = adapter.get_invocations_with_transfers(&program_ids);
 
-        for inner_ix in inner_instructions {
+        for (instr, transfers) in invocations {
             // 过滤非 Swap 指令
-            if !self.is_swap_instruction(&inner_ix.instruction.data) {
+            if !self.is_swap_instruction(&instr.data) {
                 continue;
             }
 
             // 获取 Transfer 记录
-            let transfers = self.get_transfers_for_instruction(adapter, inner_ix.outer_index)?;
+            let transfers = self.transfer_records(transfers)?;
 
             // 至少需要 2 个 transfer
             if transfers.len() < 2 {
@@ -206,13 +200,12 @@ impl DexParserTrait for RaydiumClmmParser {
             }
 
             // 提取池地址
-            let pool = self.extract_pool_address(&inner_ix.instruction.accounts)?;
+            let pool = self.extract_pool_address(&instr.accounts)?;
 
             // 从 Transfer 的 authority 中提取用户地址
             // 而不是从账户列表中提取,因为账户列表中的用户地址可能不准确
-            let user = transfers
-                .iter()
-                .find_map(|t| t.authority)
+            let user = adapter
+                .resolve_trade_owner(transfers.iter().filter_map(|t| t.authority))
                 .ok_or(ParseError::ParseFailed("无法从 Transfer 记录中提取用户地址".into()))?;
 
             // 提取唯一代币
@@ -238,6 +231,7 @@ impl DexParserTrait for RaydiumClmmParser {
 
             trades.push(ParsedTradeInfo {
                 user,
+                fee_payer: adapter.fee_payer(),
                 trade_type,
                 pool,
                 input_token,
@@ -245,79 +239,13 @@ impl DexParserTrait for RaydiumClmmParser {
                 fee,
                 fees: vec![],
                 dex: DexProtocol::RaydiumClmm.name().to_string(),
+                aggregator: None,
                 signature: adapter.signature.clone(),
                 slot: adapter.slot,
                 timestamp: adapter.timestamp,
             });
         }
 
-        // 2. 如果没有从 inner instructions 中找到，尝试从外层指令解析
-        if trades.is_empty() {
-            for (idx, instruction) in adapter.instructions.iter().enumerate() {
-                // 只处理 CLMM 程序的指令
-                if instruction.program_id != program_pubkey {
-                    continue;
-                }
-
-                // 过滤非 Swap 指令
-                if !self.is_swap_instruction(&instruction.data) {
-                    continue;
-                }
-
-                // 获取 Transfer 记录
-                let transfers = self.get_transfers_for_instruction(adapter, idx)?;
-
-                // 至少需要 2 个 transfer
-                if transfers.len() < 2 {
-                    continue;
-                }
-
-                // 提取池地址（accounts[2]）
-                let pool = self.extract_pool_address(&instruction.accounts)?;
-
-                // 从 Transfer 的 authority 中提取用户地址
-                let user = transfers
-                    .iter()
-                    .find_map(|t| t.authority)
-                    .ok_or(ParseError::ParseFailed("无法从 Transfer 记录中提取用户地址".into()))?;
-
-                // 提取唯一代币
-                let unique_transfers = self.extract_unique_tokens(&transfers);
-                if unique_transfers.len() < 2 {
-                    continue;
-                }
-
-                // 判断交易类型
-                let (trade_type, input_transfer, output_transfer) =
-                    self.determine_trade_type(user, &transfers)?;
-
-                // 构建 TokenInfo
-                let input_token = self.transfer_to_tokeninfo(input_transfer, user);
-                let output_token = self.transfer_to_tokeninfo(output_transfer, user);
-
-                // 检测费用（第3个 transfer）
-                let fee = if transfers.len() > 2 {
-                    Some(self.transfer_to_tokeninfo(&transfers[2], user))
-                } else {
-                    None
-                };
-
-                trades.push(ParsedTradeInfo {
-                    user,
-                    trade_type,
-                    pool,
-                    input_token,
-                    output_token,
-                    fee,
-                    fees: vec![],
-                    dex: DexProtocol::RaydiumClmm.name().to_string(),
-                    signature: adapter.signature.clone(),
-                    slot: adapter.slot,
-                    timestamp: adapter.timestamp,
-                });
-            }
-        }
-
         if trades.is_empty() {
             return Err(ParseError::ParseFailed("未找到有效的 Raydium CLMM 交易".to_string()));
         }
@@ -328,22 +256,6 @@ impl DexParserTrait for RaydiumClmmParser {
     fn protocol(&self) -> DexProtocol {
         DexProtocol::RaydiumClmm
     }
-
-    /// 重写 can_parse 方法，检查外层指令和内部指令
-    fn can_parse(&self, adapter: &TransactionAdapter) -> bool {
-        let program_id = self.protocol().program_id();
-        let program_pubkey: solana_sdk::pubkey::Pubkey = program_id
-            .parse()
-            .unwrap_or_else(|_| panic!("无效的程序 ID 常量 '{}': 解析失败", program_id));
-
-        // 检查 inner instructions 中是否有 CLMM 程序的指令
-        let has_inner = !adapter.get_inner_instructions_by_program(&program_pubkey).is_empty();
-
-        // 也检查外层指令中是否有 CLMM 程序的指令
-        let has_outer = adapter.instructions.iter().any(|ix| ix.program_id == program_pubkey);
-
-        has_inner || has_outer
-    }
 }
 
 #[cfg(test)]
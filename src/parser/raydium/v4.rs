@@ -9,7 +9,7 @@ use solana_sdk::pubkey::Pubkey;
 use crate::parser::{
     base_parser::{DexParserTrait, ParseError},
     discriminators::{DexProtocol as ParserDexProtocol, DiscriminatorRegistry},
-    transaction_adapter::TransactionAdapter,
+    transaction_adapter::{TransactionAdapter, TransferData},
     types::{DexProtocol, ParsedTradeInfo, TokenInfo, TradeType},
 };
 
@@ -60,14 +60,11 @@ impl RaydiumV4Parser {
         Ok(accounts[1])
     }
 
-    /// 获取指令相关的 Transfer 记录
-    fn get_transfers_for_instruction(
+    /// 将调用范围内的 Transfer 数据转换为 Transfer 记录
+    fn transfer_records(
         &self,
-        adapter: &TransactionAdapter,
-        outer_index: usize,
+        transfer_data_list: Vec<TransferData>,
     ) -> Result<Vec<TransferRecord>, ParseError> {
-        let transfer_data_list = adapter.get_transfers_for_instruction(outer_index);
-
         // 将 TransferData 转换为 TransferRecord
         let mut records = Vec::new();
         for td in transfer_data_list {
@@ -183,21 +180,19 @@ impl DexParserTrait for RaydiumV4Parser {
     ) -> Result<Vec<ParsedTradeInfo>, ParseError> {
         let mut trades = Vec::new();
 
-        let program_id_str = DexProtocol::RaydiumV4.program_id();
-        let program_pubkey = program_id_str.parse().map_err(|_| {
-            ParseError::UnsupportedProtocol("Invalid Raydium V4 program ID".to_string())
-        })?;
+        let program_ids = DexProtocol::RaydiumV4.program_ids();
 
-        let instructions = adapter.get_instructions_by_program(&program_pubkey);
+        // 外层指令与 CPI 调用（聚合器路由）均解析，每次调用只取其范围内的转账
+        let invocations = adapter.get_invocations_with_transfers(&program_ids);
 
-        for instr in instructions {
+        for (instr, transfers) in invocations {
             // 过滤非 Swap 指令
             if !self.is_swap_instruction(&instr.data) {
                 continue;
             }
 
             // 获取 Transfer 记录
-            let transfers = self.get_transfers_for_instruction(adapter, instr.index)?;
+            let transfers = self.transfer_records(transfers)?;
 
             // 至少需要 2 个 transfer
             if transfers.len() < 2 {
@@ -209,9 +204,8 @@ impl DexParserTrait for RaydiumV4Parser {
 
             // 从 Transfer 的 authority 中提取用户地址
             // 而不是从账户列表中提取,因为账户列表中的用户地址可能不准确
-            let user = transfers
-                .iter()
-                .find_map(|t| t.authority)
+            let user = adapter
+                .resolve_trade_owner(transfers.iter().filter_map(|t| t.authority))
                 .ok_or(ParseError::ParseFailed("无法从 Transfer 记录中提取用户地址".into()))?;
 
             // 提取唯一代币
@@ -237,6 +231,7 @@ impl DexParserTrait for RaydiumV4Parser {
 
             trades.push(ParsedTradeInfo {
                 user,
+                fee_payer: adapter.fee_payer(),
                 trade_type,
                 pool,
                 input_token,
@@ -244,6 +239,7 @@ impl DexParserTrait for RaydiumV4Parser {
                 fee,
                 fees: vec![],
                 dex: DexProtocol::RaydiumV4.name().to_string(),
+                aggregator: None,
                 signature: adapter.signature.clone(),
                 slot: adapter.slot,
                 timestamp: adapter.timestamp,
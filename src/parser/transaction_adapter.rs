@@ -126,6 +126,8 @@ pub struct TransactionAdapter {
     pub timestamp: i64,
     /// 账户公钥列表
     pub account_keys: Vec<Pubkey>,
+    /// 签名账户数（`account_keys` 的前 N 个为签名者，account[0] 为手续费支付者）
+    pub num_required_signatures: usize,
     /// 代币余额变化映射 (token_account -> (pre_balance, post_balance))
     pub token_balance_changes: HashMap<Pubkey, (Option<UiTokenAmount>, Option<UiTokenAmount>)>,
     /// SPL Token Account -> Mint 映射
@@ -156,6 +158,7 @@ impl TransactionAdapter {
 
         // 提取账户密钥
         let account_keys = Self::extract_account_keys(tx_with_meta)?;
+        let num_required_signatures = Self::extract_num_required_signatures(tx_with_meta)?;
 
         // 提取代币余额变化
         let (token_balance_changes, spl_token_map, spl_decimals_map) =
@@ -165,11 +168,16 @@ impl TransactionAdapter {
         let (instructions, inner_instructions, inner_instructions_json) =
             Self::extract_instructions(tx_with_meta, &account_keys)?;
 
+        for (mint, decimals) in &spl_decimals_map {
+            crate::utils::token::cache_token_decimals(*mint, *decimals);
+        }
+
         Ok(Self {
             signature,
             slot,
             timestamp,
             account_keys,
+            num_required_signatures,
             token_balance_changes,
             spl_token_map,
             spl_decimals_map,
@@ -257,6 +265,29 @@ impl TransactionAdapter {
         Ok(keys)
     }
 
+    /// 提取签名账户数
+    ///
+    /// 优先读取 message header；jsonParsed 编码没有 header，按 accountKeys 的 `signer` 标记计数。
+    fn extract_num_required_signatures(
+        tx: &EncodedTransactionWithStatusMeta,
+    ) -> Result<usize, AdapterError> {
+        let tx_value =
+            serde_json::to_value(tx).map_err(|e| AdapterError::JsonError(e.to_string()))?;
+        let message = &tx_value["transaction"]["message"];
+        if let Some(count) = message["header"]["numRequiredSignatures"].as_u64() {
+            return Ok(count as usize);
+        }
+        if let Some(account_keys) = message["accountKeys"].as_array() {
+            let signers =
+                account_keys.iter().filter(|key| key["signer"].as_bool() == Some(true)).count();
+            if signers > 0 {
+                return Ok(signers);
+            }
+        }
+        // 无法确定时只把手续费支付者视为签名者
+        Ok(1)
+    }
+
     /// 提取代币余额变化
     fn extract_token_balances(
         tx: &EncodedTransactionWithStatusMeta,
@@ -545,6 +576,40 @@ impl TransactionAdapter {
         Ok((instructions, inner_instructions, inner_instructions_json))
     }
 
+    /// 手续费支付者（account[0]）
+    pub fn fee_payer(&self) -> Option<Pubkey> {
+        self.account_keys.first().copied()
+    }
+
+    /// 交易的全部签名者
+    pub fn signers(&self) -> &[Pubkey] {
+        let count = self.num_required_signatures.min(self.account_keys.len());
+        &self.account_keys[..count]
+    }
+
+    pub fn is_signer(&self, account: &Pubkey) -> bool {
+        self.signers().contains(account)
+    }
+
+    /// 从候选授权方中确定交易归属者
+    ///
+    /// 代付（gasless）交易的 account[0] 是第三方手续费支付者，真正的交易者是代币转出的授权方。
+    /// 优先选择签名了交易的非手续费支付者候选，其次是任意签名者，最后退回第一个候选
+    /// （池子 PDA 等程序授权方不会签名）。
+    pub fn resolve_trade_owner(
+        &self,
+        candidates: impl IntoIterator<Item = Pubkey>,
+    ) -> Option<Pubkey> {
+        let candidates: Vec<Pubkey> = candidates.into_iter().collect();
+        let fee_payer = self.fee_payer();
+        candidates
+            .iter()
+            .find(|candidate| self.is_signer(candidate) && Some(**candidate) != fee_payer)
+            .or_else(|| candidates.iter().find(|candidate| self.is_signer(candidate)))
+            .or_else(|| candidates.first())
+            .copied()
+    }
+
     /// 获取指定账户的代币余额变化
     pub fn get_token_balance_change(
         &self,
@@ -560,7 +625,10 @@ impl TransactionAdapter {
 
     /// 获取 Mint 的精度
     pub fn get_mint_decimals(&self, mint: &Pubkey) -> Option<u8> {
-        self.spl_decimals_map.get(mint).copied()
+        self.spl_decimals_map
+            .get(mint)
+            .copied()
+            .or_else(|| crate::utils::token::cached_token_decimals(mint))
     }
 
     /// 获取指定程序ID的所有外部指令
@@ -579,6 +647,25 @@ impl TransactionAdapter {
             .collect()
     }
 
+    /// 获取属于任一程序ID的所有外部指令（用于同时匹配官方程序与分叉程序）
+    pub fn get_instructions_by_programs(&self, program_ids: &[Pubkey]) -> Vec<&InstructionInfo> {
+        self.instructions
+            .iter()
+            .filter(|ix| program_ids.contains(&ix.program_id))
+            .collect()
+    }
+
+    /// 获取属于任一程序ID的所有内部指令
+    pub fn get_inner_instructions_by_programs(
+        &self,
+        program_ids: &[Pubkey],
+    ) -> Vec<&InnerInstructionInfo> {
+        self.inner_instructions
+            .iter()
+            .filter(|ix| program_ids.contains(&ix.instruction.program_id))
+            .collect()
+    }
+
     /// 获取所有 transferChecked 类型的内部指令
     pub fn get_transfer_checked_instructions(&self) -> Vec<&InnerInstructionInfo> {
         self.inner_instructions
@@ -967,12 +1054,175 @@ impl TransactionAdapter {
             .filter(|t| t.outer_index == outer_index)
             .collect()
     }
+
+    /// 获取 CPI 调用范围内的 Transfer 记录
+    ///
+    /// 聚合器在一个外部指令内依次调用多个 DEX，每次调用只拥有其后、调用深度更深的转账。
+    /// RPC 未返回 `stackHeight` 时，以下一个非 Token / System 程序的调用（Anchor 事件 CPI 除外）为边界。
+    pub fn get_transfers_for_inner_instruction(
+        &self,
+        outer_index: usize,
+        inner_index: usize,
+    ) -> Vec<TransferData> {
+        let stack_height = |ix: &InnerInstructionInfo| {
+            ix.instruction
+                .parsed_json
+                .as_ref()
+                .and_then(|json| json["stackHeight"].as_u64())
+        };
+        let mut siblings =
+            self.inner_instructions.iter().filter(|ix| ix.outer_index == outer_index);
+        let Some(invocation) = siblings.clone().find(|ix| ix.inner_index == inner_index) else {
+            return vec![];
+        };
+        let height = stack_height(invocation);
+        let end = siblings
+            .find(|ix| {
+                ix.inner_index > inner_index
+                    && match (height, stack_height(ix)) {
+                        (Some(height), Some(ix_height)) => ix_height <= height,
+                        _ => is_invocation_boundary(&ix.instruction),
+                    }
+            })
+            .map_or(usize::MAX, |ix| ix.inner_index);
+        self.get_transfers_for_instruction(outer_index)
+            .into_iter()
+            .filter(|t| t.inner_index > inner_index && t.inner_index < end)
+            .collect()
+    }
+
+    /// 获取属于任一程序ID的全部调用（外层指令与 CPI 调用），以及各自范围内的 Transfer 记录
+    ///
+    /// 按执行顺序返回；外层指令的转账为该外部指令内的全部转账。
+    pub fn get_invocations_with_transfers(
+        &self,
+        program_ids: &[Pubkey],
+    ) -> Vec<(&InstructionInfo, Vec<TransferData>)> {
+        let mut invocations: Vec<((usize, usize), &InstructionInfo, Vec<TransferData>)> = self
+            .get_instructions_by_programs(program_ids)
+            .into_iter()
+            .map(|ix| ((ix.index, 0), ix, self.get_transfers_for_instruction(ix.index)))
+            .collect();
+        invocations.extend(self.get_inner_instructions_by_programs(program_ids).into_iter().map(
+            |ix| {
+                let transfers =
+                    self.get_transfers_for_inner_instruction(ix.outer_index, ix.inner_index);
+                ((ix.outer_index, ix.inner_index + 1), &ix.instruction, transfers)
+            },
+        ));
+        invocations.sort_by_key(|(order, _, _)| *order);
+        invocations.into_iter().map(|(_, ix, transfers)| (ix, transfers)).collect()
+    }
+}
+
+/// 是否开始了新的一次调用（而不是当前调用内的转账、建账户或事件 CPI）
+fn is_invocation_boundary(ix: &InstructionInfo) -> bool {
+    let nested_programs = [
+        token_program(),
+        token_program_2022(),
+        crate::constants::SYSTEM_PROGRAM,
+        crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID,
+    ];
+    !nested_programs.contains(&ix.program_id) && !ix.data.starts_with(&super::idl::EVENT_IX_TAG)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_trade_owner_for_sponsored_transaction() {
+        let (sponsor, user, pool_authority) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let adapter = TransactionAdapter {
+            signature: String::new(),
+            slot: 0,
+            timestamp: 0,
+            account_keys: vec![sponsor, user, pool_authority],
+            num_required_signatures: 2,
+            token_balance_changes: HashMap::new(),
+            spl_token_map: HashMap::new(),
+            spl_decimals_map: HashMap::new(),
+            instructions: vec![],
+            inner_instructions: vec![],
+            inner_instructions_json: vec![],
+        };
+        assert_eq!(adapter.fee_payer(), Some(sponsor));
+        assert_eq!(adapter.signers(), &[sponsor, user]);
+        assert_eq!(adapter.resolve_trade_owner([pool_authority, user]), Some(user));
+        assert_eq!(adapter.resolve_trade_owner([sponsor, pool_authority]), Some(sponsor));
+        assert_eq!(adapter.resolve_trade_owner([pool_authority]), Some(pool_authority));
+        assert_eq!(adapter.resolve_trade_owner([]), None);
+    }
+
+    #[test]
+    fn test_invocation_transfers_split_aggregator_route() {
+        let (cpmm, clmm) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let inner = |inner_index, program_id, json| InnerInstructionInfo {
+            outer_index: 0,
+            inner_index,
+            instruction: InstructionInfo {
+                program_id,
+                accounts: vec![],
+                data: vec![],
+                index: 0,
+                parsed_json: Some(json),
+            },
+        };
+        let transfer = |inner_index, mint: Pubkey| {
+            let json = serde_json::json!({
+                "programId": token_program().to_string(),
+                "stackHeight": 3,
+                "parsed": {"type": "transferChecked", "info": {
+                    "source": Pubkey::new_unique().to_string(),
+                    "destination": Pubkey::new_unique().to_string(),
+                    "mint": mint.to_string(),
+                    "tokenAmount": {"amount": "1", "uiAmount": 1.0},
+                }},
+            });
+            inner(inner_index, token_program(), json)
+        };
+        // 聚合器外部指令内依次调用 CPMM（a -> b）与 CLMM（b -> c）
+        let route = vec![
+            inner(0, cpmm, serde_json::json!({"stackHeight": 2})),
+            transfer(1, a),
+            transfer(2, b),
+            inner(3, clmm, serde_json::json!({"stackHeight": 2})),
+            transfer(4, b),
+            transfer(5, c),
+        ];
+
+        for with_stack_height in [true, false] {
+            let mut inner_instructions = route.clone();
+            if !with_stack_height {
+                for ix in &mut inner_instructions {
+                    ix.instruction.parsed_json.as_mut().unwrap()["stackHeight"] =
+                        serde_json::Value::Null;
+                }
+            }
+            let adapter = TransactionAdapter {
+                signature: String::new(),
+                slot: 0,
+                timestamp: 0,
+                account_keys: vec![],
+                num_required_signatures: 1,
+                token_balance_changes: HashMap::new(),
+                spl_token_map: HashMap::new(),
+                spl_decimals_map: [(a, 6), (b, 6), (c, 6)].into_iter().collect(),
+                instructions: vec![],
+                inner_instructions,
+                inner_instructions_json: vec![],
+            };
+            let legs: Vec<Vec<Pubkey>> = adapter
+                .get_invocations_with_transfers(&[cpmm, clmm])
+                .into_iter()
+                .map(|(_, transfers)| transfers.iter().map(|t| t.mint).collect())
+                .collect();
+            assert_eq!(legs, vec![vec![a, b], vec![b, c]]);
+        }
+    }
+
     #[test]
     fn test_instruction_info_structure() {
         let program_id = Pubkey::new_unique();
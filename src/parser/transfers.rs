@@ -0,0 +1,266 @@
+//! 非 swap 动作分类：SOL 转账、SPL 代币转账、ATA 创建 / 关闭
+//!
+//! 钱包历史中除 swap 外还有充值、提现、建 / 关代币账户等动作。[`classify_transfers`] 按执行顺序扫描交易的
+//! 外层与内部指令（需 `JsonParsed` 编码），输出涉及被关注钱包的 [`ParsedTransferInfo`]。
+//! 两个被关注钱包之间的转账会输出两条（转出方 Out、接收方 In）。
+
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::common::spl_associated_token_account::get_associated_token_address_with_program_id;
+use crate::constants::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, SOL_TOKEN_ACCOUNT, SYSTEM_PROGRAM, TOKEN_PROGRAM,
+    TOKEN_PROGRAM_2022,
+};
+
+use super::{
+    transaction_adapter::{InstructionInfo, TransactionAdapter},
+    types::{ParsedTransferInfo, TokenInfo, TransferDirection, TransferKind},
+};
+
+/// 提取交易中涉及 `wallets` 的转账与建 / 关账户动作
+pub fn classify_transfers(
+    adapter: &TransactionAdapter,
+    wallets: &HashSet<Pubkey>,
+) -> Vec<ParsedTransferInfo> {
+    let mut transfers = Vec::new();
+    for outer in &adapter.instructions {
+        let inner = adapter
+            .inner_instructions
+            .iter()
+            .filter(|ix| ix.outer_index == outer.index)
+            .map(|ix| &ix.instruction);
+        for ix in std::iter::once(outer).chain(inner) {
+            classify_instruction(adapter, ix, wallets, &mut transfers);
+        }
+    }
+    transfers
+}
+
+fn classify_instruction(
+    adapter: &TransactionAdapter,
+    ix: &InstructionInfo,
+    wallets: &HashSet<Pubkey>,
+    out: &mut Vec<ParsedTransferInfo>,
+) {
+    let Some(json) = &ix.parsed_json else {
+        return;
+    };
+    let Some(instruction_type) = json["parsed"]["type"].as_str() else {
+        return;
+    };
+    let info = &json["parsed"]["info"];
+    let pubkey = |key: &str| info[key].as_str().and_then(|s| Pubkey::from_str(s).ok());
+    let is_token_program = ix.program_id == TOKEN_PROGRAM || ix.program_id == TOKEN_PROGRAM_2022;
+
+    // (类型, 转出账户, 转入账户, 代币, [(钱包, 方向)])
+    let (kind, source, destination, token, parties) = match instruction_type {
+        "transfer" | "transferWithSeed" if ix.program_id == SYSTEM_PROGRAM => {
+            let (Some(source), Some(destination), Some(lamports)) =
+                (pubkey("source"), pubkey("destination"), info["lamports"].as_u64())
+            else {
+                return;
+            };
+            let token = TokenInfo::from_raw_amount(SOL_TOKEN_ACCOUNT, lamports, 9);
+            let parties = [
+                (Some(source), TransferDirection::Out),
+                (Some(destination), TransferDirection::In),
+            ];
+            (TransferKind::Sol, source, destination, token, parties)
+        },
+        "transfer" | "transferChecked" if is_token_program => {
+            let (Some(source), Some(destination)) = (pubkey("source"), pubkey("destination"))
+            else {
+                return;
+            };
+            let Some(mint) = pubkey("mint")
+                .or_else(|| adapter.get_token_mint(&source).copied())
+                .or_else(|| adapter.get_token_mint(&destination).copied())
+            else {
+                return;
+            };
+            let amount = info["tokenAmount"]["amount"].as_str().or(info["amount"].as_str());
+            let decimals = info["tokenAmount"]["decimals"]
+                .as_u64()
+                .map(|d| d as u8)
+                .or_else(|| adapter.get_mint_decimals(&mint));
+            let (Some(amount), Some(decimals)) =
+                (amount.and_then(|a| a.parse::<u64>().ok()), decimals)
+            else {
+                return;
+            };
+            let authority = pubkey("authority").or_else(|| pubkey("multisigAuthority"));
+            let receiver = wallets.iter().copied().find(|wallet| {
+                get_associated_token_address_with_program_id(wallet, &mint, &ix.program_id)
+                    == destination
+            });
+            let mut token = TokenInfo::from_raw_amount(mint, amount, decimals);
+            token.authority = authority;
+            let parties = [(authority, TransferDirection::Out), (receiver, TransferDirection::In)];
+            (TransferKind::Token, source, destination, token, parties)
+        },
+        "create" | "createIdempotent" if ix.program_id == ASSOCIATED_TOKEN_PROGRAM_ID => {
+            let (Some(payer), Some(account)) = (pubkey("source"), pubkey("account")) else {
+                return;
+            };
+            let mint = pubkey("mint").unwrap_or_default();
+            let decimals = adapter.get_mint_decimals(&mint).unwrap_or(0);
+            let parties =
+                [(Some(payer), TransferDirection::Out), (pubkey("wallet"), TransferDirection::In)];
+            let token = TokenInfo::from_raw_amount(mint, 0, decimals);
+            (TransferKind::CreateTokenAccount, payer, account, token, parties)
+        },
+        "closeAccount" if is_token_program => {
+            let (Some(account), Some(destination)) = (pubkey("account"), pubkey("destination"))
+            else {
+                return;
+            };
+            let mint = adapter.get_token_mint(&account).copied().unwrap_or_default();
+            let decimals = adapter.get_mint_decimals(&mint).unwrap_or(0);
+            let owner = pubkey("owner").or_else(|| pubkey("multisigOwner"));
+            let parties =
+                [(Some(destination), TransferDirection::In), (owner, TransferDirection::Out)];
+            let token = TokenInfo::from_raw_amount(mint, 0, decimals);
+            (TransferKind::CloseTokenAccount, account, destination, token, parties)
+        },
+        _ => return,
+    };
+
+    let mut seen = Vec::with_capacity(2);
+    for (wallet, direction) in parties {
+        let Some(wallet) = wallet.filter(|wallet| wallets.contains(wallet)) else {
+            continue;
+        };
+        // 同一钱包同时是两方时只记录一条（建账户记为付租金，关账户记为收回租金）
+        if seen.contains(&wallet) {
+            continue;
+        }
+        seen.push(wallet);
+        let mut token = token.clone();
+        token.source = Some(source);
+        token.destination = Some(destination);
+        out.push(ParsedTransferInfo {
+            kind,
+            wallet,
+            direction,
+            source,
+            destination,
+            token,
+            signature: adapter.signature.clone(),
+            slot: adapter.slot,
+            timestamp: adapter.timestamp,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn instruction(index: usize, program_id: Pubkey, json: serde_json::Value) -> InstructionInfo {
+        InstructionInfo {
+            program_id,
+            accounts: vec![],
+            data: vec![],
+            index,
+            parsed_json: Some(json),
+        }
+    }
+
+    #[test]
+    fn test_classify_transfers() {
+        let (alice, bob, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let alice_ata = get_associated_token_address_with_program_id(&alice, &mint, &TOKEN_PROGRAM);
+        let bob_ata = get_associated_token_address_with_program_id(&bob, &mint, &TOKEN_PROGRAM);
+        let parsed = |kind: &str, info: serde_json::Value| serde_json::json!({"parsed": {"type": kind, "info": info}});
+        let instructions = vec![
+            instruction(
+                0,
+                SYSTEM_PROGRAM,
+                parsed(
+                    "transfer",
+                    serde_json::json!({
+                        "source": alice.to_string(),
+                        "destination": Pubkey::new_unique().to_string(),
+                        "lamports": 1_500_000_000u64,
+                    }),
+                ),
+            ),
+            instruction(
+                1,
+                ASSOCIATED_TOKEN_PROGRAM_ID,
+                parsed(
+                    "createIdempotent",
+                    serde_json::json!({
+                        "source": alice.to_string(),
+                        "account": bob_ata.to_string(),
+                        "wallet": bob.to_string(),
+                        "mint": mint.to_string(),
+                    }),
+                ),
+            ),
+            instruction(
+                2,
+                TOKEN_PROGRAM,
+                parsed(
+                    "transferChecked",
+                    serde_json::json!({
+                        "source": alice_ata.to_string(),
+                        "destination": bob_ata.to_string(),
+                        "authority": alice.to_string(),
+                        "mint": mint.to_string(),
+                        "tokenAmount": {"amount": "2500000", "decimals": 6},
+                    }),
+                ),
+            ),
+            instruction(
+                3,
+                TOKEN_PROGRAM,
+                parsed(
+                    "closeAccount",
+                    serde_json::json!({
+                        "account": alice_ata.to_string(),
+                        "destination": alice.to_string(),
+                        "owner": alice.to_string(),
+                    }),
+                ),
+            ),
+        ];
+        let adapter = TransactionAdapter {
+            signature: "sig".to_string(),
+            slot: 1,
+            timestamp: 0,
+            account_keys: vec![alice],
+            num_required_signatures: 1,
+            token_balance_changes: HashMap::new(),
+            spl_token_map: [(alice_ata, mint)].into_iter().collect(),
+            spl_decimals_map: [(mint, 6)].into_iter().collect(),
+            instructions,
+            inner_instructions: vec![],
+            inner_instructions_json: vec![],
+        };
+
+        let transfers = classify_transfers(&adapter, &[alice, bob].into_iter().collect());
+        let summary: Vec<_> = transfers
+            .iter()
+            .map(|t| (t.kind, t.wallet, t.direction, t.token.amount))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (TransferKind::Sol, alice, TransferDirection::Out, 1.5),
+                (TransferKind::CreateTokenAccount, alice, TransferDirection::Out, 0.0),
+                (TransferKind::CreateTokenAccount, bob, TransferDirection::In, 0.0),
+                (TransferKind::Token, alice, TransferDirection::Out, 2.5),
+                (TransferKind::Token, bob, TransferDirection::In, 2.5),
+                (TransferKind::CloseTokenAccount, alice, TransferDirection::In, 0.0),
+            ]
+        );
+        assert_eq!(transfers[5].token.mint, mint);
+
+        let only_bob = classify_transfers(&adapter, &[bob].into_iter().collect());
+        assert_eq!(only_bob.len(), 2);
+    }
+}
@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 
 /// 交易类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -56,8 +57,11 @@ impl TokenInfo {
 /// 解析后的交易信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedTradeInfo {
-    /// 用户地址
+    /// 用户地址（代币转出授权方 / 账户 owner，不一定是手续费支付者）
     pub user: Pubkey,
+    /// 手续费支付者（交易的 account[0]）；代付（gasless）交易中与 `user` 不同
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<Pubkey>,
     /// 交易类型
     pub trade_type: TradeType,
     /// 池地址
@@ -74,6 +78,9 @@ pub struct ParsedTradeInfo {
     pub fees: Vec<FeeInfo>,
     /// DEX 协议名称
     pub dex: String,
+    /// 经由的聚合器名称（如 `Jupiter`）；直接调用 DEX 时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregator: Option<String>,
     /// 交易签名
     pub signature: String,
     /// 区块槽位
@@ -82,6 +89,65 @@ pub struct ParsedTradeInfo {
     pub timestamp: i64,
 }
 
+impl ParsedTradeInfo {
+    /// 是否由第三方代付手续费
+    pub fn is_fee_sponsored(&self) -> bool {
+        self.fee_payer.is_some_and(|fee_payer| fee_payer != self.user)
+    }
+}
+
+/// 非 swap 动作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    /// SOL 转账（System Program）
+    Sol,
+    /// SPL 代币转账
+    Token,
+    /// 创建关联代币账户（ATA）
+    CreateTokenAccount,
+    /// 关闭代币账户
+    CloseTokenAccount,
+}
+
+/// 资金相对于被关注钱包的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDirection {
+    In,
+    Out,
+}
+
+/// 解析后的非 swap 动作（转账 / 建账户 / 关账户）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedTransferInfo {
+    pub kind: TransferKind,
+    /// 被关注的钱包
+    pub wallet: Pubkey,
+    /// 转账：SOL / 代币流向；建账户：付租金为 Out、获得账户为 In；关账户：收回租金为 In
+    pub direction: TransferDirection,
+    /// 转出账户（建账户为付款方，关账户为被关闭的账户）
+    pub source: Pubkey,
+    /// 转入账户（建账户为新账户，关账户为租金接收方）
+    pub destination: Pubkey,
+    /// 代币与数量（SOL 转账的 mint 为 `SOL_TOKEN_ACCOUNT`；建 / 关账户数量为 0，mint 未知时为默认值）
+    pub token: TokenInfo,
+    /// 交易签名
+    pub signature: String,
+    /// 区块槽位
+    pub slot: u64,
+    /// 时间戳
+    pub timestamp: i64,
+}
+
+/// 钱包历史中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalletActivity {
+    Trade(ParsedTradeInfo),
+    Transfer(ParsedTransferInfo),
+}
+
 /// 费用信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeeInfo {
@@ -149,14 +215,57 @@ impl DexProtocol {
         }
     }
 
-    /// 从程序ID解析协议
+    /// 协议识别的全部程序ID：官方程序 + 运行时注册的分叉程序
+    /// （见 [`DiscriminatorRegistry::register_program`](super::DiscriminatorRegistry::register_program)）
+    pub fn program_ids(&self) -> Vec<Pubkey> {
+        let official = Pubkey::from_str(self.program_id())
+            .unwrap_or_else(|_| panic!("无效的程序 ID 常量 '{}': 解析失败", self.program_id()));
+        let mut program_ids = vec![official];
+        program_ids.extend(super::DiscriminatorRegistry::registered_programs((*self).into()));
+        program_ids
+    }
+
+    /// 程序ID是否属于该协议（含运行时注册的分叉程序）
+    pub fn matches_program(&self, program_id: &Pubkey) -> bool {
+        self.program_ids().contains(program_id)
+    }
+
+    /// 从程序ID解析协议（含运行时注册的分叉程序）
     pub fn from_program_id(program_id: &str) -> Option<Self> {
         match program_id {
             "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA" => Some(DexProtocol::PumpSwap),
             "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8" => Some(DexProtocol::RaydiumV4),
             "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK" => Some(DexProtocol::RaydiumClmm),
             "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C" => Some(DexProtocol::RaydiumCpmm),
-            _ => None,
+            _ => Pubkey::from_str(program_id)
+                .ok()
+                .and_then(|program_id| {
+                    super::DiscriminatorRegistry::registered_protocol(&program_id)
+                })
+                .map(Self::from),
+        }
+    }
+
+    /// 从协议名称（[`ParsedTradeInfo::dex`]）解析协议
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            DexProtocol::PumpSwap,
+            DexProtocol::RaydiumV4,
+            DexProtocol::RaydiumClmm,
+            DexProtocol::RaydiumCpmm,
+        ]
+        .into_iter()
+        .find(|protocol| protocol.name() == name)
+    }
+
+    /// 对应的交易协议类型
+    pub fn dex_type(&self) -> crate::trading::factory::DexType {
+        use crate::trading::factory::DexType;
+        match self {
+            DexProtocol::PumpSwap => DexType::PumpSwap,
+            DexProtocol::RaydiumV4 => DexType::RaydiumAmmV4,
+            DexProtocol::RaydiumClmm => DexType::RaydiumClmm,
+            DexProtocol::RaydiumCpmm => DexType::RaydiumCpmm,
         }
     }
 }
@@ -178,4 +287,17 @@ mod tests {
         assert_eq!(token_info.amount_raw, "1000000");
         assert_eq!(token_info.decimals, decimals);
     }
+
+    #[test]
+    fn test_dex_protocol_from_name() {
+        for protocol in [
+            DexProtocol::PumpSwap,
+            DexProtocol::RaydiumV4,
+            DexProtocol::RaydiumClmm,
+            DexProtocol::RaydiumCpmm,
+        ] {
+            assert_eq!(DexProtocol::from_name(protocol.name()), Some(protocol));
+        }
+        assert_eq!(DexProtocol::from_name("Unknown"), None);
+    }
 }
@@ -0,0 +1,262 @@
+//! 按钱包聚合的交易统计
+//!
+//! 对解析器输出的 [`ParsedTradeInfo`] 按 `user` 聚合成交次数、USD 成交额、已实现盈亏、胜率与平均持仓时间，
+//! 用于筛选跟单目标。估值方式与 [`whale`](crate::parser::whale) 一致：稳定币侧按 1 USD，
+//! SOL / WSOL 侧乘以 SOL 价格；两侧都不是计价资产的交易不计入。
+//!
+//! 盈亏按 mint 以先进先出方式匹配买卖：卖出时依次消耗最早的买入批次，
+//! 卖出价与买入成本之差计为已实现盈亏，消耗批次的数量加权持仓时长计为该笔卖出的持仓时间。
+//! 开始统计前已持有的仓位（无对应买入）只计成交额，不计盈亏。
+
+use crate::common::PoolRpcClient;
+use crate::common::pricing::pricing_config;
+use crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client;
+use crate::parser::types::ParsedTradeInfo;
+use crate::parser::whale::{is_sol, quote_side, usd_notional};
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// 单个钱包的聚合统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WalletStats {
+    pub wallet: Pubkey,
+    /// 计入统计的成交笔数
+    pub trade_count: u64,
+    pub buy_count: u64,
+    pub sell_count: u64,
+    /// USD 成交额
+    pub volume_usd: f64,
+    /// 已实现盈亏（USD）
+    pub realized_pnl_usd: f64,
+    /// 匹配到买入批次的卖出笔数
+    pub closed_trades: u64,
+    /// 已实现盈亏为正的卖出笔数
+    pub winning_trades: u64,
+    /// 已匹配卖出的持仓时长之和（秒）
+    pub total_hold_secs: f64,
+    /// 首笔 / 最近一笔成交的时间戳
+    pub first_trade_at: i64,
+    pub last_trade_at: i64,
+}
+
+impl WalletStats {
+    /// 胜率（无已匹配卖出时为 None）
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.closed_trades > 0).then(|| self.winning_trades as f64 / self.closed_trades as f64)
+    }
+
+    /// 平均持仓时间（无已匹配卖出时为 None）
+    pub fn average_hold_time(&self) -> Option<Duration> {
+        (self.closed_trades > 0)
+            .then(|| Duration::from_secs_f64(self.total_hold_secs / self.closed_trades as f64))
+    }
+}
+
+/// 一笔未平仓的买入
+#[derive(Debug, Clone)]
+struct Lot {
+    amount: f64,
+    /// 每单位代币的 USD 成本
+    unit_cost_usd: f64,
+    timestamp: i64,
+}
+
+#[derive(Debug, Default)]
+struct WalletState {
+    stats: WalletStats,
+    lots: HashMap<Pubkey, VecDeque<Lot>>,
+}
+
+/// 按钱包聚合交易统计；交易需按时间顺序写入
+#[derive(Debug, Default)]
+pub struct WalletStatsAggregator {
+    wallets: HashMap<Pubkey, WalletState>,
+}
+
+impl WalletStatsAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 写入一笔交易；`sol_price_usd` 用于 SOL 计价的交易，无法估值时返回 `false` 且不计入
+    pub fn record(&mut self, trade: &ParsedTradeInfo, sol_price_usd: Option<f64>) -> bool {
+        let config = pricing_config();
+        let (Some(quote), Some(usd)) = (
+            quote_side(trade, &config.stablecoins),
+            usd_notional(trade, sol_price_usd, &config.stablecoins),
+        ) else {
+            return false;
+        };
+        let is_buy = quote.mint == trade.input_token.mint;
+        let base = if is_buy { &trade.output_token } else { &trade.input_token };
+        if base.amount <= 0.0 {
+            return false;
+        }
+
+        let state = self.wallets.entry(trade.user).or_default();
+        let stats = &mut state.stats;
+        if stats.trade_count == 0 {
+            stats.wallet = trade.user;
+            stats.first_trade_at = trade.timestamp;
+        }
+        stats.trade_count += 1;
+        stats.volume_usd += usd;
+        stats.last_trade_at = trade.timestamp;
+
+        let lots = state.lots.entry(base.mint).or_default();
+        if is_buy {
+            stats.buy_count += 1;
+            lots.push_back(Lot {
+                amount: base.amount,
+                unit_cost_usd: usd / base.amount,
+                timestamp: trade.timestamp,
+            });
+            return true;
+        }
+
+        stats.sell_count += 1;
+        let unit_price_usd = usd / base.amount;
+        let mut remaining = base.amount;
+        let (mut matched, mut pnl, mut hold_secs) = (0.0, 0.0, 0.0);
+        while remaining > 0.0 {
+            let Some(lot) = lots.front_mut() else {
+                break;
+            };
+            let amount = remaining.min(lot.amount);
+            pnl += amount * (unit_price_usd - lot.unit_cost_usd);
+            hold_secs += amount * trade.timestamp.saturating_sub(lot.timestamp).max(0) as f64;
+            matched += amount;
+            remaining -= amount;
+            lot.amount -= amount;
+            if lot.amount <= 0.0 {
+                lots.pop_front();
+            }
+        }
+        if lots.is_empty() {
+            state.lots.remove(&base.mint);
+        }
+        if matched > 0.0 {
+            stats.closed_trades += 1;
+            stats.realized_pnl_usd += pnl;
+            stats.total_hold_secs += hold_secs / matched;
+            if pnl > 0.0 {
+                stats.winning_trades += 1;
+            }
+        }
+        true
+    }
+
+    /// 写入一笔交易，SOL 计价时读取当前 SOL 价格
+    pub async fn record_priced<T: PoolRpcClient + ?Sized>(
+        &mut self,
+        rpc: &T,
+        trade: &ParsedTradeInfo,
+    ) -> Result<bool> {
+        let sol_price_usd = match quote_side(trade, &pricing_config().stablecoins) {
+            Some(quote) if is_sol(&quote.mint) => {
+                Some(get_wsol_price_in_usd_with_client(rpc, None).await?)
+            },
+            _ => None,
+        };
+        Ok(self.record(trade, sol_price_usd))
+    }
+
+    pub fn get(&self, wallet: &Pubkey) -> Option<&WalletStats> {
+        self.wallets.get(wallet).map(|state| &state.stats)
+    }
+
+    /// 钱包在某个 mint 上尚未卖出的数量
+    pub fn open_amount(&self, wallet: &Pubkey, mint: &Pubkey) -> f64 {
+        self.wallets
+            .get(wallet)
+            .and_then(|state| state.lots.get(mint))
+            .map(|lots| lots.iter().map(|lot| lot.amount).sum())
+            .unwrap_or(0.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WalletStats> {
+        self.wallets.values().map(|state| &state.stats)
+    }
+
+    /// 按已实现盈亏从高到低排序，只保留已匹配卖出不少于 `min_closed_trades` 笔的钱包
+    pub fn ranked_by_pnl(&self, min_closed_trades: u64) -> Vec<WalletStats> {
+        let mut ranked: Vec<WalletStats> = self
+            .iter()
+            .filter(|stats| stats.closed_trades >= min_closed_trades)
+            .cloned()
+            .collect();
+        ranked.sort_by(|a, b| b.realized_pnl_usd.total_cmp(&a.realized_pnl_usd));
+        ranked
+    }
+
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.wallets.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::USDC_MINT;
+    use crate::parser::types::{TokenInfo, TradeType};
+
+    fn trade(user: Pubkey, input: TokenInfo, output: TokenInfo, timestamp: i64) -> ParsedTradeInfo {
+        ParsedTradeInfo {
+            user,
+            fee_payer: None,
+            trade_type: TradeType::Swap,
+            pool: Pubkey::default(),
+            input_token: input,
+            output_token: output,
+            fee: None,
+            fees: vec![],
+            dex: "Raydium CPMM".to_string(),
+            aggregator: None,
+            signature: "sig".to_string(),
+            slot: 1,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_fifo_pnl_and_hold_time() {
+        let (user, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let usdc = |amount| TokenInfo::from_raw_amount(USDC_MINT, amount * 1_000_000, 6);
+        let token = |amount| TokenInfo::from_raw_amount(mint, amount * 1_000_000, 6);
+
+        let mut aggregator = WalletStatsAggregator::new();
+        // 100 @ 1.0，100 @ 2.0，卖出 150 @ 3.0
+        assert!(aggregator.record(&trade(user, usdc(100), token(100), 0), None));
+        assert!(aggregator.record(&trade(user, usdc(200), token(100), 100), None));
+        assert!(aggregator.record(&trade(user, token(150), usdc(450), 200), None));
+        // 剩余 50 @ 2.0，卖出 50 @ 1.0
+        assert!(aggregator.record(&trade(user, token(50), usdc(50), 400), None));
+
+        let stats = aggregator.get(&user).unwrap();
+        assert_eq!((stats.trade_count, stats.buy_count, stats.sell_count), (4, 2, 2));
+        assert_eq!(stats.volume_usd, 800.0);
+        // 100 * 2 + 50 * 1 - 50 * 1
+        assert_eq!(stats.realized_pnl_usd, 200.0);
+        assert_eq!(stats.win_rate(), Some(0.5));
+        // (100 * 200 + 50 * 100) / 150 与 300 的平均
+        let expected = (25_000.0 / 150.0 + 300.0) / 2.0;
+        assert!((stats.average_hold_time().unwrap().as_secs_f64() - expected).abs() < 1e-6);
+        assert_eq!(aggregator.open_amount(&user, &mint), 0.0);
+
+        // 无法估值的交易不计入
+        assert!(!aggregator.record(&trade(user, token(1), token(1), 500), None));
+        assert_eq!(aggregator.get(&user).unwrap().trade_count, 4);
+        assert_eq!(aggregator.ranked_by_pnl(2).len(), 1);
+        assert!(aggregator.ranked_by_pnl(3).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! 大额交易（鲸鱼）检测
+//!
+//! 对解析器输出的 [`ParsedTradeInfo`] 流按 USD 名义金额、mint、钱包过滤，输出 [`WhaleTradeEvent`]。
+//! USD 金额按计价侧计算：稳定币侧直接按 1 USD，SOL / WSOL 侧乘以
+//! [`get_wsol_price_in_usd_with_client`] 的价格（锚定池与缓存时长见
+//! [`PricingConfig`](crate::common::pricing::PricingConfig)，高频流建议设置 `refresh_interval`）。
+//! 两侧都不是计价资产的交易无法估值，不会输出。
+
+use crate::common::PoolRpcClient;
+use crate::common::pricing::pricing_config;
+use crate::constants::{SOL_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::utils::raydium_clmm::get_wsol_price_in_usd_with_client;
+use crate::parser::types::{ParsedTradeInfo, TokenInfo};
+use futures::{Stream, StreamExt};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 大额交易过滤条件；mint / 钱包为空集合时不限制
+#[derive(Debug, Clone, Default)]
+pub struct WhaleFilter {
+    /// 最小 USD 名义金额
+    pub min_usd_notional: f64,
+    /// 只关注交易了这些代币的交易（任一侧匹配即可）
+    pub mints: HashSet<Pubkey>,
+    /// 只关注这些钱包（`user` 或 `fee_payer` 匹配即可）
+    pub wallets: HashSet<Pubkey>,
+}
+
+impl WhaleFilter {
+    pub fn new(min_usd_notional: f64) -> Self {
+        Self { min_usd_notional, ..Default::default() }
+    }
+
+    pub fn with_mints(mut self, mints: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.mints = mints.into_iter().collect();
+        self
+    }
+
+    pub fn with_wallets(mut self, wallets: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.wallets = wallets.into_iter().collect();
+        self
+    }
+
+    /// mint / 钱包条件（不涉及价格）
+    pub fn matches_accounts(&self, trade: &ParsedTradeInfo) -> bool {
+        let mint_ok = self.mints.is_empty()
+            || self.mints.contains(&trade.input_token.mint)
+            || self.mints.contains(&trade.output_token.mint);
+        let wallet_ok = self.wallets.is_empty()
+            || self.wallets.contains(&trade.user)
+            || trade.fee_payer.is_some_and(|fee_payer| self.wallets.contains(&fee_payer));
+        mint_ok && wallet_ok
+    }
+}
+
+/// 大额交易事件
+#[derive(Debug, Clone)]
+pub struct WhaleTradeEvent {
+    pub trade: ParsedTradeInfo,
+    /// USD 名义金额
+    pub usd_notional: f64,
+    /// 用于估值的计价代币
+    pub quote_mint: Pubkey,
+    /// 估值时使用的 SOL 价格（稳定币计价时为 None）
+    pub sol_price_usd: Option<f64>,
+}
+
+pub(crate) fn is_sol(mint: &Pubkey) -> bool {
+    *mint == WSOL_TOKEN_ACCOUNT || *mint == SOL_TOKEN_ACCOUNT
+}
+
+/// 交易的计价侧：优先稳定币，其次 SOL / WSOL
+pub(crate) fn quote_side<'a>(
+    trade: &'a ParsedTradeInfo,
+    stablecoins: &[Pubkey],
+) -> Option<&'a TokenInfo> {
+    let sides = [&trade.input_token, &trade.output_token];
+    sides
+        .iter()
+        .find(|token| stablecoins.contains(&token.mint))
+        .or_else(|| sides.iter().find(|token| is_sol(&token.mint)))
+        .copied()
+}
+
+/// 计算交易的 USD 名义金额；计价侧为 SOL 但未提供价格、或无法估值时返回 None
+pub fn usd_notional(
+    trade: &ParsedTradeInfo,
+    sol_price_usd: Option<f64>,
+    stablecoins: &[Pubkey],
+) -> Option<f64> {
+    let quote = quote_side(trade, stablecoins)?;
+    if is_sol(&quote.mint) {
+        sol_price_usd.map(|price| quote.amount * price)
+    } else {
+        Some(quote.amount)
+    }
+}
+
+/// 对交易流做大额过滤，输出 [`WhaleTradeEvent`]
+///
+/// 仅在交易通过 mint / 钱包条件且以 SOL 计价时读取 SOL 价格；读取失败的交易会被丢弃并记录日志。
+pub fn whale_trades<S, T>(
+    rpc: Arc<T>,
+    trades: S,
+    filter: WhaleFilter,
+) -> impl Stream<Item = WhaleTradeEvent>
+where
+    S: Stream<Item = ParsedTradeInfo>,
+    T: PoolRpcClient + ?Sized,
+{
+    let filter = Arc::new(filter);
+    trades.filter_map(move |trade| {
+        let rpc = rpc.clone();
+        let filter = filter.clone();
+        async move {
+            if !filter.matches_accounts(&trade) {
+                return None;
+            }
+            let stablecoins = pricing_config().stablecoins.clone();
+            let quote_mint = quote_side(&trade, &stablecoins)?.mint;
+            let sol_price_usd = if is_sol(&quote_mint) {
+                match get_wsol_price_in_usd_with_client(rpc.as_ref(), None).await {
+                    Ok(price) => Some(price),
+                    Err(e) => {
+                        log::warn!("whale_trades: failed to price {}: {}", trade.signature, e);
+                        return None;
+                    },
+                }
+            } else {
+                None
+            };
+            let usd_notional = usd_notional(&trade, sol_price_usd, &stablecoins)?;
+            (usd_notional >= filter.min_usd_notional).then_some(WhaleTradeEvent {
+                trade,
+                usd_notional,
+                quote_mint,
+                sol_price_usd,
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::USDC_MINT;
+    use crate::parser::types::TradeType;
+
+    fn trade(input: TokenInfo, output: TokenInfo) -> ParsedTradeInfo {
+        ParsedTradeInfo {
+            user: Pubkey::new_unique(),
+            fee_payer: None,
+            trade_type: TradeType::Buy,
+            pool: Pubkey::new_unique(),
+            input_token: input,
+            output_token: output,
+            fee: None,
+            fees: vec![],
+            dex: "Raydium CPMM".to_string(),
+            aggregator: None,
+            signature: "sig".to_string(),
+            slot: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_usd_notional_and_filter() {
+        let mint = Pubkey::new_unique();
+        let sol_buy = trade(
+            TokenInfo::from_raw_amount(WSOL_TOKEN_ACCOUNT, 50_000_000_000, 9),
+            TokenInfo::from_raw_amount(mint, 1_000_000, 6),
+        );
+        let stables = [USDC_MINT];
+        assert_eq!(usd_notional(&sol_buy, Some(150.0), &stables), Some(7_500.0));
+        assert_eq!(usd_notional(&sol_buy, None, &stables), None);
+
+        let usdc_sell = trade(
+            TokenInfo::from_raw_amount(mint, 1_000_000, 6),
+            TokenInfo::from_raw_amount(USDC_MINT, 2_000_000_000, 6),
+        );
+        assert_eq!(usd_notional(&usdc_sell, None, &stables), Some(2_000.0));
+
+        let filter = WhaleFilter::new(1_000.0).with_mints([mint]);
+        assert!(filter.matches_accounts(&sol_buy));
+        let filter = filter.with_wallets([Pubkey::new_unique()]);
+        assert!(!filter.matches_accounts(&sol_buy));
+        let filter = WhaleFilter::new(1_000.0).with_wallets([sol_buy.user]);
+        assert!(filter.matches_accounts(&sol_buy));
+    }
+}
@@ -0,0 +1,246 @@
+//! 🚀 指令构建内存池
+//!
+//! 指令构建热路径上的堆分配集中在三处：`Vec<AccountMeta>`、指令数据 `Vec<u8>`
+//! 与指令列表 `Vec<Instruction>`。`Instruction` 以所有权持有这些缓冲区，无法使用借用型 bump
+//! 分配器，这里改为按类型的缓冲区池：
+//! - 构建器从池中取出已清空、保留容量的缓冲区；
+//! - 交易消息编译完成后，指令通过 [`recycle_instructions`] 拆解并归还缓冲区；
+//! - 预热后每笔交易的指令构建不再触发堆分配，可通过 [`arena_stats`] 的 `misses` 审计。
+
+use crossbeam_queue::ArrayQueue;
+use once_cell::sync::Lazy;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 每类缓冲区的池容量
+pub const ARENA_POOL_SIZE: usize = 1024;
+/// 账户列表缓冲区预分配容量（覆盖 PumpSwap 含 transfer hook 的最长账户列表）
+pub const ACCOUNT_METAS_CAPACITY: usize = 32;
+/// 指令数据缓冲区预分配容量
+pub const INSTRUCTION_DATA_CAPACITY: usize = 64;
+/// 指令列表缓冲区预分配容量
+pub const INSTRUCTION_VEC_CAPACITY: usize = 16;
+/// 超过该倍数的缓冲区不归还，避免个别大指令长期占用内存
+const MAX_RETAINED_FACTOR: usize = 8;
+
+/// 内存池统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArenaStats {
+    /// 从池中取到缓冲区的次数
+    pub hits: u64,
+    /// 池为空或容量不足、发生堆分配的次数
+    pub misses: u64,
+    /// 归还到池中的缓冲区数量
+    pub recycled: u64,
+}
+
+/// 指令构建缓冲区池
+pub struct InstructionArena {
+    account_metas: ArrayQueue<Vec<AccountMeta>>,
+    data: ArrayQueue<Vec<u8>>,
+    instructions: ArrayQueue<Vec<Instruction>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    recycled: AtomicU64,
+}
+
+impl InstructionArena {
+    pub fn new(pool_size: usize) -> Self {
+        Self {
+            account_metas: ArrayQueue::new(pool_size),
+            data: ArrayQueue::new(pool_size),
+            instructions: ArrayQueue::new(pool_size),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            recycled: AtomicU64::new(0),
+        }
+    }
+
+    /// 预热：预先分配 `count` 组缓冲区
+    pub fn warm_up(&self, count: usize) {
+        for _ in 0..count {
+            let _ = self.account_metas.push(Vec::with_capacity(ACCOUNT_METAS_CAPACITY));
+            let _ = self.data.push(Vec::with_capacity(INSTRUCTION_DATA_CAPACITY));
+            let _ = self.instructions.push(Vec::with_capacity(INSTRUCTION_VEC_CAPACITY));
+        }
+    }
+
+    #[inline(always)]
+    fn take<T>(&self, pool: &ArrayQueue<Vec<T>>, capacity: usize, default: usize) -> Vec<T> {
+        match pool.pop() {
+            Some(mut buffer) => {
+                if buffer.capacity() >= capacity {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    buffer.reserve(capacity);
+                }
+                buffer
+            },
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(capacity.max(default))
+            },
+        }
+    }
+
+    #[inline(always)]
+    fn give<T>(&self, pool: &ArrayQueue<Vec<T>>, mut buffer: Vec<T>, default: usize) {
+        if buffer.capacity() == 0 || buffer.capacity() > default * MAX_RETAINED_FACTOR {
+            return;
+        }
+        buffer.clear();
+        if pool.push(buffer).is_ok() {
+            self.recycled.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 取出容量至少为 `capacity` 的空账户列表
+    #[inline(always)]
+    pub fn account_metas(&self, capacity: usize) -> Vec<AccountMeta> {
+        self.take(&self.account_metas, capacity, ACCOUNT_METAS_CAPACITY)
+    }
+
+    /// 取出内容为 `data` 的指令数据缓冲区
+    #[inline(always)]
+    pub fn instruction_data(&self, data: &[u8]) -> Vec<u8> {
+        let mut buffer = self.take(&self.data, data.len(), INSTRUCTION_DATA_CAPACITY);
+        buffer.extend_from_slice(data);
+        buffer
+    }
+
+    /// 取出容量至少为 `capacity` 的空指令列表
+    #[inline(always)]
+    pub fn instruction_vec(&self, capacity: usize) -> Vec<Instruction> {
+        self.take(&self.instructions, capacity, INSTRUCTION_VEC_CAPACITY)
+    }
+
+    /// 使用池中缓冲区构建指令
+    #[inline(always)]
+    pub fn instruction(
+        &self,
+        program_id: solana_sdk::pubkey::Pubkey,
+        data: &[u8],
+        accounts: &[AccountMeta],
+    ) -> Instruction {
+        let mut metas = self.account_metas(accounts.len());
+        metas.extend_from_slice(accounts);
+        Instruction {
+            program_id,
+            accounts: metas,
+            data: self.instruction_data(data),
+        }
+    }
+
+    /// 使用池中缓冲区深拷贝指令列表（每个 SWQOS 任务各持有一份）
+    pub fn clone_instructions(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let mut cloned = self.instruction_vec(instructions.len());
+        cloned.extend(
+            instructions
+                .iter()
+                .map(|ix| self.instruction(ix.program_id, &ix.data, &ix.accounts)),
+        );
+        cloned
+    }
+
+    /// 拆解指令列表并归还全部缓冲区
+    pub fn recycle_instructions(&self, mut instructions: Vec<Instruction>) {
+        for instruction in instructions.drain(..) {
+            self.give(&self.account_metas, instruction.accounts, ACCOUNT_METAS_CAPACITY);
+            self.give(&self.data, instruction.data, INSTRUCTION_DATA_CAPACITY);
+        }
+        self.give(&self.instructions, instructions, INSTRUCTION_VEC_CAPACITY);
+    }
+
+    pub fn stats(&self) -> ArenaStats {
+        ArenaStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            recycled: self.recycled.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 🚀 全局指令构建缓冲区池（预热 256 组）
+pub static INSTRUCTION_ARENA: Lazy<InstructionArena> = Lazy::new(|| {
+    let arena = InstructionArena::new(ARENA_POOL_SIZE);
+    arena.warm_up(256);
+    arena
+});
+
+/// 从全局池取出空账户列表
+#[inline(always)]
+pub fn account_metas(capacity: usize) -> Vec<AccountMeta> {
+    INSTRUCTION_ARENA.account_metas(capacity)
+}
+
+/// 从全局池取出指令数据缓冲区
+#[inline(always)]
+pub fn instruction_data(data: &[u8]) -> Vec<u8> {
+    INSTRUCTION_ARENA.instruction_data(data)
+}
+
+/// 从全局池取出空指令列表
+#[inline(always)]
+pub fn instruction_vec(capacity: usize) -> Vec<Instruction> {
+    INSTRUCTION_ARENA.instruction_vec(capacity)
+}
+
+/// 使用全局池构建指令
+#[inline(always)]
+pub fn arena_instruction(
+    program_id: solana_sdk::pubkey::Pubkey,
+    data: &[u8],
+    accounts: &[AccountMeta],
+) -> Instruction {
+    INSTRUCTION_ARENA.instruction(program_id, data, accounts)
+}
+
+/// 使用全局池深拷贝指令列表
+pub fn clone_instructions(instructions: &[Instruction]) -> Vec<Instruction> {
+    INSTRUCTION_ARENA.clone_instructions(instructions)
+}
+
+/// 归还指令列表的全部缓冲区到全局池
+pub fn recycle_instructions(instructions: Vec<Instruction>) {
+    INSTRUCTION_ARENA.recycle_instructions(instructions)
+}
+
+/// 全局池统计
+pub fn arena_stats() -> ArenaStats {
+    INSTRUCTION_ARENA.stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_no_misses_after_warm_up() {
+        let arena = InstructionArena::new(16);
+        arena.warm_up(4);
+        let accounts = vec![AccountMeta::new(Pubkey::new_unique(), true); 16];
+
+        for _ in 0..100 {
+            let mut instructions = arena.instruction_vec(2);
+            instructions.push(arena.instruction(Pubkey::new_unique(), &[1u8; 24], &accounts));
+            let cloned = arena.clone_instructions(&instructions);
+            assert_eq!(cloned, instructions);
+            arena.recycle_instructions(cloned);
+            arena.recycle_instructions(instructions);
+        }
+
+        let stats = arena.stats();
+        assert_eq!(stats.misses, 0);
+        assert_eq!(stats.hits, 100 * 6);
+
+        // 过大的缓冲区不归还
+        arena.recycle_instructions(vec![Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: Vec::with_capacity(ACCOUNT_METAS_CAPACITY * MAX_RETAINED_FACTOR + 1),
+            data: vec![],
+        }]);
+        assert_eq!(arena.stats().recycled, stats.recycled + 1);
+    }
+}
@@ -6,15 +6,21 @@
 //! - 零拷贝 I/O：内存映射、DMA传输
 //! - 系统调用绕过：批处理、快速时间
 //! - 编译器优化：内联、向量化
+//! - 指令构建内存池：预热后零堆分配
+//! - 并行签名：绑核的专用签名线程
 
+pub mod arena;
 pub mod compiler_optimization;
 pub mod hardware_optimizations;
+pub mod signing_pool;
 pub mod simd;
 pub mod syscall_bypass;
 pub mod zero_copy_io;
 
+pub use arena::{ArenaStats, INSTRUCTION_ARENA, InstructionArena, arena_stats};
 pub use compiler_optimization::*;
 pub use hardware_optimizations::*;
+pub use signing_pool::{SigningPool, configure_signing_pool, signing_pool_threads};
 pub use simd::*;
 pub use syscall_bypass::*;
 pub use zero_copy_io::*;
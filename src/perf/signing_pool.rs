@@ -0,0 +1,126 @@
+//! 🚀 并行签名线程池
+//!
+//! 多个 SWQOS 变体（不同小费 / 小费账户）各自需要一次 ed25519 签名。默认在各自的 async
+//! 任务上签名；开启签名池后，签名交给少量绑核的专用线程执行，避免与 tokio 工作线程争用 CPU，
+//! 多个变体的签名可同时进行。
+//!
+//! 签名池为进程级单例，由 [`configure_signing_pool`] 创建（`TradeConfig::with_signing_threads`）。
+
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use tokio::sync::oneshot;
+
+struct SignJob {
+    payer: Arc<Keypair>,
+    message: Vec<u8>,
+    reply: oneshot::Sender<Result<Signature>>,
+}
+
+/// 签名线程池（每个线程一个任务队列，轮询分发）
+pub struct SigningPool {
+    workers: Vec<mpsc::Sender<SignJob>>,
+    next: AtomicUsize,
+}
+
+impl SigningPool {
+    /// 创建 `threads` 个签名线程，从最后一个 CPU 核心开始倒序绑核
+    /// （发送任务按顺序从前面的核心开始绑定）
+    pub fn new(threads: usize) -> Result<Self> {
+        if threads == 0 {
+            return Err(anyhow!("signing pool requires at least one thread"));
+        }
+        let cores = core_affinity::get_core_ids().unwrap_or_default();
+        let mut workers = Vec::with_capacity(threads);
+        for i in 0..threads {
+            let (tx, rx) = mpsc::channel::<SignJob>();
+            let core_id = (!cores.is_empty()).then(|| cores[cores.len() - 1 - i % cores.len()]);
+            std::thread::Builder::new().name(format!("sol-trade-signer-{}", i)).spawn(
+                move || {
+                    if let Some(core_id) = core_id {
+                        core_affinity::set_for_current(core_id);
+                    }
+                    while let Ok(job) = rx.recv() {
+                        let signature = job
+                            .payer
+                            .try_sign_message(&job.message)
+                            .map_err(|e| anyhow!("交易签名失败: {}", e));
+                        let _ = job.reply.send(signature);
+                    }
+                },
+            )?;
+            workers.push(tx);
+        }
+        Ok(Self { workers, next: AtomicUsize::new(0) })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// 在签名线程上签名消息
+    pub async fn sign(&self, payer: Arc<Keypair>, message: Vec<u8>) -> Result<Signature> {
+        let (reply, rx) = oneshot::channel();
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        self.workers[index]
+            .send(SignJob { payer, message, reply })
+            .map_err(|_| anyhow!("signing worker {} has stopped", index))?;
+        rx.await.map_err(|_| anyhow!("signing worker {} dropped the request", index))?
+    }
+}
+
+static SIGNING_POOL: Lazy<RwLock<Option<Arc<SigningPool>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 创建（或按新线程数重建）进程级签名池；`threads = 0` 时关闭，恢复在 async 任务上签名
+pub fn configure_signing_pool(threads: usize) -> Result<()> {
+    let mut pool = SIGNING_POOL.write();
+    if threads == 0 {
+        *pool = None;
+    } else if pool.as_ref().map(|p| p.threads()) != Some(threads) {
+        *pool = Some(Arc::new(SigningPool::new(threads)?));
+    }
+    Ok(())
+}
+
+/// 当前签名池线程数（未开启时为 0）
+pub fn signing_pool_threads() -> usize {
+    SIGNING_POOL.read().as_ref().map_or(0, |p| p.threads())
+}
+
+/// 签名消息：开启签名池时在签名线程上执行，否则在当前任务上直接签名
+pub async fn sign_message(payer: &Arc<Keypair>, message: Vec<u8>) -> Result<Signature> {
+    let pool = SIGNING_POOL.read().clone();
+    match pool {
+        Some(pool) => pool.sign(payer.clone(), message).await,
+        None => payer.try_sign_message(&message).map_err(|e| anyhow!("交易签名失败: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signing_pool_signs_in_parallel() {
+        let pool = Arc::new(SigningPool::new(2).unwrap());
+        let payer = Arc::new(Keypair::new());
+        let handles: Vec<_> = (0..8u8)
+            .map(|i| {
+                let pool = pool.clone();
+                let payer = payer.clone();
+                tokio::spawn(async move { (i, pool.sign(payer, vec![i; 64]).await.unwrap()) })
+            })
+            .collect();
+        for handle in handles {
+            let (i, signature) = handle.await.unwrap();
+            assert!(signature.verify(payer.pubkey().as_ref(), &[i; 64]));
+            assert_eq!(signature, payer.sign_message(&[i; 64]));
+        }
+        assert!(SigningPool::new(0).is_err());
+    }
+}
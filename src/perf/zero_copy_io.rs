@@ -13,6 +13,8 @@ use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use anyhow::{Context, Result};
 use crossbeam_utils::CachePadded;
 use memmap2::{MmapMut, MmapOptions};
+use std::fs::OpenOptions;
+use std::path::Path;
 use std::ptr::NonNull;
 use std::slice;
 
@@ -418,6 +420,87 @@ impl MemoryMappedBuffer {
     }
 }
 
+/// 🚀 文件映射共享内存 - 多个进程映射同一文件即可共享数据
+///
+/// 映射区域在进程间共享，读写同步由使用方负责（例如在区域内放置 seqlock 序号）。
+/// 已有文件只会增长、不会被截断：其他进程仍持有的映射始终有效，访问时不会触发 SIGBUS。
+pub struct SharedMappedFile {
+    mmap: MmapMut,
+}
+
+impl SharedMappedFile {
+    /// 创建（或复用已有）文件并映射 `size` 字节，内容原地清零
+    pub fn create(path: impl AsRef<Path>, size: usize) -> Result<Self> {
+        let shared = Self::open_or_create(path, size)?;
+        unsafe { std::ptr::write_bytes(shared.as_ptr(), 0, size) };
+        Ok(shared)
+    }
+
+    /// 创建（或复用已有）文件并映射 `size` 字节，保留已有内容
+    ///
+    /// 文件不足 `size` 时扩展（新增部分为 0）。其他进程可能正在读取旧内容，
+    /// 需要重新初始化时由使用方自行同步（例如先在头部标记代数，再逐段清理）。
+    pub fn open_or_create(path: impl AsRef<Path>, size: usize) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path.as_ref())
+            .with_context(|| format!("Failed to create {}", path.as_ref().display()))?;
+        if file.metadata()?.len() < size as u64 {
+            file.set_len(size as u64)?;
+        }
+        let mmap = unsafe { MmapOptions::new().len(size).map_mut(&file) }
+            .context("Failed to map shared file")?;
+        Ok(Self { mmap })
+    }
+
+    /// 映射已有文件的全部内容
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path.as_ref())
+            .with_context(|| format!("Failed to open {}", path.as_ref().display()))?;
+        let mmap =
+            unsafe { MmapOptions::new().map_mut(&file) }.context("Failed to map shared file")?;
+        Ok(Self { mmap })
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.mmap.is_empty()
+    }
+
+    /// 映射区域起始地址（其他进程可能同时读写，访问需自行同步）
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.mmap.as_ptr() as *mut u8
+    }
+
+    /// `offset` 处的原子 u64，`offset` 必须 8 字节对齐且不越界
+    #[inline(always)]
+    pub fn atomic_u64(&self, offset: usize) -> &AtomicU64 {
+        assert!(
+            offset.is_multiple_of(8) && offset + 8 <= self.len(),
+            "invalid atomic offset {}",
+            offset
+        );
+        unsafe { &*(self.as_ptr().add(offset) as *const AtomicU64) }
+    }
+
+    /// 将修改刷写到文件（共享映射对其他进程立即可见，刷写仅用于持久化）
+    pub fn flush(&self) -> Result<()> {
+        Ok(self.mmap.flush()?)
+    }
+}
+
 /// 🚀 直接内存访问管理器 - 模拟DMA操作
 pub struct DirectMemoryAccessManager {
     /// DMA通道池
@@ -716,6 +799,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_shared_mapped_file() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("zero_copy_{}.bin", std::process::id()));
+        let writer = SharedMappedFile::create(&path, 4096)?;
+        writer.atomic_u64(8).store(42, Ordering::Release);
+
+        let reader = SharedMappedFile::open(&path)?;
+        assert_eq!(reader.len(), 4096);
+        assert_eq!(reader.atomic_u64(8).load(Ordering::Acquire), 42);
+        writer.atomic_u64(8).store(43, Ordering::Release);
+        assert_eq!(reader.atomic_u64(8).load(Ordering::Acquire), 43);
+
+        // 以更小的尺寸重新创建：文件不截断，已有映射仍可访问，内容被清零
+        let recreated = SharedMappedFile::create(&path, 1024)?;
+        assert_eq!(recreated.len(), 1024);
+        assert_eq!(std::fs::metadata(&path)?.len(), 4096);
+        assert_eq!(reader.atomic_u64(8).load(Ordering::Acquire), 0);
+        assert_eq!(reader.atomic_u64(4088).load(Ordering::Acquire), 0);
+
+        drop((writer, reader, recreated));
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_dma_transfer() -> Result<()> {
         let dma_manager = DirectMemoryAccessManager::new(4)?;
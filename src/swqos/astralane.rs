@@ -1,10 +1,11 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -168,8 +169,7 @@ impl AstralaneClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         let request_body = serde_json::to_string(&json!({
             "jsonrpc": "2.0",
@@ -177,22 +177,22 @@ impl AstralaneClient {
             "method": "sendTransaction",
             "params": [
                 content,
-                { "encoding": "base64", "skipPreflight": true },
+                { "encoding": encoding, "skipPreflight": true },
                 { "mevProtect": false }
             ]
         }))?;
 
         // Send request with api_key header
-        let response_text = self
-            .http_client
-            .post(&self.endpoint)
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .header("api_key", &self.auth_token)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&self.endpoint)
+                .body(request_body)
+                .header("Content-Type", "application/json")
+                .header("api_key", &self.auth_token)
+                .send()
+                .await?,
+        )
+        .await?;
 
         // Parse JSON response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
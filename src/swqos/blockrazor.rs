@@ -1,4 +1,6 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::{
     Client,
@@ -7,7 +9,6 @@ use reqwest::{
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -178,8 +179,7 @@ impl BlockRazorClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, _, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         // BlockRazor使用fast模式的请求格式
         let request_body = serde_json::to_string(&json!({
@@ -188,16 +188,16 @@ impl BlockRazorClient {
         }))?;
 
         // BlockRazor使用apikey header
-        let response_text = self
-            .http_client
-            .post(&self.endpoint)
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .header("apikey", &self.auth_token)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&self.endpoint)
+                .body(request_body)
+                .header("Content-Type", "application/json")
+                .header("apikey", &self.auth_token)
+                .send()
+                .await?,
+        )
+        .await?;
 
         // Parse JSON response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
@@ -1,12 +1,10 @@
-use crate::swqos::common::{
-    FormatBase64VersionedTransaction, poll_transaction_confirmation,
-    serialize_transaction_and_encode,
-};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::{encode_for, transaction_encoder};
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -88,8 +86,7 @@ impl BloxrouteClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, _, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         let body = serde_json::json!({
             "transaction": {
@@ -100,16 +97,16 @@ impl BloxrouteClient {
         });
 
         let endpoint = format!("{}/api/v2/submit", self.endpoint);
-        let response_text = self
-            .http_client
-            .post(&endpoint)
-            .body(body.to_string())
-            .header("Content-Type", "application/json")
-            .header("Authorization", self.auth_token.clone())
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&endpoint)
+                .body(body.to_string())
+                .header("Content-Type", "application/json")
+                .header("Authorization", self.auth_token.clone())
+                .send()
+                .await?,
+        )
+        .await?;
 
         // 5. Use `serde_json::from_str()` to parse JSON, reducing extra wait from `.json().await?`
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
@@ -151,13 +148,14 @@ impl BloxrouteClient {
     ) -> Result<()> {
         let start_time = Instant::now();
 
+        let contents = transaction_encoder(self.get_swqos_type()).encode_batch(transactions)?;
         let body = serde_json::json!({
-            "entries":  transactions
-                .iter()
-                .map(|tx| {
+            "entries": contents
+                .into_iter()
+                .map(|content| {
                     serde_json::json!({
                         "transaction": {
-                            "content": tx.to_base64_string(),
+                            "content": content,
                         },
                     })
                 })
@@ -165,16 +163,16 @@ impl BloxrouteClient {
         });
 
         let endpoint = format!("{}/api/v2/submit-batch", self.endpoint);
-        let response_text = self
-            .http_client
-            .post(&endpoint)
-            .body(body.to_string())
-            .header("Content-Type", "application/json")
-            .header("Authorization", self.auth_token.clone())
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&endpoint)
+                .body(body.to_string())
+                .header("Content-Type", "application/json")
+                .header("Authorization", self.auth_token.clone())
+                .send()
+                .await?,
+        )
+        .await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -1,3 +1,4 @@
+use crate::common::program_error::{ProgramError, ProgramErrorKind, decode_from_logs};
 use crate::common::types::SolanaRpcClient;
 use anyhow::Result;
 use base64::Engine;
@@ -12,6 +13,7 @@ use solana_sdk::signature::Signature;
 use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::transaction::{Transaction, TransactionError};
 use solana_transaction_status::{TransactionConfirmationStatus, UiTransactionEncoding};
+use std::future::Future;
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
@@ -21,6 +23,144 @@ pub struct TradeError {
     pub code: u32,
     pub message: String,
     pub instruction: Option<u8>,
+    /// 解码后的协议错误（仅当失败程序为已支持的 DEX 且错误码已收录时）
+    pub program_error: Option<ProgramError>,
+    /// 链上执行失败时的程序日志（仅在等待确认且能获取交易详情时填充）
+    pub logs: Vec<String>,
+}
+
+impl TradeError {
+    /// 卖出前检查发现代币账户被冻结或 mint 存在永久委托（未发送交易）
+    pub const ACCOUNT_FROZEN: u32 = 1001;
+    /// 发送前检查发现三明治攻击可榨取价值超过阈值（未发送交易）
+    pub const SANDWICH_RISK: u32 = 1002;
+    /// 超过钱包交易频率限制（未发送交易）
+    pub const RATE_LIMITED: u32 = 1003;
+    /// 买入后付款钱包的 SOL 将低于配置的保留额（未发送交易）
+    pub const BELOW_SOL_RESERVE: u32 = 1004;
+    /// 发送前检查发现池子状态不可交易或不一致（未发送交易）
+    pub const POOL_STATE_INVALID: u32 = 1005;
+    /// 在确认超时时间内未观察到交易上链（交易可能仍会上链，并非执行失败）
+    pub const CONFIRMATION_TIMED_OUT: u32 = 1006;
+    /// 调用方通过取消令牌中止了交易（发送前取消则未发送交易）
+    pub const CANCELLED: u32 = 1007;
+
+    /// 统一错误类型（便于按滑点、曲线已完成等分别处理）
+    pub fn kind(&self) -> Option<ProgramErrorKind> {
+        self.program_error.as_ref().map(|e| e.kind)
+    }
+
+    /// 构造 [`Self::ACCOUNT_FROZEN`] 错误
+    pub fn account_frozen(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::ACCOUNT_FROZEN,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为卖出前检查发现的冻结 / 永久委托错误
+    pub fn is_account_frozen(&self) -> bool {
+        self.code == Self::ACCOUNT_FROZEN
+    }
+
+    /// 构造 [`Self::SANDWICH_RISK`] 错误
+    pub fn sandwich_risk(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::SANDWICH_RISK,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为发送前三明治风险检查拒绝的交易
+    pub fn is_sandwich_risk(&self) -> bool {
+        self.code == Self::SANDWICH_RISK
+    }
+
+    /// 构造 [`Self::RATE_LIMITED`] 错误
+    pub fn rate_limited(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::RATE_LIMITED,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为钱包交易频率限制拒绝的交易
+    pub fn is_rate_limited(&self) -> bool {
+        self.code == Self::RATE_LIMITED
+    }
+
+    /// 构造 [`Self::BELOW_SOL_RESERVE`] 错误
+    pub fn below_sol_reserve(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::BELOW_SOL_RESERVE,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为最低 SOL 保留检查拒绝的买入
+    pub fn is_below_sol_reserve(&self) -> bool {
+        self.code == Self::BELOW_SOL_RESERVE
+    }
+
+    /// 构造 [`Self::POOL_STATE_INVALID`] 错误
+    pub fn pool_state_invalid(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::POOL_STATE_INVALID,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为池子状态检查拒绝的交易
+    pub fn is_pool_state_invalid(&self) -> bool {
+        self.code == Self::POOL_STATE_INVALID
+    }
+
+    /// 构造 [`Self::CONFIRMATION_TIMED_OUT`] 错误
+    pub fn confirmation_timed_out(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::CONFIRMATION_TIMED_OUT,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为确认等待超时（调用方可自行继续跟踪签名）
+    pub fn is_confirmation_timed_out(&self) -> bool {
+        self.code == Self::CONFIRMATION_TIMED_OUT
+    }
+
+    /// 构造 [`Self::CANCELLED`] 错误
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        TradeError {
+            code: Self::CANCELLED,
+            message: message.into(),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// 是否为调用方取消
+    pub fn is_cancelled(&self) -> bool {
+        self.code == Self::CANCELLED
+    }
 }
 
 impl std::fmt::Display for TradeError {
@@ -36,7 +176,13 @@ impl From<anyhow::Error> for TradeError {
         if let Some(te) = e.downcast_ref::<TradeError>() {
             return te.clone();
         }
-        TradeError { code: 500, message: format!("{}", e), instruction: None }
+        TradeError {
+            code: 500,
+            message: format!("{}", e),
+            instruction: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
     }
 }
 
@@ -53,6 +199,47 @@ impl FormatBase64VersionedTransaction for VersionedTransaction {
     }
 }
 
+/// 等待交易确认的超时与轮询间隔
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmConfig {
+    /// 超时后返回 [`TradeError::CONFIRMATION_TIMED_OUT`]（默认：15 秒）
+    pub timeout: Duration,
+    /// `getSignatureStatuses` 轮询间隔（默认：1 秒）
+    pub poll_interval: Duration,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(15), // 🔧 增加到15秒，避免网络拥堵时超时
+            poll_interval: Duration::from_millis(1000),
+        }
+    }
+}
+
+impl ConfirmConfig {
+    pub fn new(timeout: Duration, poll_interval: Duration) -> Self {
+        Self { timeout, poll_interval }
+    }
+}
+
+tokio::task_local! {
+    static CONFIRM_CONFIG: ConfirmConfig;
+}
+
+/// 在指定确认配置下运行 provider 任务，任务内的 [`poll_transaction_confirmation`] 使用该配置
+pub(crate) fn confirm_scope<F: Future>(
+    config: ConfirmConfig,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    CONFIRM_CONFIG.scope(config, future)
+}
+
+/// 当前任务的确认配置（作用域外为默认值）
+fn confirm_config() -> ConfirmConfig {
+    CONFIRM_CONFIG.try_with(|config| *config).unwrap_or_default()
+}
+
 pub async fn poll_transaction_confirmation(
     rpc: &SolanaRpcClient,
     txt_sig: Signature,
@@ -62,15 +249,18 @@ pub async fn poll_transaction_confirmation(
     if !wait_confirmation {
         return Ok(txt_sig);
     }
+    crate::trading::timing::mark_confirm_started();
 
-    let timeout: Duration = Duration::from_secs(15); // 🔧 增加到15秒，避免网络拥堵时超时
-    let interval: Duration = Duration::from_millis(1000);
+    let ConfirmConfig { timeout, poll_interval: interval } = confirm_config();
     let start: Instant = Instant::now();
     let mut poll_count = 0u32;
 
     loop {
         if start.elapsed() >= timeout {
-            return Err(anyhow::anyhow!("Transaction {}'s confirmation timed out", txt_sig));
+            return Err(anyhow::Error::new(TradeError::confirmation_timed_out(format!(
+                "Transaction {}'s confirmation timed out after {:?}",
+                txt_sig, timeout
+            ))));
         }
 
         poll_count += 1;
@@ -84,6 +274,7 @@ pub async fn poll_transaction_confirmation(
                         || status.confirmation_status
                             == Some(TransactionConfirmationStatus::Finalized))
                 {
+                    crate::trading::timing::record_landed_slot(status.slot);
                     return Ok(txt_sig);
                 }
                 // 如果 getSignatureStatuses 返回了错误，立即获取详细信息
@@ -100,7 +291,7 @@ pub async fn poll_transaction_confirmation(
 
         // 优化：只在以下情况调用 getTransaction
         // 1. getSignatureStatuses 返回了错误
-        // 2. 或者已经轮询了较长时间（超过10次，即10秒）
+        // 2. 或者已经轮询了较长时间（超过10次，默认间隔下即10秒）
         let should_get_transaction =
             status.value[0].as_ref().map(|s| s.err.is_some()).unwrap_or(false) || poll_count >= 10;
 
@@ -128,64 +319,22 @@ pub async fn poll_transaction_confirmation(
             },
         };
 
+        crate::trading::timing::record_landed_slot(tx_details.slot);
         let meta = tx_details.transaction.meta;
         if let Some(meta) = meta {
             if meta.err.is_none() {
                 return Ok(txt_sig);
             } else {
-                // 从 log_messages 中提取错误信息
-                let mut error_msg = String::new();
-                if let solana_transaction_status::option_serializer::OptionSerializer::Some(logs) =
-                    &meta.log_messages
-                {
-                    for log in logs {
-                        if let Some(idx) = log.find("Error Message: ") {
-                            let msg = log[idx + 15..].trim_end_matches('.').to_string();
-                            if !error_msg.is_empty() {
-                                error_msg.push_str("; ");
-                            }
-                            error_msg.push_str(&msg);
-                        } else if let Some(idx) = log.find("Program log: Error: ") {
-                            let msg = log[idx + 20..].trim_end_matches('.').to_string();
-                            if !error_msg.is_empty() {
-                                error_msg.push_str("; ");
-                            }
-                            error_msg.push_str(&msg);
-                        }
-                    }
-                }
-
+                let logs = match meta.log_messages {
+                    solana_transaction_status::option_serializer::OptionSerializer::Some(logs) => {
+                        logs
+                    },
+                    _ => Vec::new(),
+                };
                 let ui_err = meta.err.unwrap();
                 let tx_err: TransactionError =
                     serde_json::from_value(serde_json::to_value(&ui_err)?)?;
-
-                // 直接使用Solana原生的InstructionError中的错误码
-                let mut code = 0u32;
-                let mut index = None;
-                if let TransactionError::InstructionError(i, i_error) = &tx_err {
-                    // 直接匹配所有InstructionError类型，Custom也是其中之一
-                    code = match i_error {
-                        solana_sdk::instruction::InstructionError::Custom(c) => *c,
-                        solana_sdk::instruction::InstructionError::GenericError => 1,
-                        solana_sdk::instruction::InstructionError::InvalidArgument => 2,
-                        solana_sdk::instruction::InstructionError::InvalidInstructionData => 3,
-                        solana_sdk::instruction::InstructionError::InvalidAccountData => 4,
-                        solana_sdk::instruction::InstructionError::AccountDataTooSmall => 5,
-                        solana_sdk::instruction::InstructionError::InsufficientFunds => 6,
-                        solana_sdk::instruction::InstructionError::IncorrectProgramId => 7,
-                        solana_sdk::instruction::InstructionError::MissingRequiredSignature => 8,
-                        solana_sdk::instruction::InstructionError::AccountAlreadyInitialized => 9,
-                        solana_sdk::instruction::InstructionError::UninitializedAccount => 10,
-                        _ => 999, // 其他未知错误
-                    };
-                    index = Some(*i);
-                }
-
-                return Err(anyhow::Error::new(TradeError {
-                    code,
-                    message: format!("{} {:?}", tx_err, error_msg),
-                    instruction: index,
-                }));
+                return Err(anyhow::Error::new(failed_trade_error(&tx_err, logs)));
             }
         } else {
             sleep(interval).await;
@@ -193,6 +342,63 @@ pub async fn poll_transaction_confirmation(
     }
 }
 
+/// 由链上执行失败的交易构造 [`TradeError`]：错误码取自 `InstructionError`，
+/// 附带程序日志与按协议错误表解码的错误
+pub fn failed_trade_error(tx_err: &TransactionError, logs: Vec<String>) -> TradeError {
+    // 从 log_messages 中提取错误信息
+    let mut error_msg = String::new();
+    let program_error = decode_from_logs(&logs);
+    for log in &logs {
+        let msg = if let Some(idx) = log.find("Error Message: ") {
+            &log[idx + 15..]
+        } else if let Some(idx) = log.find("Program log: Error: ") {
+            &log[idx + 20..]
+        } else {
+            continue;
+        };
+        if !error_msg.is_empty() {
+            error_msg.push_str("; ");
+        }
+        error_msg.push_str(msg.trim_end_matches('.'));
+    }
+    // Anchor 未输出可读日志时，使用解码后的协议错误说明
+    if error_msg.is_empty()
+        && let Some(e) = &program_error
+    {
+        error_msg = e.to_string();
+    }
+
+    // 直接使用Solana原生的InstructionError中的错误码
+    let mut code = 0u32;
+    let mut index = None;
+    if let TransactionError::InstructionError(i, i_error) = tx_err {
+        // 直接匹配所有InstructionError类型，Custom也是其中之一
+        code = match i_error {
+            solana_sdk::instruction::InstructionError::Custom(c) => *c,
+            solana_sdk::instruction::InstructionError::GenericError => 1,
+            solana_sdk::instruction::InstructionError::InvalidArgument => 2,
+            solana_sdk::instruction::InstructionError::InvalidInstructionData => 3,
+            solana_sdk::instruction::InstructionError::InvalidAccountData => 4,
+            solana_sdk::instruction::InstructionError::AccountDataTooSmall => 5,
+            solana_sdk::instruction::InstructionError::InsufficientFunds => 6,
+            solana_sdk::instruction::InstructionError::IncorrectProgramId => 7,
+            solana_sdk::instruction::InstructionError::MissingRequiredSignature => 8,
+            solana_sdk::instruction::InstructionError::AccountAlreadyInitialized => 9,
+            solana_sdk::instruction::InstructionError::UninitializedAccount => 10,
+            _ => 999, // 其他未知错误
+        };
+        index = Some(*i);
+    }
+
+    TradeError {
+        code,
+        message: format!("{} {:?}", tx_err, error_msg),
+        instruction: index,
+        program_error,
+        logs,
+    }
+}
+
 pub async fn send_nb_transaction(
     client: Client,
     endpoint: &str,
@@ -242,6 +448,35 @@ pub async fn send_nb_transaction(
     Ok(signature)
 }
 
+/// POST 请求体并返回响应文本
+///
+/// 开启 `io-uring` 特性（Linux）时，明文 `http://` 端点经 io_uring 提交线程批量发送；
+/// 其余情况使用 reqwest。
+pub async fn post_text(
+    client: &Client,
+    url: &str,
+    body: String,
+    headers: &[(&str, &str)],
+) -> Result<String> {
+    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+    if url.starts_with("http://")
+        && let Some(sender) = crate::swqos::uring_http::uring_sender()
+    {
+        let text = sender.post(url, &body, headers).await?;
+        crate::swqos::response::capture(crate::swqos::response::RawResponse {
+            body: text.clone(),
+            ..Default::default()
+        });
+        return Ok(text);
+    }
+
+    let mut request = client.post(url).body(body);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    Ok(crate::swqos::response::read_response(request.send().await?).await?)
+}
+
 pub async fn serialize_and_encode(
     transaction: &Vec<u8>,
     encoding: UiTransactionEncoding,
@@ -281,3 +516,35 @@ pub async fn serialize_smart_transaction_and_encode(
     };
     Ok((serialized, *signature))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::InstructionError;
+
+    #[test]
+    fn test_failed_trade_error_keeps_logs() {
+        let logs = vec![
+            "Program pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA invoke [1]".to_string(),
+            "Program pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA failed: custom program error: 0x1774"
+                .to_string(),
+        ];
+        let tx_err = TransactionError::InstructionError(2, InstructionError::Custom(6004));
+        let error = failed_trade_error(&tx_err, logs.clone());
+        assert_eq!(error.code, 6004);
+        assert_eq!(error.instruction, Some(2));
+        assert_eq!(error.kind(), Some(ProgramErrorKind::SlippageToleranceExceeded));
+        assert!(error.message.contains("ExceededSlippage"));
+        assert_eq!(error.logs, logs);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_scope() {
+        let config = ConfirmConfig::new(Duration::from_secs(60), Duration::from_millis(200));
+        assert_eq!(confirm_scope(config, async { confirm_config() }).await, config);
+        // 作用域外使用默认值
+        assert_eq!(confirm_config(), ConfirmConfig::default());
+        let error = TradeError::confirmation_timed_out("timed out");
+        assert!(error.is_confirmation_timed_out());
+    }
+}
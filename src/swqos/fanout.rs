@@ -0,0 +1,58 @@
+//! SWQOS 分发顺序
+//!
+//! 默认情况下同一笔交易同时发送给所有 provider。不使用 durable nonce 的交易（例如多通道卖出）
+//! 各 provider 的交易签名不同，同时分发时可能被不同中继打包进相邻的两个区块而重复成交。
+//! [`SwqosFanout`] 按顺序分批发送：`order` 中的 provider 依次各占一批，其余 provider 为最后一批，
+//! 相邻两批间隔 `stagger`。等待确认时，延后的 provider 在等待期间若已有交易上链则不再发送。
+
+use std::time::Duration;
+
+use super::SwqosType;
+
+/// provider 分发顺序与间隔
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwqosFanout {
+    /// 优先发送的 provider，按顺序每个一批；未列出的 provider 在最后一批发送
+    pub order: Vec<SwqosType>,
+    /// 相邻两批之间的间隔
+    pub stagger: Duration,
+}
+
+impl SwqosFanout {
+    /// 例如 `SwqosFanout::new(vec![SwqosType::Jito], Duration::from_millis(20))`：
+    /// 先发送 Jito，20ms 后发送其余 provider
+    pub fn new(order: Vec<SwqosType>, stagger: Duration) -> Self {
+        Self { order, stagger }
+    }
+
+    /// `swqos_type` 相对第一批的发送延迟
+    pub fn delay_for(&self, swqos_type: SwqosType) -> Duration {
+        let batch = self.order.iter().position(|t| *t == swqos_type).unwrap_or(self.order.len());
+        self.stagger.saturating_mul(batch as u32)
+    }
+}
+
+/// 未配置分发顺序时不延迟
+pub(crate) fn fanout_delay(fanout: Option<&SwqosFanout>, swqos_type: SwqosType) -> Duration {
+    fanout.map(|fanout| fanout.delay_for(swqos_type)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_for() {
+        let stagger = Duration::from_millis(20);
+        let fanout = SwqosFanout::new(vec![SwqosType::Jito], stagger);
+        assert_eq!(fanout.delay_for(SwqosType::Jito), Duration::ZERO);
+        assert_eq!(fanout.delay_for(SwqosType::Default), stagger);
+        assert_eq!(fanout.delay_for(SwqosType::Bloxroute), stagger);
+
+        let fanout = SwqosFanout::new(vec![SwqosType::Jito, SwqosType::Bloxroute], stagger);
+        assert_eq!(fanout.delay_for(SwqosType::Bloxroute), stagger);
+        assert_eq!(fanout.delay_for(SwqosType::Default), stagger * 2);
+
+        assert_eq!(fanout_delay(None, SwqosType::Default), Duration::ZERO);
+    }
+}
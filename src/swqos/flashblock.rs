@@ -1,10 +1,11 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -86,8 +87,7 @@ impl FlashBlockClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, _, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         // FlashBlock API format
         let request_body = serde_json::to_string(&json!({
@@ -97,18 +97,18 @@ impl FlashBlockClient {
         let url = format!("{}/api/v2/submit-batch", self.endpoint);
 
         // Send request to FlashBlock
-        let response_text = self
-            .http_client
-            .post(&url)
-            .body(request_body)
-            .header("Authorization", &self.auth_token)
-            .header("Content-Type", "application/json")
-            .header("Connection", "keep-alive")
-            .header("Keep-Alive", "timeout=30, max=1000")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&url)
+                .body(request_body)
+                .header("Authorization", &self.auth_token)
+                .header("Content-Type", "application/json")
+                .header("Connection", "keep-alive")
+                .header("Keep-Alive", "timeout=30, max=1000")
+                .send()
+                .await?,
+        )
+        .await?;
 
         // Parse response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
@@ -1,19 +1,19 @@
+pub mod bundle_status;
 pub mod dynamic_tip;
 pub mod types;
 
+pub use bundle_status::{BundleRejectReason, BundleStatus, BundleStatusEvent, BundleTrackConfig};
 pub use dynamic_tip::{DynamicTipConfig, JitoTipFloorClient, TipPercentile};
 pub use types::JitoRegion;
 
-use crate::swqos::common::{
-    FormatBase64VersionedTransaction, poll_transaction_confirmation,
-    serialize_transaction_and_encode,
-};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::{read_response, set_bundle_id};
+use crate::swqos::serialization::{encode_for, transaction_encoder};
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -168,8 +168,7 @@ impl JitoClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         let request_body = serde_json::to_string(&json!({
             "id": 1,
@@ -178,7 +177,7 @@ impl JitoClient {
             "params": [
                 content,
                 {
-                    "encoding": "base64"
+                    "encoding": encoding
                 }
             ]
         }))?;
@@ -193,13 +192,14 @@ impl JitoClient {
         } else {
             self.http_client.post(&endpoint).header("x-jito-auth", &self.auth_token)
         };
-        let response_text = response
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            response
+                .body(request_body)
+                .header("Content-Type", "application/json")
+                .send()
+                .await?,
+        )
+        .await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -235,14 +235,14 @@ impl JitoClient {
         _wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let txs_base64 =
-            transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
+        let encoder = transaction_encoder(self.get_swqos_type());
+        let txs_base64 = encoder.encode_batch(transactions)?;
         let body = serde_json::json!({
             "jsonrpc": "2.0",
             "method": "sendBundle",
             "params": [
                 txs_base64,
-                { "encoding": "base64" }
+                { "encoding": encoder.encoding().as_str() }
             ],
             "id": 1,
         });
@@ -257,43 +257,37 @@ impl JitoClient {
         } else {
             self.http_client.post(&endpoint).header("x-jito-auth", &self.auth_token)
         };
-        let response_text = response
-            .body(body.to_string())
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            response
+                .body(body.to_string())
+                .header("Content-Type", "application/json")
+                .send()
+                .await?,
+        )
+        .await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
+            if let Some(result) = response_json.get("result") {
+                if let Some(bundle_id) = result.as_str() {
+                    set_bundle_id(bundle_id.to_string());
+                }
                 println!(" jito {} submitted: {:?}", trade_type, start_time.elapsed());
                 Ok(())
             } else if let Some(error) = response_json.get("error") {
                 // 解析 Jito 错误信息
                 let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or(-1);
-                let message = error.get("message")
-                    .and_then(|m| m.as_str())
-                    .unwrap_or("Unknown error");
+                let message =
+                    error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
 
                 // 根据错误码提供友好的错误描述和建议
                 let (error_type, suggestion) = match code {
-                    -32097 => (
-                        "⏳ 网络拥堵 / 限流",
-                        "建议：等待几秒后重试，或降低发送频率"
-                    ),
+                    -32097 => ("⏳ 网络拥堵 / 限流", "建议：等待几秒后重试，或降低发送频率"),
                     -32602 => (
                         "🔄 重复交易",
-                        "建议：交易已在内存池中，请勿重复发送，或使用新的 blockhash"
-                    ),
-                    -32603 => (
-                        "⚠️  内部错误",
-                        "建议：Jito 服务暂时不可用，请稍后重试"
+                        "建议：交易已在内存池中，请勿重复发送，或使用新的 blockhash",
                     ),
-                    _ => (
-                        "❌ 未知错误",
-                        ""
-                    )
+                    -32603 => ("⚠️  内部错误", "建议：Jito 服务暂时不可用，请稍后重试"),
+                    _ => ("❌ 未知错误", ""),
                 };
 
                 let full_error = format!(
@@ -301,7 +295,11 @@ impl JitoClient {
                     error_type,
                     code,
                     message,
-                    if !suggestion.is_empty() { format!("💡 {}", suggestion) } else { String::new() }
+                    if !suggestion.is_empty() {
+                        format!("💡 {}", suggestion)
+                    } else {
+                        String::new()
+                    }
                 );
 
                 eprintln!(" jito {} submission failed:", trade_type);
@@ -309,10 +307,18 @@ impl JitoClient {
 
                 Err(anyhow::anyhow!("Jito {} submission failed: {}", trade_type, full_error))
             } else {
-                Err(anyhow::anyhow!("Jito {} submission failed: unknown response: {}", trade_type, response_text))
+                Err(anyhow::anyhow!(
+                    "Jito {} submission failed: unknown response: {}",
+                    trade_type,
+                    response_text
+                ))
             }
         } else {
-            Err(anyhow::anyhow!("Jito {} submission failed: invalid response: {}", trade_type, response_text))
+            Err(anyhow::anyhow!(
+                "Jito {} submission failed: invalid response: {}",
+                trade_type,
+                response_text
+            ))
         }
     }
 }
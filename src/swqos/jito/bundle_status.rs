@@ -0,0 +1,413 @@
+//! Jito bundle 状态跟踪
+//!
+//! 通过 Jito 提交后，交易没有上链的原因可能是小费过低、拍卖失败、bundle 过期等，
+//! 仅凭链上查不到签名无法区分。这里轮询 `getInflightBundleStatuses`（最近 5 分钟内提交的 bundle）
+//! 与 `getBundleStatuses`（已上链 bundle 的确认状态与执行错误），把状态变化整理为
+//! [`BundleStatusEvent`]，失败原因归类为 [`BundleRejectReason`]。
+//!
+//! bundle ID 来自 [`crate::SwqosResponse::bundle_id`]（`sendTransaction` 的 `x-bundle-id`
+//! 响应头，或 `sendBundle` 的返回值）。
+
+use crate::swqos::jito::JitoClient;
+use crate::swqos::response::SwqosResponse;
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Jito 单次查询最多支持的 bundle 数
+pub const MAX_BUNDLES_PER_REQUEST: usize = 5;
+
+/// bundle 被拒绝或失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleRejectReason {
+    /// 小费低于最低要求或未写锁小费账户
+    TipTooLow,
+    /// 请求被限流
+    RateLimited,
+    /// 重复提交（交易已在 bundle 中）
+    Duplicate,
+    /// 模拟执行失败
+    SimulationFailed(String),
+    /// 所有区域均未转发（通常是拍卖出价不足）
+    AuctionLost,
+    /// Jito 中查不到该 bundle（过期或从未被接受）
+    NotFound,
+    /// 上链但执行失败
+    ExecutionFailed(String),
+    Other(String),
+}
+
+impl BundleRejectReason {
+    /// 按 Jito 返回的错误信息归类
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        if lower.contains("tip") {
+            BundleRejectReason::TipTooLow
+        } else if lower.contains("rate limit") || lower.contains("congest") {
+            BundleRejectReason::RateLimited
+        } else if lower.contains("already processed") || lower.contains("duplicate") {
+            BundleRejectReason::Duplicate
+        } else if lower.contains("simulation") {
+            BundleRejectReason::SimulationFailed(message.to_string())
+        } else {
+            BundleRejectReason::Other(message.to_string())
+        }
+    }
+
+    /// 从提交响应中解析拒绝原因（提交被接受时为 None）
+    pub fn from_response(response: &SwqosResponse) -> Option<Self> {
+        let body = response.error_body.as_ref()?;
+        if response.http_status == Some(429) {
+            return Some(BundleRejectReason::RateLimited);
+        }
+        let message = serde_json::from_str::<Value>(body)
+            .ok()
+            .and_then(|v| v.get("error")?.get("message")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| body.clone());
+        Some(Self::classify(&message))
+    }
+}
+
+/// bundle 状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleStatus {
+    /// 已被 Block Engine 接受，等待打包
+    Pending,
+    /// 已上链；`confirmation_status` 为 processed / confirmed / finalized
+    Landed { slot: u64, confirmation_status: Option<String> },
+    /// 未上链或执行失败
+    Failed(BundleRejectReason),
+    /// 跟踪超时，最后一次查询仍未得到终态
+    TimedOut,
+}
+
+impl BundleStatus {
+    /// 是否为终态（之后状态不会再变化）
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, BundleStatus::Pending)
+    }
+}
+
+/// bundle 状态变化事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleStatusEvent {
+    pub bundle_id: String,
+    pub status: BundleStatus,
+    /// 查询时 Jito 节点所在的 slot
+    pub context_slot: Option<u64>,
+    /// bundle 内的交易签名（仅 `getBundleStatuses` 返回）
+    pub transactions: Vec<String>,
+}
+
+/// 跟踪配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleTrackConfig {
+    /// 轮询间隔
+    pub poll_interval: Duration,
+    /// 最长跟踪时间，超时后发送 [`BundleStatus::TimedOut`]
+    pub timeout: Duration,
+}
+
+impl Default for BundleTrackConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(500),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+fn context_slot(result: &Value) -> Option<u64> {
+    result
+        .get("context")
+        .and_then(|context| context.get("slot"))
+        .and_then(Value::as_u64)
+}
+
+fn rpc_result(response: &Value) -> Result<&Value> {
+    if let Some(error) = response.get("error") {
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+        return Err(anyhow!("Jito bundle status request failed: {}", message));
+    }
+    response
+        .get("result")
+        .ok_or_else(|| anyhow!("Jito bundle status response has no result"))
+}
+
+/// 解析 `getInflightBundleStatuses` 响应（按请求顺序，查不到的 bundle 为 NotFound）
+pub fn parse_inflight_statuses(
+    bundle_ids: &[String],
+    response: &Value,
+) -> Result<Vec<BundleStatusEvent>> {
+    let result = rpc_result(response)?;
+    let slot = context_slot(result);
+    let values = result.get("value").and_then(Value::as_array).cloned().unwrap_or_default();
+    Ok(bundle_ids
+        .iter()
+        .map(|bundle_id| {
+            let entry = values
+                .iter()
+                .find(|v| v.get("bundle_id").and_then(Value::as_str) == Some(bundle_id));
+            let status = match entry.and_then(|v| v.get("status")).and_then(Value::as_str) {
+                Some("Pending") => BundleStatus::Pending,
+                Some("Landed") => BundleStatus::Landed {
+                    slot: entry
+                        .and_then(|v| v.get("landed_slot"))
+                        .and_then(Value::as_u64)
+                        .unwrap_or(0),
+                    confirmation_status: None,
+                },
+                Some("Failed") => BundleStatus::Failed(BundleRejectReason::AuctionLost),
+                _ => BundleStatus::Failed(BundleRejectReason::NotFound),
+            };
+            BundleStatusEvent {
+                bundle_id: bundle_id.clone(),
+                status,
+                context_slot: slot,
+                transactions: vec![],
+            }
+        })
+        .collect())
+}
+
+/// 解析 `getBundleStatuses` 响应（只包含已上链的 bundle；未上链的为 None）
+pub fn parse_bundle_statuses(
+    bundle_ids: &[String],
+    response: &Value,
+) -> Result<Vec<Option<BundleStatusEvent>>> {
+    let result = rpc_result(response)?;
+    let slot = context_slot(result);
+    let values = result.get("value").and_then(Value::as_array).cloned().unwrap_or_default();
+    Ok(bundle_ids
+        .iter()
+        .map(|bundle_id| {
+            let entry = values
+                .iter()
+                .find(|v| v.get("bundle_id").and_then(Value::as_str) == Some(bundle_id))?;
+            let err = entry.get("err").and_then(|err| err.get("Err"));
+            let status = match err {
+                Some(err) if !err.is_null() => {
+                    BundleStatus::Failed(BundleRejectReason::ExecutionFailed(err.to_string()))
+                },
+                _ => BundleStatus::Landed {
+                    slot: entry.get("slot").and_then(Value::as_u64).unwrap_or(0),
+                    confirmation_status: entry
+                        .get("confirmation_status")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                },
+            };
+            let transactions = entry
+                .get("transactions")
+                .and_then(Value::as_array)
+                .map(|txs| txs.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default();
+            Some(BundleStatusEvent {
+                bundle_id: bundle_id.clone(),
+                status,
+                context_slot: slot,
+                transactions,
+            })
+        })
+        .collect())
+}
+
+impl JitoClient {
+    async fn bundle_rpc(&self, method: &str, bundle_ids: &[String]) -> Result<Value> {
+        if bundle_ids.len() > MAX_BUNDLES_PER_REQUEST {
+            return Err(anyhow!(
+                "Jito {} supports at most {} bundles per request",
+                method,
+                MAX_BUNDLES_PER_REQUEST
+            ));
+        }
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [bundle_ids],
+        });
+        let endpoint = if self.auth_token.is_empty() {
+            format!("{}/api/v1/{}", self.endpoint, method)
+        } else {
+            format!("{}/api/v1/{}?uuid={}", self.endpoint, method, self.auth_token)
+        };
+        let request = if self.auth_token.is_empty() {
+            self.http_client.post(&endpoint)
+        } else {
+            self.http_client.post(&endpoint).header("x-jito-auth", &self.auth_token)
+        };
+        let text = request
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// 查询最近 5 分钟内提交的 bundle 状态（每次最多 5 个）
+    pub async fn get_inflight_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> Result<Vec<BundleStatusEvent>> {
+        let response = self.bundle_rpc("getInflightBundleStatuses", bundle_ids).await?;
+        parse_inflight_statuses(bundle_ids, &response)
+    }
+
+    /// 查询已上链 bundle 的确认状态与执行结果（每次最多 5 个）
+    pub async fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> Result<Vec<Option<BundleStatusEvent>>> {
+        let response = self.bundle_rpc("getBundleStatuses", bundle_ids).await?;
+        parse_bundle_statuses(bundle_ids, &response)
+    }
+
+    /// 查询单个 bundle 的当前状态：已上链时以 `getBundleStatuses` 的结果为准
+    pub async fn bundle_status(&self, bundle_id: &str) -> Result<BundleStatusEvent> {
+        let ids = [bundle_id.to_string()];
+        let inflight = self
+            .get_inflight_bundle_statuses(&ids)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("Jito returned no status for bundle {}", bundle_id))?;
+        if matches!(inflight.status, BundleStatus::Landed { .. })
+            && let Some(Some(landed)) = self.get_bundle_statuses(&ids).await?.pop()
+        {
+            return Ok(landed);
+        }
+        Ok(inflight)
+    }
+
+    /// 在后台轮询 bundle 状态，每次状态变化发送一个事件，到达终态或超时后结束
+    ///
+    /// 提交后 Jito 可能需要短暂时间才能查到 bundle，超时前的 NotFound 不视为终态。
+    pub fn track_bundle(
+        &self,
+        bundle_id: String,
+        config: BundleTrackConfig,
+    ) -> mpsc::UnboundedReceiver<BundleStatusEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let client = JitoClient {
+            endpoint: self.endpoint.clone(),
+            auth_token: self.auth_token.clone(),
+            rpc_client: self.rpc_client.clone(),
+            http_client: self.http_client.clone(),
+        };
+        tokio::spawn(async move {
+            let deadline = Instant::now() + config.timeout;
+            let mut last: Option<BundleStatus> = None;
+            loop {
+                match client.bundle_status(&bundle_id).await {
+                    Ok(event) => {
+                        let not_found =
+                            event.status == BundleStatus::Failed(BundleRejectReason::NotFound);
+                        let terminal = event.status.is_terminal() && !not_found;
+                        if last.as_ref() != Some(&event.status) && !not_found {
+                            last = Some(event.status.clone());
+                            if tx.send(event).is_err() {
+                                return;
+                            }
+                        }
+                        if terminal {
+                            return;
+                        }
+                    },
+                    Err(e) => log::warn!("Jito bundle {} status query failed: {}", bundle_id, e),
+                }
+                if Instant::now() >= deadline {
+                    let status = if last.is_none() {
+                        BundleStatus::Failed(BundleRejectReason::NotFound)
+                    } else {
+                        BundleStatus::TimedOut
+                    };
+                    let _ = tx.send(BundleStatusEvent {
+                        bundle_id,
+                        status,
+                        context_slot: None,
+                        transactions: vec![],
+                    });
+                    return;
+                }
+                tokio::time::sleep(config.poll_interval).await;
+            }
+        });
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bundle_statuses_and_reasons() {
+        let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let inflight = json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "context": { "slot": 100 },
+                "value": [
+                    { "bundle_id": "a", "status": "Landed", "landed_slot": 99 },
+                    { "bundle_id": "b", "status": "Failed", "landed_slot": null },
+                ]
+            },
+            "id": 1
+        });
+        let events = parse_inflight_statuses(&ids, &inflight).unwrap();
+        assert_eq!(events[0].status, BundleStatus::Landed { slot: 99, confirmation_status: None });
+        assert_eq!(events[1].status, BundleStatus::Failed(BundleRejectReason::AuctionLost));
+        assert_eq!(events[2].status, BundleStatus::Failed(BundleRejectReason::NotFound));
+        assert_eq!(events[0].context_slot, Some(100));
+
+        let landed = json!({
+            "result": {
+                "context": { "slot": 101 },
+                "value": [{
+                    "bundle_id": "a",
+                    "transactions": ["sig1"],
+                    "slot": 99,
+                    "confirmation_status": "confirmed",
+                    "err": { "Ok": null }
+                }]
+            }
+        });
+        let events = parse_bundle_statuses(&ids, &landed).unwrap();
+        let event = events[0].as_ref().unwrap();
+        assert_eq!(event.transactions, vec!["sig1".to_string()]);
+        assert!(
+            matches!(&event.status, BundleStatus::Landed { slot: 99, confirmation_status: Some(s) } if s == "confirmed")
+        );
+        assert!(events[1].is_none());
+
+        let error = json!({ "error": { "code": -32602, "message": "bad" } });
+        assert!(parse_inflight_statuses(&ids, &error).is_err());
+
+        assert_eq!(
+            BundleRejectReason::classify("bundle must write lock at least one tip account"),
+            BundleRejectReason::TipTooLow
+        );
+        assert_eq!(
+            BundleRejectReason::classify("Rate limit exceeded"),
+            BundleRejectReason::RateLimited
+        );
+        assert!(!BundleStatus::Pending.is_terminal());
+
+        let mut response = crate::swqos::response::RawResponse {
+            http_status: Some(200),
+            body: r#"{"error":{"code":-32602,"message":"bundle tip below minimum"}}"#.into(),
+            ..Default::default()
+        }
+        .into_response(crate::swqos::SwqosType::Jito, Default::default());
+        assert_eq!(
+            BundleRejectReason::from_response(&response),
+            Some(BundleRejectReason::TipTooLow)
+        );
+        response.error_body = None;
+        assert!(BundleRejectReason::from_response(&response).is_none());
+    }
+}
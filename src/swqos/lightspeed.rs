@@ -1,10 +1,11 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -88,8 +89,7 @@ impl LightspeedClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         // Lightspeed uses standard Solana JSON-RPC format for sendTransaction
         let request_body = serde_json::to_string(&json!({
@@ -99,7 +99,7 @@ impl LightspeedClient {
             "params": [
                 content,
                 {
-                    "encoding": "base64",
+                    "encoding": encoding,
                     "skipPreflight": true,
                     "preflightCommitment": "processed",
                     "maxRetries": 0
@@ -107,15 +107,15 @@ impl LightspeedClient {
             ]
         }))?;
 
-        let response_text = self
-            .http_client
-            .post(&self.endpoint)
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&self.endpoint)
+                .body(request_body)
+                .header("Content-Type", "application/json")
+                .send()
+                .await?,
+        )
+        .await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -2,26 +2,34 @@ pub mod astralane;
 pub mod blockrazor;
 pub mod bloxroute;
 pub mod common;
+pub mod fanout;
 pub mod flashblock;
 pub mod jito;
 pub mod lightspeed;
 pub mod nextblock;
 pub mod node1;
+pub mod region_probe;
+pub mod registry;
+pub mod response;
 pub mod serialization;
 pub mod solana_rpc;
 pub mod soyas;
 pub mod speedlanding;
 pub mod stellium;
 pub mod temporal;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub mod uring_http;
 pub mod zeroslot;
 
 use std::sync::Arc;
 
 use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::VersionedTransaction;
 use tokio::sync::RwLock;
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
 // 为 SwqosRegion 添加转换为 JitoRegion 的方法
 impl SwqosRegion {
@@ -36,6 +44,9 @@ impl SwqosRegion {
             SwqosRegion::Dublin => Some(crate::swqos::jito::JitoRegion::Dublin),
             SwqosRegion::Singapore => Some(crate::swqos::jito::JitoRegion::Singapore),
             SwqosRegion::Default => Some(crate::swqos::jito::JitoRegion::Default),
+            SwqosRegion::Auto => {
+                region_probe::resolve(SwqosType::Jito, SwqosRegion::Auto).to_jito_region()
+            },
         }
     }
 }
@@ -64,11 +75,12 @@ lazy_static::lazy_static! {
 /// SWQOS provider blacklist configuration
 /// Providers added here will be disabled even if configured by user
 /// To enable a provider, remove it from this list
+/// 运行时修改见 [`registry::set_type_blacklisted`]
 pub const SWQOS_BLACKLIST: &[SwqosType] = &[
     SwqosType::NextBlock, // NextBlock is disabled by default
 ];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TradeType {
     Create,
     CreateAndBuy,
@@ -88,7 +100,7 @@ impl std::fmt::Display for TradeType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SwqosType {
     Jito,
     NextBlock,
@@ -128,6 +140,70 @@ impl std::fmt::Display for SwqosType {
 }
 
 impl SwqosType {
+    /// 该 SWQOS 服务的全部小费账户（Default 为空）
+    pub fn tip_accounts(&self) -> &'static [Pubkey] {
+        use crate::constants::swqos::*;
+        match self {
+            SwqosType::Jito => JITO_TIP_ACCOUNTS,
+            SwqosType::NextBlock => NEXTBLOCK_TIP_ACCOUNTS,
+            SwqosType::ZeroSlot => ZEROSLOT_TIP_ACCOUNTS,
+            SwqosType::Temporal => NOZOMI_TIP_ACCOUNTS,
+            SwqosType::Bloxroute => BLOX_TIP_ACCOUNTS,
+            SwqosType::Node1 => NODE1_TIP_ACCOUNTS,
+            SwqosType::FlashBlock => FLASHBLOCK_TIP_ACCOUNTS,
+            SwqosType::BlockRazor => BLOCKRAZOR_TIP_ACCOUNTS,
+            SwqosType::Astralane => ASTRALANE_TIP_ACCOUNTS,
+            SwqosType::Stellium => STELLIUM_TIP_ACCOUNTS,
+            SwqosType::Lightspeed => LIGHTSPEED_TIP_ACCOUNTS,
+            SwqosType::Soyas => SOYAS_TIP_ACCOUNTS,
+            SwqosType::Speedlanding => SPEEDLANDING_TIP_ACCOUNTS,
+            SwqosType::Default => &[],
+        }
+    }
+
+    /// 该 SWQOS 服务要求的最低小费（SOL）
+    pub fn min_tip(&self) -> f64 {
+        use crate::constants::swqos::*;
+        match self {
+            SwqosType::Jito => SWQOS_MIN_TIP_JITO,
+            SwqosType::NextBlock => SWQOS_MIN_TIP_NEXTBLOCK,
+            SwqosType::ZeroSlot => SWQOS_MIN_TIP_ZERO_SLOT,
+            SwqosType::Temporal => SWQOS_MIN_TIP_TEMPORAL,
+            SwqosType::Bloxroute => SWQOS_MIN_TIP_BLOXROUTE,
+            SwqosType::Node1 => SWQOS_MIN_TIP_NODE1,
+            SwqosType::FlashBlock => SWQOS_MIN_TIP_FLASHBLOCK,
+            SwqosType::BlockRazor => SWQOS_MIN_TIP_BLOCKRAZOR,
+            SwqosType::Astralane => SWQOS_MIN_TIP_ASTRALANE,
+            SwqosType::Stellium => SWQOS_MIN_TIP_STELLIUM,
+            SwqosType::Lightspeed => SWQOS_MIN_TIP_LIGHTSPEED,
+            SwqosType::Soyas => SWQOS_MIN_TIP_SOYAS,
+            SwqosType::Speedlanding => SWQOS_MIN_TIP_SPEEDLANDING,
+            SwqosType::Default => SWQOS_MIN_TIP_DEFAULT,
+        }
+    }
+
+    /// 选择 `count` 个小费账户用于拆分小费
+    ///
+    /// `primary` 为客户端 `resolve_tip_account()` 返回的账户，始终排在第一位；
+    /// 其余账户从该服务的小费账户列表中随机选取且互不重复。
+    /// `count` 超过可用账户数时按可用账户数截断。
+    pub fn pick_tip_accounts(&self, primary: Pubkey, count: usize) -> Vec<Pubkey> {
+        use rand::seq::IndexedRandom;
+        let mut accounts = vec![primary];
+        if count <= 1 {
+            return accounts;
+        }
+        let registry = crate::constants::registry::registry();
+        let others: Vec<Pubkey> = registry
+            .tip_accounts(*self)
+            .iter()
+            .filter(|acc| **acc != primary)
+            .copied()
+            .collect();
+        accounts.extend(others.choose_multiple(&mut rand::rng(), count - 1).copied());
+        accounts
+    }
+
     pub fn values() -> Vec<Self> {
         vec![
             Self::Jito,
@@ -165,9 +241,23 @@ pub trait SwqosClientTrait {
     ) -> Result<()>;
     fn get_tip_account(&self) -> Result<String>;
     fn get_swqos_type(&self) -> SwqosType;
+
+    /// 实际使用的小费账户：[`Registry`](crate::constants::registry::Registry) 覆盖了该服务的小费账户时从覆盖列表随机选取，
+    /// 否则为 `get_tip_account()`
+    fn resolve_tip_account(&self) -> Result<String> {
+        use rand::seq::IndexedRandom;
+        let registry = crate::constants::registry::registry();
+        let swqos_type = self.get_swqos_type();
+        if registry.overrides_tip_accounts(swqos_type)
+            && let Some(acc) = registry.tip_accounts(swqos_type).choose(&mut rand::rng())
+        {
+            return Ok(acc.to_string());
+        }
+        self.get_tip_account()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SwqosRegion {
     NewYork,
     Frankfurt,
@@ -177,7 +267,10 @@ pub enum SwqosRegion {
     London,
     Dublin,
     Singapore,
+    #[default]
     Default,
+    /// 探测各区域延迟，自动选择最快的区域（见 [`region_probe`]）
+    Auto,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -234,15 +327,16 @@ impl SwqosConfig {
         }
     }
 
-    /// Check if current config is in the blacklist
+    /// Check if current config is in the (runtime) blacklist
     pub fn is_blacklisted(&self) -> bool {
-        SWQOS_BLACKLIST.contains(&self.swqos_type())
+        registry::is_blacklisted(self.swqos_type())
     }
 
     pub fn get_endpoint(swqos_type: SwqosType, region: SwqosRegion, url: Option<String>) -> String {
         if let Some(custom_url) = url {
             return custom_url;
         }
+        let region = region_probe::resolve(swqos_type, region);
 
         match swqos_type {
             SwqosType::Jito => SWQOS_ENDPOINTS_JITO[region as usize].to_string(),
@@ -1,10 +1,10 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::{poll_transaction_confirmation, post_text};
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -92,8 +92,7 @@ impl NextBlockClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, _, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         let request_body = serde_json::to_string(&json!({
             "transaction": {
@@ -102,16 +101,13 @@ impl NextBlockClient {
             "frontRunningProtection": false
         }))?;
 
-        let response_text = self
-            .http_client
-            .post(&self.endpoint)
-            .body(request_body)
-            .header("Authorization", &self.auth_token)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = post_text(
+            &self.http_client,
+            &self.endpoint,
+            request_body,
+            &[("Authorization", self.auth_token.as_str()), ("Content-Type", "application/json")],
+        )
+        .await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -1,10 +1,11 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -163,8 +164,7 @@ impl Node1Client {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         let request_body = serde_json::to_string(&json!({
             "jsonrpc": "2.0",
@@ -172,21 +172,21 @@ impl Node1Client {
             "method": "sendTransaction",
             "params": [
                 content,
-                { "encoding": "base64", "skipPreflight": true }
+                { "encoding": encoding, "skipPreflight": true }
             ]
         }))?;
 
         // Node1 uses api-key header instead of URL parameter
-        let response_text = self
-            .http_client
-            .post(&self.endpoint)
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .header("api-key", &self.auth_token)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&self.endpoint)
+                .body(request_body)
+                .header("Content-Type", "application/json")
+                .header("api-key", &self.auth_token)
+                .send()
+                .await?,
+        )
+        .await?;
 
         // Parse JSON response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
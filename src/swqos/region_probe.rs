@@ -0,0 +1,248 @@
+//! SWQOS 区域延迟探测与自动选区
+//!
+//! 配置为 [`SwqosRegion::Auto`]（且未指定自定义地址）的 provider，在创建客户端前
+//! 对其各区域地址做 TCP 建连测速（每个地址取 [`PROBE_SAMPLES`] 次中的最小值），
+//! 选择延迟最低的区域。选择结果进程级共享，[`SwqosRegistry`](super::registry::SwqosRegistry)
+//! 每 [`PROBE_INTERVAL`] 重新探测一次，最快区域变化时重建该 provider 的客户端。
+//!
+//! 全部区域都探测失败时保留上一次的选择；从未选出时 Jito 使用全局地址，其余使用 NewYork。
+
+use crate::swqos::registry::ProviderEntry;
+use crate::swqos::{SwqosConfig, SwqosRegion, SwqosType};
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::{TcpStream, lookup_host};
+use tokio::time::{Instant, timeout};
+
+/// 单次建连超时
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// 每个地址的建连次数
+pub const PROBE_SAMPLES: usize = 3;
+/// 定期重新探测间隔
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 参与探测的具名区域
+const CANDIDATE_REGIONS: [SwqosRegion; 8] = [
+    SwqosRegion::NewYork,
+    SwqosRegion::Frankfurt,
+    SwqosRegion::Amsterdam,
+    SwqosRegion::SLC,
+    SwqosRegion::Tokyo,
+    SwqosRegion::London,
+    SwqosRegion::Dublin,
+    SwqosRegion::Singapore,
+];
+
+static SELECTED: Lazy<RwLock<HashMap<SwqosType, SwqosRegion>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 一个区域地址的探测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionLatency {
+    pub region: SwqosRegion,
+    pub endpoint: String,
+    /// 建连耗时；不可达或超时为 None
+    pub latency: Option<Duration>,
+}
+
+/// 该 provider 当前自动选出的区域
+pub fn selected_region(swqos_type: SwqosType) -> Option<SwqosRegion> {
+    SELECTED.read().get(&swqos_type).cloned()
+}
+
+/// 手动指定自动选区的结果（例如已知部署位置时跳过探测）
+pub fn set_selected_region(swqos_type: SwqosType, region: SwqosRegion) {
+    SELECTED.write().insert(swqos_type, region);
+}
+
+/// 把 [`SwqosRegion::Auto`] 解析为具体区域，其余区域原样返回
+pub fn resolve(swqos_type: SwqosType, region: SwqosRegion) -> SwqosRegion {
+    if region != SwqosRegion::Auto {
+        return region;
+    }
+    selected_region(swqos_type).unwrap_or(match swqos_type {
+        SwqosType::Jito => SwqosRegion::Default,
+        _ => SwqosRegion::NewYork,
+    })
+}
+
+/// 需要自动选区的配置（区域为 Auto 且没有自定义地址）
+pub fn needs_probe(config: &SwqosConfig) -> bool {
+    let entry = ProviderEntry::from_config(config);
+    entry.region == SwqosRegion::Auto && entry.url.is_none()
+}
+
+/// 把配置中的 Auto 区域替换为当前选出的区域
+pub fn resolve_config(config: &SwqosConfig) -> SwqosConfig {
+    let mut entry = ProviderEntry::from_config(config);
+    if entry.region != SwqosRegion::Auto {
+        return config.clone();
+    }
+    entry.region = resolve(entry.swqos_type, entry.region);
+    entry.to_config().unwrap_or_else(|_| config.clone())
+}
+
+/// provider 各区域的地址（相同地址只保留第一个区域）
+pub fn candidate_endpoints(swqos_type: SwqosType) -> Vec<(SwqosRegion, String)> {
+    let regions: Vec<SwqosRegion> = match swqos_type {
+        SwqosType::Lightspeed | SwqosType::Default => return vec![],
+        SwqosType::Jito => std::iter::once(SwqosRegion::Default).chain(CANDIDATE_REGIONS).collect(),
+        _ => CANDIDATE_REGIONS.to_vec(),
+    };
+    let mut endpoints: Vec<(SwqosRegion, String)> = vec![];
+    for region in regions {
+        let endpoint = match swqos_type {
+            SwqosType::Jito => match region.to_jito_region() {
+                Some(jito_region) => jito_region.endpoint().to_string(),
+                None => continue,
+            },
+            _ => SwqosConfig::get_endpoint(swqos_type, region.clone(), None),
+        };
+        if !endpoints.iter().any(|(_, e)| *e == endpoint) {
+            endpoints.push((region, endpoint));
+        }
+    }
+    endpoints
+}
+
+/// 从地址中取出 `(host, port)`：http 默认 80，https 与无 scheme 默认 443
+fn host_port(endpoint: &str) -> Option<(String, u16)> {
+    let (rest, default_port) = if let Some(rest) = endpoint.strip_prefix("https://") {
+        (rest, 443)
+    } else if let Some(rest) = endpoint.strip_prefix("http://") {
+        (rest, 80)
+    } else {
+        (endpoint, 443)
+    };
+    let authority = rest.split(['/', '?']).next()?;
+    if authority.is_empty() {
+        return None;
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+        None => Some((authority.to_string(), default_port)),
+    }
+}
+
+/// 测量到地址的 TCP 建连耗时（不含 DNS 解析）
+pub async fn probe_endpoint(endpoint: &str) -> Option<Duration> {
+    let (host, port) = host_port(endpoint)?;
+    let addr = timeout(PROBE_TIMEOUT, lookup_host((host.as_str(), port)))
+        .await
+        .ok()?
+        .ok()?
+        .next()?;
+    let mut best: Option<Duration> = None;
+    for _ in 0..PROBE_SAMPLES {
+        let start = Instant::now();
+        if let Ok(Ok(_stream)) = timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await {
+            let elapsed = start.elapsed();
+            best = Some(best.map_or(elapsed, |b| b.min(elapsed)));
+        }
+    }
+    best
+}
+
+/// 并发探测 provider 的全部区域
+pub async fn probe_regions(swqos_type: SwqosType) -> Vec<RegionLatency> {
+    join_all(
+        candidate_endpoints(swqos_type)
+            .into_iter()
+            .map(|(region, endpoint)| async move {
+                let latency = probe_endpoint(&endpoint).await;
+                RegionLatency { region, endpoint, latency }
+            }),
+    )
+    .await
+}
+
+/// 探测结果中延迟最低的区域
+pub fn fastest(results: &[RegionLatency]) -> Option<SwqosRegion> {
+    results
+        .iter()
+        .filter_map(|r| r.latency.map(|latency| (latency, &r.region)))
+        .min_by_key(|(latency, _)| *latency)
+        .map(|(_, region)| region.clone())
+}
+
+/// 探测并记录 provider 的最快区域；返回选择是否发生变化
+pub async fn probe_and_select(swqos_type: SwqosType) -> bool {
+    let results = probe_regions(swqos_type).await;
+    let Some(region) = fastest(&results) else {
+        log::warn!("SWQOS {:?} region probe failed for all regions", swqos_type);
+        return false;
+    };
+    log::debug!("SWQOS {:?} region probe: {:?}", swqos_type, results);
+    let previous = SELECTED.write().insert(swqos_type, region.clone());
+    if previous.as_ref() != Some(&region) {
+        log::info!("SWQOS {:?} auto region: {:?} -> {:?}", swqos_type, previous, region);
+        return true;
+    }
+    false
+}
+
+/// 为配置中使用 Auto 区域的 provider 选区；`only_missing` 时跳过已有选择的 provider
+///
+/// 返回是否有 provider 的选择发生变化。
+pub async fn probe_auto_regions(configs: &[SwqosConfig], only_missing: bool) -> bool {
+    let mut types: Vec<SwqosType> = vec![];
+    for config in configs.iter().filter(|config| needs_probe(config)) {
+        let swqos_type = config.swqos_type();
+        if (!only_missing || selected_region(swqos_type).is_none()) && !types.contains(&swqos_type)
+        {
+            types.push(swqos_type);
+        }
+    }
+    join_all(types.into_iter().map(probe_and_select))
+        .await
+        .into_iter()
+        .any(|changed| changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_port_and_fastest() {
+        assert_eq!(
+            host_port("https://ny.mainnet.block-engine.jito.wtf"),
+            Some(("ny.mainnet.block-engine.jito.wtf".to_string(), 443))
+        );
+        assert_eq!(
+            host_port("http://newyork.solana.blockrazor.xyz:443/sendTransaction"),
+            Some(("newyork.solana.blockrazor.xyz".to_string(), 443))
+        );
+        assert_eq!(host_port("http://ny.node1.me"), Some(("ny.node1.me".to_string(), 80)));
+        assert_eq!(
+            host_port("nyc.landing.soyas.xyz:9000"),
+            Some(("nyc.landing.soyas.xyz".to_string(), 9000))
+        );
+        assert_eq!(host_port(""), None);
+
+        let result = |region, ms: Option<u64>| RegionLatency {
+            region,
+            endpoint: String::new(),
+            latency: ms.map(Duration::from_millis),
+        };
+        let results = vec![
+            result(SwqosRegion::NewYork, Some(80)),
+            result(SwqosRegion::Frankfurt, Some(3)),
+            result(SwqosRegion::Tokyo, None),
+        ];
+        assert_eq!(fastest(&results), Some(SwqosRegion::Frankfurt));
+        assert_eq!(fastest(&results[2..]), None);
+
+        // 重复地址只探测一次；Auto 未选出时的回退区域
+        let temporal = candidate_endpoints(SwqosType::Temporal);
+        assert!(temporal.len() < CANDIDATE_REGIONS.len());
+        assert_eq!(resolve(SwqosType::Stellium, SwqosRegion::Auto), SwqosRegion::NewYork);
+        assert_eq!(
+            resolve_config(&SwqosConfig::Stellium(String::new(), SwqosRegion::Auto, None)),
+            SwqosConfig::Stellium(String::new(), SwqosRegion::NewYork, None)
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! SWQOS provider 注册表：运行时黑名单与配置热加载
+//!
+//! 黑名单默认取 [`SWQOS_BLACKLIST`]，之后可在运行时修改（进程级共享），
+//! [`SwqosConfig::is_blacklisted`] 读取的是当前黑名单。
+//!
+//! [`SwqosRegistry`] 持有当前生效的 SWQOS 客户端，`TradingClient` 及其克隆共享同一个注册表。
+//! 重新加载时配置未变化的 provider 沿用已有客户端（保留连接），新增或修改的重新创建，
+//! 全部完成后整体替换，进行中的交易继续使用替换前的客户端列表。
+//!
+//! provider 配置文件为 JSON：
+//!
+//! ```json
+//! {
+//!   "providers": [
+//!     { "type": "Jito", "token": "", "region": "Frankfurt" },
+//!     { "type": "Temporal", "token": "api-key", "region": "NewYork" },
+//!     { "type": "Default", "url": "https://api.mainnet-beta.solana.com" }
+//!   ],
+//!   "blacklist": ["NextBlock"]
+//! }
+//! ```
+//!
+//! 省略 `blacklist` 时使用内置黑名单。通过 [`SwqosRegistry::watch_file`]（轮询修改时间）或
+//! [`SwqosRegistry::reload_on_sighup`]（仅 unix）在文件修改后自动重新加载；
+//! 通过 [`SwqosRegistry::set_blacklisted`] 修改的黑名单会写回文件，重启后依然生效。
+
+use crate::swqos::region_probe;
+use crate::swqos::{SWQOS_BLACKLIST, SwqosClient, SwqosConfig, SwqosRegion, SwqosType};
+use anyhow::{Result, anyhow};
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_commitment_config::CommitmentConfig;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+static BLACKLIST: Lazy<ArcSwap<HashSet<SwqosType>>> =
+    Lazy::new(|| ArcSwap::from_pointee(SWQOS_BLACKLIST.iter().copied().collect()));
+
+/// 该 SWQOS 当前是否在黑名单中
+pub fn is_blacklisted(swqos_type: SwqosType) -> bool {
+    BLACKLIST.load().contains(&swqos_type)
+}
+
+/// 当前黑名单
+pub fn blacklist() -> Vec<SwqosType> {
+    let mut types: Vec<_> = BLACKLIST.load().iter().copied().collect();
+    types.sort_by_key(|t| *t as u8);
+    types
+}
+
+/// 整体替换黑名单
+pub fn set_blacklist(types: impl IntoIterator<Item = SwqosType>) {
+    BLACKLIST.store(Arc::new(types.into_iter().collect()));
+}
+
+/// 把单个 SWQOS 加入或移出黑名单（不影响已创建的客户端，见 [`SwqosRegistry::set_blacklisted`]）
+pub fn set_type_blacklisted(swqos_type: SwqosType, blacklisted: bool) {
+    BLACKLIST.rcu(|current| {
+        let mut next = HashSet::clone(current);
+        if blacklisted {
+            next.insert(swqos_type);
+        } else {
+            next.remove(&swqos_type);
+        }
+        next
+    });
+}
+
+/// 配置文件中的一个 provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderEntry {
+    #[serde(rename = "type")]
+    pub swqos_type: SwqosType,
+    /// API token / uuid（Default 不需要）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+    #[serde(default)]
+    pub region: SwqosRegion,
+    /// 自定义地址；Default 为 RPC 地址（必填）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ProviderEntry {
+    pub fn to_config(&self) -> Result<SwqosConfig> {
+        let (token, region, url) = (self.token.clone(), self.region.clone(), self.url.clone());
+        Ok(match self.swqos_type {
+            SwqosType::Default => SwqosConfig::Default(
+                url.ok_or_else(|| anyhow!("Default provider requires \"url\""))?,
+            ),
+            SwqosType::Jito => SwqosConfig::Jito(token, region, url),
+            SwqosType::NextBlock => SwqosConfig::NextBlock(token, region, url),
+            SwqosType::ZeroSlot => SwqosConfig::ZeroSlot(token, region, url),
+            SwqosType::Temporal => SwqosConfig::Temporal(token, region, url),
+            SwqosType::Bloxroute => SwqosConfig::Bloxroute(token, region, url),
+            SwqosType::Node1 => SwqosConfig::Node1(token, region, url),
+            SwqosType::FlashBlock => SwqosConfig::FlashBlock(token, region, url),
+            SwqosType::BlockRazor => SwqosConfig::BlockRazor(token, region, url),
+            SwqosType::Astralane => SwqosConfig::Astralane(token, region, url),
+            SwqosType::Stellium => SwqosConfig::Stellium(token, region, url),
+            SwqosType::Lightspeed => SwqosConfig::Lightspeed(token, region, url),
+            SwqosType::Soyas => SwqosConfig::Soyas(token, region, url),
+            SwqosType::Speedlanding => SwqosConfig::Speedlanding(token, region, url),
+        })
+    }
+
+    pub fn from_config(config: &SwqosConfig) -> Self {
+        let swqos_type = config.swqos_type();
+        let (token, region, url) = match config {
+            SwqosConfig::Default(url) => (String::new(), SwqosRegion::Default, Some(url.clone())),
+            SwqosConfig::Jito(token, region, url)
+            | SwqosConfig::NextBlock(token, region, url)
+            | SwqosConfig::Bloxroute(token, region, url)
+            | SwqosConfig::Temporal(token, region, url)
+            | SwqosConfig::ZeroSlot(token, region, url)
+            | SwqosConfig::Node1(token, region, url)
+            | SwqosConfig::FlashBlock(token, region, url)
+            | SwqosConfig::BlockRazor(token, region, url)
+            | SwqosConfig::Astralane(token, region, url)
+            | SwqosConfig::Stellium(token, region, url)
+            | SwqosConfig::Lightspeed(token, region, url)
+            | SwqosConfig::Soyas(token, region, url)
+            | SwqosConfig::Speedlanding(token, region, url) => {
+                (token.clone(), region.clone(), url.clone())
+            },
+        };
+        Self { swqos_type, token, region, url }
+    }
+}
+
+/// provider 配置文件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderFile {
+    pub providers: Vec<ProviderEntry>,
+    /// 省略时使用内置黑名单
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blacklist: Option<Vec<SwqosType>>,
+}
+
+impl ProviderFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow!("failed to parse {}: {}", path.display(), e))
+    }
+
+    /// 写入文件（先写临时文件再重命名，避免监视方读到一半）
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn configs(&self) -> Result<Vec<SwqosConfig>> {
+        self.providers.iter().map(ProviderEntry::to_config).collect()
+    }
+}
+
+/// 一次重新加载的结果
+#[derive(Debug, Clone, Default)]
+pub struct ReloadReport {
+    /// 重新加载后生效的 provider
+    pub active: Vec<SwqosType>,
+    /// 新创建客户端的 provider
+    pub created: Vec<SwqosType>,
+    /// 因黑名单跳过的 provider
+    pub blacklisted: Vec<SwqosType>,
+    /// 创建失败的 provider 及原因（保留原有客户端，如有）
+    pub failed: Vec<(SwqosType, String)>,
+}
+
+/// 运行时可替换的 SWQOS 客户端集合
+pub struct SwqosRegistry {
+    rpc_url: String,
+    commitment: CommitmentConfig,
+    clients: ArcSwap<Vec<Arc<SwqosClient>>>,
+    state: Mutex<RegistryState>,
+    /// 串行化重新加载
+    reload_lock: tokio::sync::Mutex<()>,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    /// 配置的全部 provider（含黑名单中的）
+    configs: Vec<SwqosConfig>,
+    /// 已创建的客户端，按配置复用
+    built: HashMap<SwqosConfig, Arc<SwqosClient>>,
+    /// 黑名单修改写回的配置文件
+    config_path: Option<PathBuf>,
+}
+
+impl SwqosRegistry {
+    /// 按配置创建客户端；黑名单中的 provider 跳过，创建失败的打印错误后跳过
+    pub async fn new(
+        rpc_url: String,
+        commitment: CommitmentConfig,
+        configs: Vec<SwqosConfig>,
+    ) -> Self {
+        let registry = Self {
+            rpc_url,
+            commitment,
+            clients: ArcSwap::from_pointee(vec![]),
+            state: Mutex::new(RegistryState::default()),
+            reload_lock: tokio::sync::Mutex::new(()),
+        };
+        match registry.reload(configs).await {
+            Ok(report) => {
+                for (swqos_type, error) in &report.failed {
+                    eprintln!("Failed to create SWQOS client {:?}: {}", swqos_type, error);
+                }
+            },
+            Err(e) => eprintln!("{}", e),
+        }
+        registry
+    }
+
+    /// 当前生效的客户端
+    pub fn clients(&self) -> Arc<Vec<Arc<SwqosClient>>> {
+        self.clients.load_full()
+    }
+
+    /// 当前生效的指定类型客户端
+    pub fn get(&self, swqos_type: SwqosType) -> Option<Arc<SwqosClient>> {
+        self.clients.load().iter().find(|c| c.get_swqos_type() == swqos_type).cloned()
+    }
+
+    /// 配置的全部 provider（含黑名单中的）
+    pub fn configs(&self) -> Vec<SwqosConfig> {
+        self.state.lock().configs.clone()
+    }
+
+    /// 按新配置重新加载
+    ///
+    /// 创建失败的 provider 若原配置同类型客户端仍在，继续使用原客户端（例如新 token 写错时不中断发送）。
+    /// 结果没有任何可用客户端而配置非空时返回错误，保留原客户端。
+    pub async fn reload(&self, configs: Vec<SwqosConfig>) -> Result<ReloadReport> {
+        let _guard = self.reload_lock.lock().await;
+        region_probe::probe_auto_regions(&configs, true).await;
+        let built = self.state.lock().built.clone();
+        let current = self.clients();
+
+        let mut report = ReloadReport::default();
+        let mut clients: Vec<Arc<SwqosClient>> = vec![];
+        let mut next_built = HashMap::new();
+        for config in &configs {
+            let swqos_type = config.swqos_type();
+            if config.is_blacklisted() {
+                report.blacklisted.push(swqos_type);
+                continue;
+            }
+            // Auto 区域按当前选区创建，选区变化后重新创建
+            let config = &region_probe::resolve_config(config);
+            let client = match built.get(config) {
+                Some(client) => client.clone(),
+                None => match SwqosConfig::get_swqos_client(
+                    self.rpc_url.clone(),
+                    self.commitment,
+                    config.clone(),
+                )
+                .await
+                {
+                    Ok(client) => {
+                        report.created.push(swqos_type);
+                        client
+                    },
+                    Err(e) => {
+                        report.failed.push((swqos_type, e.to_string()));
+                        match current.iter().find(|c| c.get_swqos_type() == swqos_type) {
+                            Some(client) => client.clone(),
+                            None => continue,
+                        }
+                    },
+                },
+            };
+            if let Some((old, _)) = built.iter().find(|(_, c)| Arc::ptr_eq(c, &client)) {
+                next_built.insert(old.clone(), client.clone());
+            } else {
+                next_built.insert(config.clone(), client.clone());
+            }
+            report.active.push(swqos_type);
+            clients.push(client);
+        }
+
+        let any_configured = configs.iter().any(|c| !c.is_blacklisted());
+        if clients.is_empty() && any_configured {
+            return Err(anyhow!("no SWQOS client could be created: {:?}", report.failed));
+        }
+        {
+            let mut state = self.state.lock();
+            state.configs = configs;
+            state.built = next_built;
+        }
+        self.clients.store(Arc::new(clients));
+        Ok(report)
+    }
+
+    /// 每隔 `interval` 重新探测 Auto 区域，最快区域变化时重建对应客户端
+    pub fn start_region_probe(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let registry = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let configs = registry.configs();
+                if region_probe::probe_auto_regions(&configs, false).await
+                    && let Err(e) = registry.reload(configs).await
+                {
+                    log::error!("SWQOS reload after region change failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// 读取配置文件并重新加载（文件中的黑名单同时生效），之后黑名单修改写回该文件
+    pub async fn reload_from_file(&self, path: impl AsRef<Path>) -> Result<ReloadReport> {
+        let path = path.as_ref();
+        let file = ProviderFile::load(path)?;
+        let configs = file.configs()?;
+        set_blacklist(file.blacklist.unwrap_or_else(|| SWQOS_BLACKLIST.to_vec()));
+        self.state.lock().config_path = Some(path.to_path_buf());
+        self.reload(configs).await
+    }
+
+    /// 加入或移出黑名单并立即生效；已关联配置文件时写回文件
+    pub async fn set_blacklisted(
+        &self,
+        swqos_type: SwqosType,
+        blacklisted: bool,
+    ) -> Result<ReloadReport> {
+        set_type_blacklisted(swqos_type, blacklisted);
+        let report = self.reload(self.configs()).await?;
+        let path = self.state.lock().config_path.clone();
+        if let Some(path) = path {
+            let mut file = ProviderFile::load(&path).unwrap_or_else(|_| ProviderFile {
+                providers: self.configs().iter().map(ProviderEntry::from_config).collect(),
+                blacklist: None,
+            });
+            file.blacklist = Some(blacklist());
+            file.save(&path)?;
+        }
+        Ok(report)
+    }
+
+    /// 轮询配置文件的修改时间，变化后重新加载（启动时立即加载一次）
+    pub fn watch_file(self: &Arc<Self>, path: PathBuf, interval: Duration) -> JoinHandle<()> {
+        let registry = self.clone();
+        tokio::spawn(async move {
+            let mut last_modified: Option<SystemTime> = None;
+            loop {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if modified.is_some() && modified != last_modified {
+                    last_modified = modified;
+                    log_reload(&path, registry.reload_from_file(&path).await);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    /// 收到 SIGHUP 时重新加载配置文件
+    #[cfg(unix)]
+    pub fn reload_on_sighup(self: &Arc<Self>, path: PathBuf) -> Result<JoinHandle<()>> {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut hangup = signal(SignalKind::hangup())?;
+        let registry = self.clone();
+        Ok(tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                log_reload(&path, registry.reload_from_file(&path).await);
+            }
+        }))
+    }
+}
+
+fn log_reload(path: &Path, result: Result<ReloadReport>) {
+    match result {
+        Ok(report) => {
+            for (swqos_type, error) in &report.failed {
+                log::warn!(
+                    "SWQOS {:?} reload failed, keeping previous client: {}",
+                    swqos_type,
+                    error
+                );
+            }
+            log::info!("SWQOS providers reloaded from {}: {:?}", path.display(), report.active);
+        },
+        Err(e) => log::error!("SWQOS reload from {} failed: {}", path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_file_round_trip() {
+        let json = r#"{
+            "providers": [
+                { "type": "Jito", "region": "Frankfurt" },
+                { "type": "Temporal", "token": "key", "region": "NewYork", "url": "https://x" },
+                { "type": "Default", "url": "https://rpc" }
+            ],
+            "blacklist": ["NextBlock", "Soyas"]
+        }"#;
+        let file: ProviderFile = serde_json::from_str(json).unwrap();
+        let configs = file.configs().unwrap();
+        assert_eq!(
+            configs,
+            vec![
+                SwqosConfig::Jito(String::new(), SwqosRegion::Frankfurt, None),
+                SwqosConfig::Temporal(
+                    "key".to_string(),
+                    SwqosRegion::NewYork,
+                    Some("https://x".to_string())
+                ),
+                SwqosConfig::Default("https://rpc".to_string()),
+            ]
+        );
+        let entries: Vec<_> = configs.iter().map(ProviderEntry::from_config).collect();
+        assert_eq!(entries, file.providers);
+        let missing_url = r#"{ "providers": [{ "type": "Default" }] }"#;
+        assert!(serde_json::from_str::<ProviderFile>(missing_url).unwrap().configs().is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! SWQOS 提交响应的原始元数据
+//!
+//! 交易没有上链时，需要用 provider 返回的 bundle ID、中继分配的请求 ID 去对方控制台排查。
+//! provider 在 HTTP 提交后通过 [`read_response`] / [`capture`] 把响应记录到任务局部作用域，
+//! 执行器发送结束后按签名保存为 [`SwqosResponse`]（进程内最近 [`RESPONSE_CAPACITY`] 条），
+//! 通过 [`crate::TradeResult::responses`] 或 [`swqos_responses`] 获取。
+//!
+//! QUIC 等非 HTTP 通道没有响应体，不会产生记录。
+
+use crate::swqos::SwqosType;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use reqwest::header::HeaderMap;
+use solana_sdk::signature::Signature;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::Duration;
+
+/// 保留的最近记录条数
+pub const RESPONSE_CAPACITY: usize = 1024;
+
+/// 保存的响应体最大长度（字节）
+const MAX_BODY_LEN: usize = 4096;
+
+/// 单个 provider 的提交响应
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwqosResponse {
+    pub swqos_type: SwqosType,
+    pub signature: Signature,
+    /// HTTP 状态码（io_uring 通道不可用时为 `None`）
+    pub http_status: Option<u16>,
+    /// bundle ID（Jito `x-bundle-id` 响应头或 `sendBundle` 的返回值）
+    pub bundle_id: Option<String>,
+    /// 中继分配的 ID：`x-request-id` 响应头，或 `result` 中与签名不同的返回值
+    pub relay_id: Option<String>,
+    /// 失败时的响应体（非 2xx 状态，或 JSON 中包含 `error` / `reason`）
+    pub error_body: Option<String>,
+    /// `Retry-After` 响应头（仅支持秒数形式）
+    pub retry_after: Option<Duration>,
+    /// 原始响应体（超过 4KB 时截断）
+    pub body: String,
+}
+
+impl SwqosResponse {
+    pub fn is_error(&self) -> bool {
+        self.error_body.is_some()
+    }
+}
+
+/// provider 作用域内捕获的原始响应
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RawResponse {
+    pub http_status: Option<u16>,
+    pub bundle_id: Option<String>,
+    pub request_id: Option<String>,
+    pub retry_after: Option<Duration>,
+    pub body: String,
+}
+
+impl RawResponse {
+    fn from_headers(status: u16, headers: &HeaderMap, body: String) -> Self {
+        let header = |name: &str| {
+            headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+        };
+        RawResponse {
+            http_status: Some(status),
+            bundle_id: header("x-bundle-id"),
+            request_id: header("x-request-id"),
+            retry_after: header("retry-after")
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs),
+            body,
+        }
+    }
+
+    /// 按签名解析为 [`SwqosResponse`]
+    pub(crate) fn into_response(
+        self,
+        swqos_type: SwqosType,
+        signature: Signature,
+    ) -> SwqosResponse {
+        let json = serde_json::from_str::<serde_json::Value>(&self.body).ok();
+        let result_id = json
+            .as_ref()
+            .and_then(|json| json.get("result"))
+            .and_then(|result| result.as_str())
+            .filter(|result| *result != signature.to_string())
+            .map(str::to_string);
+        let has_error = json
+            .as_ref()
+            .is_some_and(|json| json.get("error").is_some() || json.get("reason").is_some());
+        let status_failed = self.http_status.is_some_and(|status| !(200..300).contains(&status));
+        let mut body = self.body;
+        if body.len() > MAX_BODY_LEN {
+            let mut end = MAX_BODY_LEN;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            body.truncate(end);
+        }
+        SwqosResponse {
+            swqos_type,
+            signature,
+            http_status: self.http_status,
+            bundle_id: self.bundle_id,
+            relay_id: self.request_id.or(result_id),
+            error_body: (status_failed || has_error).then(|| body.clone()),
+            retry_after: self.retry_after,
+            body,
+        }
+    }
+}
+
+tokio::task_local! {
+    static CAPTURED: RefCell<Option<RawResponse>>;
+}
+
+/// 在独立的响应捕获作用域中运行 provider 任务
+pub(crate) fn scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    CAPTURED.scope(RefCell::new(None), future)
+}
+
+/// 记录一次响应（作用域外调用时忽略；多次提交时保留最后一次）
+pub(crate) fn capture(raw: RawResponse) {
+    let _ = CAPTURED.try_with(|cell| *cell.borrow_mut() = Some(raw));
+}
+
+/// 设置最近一次响应的 bundle ID（`sendBundle` 的返回值在响应体中）
+pub(crate) fn set_bundle_id(bundle_id: String) {
+    let _ = CAPTURED.try_with(|cell| {
+        if let Some(raw) = cell.borrow_mut().as_mut() {
+            raw.bundle_id = Some(bundle_id);
+        }
+    });
+}
+
+pub(crate) fn take_captured() -> Option<RawResponse> {
+    CAPTURED.try_with(|cell| cell.borrow_mut().take()).ok().flatten()
+}
+
+/// 读取响应体，同时记录状态码与响应头
+pub(crate) async fn read_response(response: reqwest::Response) -> reqwest::Result<String> {
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let body = response.text().await?;
+    capture(RawResponse::from_headers(status, &headers, body.clone()));
+    Ok(body)
+}
+
+static RESPONSES: Lazy<Mutex<VecDeque<SwqosResponse>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(RESPONSE_CAPACITY)));
+
+pub(crate) fn record(response: SwqosResponse) {
+    let mut responses = RESPONSES.lock();
+    if responses.len() >= RESPONSE_CAPACITY {
+        responses.pop_front();
+    }
+    responses.push_back(response);
+}
+
+/// 按交易签名查询各 provider 的提交响应（签名来自 `buy` / `sell` 的返回值）
+pub fn swqos_responses(signatures: &[Signature]) -> Vec<SwqosResponse> {
+    RESPONSES
+        .lock()
+        .iter()
+        .filter(|response| signatures.contains(&response.signature))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[tokio::test]
+    async fn test_capture_and_parse_response() {
+        let signature = Signature::new_unique();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-bundle-id", HeaderValue::from_static("bundle-1"));
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        let raw = scope(async {
+            capture(RawResponse::from_headers(
+                429,
+                &headers,
+                r#"{"jsonrpc":"2.0","error":{"code":-32097,"message":"rate limited"}}"#.into(),
+            ));
+            take_captured()
+        })
+        .await
+        .unwrap();
+        let response = raw.into_response(SwqosType::Jito, signature);
+        assert_eq!(response.http_status, Some(429));
+        assert_eq!(response.bundle_id.as_deref(), Some("bundle-1"));
+        assert_eq!(response.retry_after, Some(Duration::from_secs(2)));
+        assert!(response.is_error());
+
+        // result 为签名时不视为中继 ID
+        let body = format!(r#"{{"jsonrpc":"2.0","result":"{}"}}"#, signature);
+        let ok = RawResponse::from_headers(200, &HeaderMap::new(), body)
+            .into_response(SwqosType::Jito, signature);
+        assert!(ok.relay_id.is_none() && !ok.is_error());
+        let relayed =
+            RawResponse::from_headers(200, &HeaderMap::new(), r#"{"result":"abc"}"#.into())
+                .into_response(SwqosType::Temporal, signature);
+        assert_eq!(relayed.relay_id.as_deref(), Some("abc"));
+
+        // 作用域外不记录
+        capture(RawResponse::default());
+        assert!(take_captured().is_none());
+        record(response.clone());
+        assert_eq!(swqos_responses(&[signature]), vec![response]);
+    }
+}
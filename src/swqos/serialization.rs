@@ -1,16 +1,25 @@
 //! 交易序列化模块
+//!
+//! provider 发送路径通过 [`TransactionEncoder`] 把已签名交易编码为请求中的字符串。
+//! 默认编码器为 wire 格式（bincode）+ Base64；需要 Base58 或只接受 legacy 消息的 provider
+//! 可通过 [`set_transaction_encoder`] 为对应的 [`SwqosType`] 替换编码器，无需复制发送逻辑。
 
 use crate::perf::{
     compiler_optimization::CompileTimeOptimizedEventProcessor, simd::SIMDSerializer,
 };
+use crate::swqos::SwqosType;
 use anyhow::Result;
+use arc_swap::ArcSwap;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
 use crossbeam_queue::ArrayQueue;
 use once_cell::sync::Lazy;
 use solana_client::rpc_client::SerializableTransaction;
+use solana_sdk::message::VersionedMessage;
 use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
 use solana_transaction_status::UiTransactionEncoding;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// 零分配序列化器 - 使用缓冲池避免运行时分配
@@ -21,10 +30,15 @@ pub struct ZeroAllocSerializer {
 
 impl ZeroAllocSerializer {
     pub fn new(pool_size: usize, buffer_size: usize) -> Self {
+        Self::with_prewarm(pool_size, buffer_size, pool_size)
+    }
+
+    /// 创建序列化器，仅预分配 `prewarm` 个缓冲区（其余按需分配后归还入池）
+    pub fn with_prewarm(pool_size: usize, buffer_size: usize, prewarm: usize) -> Self {
         let pool = ArrayQueue::new(pool_size);
 
         // 预分配缓冲区
-        for _ in 0..pool_size {
+        for _ in 0..prewarm.min(pool_size) {
             let buffer = vec![0; buffer_size];
             let _ = pool.push(buffer);
         }
@@ -64,14 +78,21 @@ impl ZeroAllocSerializer {
     }
 }
 
-/// 全局序列化器实例
-static SERIALIZER: Lazy<Arc<ZeroAllocSerializer>> = Lazy::new(|| {
-    Arc::new(ZeroAllocSerializer::new(
-        10_000,     // 池大小
-        256 * 1024, // 缓冲区大小: 256KB
+/// 全局序列化器实例（池大小与缓冲区大小见 `PerformanceConfig`）
+static SERIALIZER: Lazy<ArcSwap<ZeroAllocSerializer>> = Lazy::new(|| {
+    let config = crate::trading::core::transaction_pool::performance_config();
+    ArcSwap::from_pointee(ZeroAllocSerializer::with_prewarm(
+        config.serializer_pool_size,
+        config.serializer_buffer_size,
+        config.serializer_prewarm,
     ))
 });
 
+/// 按新的容量重建全局序列化缓冲池，由 `reset_pools` 调用
+pub(crate) fn reconfigure_serializer(pool_size: usize, buffer_size: usize, prewarm: usize) {
+    SERIALIZER.store(Arc::new(ZeroAllocSerializer::with_prewarm(pool_size, buffer_size, prewarm)));
+}
+
 /// 🚀 编译时优化的事件处理器 (零运行时开销)
 static COMPILE_TIME_PROCESSOR: CompileTimeOptimizedEventProcessor =
     CompileTimeOptimizedEventProcessor::new();
@@ -98,7 +119,7 @@ impl Base64Encoder {
         value: &T,
         event_type: &str,
     ) -> Result<String> {
-        let serialized = SERIALIZER.serialize_zero_alloc(value, event_type)?;
+        let serialized = SERIALIZER.load().serialize_zero_alloc(value, event_type)?;
         Ok(STANDARD.encode(&serialized))
     }
 }
@@ -111,7 +132,8 @@ pub async fn serialize_transaction(
     let signature = transaction.get_signature();
 
     // 使用零分配序列化
-    let serialized_tx = SERIALIZER.serialize_zero_alloc(transaction, "transaction")?;
+    let serializer = SERIALIZER.load();
+    let serialized_tx = serializer.serialize_zero_alloc(transaction, "transaction")?;
 
     let serialized = match encoding {
         UiTransactionEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
@@ -123,7 +145,7 @@ pub async fn serialize_transaction(
     };
 
     // 立即归还缓冲区到池中
-    SERIALIZER.return_buffer(serialized_tx);
+    serializer.return_buffer(serialized_tx);
 
     Ok((serialized, *signature))
 }
@@ -134,9 +156,10 @@ pub async fn serialize_transactions_batch(
     encoding: UiTransactionEncoding,
 ) -> Result<Vec<String>> {
     let mut results = Vec::with_capacity(transactions.len());
+    let serializer = SERIALIZER.load();
 
     for tx in transactions {
-        let serialized_tx = SERIALIZER.serialize_zero_alloc(tx, "transaction")?;
+        let serialized_tx = serializer.serialize_zero_alloc(tx, "transaction")?;
 
         let encoded = match encoding {
             UiTransactionEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
@@ -144,16 +167,144 @@ pub async fn serialize_transactions_batch(
             _ => return Err(anyhow::anyhow!("Unsupported encoding")),
         };
 
-        SERIALIZER.return_buffer(serialized_tx);
+        serializer.return_buffer(serialized_tx);
         results.push(encoded);
     }
 
     Ok(results)
 }
 
+/// 交易编码器：把已签名交易转换为 provider 请求中的字符串
+pub trait TransactionEncoder: Send + Sync {
+    fn encode(&self, transaction: &VersionedTransaction) -> Result<String>;
+
+    /// 编码结果的文本编码，用于 JSON-RPC 请求中的 `encoding` 参数
+    fn encoding(&self) -> TextEncoding;
+
+    fn encode_batch(&self, transactions: &[VersionedTransaction]) -> Result<Vec<String>> {
+        transactions.iter().map(|tx| self.encode(tx)).collect()
+    }
+}
+
+/// 文本编码
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    Base58,
+    #[default]
+    Base64,
+}
+
+impl TextEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextEncoding::Base58 => "base58",
+            TextEncoding::Base64 => "base64",
+        }
+    }
+}
+
+/// wire 格式（bincode）编码器，使用全局序列化缓冲池
+///
+/// legacy 消息的 wire 格式没有版本前缀，与 `Transaction` 的序列化结果相同；
+/// `legacy_only` 时拒绝 v0 消息，供不支持地址查找表的 provider 使用。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WireEncoder {
+    pub encoding: TextEncoding,
+    pub legacy_only: bool,
+}
+
+impl WireEncoder {
+    pub const BASE64: Self = Self { encoding: TextEncoding::Base64, legacy_only: false };
+    pub const BASE58: Self = Self { encoding: TextEncoding::Base58, legacy_only: false };
+
+    pub fn legacy_only(mut self) -> Self {
+        self.legacy_only = true;
+        self
+    }
+}
+
+impl TransactionEncoder for WireEncoder {
+    fn encode(&self, transaction: &VersionedTransaction) -> Result<String> {
+        if self.legacy_only && !matches!(transaction.message, VersionedMessage::Legacy(_)) {
+            return Err(anyhow::anyhow!("provider only accepts legacy transactions"));
+        }
+        let serializer = SERIALIZER.load();
+        let serialized_tx = serializer.serialize_zero_alloc(transaction, "transaction")?;
+        let encoded = match self.encoding {
+            TextEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
+            TextEncoding::Base64 => STANDARD.encode(&serialized_tx),
+        };
+        serializer.return_buffer(serialized_tx);
+        Ok(encoded)
+    }
+
+    fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+}
+
+static DEFAULT_ENCODER: Lazy<Arc<dyn TransactionEncoder>> =
+    Lazy::new(|| Arc::new(WireEncoder::BASE64));
+
+static ENCODERS: Lazy<ArcSwap<HashMap<SwqosType, Arc<dyn TransactionEncoder>>>> =
+    Lazy::new(|| ArcSwap::from_pointee(HashMap::new()));
+
+/// 为指定 provider 设置交易编码器
+pub fn set_transaction_encoder(swqos_type: SwqosType, encoder: Arc<dyn TransactionEncoder>) {
+    ENCODERS.rcu(|encoders| {
+        let mut encoders = HashMap::clone(encoders);
+        encoders.insert(swqos_type, encoder.clone());
+        encoders
+    });
+}
+
+/// 恢复指定 provider 的默认编码器
+pub fn reset_transaction_encoder(swqos_type: SwqosType) {
+    ENCODERS.rcu(|encoders| {
+        let mut encoders = HashMap::clone(encoders);
+        encoders.remove(&swqos_type);
+        encoders
+    });
+}
+
+/// 指定 provider 使用的编码器（未设置时为 [`WireEncoder::BASE64`]）
+pub fn transaction_encoder(swqos_type: SwqosType) -> Arc<dyn TransactionEncoder> {
+    ENCODERS
+        .load()
+        .get(&swqos_type)
+        .cloned()
+        .unwrap_or_else(|| DEFAULT_ENCODER.clone())
+}
+
+/// 按 provider 的编码器编码交易，同时返回 `encoding` 参数值与签名
+pub(crate) fn encode_for(
+    swqos_type: SwqosType,
+    transaction: &VersionedTransaction,
+) -> Result<(String, &'static str, Signature)> {
+    let encoder = transaction_encoder(swqos_type);
+    let signature = transaction.get_signature();
+    Ok((encoder.encode(transaction)?, encoder.encoding().as_str(), *signature))
+}
+
+/// 从全局缓冲池借出一个缓冲区（池为空时新分配）
+#[inline]
+pub(crate) fn acquire_buffer() -> Vec<u8> {
+    let serializer = SERIALIZER.load();
+    serializer
+        .buffer_pool
+        .pop()
+        .unwrap_or_else(|| Vec::with_capacity(serializer.buffer_size))
+}
+
+/// 归还缓冲区到全局缓冲池
+#[inline]
+pub(crate) fn release_buffer(buffer: Vec<u8>) {
+    SERIALIZER.load().return_buffer(buffer);
+}
+
 /// 获取序列化器统计信息
 pub fn get_serializer_stats() -> (usize, usize) {
-    SERIALIZER.get_pool_stats()
+    SERIALIZER.load().get_pool_stats()
 }
 
 #[cfg(test)]
@@ -171,6 +322,36 @@ mod tests {
         assert_eq!(&decoded[..data.len()], data);
     }
 
+    #[test]
+    fn test_transaction_encoder_override() {
+        use solana_sdk::message::{Message, v0};
+        use solana_sdk::pubkey::Pubkey;
+
+        let payer = Pubkey::new_unique();
+        let legacy = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(Message::new(&[], Some(&payer))),
+        };
+        let wire = bincode::serialize(&legacy).unwrap();
+        let (encoded, encoding, _) = encode_for(SwqosType::Temporal, &legacy).unwrap();
+        assert_eq!(STANDARD.decode(encoded).unwrap(), wire);
+        assert_eq!(encoding, "base64");
+
+        set_transaction_encoder(SwqosType::Temporal, Arc::new(WireEncoder::BASE58.legacy_only()));
+        let (encoded, encoding, _) = encode_for(SwqosType::Temporal, &legacy).unwrap();
+        assert_eq!(bs58::decode(encoded).into_vec().unwrap(), wire);
+        assert_eq!(encoding, "base58");
+        let v0 = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(
+                v0::Message::try_compile(&payer, &[], &[], Default::default()).unwrap(),
+            ),
+        };
+        assert!(encode_for(SwqosType::Temporal, &v0).is_err());
+        reset_transaction_encoder(SwqosType::Temporal);
+        assert!(encode_for(SwqosType::Temporal, &v0).is_ok());
+    }
+
     #[test]
     fn test_serializer_stats() {
         let (available, capacity) = get_serializer_stats();
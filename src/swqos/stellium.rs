@@ -1,11 +1,12 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::poll_transaction_confirmation;
+use crate::swqos::response::read_response;
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -133,8 +134,7 @@ impl StelliumClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         // Stellium uses standard Solana sendTransaction format
         let request_body = serde_json::to_string(&json!({
@@ -143,7 +143,7 @@ impl StelliumClient {
             "method": "sendTransaction",
             "params": [
                 content,
-                { "encoding": "base64" }
+                { "encoding": encoding }
             ]
         }))?;
 
@@ -151,17 +151,17 @@ impl StelliumClient {
         let url = format!("{}/{}", self.endpoint, self.auth_token);
 
         // Send request to Stellium
-        let response_text = self
-            .http_client
-            .post(&url)
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .header("Connection", "keep-alive")
-            .header("Keep-Alive", "timeout=30, max=1000")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = read_response(
+            self.http_client
+                .post(&url)
+                .body(request_body)
+                .header("Content-Type", "application/json")
+                .header("Connection", "keep-alive")
+                .header("Keep-Alive", "timeout=30, max=1000")
+                .send()
+                .await?,
+        )
+        .await?;
 
         // Parse response
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
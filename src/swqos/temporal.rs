@@ -1,9 +1,9 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::{poll_transaction_confirmation, post_text};
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use sha2::{Digest, Sha256};
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 use std::{sync::Arc, time::Instant};
 
@@ -189,8 +189,7 @@ impl TemporalClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         // Build request body according to Nozomi documentation requirements
         let request_body = serde_json::to_string(&json!({
@@ -199,7 +198,7 @@ impl TemporalClient {
             "method": "sendTransaction",
             "params": [
                 content,
-                { "encoding": "base64" }
+                { "encoding": encoding }
             ]
         }))?;
 
@@ -208,15 +207,13 @@ impl TemporalClient {
         url.push_str("/?c=");
         url.push_str(&self.auth_token);
 
-        let response_text = self
-            .http_client
-            .post(&url)
-            .body(request_body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = post_text(
+            &self.http_client,
+            &url,
+            request_body,
+            &[("Content-Type", "application/json")],
+        )
+        .await?;
 
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if response_json.get("result").is_some() {
@@ -0,0 +1,389 @@
+//! io_uring 提交后端（Linux，`io-uring` 特性）
+//!
+//! 明文 HTTP 端点（0slot、Temporal、NextBlock 等）的提交请求按主机交给专用线程：
+//! 每个主机一个线程和一个 ring，线程一次取出队列中所有待发请求，为每个请求压入一个 `Send` SQE，
+//! 通过一次 `submit_and_wait` 完成整批发送（N 个请求一次系统调用），
+//! 随后在保持连接的套接字上读取响应。连接与读取是阻塞的，按主机隔离后
+//! 一个慢速 provider 只会拖慢自己的请求。HTTPS 端点仍走 reqwest。
+
+use anyhow::{Result, anyhow};
+use io_uring::{IoUring, opcode, types};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::os::fd::AsRawFd;
+use std::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// 单批最多提交的请求数
+const MAX_BATCH: usize = 64;
+/// io_uring 队列深度
+const RING_ENTRIES: u32 = 128;
+/// 连接 / 读取超时
+const IO_TIMEOUT: Duration = Duration::from_millis(3000);
+
+/// 解析后的明文 HTTP 地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HttpTarget {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl HttpTarget {
+    pub fn parse(url: &str) -> Option<Self> {
+        let rest = url.strip_prefix("http://")?;
+        let (authority, path) = match rest.find(['/', '?']) {
+            Some(i) if rest.as_bytes()[i] == b'/' => (&rest[..i], rest[i..].to_string()),
+            Some(i) => (&rest[..i], format!("/{}", &rest[i..])),
+            None => (rest, "/".to_string()),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return None;
+        }
+        Some(Self { host: host.to_string(), port, path })
+    }
+
+    fn key(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// 构造 HTTP/1.1 POST 请求
+pub(crate) fn encode_post(target: &HttpTarget, body: &str, headers: &[(&str, &str)]) -> Vec<u8> {
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nConnection: keep-alive\r\nContent-Length: {}\r\n",
+        target.path,
+        target.host,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(name);
+        request.push_str(": ");
+        request.push_str(value);
+        request.push_str("\r\n");
+    }
+    request.push_str("\r\n");
+    let mut bytes = request.into_bytes();
+    bytes.extend_from_slice(body.as_bytes());
+    bytes
+}
+
+/// 解析完整的 HTTP 响应，返回 (状态码, 正文, 是否可复用连接)；数据不完整时返回 `None`
+pub(crate) fn parse_response(buf: &[u8]) -> Option<Result<(u16, String, bool)>> {
+    let header_end = buf.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&buf[..header_end]);
+    let mut lines = head.split("\r\n");
+    let status = match lines.next().and_then(|l| l.split_whitespace().nth(1)).map(str::parse) {
+        Some(Ok(status)) => status,
+        _ => return Some(Err(anyhow!("malformed HTTP status line"))),
+    };
+    let mut content_length = None;
+    let mut chunked = false;
+    let mut keep_alive = true;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.parse::<usize>().ok(),
+            "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+            "connection" => keep_alive = !value.eq_ignore_ascii_case("close"),
+            _ => {},
+        }
+    }
+    let body = &buf[header_end + 4..];
+    let body = if chunked {
+        decode_chunked(body)?
+    } else if let Some(len) = content_length {
+        if body.len() < len {
+            return None;
+        }
+        body[..len].to_vec()
+    } else {
+        // 无长度信息：只能读到连接关闭，之后不可复用
+        keep_alive = false;
+        body.to_vec()
+    };
+    Some(Ok((status, String::from_utf8_lossy(&body).into_owned(), keep_alive)))
+}
+
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let size_str = std::str::from_utf8(&data[..line_end]).ok()?;
+        let size = usize::from_str_radix(size_str.split(';').next()?.trim(), 16).ok()?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Some(body);
+        }
+        if data.len() < size + 2 {
+            return None;
+        }
+        body.extend_from_slice(&data[..size]);
+        data = &data[size + 2..];
+    }
+}
+
+struct Request {
+    target: HttpTarget,
+    bytes: Vec<u8>,
+    reply: oneshot::Sender<Result<String>>,
+}
+
+/// io_uring 提交线程句柄（每个主机一个线程，首次请求时启动）
+pub struct UringHttpSender {
+    workers: Mutex<HashMap<String, mpsc::Sender<Request>>>,
+}
+
+impl UringHttpSender {
+    fn start() -> Result<Self> {
+        // 探测内核是否支持 io_uring，不支持时由调用方回退到 reqwest
+        IoUring::new(RING_ENTRIES)?;
+        Ok(Self { workers: Mutex::new(HashMap::new()) })
+    }
+
+    fn worker(&self, key: &str) -> Result<mpsc::Sender<Request>> {
+        let mut workers = self.workers.lock();
+        if let Some(tx) = workers.get(key) {
+            return Ok(tx.clone());
+        }
+        let ring = IoUring::new(RING_ENTRIES)?;
+        let (tx, rx) = mpsc::channel::<Request>();
+        std::thread::Builder::new()
+            .name(format!("sol-trade-uring-{}", key))
+            .spawn(move || run(ring, rx))?;
+        workers.insert(key.to_string(), tx.clone());
+        Ok(tx)
+    }
+
+    /// 提交 POST 请求并返回响应正文
+    pub async fn post(&self, url: &str, body: &str, headers: &[(&str, &str)]) -> Result<String> {
+        let target =
+            HttpTarget::parse(url).ok_or_else(|| anyhow!("not a plain http url: {}", url))?;
+        let key = target.key();
+        let bytes = encode_post(&target, body, headers);
+        let (reply, rx) = oneshot::channel();
+        if self.worker(&key)?.send(Request { target, bytes, reply }).is_err() {
+            // 线程已退出：移除后由下一次请求重新启动
+            self.workers.lock().remove(&key);
+            return Err(anyhow!("io_uring sender thread for {} has stopped", key));
+        }
+        rx.await.map_err(|_| anyhow!("io_uring sender dropped the request"))?
+    }
+}
+
+static SENDER: Lazy<Option<UringHttpSender>> = Lazy::new(|| match UringHttpSender::start() {
+    Ok(sender) => Some(sender),
+    Err(e) => {
+        eprintln!("⚠️  io_uring unavailable, falling back to reqwest: {}", e);
+        None
+    },
+});
+
+/// 全局提交后端（内核不支持 io_uring 时为 `None`）
+pub fn uring_sender() -> Option<&'static UringHttpSender> {
+    SENDER.as_ref()
+}
+
+fn connect(target: &HttpTarget) -> Result<TcpStream> {
+    let addr = (target.host.as_str(), target.port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("cannot resolve {}", target.host))?;
+    let stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT)?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    Ok(stream)
+}
+
+fn read_response(stream: &mut TcpStream) -> Result<(u16, String, bool)> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            // 连接关闭：按已收到的数据解析（无长度信息的响应以此结束）
+            return match parse_response(&buf) {
+                Some(result) => result.map(|(status, body, _)| (status, body, false)),
+                None => Err(anyhow!("connection closed before response completed")),
+            };
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(result) = parse_response(&buf)
+            && !matches!(result, Ok((_, _, false)))
+        {
+            return result;
+        }
+    }
+}
+
+/// 单个主机的提交循环
+fn run(mut ring: IoUring, rx: mpsc::Receiver<Request>) {
+    let mut idle: HashMap<String, Vec<TcpStream>> = HashMap::new();
+    while let Ok(first) = rx.recv() {
+        let mut batch = vec![first];
+        while batch.len() < MAX_BATCH
+            && let Ok(request) = rx.try_recv()
+        {
+            batch.push(request);
+        }
+
+        // 为每个请求准备连接（同一批内同一主机的请求各占一条连接）
+        let mut pending = Vec::with_capacity(batch.len());
+        for request in batch {
+            let stream = match idle.get_mut(&request.target.key()).and_then(Vec::pop) {
+                Some(stream) => Ok((stream, true)),
+                None => connect(&request.target).map(|stream| (stream, false)),
+            };
+            match stream {
+                Ok((stream, reused)) => pending.push((request, stream, reused)),
+                Err(e) => {
+                    let _ = request.reply.send(Err(e));
+                },
+            }
+        }
+        if pending.is_empty() {
+            continue;
+        }
+
+        // 整批 Send 一次提交
+        let mut sent: Vec<Result<usize>> =
+            (0..pending.len()).map(|_| Err(anyhow!("send not completed"))).collect();
+        let mut queued = 0;
+        {
+            let mut submission = ring.submission();
+            for (i, (request, stream, _)) in pending.iter().enumerate() {
+                let entry = opcode::Send::new(
+                    types::Fd(stream.as_raw_fd()),
+                    request.bytes.as_ptr(),
+                    request.bytes.len() as u32,
+                )
+                .build()
+                .user_data(i as u64);
+                // SAFETY: 请求缓冲区与套接字在 submit_and_wait 返回前保持有效
+                if unsafe { submission.push(&entry) }.is_ok() {
+                    queued += 1;
+                } else {
+                    sent[i] = Err(anyhow!("io_uring submission queue full"));
+                }
+            }
+        }
+        match ring.submit_and_wait(queued) {
+            Ok(_) => {
+                for cqe in ring.completion() {
+                    let i = cqe.user_data() as usize;
+                    sent[i] = if cqe.result() >= 0 {
+                        Ok(cqe.result() as usize)
+                    } else {
+                        Err(std::io::Error::from_raw_os_error(-cqe.result()).into())
+                    };
+                }
+            },
+            Err(e) => {
+                let message = e.to_string();
+                sent.iter_mut().for_each(|r| *r = Err(anyhow!("io_uring submit: {}", message)));
+            },
+        }
+
+        // 读取响应；未发完的部分以阻塞写补齐
+        for ((request, mut stream, reused), sent) in pending.into_iter().zip(sent) {
+            let mut result = sent.and_then(|n| {
+                if n < request.bytes.len() {
+                    stream.write_all(&request.bytes[n..])?;
+                }
+                read_response(&mut stream)
+            });
+            // 复用的空闲连接可能已被服务端关闭：重新连接后阻塞重发一次
+            if result.is_err() && reused {
+                result = connect(&request.target).and_then(|fresh| {
+                    stream = fresh;
+                    stream.write_all(&request.bytes)?;
+                    read_response(&mut stream)
+                });
+            }
+            let result = result.map(|(status, body, keep_alive)| {
+                if keep_alive {
+                    idle.entry(request.target.key()).or_default().push(stream);
+                }
+                (status, body)
+            });
+            let reply = match result {
+                Ok((status, body)) if (200..300).contains(&status) || !body.is_empty() => Ok(body),
+                Ok((status, _)) => Err(anyhow!("HTTP {}", status)),
+                Err(e) => Err(e),
+            };
+            let _ = request.reply.send(reply);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target_and_response() {
+        let target = HttpTarget::parse("http://ny.0slot.trade/?api-key=abc").unwrap();
+        assert_eq!(target.host, "ny.0slot.trade");
+        assert_eq!(target.port, 80);
+        assert_eq!(target.path, "/?api-key=abc");
+        assert_eq!(HttpTarget::parse("http://127.0.0.1:8080").unwrap().path, "/");
+        assert!(HttpTarget::parse("https://example.com").is_none());
+
+        let request = encode_post(&target, "{}", &[("Content-Type", "application/json")]);
+        assert!(request.starts_with(b"POST /?api-key=abc HTTP/1.1\r\nHost: ny.0slot.trade\r\n"));
+        assert!(request.ends_with(b"Content-Type: application/json\r\n\r\n{}"));
+
+        let partial = b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n{\"a\"";
+        assert!(parse_response(partial).is_none());
+        let full = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}";
+        assert_eq!(parse_response(full).unwrap().unwrap(), (200, "{}".to_string(), true));
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\n{}\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked).unwrap().unwrap().1, "{}");
+    }
+
+    #[tokio::test]
+    async fn test_stalled_endpoint_does_not_block_other_hosts() {
+        use std::net::TcpListener;
+        use std::time::Instant;
+
+        let Some(sender) = uring_sender() else {
+            return; // 内核不支持 io_uring
+        };
+
+        // 接受连接但从不响应
+        let stalled = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stalled_url = format!("http://{}/", stalled.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let _held: Vec<_> = stalled.incoming().collect();
+        });
+        // 立即响应
+        let fast = TcpListener::bind("127.0.0.1:0").unwrap();
+        let fast_url = format!("http://{}/", fast.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in fast.incoming().flatten() {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}");
+            }
+        });
+
+        let stalled_request =
+            tokio::spawn(async move { sender.post(&stalled_url, "{}", &[]).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let start = Instant::now();
+        assert_eq!(sender.post(&fast_url, "{}", &[]).await.unwrap(), "{}");
+        assert!(start.elapsed() < IO_TIMEOUT / 2);
+        assert!(stalled_request.await.unwrap().is_err());
+    }
+}
@@ -1,10 +1,10 @@
-use crate::swqos::common::{poll_transaction_confirmation, serialize_transaction_and_encode};
+use crate::swqos::common::{poll_transaction_confirmation, post_text};
+use crate::swqos::serialization::encode_for;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use serde_json::json;
 use std::{sync::Arc, time::Instant};
 
-use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 use crate::swqos::SwqosClientTrait;
@@ -86,8 +86,7 @@ impl ZeroSlotClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
-        let (content, signature) =
-            serialize_transaction_and_encode(transaction, UiTransactionEncoding::Base64).await?;
+        let (content, encoding, signature) = encode_for(self.get_swqos_type(), transaction)?;
 
         let request_body = serde_json::to_string(&json!({
             "jsonrpc": "2.0",
@@ -95,7 +94,7 @@ impl ZeroSlotClient {
             "method": "sendTransaction",
             "params": [
                 content,
-                { "encoding": "base64", "skipPreflight": true }
+                { "encoding": encoding, "skipPreflight": true }
             ]
         }))?;
 
@@ -105,15 +104,13 @@ impl ZeroSlotClient {
         url.push_str(&self.auth_token);
 
         // 4. Use `text().await?` directly, avoiding async JSON parsing from `json().await?`
-        let response_text = self
-            .http_client
-            .post(&url)
-            .body(request_body) // Pass string directly, avoiding `json()` overhead
-            .header("Content-Type", "application/json") // Explicitly specify JSON header
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response_text = post_text(
+            &self.http_client,
+            &url,
+            request_body, // Pass string directly, avoiding `json()` overhead
+            &[("Content-Type", "application/json")], // Explicitly specify JSON header
+        )
+        .await?;
 
         // 5. Use `serde_json::from_str()` to parse JSON, reducing extra wait from `.json().await?`
         if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
@@ -0,0 +1,440 @@
+//! 同一代币跨平台套利检测
+//!
+//! [`scan_arbitrage`] 在给定的池子之间计算“在 A 买入、在 B 卖出”的往返收益：
+//!
+//! - 各池报价使用协议的 `quote_exact_in`（PumpSwap、Raydium CPMM / CLMM、Meteora DAMM V2）；
+//! - 买入一侧先并发报价，再按得到的代币数量并发报价卖出一侧；
+//! - 收益扣除 [`ArbConfig::cost_lamports`]（优先费、小费与签名费，可用
+//!   [`estimate_bundle_cost_lamports`] 按 gas 策略估算），低于 `min_profit` 的机会被丢弃。
+//!
+//! [`TradingClient::execute_arbitrage`] 把两腿作为 Jito bundle 发送（买入一笔、卖出一笔，
+//! 小费只在卖出交易中支付），两腿要么同时上链要么都不上链。
+//!
+//! [`ArbConfig::with_cancellation_token`] 可在信号失效时中止扫描（返回 `TradeError::CANCELLED`）。
+//!
+//! 仅支持以 SOL / WSOL 计价的池子；报价基于读取时的链上状态，CLMM 报价不跨 tick。
+
+use crate::common::cancellation::{CancellationToken, cancellable};
+use crate::common::{Bps, GasFeeStrategy, SolanaRpcClient};
+use crate::instruction::utils::{meteora_damm_v2, pumpswap, raydium_clmm, raydium_cpmm};
+use crate::swqos::common::TradeError;
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::TradeFactory;
+use crate::trading::basket::collect_results;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use crate::{TradeBuyParams, TradeSellParams, TradeTokenType, TradingClient};
+use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use solana_hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+/// 每笔交易的签名费（lamports）
+const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// 参与套利的池子
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbVenue {
+    pub dex_type: DexType,
+    pub pool: Pubkey,
+}
+
+impl ArbVenue {
+    pub fn new(dex_type: DexType, pool: Pubkey) -> Self {
+        Self { dex_type, pool }
+    }
+}
+
+/// 套利扫描配置（数量均为 lamports）
+#[derive(Debug, Clone)]
+pub struct ArbConfig {
+    /// 买入一侧投入的 SOL
+    pub amount_in: u64,
+    /// 最小净收益
+    pub min_profit: u64,
+    /// 执行成本（优先费、小费、签名费）
+    pub cost_lamports: u64,
+    /// 取消令牌：取消后停止报价并返回 `TradeError::CANCELLED`
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl ArbConfig {
+    pub fn new(amount_in: u64) -> Self {
+        Self {
+            amount_in,
+            min_profit: 0,
+            cost_lamports: 0,
+            cancellation_token: None,
+        }
+    }
+
+    pub fn with_min_profit(mut self, min_profit: u64) -> Self {
+        self.min_profit = min_profit;
+        self
+    }
+
+    pub fn with_cost_lamports(mut self, cost_lamports: u64) -> Self {
+        self.cost_lamports = cost_lamports;
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+}
+
+/// 一个往返套利机会
+#[derive(Debug, Clone)]
+pub struct ArbOpportunity {
+    pub mint: Pubkey,
+    pub buy_venue: ArbVenue,
+    pub sell_venue: ArbVenue,
+    pub amount_in: u64,
+    /// 买入得到的代币数量（报价）
+    pub token_amount: u64,
+    /// 卖出得到的 SOL（报价）
+    pub amount_out: u64,
+    pub cost_lamports: u64,
+    /// 净收益：amount_out - amount_in - cost_lamports
+    pub profit: i128,
+}
+
+impl ArbOpportunity {
+    /// 净收益率（基点）
+    pub fn profit_bps(&self) -> i128 {
+        if self.amount_in == 0 {
+            return 0;
+        }
+        self.profit * 10_000 / self.amount_in as i128
+    }
+}
+
+/// 按 gas 策略估算 bundle 成本：两笔交易的 Jito 优先费与签名费，加上卖出交易的小费
+pub fn estimate_bundle_cost_lamports(gas_fee_strategy: &GasFeeStrategy) -> u64 {
+    let jito = |trade_type: TradeType| {
+        gas_fee_strategy
+            .get_strategies(trade_type)
+            .into_iter()
+            .find(|(swqos_type, _, _)| *swqos_type == SwqosType::Jito)
+            .map(|(_, _, gas)| gas)
+    };
+    let priority_fee = |cu_limit: u32, cu_price: u64| cu_limit as u64 * cu_price / 1_000_000;
+    let mut cost = 2 * SIGNATURE_FEE_LAMPORTS;
+    if let Some(gas) = jito(TradeType::Buy) {
+        cost += priority_fee(gas.cu_limit, gas.cu_price);
+    }
+    if let Some(gas) = jito(TradeType::Sell) {
+        cost += priority_fee(gas.cu_limit, gas.cu_price);
+        cost += (gas.tip * 1_000_000_000.0).round() as u64;
+    }
+    cost
+}
+
+/// 在单个池子上报价：`is_buy` 为 true 时输入 SOL 得到 `mint`，否则输入 `mint` 得到 SOL
+pub async fn quote_venue(
+    rpc: &SolanaRpcClient,
+    venue: &ArbVenue,
+    mint: &Pubkey,
+    amount_in: u64,
+    is_buy: bool,
+) -> Result<u64> {
+    // 输入侧是否为池子的第一个代币（token0 / token A / base）
+    let first_in = |first_mint: &Pubkey| (first_mint == mint) != is_buy;
+    let quote = match venue.dex_type {
+        DexType::PumpSwap => {
+            let pool = pumpswap::get_pool_by_address(rpc, &venue.pool).await?;
+            if pool.base_mint != *mint {
+                return Err(anyhow!("PumpSwap pool {} does not have {} as base", venue.pool, mint));
+            }
+            pumpswap::quote_exact_in(rpc, &venue.pool, amount_in, !is_buy).await?
+        },
+        DexType::RaydiumCpmm => {
+            let pool = raydium_cpmm::get_pool_by_address(rpc, &venue.pool).await?;
+            let is_token0_in = first_in(&pool.token0_mint);
+            raydium_cpmm::quote_exact_in(rpc, &venue.pool, amount_in, is_token0_in).await?
+        },
+        DexType::RaydiumClmm => {
+            let pool = raydium_clmm::get_pool_by_address(rpc, &venue.pool).await?;
+            let zero_for_one = first_in(&pool.token_mint0);
+            raydium_clmm::quote_exact_in(rpc, &venue.pool, amount_in, zero_for_one).await?
+        },
+        DexType::MeteoraDammV2 => {
+            let pool = meteora_damm_v2::get_pool_by_address(rpc, &venue.pool).await?;
+            let a_to_b = first_in(&pool.token_a_mint);
+            meteora_damm_v2::quote_exact_in(rpc, &venue.pool, amount_in, a_to_b).await?
+        },
+        _ => return Err(anyhow!("{:?} is not supported for arbitrage", venue.dex_type)),
+    };
+    Ok(quote.amount_out)
+}
+
+/// 由两侧报价计算机会
+pub fn evaluate_round_trip(
+    mint: Pubkey,
+    buy_venue: &ArbVenue,
+    sell_venue: &ArbVenue,
+    token_amount: u64,
+    amount_out: u64,
+    config: &ArbConfig,
+) -> ArbOpportunity {
+    ArbOpportunity {
+        mint,
+        buy_venue: buy_venue.clone(),
+        sell_venue: sell_venue.clone(),
+        amount_in: config.amount_in,
+        token_amount,
+        amount_out,
+        cost_lamports: config.cost_lamports,
+        profit: amount_out as i128 - config.amount_in as i128 - config.cost_lamports as i128,
+    }
+}
+
+/// 扫描 `venues` 之间所有“买入池 → 卖出池”的组合，返回净收益不低于 `min_profit` 的机会（按收益降序）
+///
+/// 报价失败的池子会被跳过。
+pub async fn scan_arbitrage(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    venues: &[ArbVenue],
+    config: &ArbConfig,
+) -> Result<Vec<ArbOpportunity>> {
+    if venues.len() < 2 {
+        return Err(anyhow!("Arbitrage needs at least two venues"));
+    }
+    let token = config.cancellation_token.as_ref();
+    let cancelled = || anyhow!(TradeError::cancelled("arbitrage scan cancelled"));
+    let buys = cancellable(
+        token,
+        join_all(venues.iter().map(|venue| quote_venue(rpc, venue, mint, config.amount_in, true))),
+    )
+    .await
+    .ok_or_else(cancelled)?;
+
+    let mut legs = Vec::new();
+    for (buy_index, buy) in buys.into_iter().enumerate() {
+        match buy {
+            Ok(token_amount) if token_amount > 0 => {
+                for sell_index in (0..venues.len()).filter(|i| *i != buy_index) {
+                    legs.push((buy_index, sell_index, token_amount));
+                }
+            },
+            Ok(_) => {},
+            Err(e) => log::debug!("arbitrage: buy quote on {:?} failed: {}", venues[buy_index], e),
+        }
+    }
+    let sells = cancellable(
+        token,
+        join_all(legs.iter().map(|(_, sell_index, token_amount)| {
+            quote_venue(rpc, &venues[*sell_index], mint, *token_amount, false)
+        })),
+    )
+    .await
+    .ok_or_else(cancelled)?;
+
+    let mut opportunities: Vec<ArbOpportunity> = legs
+        .into_iter()
+        .zip(sells)
+        .filter_map(|((buy_index, sell_index, token_amount), sell)| match sell {
+            Ok(amount_out) => Some(evaluate_round_trip(
+                *mint,
+                &venues[buy_index],
+                &venues[sell_index],
+                token_amount,
+                amount_out,
+                config,
+            )),
+            Err(e) => {
+                log::debug!("arbitrage: sell quote on {:?} failed: {}", venues[sell_index], e);
+                None
+            },
+        })
+        .filter(|opportunity| opportunity.profit >= config.min_profit as i128)
+        .collect();
+    opportunities.sort_by(|a, b| b.profit.cmp(&a.profit));
+    Ok(opportunities)
+}
+
+impl TradingClient {
+    /// 扫描套利机会（见 [`scan_arbitrage`]）
+    pub async fn scan_arbitrage(
+        &self,
+        mint: &Pubkey,
+        venues: &[ArbVenue],
+        config: &ArbConfig,
+    ) -> Result<Vec<ArbOpportunity>> {
+        scan_arbitrage(&self.rpc, mint, venues, config).await
+    }
+
+    /// 以 Jito bundle 执行套利的两腿
+    ///
+    /// 卖出数量为买入报价按 `slippage_basis_points` 下调后的值，保证即使买入成交偏低也有足够余额；
+    /// 两腿的最小输出同样按该滑点计算。
+    ///
+    /// # Returns
+    /// Returns `(success, signatures, last_error)` in the same shape as [`TradingClient::buy`]
+    pub async fn execute_arbitrage(
+        &self,
+        opportunity: &ArbOpportunity,
+        gas_fee_strategy: GasFeeStrategy,
+        slippage_basis_points: Bps,
+        recent_blockhash: Option<Hash>,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        let jito_clients: Vec<_> = self
+            .swqos_clients()
+            .iter()
+            .filter(|c| c.get_swqos_type() == SwqosType::Jito)
+            .cloned()
+            .collect();
+        if jito_clients.is_empty() {
+            return Err(anyhow!("Arbitrage bundles require a Jito SWQOS client"));
+        }
+
+        let (buy_params, sell_params) = tokio::try_join!(
+            DexParamEnum::from_pool_force(
+                &self.rpc,
+                opportunity.buy_venue.dex_type.clone(),
+                &opportunity.buy_venue.pool
+            ),
+            DexParamEnum::from_pool_force(
+                &self.rpc,
+                opportunity.sell_venue.dex_type.clone(),
+                &opportunity.sell_venue.pool
+            ),
+        )?;
+        let blockhash = match recent_blockhash {
+            Some(hash) => hash,
+            None => self.rpc.get_latest_blockhash().await?,
+        };
+        let sell_amount = slippage_basis_points.apply_down(opportunity.token_amount);
+        if sell_amount == 0 {
+            return Err(anyhow!("Arbitrage sell amount is zero"));
+        }
+
+        let buy = TradeBuyParams {
+            dex_type: opportunity.buy_venue.dex_type.clone(),
+            input_token_type: TradeTokenType::SOL,
+            mint: opportunity.mint,
+            input_token_amount: opportunity.amount_in,
+            slippage_basis_points: Some(slippage_basis_points),
+            recent_blockhash: Some(blockhash),
+            last_valid_block_height: None,
+            extension_params: buy_params,
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: false,
+            // WSOL ATA 留给卖出一腿，由卖出交易关闭
+            create_input_token_ata: true,
+            close_input_token_ata: false,
+            create_mint_ata: true,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy: gas_fee_strategy.clone(),
+            simulate: false,
+            on_transaction_signed: None,
+            callback_execution_mode: None,
+            enable_jito_sandwich_protection: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
+        };
+        let sell = TradeSellParams {
+            dex_type: opportunity.sell_venue.dex_type.clone(),
+            output_token_type: TradeTokenType::WSOL,
+            mint: opportunity.mint,
+            input_token_amount: sell_amount,
+            slippage_basis_points: Some(slippage_basis_points),
+            recent_blockhash: Some(blockhash),
+            last_valid_block_height: None,
+            with_tip: true,
+            extension_params: sell_params,
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: false,
+            create_output_token_ata: true,
+            close_output_token_ata: true,
+            close_mint_token_ata: false,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy: gas_fee_strategy.clone(),
+            simulate: false,
+            on_transaction_signed: None,
+            callback_execution_mode: None,
+            enable_jito_sandwich_protection: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
+        };
+        let buy_instructions = TradeFactory::create_executor(buy.dex_type.clone())
+            .build_instructions(&self.build_buy_swap_params(buy)?)
+            .await?;
+        let sell_instructions = TradeFactory::create_executor(sell.dex_type.clone())
+            .build_instructions(&self.build_sell_swap_params(sell)?)
+            .await?;
+
+        let mut results = Vec::new();
+        for swqos_client in jito_clients {
+            let transactions = vec![
+                self.assemble_multi_leg_transaction(
+                    &gas_fee_strategy,
+                    TradeType::Buy,
+                    None,
+                    &swqos_client,
+                    buy_instructions.clone(),
+                    1,
+                    false,
+                    blockhash,
+                )?,
+                self.assemble_multi_leg_transaction(
+                    &gas_fee_strategy,
+                    TradeType::Sell,
+                    None,
+                    &swqos_client,
+                    sell_instructions.clone(),
+                    1,
+                    true,
+                    blockhash,
+                )?,
+            ];
+            let result = swqos_client.send_transactions(TradeType::Sell, &transactions, true).await;
+            for transaction in &transactions {
+                let result = match &result {
+                    Ok(()) => Ok(()),
+                    Err(e) => Err(anyhow!("{}", e)),
+                };
+                results.push((transaction.signatures[0], result));
+            }
+        }
+        Ok(collect_results(results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_profit_after_costs() {
+        let mint = Pubkey::new_unique();
+        let a = ArbVenue::new(DexType::PumpSwap, Pubkey::new_unique());
+        let b = ArbVenue::new(DexType::RaydiumCpmm, Pubkey::new_unique());
+        let config = ArbConfig::new(1_000_000_000).with_cost_lamports(20_000);
+        let opportunity = evaluate_round_trip(mint, &a, &b, 5_000, 1_010_000_000, &config);
+        assert_eq!(opportunity.profit, 9_980_000);
+        assert_eq!(opportunity.profit_bps(), 99);
+        let losing = evaluate_round_trip(mint, &b, &a, 5_000, 995_000_000, &config);
+        assert!(losing.profit < 0);
+        assert_eq!(estimate_bundle_cost_lamports(&GasFeeStrategy::new()), 10_000);
+    }
+}
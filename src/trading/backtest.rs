@@ -0,0 +1,353 @@
+//! 历史回测（`backtest` feature）
+//!
+//! 按时间记录池子快照（储备、协议），回放策略的买入/卖出时在下单 slot 之前最近的快照上
+//! 运行与指令构建相同的报价计算，得到模拟的预期输出与最小输出；再与解析器还原的链上实际成交
+//! （[`ResolvedFill`]）对比偏差。
+//!
+//! 快照通过 [`PoolSnapshot::capture`] 采集，接受任意 [`PoolRpcClient`]：使用
+//! [`crate::common::auto_mock_rpc::AutoMockRpcClient`] 时，采集过程中的 RPC 响应会同时被录制，
+//! 之后可离线重放。快照序列可保存为 JSONL 文件。
+//!
+//! 目前支持 PumpSwap、Raydium CPMM 与 Raydium AMM V4（恒定乘积池）。
+
+use crate::common::Bps;
+use crate::common::auto_mock_rpc::PoolRpcClient;
+use crate::instruction::utils::{pumpswap, raydium_amm_v4, raydium_cpmm};
+use crate::trading::fill::ResolvedFill;
+use crate::trading::lifecycle::TradeQuote;
+use crate::utils::calc;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+/// 可回测的池子协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotDex {
+    PumpSwap,
+    RaydiumCpmm,
+    RaydiumAmmV4,
+}
+
+/// 回测订单方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BacktestSide {
+    /// quote -> base
+    Buy,
+    /// base -> quote
+    Sell,
+}
+
+/// 某一时刻的池子状态
+///
+/// CPMM 的 base / quote 对应 token0 / token1，AMM V4 对应 coin / pc。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolSnapshot {
+    pub slot: u64,
+    /// Unix 时间戳（秒）
+    pub timestamp: i64,
+    pub dex: SnapshotDex,
+    pub pool: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+    /// PumpSwap 池的 coin creator（决定是否收取创作者费；其他协议为默认值）
+    pub coin_creator: Pubkey,
+}
+
+impl PoolSnapshot {
+    /// 读取池子当前状态作为 `slot` / `timestamp` 时刻的快照
+    pub async fn capture<T: PoolRpcClient + ?Sized>(
+        rpc: &T,
+        dex: SnapshotDex,
+        pool: Pubkey,
+        slot: u64,
+        timestamp: i64,
+    ) -> Result<Self> {
+        let snapshot = |base_mint, quote_mint, (base_reserve, quote_reserve), coin_creator| Self {
+            slot,
+            timestamp,
+            dex,
+            pool,
+            base_mint,
+            quote_mint,
+            base_reserve,
+            quote_reserve,
+            coin_creator,
+        };
+        match dex {
+            SnapshotDex::PumpSwap => {
+                let state = pumpswap::get_pool_by_address(rpc, &pool).await?;
+                let reserves = pumpswap::get_token_balances(&state, rpc).await?;
+                Ok(snapshot(state.base_mint, state.quote_mint, reserves, state.coin_creator))
+            },
+            SnapshotDex::RaydiumCpmm => {
+                let state = raydium_cpmm::get_pool_by_address(rpc, &pool).await?;
+                let reserves = raydium_cpmm::get_pool_token_balances(
+                    rpc,
+                    &pool,
+                    &state.token0_mint,
+                    &state.token1_mint,
+                )
+                .await?;
+                Ok(snapshot(state.token0_mint, state.token1_mint, reserves, Pubkey::default()))
+            },
+            SnapshotDex::RaydiumAmmV4 => {
+                let state = raydium_amm_v4::get_pool_by_address(rpc, &pool).await?;
+                let reserves =
+                    pumpswap::get_vault_balances(rpc, &state.token_coin, &state.token_pc).await?;
+                Ok(snapshot(state.coin_mint, state.pc_mint, reserves, Pubkey::default()))
+            },
+        }
+    }
+
+    /// 在该快照上报价（与指令构建使用相同的计算）
+    pub fn quote(
+        &self,
+        side: BacktestSide,
+        amount_in: u64,
+        slippage_basis_points: Bps,
+    ) -> Result<TradeQuote> {
+        let is_base_in = side == BacktestSide::Sell;
+        let (expected, min) = match self.dex {
+            SnapshotDex::PumpSwap if is_base_in => {
+                let result = calc::pumpswap::sell_base_input_internal(
+                    amount_in,
+                    slippage_basis_points,
+                    self.base_reserve,
+                    self.quote_reserve,
+                    &self.coin_creator,
+                )
+                .map_err(|e| anyhow!(e))?;
+                (result.ui_quote, result.min_quote)
+            },
+            SnapshotDex::PumpSwap => {
+                let result = calc::pumpswap::buy_quote_input_internal(
+                    amount_in,
+                    slippage_basis_points,
+                    self.base_reserve,
+                    self.quote_reserve,
+                    &self.coin_creator,
+                )
+                .map_err(|e| anyhow!(e))?;
+                (result.base, result.base)
+            },
+            SnapshotDex::RaydiumCpmm => {
+                let result = calc::raydium_cpmm::compute_swap_amount(
+                    self.base_reserve,
+                    self.quote_reserve,
+                    is_base_in,
+                    amount_in,
+                    slippage_basis_points,
+                );
+                (result.amount_out, result.min_amount_out)
+            },
+            SnapshotDex::RaydiumAmmV4 => {
+                let result = calc::raydium_amm_v4::compute_swap_amount(
+                    self.base_reserve,
+                    self.quote_reserve,
+                    is_base_in,
+                    amount_in,
+                    slippage_basis_points,
+                );
+                (result.amount_out, result.min_amount_out)
+            },
+        };
+        Ok(TradeQuote { expected_output: Some(expected), min_output: min })
+    }
+}
+
+/// 按 slot 排序的快照序列
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotSeries {
+    snapshots: Vec<PoolSnapshot>,
+}
+
+impl SnapshotSeries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 插入快照（保持按 slot 排序）
+    pub fn push(&mut self, snapshot: PoolSnapshot) {
+        let index = self.snapshots.partition_point(|s| s.slot <= snapshot.slot);
+        self.snapshots.insert(index, snapshot);
+    }
+
+    pub fn snapshots(&self) -> &[PoolSnapshot] {
+        &self.snapshots
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// `pool` 在 `slot` 时（含）最近的快照
+    pub fn at_slot(&self, pool: &Pubkey, slot: u64) -> Option<&PoolSnapshot> {
+        let end = self.snapshots.partition_point(|s| s.slot <= slot);
+        self.snapshots[..end].iter().rev().find(|s| s.pool == *pool)
+    }
+
+    /// 从 JSONL 文件加载（每行一个快照）
+    pub fn load_jsonl(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::File::open(path.as_ref())?;
+        let mut series = Self::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let snapshot = serde_json::from_str(&line)
+                .map_err(|e| anyhow!("Invalid snapshot on line {}: {}", index + 1, e))?;
+            series.push(snapshot);
+        }
+        Ok(series)
+    }
+
+    /// 保存为 JSONL 文件
+    pub fn save_jsonl(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path.as_ref())?);
+        for snapshot in &self.snapshots {
+            writeln!(file, "{}", serde_json::to_string(snapshot)?)?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// 策略的一次历史下单
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BacktestOrder {
+    pub pool: Pubkey,
+    /// 下单时的 slot（使用此前最近的快照报价）
+    pub slot: u64,
+    pub side: BacktestSide,
+    pub amount_in: u64,
+    pub slippage_basis_points: Bps,
+}
+
+/// 一次下单的模拟结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktestFill {
+    pub order: BacktestOrder,
+    /// 报价所用快照的 slot
+    pub snapshot_slot: u64,
+    pub quote: TradeQuote,
+}
+
+impl BacktestFill {
+    /// 与链上实际成交对比
+    pub fn compare(&self, actual: &ResolvedFill) -> FillComparison {
+        let simulated_output = self.quote.expected_output.unwrap_or(self.quote.min_output);
+        let actual_output = actual.output_amount();
+        let deviation_bps = actual_output.filter(|_| simulated_output > 0).map(|actual| {
+            (actual as i128 - simulated_output as i128) * 10_000 / simulated_output as i128
+        });
+        FillComparison {
+            simulated_output,
+            min_output: self.quote.min_output,
+            actual_output,
+            deviation_bps: deviation_bps.map(|bps| bps as i64),
+        }
+    }
+}
+
+/// 模拟成交与链上实际成交的对比
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FillComparison {
+    pub simulated_output: u64,
+    pub min_output: u64,
+    /// 解析出的实际输出（协议不受支持时为 `None`）
+    pub actual_output: Option<u64>,
+    /// 实际相对模拟的偏差（基点，正数表示实际更优）
+    pub deviation_bps: Option<i64>,
+}
+
+/// 在快照序列上回放订单
+#[derive(Debug, Clone, Default)]
+pub struct Backtest {
+    pub series: SnapshotSeries,
+}
+
+impl Backtest {
+    pub fn new(series: SnapshotSeries) -> Self {
+        Self { series }
+    }
+
+    /// 回放单笔订单；该 slot 之前没有池子快照时报错
+    pub fn simulate(&self, order: BacktestOrder) -> Result<BacktestFill> {
+        let snapshot = self.series.at_slot(&order.pool, order.slot).ok_or_else(|| {
+            anyhow!("No snapshot of pool {} at or before slot {}", order.pool, order.slot)
+        })?;
+        let quote = snapshot.quote(order.side, order.amount_in, order.slippage_basis_points)?;
+        Ok(BacktestFill { order, snapshot_slot: snapshot.slot, quote })
+    }
+
+    /// 按顺序回放一组订单
+    pub fn run(&self, orders: &[BacktestOrder]) -> Vec<Result<BacktestFill>> {
+        orders.iter().map(|order| self.simulate(*order)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(pool: Pubkey, slot: u64, quote_reserve: u64) -> PoolSnapshot {
+        PoolSnapshot {
+            slot,
+            timestamp: slot as i64,
+            dex: SnapshotDex::RaydiumCpmm,
+            pool,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_reserve: 1_000_000_000,
+            quote_reserve,
+            coin_creator: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn test_replay_uses_latest_snapshot() {
+        let pool = Pubkey::new_unique();
+        let mut series = SnapshotSeries::new();
+        series.push(snapshot(pool, 200, 4_000_000_000));
+        series.push(snapshot(pool, 100, 2_000_000_000));
+        series.push(snapshot(Pubkey::new_unique(), 150, 1));
+        let backtest = Backtest::new(series);
+
+        let order = |slot| BacktestOrder {
+            pool,
+            slot,
+            side: BacktestSide::Sell,
+            amount_in: 1_000_000,
+            slippage_basis_points: Bps::new(100),
+        };
+        assert!(backtest.simulate(order(99)).is_err());
+        let early = backtest.simulate(order(150)).unwrap();
+        let late = backtest.simulate(order(250)).unwrap();
+        assert_eq!(early.snapshot_slot, 100);
+        assert_eq!(late.snapshot_slot, 200);
+        assert!(late.quote.min_output > early.quote.min_output);
+        assert!(early.quote.expected_output.unwrap() >= early.quote.min_output);
+    }
+
+    #[test]
+    fn test_jsonl_roundtrip() {
+        let pool = Pubkey::new_unique();
+        let mut series = SnapshotSeries::new();
+        series.push(snapshot(pool, 1, 10));
+        series.push(snapshot(pool, 2, 20));
+        let path = std::env::temp_dir().join(format!("snapshots-{}.jsonl", pool));
+        series.save_jsonl(&path).unwrap();
+        let loaded = SnapshotSeries::load_jsonl(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.snapshots(), series.snapshots());
+    }
+}
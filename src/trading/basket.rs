@@ -0,0 +1,604 @@
+//! 篮子（Basket）买入
+//!
+//! 在一笔交易（或一个 Jito bundle）中按权重同时买入多个代币，适用于指数/篮子策略：
+//!
+//! - 总投入按各腿权重拆分，余数计入最后一腿；
+//! - 需要 WSOL 的腿共用一次 wrap（交易开头）与一次 close（交易末尾），不再各自创建/关闭 WSOL ATA；
+//! - compute unit limit 按腿数放大（上限 1.4M），交易大小超过 1232 字节时返回错误，
+//!   此时可减少腿数、使用 ALT，或改用 [`BasketMode::JitoBundle`]；
+//! - provider、最低小费与费用上限的选取规则与 [`TradingClient::buy`] 一致：
+//!   未设置 durable nonce 时只能使用一个 provider，多个 provider 需通过
+//!   [`BasketBuyParams::with_durable_nonce`] 保证最多一笔上链；
+//! - 发送前执行与买入相同的检查（SOL 保留额、池子状态、三明治风险、频率限制）与预检模拟；
+//! - bundle 模式下每腿一笔交易，仅最后一笔携带小费，只发送到第一个 Jito 客户端；
+//!   优先费上限与小时预算按整个 bundle 计算，预检只模拟第一笔交易。
+//!
+//! bundle 模式不支持 durable nonce。
+
+use crate::common::nonce_cache::DurableNonceInfo;
+use crate::common::{Bps, GasFeeStrategy, GasFeeStrategyValue, PreflightPolicy};
+use crate::constants::trade_consts::DEFAULT_SLIPPAGE;
+use crate::swqos::common::TradeError;
+use crate::swqos::{SwqosClient, SwqosType, TradeType};
+use crate::trading::TradeFactory;
+use crate::trading::common::{
+    TransactionTemplate, assemble_instructions, close_wsol, get_transaction_blockhash, handle_wsol,
+};
+use crate::trading::core::async_executor::{
+    LandingMode, SignedSubmission, plan_providers, send_parallel,
+};
+use crate::trading::core::executor::check_preflight;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use crate::trading::store::Journal;
+use crate::utils::calc::common::calculate_with_slippage_buy;
+use crate::{TradeBuyParams, TradeTokenType, TradingClient};
+use anyhow::{Result, anyhow};
+use solana_hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::str::FromStr;
+
+/// 单笔交易允许的最大 compute unit
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// 单个篮子的最大腿数（Jito bundle 最多 5 笔交易）
+pub const MAX_BASKET_LEGS: usize = 5;
+
+/// 篮子中的一腿
+#[derive(Clone)]
+pub struct BasketLeg {
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    /// 相对权重（各腿按权重拆分总投入）
+    pub weight: u64,
+    pub extension_params: DexParamEnum,
+    /// 覆盖篮子级滑点
+    pub slippage_basis_points: Option<Bps>,
+}
+
+impl BasketLeg {
+    pub fn new(
+        dex_type: DexType,
+        mint: Pubkey,
+        weight: u64,
+        extension_params: DexParamEnum,
+    ) -> Self {
+        Self {
+            dex_type,
+            mint,
+            weight,
+            extension_params,
+            slippage_basis_points: None,
+        }
+    }
+
+    pub fn with_slippage(mut self, slippage_basis_points: Bps) -> Self {
+        self.slippage_basis_points = Some(slippage_basis_points);
+        self
+    }
+}
+
+/// 篮子发送方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BasketMode {
+    /// 所有腿放在同一笔交易中；发送到多个 SWQOS 时需要 durable nonce
+    #[default]
+    SingleTransaction,
+    /// 每腿一笔交易，打包为 Jito bundle 原子执行
+    JitoBundle,
+}
+
+/// 篮子买入参数
+#[derive(Clone)]
+pub struct BasketBuyParams {
+    pub legs: Vec<BasketLeg>,
+    /// 总投入（lamports），按权重拆分到各腿
+    pub total_input_amount: u64,
+    /// 篮子级滑点，各腿可单独覆盖
+    pub slippage_basis_points: Option<Bps>,
+    pub mode: BasketMode,
+    pub gas_fee_strategy: GasFeeStrategy,
+    pub recent_blockhash: Option<Hash>,
+    /// 设置后可发送到多个 SWQOS（最多一笔上链），仅单笔交易模式支持
+    pub durable_nonce: Option<DurableNonceInfo>,
+    pub address_lookup_table_account: Option<AddressLookupTableAccount>,
+    pub wait_transaction_confirmed: bool,
+    /// 是否为各腿创建代币 ATA
+    pub create_mint_ata: bool,
+}
+
+impl BasketBuyParams {
+    pub fn new(
+        legs: Vec<BasketLeg>,
+        total_input_amount: u64,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Self {
+        Self {
+            legs,
+            total_input_amount,
+            slippage_basis_points: None,
+            mode: BasketMode::SingleTransaction,
+            gas_fee_strategy,
+            recent_blockhash: None,
+            durable_nonce: None,
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: true,
+            create_mint_ata: true,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: BasketMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage_basis_points: Bps) -> Self {
+        self.slippage_basis_points = Some(slippage_basis_points);
+        self
+    }
+
+    pub fn with_recent_blockhash(mut self, recent_blockhash: Hash) -> Self {
+        self.recent_blockhash = Some(recent_blockhash);
+        self
+    }
+
+    pub fn with_durable_nonce(mut self, durable_nonce: DurableNonceInfo) -> Self {
+        self.durable_nonce = Some(durable_nonce);
+        self
+    }
+
+    pub fn with_address_lookup_table(mut self, alt: AddressLookupTableAccount) -> Self {
+        self.address_lookup_table_account = Some(alt);
+        self
+    }
+}
+
+/// 按权重拆分总额，余数计入最后一项
+pub fn split_by_weight(total: u64, weights: &[u64]) -> Result<Vec<u64>> {
+    let weight_sum: u128 = weights.iter().map(|w| *w as u128).sum();
+    if weight_sum == 0 {
+        return Err(anyhow!("Basket weights must not all be zero"));
+    }
+    let mut amounts: Vec<u64> = weights
+        .iter()
+        .map(|w| (total as u128 * *w as u128 / weight_sum) as u64)
+        .collect();
+    let allocated: u64 = amounts.iter().sum();
+    if let Some(last) = amounts.last_mut() {
+        *last += total - allocated;
+    }
+    Ok(amounts)
+}
+
+/// PumpFun 直接使用原生 SOL，其余协议需要 WSOL
+#[inline]
+fn uses_wsol(dex_type: &DexType) -> bool {
+    *dex_type != DexType::PumpFun
+}
+
+/// 一腿的业务指令
+struct LegInstructions {
+    instructions: Vec<Instruction>,
+    /// 该腿最多消耗的 WSOL（含滑点）
+    wsol_budget: u64,
+}
+
+impl TradingClient {
+    /// Buy several mints in one transaction (or one Jito bundle) in configured ratios
+    ///
+    /// # Returns
+    /// Returns `(success, signatures, last_error)` in the same shape as [`TradingClient::buy`]
+    pub async fn buy_basket(
+        &self,
+        mut params: BasketBuyParams,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        if params.legs.is_empty() {
+            return Err(anyhow!("Basket has no legs"));
+        }
+        if params.legs.len() > MAX_BASKET_LEGS {
+            return Err(anyhow!(
+                "Basket has {} legs, at most {} are supported",
+                params.legs.len(),
+                MAX_BASKET_LEGS
+            ));
+        }
+        if params.mode == BasketMode::JitoBundle && params.durable_nonce.is_some() {
+            return Err(anyhow!("Durable nonce is not supported in Jito bundle mode"));
+        }
+        if let Some(error) = self.basket_preflight(&mut params).await? {
+            return Ok((false, vec![], Some(error)));
+        }
+        let weights: Vec<u64> = params.legs.iter().map(|leg| leg.weight).collect();
+        let amounts = split_by_weight(params.total_input_amount, &weights)?;
+
+        let mut legs = Vec::with_capacity(params.legs.len());
+        for (leg, amount) in params.legs.iter().zip(amounts) {
+            legs.push(self.build_basket_leg(&params, leg, amount).await?);
+        }
+
+        let recent_blockhash = match params.recent_blockhash {
+            None if params.durable_nonce.is_none() => Some(self.rpc.get_latest_blockhash().await?),
+            recent_blockhash => recent_blockhash,
+        };
+        let blockhash = get_transaction_blockhash(recent_blockhash, params.durable_nonce.clone())?;
+
+        match params.mode {
+            BasketMode::SingleTransaction => {
+                self.send_basket_transaction(&params, legs, blockhash).await
+            },
+            BasketMode::JitoBundle => self.send_basket_bundle(&params, legs, blockhash).await,
+        }
+    }
+
+    /// 篮子发送前的参数补全与各项检查（与买入一致），返回 `Some` 表示交易被拒绝
+    ///
+    /// SOL 保留额按篮子总投入检查（允许缩减时直接修改 `total_input_amount`），其余检查逐腿执行。
+    async fn basket_preflight(&self, params: &mut BasketBuyParams) -> Result<Option<TradeError>> {
+        let gas_fee_strategy = params
+            .gas_fee_strategy
+            .with_scaled_cu_limit(params.legs.len() as u32, MAX_COMPUTE_UNIT_LIMIT);
+        if let Some(error) = self
+            .check_sol_reserve(
+                &TradeTokenType::SOL,
+                &mut params.total_input_amount,
+                &gas_fee_strategy,
+                false,
+            )
+            .await?
+        {
+            return Ok(Some(error));
+        }
+        let weights: Vec<u64> = params.legs.iter().map(|leg| leg.weight).collect();
+        let amounts = split_by_weight(params.total_input_amount, &weights)?;
+        for (leg, amount) in params.legs.iter_mut().zip(amounts) {
+            if amount == 0 {
+                return Err(anyhow!("Basket leg {} receives a zero amount", leg.mint));
+            }
+            self.fill_protocol_params(&mut leg.extension_params, &leg.mint, &TradeTokenType::SOL)
+                .await?;
+            if let Some(error) = self.check_pool_state(&leg.extension_params, &leg.mint).await? {
+                return Ok(Some(error));
+            }
+            if let Some(error) = self.check_sandwich_guard(
+                &leg.extension_params,
+                &leg.mint,
+                true,
+                amount,
+                leg.slippage_basis_points.or(params.slippage_basis_points),
+                None,
+            ) {
+                return Ok(Some(error));
+            }
+        }
+        for leg in &params.legs {
+            if let Some(error) = self.check_rate_limit(&leg.mint, false).await {
+                return Ok(Some(error));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn build_basket_leg(
+        &self,
+        basket: &BasketBuyParams,
+        leg: &BasketLeg,
+        amount: u64,
+    ) -> Result<LegInstructions> {
+        let slippage = leg.slippage_basis_points.or(basket.slippage_basis_points);
+        let params = TradeBuyParams {
+            dex_type: leg.dex_type.clone(),
+            input_token_type: TradeTokenType::SOL,
+            mint: leg.mint,
+            input_token_amount: amount,
+            slippage_basis_points: slippage,
+            recent_blockhash: basket.recent_blockhash,
+            last_valid_block_height: None,
+            extension_params: leg.extension_params.clone(),
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: basket.wait_transaction_confirmed,
+            // WSOL 由篮子统一 wrap / close
+            create_input_token_ata: false,
+            close_input_token_ata: false,
+            create_mint_ata: basket.create_mint_ata,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy: basket.gas_fee_strategy.clone(),
+            simulate: false,
+            on_transaction_signed: None,
+            callback_execution_mode: None,
+            enable_jito_sandwich_protection: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
+        };
+        let swap_params = self.build_buy_swap_params(params)?;
+        let executor = TradeFactory::create_executor(leg.dex_type.clone());
+        let instructions = executor.build_instructions(&swap_params).await?;
+        let wsol_budget = if uses_wsol(&leg.dex_type) {
+            calculate_with_slippage_buy(amount, slippage.unwrap_or(DEFAULT_SLIPPAGE))
+        } else {
+            0
+        };
+        Ok(LegInstructions { instructions, wsol_budget })
+    }
+
+    /// 组装单笔多腿交易；`tip` 为 false 时不附带小费（bundle 中的非末尾交易）
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn assemble_multi_leg_transaction(
+        &self,
+        gas_fee_strategy: &GasFeeStrategy,
+        trade_type: TradeType,
+        address_lookup_table_account: Option<AddressLookupTableAccount>,
+        swqos_client: &SwqosClient,
+        business_instructions: Vec<Instruction>,
+        leg_count: u32,
+        tip: bool,
+        blockhash: Hash,
+    ) -> Result<VersionedTransaction> {
+        let swqos_type = swqos_client.get_swqos_type();
+        let (_, _, gas) = gas_fee_strategy
+            .get_strategies(trade_type)
+            .into_iter()
+            .find(|config| config.0 == swqos_type)
+            .ok_or_else(|| anyhow!("No gas fee strategy for {:?}", swqos_type))?;
+
+        let tip_account_str = swqos_client.resolve_tip_account()?;
+        let use_tip = tip && !tip_account_str.is_empty() && swqos_type != SwqosType::Default;
+        let tip_accounts = if use_tip {
+            let tip_account = Pubkey::from_str(&tip_account_str)
+                .map_err(|e| anyhow!("无效的小费接收地址 '{}': {}", tip_account_str, e))?;
+            swqos_type
+                .pick_tip_accounts(tip_account, gas_fee_strategy.tip_split_for(swqos_type, gas.tip))
+        } else {
+            vec![]
+        };
+
+        let gas = GasFeeStrategyValue {
+            cu_limit: gas.cu_limit.saturating_mul(leg_count).min(MAX_COMPUTE_UNIT_LIMIT),
+            ..gas
+        };
+        self.assemble_signed(
+            swqos_type,
+            gas,
+            &tip_accounts,
+            gas.tip,
+            trade_type,
+            address_lookup_table_account,
+            business_instructions,
+            None,
+            blockhash,
+        )
+    }
+
+    /// 按给定的 gas 配置与小费账户组装并签名；`tip_accounts` 为空时不附带小费
+    #[allow(clippy::too_many_arguments)]
+    fn assemble_signed(
+        &self,
+        swqos_type: SwqosType,
+        gas: GasFeeStrategyValue,
+        tip_accounts: &[Pubkey],
+        tip: f64,
+        trade_type: TradeType,
+        address_lookup_table_account: Option<AddressLookupTableAccount>,
+        business_instructions: Vec<Instruction>,
+        durable_nonce: Option<DurableNonceInfo>,
+        blockhash: Hash,
+    ) -> Result<VersionedTransaction> {
+        let use_tip = !tip_accounts.is_empty();
+        let uses_nonce = durable_nonce.is_some();
+        let instructions = assemble_instructions(
+            self.payer.as_ref(),
+            gas.cu_limit,
+            gas.cu_price,
+            business_instructions,
+            use_tip,
+            tip_accounts,
+            if use_tip { tip } else { 0.0 },
+            durable_nonce,
+            false,
+            self.instruction_layout,
+        )?;
+        TransactionTemplate::new(
+            self.payer.clone(),
+            &instructions,
+            address_lookup_table_account,
+            blockhash,
+            swqos_type,
+            trade_type,
+            uses_nonce,
+        )?
+        .sign_with_blockhash(&blockhash)
+    }
+
+    async fn send_basket_transaction(
+        &self,
+        params: &BasketBuyParams,
+        legs: Vec<LegInstructions>,
+        blockhash: Hash,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        let payer = self.payer.pubkey();
+        let leg_count = legs.len() as u32;
+        let wsol_budget: u64 = legs.iter().map(|leg| leg.wsol_budget).sum();
+
+        let mut business_instructions = Vec::new();
+        if wsol_budget > 0 {
+            business_instructions.extend(handle_wsol(&payer, wsol_budget));
+        }
+        for leg in legs {
+            business_instructions.extend(leg.instructions);
+        }
+        if wsol_budget > 0 {
+            business_instructions.extend(close_wsol(&payer));
+        }
+
+        // compute unit limit 按腿数放大；provider 选取、最低小费与费用上限与买入一致
+        let gas_fee_strategy =
+            params.gas_fee_strategy.with_scaled_cu_limit(leg_count, MAX_COMPUTE_UNIT_LIMIT);
+        let plans = plan_providers(
+            &self.swqos_clients(),
+            &gas_fee_strategy,
+            TradeType::Buy,
+            true,
+            LandingMode::for_swap(true, params.durable_nonce.is_some()),
+        )?;
+        let mut submissions = Vec::with_capacity(plans.len());
+        for plan in plans {
+            let tip = plan.tip();
+            let (swqos_type, _, gas) = plan.config;
+            let transaction = self.assemble_signed(
+                swqos_type,
+                gas,
+                &plan.tip_accounts,
+                tip,
+                TradeType::Buy,
+                params.address_lookup_table_account.clone(),
+                business_instructions.clone(),
+                params.durable_nonce.clone(),
+                blockhash,
+            )?;
+            submissions.push(SignedSubmission {
+                swqos_client: plan.swqos_client,
+                transaction,
+                cu_limit: gas.cu_limit,
+                cu_price: gas.cu_price,
+                tip,
+            });
+        }
+        if self.preflight != PreflightPolicy::Skip {
+            check_preflight(
+                self.preflight,
+                self.simulate_external(&submissions[0].transaction).await,
+            )?;
+        }
+
+        let (success, signatures, error) = send_parallel(
+            submissions,
+            TradeType::Buy,
+            params.wait_transaction_confirmed,
+            Some(self.fee_ledger.clone()),
+            self.trade_store.clone().map(|store| Journal::new(store, None)),
+            self.confirm,
+            None,
+            self.swqos_fanout.as_deref(),
+        )
+        .await?;
+        Ok((success, signatures, error.map(TradeError::from)))
+    }
+
+    async fn send_basket_bundle(
+        &self,
+        params: &BasketBuyParams,
+        legs: Vec<LegInstructions>,
+        blockhash: Hash,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        let payer = self.payer.pubkey();
+        let wsol_budget: u64 = legs.iter().map(|leg| leg.wsol_budget).sum();
+        let leg_count = legs.len() as u32;
+        let last = legs.len() - 1;
+
+        // 同一篮子只发送一个 bundle；费用上限与小时预算按整个 bundle（各笔 cu_limit 之和）校验
+        let jito = self
+            .swqos_clients()
+            .iter()
+            .find(|client| client.get_swqos_type() == SwqosType::Jito)
+            .cloned()
+            .ok_or_else(|| anyhow!("Jito bundle mode requires a Jito SWQOS client"))?;
+        let gas_fee_strategy = params.gas_fee_strategy.with_scaled_cu_limit(leg_count, u32::MAX);
+        let plan =
+            plan_providers(&[jito], &gas_fee_strategy, TradeType::Buy, true, LandingMode::Single)?
+                .remove(0);
+        let (swqos_type, _, bundle_gas) = plan.config;
+        let gas = GasFeeStrategyValue { cu_limit: bundle_gas.cu_limit / leg_count, ..bundle_gas };
+
+        let mut transactions = Vec::with_capacity(legs.len());
+        for (i, leg) in legs.into_iter().enumerate() {
+            let mut business_instructions = Vec::new();
+            if i == 0 && wsol_budget > 0 {
+                business_instructions.extend(handle_wsol(&payer, wsol_budget));
+            }
+            business_instructions.extend(leg.instructions);
+            if i == last && wsol_budget > 0 {
+                business_instructions.extend(close_wsol(&payer));
+            }
+            let tip_accounts: &[Pubkey] = if i == last { &plan.tip_accounts } else { &[] };
+            transactions.push(self.assemble_signed(
+                swqos_type,
+                gas,
+                tip_accounts,
+                plan.tip(),
+                TradeType::Buy,
+                params.address_lookup_table_account.clone(),
+                business_instructions,
+                None,
+                blockhash,
+            )?);
+        }
+        // 后续交易依赖前一笔的状态（如 WSOL），单独模拟只对第一笔有意义
+        if self.preflight != PreflightPolicy::Skip {
+            check_preflight(self.preflight, self.simulate_external(&transactions[0]).await)?;
+        }
+
+        let result = plan
+            .swqos_client
+            .send_transactions(TradeType::Buy, &transactions, params.wait_transaction_confirmed)
+            .await;
+        let results = transactions
+            .iter()
+            .map(|transaction| {
+                let result = match &result {
+                    Ok(()) => Ok(()),
+                    Err(e) => Err(anyhow!("{}", e)),
+                };
+                (transaction.signatures[0], result)
+            })
+            .collect();
+        Ok(collect_results(results))
+    }
+}
+
+pub(crate) fn collect_results(
+    results: Vec<(Signature, Result<()>)>,
+) -> (bool, Vec<Signature>, Option<TradeError>) {
+    let mut success = false;
+    let mut signatures = Vec::with_capacity(results.len());
+    let mut last_error = None;
+    for (signature, result) in results {
+        signatures.push(signature);
+        match result {
+            Ok(()) => success = true,
+            Err(e) => last_error = Some(TradeError::from(e)),
+        }
+    }
+    (success, signatures, if success { None } else { last_error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_by_weight() {
+        assert_eq!(split_by_weight(1_000, &[1, 1, 2]).unwrap(), vec![250, 250, 500]);
+        // 余数计入最后一腿
+        assert_eq!(split_by_weight(100, &[1, 1, 1]).unwrap(), vec![33, 33, 34]);
+        assert_eq!(split_by_weight(10, &[0, 5]).unwrap(), vec![0, 10]);
+        assert!(split_by_weight(10, &[0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_wsol_legs() {
+        assert!(!uses_wsol(&DexType::PumpFun));
+        assert!(uses_wsol(&DexType::PumpSwap));
+        assert!(uses_wsol(&DexType::RaydiumCpmm));
+    }
+}
@@ -0,0 +1,297 @@
+//! Raydium CLMM 流动性仓位管理
+//!
+//! 在交易所用的同一个 [`TradingClient`] 上开仓、追加/减少流动性、领取手续费与关闭仓位，
+//! 指令构建见 [`crate::instruction::raydium_clmm_position`]。
+//!
+//! - 仓位以 NFT 表示，开仓时生成新的 mint keypair，返回其地址作为后续操作的仓位标识；
+//! - 池中一侧为 WSOL 时自动包装所需 SOL，并在交易结束时关闭 WSOL 账户取回剩余 SOL；
+//! - 所有操作发送后等待确认。
+
+use crate::TradingClient;
+use crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed;
+use crate::constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::raydium_clmm_position::{
+    TickRange, close_position, decrease_liquidity_v2, increase_liquidity_v2, open_position_v2,
+};
+use crate::instruction::utils::raydium_clmm::{get_personal_position, get_pool_by_address_force};
+use crate::instruction::utils::raydium_clmm_types::{PersonalPositionState, PoolState};
+use crate::trading::common::compute_budget_manager::compute_budget_instructions;
+use crate::trading::common::wsol_manager::{close_wsol, handle_wsol};
+use crate::trading::core::params::RaydiumClmmParams;
+use crate::utils::calc::clmm_math::liquidity_math::get_liquidity_from_amounts;
+use crate::utils::calc::clmm_math::tick_math::get_sqrt_price_at_tick;
+use anyhow::{Result, anyhow};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+/// 仓位操作的 compute unit 上限
+const POSITION_COMPUTE_UNIT_LIMIT: u32 = 400_000;
+
+/// 按池当前价格计算 `[tick_lower, tick_upper]` 区间内不超过给定数量可提供的流动性
+pub fn liquidity_for_amounts(
+    pool_state: &PoolState,
+    range: &TickRange,
+    amount_0_max: u64,
+    amount_1_max: u64,
+) -> Result<u128> {
+    let sqrt_price_lower = get_sqrt_price_at_tick(range.tick_lower).map_err(|e| anyhow!(e))?;
+    let sqrt_price_upper = get_sqrt_price_at_tick(range.tick_upper).map_err(|e| anyhow!(e))?;
+    Ok(get_liquidity_from_amounts(
+        pool_state.sqrt_price_x64,
+        sqrt_price_lower,
+        sqrt_price_upper,
+        amount_0_max,
+        amount_1_max,
+    ))
+}
+
+/// 已初始化的池奖励：(reward_vault, reward_mint)
+fn pool_rewards(pool_state: &PoolState) -> Vec<(Pubkey, Pubkey)> {
+    pool_state
+        .reward_infos
+        .iter()
+        .filter(|reward| reward.token_mint != Pubkey::default())
+        .map(|reward| (reward.token_vault, reward.token_mint))
+        .collect()
+}
+
+impl TradingClient {
+    /// 查询仓位 NFT 对应的仓位状态
+    pub async fn get_clmm_position(
+        &self,
+        position_nft_mint: &Pubkey,
+    ) -> Result<PersonalPositionState> {
+        get_personal_position(self.rpc.as_ref(), position_nft_mint).await
+    }
+
+    /// 在 `pool` 的 `[tick_lower, tick_upper]` 区间开仓，返回 (交易签名, 仓位 NFT mint)
+    ///
+    /// 流动性按池当前价格与 `amount_0_max` / `amount_1_max` 计算，实际投入不超过这两个数量。
+    pub async fn open_clmm_position(
+        &self,
+        pool: &Pubkey,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<(Signature, Pubkey)> {
+        let pool_state = get_pool_by_address_force(self.rpc.as_ref(), pool).await?;
+        let params = RaydiumClmmParams::from_pool_address_by_rpc(&self.rpc, pool).await?;
+        let range = TickRange::new(tick_lower, tick_upper, pool_state.tick_spacing)?;
+        let liquidity = liquidity_for_amounts(&pool_state, &range, amount_0_max, amount_1_max)?;
+        if liquidity == 0 {
+            return Err(anyhow!("amounts are too small to provide liquidity in this range"));
+        }
+
+        let payer = self.payer.pubkey();
+        let position_nft_mint = Keypair::new();
+        let open = open_position_v2(
+            &payer,
+            &params,
+            &range,
+            &position_nft_mint.pubkey(),
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            None,
+            true,
+            self.use_seed_optimize,
+        )?;
+        let instructions = self.with_wsol_wrapping(&params, amount_0_max, amount_1_max, vec![open]);
+        let signature =
+            self.send_position_transaction(&instructions, &[&position_nft_mint]).await?;
+        Ok((signature, position_nft_mint.pubkey()))
+    }
+
+    /// 向已有仓位追加流动性（实际投入不超过 `amount_0_max` / `amount_1_max`）
+    pub async fn increase_clmm_liquidity(
+        &self,
+        position_nft_mint: &Pubkey,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<Signature> {
+        let position = self.get_clmm_position(position_nft_mint).await?;
+        let pool_state = get_pool_by_address_force(self.rpc.as_ref(), &position.pool_id).await?;
+        let params =
+            RaydiumClmmParams::from_pool_address_by_rpc(&self.rpc, &position.pool_id).await?;
+        let range = TickRange::new(
+            position.tick_lower_index,
+            position.tick_upper_index,
+            pool_state.tick_spacing,
+        )?;
+        let liquidity = liquidity_for_amounts(&pool_state, &range, amount_0_max, amount_1_max)?;
+        if liquidity == 0 {
+            return Err(anyhow!("amounts are too small to provide liquidity in this range"));
+        }
+
+        let increase = increase_liquidity_v2(
+            &self.payer.pubkey(),
+            &params,
+            &position,
+            pool_state.tick_spacing,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            None,
+            self.use_seed_optimize,
+        )?;
+        let instructions =
+            self.with_wsol_wrapping(&params, amount_0_max, amount_1_max, vec![increase]);
+        self.send_position_transaction(&instructions, &[]).await
+    }
+
+    /// 减少仓位流动性，同时领取累计的手续费与奖励
+    ///
+    /// 接收代币与奖励的账户不存在时会先创建；WSOL 在交易结束时解包为 SOL。
+    pub async fn decrease_clmm_liquidity(
+        &self,
+        position_nft_mint: &Pubkey,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<Signature> {
+        let position = self.get_clmm_position(position_nft_mint).await?;
+        if liquidity > position.liquidity {
+            return Err(anyhow!(
+                "liquidity {} exceeds position liquidity {}",
+                liquidity,
+                position.liquidity
+            ));
+        }
+        let pool_state = get_pool_by_address_force(self.rpc.as_ref(), &position.pool_id).await?;
+        let params =
+            RaydiumClmmParams::from_pool_address_by_rpc(&self.rpc, &position.pool_id).await?;
+        let rewards = pool_rewards(&pool_state);
+
+        let payer = self.payer.pubkey();
+        let mut instructions = Vec::new();
+        for (mint, token_program) in [
+            (params.token0_mint, params.token0_program),
+            (params.token1_mint, params.token1_program),
+        ] {
+            instructions.extend(create_associated_token_account_idempotent_fast_use_seed(
+                &payer,
+                &payer,
+                &mint,
+                &token_program,
+                self.use_seed_optimize,
+            ));
+        }
+        for (_, reward_mint) in &rewards {
+            instructions.extend(create_associated_token_account_idempotent_fast_use_seed(
+                &payer,
+                &payer,
+                reward_mint,
+                &TOKEN_PROGRAM,
+                false,
+            ));
+        }
+        instructions.push(decrease_liquidity_v2(
+            &payer,
+            &params,
+            &position,
+            pool_state.tick_spacing,
+            liquidity,
+            amount_0_min,
+            amount_1_min,
+            &rewards,
+            self.use_seed_optimize,
+        )?);
+        if params.token0_mint == WSOL_TOKEN_ACCOUNT || params.token1_mint == WSOL_TOKEN_ACCOUNT {
+            instructions.extend(close_wsol(&payer));
+        }
+        self.send_position_transaction(&instructions, &[]).await
+    }
+
+    /// 仅领取仓位的手续费与奖励（不减少流动性）
+    pub async fn collect_clmm_fees(&self, position_nft_mint: &Pubkey) -> Result<Signature> {
+        self.decrease_clmm_liquidity(position_nft_mint, 0, 0, 0).await
+    }
+
+    /// 关闭仓位并销毁仓位 NFT，回收租金
+    ///
+    /// 仓位流动性必须已清零；未领取的手续费会随关闭丢失，请先调用 [`Self::decrease_clmm_liquidity`]。
+    pub async fn close_clmm_position(&self, position_nft_mint: &Pubkey) -> Result<Signature> {
+        let position = self.get_clmm_position(position_nft_mint).await?;
+        if position.liquidity > 0 {
+            return Err(anyhow!(
+                "position {} still holds liquidity {}",
+                position_nft_mint,
+                position.liquidity
+            ));
+        }
+        let close = close_position(&self.payer.pubkey(), position_nft_mint);
+        self.send_position_transaction(&[close], &[]).await
+    }
+
+    /// 池一侧为 WSOL 时，在仓位指令前包装所需 SOL、之后关闭 WSOL 账户
+    fn with_wsol_wrapping(
+        &self,
+        params: &RaydiumClmmParams,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        position_instructions: Vec<Instruction>,
+    ) -> Vec<Instruction> {
+        let wsol_amount = if params.token0_mint == WSOL_TOKEN_ACCOUNT {
+            amount_0_max
+        } else if params.token1_mint == WSOL_TOKEN_ACCOUNT {
+            amount_1_max
+        } else {
+            return position_instructions;
+        };
+        let payer = self.payer.pubkey();
+        let mut instructions: Vec<Instruction> =
+            handle_wsol(&payer, wsol_amount).into_iter().collect();
+        instructions.extend(position_instructions);
+        instructions.extend(close_wsol(&payer));
+        instructions
+    }
+
+    async fn send_position_transaction(
+        &self,
+        instructions: &[Instruction],
+        extra_signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let mut all_instructions: Vec<Instruction> =
+            compute_budget_instructions(0, POSITION_COMPUTE_UNIT_LIMIT)
+                .into_iter()
+                .collect();
+        all_instructions.extend_from_slice(instructions);
+
+        let mut signers: Vec<&Keypair> = vec![self.payer.as_ref()];
+        signers.extend_from_slice(extra_signers);
+        let recent_blockhash = self.rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            recent_blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liquidity_for_amounts_in_range() {
+        let pool_state = PoolState {
+            tick_spacing: 60,
+            sqrt_price_x64: get_sqrt_price_at_tick(0).unwrap(),
+            ..Default::default()
+        };
+        let range = TickRange::new(-600, 600, 60).unwrap();
+        let liquidity = liquidity_for_amounts(&pool_state, &range, 1_000_000, 1_000_000).unwrap();
+        assert!(liquidity > 0);
+        // 只提供一侧代币时，价格在区间内无法提供流动性
+        assert_eq!(liquidity_for_amounts(&pool_state, &range, 1_000_000, 0).unwrap(), 0);
+        assert!(pool_rewards(&pool_state).is_empty());
+    }
+}
@@ -45,3 +45,36 @@ pub fn compute_budget_instructions(unit_price: u64, unit_limit: u32) -> SmallVec
 
     insts
 }
+
+/// 取出指令列表中调用方提供的 SetComputeUnitLimit / SetComputeUnitPrice
+///
+/// 返回 `(cu_limit, cu_price)` 覆盖值，对应指令从列表中移除（由 [`compute_budget_instructions`]
+/// 按覆盖值重新生成）；其他 Compute Budget 指令保持原位。
+pub fn take_compute_budget_overrides(
+    instructions: &mut Vec<Instruction>,
+) -> (Option<u32>, Option<u64>) {
+    let (mut cu_limit, mut cu_price) = (None, None);
+    if !instructions
+        .iter()
+        .any(|ix| solana_compute_budget_interface::check_id(&ix.program_id))
+    {
+        return (cu_limit, cu_price);
+    }
+    instructions.retain(|ix| {
+        if !solana_compute_budget_interface::check_id(&ix.program_id) {
+            return true;
+        }
+        match ix.data.split_first() {
+            Some((2, rest)) if rest.len() == 4 => {
+                cu_limit = Some(u32::from_le_bytes(rest.try_into().unwrap()));
+                false
+            },
+            Some((3, rest)) if rest.len() == 8 => {
+                cu_price = Some(u64::from_le_bytes(rest.try_into().unwrap()));
+                false
+            },
+            _ => true,
+        }
+    });
+    (cu_limit, cu_price)
+}
@@ -0,0 +1,104 @@
+//! 第三方手续费支付者（代付 gas）
+//!
+//! 设置 `fee_payer` 后，交易消息以 fee payer 为 account[0]，由其支付基础手续费与优先费；
+//! 交易钱包（payer）仍作为代币账户 owner、ATA 租金与小费的支付方签名。
+//! fee payer 可以是本地 `Keypair`，也可以是任意实现了 `Signer` 的远程签名器。
+
+use anyhow::{Result, anyhow};
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+use std::sync::Arc;
+
+/// 手续费支付者（本地 Keypair 或远程签名器）
+pub type FeePayerRef = Arc<dyn Signer + Send + Sync>;
+
+/// 校验 fee payer 可用且与交易钱包不同
+pub fn validate_fee_payer(fee_payer: &FeePayerRef, payer: &Pubkey) -> Result<Pubkey> {
+    let fee_payer_pubkey = fee_payer
+        .try_pubkey()
+        .map_err(|e| anyhow!("Failed to get fee payer pubkey: {}", e))?;
+    if fee_payer_pubkey == *payer {
+        return Err(anyhow!(
+            "fee_payer must differ from the trading wallet; leave it unset instead"
+        ));
+    }
+    Ok(fee_payer_pubkey)
+}
+
+/// 按消息中签名账户的顺序签名
+///
+/// 签名账户只能是 fee payer（必须位于 account[0]）与交易钱包，出现其他签名者时返回错误。
+pub fn sign_with_fee_payer(
+    message: &VersionedMessage,
+    payer: &Keypair,
+    fee_payer: &FeePayerRef,
+) -> Result<Vec<Signature>> {
+    let fee_payer_pubkey = validate_fee_payer(fee_payer, &payer.pubkey())?;
+    let num_signers = message.header().num_required_signatures as usize;
+    let signer_keys = message
+        .static_account_keys()
+        .get(..num_signers)
+        .ok_or_else(|| anyhow!("Invalid message header: {} signers", num_signers))?;
+    if signer_keys.first() != Some(&fee_payer_pubkey) {
+        return Err(anyhow!(
+            "fee_payer {} is not the first account of the message",
+            fee_payer_pubkey
+        ));
+    }
+    let message_bytes = message.serialize();
+    signer_keys
+        .iter()
+        .map(|key| {
+            if *key == fee_payer_pubkey {
+                fee_payer
+                    .try_sign_message(&message_bytes)
+                    .map_err(|e| anyhow!("fee_payer failed to sign: {}", e))
+            } else if *key == payer.pubkey() {
+                payer
+                    .try_sign_message(&message_bytes)
+                    .map_err(|e| anyhow!("交易签名失败: {}", e))
+            } else {
+                Err(anyhow!("Transaction requires an unexpected signer {}", key))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, instruction::Instruction, message::Message};
+    use solana_system_interface::instruction::transfer;
+
+    #[test]
+    fn test_sign_with_fee_payer_orders_signatures() {
+        let payer = Keypair::new();
+        let sponsor = Arc::new(Keypair::new());
+        let fee_payer: FeePayerRef = sponsor.clone();
+        let ix: Instruction = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[ix.clone()],
+            Some(&sponsor.pubkey()),
+            &Hash::default(),
+        ));
+        let signatures = sign_with_fee_payer(&message, &payer, &fee_payer).unwrap();
+        assert_eq!(signatures.len(), 2);
+        let bytes = message.serialize();
+        assert!(signatures[0].verify(sponsor.pubkey().as_ref(), &bytes));
+        assert!(signatures[1].verify(payer.pubkey().as_ref(), &bytes));
+
+        let same: FeePayerRef = Arc::new(payer.insecure_clone());
+        assert!(validate_fee_payer(&same, &payer.pubkey()).is_err());
+
+        let other = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[ix],
+            Some(&payer.pubkey()),
+            &Hash::default(),
+        ));
+        assert!(sign_with_fee_payer(&other, &payer, &fee_payer).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! 交易指令排列策略
+//!
+//! 默认顺序：nonce advance → jitodontfront 标记 → 小费转账 → compute budget → 业务指令。
+//! 部分中继要求小费在最后、部分 MEV 场景希望小费紧随 compute budget，
+//! 通过 [`InstructionLayout`] 可以调整小费、compute budget、ATA 创建与关闭账户指令的位置。
+//!
+//! nonce advance 必须是交易的第一条指令（运行时要求），jitodontfront 标记紧随其后，两者位置不可配置。
+
+use crate::constants::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use solana_sdk::instruction::Instruction;
+
+/// SPL Token `CloseAccount` 指令标识
+const CLOSE_ACCOUNT_DISCRIMINATOR: u8 = 9;
+
+/// 小费转账位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TipPlacement {
+    /// 在 compute budget 之前（默认）
+    #[default]
+    BeforeComputeBudget,
+    /// 在 compute budget 之后、业务指令之前
+    AfterComputeBudget,
+    /// 交易最后一条指令
+    Last,
+}
+
+/// ATA 创建指令位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtaCreatePlacement {
+    /// 保持协议构建时的位置（默认）
+    #[default]
+    Inline,
+    /// 提前到所有业务指令之前
+    Hoisted,
+}
+
+/// 关闭账户指令位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClosePlacement {
+    /// 保持协议构建时的位置（默认）
+    #[default]
+    Inline,
+    /// 移到所有业务指令之后（小费为 `Last` 时仍在小费之前）
+    Last,
+}
+
+/// 交易指令排列配置（默认与历史固定顺序一致）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InstructionLayout {
+    pub tip: TipPlacement,
+    pub ata_create: AtaCreatePlacement,
+    pub close: ClosePlacement,
+}
+
+impl InstructionLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tip(mut self, tip: TipPlacement) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn with_ata_create(mut self, ata_create: AtaCreatePlacement) -> Self {
+        self.ata_create = ata_create;
+        self
+    }
+
+    pub fn with_close(mut self, close: ClosePlacement) -> Self {
+        self.close = close;
+        self
+    }
+
+    /// 按配置重排业务指令（ATA 创建 / 关闭账户），其余指令保持相对顺序
+    pub fn arrange_business_instructions(
+        &self,
+        business_instructions: Vec<Instruction>,
+    ) -> Vec<Instruction> {
+        if self.ata_create == AtaCreatePlacement::Inline && self.close == ClosePlacement::Inline {
+            return business_instructions;
+        }
+        let mut creates = Vec::new();
+        let mut closes = Vec::new();
+        let mut rest = Vec::with_capacity(business_instructions.len());
+        for ix in business_instructions {
+            if self.ata_create == AtaCreatePlacement::Hoisted && is_ata_create(&ix) {
+                creates.push(ix);
+            } else if self.close == ClosePlacement::Last && is_close_account(&ix) {
+                closes.push(ix);
+            } else {
+                rest.push(ix);
+            }
+        }
+        creates.extend(rest);
+        creates.extend(closes);
+        creates
+    }
+}
+
+fn is_ata_create(ix: &Instruction) -> bool {
+    ix.program_id == ASSOCIATED_TOKEN_PROGRAM_ID
+}
+
+fn is_close_account(ix: &Instruction) -> bool {
+    (ix.program_id == TOKEN_PROGRAM || ix.program_id == TOKEN_PROGRAM_2022)
+        && ix.data.first() == Some(&CLOSE_ACCOUNT_DISCRIMINATOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn ix(program_id: Pubkey, data: Vec<u8>) -> Instruction {
+        Instruction { program_id, accounts: vec![], data }
+    }
+
+    #[test]
+    fn test_arrange_business_instructions() {
+        let swap_program = Pubkey::new_unique();
+        let close = ix(TOKEN_PROGRAM, vec![CLOSE_ACCOUNT_DISCRIMINATOR]);
+        let sync_native = ix(TOKEN_PROGRAM, vec![17]);
+        let create = ix(ASSOCIATED_TOKEN_PROGRAM_ID, vec![1]);
+        let swap = ix(swap_program, vec![0xaa]);
+        let instructions = vec![sync_native.clone(), close.clone(), create.clone(), swap.clone()];
+
+        let unchanged =
+            InstructionLayout::default().arrange_business_instructions(instructions.clone());
+        assert_eq!(unchanged, instructions);
+
+        let arranged = InstructionLayout::new()
+            .with_ata_create(AtaCreatePlacement::Hoisted)
+            .with_close(ClosePlacement::Last)
+            .arrange_business_instructions(instructions);
+        assert_eq!(arranged, vec![create, sync_native, swap, close]);
+    }
+}
@@ -1,12 +1,18 @@
 pub mod compute_budget_manager;
+pub mod fee_payer;
+pub mod instruction_layout;
 pub mod nonce_manager;
 pub mod transaction_builder;
+pub mod transaction_template;
 pub mod utils;
 pub mod wsol_manager;
 
 // Re-export commonly used functions
 pub use compute_budget_manager::*;
+pub use fee_payer::FeePayerRef;
+pub use instruction_layout::{AtaCreatePlacement, ClosePlacement, InstructionLayout, TipPlacement};
 pub use nonce_manager::*;
 pub use transaction_builder::*;
+pub use transaction_template::TransactionTemplate;
 pub use utils::*;
 pub use wsol_manager::*;
@@ -9,20 +9,42 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use super::{
-    compute_budget_manager::compute_budget_instructions,
+    compute_budget_manager::{compute_budget_instructions, take_compute_budget_overrides},
+    fee_payer::{FeePayerRef, sign_with_fee_payer, validate_fee_payer},
+    instruction_layout::{InstructionLayout, TipPlacement},
     nonce_manager::{add_nonce_instruction, get_transaction_blockhash},
 };
 use crate::{
-    common::{SolanaRpcClient, nonce_cache::DurableNonceInfo},
+    common::{
+        SolanaRpcClient, address_lookup::lookup_table_manager, nonce_cache::DurableNonceInfo,
+    },
+    constants::trade_consts::MAX_TRANSACTION_SIZE,
+    perf::{arena::recycle_instructions, signing_pool::sign_message},
     trading::{
-        MiddlewareManager,
+        MiddlewareManager, TradeMetadata,
         core::transaction_pool::{acquire_builder, release_builder},
     },
 };
 
+/// 按小费账户拆分小费：平均拆分，余数计入第一笔；拆分后单笔为 0 时退化为单笔转账
+pub(crate) fn split_tip(tip_accounts: &[Pubkey], tip: f64) -> anyhow::Result<Vec<(Pubkey, u64)>> {
+    let tip_lamports = sol_str_to_lamports(&tip.to_string())
+        .ok_or_else(|| anyhow::anyhow!("无效的小费金额 '{}': 转换失败", tip))?;
+    let splits = (tip_accounts.len() as u64).min(tip_lamports).max(1);
+    let per_account = tip_lamports / splits;
+    let remainder = tip_lamports % splits;
+    Ok(tip_accounts
+        .iter()
+        .take(splits as usize)
+        .enumerate()
+        .map(|(i, account)| (*account, if i == 0 { per_account + remainder } else { per_account }))
+        .collect())
+}
+
 /// Build standard RPC transaction
 pub async fn build_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<FeePayerRef>,
     _rpc: Option<Arc<SolanaRpcClient>>,
     unit_limit: u32,
     unit_price: u64,
@@ -33,17 +55,67 @@ pub async fn build_transaction(
     protocol_name: &str,
     is_buy: bool,
     with_tip: bool,
-    tip_account: &Pubkey,
+    tip_accounts: &[Pubkey],
     tip_amount: f64,
     durable_nonce: Option<DurableNonceInfo>,
     enable_jito_sandwich_protection: bool,
+    layout: InstructionLayout,
+    metadata: Option<&TradeMetadata>,
     // nonce_account: Option<Pubkey>,
     // current_nonce: Option<Hash>,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let mut instructions = Vec::with_capacity(business_instructions.len() + 5);
+    let instructions = assemble_instructions(
+        payer.as_ref(),
+        unit_limit,
+        unit_price,
+        business_instructions,
+        with_tip,
+        tip_accounts,
+        tip_amount,
+        durable_nonce.clone(),
+        enable_jito_sandwich_protection,
+        layout,
+    )?;
+
+    // Get blockhash for transaction
+    let blockhash = get_transaction_blockhash(recent_blockhash, durable_nonce.clone())?;
+
+    // Build transaction
+    build_versioned_transaction(
+        payer,
+        fee_payer,
+        instructions,
+        address_lookup_table_account,
+        blockhash,
+        middleware_manager,
+        protocol_name,
+        is_buy,
+        metadata,
+    )
+    .await
+}
+
+/// Assemble the full instruction list for a trade transaction
+///
+/// 默认顺序：nonce advance → jitodontfront 标记 → 小费转账 → compute budget → 业务指令；
+/// 小费、ATA 创建与关闭账户指令的位置可通过 `layout` 调整。
+/// 业务指令中的 SetComputeUnitLimit / SetComputeUnitPrice 会替换 gas 策略生成的对应指令。
+pub fn assemble_instructions(
+    payer: &Keypair,
+    unit_limit: u32,
+    unit_price: u64,
+    mut business_instructions: Vec<Instruction>,
+    with_tip: bool,
+    tip_accounts: &[Pubkey],
+    tip_amount: f64,
+    durable_nonce: Option<DurableNonceInfo>,
+    enable_jito_sandwich_protection: bool,
+    layout: InstructionLayout,
+) -> Result<Vec<Instruction>, anyhow::Error> {
+    let mut instructions = crate::perf::arena::instruction_vec(business_instructions.len() + 5);
 
     // Add nonce instruction
-    add_nonce_instruction(&mut instructions, payer.as_ref(), durable_nonce.clone())?;
+    add_nonce_instruction(&mut instructions, payer, durable_nonce)?;
 
     // Add Jito 三明治攻击防护（如果启用）
     //
@@ -108,44 +180,51 @@ pub async fn build_transaction(
         });
     }
 
-    // Add tip transfer instruction
-    if with_tip && tip_amount > 0.0 {
-        let tip_lamports = sol_str_to_lamports(&tip_amount.to_string())
-            .ok_or_else(|| anyhow::anyhow!("无效的小费金额 '{}': 转换失败", tip_amount))?;
-        instructions.push(transfer(&payer.pubkey(), tip_account, tip_lamports));
+    // Build tip transfer instruction(s)
+    // 多个小费账户时平均拆分，余数计入第一笔；拆分后单笔为 0 时退化为单笔转账
+    let mut tip_instructions = Vec::new();
+    if with_tip && tip_amount > 0.0 && !tip_accounts.is_empty() {
+        for (tip_account, lamports) in split_tip(tip_accounts, tip_amount)? {
+            tip_instructions.push(transfer(&payer.pubkey(), &tip_account, lamports));
+        }
     }
 
-    // Add compute budget instructions
-    instructions.extend(compute_budget_instructions(unit_price, unit_limit));
-
-    // Add business instructions
-    instructions.extend(business_instructions);
-
-    // Get blockhash for transaction
-    let blockhash = get_transaction_blockhash(recent_blockhash, durable_nonce.clone())?;
+    let (cu_limit, cu_price) = take_compute_budget_overrides(&mut business_instructions);
+    let business_instructions = layout.arrange_business_instructions(business_instructions);
+    let compute_budget =
+        compute_budget_instructions(cu_price.unwrap_or(unit_price), cu_limit.unwrap_or(unit_limit));
+    match layout.tip {
+        TipPlacement::BeforeComputeBudget => {
+            instructions.extend(tip_instructions);
+            instructions.extend(compute_budget);
+            instructions.extend(business_instructions);
+        },
+        TipPlacement::AfterComputeBudget => {
+            instructions.extend(compute_budget);
+            instructions.extend(tip_instructions);
+            instructions.extend(business_instructions);
+        },
+        TipPlacement::Last => {
+            instructions.extend(compute_budget);
+            instructions.extend(business_instructions);
+            instructions.extend(tip_instructions);
+        },
+    }
 
-    // Build transaction
-    build_versioned_transaction(
-        payer,
-        instructions,
-        address_lookup_table_account,
-        blockhash,
-        middleware_manager,
-        protocol_name,
-        is_buy,
-    )
-    .await
+    Ok(instructions)
 }
 
 /// Low-level function for building versioned transactions
 async fn build_versioned_transaction(
     payer: Arc<Keypair>,
+    fee_payer: Option<FeePayerRef>,
     instructions: Vec<Instruction>,
     address_lookup_table_account: Option<AddressLookupTableAccount>,
     blockhash: Hash,
     middleware_manager: Option<Arc<MiddlewareManager>>,
     protocol_name: &str,
     is_buy: bool,
+    metadata: Option<&TradeMetadata>,
 ) -> Result<VersionedTransaction, anyhow::Error> {
     let full_instructions = match middleware_manager {
         Some(middleware_manager) => middleware_manager
@@ -153,28 +232,51 @@ async fn build_versioned_transaction(
                 instructions,
                 protocol_name.to_string(),
                 is_buy,
+                metadata,
             )?,
         None => instructions,
     };
 
+    let fee_payer_pubkey = match &fee_payer {
+        Some(fee_payer) => validate_fee_payer(fee_payer, &payer.pubkey())?,
+        None => payer.pubkey(),
+    };
+    if let Some(alt) = &address_lookup_table_account {
+        lookup_table_manager().record_use(&alt.key);
+    }
     // 使用预分配的交易构建器以降低延迟
     let mut builder = acquire_builder();
 
     let versioned_msg = builder.build_zero_alloc(
-        &payer.pubkey(),
+        &fee_payer_pubkey,
         &full_instructions,
         address_lookup_table_account,
         blockhash,
     );
+    // 消息已编译，归还指令缓冲区
+    recycle_instructions(full_instructions);
 
-    let msg_bytes = versioned_msg.serialize();
-    let signature = payer
-        .try_sign_message(&msg_bytes)
-        .expect("交易签名失败：payer 密钥无效或消息序列化错误");
-    let tx = VersionedTransaction { signatures: vec![signature], message: versioned_msg };
+    let sign_start = std::time::Instant::now();
+    let signatures = match &fee_payer {
+        Some(fee_payer) => sign_with_fee_payer(&versioned_msg, &payer, fee_payer)?,
+        None => vec![sign_message(&payer, versioned_msg.serialize()).await?],
+    };
+    crate::trading::timing::record_sign(sign_start.elapsed());
+    let tx = VersionedTransaction { signatures, message: versioned_msg };
 
     // 归还构建器到池
     release_builder(builder);
 
+    // 校验交易大小（自定义指令或过多账户可能导致超出单包限制）
+    let tx_size = bincode::serialized_size(&tx)
+        .map_err(|e| anyhow::anyhow!("交易序列化失败: {}", e))? as usize;
+    if tx_size > MAX_TRANSACTION_SIZE {
+        return Err(anyhow::anyhow!(
+            "Transaction too large: {} bytes > {} bytes (reduce extra instructions or use an address lookup table)",
+            tx_size,
+            MAX_TRANSACTION_SIZE
+        ));
+    }
+
     Ok(tx)
 }
@@ -0,0 +1,217 @@
+//! 预签名交易模板
+//!
+//! 对延迟敏感的场景（狙击、跟单），账户与指令数据可以提前确定，
+//! 触发时只剩下「换上最新 blockhash / nonce 并重新签名」这一步。
+//! 模板在创建时完成消息编译与序列化，并记录 blockhash 在消息字节中的偏移量，
+//! 触发时直接就地替换 32 字节并签名，避免重新编译消息。
+
+use anyhow::{Result, anyhow};
+use solana_hash::Hash;
+use solana_sdk::{
+    instruction::Instruction,
+    message::{AddressLookupTableAccount, VersionedMessage},
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+use std::sync::Arc;
+
+use crate::common::nonce_cache::DurableNonceInfo;
+use crate::constants::trade_consts::MAX_TRANSACTION_SIZE;
+use crate::swqos::serialization::{acquire_buffer, release_buffer};
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::core::transaction_pool::{acquire_builder, release_builder};
+
+/// 预编译的交易模板
+#[derive(Clone)]
+pub struct TransactionTemplate {
+    payer: Arc<Keypair>,
+    message: VersionedMessage,
+    message_bytes: Vec<u8>,
+    blockhash_offset: usize,
+    swqos_type: SwqosType,
+    trade_type: TradeType,
+    uses_nonce: bool,
+}
+
+impl TransactionTemplate {
+    /// 由完整指令列表创建模板
+    ///
+    /// `placeholder_blockhash` 仅用于编译消息，触发时会被替换；
+    /// 若指令中包含 nonce advance，请将 `uses_nonce` 设为 true 并用 [`Self::sign_with_nonce`] 触发。
+    pub fn new(
+        payer: Arc<Keypair>,
+        instructions: &[Instruction],
+        address_lookup_table_account: Option<AddressLookupTableAccount>,
+        placeholder_blockhash: Hash,
+        swqos_type: SwqosType,
+        trade_type: TradeType,
+        uses_nonce: bool,
+    ) -> Result<Self> {
+        let mut builder = acquire_builder();
+        let message = builder.build_zero_alloc(
+            &payer.pubkey(),
+            instructions,
+            address_lookup_table_account,
+            placeholder_blockhash,
+        );
+        release_builder(builder);
+        Self::from_message(payer, message, swqos_type, trade_type, uses_nonce)
+    }
+
+    /// 由已编译的消息创建模板（例如从 `CallbackContext.transaction.message` 获取）
+    pub fn from_message(
+        payer: Arc<Keypair>,
+        message: VersionedMessage,
+        swqos_type: SwqosType,
+        trade_type: TradeType,
+        uses_nonce: bool,
+    ) -> Result<Self> {
+        if message.header().num_required_signatures != 1 {
+            return Err(anyhow!(
+                "Transaction template only supports a single signer, got {}",
+                message.header().num_required_signatures
+            ));
+        }
+        if message.static_account_keys().first() != Some(&payer.pubkey()) {
+            return Err(anyhow!("Transaction template fee payer does not match payer keypair"));
+        }
+
+        let message_bytes = message.serialize();
+        // 1 字节签名数量前缀 + 64 字节签名
+        let tx_size = message_bytes.len() + 1 + 64;
+        if tx_size > MAX_TRANSACTION_SIZE {
+            return Err(anyhow!(
+                "Transaction too large: {} bytes > {} bytes",
+                tx_size,
+                MAX_TRANSACTION_SIZE
+            ));
+        }
+        let blockhash_offset = blockhash_offset(&message);
+        let end = blockhash_offset + 32;
+        if message_bytes.len() < end
+            || &message_bytes[blockhash_offset..end] != message.recent_blockhash().as_ref()
+        {
+            return Err(anyhow!("Failed to locate blockhash in serialized message"));
+        }
+
+        Ok(Self {
+            payer,
+            message,
+            message_bytes,
+            blockhash_offset,
+            swqos_type,
+            trade_type,
+            uses_nonce,
+        })
+    }
+
+    /// 模板对应的 SWQOS 类型
+    pub fn swqos_type(&self) -> SwqosType {
+        self.swqos_type
+    }
+
+    /// 模板对应的交易类型
+    pub fn trade_type(&self) -> TradeType {
+        self.trade_type
+    }
+
+    /// 模板是否基于 durable nonce
+    pub fn uses_nonce(&self) -> bool {
+        self.uses_nonce
+    }
+
+    /// 已编译的消息（blockhash 为创建时的占位值）
+    pub fn message(&self) -> &VersionedMessage {
+        &self.message
+    }
+
+    /// 换上新的 blockhash 并签名
+    pub fn sign_with_blockhash(&self, blockhash: &Hash) -> Result<VersionedTransaction> {
+        let mut buffer = acquire_buffer();
+        buffer.clear();
+        buffer.extend_from_slice(&self.message_bytes);
+        buffer[self.blockhash_offset..self.blockhash_offset + 32]
+            .copy_from_slice(blockhash.as_ref());
+        let signature = self.payer.try_sign_message(&buffer);
+        release_buffer(buffer);
+        let signature: Signature = signature?;
+
+        let mut message = self.message.clone();
+        message.set_recent_blockhash(*blockhash);
+        Ok(VersionedTransaction { signatures: vec![signature], message })
+    }
+
+    /// 使用 durable nonce 的当前值签名
+    pub fn sign_with_nonce(
+        &self,
+        durable_nonce: &DurableNonceInfo,
+    ) -> Result<VersionedTransaction> {
+        if !self.uses_nonce {
+            return Err(anyhow!("Transaction template was not built with a nonce advance"));
+        }
+        let nonce = durable_nonce
+            .current_nonce
+            .ok_or_else(|| anyhow!("Current nonce not set in DurableNonceInfo"))?;
+        self.sign_with_blockhash(&nonce)
+    }
+}
+
+/// 计算 recent_blockhash 在序列化消息中的偏移量
+///
+/// 布局：[版本前缀(仅 V0)] + header(3) + short_vec(账户数) + 账户(32 * n) + blockhash
+fn blockhash_offset(message: &VersionedMessage) -> usize {
+    let (prefix, num_keys) = match message {
+        VersionedMessage::Legacy(m) => (0, m.account_keys.len()),
+        VersionedMessage::V0(m) => (1, m.account_keys.len()),
+    };
+    prefix + 3 + short_vec_len(num_keys) + num_keys * 32
+}
+
+#[inline]
+fn short_vec_len(mut len: usize) -> usize {
+    let mut bytes = 1;
+    while len >= 0x80 {
+        len >>= 7;
+        bytes += 1;
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_system_interface::instruction::transfer;
+
+    #[test]
+    fn test_sign_with_blockhash_matches_fresh_build() {
+        let payer = Arc::new(Keypair::new());
+        let ixs = vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)];
+        let template = TransactionTemplate::new(
+            payer.clone(),
+            &ixs,
+            None,
+            Hash::default(),
+            SwqosType::Default,
+            TradeType::Buy,
+            false,
+        )
+        .unwrap();
+
+        let fresh = Hash::new_from_array([7u8; 32]);
+        let tx = template.sign_with_blockhash(&fresh).unwrap();
+        assert_eq!(*tx.message.recent_blockhash(), fresh);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+        let no_nonce = DurableNonceInfo { nonce_account: None, current_nonce: None };
+        assert!(template.sign_with_nonce(&no_nonce).is_err());
+    }
+
+    #[test]
+    fn test_short_vec_len() {
+        assert_eq!(short_vec_len(0), 1);
+        assert_eq!(short_vec_len(127), 1);
+        assert_eq!(short_vec_len(128), 2);
+        assert_eq!(short_vec_len(16_384), 3);
+    }
+}
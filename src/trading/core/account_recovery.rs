@@ -0,0 +1,245 @@
+//! 缺失账户的自动恢复
+//!
+//! 协议参数过期时（CLMM 价格越过已加载的 tick array、creator vault 变更、缓存的 PDA 与链上不一致等），
+//! 构建或模拟会因缺少账户失败。执行器在这类失败后从错误与程序日志中解析出缺失的账户，
+//! 通过 RPC 重新加载协议参数并重新构建一次；只重试一次，仍失败时返回新的错误。
+
+use crate::common::SolanaRpcClient;
+use crate::instruction::utils::bonk;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::core::params_cache::params_cache;
+use anyhow::{Result, anyhow};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Anchor `AccountNotInitialized`
+const ANCHOR_ACCOUNT_NOT_INITIALIZED: u32 = 3012;
+/// Anchor `AccountOwnedByWrongProgram`（账户不存在时 owner 为系统程序）
+const ANCHOR_ACCOUNT_OWNED_BY_WRONG_PROGRAM: u32 = 3007;
+/// Anchor `ConstraintSeeds`
+const ANCHOR_CONSTRAINT_SEEDS: u32 = 2006;
+
+/// 模拟失败的详细信息（作为 `anyhow::Error` 返回，可通过 `downcast_ref` 取得）
+#[derive(Debug, Clone)]
+pub struct SimulationFailure {
+    /// RPC 返回的原始错误文本
+    pub message: String,
+    /// 解析后的交易错误（无法解析时为 `None`）
+    pub error: Option<TransactionError>,
+    /// 程序日志
+    pub logs: Vec<String>,
+}
+
+impl fmt::Display for SimulationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SimulationFailure {}
+
+/// 缺失账户的类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingAccountKind {
+    /// 账户不存在或未传入
+    NotFound,
+    /// 账户存在但未初始化（或 owner 不符）
+    NotInitialized,
+    /// 传入的 PDA 与程序推导的地址不一致；`expected` 为程序期望的地址
+    SeedsMismatch { expected: Option<Pubkey> },
+}
+
+/// 从错误中解析出的缺失账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingAccount {
+    pub kind: MissingAccountKind,
+    /// 账户地址（错误或日志中给出时）
+    pub pubkey: Option<Pubkey>,
+    /// Anchor 日志中的账户名（如 `tick_array_lower`）
+    pub name: Option<String>,
+}
+
+impl fmt::Display for MissingAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.kind)?;
+        if let Some(name) = &self.name {
+            write!(f, " account={}", name)?;
+        }
+        if let Some(pubkey) = &self.pubkey {
+            write!(f, " pubkey={}", pubkey)?;
+        }
+        Ok(())
+    }
+}
+
+impl MissingAccount {
+    /// 从模拟失败中解析缺失账户；与账户无关的失败（滑点、余额不足等）返回 `None`
+    pub fn from_simulation(failure: &SimulationFailure) -> Option<Self> {
+        let kind = match failure.error.as_ref()? {
+            TransactionError::AccountNotFound | TransactionError::ProgramAccountNotFound => {
+                MissingAccountKind::NotFound
+            },
+            TransactionError::InstructionError(_, error) => match error {
+                InstructionError::MissingAccount | InstructionError::NotEnoughAccountKeys => {
+                    MissingAccountKind::NotFound
+                },
+                InstructionError::UninitializedAccount
+                | InstructionError::Custom(ANCHOR_ACCOUNT_NOT_INITIALIZED)
+                | InstructionError::Custom(ANCHOR_ACCOUNT_OWNED_BY_WRONG_PROGRAM) => {
+                    MissingAccountKind::NotInitialized
+                },
+                InstructionError::Custom(ANCHOR_CONSTRAINT_SEEDS) => {
+                    let (left, right) = seeds_from_logs(&failure.logs);
+                    return Some(Self {
+                        kind: MissingAccountKind::SeedsMismatch { expected: right },
+                        pubkey: left,
+                        name: account_name_from_logs(&failure.logs),
+                    });
+                },
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            pubkey: None,
+            name: account_name_from_logs(&failure.logs),
+        })
+    }
+
+    /// 从错误中解析缺失账户：[`SimulationFailure`]，或构建时 RPC 返回的 `AccountNotFound: pubkey=...`
+    pub fn from_error(error: &anyhow::Error) -> Option<Self> {
+        if let Some(failure) = error.downcast_ref::<SimulationFailure>() {
+            return Self::from_simulation(failure);
+        }
+        let text = format!("{:#}", error);
+        let rest =
+            &text[text.find("AccountNotFound: pubkey=")? + "AccountNotFound: pubkey=".len()..];
+        let end = rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len());
+        let pubkey = Pubkey::from_str(&rest[..end]).ok();
+        Some(Self { kind: MissingAccountKind::NotFound, pubkey, name: None })
+    }
+}
+
+/// 解析 `AnchorError caused by account: <name>.`
+fn account_name_from_logs(logs: &[String]) -> Option<String> {
+    logs.iter().find_map(|log| {
+        let rest = &log[log.find("caused by account: ")? + "caused by account: ".len()..];
+        let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))?;
+        Some(rest[..end].to_string())
+    })
+}
+
+/// 解析 `ConstraintSeeds` 之后的 `Left:` / `Right:` 地址
+fn seeds_from_logs(logs: &[String]) -> (Option<Pubkey>, Option<Pubkey>) {
+    let pubkey_after = |label: &str| {
+        let index = logs.iter().position(|log| log.trim_end().ends_with(label))?;
+        let value = logs.get(index + 1)?.rsplit(' ').next()?;
+        Pubkey::from_str(value.trim()).ok()
+    };
+    (pubkey_after("Left:"), pubkey_after("Right:"))
+}
+
+/// 重新加载协议参数（含静态账户），保留调用方设置的选项
+///
+/// 同时移除该 mint 在 [`params_cache`] 中的条目，避免下次仍读取到过期的静态账户。
+pub async fn refresh_params(
+    rpc: &SolanaRpcClient,
+    protocol_params: &DexParamEnum,
+    mint: &Pubkey,
+) -> Result<DexParamEnum> {
+    let dex_type = protocol_params.dex_type();
+    let pool = match protocol_params {
+        DexParamEnum::PumpFun(_) => *mint,
+        DexParamEnum::PumpSwap(p) => p.pool,
+        DexParamEnum::Bonk(p) if p.pool_state == Pubkey::default() => {
+            bonk::get_pool_pda(mint, &crate::constants::WSOL_TOKEN_ACCOUNT)
+                .ok_or_else(|| anyhow!("Bonk pool PDA not found for mint {}", mint))?
+        },
+        DexParamEnum::Bonk(p) => p.pool_state,
+        DexParamEnum::RaydiumCpmm(p) => p.pool_state,
+        DexParamEnum::RaydiumAmmV4(p) => p.amm,
+        DexParamEnum::RaydiumClmm(p) => p.pool_state,
+        DexParamEnum::MeteoraDammV2(p) => p.pool,
+    };
+    if pool == Pubkey::default() {
+        return Err(anyhow!("{:?} params have no pool address to refresh from", dex_type));
+    }
+    params_cache().invalidate(&dex_type, mint);
+    let mut fresh = DexParamEnum::from_pool_force(rpc, dex_type, &pool).await?;
+    match (protocol_params, &mut fresh) {
+        (DexParamEnum::PumpFun(old), DexParamEnum::PumpFun(new)) => {
+            new.close_token_account_when_sell = old.close_token_account_when_sell;
+        },
+        (DexParamEnum::PumpSwap(old), DexParamEnum::PumpSwap(new)) => {
+            new.exact_quote_in = old.exact_quote_in;
+        },
+        (DexParamEnum::RaydiumAmmV4(old), DexParamEnum::RaydiumAmmV4(new)) => {
+            new.swap_mode = old.swap_mode;
+        },
+        (DexParamEnum::RaydiumClmm(old), DexParamEnum::RaydiumClmm(new)) => {
+            new.swap_mode = old.swap_mode;
+            new.tick_array_depth = old.tick_array_depth;
+        },
+        _ => {},
+    }
+    Ok(fresh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_missing_account() {
+        let failure = SimulationFailure {
+            message: "InstructionError(2, Custom(3012))".to_string(),
+            error: Some(TransactionError::InstructionError(2, InstructionError::Custom(3012))),
+            logs: logs(&[
+                "Program log: Instruction: Swap",
+                "Program log: AnchorError caused by account: tick_array_lower. Error Code: \
+                 AccountNotInitialized. Error Number: 3012.",
+            ]),
+        };
+        let missing = MissingAccount::from_simulation(&failure).unwrap();
+        assert_eq!(missing.kind, MissingAccountKind::NotInitialized);
+        assert_eq!(missing.name.as_deref(), Some("tick_array_lower"));
+
+        let (left, right) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let failure = SimulationFailure {
+            message: "InstructionError(1, Custom(2006))".to_string(),
+            error: Some(TransactionError::InstructionError(1, InstructionError::Custom(2006))),
+            logs: logs(&[
+                "Program log: AnchorError caused by account: coin_creator_vault_authority. \
+                 Error Code: ConstraintSeeds. Error Number: 2006.",
+                "Program log: Left:",
+                &format!("Program log: {}", left),
+                "Program log: Right:",
+                &format!("Program log: {}", right),
+            ]),
+        };
+        let missing = MissingAccount::from_simulation(&failure).unwrap();
+        assert_eq!(missing.kind, MissingAccountKind::SeedsMismatch { expected: Some(right) });
+        assert_eq!(missing.pubkey, Some(left));
+
+        let slippage = SimulationFailure {
+            message: "InstructionError(2, Custom(6001))".to_string(),
+            error: Some(TransactionError::InstructionError(2, InstructionError::Custom(6001))),
+            logs: Vec::new(),
+        };
+        assert!(MissingAccount::from_simulation(&slippage).is_none());
+
+        let pubkey = Pubkey::new_unique();
+        let error = anyhow!("RPC response error: AccountNotFound: pubkey={}: error", pubkey);
+        let missing = MissingAccount::from_error(&error).unwrap();
+        assert_eq!(missing.kind, MissingAccountKind::NotFound);
+        assert_eq!(missing.pubkey, Some(pubkey));
+    }
+}
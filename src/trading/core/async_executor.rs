@@ -6,23 +6,28 @@ use solana_hash::Hash;
 use solana_sdk::message::AddressLookupTableAccount;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Keypair, signature::Signature,
+    transaction::VersionedTransaction,
 };
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::{str::FromStr, sync::Arc, time::Instant};
-use tokio::sync::Notify;
+use tokio::sync::{Notify, watch};
 
 use crate::{
+    common::cancellation::{CancellationToken, cancellable, cancelled_error, wait_cancelled},
+    common::fee_ledger::{FeeLedger, TxFee},
     common::nonce_cache::DurableNonceInfo,
-    common::{GasFeeStrategy, SolanaRpcClient},
-    constants::swqos::{
-        SWQOS_MIN_TIP_ASTRALANE, SWQOS_MIN_TIP_BLOCKRAZOR, SWQOS_MIN_TIP_BLOXROUTE,
-        SWQOS_MIN_TIP_DEFAULT, SWQOS_MIN_TIP_FLASHBLOCK, SWQOS_MIN_TIP_JITO,
-        SWQOS_MIN_TIP_LIGHTSPEED, SWQOS_MIN_TIP_NEXTBLOCK, SWQOS_MIN_TIP_NODE1,
-        SWQOS_MIN_TIP_SOYAS, SWQOS_MIN_TIP_SPEEDLANDING, SWQOS_MIN_TIP_STELLIUM,
-        SWQOS_MIN_TIP_TEMPORAL, SWQOS_MIN_TIP_ZERO_SLOT,
+    common::{GasFeeStrategy, GasFeeStrategyType, GasFeeStrategyValue, SolanaRpcClient},
+    perf::arena::{clone_instructions, recycle_instructions},
+    swqos::common::{ConfirmConfig, confirm_scope},
+    swqos::fanout::{SwqosFanout, fanout_delay},
+    swqos::{SwqosClient, SwqosType, TradeType, response},
+    trading::{
+        CallbackContext, CallbackRegistry, MiddlewareManager, TradeDetails,
+        common::{FeePayerRef, InstructionLayout, build_transaction},
+        store::{Journal, JournalEntry},
+        submission_queue::SubmissionTicket,
+        timing::{self, ProviderTiming},
     },
-    swqos::{SwqosClient, SwqosType, TradeType},
-    trading::{MiddlewareManager, common::build_transaction},
 };
 
 #[repr(align(64))]
@@ -40,6 +45,10 @@ fn is_landed_error(error: &anyhow::Error) -> bool {
 
     // If it's a TradeError with a non-zero code, the tx landed but failed on-chain
     if let Some(trade_error) = error.downcast_ref::<TradeError>() {
+        // Confirmation timeout / caller cancellation: tx not observed on-chain (yet)
+        if trade_error.is_confirmation_timed_out() || trade_error.is_cancelled() {
+            return false;
+        }
         // Code 500 with "timed out" message means tx never landed
         if trade_error.code == 500 && trade_error.message.contains("timed out") {
             return false;
@@ -65,6 +74,8 @@ struct ResultCollector {
     completed_count: Arc<AtomicUsize>,
     total_tasks: usize,
     notify: Arc<Notify>, // 🔧 事件驱动：通知机制
+    /// 任一交易上链（成功或失败）后置为 true，其余 provider 据此取消提交与确认轮询
+    landed: watch::Sender<bool>,
 }
 
 impl ResultCollector {
@@ -76,9 +87,16 @@ impl ResultCollector {
             completed_count: Arc::new(AtomicUsize::new(0)),
             total_tasks: capacity,
             notify: Arc::new(Notify::new()), // 🔧 创建通知实例
+            landed: watch::channel(false).0,
         }
     }
 
+    /// 等待任一交易上链（同一 durable nonce 下其余交易已不可能成功）
+    async fn wait_landed(&self) {
+        let mut landed = self.landed.subscribe();
+        let _ = landed.wait_for(|landed| *landed).await;
+    }
+
     fn submit(&self, result: TaskResult) {
         // 🚀 优化：ArrayQueue 内部已保证同步，无需额外 fence
         let is_success = result.success;
@@ -92,6 +110,9 @@ impl ResultCollector {
             // 🔧 Tx landed but failed (e.g., ExceededSlippage) - nonce is consumed, no point waiting
             self.landed_failed_flag.store(true, Ordering::Release);
         }
+        if is_success || is_landed_failed {
+            self.landed.send_replace(true);
+        }
 
         self.completed_count.fetch_add(1, Ordering::Release);
 
@@ -204,10 +225,136 @@ impl ResultCollector {
     }
 }
 
+/// 一个 provider 的发送计划：使用的 gas 配置与小费账户
+pub(crate) struct ProviderPlan {
+    pub swqos_client: Arc<SwqosClient>,
+    pub config: (SwqosType, GasFeeStrategyType, GasFeeStrategyValue),
+    /// 已按拆分数选取的小费账户；为空表示不追加小费
+    pub tip_accounts: Vec<Pubkey>,
+}
+
+impl ProviderPlan {
+    /// 实际支付的小费（SOL），不追加小费时为 0
+    pub fn tip(&self) -> f64 {
+        if self.tip_accounts.is_empty() { 0.0 } else { self.config.2.tip }
+    }
+}
+
+/// 发送给多个 provider 时各笔交易的上链方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LandingMode {
+    /// 只允许一个 provider（未使用 durable nonce 的买入）
+    Single,
+    /// 各笔交易共用 durable nonce，最多一笔上链
+    Exclusive,
+    /// 各笔交易可分别上链（未使用 durable nonce 的卖出）
+    Independent,
+}
+
+impl LandingMode {
+    /// swap 的规则：使用 durable nonce 时最多一笔上链；未使用时只有卖出允许多个 provider
+    pub fn for_swap(is_buy: bool, durable_nonce: bool) -> Self {
+        match (durable_nonce, is_buy) {
+            (true, _) => Self::Exclusive,
+            (false, true) => Self::Single,
+            (false, false) => Self::Independent,
+        }
+    }
+}
+
+/// 按 gas 策略选出参与发送的 provider 与配置（并行执行器、流水线、外部交易与篮子共用）
+///
+/// - `with_tip = false` 时只使用 `SwqosType::Default`；
+/// - 需要小费时跳过低于 provider 最低小费的配置，小费地址无效的 provider 跳过；
+/// - 小费账户为空（如 Default RPC）或当前集群不支持小费时不追加小费；
+/// - 多于一个计划且为 [`LandingMode::Single`] 时报错（多笔交易需要 durable nonce）；
+/// - 最后按费用上限与小时预算校验：各笔可分别上链时按总花费预留预算。
+pub(crate) fn plan_providers(
+    swqos_clients: &[Arc<SwqosClient>],
+    gas_fee_strategy: &GasFeeStrategy,
+    trade_type: TradeType,
+    with_tip: bool,
+    landing: LandingMode,
+) -> Result<Vec<ProviderPlan>> {
+    if swqos_clients.is_empty() {
+        return Err(anyhow!("swqos_clients is empty"));
+    }
+    if !with_tip && !swqos_clients.iter().any(|swqos| swqos.get_swqos_type() == SwqosType::Default)
+    {
+        return Err(anyhow!("No Rpc Default Swqos configured."));
+    }
+
+    let strategies = gas_fee_strategy.get_strategies(trade_type);
+    let supports_tips = crate::common::cluster::current_cluster().supports_tips();
+    let mut plans = Vec::new();
+    for swqos_client in swqos_clients {
+        let swqos_type = swqos_client.get_swqos_type();
+        if !with_tip && swqos_type != SwqosType::Default {
+            continue;
+        }
+        // 获取小费地址：空字符串表示客户端不支持小费（如 Default RPC）；小费账户仅主网有效
+        let tip_account_str = swqos_client.resolve_tip_account()?;
+        let tip_account = if !with_tip
+            || swqos_type == SwqosType::Default
+            || tip_account_str.is_empty()
+            || !supports_tips
+        {
+            None
+        } else {
+            match Pubkey::from_str(&tip_account_str) {
+                Ok(pubkey) => Some(pubkey),
+                Err(e) => {
+                    eprintln!(
+                        "⚠️  [{}] 跳过：无效的小费接收地址 '{}': {}",
+                        swqos_type, tip_account_str, e
+                    );
+                    continue;
+                },
+            }
+        };
+        for config in strategies.iter().filter(|config| config.0 == swqos_type) {
+            // 当需要 tip 且不是 Default 时，按 provider 最低小费进行筛选
+            if with_tip && swqos_type != SwqosType::Default && config.2.tip < swqos_type.min_tip() {
+                println!(
+                    "⚠️ Config filtered: {:?} tip {} is below minimum required tip {}",
+                    swqos_type,
+                    config.2.tip,
+                    swqos_type.min_tip()
+                );
+                continue;
+            }
+            // 小费拆分：按策略配置选取多个小费账户
+            let tip_accounts = match tip_account {
+                Some(tip_account) => swqos_type.pick_tip_accounts(
+                    tip_account,
+                    gas_fee_strategy.tip_split_for(swqos_type, config.2.tip),
+                ),
+                None => vec![],
+            };
+            plans.push(ProviderPlan {
+                swqos_client: swqos_client.clone(),
+                config: *config,
+                tip_accounts,
+            });
+        }
+    }
+
+    if plans.is_empty() {
+        return Err(anyhow!("No available gas fee strategy configs"));
+    }
+    if plans.len() > 1 && landing == LandingMode::Single {
+        return Err(anyhow!("Multiple swqos transactions require durable_nonce to be set."));
+    }
+    let configs: Vec<_> = plans.iter().map(|plan| plan.config).collect();
+    gas_fee_strategy.enforce_caps(&configs, with_tip, landing != LandingMode::Independent)?;
+    Ok(plans)
+}
+
 /// 🔧 修复：返回Vec<Signature>支持多SWQOS并发交易
 pub async fn execute_parallel(
     swqos_clients: Vec<Arc<SwqosClient>>,
     payer: Arc<Keypair>,
+    fee_payer: Option<FeePayerRef>,
     rpc: Option<Arc<SolanaRpcClient>>,
     instructions: Vec<Instruction>,
     address_lookup_table_account: Option<AddressLookupTableAccount>,
@@ -222,157 +369,85 @@ pub async fn execute_parallel(
     on_transaction_signed: Option<crate::trading::CallbackRef>,
     callback_execution_mode: crate::common::CallbackExecutionMode,
     enable_jito_sandwich_protection: bool,
+    fee_ledger: Option<Arc<FeeLedger>>,
+    journal: Option<Journal>,
+    instruction_layout: InstructionLayout,
+    details: Arc<TradeDetails>,
+    submission: Option<SubmissionTicket>,
+    confirm: ConfirmConfig,
+    cancellation_token: Option<CancellationToken>,
+    fanout: Option<Arc<SwqosFanout>>,
 ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
     let _exec_start = Instant::now();
 
-    if swqos_clients.is_empty() {
-        return Err(anyhow!("swqos_clients is empty"));
-    }
-
-    if !with_tip
-        && !swqos_clients
-            .iter()
-            .any(|swqos| matches!(swqos.get_swqos_type(), SwqosType::Default))
-    {
-        return Err(anyhow!("No Rpc Default Swqos configured."));
-    }
-
     let cores = core_affinity::get_core_ids().unwrap();
     let instructions = Arc::new(instructions);
 
-    // 预先计算所有有效的组合
-    let task_configs: Vec<_> = swqos_clients
-        .iter()
-        .enumerate()
-        .filter(|(_, swqos_client)| {
-            with_tip || matches!(swqos_client.get_swqos_type(), SwqosType::Default)
-        })
-        .flat_map(|(i, swqos_client)| {
-            let gas_fee_strategy_configs = gas_fee_strategy.get_strategies(if is_buy {
-                TradeType::Buy
-            } else {
-                TradeType::Sell
-            });
-            gas_fee_strategy_configs
-                .into_iter()
-                .filter(|config| config.0.eq(&swqos_client.get_swqos_type()))
-                .filter(|config| {
-                    // 当需要 tip 且不是 Default 时，按 provider 最低小费进行筛选
-                    if with_tip && !matches!(config.0, SwqosType::Default) {
-                        let min_tip = match config.0 {
-                            SwqosType::Jito => SWQOS_MIN_TIP_JITO,
-                            SwqosType::NextBlock => SWQOS_MIN_TIP_NEXTBLOCK,
-                            SwqosType::ZeroSlot => SWQOS_MIN_TIP_ZERO_SLOT,
-                            SwqosType::Temporal => SWQOS_MIN_TIP_TEMPORAL,
-                            SwqosType::Bloxroute => SWQOS_MIN_TIP_BLOXROUTE,
-                            SwqosType::Node1 => SWQOS_MIN_TIP_NODE1,
-                            SwqosType::FlashBlock => SWQOS_MIN_TIP_FLASHBLOCK,
-                            SwqosType::BlockRazor => SWQOS_MIN_TIP_BLOCKRAZOR,
-                            SwqosType::Astralane => SWQOS_MIN_TIP_ASTRALANE,
-                            SwqosType::Stellium => SWQOS_MIN_TIP_STELLIUM,
-                            SwqosType::Lightspeed => SWQOS_MIN_TIP_LIGHTSPEED,
-                            SwqosType::Soyas => SWQOS_MIN_TIP_SOYAS,
-                            SwqosType::Speedlanding => SWQOS_MIN_TIP_SPEEDLANDING,
-                            SwqosType::Default => SWQOS_MIN_TIP_DEFAULT,
-                        };
-                        if config.2.tip < min_tip {
-                            println!(
-                                "⚠️ Config filtered: {:?} tip {} is below minimum required tip {}",
-                                config.0, config.2.tip, min_tip
-                            );
-                        }
-                        config.2.tip >= min_tip
-                    } else {
-                        true
-                    }
-                })
-                .map(move |config| (i, swqos_client.clone(), config))
-        })
-        .collect();
-
-    if task_configs.is_empty() {
-        return Err(anyhow!("No available gas fee strategy configs"));
-    }
-
-    if is_buy && task_configs.len() > 1 && durable_nonce.is_none() {
-        return Err(anyhow!("Multiple swqos transactions require durable_nonce to be set.",));
-    }
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let plans = plan_providers(
+        &swqos_clients,
+        &gas_fee_strategy,
+        trade_type,
+        with_tip,
+        LandingMode::for_swap(is_buy, durable_nonce.is_some()),
+    )?;
 
     // Task preparation completed
 
-    let collector = Arc::new(ResultCollector::new(task_configs.len()));
+    let collector = Arc::new(ResultCollector::new(plans.len()));
     let _spawn_start = Instant::now();
 
-    for (i, swqos_client, gas_fee_strategy_config) in task_configs {
+    for (i, plan) in plans.into_iter().enumerate() {
         let core_id = cores[i % cores.len()];
         let payer = payer.clone();
+        let fee_payer = fee_payer.clone();
         let instructions = instructions.clone();
         let middleware_manager = middleware_manager.clone();
-        let swqos_type = swqos_client.get_swqos_type();
-
-        // 获取小费地址，优雅处理不支持小费的客户端
-        let tip_account_str = swqos_client.get_tip_account()?;
-        let (tip_account, should_use_tip) = if tip_account_str.is_empty() {
-            // 空字符串表示客户端不支持小费（如 Default RPC）
-            // 使用一个默认地址，但后续会通过 should_use_tip 禁用小费
-            (Pubkey::default(), false)
-        } else {
-            // 非空字符串，尝试转换为 Pubkey
-            match Pubkey::from_str(&tip_account_str) {
-                Ok(pubkey) => (pubkey, true),
-                Err(e) => {
-                    // 转换失败，记录错误并跳过此 SWQOS
-                    eprintln!(
-                        "⚠️  [{}] 跳过：无效的小费接收地址 '{}': {}",
-                        swqos_type, tip_account_str, e
-                    );
-                    collector.submit(TaskResult {
-                        success: false,
-                        signature: Signature::default(),
-                        error: Some(anyhow::anyhow!("无效的小费接收地址: {}", e)),
-                        _swqos_type: swqos_type,
-                        landed_on_chain: false,
-                    });
-                    continue;
-                },
-            }
-        };
-
+        let swqos_type = plan.swqos_client.get_swqos_type();
+        let swqos_client = plan.swqos_client.clone();
         let collector = collector.clone();
         let on_transaction_signed = on_transaction_signed.clone();
 
-        let tip = gas_fee_strategy_config.2.tip;
-        let unit_limit = gas_fee_strategy_config.2.cu_limit;
-        let unit_price = gas_fee_strategy_config.2.cu_price;
+        let tip_amount = plan.tip();
+        let tip_accounts = plan.tip_accounts;
+        let unit_limit = plan.config.2.cu_limit;
+        let unit_price = plan.config.2.cu_price;
         let rpc = rpc.clone();
         let durable_nonce = durable_nonce.clone();
         let address_lookup_table_account = address_lookup_table_account.clone();
+        let fee_ledger = fee_ledger.clone();
+        let journal = journal.clone();
+        let details = details.clone();
+        let submission = submission.clone();
+        let token = cancellation_token.clone();
+        let delay = fanout_delay(fanout.as_deref(), swqos_type);
 
-        tokio::spawn(async move {
+        let task = response::scope(timing::scope(async move {
             let _task_start = Instant::now();
             core_affinity::set_for_current(core_id);
 
-            // 判断是否使用小费：需要同时满足用户配置、SWQOS支持、地址有效
-            let use_tip = with_tip && should_use_tip && swqos_type != SwqosType::Default;
-            let tip_amount = if use_tip { tip } else { 0.0 };
+            let use_tip = !tip_accounts.is_empty();
 
             let _build_start = Instant::now();
             let transaction = match build_transaction(
                 payer,
+                fee_payer,
                 rpc,
                 unit_limit,
                 unit_price,
-                instructions.as_ref().clone(),
+                clone_instructions(&instructions),
                 address_lookup_table_account,
                 recent_blockhash,
                 middleware_manager,
                 protocol_name,
                 is_buy,
                 use_tip,
-                &tip_account,
+                &tip_accounts,
                 tip_amount,
                 durable_nonce,
                 enable_jito_sandwich_protection,
+                instruction_layout,
+                details.metadata.as_deref(),
             )
             .await
             {
@@ -389,72 +464,160 @@ pub async fn execute_parallel(
                     return;
                 },
             };
+            // 最后一个完成构建的任务归还共享指令的缓冲区
+            if let Ok(instructions) = Arc::try_unwrap(instructions) {
+                recycle_instructions(instructions);
+            }
+            // 签名完成，释放构建名额（全部 provider 完成后名额交给下一笔交易）
+            let provider_queue = submission.map(|ticket| (ticket.queue, ticket.urgency));
+            let sign_elapsed = timing::stage_marks().sign.unwrap_or_default();
+            let build_elapsed = _build_start.elapsed().saturating_sub(sign_elapsed);
+            let provider_timing = transaction.signatures.first().map(|signature| ProviderTiming {
+                swqos_type,
+                signature: *signature,
+                build: build_elapsed,
+                sign: sign_elapsed,
+                submit: None,
+                confirm: None,
+                landed_slot: None,
+            });
 
             // 🎯 调用交易签名回调（在发送前）
-            // 根据 callback_execution_mode 选择同步或异步执行
+            // 按注册表顺序执行；各回调按自身模式（或 callback_execution_mode）同步或异步执行
             if let Some(callback) = &on_transaction_signed {
-                let callback_clone = callback.clone();
-                let tx_clone = transaction.clone();
-                let swqos_type_clone = swqos_type;
-                let trade_type_clone = if is_buy { TradeType::Buy } else { TradeType::Sell };
-                let with_tip_clone = swqos_type != SwqosType::Default;
-                let tip_amount_clone = tip_amount;
-                let execution_mode = callback_execution_mode;
-
-                use crate::trading::CallbackContext;
-                let context = CallbackContext::new(
-                    tx_clone,
-                    swqos_type_clone,
-                    trade_type_clone,
-                    with_tip_clone,
-                    tip_amount_clone,
-                );
+                let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+                let mut context = CallbackContext::new(
+                    transaction.clone(),
+                    swqos_type,
+                    trade_type,
+                    swqos_type != SwqosType::Default,
+                    tip_amount,
+                )
+                .with_details(details.clone());
+                if let Some(provider_timing) = provider_timing {
+                    context = context.with_timing(provider_timing);
+                }
 
-                match execution_mode {
-                    crate::common::CallbackExecutionMode::Sync => {
-                        // 同步模式：等待回调完成，失败则阻止交易发送
-                        if let Err(e) = callback_clone.on_transaction_signed(context).await {
-                            eprintln!(
-                                "[Callback Error] on_transaction_signed failed (Sync mode): {:?}",
-                                e
-                            );
-                            collector.submit(TaskResult {
-                                success: false,
-                                signature: Signature::default(),
-                                error: Some(anyhow!("Callback failed: {}", e)),
-                                _swqos_type: swqos_type,
-                                landed_on_chain: false,
-                            });
-                            return;
-                        }
-                    },
-                    crate::common::CallbackExecutionMode::Async => {
-                        // 异步模式：不阻塞交易发送
-                        tokio::spawn(async move {
-                            if let Err(e) = callback_clone.on_transaction_signed(context).await {
-                                eprintln!(
-                                    "[Callback Error] on_transaction_signed failed (Async mode): {:?}",
-                                    e
-                                );
-                            }
-                        });
+                let single;
+                let registry = match callback.as_registry() {
+                    Some(registry) => registry,
+                    None => {
+                        single = CallbackRegistry::new().with_callback(callback.clone(), 0, None);
+                        &single
                     },
+                };
+                if let Err(e) = registry.dispatch(context, callback_execution_mode).await {
+                    // 同步回调失败：阻止交易发送
+                    eprintln!("[Callback Error] on_transaction_signed failed (Sync mode): {:?}", e);
+                    collector.submit(TaskResult {
+                        success: false,
+                        signature: Signature::default(),
+                        error: Some(anyhow!("Callback failed: {}", e)),
+                        _swqos_type: swqos_type,
+                        landed_on_chain: false,
+                    });
+                    return;
                 }
             }
 
             // Transaction sent
 
-            let _send_start = Instant::now();
-            let mut err: Option<anyhow::Error> = None;
-            let landed_on_chain;
-            let success = match swqos_client
-                .send_transaction(
-                    if is_buy { TradeType::Buy } else { TradeType::Sell },
-                    &transaction,
-                    wait_transaction_confirmed,
+            // 分发顺序：延后的 provider 先等待；等待确认时若其他 provider 已上链则不再发送
+            // 调用方取消时结束等待，由下方的取消检查处理
+            if !delay.is_zero()
+                && cancellable(
+                    token.as_ref(),
+                    stagger(&collector, delay, wait_transaction_confirmed),
                 )
                 .await
+                .unwrap_or(false)
             {
+                collector.submit(TaskResult {
+                    success: false,
+                    signature: Signature::default(),
+                    error: Some(anyhow!("skipped: another provider already landed this trade")),
+                    _swqos_type: swqos_type,
+                    landed_on_chain: false,
+                });
+                return;
+            }
+
+            if let Some(fee_ledger) = &fee_ledger {
+                fee_ledger.record_submitted(swqos_type);
+            }
+            let journal_context = journal.as_ref().map(|_| {
+                CallbackContext::new(
+                    transaction.clone(),
+                    swqos_type,
+                    if is_buy { TradeType::Buy } else { TradeType::Sell },
+                    swqos_type != SwqosType::Default,
+                    tip_amount,
+                )
+                .with_details(details)
+            });
+
+            // 有频率限制的 provider 按紧急程度分配发送额度，等待超时则放弃该 provider
+            // 排队期间调用方取消则放弃等待，由下方的取消检查处理
+            if let Some((queue, urgency)) = provider_queue
+                && let Err(e) =
+                    cancellable(token.as_ref(), queue.acquire_provider(swqos_type, urgency))
+                        .await
+                        .unwrap_or(Ok(()))
+            {
+                collector.submit(TaskResult {
+                    success: false,
+                    signature: Signature::default(),
+                    error: Some(e),
+                    _swqos_type: swqos_type,
+                    landed_on_chain: false,
+                });
+                return;
+            }
+
+            // 调用方已取消：不再提交
+            if let Some(error) = cancelled_error(token.as_ref(), "before submission") {
+                collector.submit(TaskResult {
+                    success: false,
+                    signature: Signature::default(),
+                    error: Some(error.into()),
+                    _swqos_type: swqos_type,
+                    landed_on_chain: false,
+                });
+                return;
+            }
+
+            // 交易日志：发送前写入待确认记录，崩溃重启后可据此恢复
+            let pending_recorded = match (&journal, &journal_context) {
+                (Some(journal), Some(context)) => journal.record_pending(context).await,
+                _ => false,
+            };
+
+            let _send_start = Instant::now();
+            let mut err: Option<anyhow::Error> = None;
+            let landed_on_chain;
+            let send = swqos_client.send_transaction(
+                if is_buy { TradeType::Buy } else { TradeType::Sell },
+                &transaction,
+                wait_transaction_confirmed,
+            );
+            // 等待确认时，其他 provider 的交易上链或调用方取消后停止本任务剩余的提交与确认轮询
+            let send_result = if wait_transaction_confirmed {
+                tokio::select! {
+                    biased;
+                    result = send => Some(result),
+                    _ = collector.wait_landed() => None,
+                    _ = wait_cancelled(token.as_ref()) => None,
+                }
+            } else {
+                Some(send.await)
+            };
+            let cancelled = send_result.is_none();
+            let success = match send_result.unwrap_or_else(|| {
+                match cancelled_error(token.as_ref(), "while waiting for confirmation") {
+                    Some(error) => Err(error.into()),
+                    None => Err(anyhow!("cancelled: another provider already landed this trade")),
+                }
+            }) {
                 Ok(()) => {
                     landed_on_chain = true; // Success means tx confirmed on-chain
                     true
@@ -470,6 +633,52 @@ pub async fn execute_parallel(
 
             // Transaction sent
 
+            // 发送耗时：等待确认时以开始轮询确认为界拆分提交与确认
+            let send_end = Instant::now();
+            if let Some(mut provider_timing) = provider_timing {
+                provider_timing.landed_slot = timing::stage_marks().landed_slot;
+                match timing::stage_marks().confirm_started_at {
+                    Some(confirm_start) => {
+                        provider_timing.submit =
+                            Some(confirm_start.saturating_duration_since(_send_start));
+                        provider_timing.confirm =
+                            Some(send_end.saturating_duration_since(confirm_start));
+                    },
+                    None => {
+                        provider_timing.submit = Some(send_end.duration_since(_send_start));
+                    },
+                }
+                timing::record_provider(provider_timing);
+            }
+
+            // 手续费统计：只有等待确认时才能确定交易已上链并实际扣费
+            if let (Some(fee_ledger), Some(signature)) =
+                (&fee_ledger, transaction.signatures.first())
+                && wait_transaction_confirmed
+                && landed_on_chain
+            {
+                fee_ledger.record_landed(
+                    TxFee::new(*signature, swqos_type, unit_limit, unit_price, tip_amount, success),
+                    success,
+                );
+            }
+
+            // 交易日志：发送 → 上链，在后台按顺序更新待确认记录
+            if let (Some(journal), Some(context)) = (journal, journal_context) {
+                let err_msg = err.as_ref().map(|e| e.to_string());
+                let submit_error =
+                    if success || landed_on_chain || cancelled { None } else { err_msg.clone() };
+                let landed = (wait_transaction_confirmed && landed_on_chain)
+                    .then(|| (success, if success { None } else { err_msg }));
+                journal.finish(JournalEntry { context, pending_recorded, submit_error, landed });
+            }
+
+            if let (Some(raw), Some(signature)) =
+                (response::take_captured(), transaction.signatures.first())
+            {
+                response::record(raw.into_response(swqos_type, *signature));
+            }
+
             if let Some(signature) = transaction.signatures.first() {
                 collector.submit(TaskResult {
                     success,
@@ -479,11 +688,208 @@ pub async fn execute_parallel(
                     landed_on_chain,         // 🔧 Whether tx landed (even if it failed)
                 });
             }
-        });
+        }));
+        tokio::spawn(confirm_scope(confirm, task));
     }
 
     // All tasks spawned
+    drop(instructions);
+    drop(submission);
+
+    await_results(&collector, wait_transaction_confirmed).await
+}
+
+/// 一个 provider 的已签名交易（外部构建的交易，见 [`crate::trading::sender`]）
+pub(crate) struct SignedSubmission {
+    pub swqos_client: Arc<SwqosClient>,
+    pub transaction: VersionedTransaction,
+    /// 交易中的 compute budget，用于手续费统计
+    pub cu_limit: u32,
+    pub cu_price: u64,
+    /// 追加的小费（SOL），未追加时为 0
+    pub tip: f64,
+}
+
+/// 并行提交已签名的交易；取消、交易日志、结果收集、耗时与手续费统计与 [`execute_parallel`] 一致
+pub(crate) async fn send_parallel(
+    submissions: Vec<SignedSubmission>,
+    trade_type: TradeType,
+    wait_transaction_confirmed: bool,
+    fee_ledger: Option<Arc<FeeLedger>>,
+    journal: Option<Journal>,
+    confirm: ConfirmConfig,
+    cancellation_token: Option<CancellationToken>,
+    fanout: Option<&SwqosFanout>,
+) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
+    if submissions.is_empty() {
+        return Err(anyhow!("No SWQOS to submit the transaction to"));
+    }
+    let collector = Arc::new(ResultCollector::new(submissions.len()));
+    for submission in submissions {
+        let collector = collector.clone();
+        let fee_ledger = fee_ledger.clone();
+        let journal = journal.clone();
+        let token = cancellation_token.clone();
+        let delay = fanout_delay(fanout, submission.swqos_client.get_swqos_type());
+        let task = response::scope(timing::scope(async move {
+            let SignedSubmission { swqos_client, transaction, cu_limit, cu_price, tip } =
+                submission;
+            let swqos_type = swqos_client.get_swqos_type();
+            let signature = transaction.signatures.first().copied().unwrap_or_default();
+            if !delay.is_zero()
+                && cancellable(
+                    token.as_ref(),
+                    stagger(&collector, delay, wait_transaction_confirmed),
+                )
+                .await
+                .unwrap_or(false)
+            {
+                collector.submit(TaskResult {
+                    success: false,
+                    signature: Signature::default(),
+                    error: Some(anyhow!(
+                        "skipped: another provider already landed this transaction"
+                    )),
+                    _swqos_type: swqos_type,
+                    landed_on_chain: false,
+                });
+                return;
+            }
+            // 调用方已取消：不再提交
+            if let Some(error) = cancelled_error(token.as_ref(), "before submission") {
+                collector.submit(TaskResult {
+                    success: false,
+                    signature: Signature::default(),
+                    error: Some(error.into()),
+                    _swqos_type: swqos_type,
+                    landed_on_chain: false,
+                });
+                return;
+            }
+            if let Some(fee_ledger) = &fee_ledger {
+                fee_ledger.record_submitted(swqos_type);
+            }
+
+            // 交易日志：发送前写入待确认记录，崩溃重启后可据此恢复
+            let journal_context = journal.as_ref().map(|_| {
+                CallbackContext::new(
+                    transaction.clone(),
+                    swqos_type,
+                    trade_type,
+                    swqos_type != SwqosType::Default,
+                    tip,
+                )
+            });
+            let pending_recorded = match (&journal, &journal_context) {
+                (Some(journal), Some(context)) => journal.record_pending(context).await,
+                _ => false,
+            };
+
+            let send_start = Instant::now();
+            let send =
+                swqos_client.send_transaction(trade_type, &transaction, wait_transaction_confirmed);
+            // 等待确认时，其他 provider 的交易上链或调用方取消后停止确认轮询
+            let send_result = if wait_transaction_confirmed {
+                tokio::select! {
+                    biased;
+                    result = send => Some(result),
+                    _ = collector.wait_landed() => None,
+                    _ = wait_cancelled(token.as_ref()) => None,
+                }
+            } else {
+                Some(send.await)
+            };
+            let cancelled = send_result.is_none();
+            let send_result = send_result.unwrap_or_else(|| {
+                match cancelled_error(token.as_ref(), "while waiting for confirmation") {
+                    Some(error) => Err(error.into()),
+                    None => {
+                        Err(anyhow!("cancelled: another provider already landed this transaction"))
+                    },
+                }
+            });
+            let (success, landed_on_chain, error) = match send_result {
+                Ok(()) => (true, true, None),
+                Err(e) => (false, is_landed_error(&e), Some(e)),
+            };
+
+            let send_end = Instant::now();
+            let marks = timing::stage_marks();
+            let (submit, confirm) = match marks.confirm_started_at {
+                Some(confirm_start) => (
+                    confirm_start.saturating_duration_since(send_start),
+                    Some(send_end.saturating_duration_since(confirm_start)),
+                ),
+                None => (send_end.duration_since(send_start), None),
+            };
+            timing::record_provider(ProviderTiming {
+                swqos_type,
+                signature,
+                build: Default::default(),
+                sign: Default::default(),
+                submit: Some(submit),
+                confirm,
+                landed_slot: marks.landed_slot,
+            });
+
+            if let Some(fee_ledger) = &fee_ledger
+                && wait_transaction_confirmed
+                && landed_on_chain
+            {
+                fee_ledger.record_landed(
+                    TxFee::new(signature, swqos_type, cu_limit, cu_price, tip, success),
+                    success,
+                );
+            }
+            // 交易日志：发送 → 上链，在后台按顺序更新待确认记录
+            if let (Some(journal), Some(context)) = (journal, journal_context) {
+                let err_msg = error.as_ref().map(|e| e.to_string());
+                let submit_error =
+                    if success || landed_on_chain || cancelled { None } else { err_msg.clone() };
+                let landed = (wait_transaction_confirmed && landed_on_chain)
+                    .then(|| (success, if success { None } else { err_msg }));
+                journal.finish(JournalEntry { context, pending_recorded, submit_error, landed });
+            }
+            if let Some(raw) = response::take_captured() {
+                response::record(raw.into_response(swqos_type, signature));
+            }
+            collector.submit(TaskResult {
+                success,
+                signature,
+                error,
+                _swqos_type: swqos_type,
+                landed_on_chain,
+            });
+        }));
+        tokio::spawn(confirm_scope(confirm, task));
+    }
+
+    await_results(&collector, wait_transaction_confirmed).await
+}
+
+/// 按分发顺序延后发送；等待确认且期间已有交易上链时返回 `true`（跳过发送）
+///
+/// 未等待确认时提交成功也会标记上链，因此只在等待确认时据此跳过。
+async fn stagger(
+    collector: &ResultCollector,
+    delay: std::time::Duration,
+    wait_transaction_confirmed: bool,
+) -> bool {
+    if !wait_transaction_confirmed {
+        tokio::time::sleep(delay).await;
+        return false;
+    }
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => false,
+        _ = collector.wait_landed() => true,
+    }
+}
 
+/// 未等待确认时返回最先提交的结果，否则等待任一交易上链或全部失败
+async fn await_results(
+    collector: &ResultCollector,
+    wait_transaction_confirmed: bool,
+) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
     if !wait_transaction_confirmed {
         // 🔧 事件驱动：等待第一个结果，最多等待 100ms
         // 相比固定等待 10ms，这种方式：
@@ -507,3 +913,212 @@ pub async fn execute_parallel(
         Err(anyhow!("All transactions failed"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_landed_signal_cancels_waiters() {
+        let collector = Arc::new(ResultCollector::new(2));
+        let waiter = tokio::spawn({
+            let collector = collector.clone();
+            async move { collector.wait_landed().await }
+        });
+        // 发送失败但未上链：不取消
+        collector.submit(TaskResult {
+            success: false,
+            signature: Signature::default(),
+            error: Some(anyhow!("rate limited")),
+            _swqos_type: SwqosType::Jito,
+            landed_on_chain: false,
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        collector.submit(TaskResult {
+            success: true,
+            signature: Signature::default(),
+            error: None,
+            _swqos_type: SwqosType::Default,
+            landed_on_chain: true,
+        });
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        // 上链后才开始等待的任务立即返回
+        tokio::time::timeout(Duration::from_secs(1), collector.wait_landed())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stagger_skips_after_landing() {
+        let collector = ResultCollector::new(1);
+        assert!(!stagger(&collector, Duration::from_millis(1), true).await);
+
+        collector.submit(TaskResult {
+            success: true,
+            signature: Signature::default(),
+            error: None,
+            _swqos_type: SwqosType::Jito,
+            landed_on_chain: true,
+        });
+        assert!(stagger(&collector, Duration::from_secs(60), true).await);
+        // 未等待确认：提交成功不代表上链，照常发送
+        assert!(!stagger(&collector, Duration::from_millis(1), false).await);
+    }
+
+    struct StubSwqos {
+        swqos_type: SwqosType,
+        /// 等待确认时永不返回（模拟迟迟未上链的交易）
+        pending: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::swqos::SwqosClientTrait for StubSwqos {
+        async fn send_transaction(
+            &self,
+            _trade_type: TradeType,
+            _transaction: &VersionedTransaction,
+            wait_confirmation: bool,
+        ) -> Result<()> {
+            if self.pending && wait_confirmation {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+
+        async fn send_transactions(
+            &self,
+            _trade_type: TradeType,
+            _transactions: &[VersionedTransaction],
+            _wait_confirmation: bool,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            Ok(match self.swqos_type {
+                SwqosType::Default => String::new(),
+                swqos_type => swqos_type.tip_accounts()[0].to_string(),
+            })
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            self.swqos_type
+        }
+    }
+
+    #[test]
+    fn test_plan_providers_shared_rules() {
+        let clients: Vec<Arc<SwqosClient>> = vec![
+            Arc::new(StubSwqos { swqos_type: SwqosType::Default, pending: false }),
+            Arc::new(StubSwqos { swqos_type: SwqosType::Jito, pending: false }),
+        ];
+        let strategy = GasFeeStrategy::new();
+        let min_tip = SwqosType::Jito.min_tip();
+        for (swqos_type, tip) in [(SwqosType::Default, 0.0), (SwqosType::Jito, min_tip * 2.0)] {
+            strategy.set(swqos_type, TradeType::Buy, GasFeeStrategyType::Normal, 200_000, 1, tip);
+        }
+
+        let plans =
+            plan_providers(&clients, &strategy, TradeType::Buy, true, LandingMode::Exclusive)
+                .unwrap();
+        assert_eq!(plans.len(), 2);
+        let default = plans.iter().find(|p| p.config.0 == SwqosType::Default).unwrap();
+        assert!(default.tip_accounts.is_empty());
+        assert_eq!(default.tip(), 0.0);
+        let jito = plans.iter().find(|p| p.config.0 == SwqosType::Jito).unwrap();
+        assert_eq!(jito.tip_accounts, vec![SwqosType::Jito.tip_accounts()[0]]);
+        assert_eq!(jito.tip(), min_tip * 2.0);
+
+        // 多笔交易需要 durable nonce；不使用小费时只走 Default
+        let err = plan_providers(&clients, &strategy, TradeType::Buy, true, LandingMode::Single)
+            .unwrap_err();
+        assert!(err.to_string().contains("durable_nonce"));
+        let plans = plan_providers(&clients, &strategy, TradeType::Buy, false, LandingMode::Single)
+            .unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].config.0, SwqosType::Default);
+
+        // 低于 provider 最低小费的配置被跳过；费用上限生效
+        strategy.set(SwqosType::Jito, TradeType::Buy, GasFeeStrategyType::Normal, 200_000, 1, 0.0);
+        let plans =
+            plan_providers(&clients, &strategy, TradeType::Buy, true, LandingMode::Exclusive)
+                .unwrap();
+        assert_eq!(plans.len(), 1);
+        strategy.set_fee_caps(crate::common::GasFeeCaps::new().with_max_priority_fee_lamports(0));
+        assert!(
+            plan_providers(&clients, &strategy, TradeType::Buy, true, LandingMode::Exclusive)
+                .is_err()
+        );
+
+        // 未使用 durable nonce 的卖出：各笔均可上链，小时预算按总花费预留
+        assert_eq!(LandingMode::for_swap(false, false), LandingMode::Independent);
+        assert_eq!(LandingMode::for_swap(true, true), LandingMode::Exclusive);
+        let strategy = GasFeeStrategy::new();
+        for (swqos_type, tip) in [(SwqosType::Default, 0.0), (SwqosType::Jito, min_tip)] {
+            strategy.set(
+                swqos_type,
+                TradeType::Sell,
+                GasFeeStrategyType::Normal,
+                1_000_000,
+                1_000,
+                tip,
+            );
+        }
+        strategy.set_fee_caps(
+            crate::common::GasFeeCaps::new().with_hourly_budget_lamports(1_000_000_000),
+        );
+        let plans =
+            plan_providers(&clients, &strategy, TradeType::Sell, true, LandingMode::Independent)
+                .unwrap();
+        assert_eq!(plans.len(), 2);
+        // 每笔 1_000 lamports 优先费，Jito 另加小费
+        let tip_lamports = crate::common::fee_ledger::sol_to_lamports(min_tip);
+        assert_eq!(strategy.hourly_spent_lamports(), 2_000 + tip_lamports);
+    }
+
+    #[tokio::test]
+    async fn test_send_parallel_cancel_during_confirm() {
+        use crate::swqos::common::TradeError;
+        use solana_sdk::message::{Message, VersionedMessage};
+        use solana_sdk::signer::Signer;
+
+        let payer = Keypair::new();
+        let message = VersionedMessage::Legacy(Message::new(&[], Some(&payer.pubkey())));
+        let transaction = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        let signature = transaction.signatures[0];
+        let submissions = vec![SignedSubmission {
+            swqos_client: Arc::new(StubSwqos { swqos_type: SwqosType::Default, pending: true }),
+            transaction,
+            cu_limit: 0,
+            cu_price: 0,
+            tip: 0.0,
+        }];
+        let token = CancellationToken::new();
+        let send = tokio::spawn(send_parallel(
+            submissions,
+            TradeType::Buy,
+            true,
+            None,
+            None,
+            ConfirmConfig::default(),
+            Some(token.clone()),
+            None,
+        ));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!send.is_finished());
+
+        // 等待确认期间取消：停止轮询，已提交的签名照常返回
+        token.cancel();
+        let (success, signatures, error) = tokio::time::timeout(Duration::from_secs(1), send)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(!success);
+        assert_eq!(signatures, vec![signature]);
+        assert!(TradeError::from(error.unwrap()).is_cancelled());
+    }
+}
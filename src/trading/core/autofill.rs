@@ -0,0 +1,92 @@
+//! 协议参数自动补全
+//!
+//! 快速路径可以只传最小参数（例如事件流中拿到的 pool 地址），其余字段保持 `Pubkey::default()`；
+//! 开启 `TradeConfig::auto_fill_params` 后，交易执行前会通过 RPC 从链上补全缺失字段
+//! （bonding curve、creator vault、observation state 等），慢路径因此也能直接工作。
+//!
+//! 是否需要补全以 [`DexParamEnum::validate`] 为准：参数已通过校验时不发起任何 RPC 请求。
+
+use super::params::{
+    BonkParams, DexParamEnum, MeteoraDammV2Params, PumpFunParams, PumpSwapParams,
+    RaydiumAmmV4Params, RaydiumClmmParams, RaydiumCpmmParams,
+};
+use crate::common::SolanaRpcClient;
+use crate::constants::USD1_TOKEN_ACCOUNT;
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use solana_sdk::pubkey::Pubkey;
+
+impl DexParamEnum {
+    /// 参数对应的 DEX 类型
+    pub fn dex_type(&self) -> DexType {
+        match self {
+            DexParamEnum::PumpFun(_) => DexType::PumpFun,
+            DexParamEnum::PumpSwap(_) => DexType::PumpSwap,
+            DexParamEnum::Bonk(_) => DexType::Bonk,
+            DexParamEnum::RaydiumCpmm(_) => DexType::RaydiumCpmm,
+            DexParamEnum::RaydiumAmmV4(_) => DexType::RaydiumAmmV4,
+            DexParamEnum::RaydiumClmm(_) => DexType::RaydiumClmm,
+            DexParamEnum::MeteoraDammV2(_) => DexType::MeteoraDammV2,
+        }
+    }
+
+    /// 从链上补全缺失的参数
+    ///
+    /// * `mint` - 交易的目标代币
+    /// * `quote_mint` - 计价代币（SOL / WSOL / USDC / USD1），用于定位 Bonk USD1 池
+    ///
+    /// 返回是否发起了补全。池地址本身缺失且无法由 mint 推导时返回错误。
+    pub async fn fill_missing(
+        &mut self,
+        rpc: &SolanaRpcClient,
+        mint: &Pubkey,
+        quote_mint: &Pubkey,
+    ) -> Result<bool> {
+        if self.validate(&self.dex_type()).is_ok() {
+            return Ok(false);
+        }
+        match self {
+            DexParamEnum::PumpFun(p) => {
+                let close_token_account_when_sell = p.close_token_account_when_sell;
+                *p = PumpFunParams::from_mint_by_rpc(rpc, mint).await?;
+                p.close_token_account_when_sell = close_token_account_when_sell;
+            },
+            DexParamEnum::PumpSwap(p) => {
+                *p = if p.pool == Pubkey::default() {
+                    PumpSwapParams::from_mint_by_rpc(rpc, mint).await?
+                } else {
+                    PumpSwapParams::from_pool_address_by_rpc(rpc, &p.pool).await?
+                };
+            },
+            DexParamEnum::Bonk(p) => {
+                let usd1_pool = *quote_mint == USD1_TOKEN_ACCOUNT;
+                *p = BonkParams::from_mint_by_rpc(rpc, mint, usd1_pool).await?;
+            },
+            DexParamEnum::RaydiumCpmm(p) => {
+                let pool = required_pool("pool_state", &p.pool_state)?;
+                *p = RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool).await?;
+            },
+            DexParamEnum::RaydiumAmmV4(p) => {
+                let amm = required_pool("amm", &p.amm)?;
+                *p = RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, amm).await?;
+            },
+            DexParamEnum::RaydiumClmm(p) => {
+                let pool = required_pool("pool_state", &p.pool_state)?;
+                *p = RaydiumClmmParams::from_pool_address_by_rpc(rpc, &pool).await?;
+            },
+            DexParamEnum::MeteoraDammV2(p) => {
+                let pool = required_pool("pool", &p.pool)?;
+                *p = MeteoraDammV2Params::from_pool_address_by_rpc(rpc, &pool).await?;
+            },
+        }
+        Ok(true)
+    }
+}
+
+/// 这些协议无法仅凭 mint 定位池，池地址必须由调用方提供
+fn required_pool(field: &str, pool: &Pubkey) -> Result<Pubkey> {
+    if *pool == Pubkey::default() {
+        return Err(anyhow!("Cannot auto-fill params: `{}` is required", field));
+    }
+    Ok(*pool)
+}
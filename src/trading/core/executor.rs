@@ -4,19 +4,33 @@ use solana_sdk::{
     instruction::Instruction, message::AddressLookupTableAccount, pubkey::Pubkey,
     signature::Keypair, signature::Signature,
 };
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use super::{params::SwapParams, traits::InstructionBuilder};
+use super::{
+    account_recovery::{self, MissingAccount, SimulationFailure},
+    params::{DexParamEnum, SwapParams},
+    traits::InstructionBuilder,
+};
 use crate::swqos::TradeType;
+use crate::swqos::common::TradeError;
 use crate::{
-    common::{GasFeeStrategy, SolanaRpcClient, nonce_cache::DurableNonceInfo},
+    common::cancellation::{cancellable, cancelled_error},
+    common::{GasFeeStrategy, PreflightPolicy, RpcProviderRef, nonce_cache::DurableNonceInfo},
     perf::syscall_bypass::SystemCallBypassManager,
     trading::MiddlewareManager,
+    trading::common::{FeePayerRef, InstructionLayout},
     trading::core::{
         async_executor::execute_parallel,
         execution::{InstructionProcessor, Prefetch},
         traits::TradeExecutor,
     },
+    trading::lifecycle::{self, TradeDetails},
+    trading::store::Journal,
+    trading::submission_queue::SubmissionTicket,
+    trading::timing::{self, TradeStages},
 };
 use once_cell::sync::Lazy;
 
@@ -42,29 +56,37 @@ impl GenericTradeExecutor {
     }
 }
 
-#[async_trait::async_trait]
-impl TradeExecutor for GenericTradeExecutor {
-    async fn swap(
-        &self,
-        params: SwapParams,
-    ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
-        let total_start = Instant::now();
-
-        // 判断买卖方向
-        let is_buy =
-            params.trade_type == TradeType::Buy || params.trade_type == TradeType::CreateAndBuy;
-
-        // CPU 预取
-        Prefetch::keypair(&params.payer);
+/// 构建完成、待提交的指令
+struct PreparedInstructions {
+    instructions: Vec<Instruction>,
+    details: Arc<TradeDetails>,
+    build_elapsed: Duration,
+}
 
-        // 构建指令
+impl GenericTradeExecutor {
+    /// 构建协议指令，并完成预处理、中间件、自定义指令拼接与多签包装
+    async fn prepare(&self, params: &SwapParams, is_buy: bool) -> Result<PreparedInstructions> {
         let build_start = Instant::now();
-        let instructions = if is_buy {
-            self.instruction_builder.build_buy_instructions(&params).await?
-        } else {
-            self.instruction_builder.build_sell_instructions(&params).await?
-        };
+        let (instructions, quote) = lifecycle::capture_quote(async {
+            if is_buy {
+                self.instruction_builder.build_buy_instructions(params).await
+            } else {
+                self.instruction_builder.build_sell_instructions(params).await
+            }
+        })
+        .await;
+        let instructions = instructions?;
         let build_elapsed = build_start.elapsed();
+        let details = Arc::new(TradeDetails {
+            dex_type: params.protocol_params.dex_type(),
+            mint: if is_buy { params.output_mint } else { params.input_mint },
+            input_mint: params.input_mint,
+            output_mint: params.output_mint,
+            input_amount: params.input_amount,
+            slippage_basis_points: params.slippage_basis_points,
+            quote,
+            metadata: params.metadata.clone(),
+        });
 
         // 指令预处理
         InstructionProcessor::preprocess(&instructions)?;
@@ -76,9 +98,162 @@ impl TradeExecutor for GenericTradeExecutor {
                     instructions,
                     self.protocol_name.to_string(),
                     is_buy,
+                    params.metadata.as_deref(),
                 )?,
             None => instructions,
         };
+        let mut final_instructions = with_extra_instructions(params, final_instructions)?;
+        crate::constants::registry::remap_instructions(&details.dex_type, &mut final_instructions)?;
+        if let Some(multisig) = &params.multisig {
+            use solana_sdk::signer::Signer;
+            let rpc = params
+                .rpc
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("RPC client is required for multisig mode"))?;
+            final_instructions = crate::trading::multisig::wrap_in_proposal(
+                rpc,
+                multisig,
+                &params.payer.pubkey(),
+                &[params.input_mint, params.output_mint],
+                params.open_seed_optimize,
+                final_instructions,
+            )
+            .await?;
+        }
+
+        Ok(PreparedInstructions { instructions: final_instructions, details, build_elapsed })
+    }
+
+    /// 缺失账户恢复：重新加载协议参数，成功时返回 `true`
+    async fn recover(
+        &self,
+        params: &mut SwapParams,
+        is_buy: bool,
+        missing: &MissingAccount,
+    ) -> bool {
+        let Some(rpc) = params.rpc.clone() else {
+            return false;
+        };
+        let mint = if is_buy { params.output_mint } else { params.input_mint };
+        log::warn!(
+            "[{}] missing account ({}), reloading params and rebuilding",
+            self.protocol_name,
+            missing
+        );
+        match account_recovery::refresh_params(&rpc, &params.protocol_params, &mint).await {
+            Ok(fresh) => {
+                params.protocol_params = fresh;
+                true
+            },
+            Err(e) => {
+                log::warn!("[{}] failed to reload params: {}", self.protocol_name, e);
+                false
+            },
+        }
+    }
+
+    /// 模拟交易；因缺失账户失败且尚未恢复过时，重新加载参数、重建指令后再模拟一次
+    async fn simulate_with_recovery(
+        &self,
+        params: &mut SwapParams,
+        prepared: &mut PreparedInstructions,
+        is_buy: bool,
+        with_tip: bool,
+        recovered: &mut bool,
+    ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
+        let result = self.simulate(params, prepared.instructions.clone(), is_buy, with_tip).await;
+        if *recovered {
+            return result;
+        }
+        let missing = match &result {
+            Ok((false, _, Some(err))) => MissingAccount::from_error(err),
+            _ => None,
+        };
+        let Some(missing) = missing else {
+            return result;
+        };
+        if !self.recover(params, is_buy, &missing).await {
+            return result;
+        }
+        *recovered = true;
+        *prepared = self.prepare(params, is_buy).await?;
+        self.simulate(params, prepared.instructions.clone(), is_buy, with_tip).await
+    }
+
+    async fn simulate(
+        &self,
+        params: &SwapParams,
+        instructions: Vec<Instruction>,
+        is_buy: bool,
+        with_tip: bool,
+    ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
+        simulate_transaction(
+            params.simulation_provider(),
+            params.payer.clone(),
+            params.fee_payer.clone(),
+            instructions,
+            params.address_lookup_table_account.clone(),
+            params.recent_blockhash,
+            params.durable_nonce.clone(),
+            params.middleware_manager.clone(),
+            self.protocol_name,
+            is_buy,
+            with_tip,
+            params.gas_fee_strategy.clone(),
+            params.instruction_layout,
+            params.metadata.as_deref(),
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl TradeExecutor for GenericTradeExecutor {
+    async fn swap(
+        &self,
+        mut params: SwapParams,
+    ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
+        let total_start = Instant::now();
+
+        // 判断买卖方向
+        let is_buy =
+            params.trade_type == TradeType::Buy || params.trade_type == TradeType::CreateAndBuy;
+
+        // 提交队列：按紧急程度排队获取构建 / 签名名额（模拟交易不排队）
+        let submission = match (&params.submission_queue, params.simulate) {
+            (Some(queue), false) => {
+                let acquire = SubmissionTicket::acquire(queue.clone(), params.urgency);
+                match cancellable(params.cancellation_token.as_ref(), acquire).await {
+                    Some(ticket) => Some(ticket),
+                    None => {
+                        return Ok((
+                            false,
+                            vec![],
+                            Some(TradeError::cancelled("cancelled while queued").into()),
+                        ));
+                    },
+                }
+            },
+            _ => None,
+        };
+
+        // CPU 预取
+        Prefetch::keypair(&params.payer);
+
+        // 构建指令；因缺失账户失败时重新加载协议参数后重建一次
+        let mut recovered = false;
+        let mut prepared = match self.prepare(&params, is_buy).await {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                let Some(missing) = MissingAccount::from_error(&e) else { return Err(e) };
+                if !self.recover(&mut params, is_buy, &missing).await {
+                    return Err(e);
+                }
+                recovered = true;
+                self.prepare(&params, is_buy).await?
+            },
+        };
+        let build_elapsed = prepared.build_elapsed;
 
         // 提交前耗时
         let before_submit_elapsed = total_start.elapsed();
@@ -86,20 +261,16 @@ impl TradeExecutor for GenericTradeExecutor {
         // 如果是模拟模式，直接通过 RPC 模拟交易
         if params.simulate {
             let send_start = Instant::now();
-            let result = simulate_transaction(
-                params.rpc,
-                params.payer,
-                final_instructions,
-                params.address_lookup_table_account,
-                params.recent_blockhash,
-                params.durable_nonce,
-                params.middleware_manager,
-                self.protocol_name,
-                is_buy,
-                if is_buy { true } else { params.with_tip },
-                params.gas_fee_strategy,
-            )
-            .await;
+            let with_tip = is_buy || params.with_tip;
+            let result = self
+                .simulate_with_recovery(
+                    &mut params,
+                    &mut prepared,
+                    is_buy,
+                    with_tip,
+                    &mut recovered,
+                )
+                .await;
             let send_elapsed = send_start.elapsed();
             let total_elapsed = total_start.elapsed();
 
@@ -132,13 +303,49 @@ impl TradeExecutor for GenericTradeExecutor {
             return result;
         }
 
+        // 发送前模拟，避免为必然失败的交易支付小费
+        let mut preflight_elapsed = None;
+        if params.preflight != PreflightPolicy::Skip {
+            let preflight_start = Instant::now();
+            let preflight = self
+                .simulate_with_recovery(&mut params, &mut prepared, is_buy, false, &mut recovered)
+                .await;
+            check_preflight(params.preflight, preflight)?;
+            preflight_elapsed = Some(preflight_start.elapsed());
+            #[cfg(feature = "perf-trace")]
+            log::trace!("[Preflight] us={}", preflight_elapsed.unwrap_or_default().as_micros());
+        }
+
+        // 调用方在构建 / 预检期间取消：不发送
+        if let Some(error) =
+            cancelled_error(params.cancellation_token.as_ref(), "before submission")
+        {
+            return Ok((false, vec![], Some(error.into())));
+        }
+
+        // 等待确认时并发查询发送时的 slot，用于计算上链前等待的 slot 数（不阻塞发送）
+        let submit_slot = match (&params.rpc, params.wait_transaction_confirmed) {
+            (Some(rpc), true) => {
+                let rpc = rpc.clone();
+                Some(tokio::spawn(async move {
+                    rpc.get_slot_with_commitment(
+                        solana_commitment_config::CommitmentConfig::processed(),
+                    )
+                    .await
+                    .ok()
+                }))
+            },
+            _ => None,
+        };
+
         // 并行发送交易
         let send_start = Instant::now();
         let result = execute_parallel(
             params.swqos_clients.clone(),
             params.payer,
+            params.fee_payer,
             params.rpc,
-            final_instructions,
+            prepared.instructions,
             params.address_lookup_table_account,
             params.recent_blockhash,
             params.durable_nonce,
@@ -151,6 +358,16 @@ impl TradeExecutor for GenericTradeExecutor {
             params.on_transaction_signed,
             params.callback_execution_mode.unwrap_or_default(),
             params.enable_jito_sandwich_protection.unwrap_or(false),
+            params.fee_ledger,
+            params
+                .trade_store
+                .map(|store| Journal::new(store, params.last_valid_block_height)),
+            params.instruction_layout,
+            prepared.details,
+            submission,
+            params.confirm,
+            params.cancellation_token,
+            params.fanout,
         )
         .await;
         let send_elapsed = send_start.elapsed();
@@ -170,21 +387,155 @@ impl TradeExecutor for GenericTradeExecutor {
             );
         }
 
-        #[cfg(not(feature = "perf-trace"))]
-        let _ = (build_elapsed, before_submit_elapsed, send_elapsed, total_elapsed);
+        let submit_slot = match submit_slot {
+            Some(task) => task.await.ok().flatten(),
+            None => None,
+        };
+        if let Ok((_, signatures, _)) = &result {
+            timing::record_trade(
+                signatures,
+                TradeStages {
+                    instruction_build: build_elapsed,
+                    before_submit: before_submit_elapsed,
+                    preflight: preflight_elapsed,
+                    send: send_elapsed,
+                    total: total_elapsed,
+                    submit_slot,
+                },
+            );
+        }
 
         result
     }
 
+    fn static_accounts(
+        &self,
+        protocol_params: &DexParamEnum,
+        mint: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        self.instruction_builder.static_accounts(protocol_params, mint)
+    }
+
+    async fn build_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>> {
+        let is_buy =
+            params.trade_type == TradeType::Buy || params.trade_type == TradeType::CreateAndBuy;
+        let instructions = if is_buy {
+            self.instruction_builder.build_buy_instructions(params).await?
+        } else {
+            self.instruction_builder.build_sell_instructions(params).await?
+        };
+        InstructionProcessor::preprocess(&instructions)?;
+        let instructions = match &params.middleware_manager {
+            Some(middleware_manager) => middleware_manager
+                .apply_middlewares_process_protocol_instructions(
+                    instructions,
+                    self.protocol_name.to_string(),
+                    is_buy,
+                    params.metadata.as_deref(),
+                )?,
+            None => instructions,
+        };
+        with_extra_instructions(params, instructions)
+    }
+
     fn protocol_name(&self) -> &'static str {
         self.protocol_name
     }
 }
 
+/// 将调用方提供的自定义指令拼接到协议指令前后
+fn with_extra_instructions(
+    params: &SwapParams,
+    instructions: Vec<Instruction>,
+) -> Result<Vec<Instruction>> {
+    if params.pre_swap_instructions.is_empty() && params.post_swap_instructions.is_empty() {
+        return Ok(instructions);
+    }
+    check_extra_instructions(&params.pre_swap_instructions, &params.post_swap_instructions)?;
+    let mut all = Vec::with_capacity(
+        params.pre_swap_instructions.len()
+            + instructions.len()
+            + params.post_swap_instructions.len(),
+    );
+    all.extend_from_slice(&params.pre_swap_instructions);
+    all.extend(instructions);
+    all.extend_from_slice(&params.post_swap_instructions);
+    Ok(all)
+}
+
+/// 自定义指令中的 SetComputeUnitLimit / SetComputeUnitPrice 在组装时替换 gas 策略生成的指令；
+/// 同类 Compute Budget 指令出现多次会使整笔交易失败，发送前直接返回错误
+fn check_extra_instructions(pre: &[Instruction], post: &[Instruction]) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    for ix in pre.iter().chain(post) {
+        if !solana_compute_budget_interface::check_id(&ix.program_id) {
+            continue;
+        }
+        if let Some(kind) = ix.data.first()
+            && !seen.insert(*kind)
+        {
+            return Err(anyhow::anyhow!(
+                "pre/post-swap instructions contain duplicate compute budget instructions"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 按发送前模拟策略处理模拟结果；`SimulateAndAbortOnError` 且模拟失败时返回错误
+pub(crate) fn check_preflight(
+    policy: PreflightPolicy,
+    result: Result<(bool, Vec<Signature>, Option<anyhow::Error>)>,
+) -> Result<()> {
+    match result {
+        Ok((false, _, err)) => {
+            let err = err.unwrap_or_else(|| anyhow::anyhow!("unknown simulation error"));
+            if policy == PreflightPolicy::SimulateAndAbortOnError {
+                return Err(anyhow::anyhow!("Preflight simulation failed: {}", err));
+            }
+            log::warn!("preflight simulation failed, sending anyway: {}", err);
+        },
+        Ok(_) => {},
+        Err(e) => log::warn!("preflight simulation could not run, sending anyway: {}", e),
+    }
+    Ok(())
+}
+
+/// 按 `params.preflight` 模拟已构建的指令（吞吐模式使用，不重新加载参数）
+pub(crate) async fn simulate_preflight(
+    params: &SwapParams,
+    instructions: Vec<Instruction>,
+    protocol_name: &'static str,
+    is_buy: bool,
+) -> Result<()> {
+    if params.preflight == PreflightPolicy::Skip {
+        return Ok(());
+    }
+    let result = simulate_transaction(
+        params.simulation_provider(),
+        params.payer.clone(),
+        params.fee_payer.clone(),
+        instructions,
+        params.address_lookup_table_account.clone(),
+        params.recent_blockhash,
+        params.durable_nonce.clone(),
+        params.middleware_manager.clone(),
+        protocol_name,
+        is_buy,
+        false,
+        params.gas_fee_strategy.clone(),
+        params.instruction_layout,
+        params.metadata.as_deref(),
+    )
+    .await;
+    check_preflight(params.preflight, result)
+}
+
 /// 🔧 修复：Simulate模式返回Vec<Signature>（单个RPC模拟）
 async fn simulate_transaction(
-    rpc: Option<Arc<SolanaRpcClient>>,
+    rpc: Option<RpcProviderRef>,
     payer: Arc<Keypair>,
+    fee_payer: Option<FeePayerRef>,
     instructions: Vec<Instruction>,
     address_lookup_table_account: Option<AddressLookupTableAccount>,
     recent_blockhash: Option<Hash>,
@@ -194,6 +545,8 @@ async fn simulate_transaction(
     is_buy: bool,
     with_tip: bool,
     gas_fee_strategy: GasFeeStrategy,
+    instruction_layout: InstructionLayout,
+    metadata: Option<&crate::trading::TradeMetadata>,
 ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
     use crate::trading::common::build_transaction;
     use solana_client::rpc_config::RpcSimulateTransactionConfig;
@@ -219,7 +572,8 @@ async fn simulate_transaction(
     // Build transaction for simulation
     let transaction = build_transaction(
         payer.clone(),
-        Some(rpc.clone()),
+        fee_payer,
+        None,
         unit_limit,
         unit_price,
         instructions,
@@ -229,17 +583,19 @@ async fn simulate_transaction(
         protocol_name,
         is_buy,
         false, // simulate doesn't need tip instruction
-        &Pubkey::default(),
+        &[],
         tip,
         durable_nonce,
         false, // simulate doesn't need sandwich protection
+        instruction_layout,
+        metadata,
     )
     .await?;
 
     // Simulate the transaction
     use solana_commitment_config::CommitmentConfig;
     let simulate_result = rpc
-        .simulate_transaction_with_config(
+        .simulate_transaction(
             &transaction,
             RpcSimulateTransactionConfig {
                 sig_verify: false, // Don't verify signature during simulation for speed
@@ -260,41 +616,74 @@ async fn simulate_transaction(
         .first()
         .ok_or_else(|| anyhow::anyhow!("Transaction has no signatures"))?;
 
-    if let Some(err) = simulate_result.value.err {
+    if let Some(err) = simulate_result.err {
         eprintln!("\n[Simulation Failed] error={:?} signature={:?}", err, signature);
-        if let Some(logs) = &simulate_result.value.logs {
+        if let Some(logs) = &simulate_result.logs {
             eprintln!("Transaction logs:");
             for log in logs {
                 eprintln!("  {}", log);
             }
         }
-        if let Some(units_consumed) = simulate_result.value.units_consumed {
+        if let Some(units_consumed) = simulate_result.units_consumed {
             eprintln!("Compute Units Consumed: {}", units_consumed);
         }
         #[cfg(feature = "perf-trace")]
         {
             log::warn!("[Simulation Failed] error={:?} signature={:?}", err, signature);
-            if let Some(logs) = &simulate_result.value.logs {
+            if let Some(logs) = &simulate_result.logs {
                 log::trace!("Transaction logs: {:?}", logs);
             }
-            if let Some(units_consumed) = simulate_result.value.units_consumed {
+            if let Some(units_consumed) = simulate_result.units_consumed {
                 log::trace!("Compute Units Consumed: {}", units_consumed);
             }
         }
-        return Ok((false, vec![signature], Some(anyhow::anyhow!("{:?}", err))));
+        let failure = SimulationFailure {
+            message: format!("{:?}", err),
+            error: serde_json::to_value(&err).ok().and_then(|v| serde_json::from_value(v).ok()),
+            logs: simulate_result.logs.unwrap_or_default(),
+        };
+        return Ok((false, vec![signature], Some(anyhow::Error::new(failure))));
     }
 
     // Simulation succeeded
     #[cfg(feature = "perf-trace")]
     {
         log::info!("[Simulation Succeeded] signature={:?}", signature);
-        if let Some(units_consumed) = simulate_result.value.units_consumed {
+        if let Some(units_consumed) = simulate_result.units_consumed {
             log::trace!("Compute Units Consumed: {}", units_consumed);
         }
-        if let Some(logs) = &simulate_result.value.logs {
+        if let Some(logs) = &simulate_result.logs {
             log::trace!("Transaction logs: {:?}", logs);
         }
     }
 
     Ok((true, vec![signature], None))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_compute_budget_interface::ComputeBudgetInstruction;
+    use solana_system_interface::instruction::transfer;
+
+    #[test]
+    fn test_extra_compute_budget_overrides_strategy() {
+        let payer = Pubkey::new_unique();
+        let transfer_ix = transfer(&payer, &Pubkey::new_unique(), 1);
+        let cu_price = ComputeBudgetInstruction::set_compute_unit_price(1_000);
+        let cu_limit = ComputeBudgetInstruction::set_compute_unit_limit(200_000);
+        assert!(check_extra_instructions(&[], &[]).is_ok());
+        assert!(
+            check_extra_instructions(&[cu_price.clone(), transfer_ix.clone()], &[cu_limit.clone()])
+                .is_ok()
+        );
+        assert!(check_extra_instructions(&[cu_price.clone()], &[cu_price.clone()]).is_err());
+
+        let mut instructions = vec![cu_price, transfer_ix.clone(), cu_limit];
+        assert_eq!(
+            crate::trading::common::take_compute_budget_overrides(&mut instructions),
+            (Some(200_000), Some(1_000))
+        );
+        assert_eq!(instructions, vec![transfer_ix]);
+    }
+}
@@ -1,6 +1,10 @@
+pub mod account_recovery;
 pub mod async_executor;
+pub mod autofill;
 pub mod execution;
 pub mod executor;
 pub mod params;
+pub mod params_cache;
 pub mod traits;
 pub mod transaction_pool;
+pub mod validation;
@@ -1,22 +1,29 @@
 use crate::common::bonding_curve::BondingCurveAccount;
 use crate::common::nonce_cache::DurableNonceInfo;
 use crate::common::spl_associated_token_account::get_associated_token_address_with_program_id;
-use crate::common::{GasFeeStrategy, SolanaRpcClient, auto_mock_rpc::PoolRpcClient};
-use crate::constants::TOKEN_PROGRAM;
+use crate::common::transfer_hook::{TransferHookInfo, fetch_transfer_hook_info};
+use crate::common::{Bps, GasFeeStrategy, SolanaRpcClient, auto_mock_rpc::PoolRpcClient};
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
 use crate::instruction::utils::pumpfun::global_constants::MAYHEM_FEE_RECIPIENT;
 use crate::instruction::utils::pumpswap::accounts::MAYHEM_FEE_RECIPIENT as MAYHEM_FEE_RECIPIENT_SWAP;
+use crate::instruction::utils::pumpswap_types::PumpSwapFeeConfig;
 use crate::swqos::{SwqosClient, TradeType};
 use crate::trading::MiddlewareManager;
 use crate::trading::common::get_multi_token_balances_with_client;
+use crate::trading::factory::DexType;
 use crate::utils::token::calculate_ata;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use solana_hash::Hash;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::message::AddressLookupTableAccount;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 use std::sync::Arc;
 
 /// DEX 参数枚举 - 零开销抽象替代 Box<dyn ProtocolParams>
-#[derive(Clone)]
+///
+/// 支持 serde 序列化：解析好的池子参数可缓存到磁盘，或由发现服务发送给执行服务直接使用。
+#[derive(Clone, Serialize, Deserialize)]
 pub enum DexParamEnum {
     PumpFun(PumpFunParams),
     PumpSwap(PumpSwapParams),
@@ -41,12 +48,173 @@ impl DexParamEnum {
             DexParamEnum::MeteoraDammV2(p) => p,
         }
     }
+
+    /// 输入侧储备（用于按池子深度估算价格冲击）
+    ///
+    /// 买入时为计价侧储备，卖出时为 `mint` 侧储备；CLMM / DAMM v2 等集中流动性池返回 None。
+    pub fn input_reserve(&self, mint: &Pubkey, is_buy: bool) -> Option<u64> {
+        let pick = |mint_a: &Pubkey, reserve_a: u64, reserve_b: u64| {
+            let (mint_reserve, other_reserve) =
+                if mint_a == mint { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+            if is_buy { other_reserve } else { mint_reserve }
+        };
+        match self {
+            DexParamEnum::PumpFun(p) => Some(if is_buy {
+                p.bonding_curve.virtual_sol_reserves
+            } else {
+                p.bonding_curve.virtual_token_reserves
+            }),
+            DexParamEnum::PumpSwap(p) => {
+                Some(pick(&p.base_mint, p.pool_base_token_reserves, p.pool_quote_token_reserves))
+            },
+            DexParamEnum::Bonk(p) => {
+                let reserve = if is_buy {
+                    p.virtual_quote + p.real_quote
+                } else {
+                    p.virtual_base.saturating_sub(p.real_base)
+                };
+                u64::try_from(reserve).ok()
+            },
+            DexParamEnum::RaydiumCpmm(p) => {
+                Some(pick(&p.base_mint, p.base_reserve, p.quote_reserve))
+            },
+            DexParamEnum::RaydiumAmmV4(p) => Some(pick(&p.coin_mint, p.coin_reserve, p.pc_reserve)),
+            DexParamEnum::RaydiumClmm(_) | DexParamEnum::MeteoraDammV2(_) => None,
+        }
+    }
+
+    /// 交易 `mint` 时该池引用的全部静态账户（去重），用于预先为池子构建地址查找表
+    ///
+    /// 由各协议的 [`InstructionBuilder::static_accounts`](crate::trading::core::traits::InstructionBuilder::static_accounts)
+    /// 给出，另外加入每笔交易都会用到的 System / Token / ATA 程序与 WSOL mint。
+    pub fn static_accounts(&self, mint: &Pubkey) -> Result<Vec<Pubkey>> {
+        let executor = crate::trading::factory::TradeFactory::create_executor(self.dex_type());
+        let mut accounts = executor.static_accounts(self, mint)?;
+        accounts.extend([
+            crate::constants::SYSTEM_PROGRAM,
+            TOKEN_PROGRAM,
+            crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID,
+            crate::constants::WSOL_TOKEN_ACCOUNT,
+        ]);
+        let mut seen = std::collections::HashSet::with_capacity(accounts.len());
+        accounts.retain(|account| seen.insert(*account));
+        Ok(accounts)
+    }
+
+    /// 统一构造：按协议类型与池地址通过 RPC 获取完整参数
+    ///
+    /// 池数据经由各协议的 Pool 缓存读取（见 [`crate::common::dex_pool_cache`]），
+    /// 需要最新储备时使用 [`Self::from_pool_force`]。
+    ///
+    /// PumpFun 的 bonding curve 账户不记录 mint，因此 `DexType::PumpFun` 时 `pool` 传代币 mint。
+    /// 失败时返回的错误统一包含协议类型与地址。
+    ///
+    /// 该池已在 [`params_cache`](crate::trading::core::params_cache::params_cache) 中时
+    /// 复用缓存的静态账户，只按规则刷新储备。
+    pub async fn from_pool(
+        rpc: &SolanaRpcClient,
+        dex_type: DexType,
+        pool: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        let cache = crate::trading::core::params_cache::params_cache();
+        match cache.mint_for_pool(&dex_type, pool) {
+            Some(mint) => cache.get(rpc, dex_type, &mint).await,
+            None => Self::from_pool_uncached(rpc, dex_type, pool).await,
+        }
+    }
+
+    /// 统一构造：按协议类型与代币 mint 获取参数，经由全局参数缓存
+    ///
+    /// 首次调用按 mint 查找池子并完整加载；之后只刷新储备（规则见
+    /// [`ParamsCache`](crate::trading::core::params_cache::ParamsCache)）。
+    /// Bonk 使用 WSOL 池；MeteoraDammV2 不支持按 mint 查找，请使用 [`Self::from_pool`]。
+    pub async fn from_mint(
+        rpc: &SolanaRpcClient,
+        dex_type: DexType,
+        mint: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        crate::trading::core::params_cache::params_cache()
+            .get(rpc, dex_type.clone(), mint)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("failed to load {:?} params for mint {}: {}", dex_type, mint, e)
+            })
+    }
+
+    /// 不经过参数缓存，直接加载完整参数
+    pub(crate) async fn from_pool_uncached(
+        rpc: &SolanaRpcClient,
+        dex_type: DexType,
+        pool: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        let params = match dex_type {
+            DexType::PumpFun => {
+                PumpFunParams::from_mint_by_rpc(rpc, pool).await.map(DexParamEnum::PumpFun)
+            },
+            DexType::PumpSwap => PumpSwapParams::from_pool_address_by_rpc(rpc, pool)
+                .await
+                .map(DexParamEnum::PumpSwap),
+            DexType::Bonk => {
+                BonkParams::from_pool_address_by_rpc(rpc, pool).await.map(DexParamEnum::Bonk)
+            },
+            DexType::RaydiumCpmm => RaydiumCpmmParams::from_pool_address_by_rpc(rpc, pool)
+                .await
+                .map(DexParamEnum::RaydiumCpmm),
+            DexType::RaydiumAmmV4 => RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, *pool)
+                .await
+                .map(DexParamEnum::RaydiumAmmV4),
+            DexType::RaydiumClmm => RaydiumClmmParams::from_pool_address_by_rpc(rpc, pool)
+                .await
+                .map(DexParamEnum::RaydiumClmm),
+            DexType::MeteoraDammV2 => MeteoraDammV2Params::from_pool_address_by_rpc(rpc, pool)
+                .await
+                .map(DexParamEnum::MeteoraDammV2),
+        };
+        params.map_err(|e| {
+            anyhow::anyhow!("failed to load {:?} params for {}: {}", dex_type, pool, e)
+        })
+    }
+
+    /// 与 [`Self::from_pool`] 相同，但先刷新该池的缓存数据
+    pub async fn from_pool_force(
+        rpc: &SolanaRpcClient,
+        dex_type: DexType,
+        pool: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
+        use crate::instruction::utils::{
+            bonk, meteora_damm_v2, pumpswap, raydium_amm_v4, raydium_clmm, raydium_cpmm,
+        };
+        let refreshed = match dex_type {
+            // PumpFun 的 bonding curve 不经过缓存
+            DexType::PumpFun => Ok(()),
+            DexType::PumpSwap => pumpswap::get_pool_by_address_force(rpc, pool).await.map(|_| ()),
+            DexType::Bonk => bonk::get_pool_by_address_force(rpc, pool).await.map(|_| ()),
+            DexType::RaydiumCpmm => {
+                raydium_cpmm::get_pool_by_address_force(rpc, pool).await.map(|_| ())
+            },
+            DexType::RaydiumAmmV4 => {
+                raydium_amm_v4::get_pool_by_address_force(rpc, pool).await.map(|_| ())
+            },
+            DexType::RaydiumClmm => {
+                raydium_clmm::get_pool_by_address_force(rpc, pool).await.map(|_| ())
+            },
+            DexType::MeteoraDammV2 => {
+                meteora_damm_v2::get_pool_by_address_force(rpc, pool).await.map(|_| ())
+            },
+        };
+        refreshed.map_err(|e| {
+            anyhow::anyhow!("failed to load {:?} params for {}: {}", dex_type, pool, e)
+        })?;
+        Self::from_pool_uncached(rpc, dex_type, pool).await
+    }
 }
 
 /// Swap parameters
 #[derive(Clone)]
 pub struct SwapParams {
     pub rpc: Option<Arc<SolanaRpcClient>>,
+    /// 自定义 RPC 传输（可选），设置后模拟与发送前模拟优先使用
+    pub rpc_provider: Option<crate::common::RpcProviderRef>,
     pub payer: Arc<Keypair>,
     pub trade_type: TradeType,
     pub input_mint: Pubkey,
@@ -54,9 +222,11 @@ pub struct SwapParams {
     pub output_mint: Pubkey,
     pub output_token_program: Option<Pubkey>,
     pub input_amount: Option<u64>,
-    pub slippage_basis_points: Option<u64>,
+    pub slippage_basis_points: Option<Bps>,
     pub address_lookup_table_account: Option<AddressLookupTableAccount>,
     pub recent_blockhash: Option<Hash>,
+    /// `recent_blockhash` 的最后有效区块高度（写入交易日志）
+    pub last_valid_block_height: Option<u64>,
     pub wait_transaction_confirmed: bool,
     pub protocol_params: DexParamEnum,
     pub open_seed_optimize: bool,
@@ -90,6 +260,33 @@ pub struct SwapParams {
     ///
     /// 参见 `TradeConfig.enable_jito_sandwich_protection` 字段的详细文档。
     pub enable_jito_sandwich_protection: Option<bool>,
+    /// 在协议指令之前插入的自定义指令（位于 compute budget 之后，不能包含 Compute Budget 指令）
+    pub pre_swap_instructions: Vec<Instruction>,
+    /// 在协议指令之后插入的自定义指令
+    pub post_swap_instructions: Vec<Instruction>,
+    /// 用户自定义交易上下文
+    pub metadata: Option<Arc<crate::trading::TradeMetadata>>,
+    /// 手续费账本（可选），用于累计优先费与小费支出
+    pub fee_ledger: Option<Arc<crate::common::fee_ledger::FeeLedger>>,
+    /// 交易日志存储（可选）
+    pub trade_store: Option<crate::trading::store::TradeStoreRef>,
+    /// 发送前模拟策略
+    pub preflight: crate::common::PreflightPolicy,
+    /// 交易指令排列策略
+    pub instruction_layout: crate::trading::common::InstructionLayout,
+    /// Squads 多签执行模式（可选）：设置后交易包装为多签提案，由 payer 作为成员发起
+    pub multisig: Option<crate::trading::multisig::SquadsConfig>,
+    /// 第三方手续费支付者（可选）：设置后由其作为 account[0] 支付手续费并签名
+    pub fee_payer: Option<crate::trading::common::FeePayerRef>,
+    /// 提交队列（可选）：按紧急程度分配构建名额与 provider 发送额度
+    pub submission_queue: Option<Arc<crate::trading::submission_queue::SubmissionQueue>>,
+    pub urgency: crate::trading::submission_queue::Urgency,
+    /// 等待确认的超时与轮询间隔
+    pub confirm: crate::swqos::common::ConfirmConfig,
+    /// 取消令牌：取消后不再发送尚未提交的交易，并停止确认轮询
+    pub cancellation_token: Option<crate::common::cancellation::CancellationToken>,
+    /// SWQOS 分发顺序与间隔（None 表示同时发送）
+    pub fanout: Option<Arc<crate::swqos::fanout::SwqosFanout>>,
 }
 
 impl std::fmt::Debug for SwapParams {
@@ -98,9 +295,18 @@ impl std::fmt::Debug for SwapParams {
     }
 }
 
+impl SwapParams {
+    /// 模拟使用的 RPC 传输：优先自定义 `rpc_provider`，否则使用 `rpc`
+    pub fn simulation_provider(&self) -> Option<crate::common::RpcProviderRef> {
+        self.rpc_provider
+            .clone()
+            .or_else(|| self.rpc.clone().map(|rpc| rpc as crate::common::RpcProviderRef))
+    }
+}
+
 /// PumpFun protocol specific parameters
 /// Configuration parameters specific to PumpFun trading protocol
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PumpFunParams {
     pub bonding_curve: Arc<BondingCurveAccount>,
     pub associated_bonding_curve: Pubkey,
@@ -111,6 +317,29 @@ pub struct PumpFunParams {
 }
 
 impl PumpFunParams {
+    /// 是否为 Mayhem 模式的 bonding curve
+    #[inline]
+    pub fn is_mayhem_mode(&self) -> bool {
+        self.bonding_curve.is_mayhem_mode
+    }
+
+    /// 实际使用的代币程序：Mayhem 模式的代币总是 Token-2022
+    #[inline]
+    pub fn effective_token_program(&self) -> Pubkey {
+        if self.is_mayhem_mode() { TOKEN_PROGRAM_2022 } else { self.token_program }
+    }
+
+    /// 标记 Mayhem 模式（例如 `immediate_sell` 时调用方已从事件中得知），同时切换到 Token-2022
+    pub fn with_mayhem_mode(mut self, is_mayhem_mode: bool) -> Self {
+        let mut bonding_curve = (*self.bonding_curve).clone();
+        bonding_curve.is_mayhem_mode = is_mayhem_mode;
+        self.bonding_curve = Arc::new(bonding_curve);
+        if is_mayhem_mode {
+            self.token_program = TOKEN_PROGRAM_2022;
+        }
+        self
+    }
+
     pub fn immediate_sell(
         creator_vault: Pubkey,
         token_program: Pubkey,
@@ -232,7 +461,7 @@ impl PumpFunParams {
 /// **Performance Note**: If these parameters are not provided, the system will attempt to
 /// retrieve the relevant information from RPC, which will increase transaction time.
 /// For optimal performance, it is recommended to provide all necessary parameters in advance.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PumpSwapParams {
     /// Liquidity pool address
     pub pool: Pubkey,
@@ -250,9 +479,11 @@ pub struct PumpSwapParams {
     pub pool_base_token_reserves: u64,
     /// Quote token reserves in the pool
     pub pool_quote_token_reserves: u64,
-    /// Coin creator vault ATA
+    /// Coin creator（池子 `coin_creator` 字段；默认值表示没有 creator）
+    pub coin_creator: Pubkey,
+    /// Coin creator vault ATA（默认值时由 `coin_creator` 推导）
     pub coin_creator_vault_ata: Pubkey,
-    /// Coin creator vault authority
+    /// Coin creator vault authority（默认值时由 `coin_creator` 推导）
     pub coin_creator_vault_authority: Pubkey,
     /// Token program ID
     pub base_token_program: Pubkey,
@@ -260,6 +491,16 @@ pub struct PumpSwapParams {
     pub quote_token_program: Pubkey,
     /// Whether the pool is in mayhem mode
     pub is_mayhem_mode: bool,
+    /// Fee basis points from GlobalConfig (defaults to the SDK constants until loaded via RPC)
+    pub fee_config: PumpSwapFeeConfig,
+    /// Token-2022 transfer hook of the base mint (None if the mint has no hook)
+    pub base_transfer_hook: Option<Arc<TransferHookInfo>>,
+    /// Token-2022 transfer hook of the quote mint (None if the mint has no hook)
+    pub quote_transfer_hook: Option<Arc<TransferHookInfo>>,
+    /// 买入时 `input_amount` 为含全部手续费的总支出（`buy_exact_quote_in`），而不是滑点前的报价；
+    /// 仅对 quote 为 WSOL / USDC 且未设置 `fixed_output_amount` 的买入生效
+    #[serde(default)]
+    pub exact_quote_in: bool,
 }
 
 impl PumpSwapParams {
@@ -286,14 +527,124 @@ impl PumpSwapParams {
             pool_quote_token_account,
             pool_base_token_reserves,
             pool_quote_token_reserves,
+            coin_creator: Pubkey::default(),
             coin_creator_vault_ata,
             coin_creator_vault_authority,
             base_token_program,
             quote_token_program,
             is_mayhem_mode,
+            fee_config: PumpSwapFeeConfig::default(),
+            base_transfer_hook: None,
+            quote_transfer_hook: None,
+            exact_quote_in: false,
         }
     }
 
+    /// 由已解码的池子账户构造参数，creator vault 由 `pool_data.coin_creator` 推导，
+    /// token program 由池子 token 账户是否为 Token 程序 ATA 判断
+    pub fn from_pool_data(
+        pool: Pubkey,
+        pool_data: &crate::instruction::utils::pumpswap_types::Pool,
+        pool_base_token_reserves: u64,
+        pool_quote_token_reserves: u64,
+    ) -> Self {
+        let token_program_for = |token_account: &Pubkey, mint: &Pubkey| {
+            if *token_account
+                == get_associated_token_address_with_program_id(&pool, mint, &TOKEN_PROGRAM)
+            {
+                TOKEN_PROGRAM
+            } else {
+                crate::constants::TOKEN_PROGRAM_2022
+            }
+        };
+        Self {
+            pool,
+            base_mint: pool_data.base_mint,
+            quote_mint: pool_data.quote_mint,
+            pool_base_token_account: pool_data.pool_base_token_account,
+            pool_quote_token_account: pool_data.pool_quote_token_account,
+            pool_base_token_reserves,
+            pool_quote_token_reserves,
+            coin_creator: Pubkey::default(),
+            coin_creator_vault_ata: Pubkey::default(),
+            coin_creator_vault_authority: Pubkey::default(),
+            base_token_program: token_program_for(
+                &pool_data.pool_base_token_account,
+                &pool_data.base_mint,
+            ),
+            quote_token_program: token_program_for(
+                &pool_data.pool_quote_token_account,
+                &pool_data.quote_mint,
+            ),
+            is_mayhem_mode: pool_data.is_mayhem_mode,
+            fee_config: PumpSwapFeeConfig::default(),
+            base_transfer_hook: None,
+            quote_transfer_hook: None,
+            exact_quote_in: false,
+        }
+        .with_coin_creator(pool_data.coin_creator)
+    }
+
+    /// 买入按含手续费的总支出计算（见 [`PumpSwapParams::exact_quote_in`]）
+    pub fn with_exact_quote_in(mut self, exact_quote_in: bool) -> Self {
+        self.exact_quote_in = exact_quote_in;
+        self
+    }
+
+    /// 设置 coin creator 并重新推导 creator vault authority / ATA
+    pub fn with_coin_creator(mut self, coin_creator: Pubkey) -> Self {
+        self.coin_creator = coin_creator;
+        self.coin_creator_vault_ata = Pubkey::default();
+        self.coin_creator_vault_authority = Pubkey::default();
+        let (ata, authority) = self.resolved_coin_creator_vault();
+        self.coin_creator_vault_ata = ata;
+        self.coin_creator_vault_authority = authority;
+        self
+    }
+
+    /// 实际使用的 (creator vault ATA, creator vault authority)
+    ///
+    /// 显式传入的字段优先；为默认值时由 `coin_creator` 推导
+    /// （没有 creator 的池子推导结果即 `DEFAULT_COIN_CREATOR_VAULT_AUTHORITY`）。
+    pub fn resolved_coin_creator_vault(&self) -> (Pubkey, Pubkey) {
+        let authority = if self.coin_creator_vault_authority == Pubkey::default() {
+            crate::instruction::utils::pumpswap::coin_creator_vault_authority(self.coin_creator)
+        } else {
+            self.coin_creator_vault_authority
+        };
+        let ata = if self.coin_creator_vault_ata == Pubkey::default() {
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                &authority,
+                &self.quote_mint,
+                &self.quote_token_program,
+            )
+        } else {
+            self.coin_creator_vault_ata
+        };
+        (ata, authority)
+    }
+
+    /// 指定 mint（base 或 quote）的 transfer hook
+    pub fn transfer_hook_for(&self, mint: &Pubkey) -> Option<&TransferHookInfo> {
+        if *mint == self.base_mint {
+            self.base_transfer_hook.as_deref()
+        } else if *mint == self.quote_mint {
+            self.quote_transfer_hook.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// 通过 RPC 加载 base / quote mint 的 transfer hook（用于 `new` 构造的参数）
+    pub async fn with_transfer_hooks(
+        mut self,
+        rpc: &SolanaRpcClient,
+    ) -> Result<Self, anyhow::Error> {
+        self.base_transfer_hook = fetch_transfer_hook_info(rpc, &self.base_mint).await?;
+        self.quote_transfer_hook = fetch_transfer_hook_info(rpc, &self.quote_mint).await?;
+        Ok(self)
+    }
+
     pub async fn from_mint_by_rpc(
         rpc: &SolanaRpcClient,
         mint: &Pubkey,
@@ -309,55 +660,52 @@ impl PumpSwapParams {
     ) -> Result<Self, anyhow::Error> {
         let pool_data =
             crate::instruction::utils::pumpswap::get_pool_by_address(rpc, pool_address).await?;
-        let (pool_base_token_reserves, pool_quote_token_reserves) =
-            crate::instruction::utils::pumpswap::get_token_balances(&pool_data, rpc).await?;
-        let creator = pool_data.coin_creator;
-        let coin_creator_vault_ata = crate::instruction::utils::pumpswap::coin_creator_vault_ata(
-            creator,
-            pool_data.quote_mint,
-        );
-        let coin_creator_vault_authority =
-            crate::instruction::utils::pumpswap::coin_creator_vault_authority(creator);
-
-        let base_token_program_ata = get_associated_token_address_with_program_id(
-            pool_address,
-            &pool_data.base_mint,
-            &crate::constants::TOKEN_PROGRAM,
-        );
-        let quote_token_program_ata = get_associated_token_address_with_program_id(
-            pool_address,
-            &pool_data.quote_mint,
-            &crate::constants::TOKEN_PROGRAM,
+        let (balances, fee_config) = tokio::join!(
+            crate::instruction::utils::pumpswap::get_token_balances(&pool_data, rpc),
+            crate::instruction::utils::pumpswap::get_fee_config(rpc),
         );
-
-        Ok(Self {
-            pool: *pool_address,
-            base_mint: pool_data.base_mint,
-            quote_mint: pool_data.quote_mint,
-            pool_base_token_account: pool_data.pool_base_token_account,
-            pool_quote_token_account: pool_data.pool_quote_token_account,
+        let (pool_base_token_reserves, pool_quote_token_reserves) = balances?;
+        let mut params = Self::from_pool_data(
+            *pool_address,
+            &pool_data,
             pool_base_token_reserves,
             pool_quote_token_reserves,
-            coin_creator_vault_ata,
-            coin_creator_vault_authority,
-            base_token_program: if pool_data.pool_base_token_account == base_token_program_ata {
-                crate::constants::TOKEN_PROGRAM
-            } else {
-                crate::constants::TOKEN_PROGRAM_2022
-            },
-            quote_token_program: if pool_data.pool_quote_token_account == quote_token_program_ata {
-                crate::constants::TOKEN_PROGRAM
-            } else {
-                crate::constants::TOKEN_PROGRAM_2022
-            },
-            is_mayhem_mode: pool_data.is_mayhem_mode,
-        })
+        );
+        params.fee_config = fee_config?;
+        if params.base_token_program != TOKEN_PROGRAM {
+            params.base_transfer_hook = fetch_transfer_hook_info(rpc, &params.base_mint).await?;
+        }
+        if params.quote_token_program != TOKEN_PROGRAM {
+            params.quote_transfer_hook = fetch_transfer_hook_info(rpc, &params.quote_mint).await?;
+        }
+        Ok(params)
+    }
+
+    /// 只刷新会变化的状态（池子储备、费率配置），跨多笔交易复用参数时使用；
+    /// 账户地址、token program、transfer hook 等不可变字段保持不变
+    pub async fn refresh<T: PoolRpcClient + ?Sized>(
+        &mut self,
+        rpc: &T,
+    ) -> Result<(), anyhow::Error> {
+        let (balances, fee_config) = tokio::join!(
+            crate::instruction::utils::pumpswap::get_vault_balances(
+                rpc,
+                &self.pool_base_token_account,
+                &self.pool_quote_token_account,
+            ),
+            crate::instruction::utils::pumpswap::get_fee_config(rpc),
+        );
+        let (pool_base_token_reserves, pool_quote_token_reserves) = balances?;
+        self.pool_base_token_reserves = pool_base_token_reserves;
+        self.pool_quote_token_reserves = pool_quote_token_reserves;
+        self.fee_config = fee_config?;
+        Ok(())
     }
 }
 
 /// Bonk protocol specific parameters
 /// Configuration parameters specific to Bonk trading protocol
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct BonkParams {
     pub virtual_base: u128,
     pub virtual_quote: u128,
@@ -448,7 +796,7 @@ impl BonkParams {
                 DEFAULT_VIRTUAL_QUOTE,
                 0,
                 0,
-                0,
+                crate::common::Bps::ZERO,
             )
         };
         let real_quote = crate::instruction::utils::bonk::get_amount_in_net(
@@ -467,7 +815,7 @@ impl BonkParams {
                 DEFAULT_VIRTUAL_QUOTE,
                 0,
                 0,
-                0,
+                crate::common::Bps::ZERO,
             ) as u128
         } else {
             amount_out as u128
@@ -503,8 +851,15 @@ impl BonkParams {
             },
         )
         .ok_or_else(|| anyhow::anyhow!("Bonk pool PDA not found for mint"))?;
+        Self::from_pool_address_by_rpc(rpc, &pool_address).await
+    }
+
+    pub async fn from_pool_address_by_rpc(
+        rpc: &SolanaRpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<Self, anyhow::Error> {
         let pool_data =
-            crate::instruction::utils::bonk::get_pool_by_address(rpc, &pool_address).await?;
+            crate::instruction::utils::bonk::get_pool_by_address(rpc, pool_address).await?;
         let token_account = rpc.get_account(&pool_data.base_mint).await?;
         let platform_associated_account =
             crate::instruction::utils::bonk::get_platform_associated_account(
@@ -519,21 +874,24 @@ impl BonkParams {
             virtual_quote: pool_data.virtual_quote as u128,
             real_base: pool_data.real_base as u128,
             real_quote: pool_data.real_quote as u128,
-            pool_state: pool_address,
+            pool_state: *pool_address,
             base_vault: pool_data.base_vault,
             quote_vault: pool_data.quote_vault,
             mint_token_program: token_account.owner,
             platform_config: pool_data.platform_config,
             platform_associated_account,
             creator_associated_account,
-            global_config: pool_data.global_config,
+            global_config: crate::instruction::utils::bonk::resolve_global_config(
+                &pool_data.quote_mint,
+            )
+            .unwrap_or(pool_data.global_config),
         })
     }
 }
 
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RaydiumCpmmParams {
     /// Pool address
     pub pool_state: Pubkey,
@@ -557,6 +915,10 @@ pub struct RaydiumCpmmParams {
     pub quote_token_program: Pubkey,
     /// Observation state account
     pub observation_state: Pubkey,
+    /// Token-2022 transfer hook of the base mint (None if the mint has no hook)
+    pub base_transfer_hook: Option<Arc<TransferHookInfo>>,
+    /// Token-2022 transfer hook of the quote mint (None if the mint has no hook)
+    pub quote_transfer_hook: Option<Arc<TransferHookInfo>>,
 }
 
 impl RaydiumCpmmParams {
@@ -585,9 +947,32 @@ impl RaydiumCpmmParams {
             base_token_program: input_token_program,
             quote_token_program: output_token_program,
             observation_state,
+            base_transfer_hook: None,
+            quote_transfer_hook: None,
+        }
+    }
+
+    /// 指定 mint（base 或 quote）的 transfer hook
+    pub fn transfer_hook_for(&self, mint: &Pubkey) -> Option<&TransferHookInfo> {
+        if *mint == self.base_mint {
+            self.base_transfer_hook.as_deref()
+        } else if *mint == self.quote_mint {
+            self.quote_transfer_hook.as_deref()
+        } else {
+            None
         }
     }
 
+    /// 通过 RPC 加载 base / quote mint 的 transfer hook（用于 `from_trade` 构造的参数）
+    pub async fn with_transfer_hooks(
+        mut self,
+        rpc: &SolanaRpcClient,
+    ) -> Result<Self, anyhow::Error> {
+        self.base_transfer_hook = fetch_transfer_hook_info(rpc, &self.base_mint).await?;
+        self.quote_transfer_hook = fetch_transfer_hook_info(rpc, &self.quote_mint).await?;
+        Ok(self)
+    }
+
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
@@ -602,6 +987,16 @@ impl RaydiumCpmmParams {
                 &pool.token1_mint,
             )
             .await?;
+        let base_transfer_hook = if pool.token0_program == crate::constants::TOKEN_PROGRAM_2022 {
+            fetch_transfer_hook_info(rpc, &pool.token0_mint).await?
+        } else {
+            None
+        };
+        let quote_transfer_hook = if pool.token1_program == crate::constants::TOKEN_PROGRAM_2022 {
+            fetch_transfer_hook_info(rpc, &pool.token1_mint).await?
+        } else {
+            None
+        };
         Ok(Self {
             pool_state: *pool_address,
             amm_config: pool.amm_config,
@@ -614,13 +1009,15 @@ impl RaydiumCpmmParams {
             base_token_program: pool.token0_program,
             quote_token_program: pool.token1_program,
             observation_state: pool.observation_key,
+            base_transfer_hook,
+            quote_transfer_hook,
         })
     }
 }
 
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RaydiumAmmV4Params {
     /// AMM pool address
     pub amm: Pubkey,
@@ -636,6 +1033,9 @@ pub struct RaydiumAmmV4Params {
     pub coin_reserve: u64,
     /// Current pc reserve amount in the pool
     pub pc_reserve: u64,
+    /// 兑换模式，买卖均生效；默认 ExactIn（SwapBaseIn），ExactOut 使用 SwapBaseOut
+    #[serde(default)]
+    pub swap_mode: SwapMode,
 }
 
 impl RaydiumAmmV4Params {
@@ -656,9 +1056,15 @@ impl RaydiumAmmV4Params {
             token_pc,
             coin_reserve,
             pc_reserve,
+            swap_mode: SwapMode::ExactIn,
         }
     }
 
+    pub fn with_swap_mode(mut self, swap_mode: SwapMode) -> Self {
+        self.swap_mode = swap_mode;
+        self
+    }
+
     /// 从 AMM 地址通过 RPC 获取参数（泛型版本，支持 Auto Mock）
     pub async fn from_amm_address_by_rpc_with_client<T: PoolRpcClient + ?Sized>(
         rpc: &T,
@@ -677,6 +1083,7 @@ impl RaydiumAmmV4Params {
             token_pc: amm_info.token_pc,
             coin_reserve,
             pc_reserve,
+            swap_mode: SwapMode::ExactIn,
         })
     }
 
@@ -689,9 +1096,85 @@ impl RaydiumAmmV4Params {
     }
 }
 
+/// Raydium CLMM 兑换方向（按池内 token0/token1 排序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapDirection {
+    /// token0 -> token1，价格下降
+    ZeroForOne,
+    /// token1 -> token0，价格上升
+    OneForZero,
+}
+
+impl SwapDirection {
+    /// 官方 SDK 的 MIN_SQRT_PRICE_X64 / MAX_SQRT_PRICE_X64
+    pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+    pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
+
+    pub fn is_zero_for_one(&self) -> bool {
+        matches!(self, SwapDirection::ZeroForOne)
+    }
+
+    /// 不限价时的 sqrt_price_limit_x64，与 SDK 一致：
+    /// ZeroForOne 取 MIN_SQRT_PRICE_X64_ADD_ONE，OneForZero 取 MAX_SQRT_PRICE_X64_SUB_ONE
+    pub fn sqrt_price_limit_x64(&self) -> u128 {
+        match self {
+            SwapDirection::ZeroForOne => Self::MIN_SQRT_PRICE_X64 + 1,
+            SwapDirection::OneForZero => Self::MAX_SQRT_PRICE_X64 - 1,
+        }
+    }
+}
+
+/// Raydium CLMM 兑换模式（SwapV2 的 `is_base_input`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapMode {
+    /// 固定输入：amount 为输入量，other_amount_threshold 为最小输出（默认）
+    #[default]
+    ExactIn,
+    /// 固定输出：amount 为 `fixed_output_token_amount`，
+    /// other_amount_threshold 为最大输入（取 `input_token_amount`）
+    ExactOut,
+}
+
+impl SwapMode {
+    pub fn is_base_input(&self) -> bool {
+        matches!(self, SwapMode::ExactIn)
+    }
+}
+
+/// Raydium CLMM 沿兑换方向预取并注册的 tick array 数量（含当前 array）
+///
+/// 报价按该数量读取 tick array，SwapV2 按该数量注册 tick array PDA；兑换跨过最后一个
+/// 注册的 array 时链上会失败。每多注册一个约增加 32 字节交易大小（未使用 ALT 时）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TickArrayDepth {
+    /// 固定数量
+    Fixed(usize),
+    /// 按交易量与当前流动性估算跨越的 array 数，限制在 `[min, max]` 内（默认 5 - 10）
+    Adaptive { min: usize, max: usize },
+}
+
+impl Default for TickArrayDepth {
+    fn default() -> Self {
+        TickArrayDepth::Adaptive { min: 5, max: 10 }
+    }
+}
+
+impl TickArrayDepth {
+    /// 按估算的跨越数量得到实际深度（至少为 1）
+    pub fn resolve(&self, estimated: usize) -> usize {
+        match *self {
+            TickArrayDepth::Fixed(depth) => depth.max(1),
+            TickArrayDepth::Adaptive { min, max } => {
+                let min = min.max(1);
+                estimated.clamp(min, max.max(min))
+            },
+        }
+    }
+}
+
 /// RaydiumClmm protocol specific parameters
 /// Configuration parameters specific to Raydium CLMM trading protocol
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RaydiumClmmParams {
     /// Pool state address
     pub pool_state: Pubkey,
@@ -715,6 +1198,10 @@ pub struct RaydiumClmmParams {
     pub token0_program: Pubkey,
     /// Token1 program ID
     pub token1_program: Pubkey,
+    /// 兑换模式，买卖均生效；默认 ExactIn
+    pub swap_mode: SwapMode,
+    /// tick array 预取深度；默认按交易量自适应
+    pub tick_array_depth: TickArrayDepth,
 }
 
 impl RaydiumClmmParams {
@@ -743,6 +1230,29 @@ impl RaydiumClmmParams {
             token1_decimals,
             token0_program,
             token1_program,
+            swap_mode: SwapMode::ExactIn,
+            tick_array_depth: TickArrayDepth::default(),
+        }
+    }
+
+    pub fn with_swap_mode(mut self, swap_mode: SwapMode) -> Self {
+        self.swap_mode = swap_mode;
+        self
+    }
+
+    pub fn with_tick_array_depth(mut self, tick_array_depth: TickArrayDepth) -> Self {
+        self.tick_array_depth = tick_array_depth;
+        self
+    }
+
+    /// 按输入代币确定兑换方向；输入代币不属于本池时返回错误
+    pub fn swap_direction(&self, input_mint: &Pubkey) -> Result<SwapDirection> {
+        if *input_mint == self.token0_mint {
+            Ok(SwapDirection::ZeroForOne)
+        } else if *input_mint == self.token1_mint {
+            Ok(SwapDirection::OneForZero)
+        } else {
+            Err(anyhow::anyhow!("Input mint {} does not match pool tokens", input_mint))
         }
     }
 
@@ -782,13 +1292,15 @@ impl RaydiumClmmParams {
             token1_decimals: pool_state.mint_decimals1,
             token0_program,
             token1_program,
+            swap_mode: SwapMode::ExactIn,
+            tick_array_depth: TickArrayDepth::default(),
         })
     }
 }
 
 /// MeteoraDammV2 protocol specific parameters
 /// Configuration parameters specific to Meteora Damm V2 trading protocol
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MeteoraDammV2Params {
     pub pool: Pubkey,
     pub token_a_vault: Pubkey,
@@ -833,8 +1345,39 @@ impl MeteoraDammV2Params {
             token_b_vault: pool_data.token_b_vault,
             token_a_mint: pool_data.token_a_mint,
             token_b_mint: pool_data.token_b_mint,
-            token_a_program: TOKEN_PROGRAM,
-            token_b_program: TOKEN_PROGRAM,
+            token_a_program: pool_data.token_a_program(),
+            token_b_program: pool_data.token_b_program(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_params_serde_round_trip() {
+        let mut clmm = RaydiumClmmParams::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            9,
+            TOKEN_PROGRAM,
+            TOKEN_PROGRAM_2022,
+        );
+        clmm.swap_mode = SwapMode::ExactOut;
+        clmm.tick_array_depth = TickArrayDepth::Fixed(3);
+        let bonk = BonkParams { virtual_base: u64::MAX as u128 + 1, ..Default::default() };
+
+        for params in [DexParamEnum::RaydiumClmm(clmm), DexParamEnum::Bonk(bonk)] {
+            let json = serde_json::to_string(&params).unwrap();
+            let decoded: DexParamEnum = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        }
+    }
+}
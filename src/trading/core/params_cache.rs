@@ -0,0 +1,287 @@
+//! 按 mint 缓存的协议参数
+//!
+//! 各协议参数由两部分组成：池地址、vault、token program、transfer hook 等静态账户，
+//! 以及随每笔成交变化的储备。[`ParamsCache`] 以 `(DexType, mint)` 为键：
+//!
+//! - 首次访问时按 mint 查找池子并加载完整参数，静态部分此后一直复用；
+//! - 再次访问时按该协议的 [`Freshness`] 规则只刷新储备（默认每笔交易刷新一次）。
+//!
+//! [`DexParamEnum::from_mint`] 自动使用全局缓存 [`params_cache`]；[`DexParamEnum::from_pool`]
+//! 命中已缓存的池地址时同样走缓存。`clear_all_pool_caches` 会一并清空该缓存。
+//!
+//! 安装了跨进程共享缓存（[`crate::common::shared_pool_cache`]）时：只读进程在本地未命中或储备过期时
+//! 优先采用共享缓存中仍满足刷新规则的条目；写入进程在每次加载 / 刷新后发布到共享缓存。
+
+use crate::common::SolanaRpcClient;
+use crate::common::shared_pool_cache::shared_pool_cache;
+use crate::instruction::utils::{
+    bonk, pumpfun, pumpswap, raydium_amm_v4, raydium_clmm, raydium_cpmm,
+};
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use std::time::{Duration, Instant};
+
+/// 储备的刷新规则（静态账户始终缓存）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Freshness {
+    /// 每次读取都刷新储备
+    #[default]
+    PerTrade,
+    /// 距上次刷新超过给定时间才刷新
+    MaxAge(Duration),
+    /// 不刷新储备（由调用方自行维护，例如通过事件流更新后 [`ParamsCache::insert`]）
+    Static,
+}
+
+impl Freshness {
+    /// 上次刷新时间为 `refreshed_at` 的参数在 `now` 是否需要刷新
+    pub fn is_stale(&self, refreshed_at: Instant, now: Instant) -> bool {
+        match self {
+            Freshness::PerTrade => true,
+            Freshness::MaxAge(max_age) => now.saturating_duration_since(refreshed_at) >= *max_age,
+            Freshness::Static => false,
+        }
+    }
+}
+
+/// 缓存的参数及其新鲜度信息
+#[derive(Clone)]
+pub struct CachedParams {
+    pub params: DexParamEnum,
+    /// 池地址（PumpFun 为 mint，与 [`DexParamEnum::from_pool`] 的约定一致）
+    pub pool: Pubkey,
+    /// 完整加载（含静态账户）的时间
+    pub loaded_at: Instant,
+    /// 储备最近一次刷新的时间
+    pub refreshed_at: Instant,
+}
+
+/// 按 `(DexType, mint)` 缓存的协议参数
+pub struct ParamsCache {
+    entries: DashMap<(DexType, Pubkey), CachedParams>,
+    /// `(DexType, pool)` -> mint
+    pools: DashMap<(DexType, Pubkey), Pubkey>,
+    rules: DashMap<DexType, Freshness>,
+}
+
+static PARAMS_CACHE: Lazy<ParamsCache> = Lazy::new(ParamsCache::new);
+
+/// 全局参数缓存
+pub fn params_cache() -> &'static ParamsCache {
+    &PARAMS_CACHE
+}
+
+impl Default for ParamsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParamsCache {
+    pub fn new() -> Self {
+        Self {
+            entries: DashMap::new(),
+            pools: DashMap::new(),
+            rules: DashMap::new(),
+        }
+    }
+
+    /// 协议的储备刷新规则（未设置时为 [`Freshness::PerTrade`]）
+    pub fn rule(&self, dex_type: &DexType) -> Freshness {
+        self.rules.get(dex_type).map(|rule| *rule).unwrap_or_default()
+    }
+
+    /// 设置协议的储备刷新规则
+    pub fn set_rule(&self, dex_type: DexType, freshness: Freshness) {
+        self.rules.insert(dex_type, freshness);
+    }
+
+    /// 不访问 RPC，直接读取缓存条目
+    pub fn peek(&self, dex_type: &DexType, mint: &Pubkey) -> Option<CachedParams> {
+        self.entries.get(&(dex_type.clone(), *mint)).map(|entry| entry.clone())
+    }
+
+    /// 写入（或覆盖）缓存条目，例如由事件流构造的参数
+    pub fn insert(&self, dex_type: DexType, mint: Pubkey, pool: Pubkey, params: DexParamEnum) {
+        let now = Instant::now();
+        self.store(
+            dex_type,
+            mint,
+            CachedParams { params, pool, loaded_at: now, refreshed_at: now },
+        );
+    }
+
+    fn store(&self, dex_type: DexType, mint: Pubkey, entry: CachedParams) {
+        self.pools.insert((dex_type.clone(), entry.pool), mint);
+        self.entries.insert((dex_type, mint), entry);
+    }
+
+    /// 移除一个 mint 的缓存（例如迁移到新池后）
+    pub fn invalidate(&self, dex_type: &DexType, mint: &Pubkey) {
+        if let Some((_, entry)) = self.entries.remove(&(dex_type.clone(), *mint)) {
+            self.pools.remove(&(dex_type.clone(), entry.pool));
+        }
+    }
+
+    pub fn clear(&self) {
+        self.entries.clear();
+        self.pools.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 已缓存池地址对应的 mint
+    pub fn mint_for_pool(&self, dex_type: &DexType, pool: &Pubkey) -> Option<Pubkey> {
+        self.pools.get(&(dex_type.clone(), *pool)).map(|mint| *mint)
+    }
+
+    /// 读取 mint 的参数：未缓存时按 mint 查找池子并完整加载，已缓存时按规则刷新储备
+    pub async fn get(
+        &self,
+        rpc: &SolanaRpcClient,
+        dex_type: DexType,
+        mint: &Pubkey,
+    ) -> Result<DexParamEnum> {
+        let key = (dex_type.clone(), *mint);
+        let rule = self.rule(&dex_type);
+        let cached = self.entries.get(&key).map(|entry| entry.clone());
+        let Some(mut entry) = cached else {
+            if let Some(mut entry) = shared_entry(&dex_type, mint) {
+                // 共享缓存提供了静态账户，储备过期时只需刷新储备
+                if rule.is_stale(entry.refreshed_at, Instant::now()) {
+                    refresh_reserves(rpc, &mut entry).await?;
+                    entry.refreshed_at = Instant::now();
+                }
+                let params = entry.params.clone();
+                self.store(dex_type, *mint, entry);
+                return Ok(params);
+            }
+            let pool = pool_for_mint(rpc, &dex_type, mint).await?;
+            let params = DexParamEnum::from_pool_uncached(rpc, dex_type.clone(), &pool).await?;
+            publish(&dex_type, mint, &pool, &params);
+            self.insert(dex_type, *mint, pool, params.clone());
+            return Ok(params);
+        };
+        let now = Instant::now();
+        if !rule.is_stale(entry.refreshed_at, now) {
+            return Ok(entry.params);
+        }
+        match shared_entry(&dex_type, mint) {
+            Some(shared)
+                if shared.refreshed_at > entry.refreshed_at
+                    && !rule.is_stale(shared.refreshed_at, now) =>
+            {
+                entry = shared;
+            },
+            _ => {
+                refresh_reserves(rpc, &mut entry).await?;
+                entry.refreshed_at = Instant::now();
+                publish(&dex_type, mint, &entry.pool, &entry.params);
+            },
+        }
+        let params = entry.params.clone();
+        // 刷新期间条目被移除（invalidate / clear）时不再写回
+        if let Some(mut slot) = self.entries.get_mut(&key) {
+            *slot = entry;
+        }
+        Ok(params)
+    }
+}
+
+/// 只读进程从共享缓存读取条目，刷新时间按发布时间折算
+fn shared_entry(dex_type: &DexType, mint: &Pubkey) -> Option<CachedParams> {
+    let shared = shared_pool_cache().filter(|cache| !cache.is_writer())?;
+    let entry = shared.get_params(dex_type, mint)?;
+    let now = Instant::now();
+    let refreshed_at = now.checked_sub(entry.age()).unwrap_or(now);
+    Some(CachedParams {
+        params: entry.params,
+        pool: entry.pool,
+        loaded_at: refreshed_at,
+        refreshed_at,
+    })
+}
+
+/// 写入进程发布加载 / 刷新后的参数
+fn publish(dex_type: &DexType, mint: &Pubkey, pool: &Pubkey, params: &DexParamEnum) {
+    if let Some(shared) = shared_pool_cache().filter(|cache| cache.is_writer())
+        && let Err(e) = shared.put_params(dex_type, mint, pool, params)
+    {
+        log::warn!("failed to publish {:?} params of {}: {}", dex_type, mint, e);
+    }
+}
+
+/// 按 mint 查找 `from_pool` 使用的池地址
+async fn pool_for_mint(rpc: &SolanaRpcClient, dex_type: &DexType, mint: &Pubkey) -> Result<Pubkey> {
+    Ok(match dex_type {
+        DexType::PumpFun => *mint,
+        DexType::PumpSwap => pumpswap::get_pool_by_mint(rpc, mint).await?.0,
+        DexType::Bonk => bonk::get_pool_pda(mint, &crate::constants::WSOL_TOKEN_ACCOUNT)
+            .ok_or_else(|| anyhow!("Bonk pool PDA not found for mint {}", mint))?,
+        DexType::RaydiumCpmm => raydium_cpmm::get_pool_by_mint(rpc, mint).await?.0,
+        DexType::RaydiumAmmV4 => raydium_amm_v4::get_pool_by_mint(rpc, mint).await?.0,
+        DexType::RaydiumClmm => raydium_clmm::get_pool_by_mint(rpc, mint).await?.0,
+        DexType::MeteoraDammV2 => {
+            return Err(anyhow!(
+                "MeteoraDammV2 pools cannot be looked up by mint; use DexParamEnum::from_pool"
+            ));
+        },
+    })
+}
+
+/// 只刷新会变化的状态，静态账户沿用缓存
+async fn refresh_reserves(rpc: &SolanaRpcClient, entry: &mut CachedParams) -> Result<()> {
+    match &mut entry.params {
+        DexParamEnum::PumpFun(p) => {
+            let mint = entry.pool;
+            let (bonding_curve, _) = pumpfun::fetch_bonding_curve_account(rpc, &mint).await?;
+            p.bonding_curve = bonding_curve;
+        },
+        DexParamEnum::PumpSwap(p) => p.refresh(rpc).await?,
+        params => {
+            *params = DexParamEnum::from_pool_force(rpc, params.dex_type(), &entry.pool).await?;
+        },
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::BonkParams;
+
+    #[test]
+    fn test_freshness_and_entries() {
+        let now = Instant::now();
+        let earlier = now - Duration::from_secs(5);
+        assert!(Freshness::PerTrade.is_stale(now, now));
+        assert!(!Freshness::Static.is_stale(earlier, now));
+        assert!(Freshness::MaxAge(Duration::from_secs(5)).is_stale(earlier, now));
+        assert!(!Freshness::MaxAge(Duration::from_secs(10)).is_stale(earlier, now));
+
+        let cache = ParamsCache::new();
+        assert_eq!(cache.rule(&DexType::Bonk), Freshness::PerTrade);
+        cache.set_rule(DexType::Bonk, Freshness::Static);
+        assert_eq!(cache.rule(&DexType::Bonk), Freshness::Static);
+
+        let (mint, pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        cache.insert(DexType::Bonk, mint, pool, DexParamEnum::Bonk(BonkParams::default()));
+        assert_eq!(cache.mint_for_pool(&DexType::Bonk, &pool), Some(mint));
+        assert_eq!(cache.peek(&DexType::Bonk, &mint).map(|e| e.pool), Some(pool));
+        assert!(cache.peek(&DexType::PumpSwap, &mint).is_none());
+
+        cache.invalidate(&DexType::Bonk, &mint);
+        assert!(cache.is_empty());
+        assert_eq!(cache.mint_for_pool(&DexType::Bonk, &pool), None);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::trading::SwapParams;
+use crate::trading::core::params::DexParamEnum;
 use anyhow::Result;
-use solana_sdk::{instruction::Instruction, signature::Signature};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signature};
 
 /// 交易执行器trait - 定义了所有交易协议都需要实现的核心方法
 #[async_trait::async_trait]
@@ -13,6 +14,11 @@ pub trait TradeExecutor: Send + Sync {
         &self,
         params: SwapParams,
     ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)>;
+    /// 仅构建协议指令（含预处理与中间件），不签名、不发送
+    async fn build_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>>;
+    /// 该池交易 `mint` 时引用的静态账户，见 [`InstructionBuilder::static_accounts`]
+    fn static_accounts(&self, protocol_params: &DexParamEnum, mint: &Pubkey)
+    -> Result<Vec<Pubkey>>;
     /// 获取协议名称
     fn protocol_name(&self) -> &'static str;
 }
@@ -25,4 +31,11 @@ pub trait InstructionBuilder: Send + Sync {
 
     /// 构建卖出指令
     async fn build_sell_instructions(&self, params: &SwapParams) -> Result<Vec<Instruction>>;
+
+    /// 该池交易 `mint` 时买卖指令引用的全部静态账户，用于预先为池子构建地址查找表
+    ///
+    /// 不含用户相关账户（钱包、用户 ATA、用户 PDA）、随价格移动的账户（CLMM tick array）
+    /// 以及顶层调用的 DEX 程序本身（被调用的程序不能通过查找表加载）。
+    fn static_accounts(&self, protocol_params: &DexParamEnum, mint: &Pubkey)
+    -> Result<Vec<Pubkey>>;
 }
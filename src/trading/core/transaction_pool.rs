@@ -5,7 +5,13 @@
 //! - 零分配构建
 //! - 零拷贝 I/O
 //! - 内存预热
+//!
+//! 指令本身的缓冲区由 [`crate::perf::arena`] 复用。
+//!
+//! 池容量、预热数量与序列化缓冲区大小可通过 `TradeConfig::with_performance` 调整，
+//! [`reset_pools`] 按当前配置重建所有池。
 
+use arc_swap::ArcSwap;
 use crossbeam_queue::ArrayQueue;
 use once_cell::sync::Lazy;
 use solana_sdk::{
@@ -15,10 +21,59 @@ use solana_sdk::{
     pubkey::Pubkey,
 };
 use std::sync::Arc;
+
+/// 对象池容量与预热配置（进程级共享，以最后创建的 `TradingClient` 配置为准）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerformanceConfig {
+    /// 交易构建器池容量（默认：1000）
+    pub tx_builder_pool_size: usize,
+    /// 启动时预分配的交易构建器数量（默认：100）
+    pub tx_builder_prewarm: usize,
+    /// 序列化缓冲池容量（默认：10_000）
+    pub serializer_pool_size: usize,
+    /// 单个序列化缓冲区大小（字节，默认：256KB）
+    pub serializer_buffer_size: usize,
+    /// 启动时预分配的序列化缓冲区数量（默认：10_000）
+    pub serializer_prewarm: usize,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            tx_builder_pool_size: 1000,
+            tx_builder_prewarm: 100,
+            serializer_pool_size: 10_000,
+            serializer_buffer_size: 256 * 1024,
+            serializer_prewarm: 10_000,
+        }
+    }
+}
+
+impl PerformanceConfig {
+    /// 低内存配置：适合每秒仅数笔交易的部署
+    pub fn low_memory() -> Self {
+        Self {
+            tx_builder_pool_size: 64,
+            tx_builder_prewarm: 8,
+            serializer_pool_size: 64,
+            serializer_buffer_size: 4 * 1024,
+            serializer_prewarm: 8,
+        }
+    }
+
+    fn normalized(mut self) -> Self {
+        // ArrayQueue 容量不能为 0；预热数量不超过容量
+        self.tx_builder_pool_size = self.tx_builder_pool_size.max(1);
+        self.tx_builder_prewarm = self.tx_builder_prewarm.min(self.tx_builder_pool_size);
+        self.serializer_pool_size = self.serializer_pool_size.max(1);
+        self.serializer_buffer_size = self.serializer_buffer_size.max(1);
+        self.serializer_prewarm = self.serializer_prewarm.min(self.serializer_pool_size);
+        self
+    }
+}
+
 /// 预分配的交易构建器
 pub struct PreallocatedTxBuilder {
-    /// 预分配的指令容器
-    instructions: Vec<Instruction>,
     /// 预分配的地址查找表
     lookup_tables: Vec<v0::MessageAddressTableLookup>,
 }
@@ -26,7 +81,6 @@ pub struct PreallocatedTxBuilder {
 impl PreallocatedTxBuilder {
     fn new() -> Self {
         Self {
-            instructions: Vec::with_capacity(32), // 预分配32条指令空间
             lookup_tables: Vec::with_capacity(8), // 预分配8个查找表空间
         }
     }
@@ -34,7 +88,6 @@ impl PreallocatedTxBuilder {
     /// 重置构建器 (清空但保留容量)
     #[inline(always)]
     fn reset(&mut self) {
-        self.instructions.clear();
         self.lookup_tables.clear();
     }
 
@@ -59,15 +112,14 @@ impl PreallocatedTxBuilder {
         address_lookup_table_account: Option<AddressLookupTableAccount>,
         recent_blockhash: Hash,
     ) -> VersionedMessage {
-        // 重用已分配的 vector
+        // 直接基于调用方的指令编译，不再逐条拷贝
         self.reset();
-        self.instructions.extend_from_slice(instructions);
 
         // ✅ 如果有查找表，使用 V0 消息
         if let Some(address_lookup_table_account) = address_lookup_table_account {
             let message = v0::Message::try_compile(
                 payer,
-                &self.instructions,
+                instructions,
                 &[address_lookup_table_account],
                 recent_blockhash,
             )
@@ -76,41 +128,78 @@ impl PreallocatedTxBuilder {
             VersionedMessage::V0(message)
         } else {
             // ✅ 没有查找表，使用 Legacy 消息（兼容所有 RPC）
-            let message =
-                Message::new_with_blockhash(&self.instructions, Some(payer), &recent_blockhash);
+            let message = Message::new_with_blockhash(instructions, Some(payer), &recent_blockhash);
             VersionedMessage::Legacy(message)
         }
     }
 }
 
-/// 🚀 全局交易构建器对象池
-static TX_BUILDER_POOL: Lazy<Arc<ArrayQueue<PreallocatedTxBuilder>>> = Lazy::new(|| {
-    let pool = ArrayQueue::new(1000); // 1000个预分配构建器
+/// 当前生效的池配置
+static PERFORMANCE_CONFIG: Lazy<ArcSwap<PerformanceConfig>> =
+    Lazy::new(|| ArcSwap::from_pointee(PerformanceConfig::default()));
+
+fn new_builder_pool(config: &PerformanceConfig) -> Arc<ArrayQueue<PreallocatedTxBuilder>> {
+    let pool = ArrayQueue::new(config.tx_builder_pool_size);
 
     // 预填充池
-    for _ in 0..100 {
+    for _ in 0..config.tx_builder_prewarm {
         let _ = pool.push(PreallocatedTxBuilder::new());
     }
 
     Arc::new(pool)
-});
+}
+
+/// 🚀 全局交易构建器对象池
+static TX_BUILDER_POOL: Lazy<ArcSwap<ArrayQueue<PreallocatedTxBuilder>>> =
+    Lazy::new(|| ArcSwap::new(new_builder_pool(&PERFORMANCE_CONFIG.load())));
 
 /// 🚀 从池中获取构建器
 #[inline(always)]
 pub fn acquire_builder() -> PreallocatedTxBuilder {
-    TX_BUILDER_POOL.pop().unwrap_or_else(PreallocatedTxBuilder::new)
+    TX_BUILDER_POOL.load().pop().unwrap_or_else(PreallocatedTxBuilder::new)
 }
 
 /// 🚀 归还构建器到池
 #[inline(always)]
 pub fn release_builder(mut builder: PreallocatedTxBuilder) {
     builder.reset();
-    let _ = TX_BUILDER_POOL.push(builder);
+    let _ = TX_BUILDER_POOL.load().push(builder);
 }
 
 /// 获取池统计
 pub fn get_pool_stats() -> (usize, usize) {
-    (TX_BUILDER_POOL.len(), TX_BUILDER_POOL.capacity())
+    let pool = TX_BUILDER_POOL.load();
+    (pool.len(), pool.capacity())
+}
+
+/// 当前生效的池配置
+pub fn performance_config() -> PerformanceConfig {
+    **PERFORMANCE_CONFIG.load()
+}
+
+/// 设置池配置并按新配置重建交易构建器池与序列化缓冲池
+///
+/// 配置未变化时不做任何事，避免每创建一个 `TradingClient` 都丢弃已预热的池。
+pub fn set_performance_config(config: PerformanceConfig) {
+    let config = config.normalized();
+    if **PERFORMANCE_CONFIG.load() == config {
+        return;
+    }
+    PERFORMANCE_CONFIG.store(Arc::new(config));
+    reset_pools();
+}
+
+/// 按当前配置重建所有池（交易构建器池与序列化缓冲池）
+///
+/// 正在使用中的构建器与缓冲区归还到新池，超出容量的部分直接释放。
+pub fn reset_pools() {
+    let config = performance_config();
+    TX_BUILDER_POOL.store(new_builder_pool(&config));
+    crate::swqos::serialization::reconfigure_serializer(
+        config.serializer_pool_size,
+        config.serializer_buffer_size,
+        config.serializer_prewarm,
+    );
 }
 
 /// 🚀 RAII 构建器包装器 (自动归还)
@@ -143,3 +232,25 @@ impl Drop for TxBuilderGuard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_performance_config_normalized() {
+        let config = PerformanceConfig {
+            tx_builder_pool_size: 0,
+            tx_builder_prewarm: 10,
+            serializer_pool_size: 4,
+            serializer_buffer_size: 0,
+            serializer_prewarm: 8,
+        }
+        .normalized();
+        assert_eq!(config.tx_builder_pool_size, 1);
+        assert_eq!(config.tx_builder_prewarm, 1);
+        assert_eq!(config.serializer_buffer_size, 1);
+        assert_eq!(config.serializer_prewarm, 4);
+        assert_eq!(PerformanceConfig::default().normalized(), PerformanceConfig::default());
+    }
+}
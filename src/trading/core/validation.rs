@@ -0,0 +1,353 @@
+//! 协议参数校验
+//!
+//! `buy()` / `sell()` 在构建交易前调用 [`DexParamEnum::validate`]，提前发现
+//! `Pubkey::default()` 占位符、不一致的 vault/mint 等问题，
+//! 返回精确到字段的错误，而不是构建出一笔必然失败的交易。
+//!
+//! 可由指令构建器自动推导的账户（如 PumpFun 的 bonding curve PDA、CPMM 的 vault PDA）
+//! 允许为默认值，不在此处报错。
+
+use super::params::{
+    BonkParams, DexParamEnum, MeteoraDammV2Params, PumpFunParams, PumpSwapParams,
+    RaydiumAmmV4Params, RaydiumClmmParams, RaydiumCpmmParams,
+};
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use crate::trading::factory::DexType;
+use solana_sdk::pubkey::Pubkey;
+
+/// 协议参数校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamsValidationError {
+    pub dex_type: DexType,
+    /// 出错的字段名
+    pub field: &'static str,
+    /// 具体原因
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParamsValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {:?} params: `{}` {}", self.dex_type, self.field, self.reason)
+    }
+}
+
+impl std::error::Error for ParamsValidationError {}
+
+type ValidationResult = Result<(), ParamsValidationError>;
+
+struct Checker {
+    dex_type: DexType,
+}
+
+impl Checker {
+    fn fail(&self, field: &'static str, reason: impl Into<String>) -> ValidationResult {
+        Err(ParamsValidationError {
+            dex_type: self.dex_type.clone(),
+            field,
+            reason: reason.into(),
+        })
+    }
+
+    fn required(&self, field: &'static str, value: &Pubkey) -> ValidationResult {
+        if *value == Pubkey::default() {
+            return self.fail(field, "is required but is Pubkey::default()");
+        }
+        Ok(())
+    }
+
+    fn token_program(&self, field: &'static str, value: &Pubkey) -> ValidationResult {
+        if *value != TOKEN_PROGRAM && *value != TOKEN_PROGRAM_2022 {
+            return self.fail(field, format!("must be Token or Token-2022 program, got {}", value));
+        }
+        Ok(())
+    }
+
+    fn distinct_mints(&self, field: &'static str, a: &Pubkey, b: &Pubkey) -> ValidationResult {
+        if a == b {
+            return self.fail(field, format!("both mints of the pair are {}", a));
+        }
+        Ok(())
+    }
+
+    fn ata(
+        &self,
+        field: &'static str,
+        value: &Pubkey,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> ValidationResult {
+        let expected = crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+            owner,
+            mint,
+            token_program,
+        );
+        if *value != expected {
+            return self.fail(
+                field,
+                format!(
+                    "{} is not the token account of mint {} (expected {})",
+                    value, mint, expected
+                ),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl DexParamEnum {
+    /// 校验参数是否与 `dex_type` 匹配且字段完整、一致
+    pub fn validate(&self, dex_type: &DexType) -> Result<(), ParamsValidationError> {
+        let c = Checker { dex_type: dex_type.clone() };
+        match (dex_type, self) {
+            (DexType::PumpFun, DexParamEnum::PumpFun(p)) => validate_pumpfun(&c, p),
+            (DexType::PumpSwap, DexParamEnum::PumpSwap(p)) => validate_pumpswap(&c, p),
+            (DexType::Bonk, DexParamEnum::Bonk(p)) => validate_bonk(&c, p),
+            (DexType::RaydiumCpmm, DexParamEnum::RaydiumCpmm(p)) => validate_raydium_cpmm(&c, p),
+            (DexType::RaydiumAmmV4, DexParamEnum::RaydiumAmmV4(p)) => {
+                validate_raydium_amm_v4(&c, p)
+            },
+            (DexType::RaydiumClmm, DexParamEnum::RaydiumClmm(p)) => validate_raydium_clmm(&c, p),
+            (DexType::MeteoraDammV2, DexParamEnum::MeteoraDammV2(p)) => {
+                validate_meteora_damm_v2(&c, p)
+            },
+            _ => c.fail("extension_params", format!("does not match dex type {:?}", dex_type)),
+        }
+    }
+}
+
+fn validate_pumpfun(c: &Checker, p: &PumpFunParams) -> ValidationResult {
+    // bonding_curve.account / associated_bonding_curve 为默认值时由 mint 推导
+    c.required("creator_vault", &p.creator_vault)?;
+    c.token_program("token_program", &p.token_program)
+}
+
+fn validate_pumpswap(c: &Checker, p: &PumpSwapParams) -> ValidationResult {
+    c.required("pool", &p.pool)?;
+    c.required("base_mint", &p.base_mint)?;
+    c.required("quote_mint", &p.quote_mint)?;
+    c.distinct_mints("quote_mint", &p.base_mint, &p.quote_mint)?;
+    // coin_creator_vault_ata / coin_creator_vault_authority 为默认值时由 coin_creator 推导
+    c.token_program("base_token_program", &p.base_token_program)?;
+    c.token_program("quote_token_program", &p.quote_token_program)?;
+    c.required("pool_base_token_account", &p.pool_base_token_account)?;
+    c.required("pool_quote_token_account", &p.pool_quote_token_account)?;
+    c.ata(
+        "pool_base_token_account",
+        &p.pool_base_token_account,
+        &p.pool,
+        &p.base_mint,
+        &p.base_token_program,
+    )?;
+    c.ata(
+        "pool_quote_token_account",
+        &p.pool_quote_token_account,
+        &p.pool,
+        &p.quote_mint,
+        &p.quote_token_program,
+    )
+}
+
+fn validate_bonk(c: &Checker, p: &BonkParams) -> ValidationResult {
+    // pool_state / base_vault / quote_vault 为默认值时由 mint 推导
+    c.token_program("mint_token_program", &p.mint_token_program)?;
+    c.required("global_config", &p.global_config)?;
+    c.required("platform_config", &p.platform_config)?;
+    c.required("platform_associated_account", &p.platform_associated_account)?;
+    c.required("creator_associated_account", &p.creator_associated_account)
+}
+
+fn validate_raydium_cpmm(c: &Checker, p: &RaydiumCpmmParams) -> ValidationResult {
+    // pool_state / vault / observation_state 为默认值时由 amm_config 与 mint 推导
+    c.required("amm_config", &p.amm_config)?;
+    c.required("base_mint", &p.base_mint)?;
+    c.required("quote_mint", &p.quote_mint)?;
+    c.distinct_mints("quote_mint", &p.base_mint, &p.quote_mint)?;
+    c.token_program("base_token_program", &p.base_token_program)?;
+    c.token_program("quote_token_program", &p.quote_token_program)
+}
+
+fn validate_raydium_amm_v4(c: &Checker, p: &RaydiumAmmV4Params) -> ValidationResult {
+    c.required("amm", &p.amm)?;
+    c.required("coin_mint", &p.coin_mint)?;
+    c.required("pc_mint", &p.pc_mint)?;
+    c.distinct_mints("pc_mint", &p.coin_mint, &p.pc_mint)?;
+    c.required("token_coin", &p.token_coin)?;
+    c.required("token_pc", &p.token_pc)?;
+    if p.token_coin == p.token_pc {
+        return c.fail("token_pc", "must differ from token_coin");
+    }
+    Ok(())
+}
+
+fn validate_raydium_clmm(c: &Checker, p: &RaydiumClmmParams) -> ValidationResult {
+    c.required("pool_state", &p.pool_state)?;
+    c.required("amm_config", &p.amm_config)?;
+    c.required("token0_mint", &p.token0_mint)?;
+    c.required("token1_mint", &p.token1_mint)?;
+    // CLMM 池要求 token0 < token1（按字节序）
+    if p.token0_mint >= p.token1_mint {
+        return c.fail("token0_mint", "must sort before token1_mint (mints swapped?)");
+    }
+    c.required("token0_vault", &p.token0_vault)?;
+    c.required("token1_vault", &p.token1_vault)?;
+    if p.token0_vault == p.token1_vault {
+        return c.fail("token1_vault", "must differ from token0_vault");
+    }
+    c.required("observation_state", &p.observation_state)?;
+    // decimals 不做校验：0 是合法的 SPL mint 精度
+    c.token_program("token0_program", &p.token0_program)?;
+    c.token_program("token1_program", &p.token1_program)
+}
+
+fn validate_meteora_damm_v2(c: &Checker, p: &MeteoraDammV2Params) -> ValidationResult {
+    c.required("pool", &p.pool)?;
+    c.required("token_a_mint", &p.token_a_mint)?;
+    c.required("token_b_mint", &p.token_b_mint)?;
+    c.distinct_mints("token_b_mint", &p.token_a_mint, &p.token_b_mint)?;
+    c.required("token_a_vault", &p.token_a_vault)?;
+    c.required("token_b_vault", &p.token_b_vault)?;
+    if p.token_a_vault == p.token_b_vault {
+        return c.fail("token_b_vault", "must differ from token_a_vault");
+    }
+    c.token_program("token_a_program", &p.token_a_program)?;
+    c.token_program("token_b_program", &p.token_b_program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::WSOL_TOKEN_ACCOUNT;
+    use crate::instruction::utils::pumpfun::get_creator_vault_pda;
+
+    #[test]
+    fn test_pumpfun_default_creator_vault_rejected() {
+        let params = DexParamEnum::PumpFun(PumpFunParams::immediate_sell(
+            Pubkey::default(),
+            TOKEN_PROGRAM,
+            false,
+        ));
+        let err = params.validate(&DexType::PumpFun).unwrap_err();
+        assert_eq!(err.field, "creator_vault");
+
+        let creator_vault = get_creator_vault_pda(&Pubkey::new_unique()).unwrap();
+        let params = DexParamEnum::PumpFun(PumpFunParams::immediate_sell(
+            creator_vault,
+            TOKEN_PROGRAM,
+            false,
+        ));
+        assert!(params.validate(&DexType::PumpFun).is_ok());
+        assert_eq!(params.validate(&DexType::Bonk).unwrap_err().field, "extension_params");
+    }
+
+    #[test]
+    fn test_pumpswap_pool_token_account_must_match_mint() {
+        let pool = Pubkey::new_unique();
+        let base_mint = Pubkey::new_unique();
+        let ata = |mint: &Pubkey| {
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                &pool,
+                mint,
+                &TOKEN_PROGRAM,
+            )
+        };
+        let mut params = PumpSwapParams::new(
+            pool,
+            base_mint,
+            WSOL_TOKEN_ACCOUNT,
+            ata(&base_mint),
+            ata(&WSOL_TOKEN_ACCOUNT),
+            1,
+            1,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            TOKEN_PROGRAM,
+            TOKEN_PROGRAM,
+            Pubkey::default(),
+        );
+        assert!(DexParamEnum::PumpSwap(params.clone()).validate(&DexType::PumpSwap).is_ok());
+
+        params.base_token_program = TOKEN_PROGRAM_2022;
+        let err = DexParamEnum::PumpSwap(params).validate(&DexType::PumpSwap).unwrap_err();
+        assert_eq!(err.field, "pool_base_token_account");
+    }
+
+    #[test]
+    fn test_pumpswap_creator_vault_derived_from_coin_creator() {
+        use crate::instruction::utils::pumpswap::accounts::DEFAULT_COIN_CREATOR_VAULT_AUTHORITY;
+
+        let pool = Pubkey::new_unique();
+        let pool_data = crate::instruction::utils::pumpswap_types::Pool {
+            base_mint: Pubkey::new_unique(),
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            ..Default::default()
+        };
+        let ata = |mint: &Pubkey| {
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                &pool,
+                mint,
+                &TOKEN_PROGRAM,
+            )
+        };
+        let pool_data = crate::instruction::utils::pumpswap_types::Pool {
+            pool_base_token_account: ata(&pool_data.base_mint),
+            pool_quote_token_account: ata(&WSOL_TOKEN_ACCOUNT),
+            ..pool_data
+        };
+
+        // 没有 coin creator 的池子推导出默认 vault authority
+        let params = PumpSwapParams::from_pool_data(pool, &pool_data, 1, 1);
+        assert_eq!(params.coin_creator_vault_authority, DEFAULT_COIN_CREATOR_VAULT_AUTHORITY);
+        assert_eq!(params.quote_token_program, TOKEN_PROGRAM);
+        assert!(DexParamEnum::PumpSwap(params.clone()).validate(&DexType::PumpSwap).is_ok());
+
+        let creator = Pubkey::new_unique();
+        let params = params.with_coin_creator(creator);
+        let authority = crate::instruction::utils::pumpswap::coin_creator_vault_authority(creator);
+        assert_eq!(params.coin_creator_vault_authority, authority);
+        assert_eq!(
+            params.coin_creator_vault_ata,
+            crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+                &authority,
+                &WSOL_TOKEN_ACCOUNT,
+                &TOKEN_PROGRAM,
+            )
+        );
+
+        // 未填 vault 字段的参数在构建时按 coin_creator 推导
+        let mut unresolved = params.clone();
+        unresolved.coin_creator_vault_ata = Pubkey::default();
+        unresolved.coin_creator_vault_authority = Pubkey::default();
+        assert_eq!(
+            unresolved.resolved_coin_creator_vault(),
+            (params.coin_creator_vault_ata, authority)
+        );
+    }
+
+    #[test]
+    fn test_clmm_zero_decimals_and_mint_order() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (token0, token1) = if a < b { (a, b) } else { (b, a) };
+        let build = |token0_mint, token1_mint, token0_decimals| {
+            DexParamEnum::RaydiumClmm(RaydiumClmmParams::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                token0_mint,
+                token1_mint,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                token0_decimals,
+                9,
+                TOKEN_PROGRAM,
+                TOKEN_PROGRAM,
+            ))
+        };
+        assert!(build(token0, token1, 6).validate(&DexType::RaydiumClmm).is_ok());
+        // 0 位精度的 mint 是合法的
+        assert!(build(token0, token1, 0).validate(&DexType::RaydiumClmm).is_ok());
+        let err = build(token1, token0, 6).validate(&DexType::RaydiumClmm).unwrap_err();
+        assert_eq!(err.field, "token0_mint");
+        assert!(err.to_string().contains("RaydiumClmm"));
+    }
+}
@@ -0,0 +1,143 @@
+//! 双平台同时提交，先上链者成交
+//!
+//! 同一个 mint 在两个平台（例如 PumpFun 与 PumpSwap、两个不同的 AMM 池）都有流动性时，
+//! 拥堵期间可以为每个平台各构建一笔交易并同时提交，提高上链概率。
+//!
+//! 两笔交易使用同一个 durable nonce：交易的第一条指令推进该 nonce，
+//! 因此其中一笔上链后另一笔必然失效，不会重复买入/卖出。
+//! 两笔都等待确认，先确认成功的一笔即为成交，另一笔的等待被直接放弃。
+
+use crate::common::nonce_cache::DurableNonceInfo;
+use crate::trading::factory::DexType;
+use crate::{TradeBuyParams, TradeResult, TradeSellParams, TradingClient};
+use anyhow::{Result, anyhow};
+use solana_sdk::pubkey::Pubkey;
+use std::future::Future;
+use std::pin::pin;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 双平台提交结果
+#[derive(Debug)]
+pub struct DualVenueResult {
+    /// 成交的一侧（0 或 1）；两侧都未成功时为 None
+    pub filled_leg: Option<usize>,
+    /// 成交平台
+    pub filled_dex_type: Option<DexType>,
+    /// 每一侧的执行结果；成交后被放弃等待的一侧为 None
+    pub results: [Option<Result<TradeResult, String>>; 2],
+    pub elapsed: Duration,
+}
+
+impl DualVenueResult {
+    pub fn is_filled(&self) -> bool {
+        self.filled_leg.is_some()
+    }
+
+    /// 成交一侧的交易结果
+    pub fn fill(&self) -> Option<&TradeResult> {
+        self.filled_leg.and_then(|leg| self.results[leg].as_ref()?.as_ref().ok())
+    }
+}
+
+/// 检查两侧是否为同一个 mint，且 nonce 信息完整
+pub fn validate_dual_venue(mints: [&Pubkey; 2], durable_nonce: &DurableNonceInfo) -> Result<()> {
+    if mints[0] != mints[1] {
+        return Err(anyhow!(
+            "dual venue legs must trade the same mint: {} vs {}",
+            mints[0],
+            mints[1]
+        ));
+    }
+    if durable_nonce.nonce_account.is_none() || durable_nonce.current_nonce.is_none() {
+        return Err(anyhow!("dual venue submission requires a durable nonce account and value"));
+    }
+    Ok(())
+}
+
+impl TradingClient {
+    /// 在两个平台同时买入同一个 mint，先确认者成交
+    ///
+    /// 两侧的 `durable_nonce` 被覆盖为传入的 nonce，并强制等待确认。
+    pub async fn buy_dual_venue(
+        &self,
+        legs: [TradeBuyParams; 2],
+        durable_nonce: DurableNonceInfo,
+    ) -> Result<DualVenueResult> {
+        validate_dual_venue([&legs[0].mint, &legs[1].mint], &durable_nonce)?;
+        let dex_types = [legs[0].dex_type.clone(), legs[1].dex_type.clone()];
+        let [first, second] = legs.map(|mut leg| {
+            leg.durable_nonce = Some(durable_nonce.clone());
+            leg.wait_transaction_confirmed = true;
+            leg
+        });
+        Ok(race_venues(dex_types, self.buy_with_result(first), self.buy_with_result(second)).await)
+    }
+
+    /// 在两个平台同时卖出同一个 mint，先确认者成交
+    pub async fn sell_dual_venue(
+        &self,
+        legs: [TradeSellParams; 2],
+        durable_nonce: DurableNonceInfo,
+    ) -> Result<DualVenueResult> {
+        validate_dual_venue([&legs[0].mint, &legs[1].mint], &durable_nonce)?;
+        let dex_types = [legs[0].dex_type.clone(), legs[1].dex_type.clone()];
+        let [first, second] = legs.map(|mut leg| {
+            leg.durable_nonce = Some(durable_nonce.clone());
+            leg.wait_transaction_confirmed = true;
+            leg
+        });
+        Ok(race_venues(dex_types, self.sell_with_result(first), self.sell_with_result(second))
+            .await)
+    }
+}
+
+/// 同时等待两侧，第一笔成功的交易即为成交
+async fn race_venues<A, B>(dex_types: [DexType; 2], first: A, second: B) -> DualVenueResult
+where
+    A: Future<Output = Result<TradeResult>>,
+    B: Future<Output = Result<TradeResult>>,
+{
+    let start = Instant::now();
+    let mut first = pin!(first);
+    let mut second = pin!(second);
+    let mut results: [Option<Result<TradeResult, String>>; 2] = [None, None];
+    let mut filled_leg = None;
+    while filled_leg.is_none() && (results[0].is_none() || results[1].is_none()) {
+        let (leg, result) = tokio::select! {
+            result = &mut first, if results[0].is_none() => (0, result),
+            result = &mut second, if results[1].is_none() => (1, result),
+        };
+        if result.as_ref().is_ok_and(|result| result.success) {
+            filled_leg = Some(leg);
+        } else if let Err(e) = &result {
+            log::warn!("dual venue leg {} ({:?}) failed: {}", leg, dex_types[leg], e);
+        }
+        results[leg] = Some(result.map_err(|e| e.to_string()));
+    }
+    DualVenueResult {
+        filled_leg,
+        filled_dex_type: filled_leg.map(|leg| dex_types[leg].clone()),
+        results,
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_hash::Hash;
+
+    #[test]
+    fn test_validate_dual_venue_requires_same_mint_and_nonce() {
+        let mint = Pubkey::new_unique();
+        let nonce = DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::new_from_array([1; 32])),
+        };
+        assert!(validate_dual_venue([&mint, &mint], &nonce).is_ok());
+        assert!(validate_dual_venue([&mint, &Pubkey::new_unique()], &nonce).is_err());
+        let missing = DurableNonceInfo { nonce_account: nonce.nonce_account, current_nonce: None };
+        assert!(validate_dual_venue([&mint, &mint], &missing).is_err());
+    }
+}
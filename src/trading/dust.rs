@@ -0,0 +1,285 @@
+//! 零散持仓（dust）清算
+//!
+//! [`TradingClient::liquidate_dust`] 枚举 payer 名下的代币余额，通过各协议的 USD 定价函数估值，
+//! 将价值低于阈值的持仓在报价最高的平台全部卖出，并在卖出交易中关闭对应 ATA 回收租金。
+//!
+//! - 定价平台：PumpFun（未毕业的 bonding curve）、PumpSwap、Raydium CPMM / AMM V4 / CLMM；
+//! - 无法在任何平台定价的代币跳过，不会卖出；
+//! - 计价资产（WSOL / USDC / USDT / USD1）不参与清算；
+//! - 逐个顺序卖出并等待确认，单个卖出失败不影响其余持仓。
+
+use crate::common::{GasFeeStrategy, SolanaRpcClient};
+use crate::constants::{USD1_TOKEN_ACCOUNT, USDC_MINT, USDT_MINT, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::utils::{pumpswap, raydium_amm_v4, raydium_clmm, raydium_cpmm};
+use crate::swqos::common::TradeError;
+use crate::trading::core::params::{
+    DexParamEnum, PumpFunParams, PumpSwapParams, RaydiumAmmV4Params, RaydiumClmmParams,
+    RaydiumCpmmParams,
+};
+use crate::trading::factory::DexType;
+use crate::utils::price::pumpfun::price_token_in_sol;
+use crate::utils::wallet::WalletTokenAccount;
+use crate::{TradeSellParams, TradeTokenType, TradingClient};
+use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+/// 参与定价的平台（按优先级排列，报价相同时取靠前者）
+const DUST_VENUES: [DexType; 5] = [
+    DexType::PumpFun,
+    DexType::PumpSwap,
+    DexType::RaydiumCpmm,
+    DexType::RaydiumAmmV4,
+    DexType::RaydiumClmm,
+];
+
+/// 一个被估值的持仓
+#[derive(Debug, Clone)]
+pub struct DustPosition {
+    pub account: WalletTokenAccount,
+    pub decimals: u8,
+    /// 报价最高的平台
+    pub dex_type: DexType,
+    /// 单个代币的 USD 价格
+    pub price_usd: f64,
+    /// 持仓 USD 价值
+    pub value_usd: f64,
+}
+
+/// dust 清算结果
+#[derive(Debug, Default)]
+pub struct DustLiquidationReport {
+    /// 成功卖出的持仓及其交易签名
+    pub sold: Vec<(DustPosition, Vec<Signature>)>,
+    /// 价值不低于阈值而保留的持仓
+    pub kept: Vec<DustPosition>,
+    /// 无法定价而跳过的代币账户
+    pub unpriced: Vec<WalletTokenAccount>,
+    /// 卖出失败的持仓：(持仓, 错误信息)
+    pub failed: Vec<(DustPosition, String)>,
+}
+
+impl DustLiquidationReport {
+    /// 已卖出持仓的估值合计（USD）
+    pub fn total_sold_usd(&self) -> f64 {
+        self.sold.iter().map(|(position, _)| position.value_usd).sum()
+    }
+}
+
+fn is_quote_asset(mint: &Pubkey) -> bool {
+    *mint == WSOL_TOKEN_ACCOUNT
+        || *mint == USDC_MINT
+        || *mint == USDT_MINT
+        || *mint == USD1_TOKEN_ACCOUNT
+}
+
+/// 在指定平台上获取代币的 USD 价格
+async fn price_on_venue(rpc: &SolanaRpcClient, dex_type: &DexType, mint: &Pubkey) -> Result<f64> {
+    match dex_type {
+        DexType::PumpFun => {
+            let params = PumpFunParams::from_mint_by_rpc(rpc, mint).await?;
+            if params.bonding_curve.complete {
+                return Err(anyhow!("bonding curve is complete"));
+            }
+            let price_in_sol = price_token_in_sol(
+                params.bonding_curve.virtual_sol_reserves,
+                params.bonding_curve.virtual_token_reserves,
+            );
+            let sol_price = raydium_clmm::get_wsol_price_in_usd_with_client(rpc, None).await?;
+            Ok(price_in_sol * sol_price)
+        },
+        DexType::PumpSwap => pumpswap::get_token_price_in_usd(rpc, mint, None).await,
+        DexType::RaydiumCpmm => raydium_cpmm::get_token_price_in_usd(rpc, mint, None).await,
+        DexType::RaydiumAmmV4 => raydium_amm_v4::get_token_price_in_usd(rpc, mint, None).await,
+        DexType::RaydiumClmm => raydium_clmm::get_token_price_in_usd(rpc, mint, None).await,
+        _ => Err(anyhow!("{:?} is not supported for dust pricing", dex_type)),
+    }
+}
+
+/// 在所有平台并发定价，返回报价最高的平台与价格；所有平台都无法定价时返回 `None`
+pub async fn best_usd_price(rpc: &SolanaRpcClient, mint: &Pubkey) -> Option<(DexType, f64)> {
+    let prices =
+        join_all(DUST_VENUES.iter().map(|dex_type| price_on_venue(rpc, dex_type, mint))).await;
+    select_best_price(&DUST_VENUES, prices)
+}
+
+fn select_best_price(venues: &[DexType], prices: Vec<Result<f64>>) -> Option<(DexType, f64)> {
+    venues
+        .iter()
+        .zip(prices)
+        .filter_map(|(dex_type, price)| match price {
+            Ok(price) if price.is_finite() && price > 0.0 => Some((dex_type.clone(), price)),
+            _ => None,
+        })
+        .fold(None, |best: Option<(DexType, f64)>, candidate| match best {
+            Some(best) if best.1 >= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })
+}
+
+/// 为卖出构建对应平台的协议参数
+async fn sell_params_for(
+    rpc: &SolanaRpcClient,
+    dex_type: &DexType,
+    mint: &Pubkey,
+) -> Result<DexParamEnum> {
+    Ok(match dex_type {
+        DexType::PumpFun => {
+            let mut params = PumpFunParams::from_mint_by_rpc(rpc, mint).await?;
+            params.close_token_account_when_sell = Some(true);
+            DexParamEnum::PumpFun(params)
+        },
+        DexType::PumpSwap => {
+            DexParamEnum::PumpSwap(PumpSwapParams::from_mint_by_rpc(rpc, mint).await?)
+        },
+        DexType::RaydiumCpmm => {
+            let (pool, _) = raydium_cpmm::get_pool_by_mint(rpc, mint).await?;
+            DexParamEnum::RaydiumCpmm(
+                RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool).await?,
+            )
+        },
+        DexType::RaydiumAmmV4 => {
+            let (amm, _) = raydium_amm_v4::get_pool_by_mint(rpc, mint).await?;
+            DexParamEnum::RaydiumAmmV4(RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, amm).await?)
+        },
+        DexType::RaydiumClmm => {
+            let (pool, _) = raydium_clmm::get_pool_by_mint(rpc, mint).await?;
+            DexParamEnum::RaydiumClmm(
+                RaydiumClmmParams::from_pool_address_by_rpc(rpc, &pool).await?,
+            )
+        },
+        _ => return Err(anyhow!("{:?} is not supported for dust liquidation", dex_type)),
+    })
+}
+
+impl TradingClient {
+    /// 估值 payer 名下所有代币持仓（不卖出），返回 (可定价持仓, 无法定价的账户)
+    pub async fn value_token_positions(
+        &self,
+    ) -> Result<(Vec<DustPosition>, Vec<WalletTokenAccount>)> {
+        let owner = self.payer.pubkey();
+        let accounts = self.get_payer_token_accounts().await?;
+        let mut positions = Vec::new();
+        let mut unpriced = Vec::new();
+        for account in accounts {
+            if account.amount == 0 || account.is_frozen || is_quote_asset(&account.mint) {
+                continue;
+            }
+            if account.close_authority.is_some_and(|authority| authority != owner) {
+                log::warn!("skip {}: close authority is not the payer", account.address);
+                continue;
+            }
+            let Some((dex_type, price_usd)) = best_usd_price(&self.rpc, &account.mint).await else {
+                unpriced.push(account);
+                continue;
+            };
+            let decimals =
+                match crate::utils::token::get_token_decimals(&self.rpc, &account.mint).await {
+                    Ok(decimals) => decimals,
+                    Err(e) => {
+                        log::warn!("skip {}: failed to get decimals: {}", account.mint, e);
+                        unpriced.push(account);
+                        continue;
+                    },
+                };
+            let value_usd = account.amount as f64 / 10f64.powi(decimals as i32) * price_usd;
+            positions.push(DustPosition { account, decimals, dex_type, price_usd, value_usd });
+        }
+        Ok((positions, unpriced))
+    }
+
+    /// 卖出所有价值低于 `max_usd_value` 的持仓，并关闭对应 ATA
+    ///
+    /// 每个持仓在报价最高的平台全额卖出，PumpFun 卖出得到 SOL，其他平台得到 WSOL。
+    /// 卖出逐个执行并等待确认。
+    pub async fn liquidate_dust(
+        &self,
+        max_usd_value: f64,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<DustLiquidationReport> {
+        let (positions, unpriced) = self.value_token_positions().await?;
+        let mut report = DustLiquidationReport { unpriced, ..Default::default() };
+
+        for position in positions {
+            if position.value_usd >= max_usd_value {
+                report.kept.push(position);
+                continue;
+            }
+            match self.sell_dust_position(&position, gas_fee_strategy.clone()).await {
+                Ok((true, signatures, _)) => report.sold.push((position, signatures)),
+                Ok((false, _, err)) => {
+                    let reason =
+                        err.map_or_else(|| "transaction failed".to_string(), |e| e.to_string());
+                    report.failed.push((position, reason));
+                },
+                Err(e) => report.failed.push((position, e.to_string())),
+            }
+        }
+        Ok(report)
+    }
+
+    async fn sell_dust_position(
+        &self,
+        position: &DustPosition,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        let mint = position.account.mint;
+        let extension_params = sell_params_for(&self.rpc, &position.dex_type, &mint).await?;
+        let is_pumpfun = position.dex_type == DexType::PumpFun;
+        log::info!(
+            "liquidating dust {} ({:.4} USD) via {:?}",
+            mint,
+            position.value_usd,
+            position.dex_type
+        );
+        self.sell(TradeSellParams {
+            dex_type: position.dex_type.clone(),
+            output_token_type: if is_pumpfun { TradeTokenType::SOL } else { TradeTokenType::WSOL },
+            mint,
+            input_token_amount: position.account.amount,
+            slippage_basis_points: None,
+            recent_blockhash: None,
+            last_valid_block_height: None,
+            with_tip: false,
+            extension_params,
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: true,
+            create_output_token_ata: !is_pumpfun,
+            close_output_token_ata: false,
+            close_mint_token_ata: true,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy,
+            simulate: false,
+            on_transaction_signed: None,
+            callback_execution_mode: None,
+            enable_jito_sandwich_protection: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_best_price() {
+        let prices = vec![Err(anyhow!("no pool")), Ok(0.002), Ok(f64::NAN), Ok(0.003), Ok(0.003)];
+        assert_eq!(select_best_price(&DUST_VENUES, prices), Some((DexType::RaydiumCpmm, 0.003)));
+        let none = vec![Err(anyhow!("no pool")), Ok(0.0), Err(anyhow!("x")), Ok(-1.0), Ok(0.0)];
+        assert_eq!(select_best_price(&DUST_VENUES, none), None);
+        assert!(is_quote_asset(&WSOL_TOKEN_ACCOUNT));
+    }
+}
@@ -0,0 +1,320 @@
+//! 成交回查
+//!
+//! 交易上链后拉取交易，用 crate 自带的解析器（[`crate::parser::DexParser`]）还原实际成交数量、
+//! 费用与成交均价，供对账使用。目前支持 PumpSwap 与 Raydium（AMM V4 / CPMM / CLMM），
+//! 其他协议只返回链上手续费与 SOL 余额变化。
+//!
+//! 结合构建时的报价（[`TradeQuote`]）可检测实际输出低于最小输出的情况（[`SlippageViolation`]），
+//! 这通常意味着本地计算有误或池状态已过期。
+//!
+//! 不使用 durable nonce 的交易发送给多个 provider 时各自的签名不同，可能被不同中继重复打包。
+//! 把同一次买入/卖出返回的全部签名解析后交给 [`detect_duplicates`]，可发现重复成交
+//! （[`DuplicateExecution`]）并据此修正持仓。
+
+use crate::common::SolanaRpcClient;
+use crate::parser::{DexParser, ParsedTradeInfo, TokenInfo};
+use crate::trading::lifecycle::TradeQuote;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use std::collections::BTreeMap;
+
+/// 仅用于解析已获取的交易，不会通过其 RPC 客户端发起请求
+static FILL_PARSER: Lazy<DexParser> = Lazy::new(DexParser::default);
+
+/// 一笔已上链交易的实际成交结果
+#[derive(Debug, Clone)]
+pub struct ResolvedFill {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// 交易是否执行成功
+    pub success: bool,
+    /// 链上网络手续费（基础费 + 优先费，lamports）
+    pub network_fee_lamports: u64,
+    /// 付款账户 SOL 余额变化（含手续费、小费、租金与 SOL 成交额，lamports）
+    pub sol_change_lamports: i64,
+    /// 解析出的成交明细（按指令顺序；协议不受支持时为空）
+    pub trades: Vec<ParsedTradeInfo>,
+}
+
+impl ResolvedFill {
+    /// 实际支付的代币（首笔成交的输入）
+    pub fn input(&self) -> Option<&TokenInfo> {
+        self.trades.first().map(|trade| &trade.input_token)
+    }
+
+    /// 实际收到的代币（末笔成交的输出，多跳路由时为最终代币）
+    pub fn output(&self) -> Option<&TokenInfo> {
+        self.trades.last().map(|trade| &trade.output_token)
+    }
+
+    /// 实际输入数量（原始精度）
+    pub fn input_amount(&self) -> Option<u64> {
+        self.input().and_then(|token| token.amount_raw.parse().ok())
+    }
+
+    /// 实际输出数量（原始精度）
+    pub fn output_amount(&self) -> Option<u64> {
+        self.output().and_then(|token| token.amount_raw.parse().ok())
+    }
+
+    /// 协议费用（LP / 协议 / 创作者费），按代币 mint 汇总的原始数量
+    pub fn protocol_fees(&self) -> BTreeMap<Pubkey, u64> {
+        let mut fees = BTreeMap::new();
+        for trade in &self.trades {
+            let tokens: Vec<&TokenInfo> = if trade.fees.is_empty() {
+                trade.fee.iter().collect()
+            } else {
+                trade.fees.iter().map(|fee| &fee.token).collect()
+            };
+            for token in tokens {
+                let amount: u64 = token.amount_raw.parse().unwrap_or(0);
+                *fees.entry(token.mint).or_default() += amount;
+            }
+        }
+        fees
+    }
+
+    /// 成交均价：每单位输出代币付出的输入代币数量（按精度换算）
+    pub fn effective_price(&self) -> Option<f64> {
+        let (input, output) = (self.input()?, self.output()?);
+        (output.amount > 0.0).then(|| input.amount / output.amount)
+    }
+
+    /// 与构建时的报价比较：实际输出低于报价的最小输出时返回违规详情
+    ///
+    /// 交易失败或无法解析输出数量时不做判断。
+    pub fn check_slippage(&self, quote: &TradeQuote) -> Option<SlippageViolation> {
+        if !self.success {
+            return None;
+        }
+        let realized_output = self.output_amount()?;
+        (realized_output < quote.min_output).then(|| SlippageViolation {
+            signature: self.signature,
+            min_output: quote.min_output,
+            expected_output: quote.expected_output,
+            realized_output,
+        })
+    }
+
+    /// 从已获取的交易（`JsonParsed` 编码）解析成交结果
+    pub async fn from_transaction(
+        signature: Signature,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<Self> {
+        let meta = tx
+            .transaction
+            .meta
+            .as_ref()
+            .ok_or_else(|| anyhow!("Transaction {} has no status meta", signature))?;
+        let sol_change_lamports = match (meta.pre_balances.first(), meta.post_balances.first()) {
+            (Some(pre), Some(post)) => *post as i64 - *pre as i64,
+            _ => 0,
+        };
+        let trades = FILL_PARSER
+            .parse_encoded_transaction(tx)
+            .await
+            .map_err(|e| anyhow!("Failed to parse {}: {}", signature, e))?;
+        Ok(Self {
+            signature,
+            slot: tx.slot,
+            block_time: tx.block_time,
+            success: meta.err.is_none(),
+            network_fee_lamports: meta.fee,
+            sol_change_lamports,
+            trades,
+        })
+    }
+}
+
+/// 实际成交输出低于报价允许的最小输出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlippageViolation {
+    pub signature: Signature,
+    /// 报价的最小输出（写入指令）
+    pub min_output: u64,
+    /// 报价的预期输出（未计滑点）
+    pub expected_output: Option<u64>,
+    /// 链上实际输出
+    pub realized_output: u64,
+}
+
+impl SlippageViolation {
+    /// 实际输出比最小输出少的数量
+    pub fn shortfall(&self) -> u64 {
+        self.min_output.saturating_sub(self.realized_output)
+    }
+
+    /// JSON 表示（用于日志与离线分析）
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "signature": self.signature.to_string(),
+            "min_output": self.min_output,
+            "expected_output": self.expected_output,
+            "realized_output": self.realized_output,
+            "shortfall": self.shortfall(),
+        })
+    }
+}
+
+/// 同一笔逻辑交易重复上链：除最先上链的一笔外，其余成功的成交均为重复
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateExecution {
+    /// 最先上链的交易
+    pub original: Signature,
+    pub original_slot: u64,
+    /// 重复上链的交易
+    pub duplicate: Signature,
+    pub duplicate_slot: u64,
+    /// 重复成交的实际输入数量（原始精度，无法解析时为 None）
+    pub duplicate_input_amount: Option<u64>,
+    /// 重复成交的实际输出数量（原始精度，无法解析时为 None）
+    pub duplicate_output_amount: Option<u64>,
+}
+
+impl DuplicateExecution {
+    /// JSON 表示（用于日志与离线分析）
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "original": self.original.to_string(),
+            "original_slot": self.original_slot,
+            "duplicate": self.duplicate.to_string(),
+            "duplicate_slot": self.duplicate_slot,
+            "duplicate_input_amount": self.duplicate_input_amount,
+            "duplicate_output_amount": self.duplicate_output_amount,
+        })
+    }
+}
+
+/// 在同一笔逻辑交易（同一次买入/卖出在各 provider 上的交易）的成交中查找重复成交
+///
+/// 按上链 slot 排序，最早成功的一笔为原始成交（同一 slot 时取靠前的），其余成功的成交各记一次重复；
+/// 失败的交易不计入。
+pub fn detect_duplicates(fills: &[ResolvedFill]) -> Vec<DuplicateExecution> {
+    let mut landed: Vec<&ResolvedFill> = fills.iter().filter(|fill| fill.success).collect();
+    landed.sort_by_key(|fill| fill.slot);
+    let Some((original, rest)) = landed.split_first() else {
+        return Vec::new();
+    };
+    rest.iter()
+        .filter(|fill| fill.signature != original.signature)
+        .map(|fill| DuplicateExecution {
+            original: original.signature,
+            original_slot: original.slot,
+            duplicate: fill.signature,
+            duplicate_slot: fill.slot,
+            duplicate_input_amount: fill.input_amount(),
+            duplicate_output_amount: fill.output_amount(),
+        })
+        .collect()
+}
+
+/// 拉取已上链交易并解析成交结果
+pub async fn resolve_fill(rpc: &SolanaRpcClient, signature: &Signature) -> Result<ResolvedFill> {
+    let tx = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::JsonParsed),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to fetch transaction {}: {}", signature, e))?;
+    ResolvedFill::from_transaction(*signature, &tx).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{FeeInfo, TradeType};
+
+    fn trade(input: TokenInfo, output: TokenInfo, fees: Vec<FeeInfo>) -> ParsedTradeInfo {
+        ParsedTradeInfo {
+            user: Pubkey::new_unique(),
+            fee_payer: None,
+            trade_type: TradeType::Buy,
+            pool: Pubkey::new_unique(),
+            input_token: input,
+            output_token: output,
+            fee: None,
+            fees,
+            dex: "PumpSwap".to_string(),
+            aggregator: None,
+            signature: String::new(),
+            slot: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_amounts_fees_and_price() {
+        let (sol, token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let fee = |amount| FeeInfo {
+            token: TokenInfo::from_raw_amount(sol, amount, 9),
+            fee_type: Some("LP Fee".to_string()),
+        };
+        let fill = ResolvedFill {
+            signature: Signature::default(),
+            slot: 1,
+            block_time: None,
+            success: true,
+            network_fee_lamports: 5_000,
+            sol_change_lamports: -500_105_000,
+            trades: vec![trade(
+                TokenInfo::from_raw_amount(sol, 500_000_000, 9),
+                TokenInfo::from_raw_amount(token, 2_000_000_000, 6),
+                vec![fee(1_000_000), fee(250_000)],
+            )],
+        };
+        assert_eq!(fill.input_amount(), Some(500_000_000));
+        assert_eq!(fill.output_amount(), Some(2_000_000_000));
+        assert_eq!(fill.protocol_fees().get(&sol), Some(&1_250_000));
+        assert!((fill.effective_price().unwrap() - 0.00025).abs() < 1e-12);
+
+        let quote = |min_output| TradeQuote { expected_output: Some(2_100_000_000), min_output };
+        assert_eq!(fill.check_slippage(&quote(1_900_000_000)), None);
+        let violation = fill.check_slippage(&quote(2_050_000_000)).unwrap();
+        assert_eq!(violation.realized_output, 2_000_000_000);
+        assert_eq!(violation.shortfall(), 50_000_000);
+        assert_eq!(violation.to_json()["expected_output"], 2_100_000_000u64);
+
+        let empty = ResolvedFill { trades: vec![], ..fill };
+        assert_eq!(empty.check_slippage(&quote(1)), None);
+        assert_eq!(empty.effective_price(), None);
+        assert!(empty.protocol_fees().is_empty());
+    }
+
+    #[test]
+    fn test_detect_duplicates() {
+        let (sol, token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let fill = |slot, success| ResolvedFill {
+            signature: Signature::new_unique(),
+            slot,
+            block_time: None,
+            success,
+            network_fee_lamports: 5_000,
+            sol_change_lamports: 0,
+            trades: vec![trade(
+                TokenInfo::from_raw_amount(sol, 100, 9),
+                TokenInfo::from_raw_amount(token, 400, 6),
+                vec![],
+            )],
+        };
+        let (late, failed, early) = (fill(11, true), fill(10, false), fill(10, true));
+        assert!(detect_duplicates(&[early.clone(), failed.clone()]).is_empty());
+
+        let duplicates = detect_duplicates(&[late.clone(), failed, early.clone()]);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].original, early.signature);
+        assert_eq!(duplicates[0].duplicate, late.signature);
+        assert_eq!(duplicates[0].duplicate_slot, 11);
+        assert_eq!(duplicates[0].duplicate_output_amount, Some(400));
+        assert_eq!(duplicates[0].to_json()["original_slot"], 10);
+    }
+}
@@ -2,11 +2,19 @@
 //!
 //! 提供交易生命周期钩子，允许上游应用在关键节点拦截和处理交易
 
+use crate::common::{Bps, CallbackExecutionMode};
 use crate::swqos::{SwqosType, TradeType};
+use crate::trading::factory::DexType;
+use crate::trading::metadata::TradeMetadata;
+use crate::trading::timing::ProviderTiming;
 use anyhow::Result;
 use base64::Engine;
+use solana_sdk::message::MessageHeader;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::VersionedTransaction;
-use std::sync::Arc;
+use std::cell::Cell;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
 use tracing::warn;
 
 /// 交易生命周期回调 Trait
@@ -31,6 +39,70 @@ pub trait TransactionLifecycleCallback: Send + Sync {
         &self,
         context: CallbackContext,
     ) -> futures::future::BoxFuture<'static, Result<()>>;
+
+    /// 若该回调是 [`CallbackRegistry`]，返回注册表本身（执行器按条目顺序与模式分发）
+    fn as_registry(&self) -> Option<&CallbackRegistry> {
+        None
+    }
+}
+
+/// 构建指令时的报价结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TradeQuote {
+    /// 未计滑点的预期输出（协议无法在本地报价时为 `None`）
+    pub expected_output: Option<u64>,
+    /// 写入指令的最小输出（滑点保护后，或 `fixed_output_amount`）
+    pub min_output: u64,
+}
+
+tokio::task_local! {
+    static BUILD_QUOTE: Cell<Option<TradeQuote>>;
+}
+
+/// 运行指令构建并取回构建过程中记录的报价
+pub(crate) async fn capture_quote<F: Future>(future: F) -> (F::Output, Option<TradeQuote>) {
+    BUILD_QUOTE
+        .scope(Cell::new(None), async {
+            let output = future.await;
+            (output, BUILD_QUOTE.with(|quote| quote.get()))
+        })
+        .await
+}
+
+/// 指令构建器记录报价（不在 [`capture_quote`] 中调用时忽略）
+pub(crate) fn record_quote(expected_output: Option<u64>, min_output: u64) {
+    let _ =
+        BUILD_QUOTE.try_with(|quote| quote.set(Some(TradeQuote { expected_output, min_output })));
+}
+
+/// 交易参数摘要，随回调上下文一起提供，入库时无需再与原始参数关联
+#[derive(Debug, Clone)]
+pub struct TradeDetails {
+    pub dex_type: DexType,
+    /// 交易的代币 mint（买入为输出 mint，卖出为输入 mint）
+    pub mint: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// 调用方传入的输入数量
+    pub input_amount: Option<u64>,
+    /// 滑点
+    pub slippage_basis_points: Option<Bps>,
+    /// 构建指令时的报价（协议未记录时为 `None`）
+    pub quote: Option<TradeQuote>,
+    /// 调用方附加的交易上下文（策略标识、关联 ID 等）
+    pub metadata: Option<Arc<TradeMetadata>>,
+}
+
+impl TradeDetails {
+    /// 写入指令的最小输出
+    pub fn output_amount(&self) -> Option<u64> {
+        self.quote.map(|quote| quote.min_output)
+    }
+
+    /// 未计滑点的预期输出
+    pub fn expected_output(&self) -> Option<u64> {
+        self.quote.and_then(|quote| quote.expected_output)
+    }
 }
 
 /// 回调上下文
@@ -58,6 +130,15 @@ pub struct CallbackContext {
 
     /// 小费金额（SOL）
     pub tip_amount: f64,
+
+    /// 该 provider 的构建与签名耗时（回调在发送前执行，`submit` / `confirm` 为 `None`）
+    pub timing: Option<ProviderTiming>,
+
+    /// 交易参数摘要：mint、数量、报价、滑点、协议
+    pub details: Option<Arc<TradeDetails>>,
+
+    /// 序列化后的交易（首次访问时生成，克隆的上下文共享）
+    serialized: Arc<OnceLock<Vec<u8>>>,
 }
 
 impl CallbackContext {
@@ -91,23 +172,127 @@ impl CallbackContext {
             timestamp_ns,
             with_tip,
             tip_amount,
+            timing: None,
+            details: None,
+            serialized: Arc::new(OnceLock::new()),
         }
     }
 
+    /// 附加 provider 耗时
+    pub fn with_timing(mut self, timing: ProviderTiming) -> Self {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// 附加交易参数摘要
+    pub fn with_details(mut self, details: Arc<TradeDetails>) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// 调用方附加的交易上下文
+    pub fn metadata(&self) -> Option<&TradeMetadata> {
+        self.details.as_deref().and_then(|d| d.metadata.as_deref())
+    }
+
+    /// 序列化后的交易字节（bincode，即发送到链上的线格式）；序列化失败时为空
+    pub fn serialized_transaction(&self) -> &[u8] {
+        self.serialized.get_or_init(|| {
+            bincode::serialize(&self.transaction).unwrap_or_else(|e| {
+                warn!("交易序列化失败: {}", e);
+                Vec::new()
+            })
+        })
+    }
+
     /// 获取交易的 Base64 编码
     pub fn to_base64(&self) -> String {
-        use bincode::serialize;
-        serialize(&self.transaction)
-            .ok()
-            .and_then(|bytes| base64::engine::general_purpose::STANDARD.encode(&bytes).into())
-            .unwrap_or_else(|| {
-                warn!("交易序列化为 Base64 失败，返回空字符串");
-                String::new()
+        base64::engine::general_purpose::STANDARD.encode(self.serialized_transaction())
+    }
+
+    /// 序列化后的交易字节（拷贝）
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.serialized_transaction().to_vec()
+    }
+
+    /// 获取交易的 Base58 编码
+    pub fn to_base58(&self) -> String {
+        bs58::encode(self.serialized_transaction()).into_string()
+    }
+
+    /// 交易消息头（签名数与只读账户数）
+    pub fn message_header(&self) -> MessageHeader {
+        *self.transaction.message.header()
+    }
+
+    /// 消息中的静态账户列表（不含地址查找表加载的账户），附带签名 / 可写标记
+    pub fn accounts(&self) -> Vec<TransactionAccount> {
+        let header = self.message_header();
+        let keys = self.transaction.message.static_account_keys();
+        let signers = header.num_required_signatures as usize;
+        let writable_signers = signers.saturating_sub(header.num_readonly_signed_accounts as usize);
+        let writable_unsigned =
+            keys.len().saturating_sub(header.num_readonly_unsigned_accounts as usize);
+        keys.iter()
+            .enumerate()
+            .map(|(index, pubkey)| TransactionAccount {
+                pubkey: *pubkey,
+                is_signer: index < signers,
+                is_writable: if index < signers {
+                    index < writable_signers
+                } else {
+                    index < writable_unsigned
+                },
             })
+            .collect()
+    }
+
+    /// 交易线格式的 JSON 表示：签名、消息头、账户、blockhash、指令（data 为 Base58）与查找表
+    pub fn to_wire_json(&self) -> serde_json::Value {
+        let message = &self.transaction.message;
+        let header = self.message_header();
+        let instructions: Vec<serde_json::Value> = message
+            .instructions()
+            .iter()
+            .map(|ix| {
+                serde_json::json!({
+                    "program_id_index": ix.program_id_index,
+                    "accounts": ix.accounts,
+                    "data": bs58::encode(&ix.data).into_string(),
+                })
+            })
+            .collect();
+        let lookups: Vec<serde_json::Value> = message
+            .address_table_lookups()
+            .unwrap_or_default()
+            .iter()
+            .map(|lookup| {
+                serde_json::json!({
+                    "account_key": lookup.account_key.to_string(),
+                    "writable_indexes": lookup.writable_indexes,
+                    "readonly_indexes": lookup.readonly_indexes,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "signatures": self.transaction.signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            "message": {
+                "header": {
+                    "num_required_signatures": header.num_required_signatures,
+                    "num_readonly_signed_accounts": header.num_readonly_signed_accounts,
+                    "num_readonly_unsigned_accounts": header.num_readonly_unsigned_accounts,
+                },
+                "account_keys": self.accounts().iter().map(|a| a.to_json()).collect::<Vec<_>>(),
+                "recent_blockhash": message.recent_blockhash().to_string(),
+                "instructions": instructions,
+                "address_table_lookups": lookups,
+            },
+        })
     }
 
     /// 获取交易的 JSON 表示（用于日志）
     pub fn to_json(&self) -> serde_json::Value {
+        let details = self.details.as_deref();
         serde_json::json!({
             "signature": self.signature,
             "swqos_type": format!("{:?}", self.swqos_type),
@@ -115,11 +300,38 @@ impl CallbackContext {
             "timestamp_ns": self.timestamp_ns,
             "with_tip": self.with_tip,
             "tip_amount": self.tip_amount,
+            "dex_type": details.map(|d| format!("{:?}", d.dex_type)),
+            "mint": details.map(|d| d.mint.to_string()),
+            "input_mint": details.map(|d| d.input_mint.to_string()),
+            "output_mint": details.map(|d| d.output_mint.to_string()),
+            "input_amount": details.and_then(|d| d.input_amount),
+            "output_amount": details.and_then(|d| d.output_amount()),
+            "expected_output": details.and_then(|d| d.expected_output()),
+            "slippage_basis_points": details.and_then(|d| d.slippage_basis_points),
+            "metadata": self.metadata().map(|m| m.labels_json()),
             "transaction_base64": self.to_base64(),
         })
     }
 }
 
+/// 交易消息中的账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TransactionAccount {
+    fn to_json(self) -> serde_json::Value {
+        serde_json::json!({
+            "pubkey": self.pubkey.to_string(),
+            "signer": self.is_signer,
+            "writable": self.is_writable,
+        })
+    }
+}
+
 /// 空回调实现（默认实现，不做任何操作）
 #[derive(Clone)]
 pub struct NoopCallback;
@@ -135,3 +347,250 @@ impl TransactionLifecycleCallback for NoopCallback {
 
 /// Arc 包装的回调（便于共享）
 pub type CallbackRef = Arc<dyn TransactionLifecycleCallback>;
+
+/// 回调注册项
+#[derive(Clone)]
+pub struct CallbackEntry {
+    pub callback: CallbackRef,
+    /// 执行顺序：小的先执行，相同时按注册顺序
+    pub order: i32,
+    /// 执行模式；`None` 时使用交易级 / 全局的 `callback_execution_mode`
+    pub mode: Option<CallbackExecutionMode>,
+}
+
+/// 多回调注册表（例如 入库 + 指标 + webhook）
+///
+/// 按 `order` 依次执行：`Sync` 条目等待完成，失败时阻止交易发送；
+/// `Async` 条目按顺序 spawn，不阻塞发送。
+///
+/// 客户端级通过 `TradingClient::with_callback` 注册；交易级将注册表作为
+/// `on_transaction_signed` 传入（`Some(Arc::new(registry))`），两者合并后客户端条目在同序号下先执行。
+#[derive(Clone, Default)]
+pub struct CallbackRegistry {
+    entries: Vec<CallbackEntry>,
+}
+
+impl std::fmt::Debug for CallbackRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackRegistry")
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+impl CallbackRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册回调
+    pub fn register(
+        &mut self,
+        callback: CallbackRef,
+        order: i32,
+        mode: Option<CallbackExecutionMode>,
+    ) {
+        self.push(CallbackEntry { callback, order, mode });
+    }
+
+    /// 注册回调（builder 形式）
+    pub fn with_callback(
+        mut self,
+        callback: CallbackRef,
+        order: i32,
+        mode: Option<CallbackExecutionMode>,
+    ) -> Self {
+        self.register(callback, order, mode);
+        self
+    }
+
+    fn push(&mut self, entry: CallbackEntry) {
+        // 插入到最后一个 order 不大于它的条目之后，保持同序号的注册顺序
+        let index = self.entries.partition_point(|e| e.order <= entry.order);
+        self.entries.insert(index, entry);
+    }
+
+    /// 按执行顺序排列的条目
+    pub fn entries(&self) -> &[CallbackEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 合并客户端注册表与交易级回调；客户端注册表为空时原样返回交易级回调
+    pub fn merge_with(&self, trade_callback: Option<CallbackRef>) -> Option<CallbackRef> {
+        if self.is_empty() {
+            return trade_callback;
+        }
+        let mut merged = self.clone();
+        if let Some(callback) = trade_callback {
+            match callback.as_registry() {
+                Some(registry) => registry.entries.iter().cloned().for_each(|e| merged.push(e)),
+                None => merged.push(CallbackEntry { callback, order: 0, mode: None }),
+            }
+        }
+        Some(Arc::new(merged))
+    }
+
+    /// 按顺序执行所有回调；`default_mode` 用于未指定模式的条目
+    ///
+    /// 返回第一个 `Sync` 回调的错误（其后的回调不再执行）。
+    pub async fn dispatch(
+        &self,
+        context: CallbackContext,
+        default_mode: CallbackExecutionMode,
+    ) -> Result<()> {
+        for entry in &self.entries {
+            match entry.mode.unwrap_or(default_mode) {
+                CallbackExecutionMode::Sync => {
+                    entry.callback.on_transaction_signed(context.clone()).await?;
+                },
+                CallbackExecutionMode::Async => {
+                    let future = entry.callback.on_transaction_signed(context.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = future.await {
+                            eprintln!(
+                                "[Callback Error] on_transaction_signed failed (Async mode): {:?}",
+                                e
+                            );
+                        }
+                    });
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TransactionLifecycleCallback for CallbackRegistry {
+    fn on_transaction_signed(
+        &self,
+        context: CallbackContext,
+    ) -> futures::future::BoxFuture<'static, Result<()>> {
+        let registry = self.clone();
+        Box::pin(async move { registry.dispatch(context, CallbackExecutionMode::Sync).await })
+    }
+
+    fn as_registry(&self) -> Option<&CallbackRegistry> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+        fail: bool,
+    }
+
+    impl TransactionLifecycleCallback for Recorder {
+        fn on_transaction_signed(
+            &self,
+            _context: CallbackContext,
+        ) -> futures::future::BoxFuture<'static, Result<()>> {
+            self.log.lock().push(self.name);
+            let fail = self.fail;
+            Box::pin(async move { if fail { Err(anyhow::anyhow!("failed")) } else { Ok(()) } })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_order_and_merge() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let recorder =
+            |name, fail| -> CallbackRef { Arc::new(Recorder { name, log: log.clone(), fail }) };
+        let client = CallbackRegistry::new()
+            .with_callback(recorder("webhook", false), 20, None)
+            .with_callback(recorder("db", false), 0, None);
+        let trade = CallbackRegistry::new().with_callback(recorder("metrics", false), 10, None);
+        let merged = client.merge_with(Some(Arc::new(trade))).unwrap();
+        let merged = merged.as_registry().unwrap();
+        assert_eq!(merged.len(), 3);
+
+        let context = CallbackContext::new(
+            VersionedTransaction::default(),
+            SwqosType::Jito,
+            TradeType::Buy,
+            true,
+            0.001,
+        );
+        merged.dispatch(context.clone(), CallbackExecutionMode::Sync).await.unwrap();
+        assert_eq!(*log.lock(), vec!["db", "metrics", "webhook"]);
+
+        // Sync 回调失败时中止后续回调
+        log.lock().clear();
+        let failing = CallbackRegistry::new()
+            .with_callback(recorder("a", true), 0, None)
+            .with_callback(recorder("b", false), 0, None);
+        assert!(failing.dispatch(context, CallbackExecutionMode::Sync).await.is_err());
+        assert_eq!(*log.lock(), vec!["a"]);
+
+        // 客户端注册表为空时不包装交易级回调
+        assert!(CallbackRegistry::new().merge_with(None).is_none());
+    }
+
+    #[test]
+    fn test_export_formats() {
+        use solana_sdk::{
+            instruction::{AccountMeta, Instruction},
+            message::{VersionedMessage, v0},
+            signature::{Keypair, Signer},
+        };
+        let payer = Keypair::new();
+        let (writable, readonly, program) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = Instruction::new_with_bytes(
+            program,
+            &[1, 2, 3],
+            vec![AccountMeta::new(writable, false), AccountMeta::new_readonly(readonly, false)],
+        );
+        let message =
+            v0::Message::try_compile(&payer.pubkey(), &[ix], &[], Default::default()).unwrap();
+        let transaction =
+            VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap();
+        let context =
+            CallbackContext::new(transaction, SwqosType::Jito, TradeType::Buy, false, 0.0);
+
+        assert_eq!(bs58::decode(context.to_base58()).into_vec().unwrap(), context.to_bytes());
+        assert_eq!(context.message_header().num_required_signatures, 1);
+        let accounts = context.accounts();
+        let find = |key: Pubkey| *accounts.iter().find(|a| a.pubkey == key).unwrap();
+        assert!(find(payer.pubkey()).is_signer && find(payer.pubkey()).is_writable);
+        assert!(find(writable).is_writable && !find(writable).is_signer);
+        assert!(!find(readonly).is_writable && !find(program).is_writable);
+
+        let json = context.to_wire_json();
+        assert_eq!(json["signatures"][0], context.signature);
+        assert_eq!(json["message"]["account_keys"].as_array().unwrap().len(), 4);
+        assert_eq!(
+            json["message"]["instructions"][0]["data"],
+            bs58::encode([1, 2, 3]).into_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_capture_quote() {
+        let (value, quote) = capture_quote(async {
+            record_quote(Some(1_000), 950);
+            7
+        })
+        .await;
+        assert_eq!(value, 7);
+        assert_eq!(quote, Some(TradeQuote { expected_output: Some(1_000), min_output: 950 }));
+
+        // 作用域外记录被忽略
+        record_quote(None, 1);
+        let ((), quote) = capture_quote(async {}).await;
+        assert_eq!(quote, None);
+    }
+}
@@ -0,0 +1,108 @@
+//! 回收闲置的地址查找表（ALT）
+//!
+//! ALT 需先停用，停用 slot 移出 SlotHashes（约 512 个 slot）后才能关闭并取回租金。
+//! [`TradingClient::deactivate_and_close_stale_tables`] 每次调用推进一步：对仍处于激活状态的闲置表发送停用指令，
+//! 对冷却期已过的表发送关闭指令，其余留待下次调用。只处理 authority 为 payer 的表。
+
+use crate::TradingClient;
+use crate::common::address_lookup::lookup_table_manager;
+use anyhow::Result;
+use solana_address_lookup_table_interface::instruction::{
+    close_lookup_table, deactivate_lookup_table,
+};
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use std::time::Duration;
+use tracing::warn;
+
+/// 停用后需等待的 slot 数（SlotHashes 容量 + 1）
+pub const DEACTIVATION_COOLDOWN_SLOTS: u64 = 513;
+
+/// 一次回收的结果
+#[derive(Debug, Clone, Default)]
+pub struct StaleTableReport {
+    /// 本次发送停用指令的表
+    pub deactivated: Vec<Pubkey>,
+    /// 本次关闭的表
+    pub closed: Vec<Pubkey>,
+    /// 已停用、仍在冷却期的表
+    pub cooling_down: Vec<Pubkey>,
+    /// 无法处理的表（authority 不是 payer、已冻结或账户数据无效），已取消跟踪
+    pub skipped: Vec<Pubkey>,
+    /// 关闭取回的 lamports
+    pub reclaimed_lamports: u64,
+}
+
+impl TradingClient {
+    /// Deactivate, and once the cooldown has passed close, lookup tables that no strategy
+    /// references and that have been idle for at least `max_idle`
+    ///
+    /// 候选表来自 [`lookup_table_manager`]；已不存在的表直接取消跟踪。
+    pub async fn deactivate_and_close_stale_tables(
+        &self,
+        max_idle: Duration,
+    ) -> Result<StaleTableReport> {
+        let mut report = StaleTableReport::default();
+        let stale = lookup_table_manager().stale_tables(max_idle);
+        if stale.is_empty() {
+            return Ok(report);
+        }
+        let payer = self.payer.pubkey();
+        let current_slot = self.rpc.get_slot().await?;
+        let accounts = self.rpc.get_multiple_accounts(&stale).await?;
+
+        for (address, account) in stale.into_iter().zip(accounts) {
+            let Some(account) = account else {
+                lookup_table_manager().untrack(&address);
+                continue;
+            };
+            let meta = match AddressLookupTable::deserialize(&account.data) {
+                Ok(table) => table.meta,
+                Err(e) => {
+                    warn!("Invalid lookup table {}: {}", address, e);
+                    lookup_table_manager().untrack(&address);
+                    report.skipped.push(address);
+                    continue;
+                },
+            };
+            if meta.authority != Some(payer) {
+                lookup_table_manager().untrack(&address);
+                report.skipped.push(address);
+                continue;
+            }
+
+            if meta.deactivation_slot == u64::MAX {
+                self.send_lookup_table_instruction(deactivate_lookup_table(address, payer))
+                    .await?;
+                lookup_table_manager().mark_deactivated(&address, current_slot);
+                report.deactivated.push(address);
+            } else if current_slot > meta.deactivation_slot + DEACTIVATION_COOLDOWN_SLOTS {
+                self.send_lookup_table_instruction(close_lookup_table(address, payer, payer))
+                    .await?;
+                lookup_table_manager().untrack(&address);
+                report.reclaimed_lamports += account.lamports;
+                report.closed.push(address);
+            } else {
+                lookup_table_manager().mark_deactivated(&address, meta.deactivation_slot);
+                report.cooling_down.push(address);
+            }
+        }
+        Ok(report)
+    }
+
+    async fn send_lookup_table_instruction(&self, ix: Instruction) -> Result<Signature> {
+        let payer = self.payer.pubkey();
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer),
+            &[self.payer.as_ref()],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+    }
+}
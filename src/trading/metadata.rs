@@ -0,0 +1,88 @@
+//! 用户自定义交易上下文
+//!
+//! 通过 `TradeBuyParams::metadata` / `TradeSellParams::metadata` 附加策略标识、关联 ID 等信息，
+//! 随交易传递给中间件（[`crate::trading::InstructionMiddleware`]）、签名回调
+//! （[`crate::trading::CallbackContext::metadata`]）与 webhook 事件（[`crate::trading::TradeEvent`]）。
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// 交易上下文：字符串标签 + 任意类型扩展数据
+#[derive(Clone, Default)]
+pub struct TradeMetadata {
+    /// 字符串标签（会序列化到事件 JSON 中）
+    pub labels: BTreeMap<String, String>,
+    /// 任意类型的扩展数据（仅进程内可见，不序列化）
+    pub extension: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl std::fmt::Debug for TradeMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TradeMetadata")
+            .field("labels", &self.labels)
+            .field("extension", &self.extension.is_some())
+            .finish()
+    }
+}
+
+impl TradeMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加字符串标签
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// 设置扩展数据
+    pub fn with_extension<T: Any + Send + Sync>(mut self, extension: T) -> Self {
+        self.extension = Some(Arc::new(extension));
+        self
+    }
+
+    /// 读取字符串标签
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels.get(key).map(String::as_str)
+    }
+
+    /// 按类型读取扩展数据
+    pub fn extension<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.extension.as_deref().and_then(|ext| ext.downcast_ref::<T>())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.extension.is_none()
+    }
+
+    /// 标签的 JSON 表示（用于事件与日志）
+    pub fn labels_json(&self) -> serde_json::Value {
+        serde_json::json!(self.labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Strategy {
+        id: u32,
+    }
+
+    #[test]
+    fn test_labels_and_extension() {
+        let metadata = TradeMetadata::new()
+            .with_label("strategy", "momentum")
+            .with_label("correlation_id", "abc-123")
+            .with_extension(Strategy { id: 7 });
+        assert_eq!(metadata.label("strategy"), Some("momentum"));
+        assert_eq!(metadata.label("missing"), None);
+        assert_eq!(metadata.extension::<Strategy>(), Some(&Strategy { id: 7 }));
+        assert_eq!(metadata.extension::<u32>(), None);
+        assert_eq!(metadata.labels_json()["correlation_id"], "abc-123");
+        assert!(TradeMetadata::new().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::trading::TradeMetadata;
 use anyhow::Result;
 use solana_sdk::instruction::Instruction;
 
@@ -40,6 +41,34 @@ pub trait InstructionMiddleware: Send + Sync {
         is_buy: bool,
     ) -> Result<Vec<Instruction>>;
 
+    /// Process protocol_instructions with the trade's user-defined metadata
+    ///
+    /// 默认忽略 metadata，直接调用 [`Self::process_protocol_instructions`]
+    fn process_protocol_instructions_with_metadata(
+        &self,
+        protocol_instructions: Vec<Instruction>,
+        protocol_name: String,
+        is_buy: bool,
+        metadata: Option<&TradeMetadata>,
+    ) -> Result<Vec<Instruction>> {
+        let _ = metadata;
+        self.process_protocol_instructions(protocol_instructions, protocol_name, is_buy)
+    }
+
+    /// Process full_instructions with the trade's user-defined metadata
+    ///
+    /// 默认忽略 metadata，直接调用 [`Self::process_full_instructions`]
+    fn process_full_instructions_with_metadata(
+        &self,
+        full_instructions: Vec<Instruction>,
+        protocol_name: String,
+        is_buy: bool,
+        metadata: Option<&TradeMetadata>,
+    ) -> Result<Vec<Instruction>> {
+        let _ = metadata;
+        self.process_full_instructions(full_instructions, protocol_name, is_buy)
+    }
+
     /// Clone middleware
     fn clone_box(&self) -> Box<dyn InstructionMiddleware>;
 }
@@ -80,12 +109,14 @@ impl MiddlewareManager {
         mut full_instructions: Vec<Instruction>,
         protocol_name: String,
         is_buy: bool,
+        metadata: Option<&TradeMetadata>,
     ) -> Result<Vec<Instruction>> {
         for middleware in &self.middlewares {
-            full_instructions = middleware.process_full_instructions(
+            full_instructions = middleware.process_full_instructions_with_metadata(
                 full_instructions,
                 protocol_name.clone(),
                 is_buy,
+                metadata,
             )?;
             if full_instructions.is_empty() {
                 break;
@@ -100,12 +131,14 @@ impl MiddlewareManager {
         mut protocol_instructions: Vec<Instruction>,
         protocol_name: String,
         is_buy: bool,
+        metadata: Option<&TradeMetadata>,
     ) -> Result<Vec<Instruction>> {
         for middleware in &self.middlewares {
-            protocol_instructions = middleware.process_protocol_instructions(
+            protocol_instructions = middleware.process_protocol_instructions_with_metadata(
                 protocol_instructions,
                 protocol_name.clone(),
                 is_buy,
+                metadata,
             )?;
             if protocol_instructions.is_empty() {
                 break;
@@ -0,0 +1,247 @@
+//! 从解析后的链上交易构建交易参数（跟单 / 重放）
+//!
+//! 解析器（[`crate::parser`]）输出的 [`ParsedTradeInfo`] 记录了协议、池地址、输入输出代币与数量，
+//! [`TradeBuyParams::from_parsed_trade`] / [`TradeSellParams::from_parsed_trade`] 据此通过 RPC
+//! 重建协议参数，数量默认与被观察交易相同，可在返回后按需调整 `input_token_amount`。
+//!
+//! [`TradingClient::mirror_trade`] 按交易方向自动选择买入或卖出，一行完成跟单。
+
+use crate::common::{GasFeeStrategy, SolanaRpcClient};
+use crate::constants::{SOL_TOKEN_ACCOUNT, USD1_TOKEN_ACCOUNT, USDC_MINT, WSOL_TOKEN_ACCOUNT};
+use crate::parser::types::{DexProtocol, ParsedTradeInfo, TokenInfo, TradeType};
+use crate::swqos::common::TradeError;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use crate::{TradeBuyParams, TradeSellParams, TradeTokenType, TradingClient};
+use anyhow::{Result, anyhow};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+/// 被观察交易的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorSide {
+    Buy,
+    Sell,
+}
+
+/// 计价资产对应的 [`TradeTokenType`]（WSOL 按 SOL 处理，由 SDK 负责 wrap / unwrap）
+fn quote_token_type(mint: &Pubkey) -> Option<TradeTokenType> {
+    match *mint {
+        m if m == WSOL_TOKEN_ACCOUNT || m == SOL_TOKEN_ACCOUNT => Some(TradeTokenType::SOL),
+        m if m == USDC_MINT => Some(TradeTokenType::USDC),
+        m if m == USD1_TOKEN_ACCOUNT => Some(TradeTokenType::USD1),
+        _ => None,
+    }
+}
+
+/// 判断交易方向：`Swap` 按哪一侧是计价资产推断
+pub fn mirror_side(trade: &ParsedTradeInfo) -> Result<MirrorSide> {
+    match trade.trade_type {
+        TradeType::Buy => Ok(MirrorSide::Buy),
+        TradeType::Sell => Ok(MirrorSide::Sell),
+        TradeType::Swap => {
+            if quote_token_type(&trade.input_token.mint).is_some() {
+                Ok(MirrorSide::Buy)
+            } else if quote_token_type(&trade.output_token.mint).is_some() {
+                Ok(MirrorSide::Sell)
+            } else {
+                Err(anyhow!(
+                    "trade {} swaps {} for {}; neither side is a supported quote asset",
+                    trade.signature,
+                    trade.input_token.mint,
+                    trade.output_token.mint
+                ))
+            }
+        },
+    }
+}
+
+fn parsed_dex_type(trade: &ParsedTradeInfo) -> Result<DexType> {
+    DexProtocol::from_name(&trade.dex)
+        .map(|protocol| protocol.dex_type())
+        .ok_or_else(|| anyhow!("unsupported dex in parsed trade: {}", trade.dex))
+}
+
+fn raw_amount(token: &TokenInfo) -> Result<u64> {
+    token
+        .amount_raw
+        .parse::<u64>()
+        .map_err(|e| anyhow!("invalid raw amount {:?}: {}", token.amount_raw, e))
+}
+
+impl TradeBuyParams {
+    /// 按被观察的买入交易构建买入参数：同一协议、同一池、相同投入数量
+    pub async fn from_parsed_trade(
+        rpc: &SolanaRpcClient,
+        trade: &ParsedTradeInfo,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<Self> {
+        if mirror_side(trade)? != MirrorSide::Buy {
+            return Err(anyhow!("trade {} is not a buy", trade.signature));
+        }
+        let input_token_type = quote_token_type(&trade.input_token.mint)
+            .ok_or_else(|| anyhow!("unsupported input token {} for buy", trade.input_token.mint))?;
+        let dex_type = parsed_dex_type(trade)?;
+        let extension_params = DexParamEnum::from_pool(rpc, dex_type.clone(), &trade.pool).await?;
+        let is_sol = input_token_type == TradeTokenType::SOL;
+        Ok(Self {
+            dex_type,
+            input_token_type,
+            mint: trade.output_token.mint,
+            input_token_amount: raw_amount(&trade.input_token)?,
+            slippage_basis_points: None,
+            recent_blockhash: None,
+            last_valid_block_height: None,
+            extension_params,
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: true,
+            create_input_token_ata: is_sol,
+            close_input_token_ata: is_sol,
+            create_mint_ata: true,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy,
+            simulate: false,
+            on_transaction_signed: None,
+            callback_execution_mode: None,
+            enable_jito_sandwich_protection: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
+        })
+    }
+}
+
+impl TradeSellParams {
+    /// 按被观察的卖出交易构建卖出参数：同一协议、同一池、相同卖出数量
+    ///
+    /// 卖出得到 SOL 时输出为 WSOL（复用 WSOL ATA，不关闭），其他计价资产按原样输出。
+    pub async fn from_parsed_trade(
+        rpc: &SolanaRpcClient,
+        trade: &ParsedTradeInfo,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<Self> {
+        if mirror_side(trade)? != MirrorSide::Sell {
+            return Err(anyhow!("trade {} is not a sell", trade.signature));
+        }
+        let output_token_type = match quote_token_type(&trade.output_token.mint) {
+            Some(TradeTokenType::SOL) => TradeTokenType::WSOL,
+            Some(token_type) => token_type,
+            None => {
+                return Err(anyhow!(
+                    "unsupported output token {} for sell",
+                    trade.output_token.mint
+                ));
+            },
+        };
+        let dex_type = parsed_dex_type(trade)?;
+        let extension_params = DexParamEnum::from_pool(rpc, dex_type.clone(), &trade.pool).await?;
+        Ok(Self {
+            dex_type,
+            output_token_type,
+            mint: trade.input_token.mint,
+            input_token_amount: raw_amount(&trade.input_token)?,
+            slippage_basis_points: None,
+            recent_blockhash: None,
+            last_valid_block_height: None,
+            with_tip: true,
+            extension_params,
+            address_lookup_table_account: None,
+            wait_transaction_confirmed: true,
+            create_output_token_ata: true,
+            close_output_token_ata: false,
+            close_mint_token_ata: false,
+            durable_nonce: None,
+            fixed_output_token_amount: None,
+            gas_fee_strategy,
+            simulate: false,
+            on_transaction_signed: None,
+            callback_execution_mode: None,
+            enable_jito_sandwich_protection: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
+        })
+    }
+}
+
+impl TradingClient {
+    /// 按被观察交易的方向与数量跟单（买入或卖出）
+    ///
+    /// 卖出数量与被观察交易相同，持仓不足时交易会失败；需要按比例跟单时请使用
+    /// [`TradeBuyParams::from_parsed_trade`] / [`TradeSellParams::from_parsed_trade`] 并调整数量。
+    pub async fn mirror_trade(
+        &self,
+        trade: &ParsedTradeInfo,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        match mirror_side(trade)? {
+            MirrorSide::Buy => {
+                self.buy(
+                    TradeBuyParams::from_parsed_trade(&self.rpc, trade, gas_fee_strategy).await?,
+                )
+                .await
+            },
+            MirrorSide::Sell => {
+                self.sell(
+                    TradeSellParams::from_parsed_trade(&self.rpc, trade, gas_fee_strategy).await?,
+                )
+                .await
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed_trade(trade_type: TradeType, input: Pubkey, output: Pubkey) -> ParsedTradeInfo {
+        ParsedTradeInfo {
+            user: Pubkey::new_unique(),
+            fee_payer: None,
+            trade_type,
+            pool: Pubkey::new_unique(),
+            input_token: TokenInfo::from_raw_amount(input, 1_000_000, 9),
+            output_token: TokenInfo::from_raw_amount(output, 5_000_000, 6),
+            fee: None,
+            fees: vec![],
+            dex: DexProtocol::RaydiumCpmm.name().to_string(),
+            aggregator: None,
+            signature: "sig".to_string(),
+            slot: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_mirror_side() {
+        let token = Pubkey::new_unique();
+        let buy = parsed_trade(TradeType::Swap, WSOL_TOKEN_ACCOUNT, token);
+        assert_eq!(mirror_side(&buy).unwrap(), MirrorSide::Buy);
+        assert_eq!(parsed_dex_type(&buy).unwrap(), DexType::RaydiumCpmm);
+        assert_eq!(raw_amount(&buy.input_token).unwrap(), 1_000_000);
+
+        let sell = parsed_trade(TradeType::Swap, token, USDC_MINT);
+        assert_eq!(mirror_side(&sell).unwrap(), MirrorSide::Sell);
+
+        let unknown = parsed_trade(TradeType::Swap, token, Pubkey::new_unique());
+        assert!(mirror_side(&unknown).is_err());
+        assert_eq!(
+            mirror_side(&parsed_trade(TradeType::Sell, token, token)).unwrap(),
+            MirrorSide::Sell
+        );
+    }
+}
@@ -0,0 +1,524 @@
+//! 实验性：PumpSwap / Raydium CPMM 库存感知做市循环
+//!
+//! AMM 池不能挂单，这里的“报价”是策略自身的买卖价：以池子中间价的 EMA 作为公允价，
+//! 两侧各偏离 `half_spread_bps`，并按库存相对目标仓位的偏离整体平移（库存越多报价越低，
+//! 越倾向卖出）。每个周期：
+//!
+//! 1. 刷新协议参数，用 calc 模块计算 `order_size` 大小的实际买入 / 卖出成交价；
+//! 2. 按估算成交额与中间价计算盈亏，亏损超过 `max_loss` 时停止；
+//! 3. 仓位偏离目标超过 `rebalance_threshold` 时直接向目标方向成交（受 `order_size` 限制）；
+//! 4. 否则池子卖价不高于策略买价时买入、池子买价不低于策略卖价时卖出，仓位不超过 `max_position`。
+//!
+//! 成交数量按报价估算（不回读链上余额），适合作为参考实现，生产使用前应结合实际余额校正。
+
+use crate::common::Bps;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use crate::utils::calc;
+use crate::{TradeBuyParams, TradeSellParams, TradingClient};
+use anyhow::{Result, anyhow};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// 做市配置（数量均为原始单位：base 为做市代币，quote 为计价资产）
+#[derive(Debug, Clone)]
+pub struct MmConfig {
+    /// 池地址
+    pub pool: Pubkey,
+    /// 单次成交的计价资产数量
+    pub order_size: u64,
+    /// 报价半价差（基点）
+    pub half_spread_bps: Bps,
+    /// 仓位达到 `max_position` 时报价的最大平移（基点）
+    pub inventory_skew_bps: Bps,
+    /// 目标仓位
+    pub target_position: u64,
+    /// 最大仓位
+    pub max_position: u64,
+    /// 仓位偏离目标超过该值时强制再平衡；0 表示不再平衡
+    pub rebalance_threshold: u64,
+    /// 最大亏损（计价资产），超过后停止
+    pub max_loss: u64,
+    /// 启动时已持有的仓位
+    pub initial_position: u64,
+    /// 公允价 EMA 系数（0.0 - 1.0，越大越贴近最新中间价）
+    pub fair_value_alpha: f64,
+    /// 单笔滑点
+    pub slippage_bps: Bps,
+    /// 循环间隔
+    pub interval: Duration,
+}
+
+impl MmConfig {
+    pub fn new(pool: Pubkey, order_size: u64, max_position: u64) -> Self {
+        Self {
+            pool,
+            order_size,
+            half_spread_bps: Bps::new(50),
+            inventory_skew_bps: Bps::new(50),
+            target_position: 0,
+            max_position,
+            rebalance_threshold: 0,
+            max_loss: u64::MAX,
+            initial_position: 0,
+            fair_value_alpha: 0.2,
+            slippage_bps: Bps::new(100),
+            interval: Duration::from_secs(2),
+        }
+    }
+
+    pub fn with_spread(mut self, half_spread_bps: Bps, inventory_skew_bps: Bps) -> Self {
+        self.half_spread_bps = half_spread_bps;
+        self.inventory_skew_bps = inventory_skew_bps;
+        self
+    }
+
+    pub fn with_target_position(mut self, target_position: u64, rebalance_threshold: u64) -> Self {
+        self.target_position = target_position;
+        self.rebalance_threshold = rebalance_threshold;
+        self
+    }
+
+    pub fn with_max_loss(mut self, max_loss: u64) -> Self {
+        self.max_loss = max_loss;
+        self
+    }
+
+    pub fn with_initial_position(mut self, initial_position: u64) -> Self {
+        self.initial_position = initial_position;
+        self
+    }
+
+    pub fn with_fair_value_alpha(mut self, fair_value_alpha: f64) -> Self {
+        self.fair_value_alpha = fair_value_alpha.clamp(0.01, 1.0);
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage_bps: Bps) -> Self {
+        self.slippage_bps = slippage_bps;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+/// 池子在 `order_size` 下的可成交价格（quote / base）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolPrices {
+    pub mid: f64,
+    /// 买入 `order_size` 计价资产可得的 base 数量
+    pub buy_base_out: u64,
+    /// 与 `order_size` 等值（按中间价）的卖出 base 数量
+    pub sell_base_in: u64,
+    /// 卖出 `sell_base_in` 可得的计价资产
+    pub sell_quote_out: u64,
+}
+
+impl PoolPrices {
+    /// 买入成交价（含手续费）
+    pub fn buy_price(&self, order_size: u64) -> f64 {
+        if self.buy_base_out == 0 {
+            return f64::INFINITY;
+        }
+        order_size as f64 / self.buy_base_out as f64
+    }
+
+    /// 卖出成交价（含手续费）
+    pub fn sell_price(&self) -> f64 {
+        if self.sell_base_in == 0 {
+            return 0.0;
+        }
+        self.sell_quote_out as f64 / self.sell_base_in as f64
+    }
+}
+
+/// 用 calc 模块计算池子的可成交价格（仅支持 PumpSwap 与 Raydium CPMM）
+pub fn pool_prices(params: &DexParamEnum, mint: &Pubkey, order_size: u64) -> Result<PoolPrices> {
+    let quote_reserve = params
+        .input_reserve(mint, true)
+        .ok_or_else(|| anyhow!("pool reserves unavailable"))?;
+    let base_reserve = params
+        .input_reserve(mint, false)
+        .ok_or_else(|| anyhow!("pool reserves unavailable"))?;
+    if base_reserve == 0 || quote_reserve == 0 {
+        return Err(anyhow!("pool has no liquidity"));
+    }
+    let mid = quote_reserve as f64 / base_reserve as f64;
+    let sell_base_in = ((order_size as f64 / mid) as u64).max(1);
+    let (buy_base_out, sell_quote_out) = match params {
+        DexParamEnum::PumpSwap(p) => {
+            if p.base_mint != *mint {
+                return Err(anyhow!("market making requires {} to be the pool base mint", mint));
+            }
+            let buy = calc::pumpswap::buy_quote_input_internal(
+                order_size,
+                Bps::ZERO,
+                base_reserve,
+                quote_reserve,
+                &p.coin_creator,
+            )
+            .map_err(|e| anyhow!(e))?;
+            let sell = calc::pumpswap::sell_base_input_internal(
+                sell_base_in,
+                Bps::ZERO,
+                base_reserve,
+                quote_reserve,
+                &p.coin_creator,
+            )
+            .map_err(|e| anyhow!(e))?;
+            (buy.base, sell.ui_quote)
+        },
+        DexParamEnum::RaydiumCpmm(_) => {
+            let buy = calc::raydium_cpmm::compute_swap_amount(
+                base_reserve,
+                quote_reserve,
+                false,
+                order_size,
+                Bps::ZERO,
+            );
+            let sell = calc::raydium_cpmm::compute_swap_amount(
+                base_reserve,
+                quote_reserve,
+                true,
+                sell_base_in,
+                Bps::ZERO,
+            );
+            (buy.amount_out, sell.amount_out)
+        },
+        other => return Err(anyhow!("market making is not supported on {:?}", other.dex_type())),
+    };
+    Ok(PoolPrices { mid, buy_base_out, sell_base_in, sell_quote_out })
+}
+
+/// 策略报价
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MmQuotes {
+    pub bid: f64,
+    pub ask: f64,
+}
+
+/// 按公允价与当前仓位计算报价
+pub fn compute_quotes(fair_value: f64, position: u64, config: &MmConfig) -> MmQuotes {
+    let deviation = position as f64 - config.target_position as f64;
+    let scale = config.max_position.max(1) as f64;
+    let skew = (deviation / scale).clamp(-1.0, 1.0) * config.inventory_skew_bps.to_fraction();
+    let half_spread = config.half_spread_bps.to_fraction();
+    MmQuotes {
+        bid: fair_value * (1.0 - (half_spread + skew)),
+        ask: fair_value * (1.0 + (half_spread - skew)),
+    }
+}
+
+/// 停止原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmStopReason {
+    Stopped,
+    LossLimit,
+}
+
+/// 单个周期的决策
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmAction {
+    Hold,
+    Buy { quote_in: u64, base_out: u64 },
+    Sell { base_in: u64, quote_out: u64 },
+    Stop(MmStopReason),
+}
+
+/// 做市统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MmStats {
+    /// 当前仓位（估算）
+    pub position: u64,
+    /// 累计计价资产净流入（卖出所得 - 买入支出）
+    pub quote_flow: i128,
+    /// 最近一次按中间价计算的盈亏（计价资产）
+    pub pnl: f64,
+    pub fair_value: f64,
+    pub buys: usize,
+    pub sells: usize,
+    pub rebalances: usize,
+    pub failures: usize,
+    /// 成功交易支付的优先费与小费（lamports）
+    pub fee_lamports: u64,
+}
+
+impl MmStats {
+    /// 以 `mid` 计价的盈亏：资金净流入 + 仓位变化市值
+    pub fn mark_to_market(&self, mid: f64, initial_position: u64) -> f64 {
+        self.quote_flow as f64 + (self.position as f64 - initial_position as f64) * mid
+    }
+}
+
+/// 根据池子价格、报价与仓位决定本周期动作
+pub fn decide(stats: &MmStats, prices: &PoolPrices, config: &MmConfig) -> MmAction {
+    if stats.pnl < -(config.max_loss as f64) {
+        return MmAction::Stop(MmStopReason::LossLimit);
+    }
+    let buy = MmAction::Buy { quote_in: config.order_size, base_out: prices.buy_base_out };
+    let can_buy = stats.position.saturating_add(prices.buy_base_out) <= config.max_position;
+    let sell_base_in = prices.sell_base_in.min(stats.position);
+    let sell = MmAction::Sell {
+        base_in: sell_base_in,
+        quote_out: (prices.sell_quote_out as u128 * sell_base_in as u128
+            / prices.sell_base_in.max(1) as u128) as u64,
+    };
+    let can_sell = sell_base_in > 0;
+
+    if config.rebalance_threshold > 0 {
+        let target = config.target_position;
+        if stats.position > target.saturating_add(config.rebalance_threshold) && can_sell {
+            return sell;
+        }
+        if stats.position.saturating_add(config.rebalance_threshold) < target && can_buy {
+            return buy;
+        }
+    }
+
+    let quotes = compute_quotes(stats.fair_value, stats.position, config);
+    if can_buy && prices.buy_price(config.order_size) <= quotes.bid {
+        return buy;
+    }
+    if can_sell && prices.sell_price() >= quotes.ask {
+        return sell;
+    }
+    MmAction::Hold
+}
+
+/// 做市结果
+#[derive(Debug, Clone, Copy)]
+pub struct MmReport {
+    pub stats: MmStats,
+    pub reason: MmStopReason,
+}
+
+/// 后台做市任务句柄
+pub struct MmHandle {
+    stop_tx: watch::Sender<bool>,
+    stats: Arc<Mutex<MmStats>>,
+    task: JoinHandle<MmReport>,
+}
+
+impl MmHandle {
+    pub fn stats(&self) -> MmStats {
+        *self.stats.lock()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// 停止循环（正在提交的交易会执行完）并等待任务结束
+    pub async fn stop(self) -> Result<MmReport> {
+        let _ = self.stop_tx.send(true);
+        self.wait().await
+    }
+
+    pub async fn wait(self) -> Result<MmReport> {
+        self.task.await.map_err(|e| anyhow!("market maker task failed: {}", e))
+    }
+}
+
+impl TradingClient {
+    /// 启动后台做市循环
+    ///
+    /// `buy_template` / `sell_template` 提供 gas 策略、ATA 选项等；数量、滑点与协议参数
+    /// 每个周期由循环填写。两个模板必须是同一协议、同一 mint。
+    pub fn start_market_maker(
+        &self,
+        buy_template: TradeBuyParams,
+        sell_template: TradeSellParams,
+        config: MmConfig,
+    ) -> Result<MmHandle> {
+        if !matches!(buy_template.dex_type, DexType::PumpSwap | DexType::RaydiumCpmm) {
+            return Err(anyhow!("market making is not supported on {:?}", buy_template.dex_type));
+        }
+        if buy_template.dex_type != sell_template.dex_type
+            || buy_template.mint != sell_template.mint
+        {
+            return Err(anyhow!("buy and sell templates must target the same pool"));
+        }
+        pool_prices(&buy_template.extension_params, &buy_template.mint, config.order_size)?;
+
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let stats = Arc::new(Mutex::new(MmStats {
+            position: config.initial_position,
+            ..Default::default()
+        }));
+        let client = self.clone();
+        let task_stats = stats.clone();
+        let task = tokio::spawn(async move {
+            client
+                .run_market_maker(buy_template, sell_template, config, stop_rx, task_stats)
+                .await
+        });
+        Ok(MmHandle { stop_tx, stats, task })
+    }
+
+    async fn run_market_maker(
+        &self,
+        mut buy_template: TradeBuyParams,
+        mut sell_template: TradeSellParams,
+        config: MmConfig,
+        mut stop: watch::Receiver<bool>,
+        shared: Arc<Mutex<MmStats>>,
+    ) -> MmReport {
+        let mint = buy_template.mint;
+        let mut stats = *shared.lock();
+        let mut interval = tokio::time::interval(config.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let reason = loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = stop.changed() => break MmStopReason::Stopped,
+            }
+            if *stop.borrow() {
+                break MmStopReason::Stopped;
+            }
+
+            match DexParamEnum::from_pool_force(
+                &self.rpc,
+                buy_template.dex_type.clone(),
+                &config.pool,
+            )
+            .await
+            {
+                Ok(params) => {
+                    buy_template.extension_params = params.clone();
+                    sell_template.extension_params = params;
+                },
+                Err(e) => {
+                    log::warn!("mm: failed to refresh pool {}: {}", config.pool, e);
+                    continue;
+                },
+            }
+            let prices = match pool_prices(&buy_template.extension_params, &mint, config.order_size)
+            {
+                Ok(prices) => prices,
+                Err(e) => {
+                    log::warn!("mm: failed to price pool {}: {}", config.pool, e);
+                    continue;
+                },
+            };
+            stats.fair_value = if stats.fair_value == 0.0 {
+                prices.mid
+            } else {
+                config.fair_value_alpha * prices.mid
+                    + (1.0 - config.fair_value_alpha) * stats.fair_value
+            };
+            stats.pnl = stats.mark_to_market(prices.mid, config.initial_position);
+
+            let action = decide(&stats, &prices, &config);
+            let rebalance = config.rebalance_threshold > 0
+                && stats.position.abs_diff(config.target_position) > config.rebalance_threshold;
+            let result = match action {
+                MmAction::Hold => None,
+                MmAction::Stop(reason) => {
+                    *shared.lock() = stats;
+                    break reason;
+                },
+                MmAction::Buy { quote_in, .. } => {
+                    let mut params = buy_template.clone();
+                    params.input_token_amount = quote_in;
+                    params.slippage_basis_points = Some(config.slippage_bps);
+                    params.fixed_output_token_amount = None;
+                    Some(self.buy_with_result(params).await)
+                },
+                MmAction::Sell { base_in, .. } => {
+                    let mut params = sell_template.clone();
+                    params.input_token_amount = base_in;
+                    params.slippage_basis_points = Some(config.slippage_bps);
+                    params.fixed_output_token_amount = None;
+                    Some(self.sell_with_result(params).await)
+                },
+            };
+            match result {
+                Some(Ok(result)) if result.success => {
+                    stats.fee_lamports += result.fee_lamports();
+                    if rebalance {
+                        stats.rebalances += 1;
+                    }
+                    match action {
+                        MmAction::Buy { quote_in, base_out } => {
+                            stats.buys += 1;
+                            stats.position += base_out;
+                            stats.quote_flow -= quote_in as i128;
+                        },
+                        MmAction::Sell { base_in, quote_out } => {
+                            stats.sells += 1;
+                            stats.position -= base_in;
+                            stats.quote_flow += quote_out as i128;
+                        },
+                        _ => {},
+                    }
+                    stats.pnl = stats.mark_to_market(prices.mid, config.initial_position);
+                },
+                Some(Ok(result)) => {
+                    stats.failures += 1;
+                    log::warn!("mm: {:?} failed: {:?}", action, result.error);
+                },
+                Some(Err(e)) => {
+                    stats.failures += 1;
+                    log::warn!("mm: {:?} failed: {}", action, e);
+                },
+                None => {},
+            }
+            *shared.lock() = stats;
+        };
+        MmReport { stats: *shared.lock(), reason }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes_skew_with_inventory_and_decide() {
+        let config = MmConfig::new(Pubkey::new_unique(), 1_000, 10_000)
+            .with_spread(Bps::new(100), Bps::new(100))
+            .with_target_position(5_000, 0);
+        let flat = compute_quotes(1.0, 5_000, &config);
+        assert!((flat.bid - 0.99).abs() < 1e-9 && (flat.ask - 1.01).abs() < 1e-9);
+        // 库存高于目标：报价下移，更容易卖出
+        let long = compute_quotes(1.0, 10_000, &config);
+        assert!(long.bid < flat.bid && long.ask < flat.ask);
+
+        let stats = MmStats { position: 5_000, fair_value: 1.0, ..Default::default() };
+        let cheap = PoolPrices {
+            mid: 0.95,
+            buy_base_out: 1_040,
+            sell_base_in: 1_052,
+            sell_quote_out: 990,
+        };
+        assert_eq!(
+            decide(&stats, &cheap, &config),
+            MmAction::Buy { quote_in: 1_000, base_out: 1_040 }
+        );
+        let fair = PoolPrices {
+            mid: 1.0,
+            buy_base_out: 990,
+            sell_base_in: 1_000,
+            sell_quote_out: 990,
+        };
+        assert_eq!(decide(&stats, &fair, &config), MmAction::Hold);
+
+        // 买入会超过最大仓位
+        let full = MmStats { position: 9_500, ..stats };
+        assert!(!matches!(decide(&full, &cheap, &config), MmAction::Buy { .. }));
+
+        let config = config.with_target_position(5_000, 1_000).with_max_loss(100);
+        let over = MmStats { position: 8_000, ..stats };
+        assert!(matches!(decide(&over, &fair, &config), MmAction::Sell { base_in: 1_000, .. }));
+        let losing = MmStats { pnl: -101.0, ..stats };
+        assert_eq!(decide(&losing, &fair, &config), MmAction::Stop(MmStopReason::LossLimit));
+    }
+}
@@ -1,11 +1,77 @@
+pub mod arbitrage;
+#[cfg(feature = "backtest")]
+pub mod backtest;
+pub mod basket;
+pub mod clmm_liquidity;
 pub mod common;
 pub mod core;
+pub mod dual_venue;
+pub mod dust;
 pub mod factory;
+pub mod fill;
 pub mod lifecycle;
+pub mod lookup_tables;
+pub mod metadata;
 pub mod middleware;
+pub mod mirror;
+pub mod mm;
+pub mod multisig;
+pub mod nonce_accounts;
+pub mod notifier;
+pub mod orders;
+pub mod pipeline;
+pub mod pool_guard;
+pub mod rate_limit;
+pub mod recovery;
+pub mod resubmitter;
+pub mod sandwich_guard;
+pub mod self_check;
+pub mod sell_guard;
+pub mod sender;
+pub mod signal;
+pub mod sniper;
+pub mod sol_reserve;
+pub mod store;
+pub mod submission_queue;
+pub mod timing;
+pub mod tranche;
+pub mod transfer;
+pub mod transfer_fee;
+pub mod twap;
 
+pub use arbitrage::{ArbConfig, ArbOpportunity, ArbVenue};
+pub use basket::{BasketBuyParams, BasketLeg, BasketMode};
 pub use core::params::SwapParams;
 pub use core::traits::InstructionBuilder;
+pub use dual_venue::DualVenueResult;
+pub use dust::{DustLiquidationReport, DustPosition};
 pub use factory::TradeFactory;
-pub use lifecycle::{CallbackContext, CallbackRef, NoopCallback, TransactionLifecycleCallback};
+pub use fill::{DuplicateExecution, ResolvedFill, SlippageViolation, detect_duplicates};
+pub use lifecycle::{
+    CallbackContext, CallbackEntry, CallbackRef, CallbackRegistry, NoopCallback, TradeDetails,
+    TradeQuote, TransactionAccount, TransactionLifecycleCallback,
+};
+pub use metadata::TradeMetadata;
 pub use middleware::{InstructionMiddleware, MiddlewareManager};
+pub use mirror::MirrorSide;
+pub use mm::{MmConfig, MmHandle, MmReport, MmStats};
+pub use multisig::SquadsConfig;
+pub use notifier::{TradeEvent, WebhookConfig, WebhookNotifier};
+pub use pipeline::{
+    PipelineConfig, PipelineMetrics, PipelineResult, PipelineTrade, StageMetrics, TradePipeline,
+};
+pub use recovery::{NonceRecovery, RecoveryReport};
+pub use resubmitter::{NonceResubmitter, ResubmitConfig, ResubmitHandle, ResubmitOutcome};
+pub use sender::SendOptions;
+pub use signal::{
+    SignalExecutor, SignalExecutorConfig, SignalResult, SignalRunStats, SignalSide, SignalUrgency,
+    TradeSignal,
+};
+pub use sniper::{
+    NewPoolEvent, SnipeOutcome, SnipeParamsBuilder, Sniper, SniperConfig, SniperFilter,
+};
+pub use store::{MemoryTradeStore, TradeRecord, TradeRecordStatus, TradeStore, TradeStoreRef};
+pub use timing::{ProviderTiming, TradeTiming};
+pub use tranche::{TrancheConfig, TrancheReport, TrancheSpacing};
+pub use transfer::TransferOptions;
+pub use twap::{TwapConfig, TwapHandle, TwapOrder, TwapReport};
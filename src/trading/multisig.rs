@@ -0,0 +1,357 @@
+//! Squads v4 多签执行模式
+//!
+//! 资金存放在 Squads 多签金库（vault）时，交易不能由 payer 直接签名执行。此模式下各协议的
+//! 指令构建器保持不变，执行前将构建好的指令：
+//! 1. 把交易主体从 payer 替换为金库（包括 payer 的 ATA 替换为金库的 ATA）；
+//! 2. 编码为 Squads 的 `TransactionMessage`，包装成 `vault_transaction_create` +
+//!    `proposal_create`（可选 `proposal_approve`）指令，由 payer（多签成员）签名发送。
+//!
+//! 提案达到阈值后由成员在 Squads 中执行。内层交易的账户与指令数据会全部写入
+//! `vault_transaction_create`，大交易可能超出单笔交易的大小限制，必要时配合地址查找表使用。
+
+use crate::common::auto_mock_rpc::PoolRpcClient;
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
+use crate::constants::{SYSTEM_PROGRAM, TOKEN_PROGRAM, TOKEN_PROGRAM_2022, WSOL_TOKEN_ACCOUNT};
+use anyhow::{Result, anyhow};
+use borsh::BorshSerialize;
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey,
+    pubkey::Pubkey,
+};
+use std::collections::HashMap;
+
+/// Squads v4 程序
+pub const SQUADS_V4_PROGRAM: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+/// Multisig 账户中 `transaction_index` 的偏移：
+/// discriminator(8) + create_key(32) + config_authority(32) + threshold(2) + time_lock(4)
+const MULTISIG_TRANSACTION_INDEX_OFFSET: usize = 78;
+
+/// 多签执行配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SquadsConfig {
+    /// Squads multisig 账户
+    pub multisig: Pubkey,
+    /// 金库序号（默认：0）
+    pub vault_index: u8,
+    /// 创建提案后是否由 payer 立即投赞成票（默认：true）
+    pub approve: bool,
+    /// 提案备注
+    pub memo: Option<String>,
+}
+
+impl SquadsConfig {
+    pub fn new(multisig: Pubkey) -> Self {
+        Self { multisig, vault_index: 0, approve: true, memo: None }
+    }
+
+    pub fn with_vault_index(mut self, vault_index: u8) -> Self {
+        self.vault_index = vault_index;
+        self
+    }
+
+    pub fn with_approve(mut self, approve: bool) -> Self {
+        self.approve = approve;
+        self
+    }
+
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// 金库地址（交易的实际主体）
+    pub fn vault(&self) -> Pubkey {
+        get_vault_pda(&self.multisig, self.vault_index)
+    }
+}
+
+pub fn get_vault_pda(multisig: &Pubkey, vault_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[vault_index]],
+        &SQUADS_V4_PROGRAM,
+    )
+    .0
+}
+
+pub fn get_transaction_pda(multisig: &Pubkey, transaction_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_TRANSACTION, &transaction_index.to_le_bytes()],
+        &SQUADS_V4_PROGRAM,
+    )
+    .0
+}
+
+pub fn get_proposal_pda(multisig: &Pubkey, transaction_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+            SEED_PROPOSAL,
+        ],
+        &SQUADS_V4_PROGRAM,
+    )
+    .0
+}
+
+/// 读取 multisig 账户当前的 `transaction_index`
+pub async fn fetch_transaction_index<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    multisig: &Pubkey,
+) -> Result<u64> {
+    let account = rpc.get_account(multisig).await.map_err(|e| anyhow!(e))?;
+    if account.owner != SQUADS_V4_PROGRAM {
+        return Err(anyhow!("Account {} is not a Squads v4 multisig", multisig));
+    }
+    let bytes = account
+        .data
+        .get(MULTISIG_TRANSACTION_INDEX_OFFSET..MULTISIG_TRANSACTION_INDEX_OFFSET + 8)
+        .ok_or_else(|| anyhow!("Multisig account {} is too short", multisig))?;
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+/// 把指令中的 payer 及其 ATA 替换为金库及金库的 ATA
+///
+/// `mints` 为交易涉及的 mint（输入、输出与 WSOL），两种 Token 程序的 ATA 都会替换。
+pub fn remap_to_vault(
+    instructions: &mut [Instruction],
+    payer: &Pubkey,
+    vault: &Pubkey,
+    mints: &[Pubkey],
+    use_seed: bool,
+) {
+    let mut mapping = HashMap::from([(*payer, *vault)]);
+    for mint in mints {
+        for token_program in [TOKEN_PROGRAM, TOKEN_PROGRAM_2022] {
+            for seed in [false, use_seed] {
+                let ata = |owner| {
+                    get_associated_token_address_with_program_id_fast_use_seed(
+                        owner,
+                        mint,
+                        &token_program,
+                        seed,
+                    )
+                };
+                mapping.insert(ata(payer), ata(vault));
+            }
+        }
+    }
+    for instruction in instructions.iter_mut() {
+        for meta in instruction.accounts.iter_mut() {
+            if let Some(target) = mapping.get(&meta.pubkey) {
+                meta.pubkey = *target;
+            }
+        }
+    }
+}
+
+/// 按 Squads `TransactionMessage` 格式编码内层交易（长度前缀为 u8 / u16 的紧凑数组）
+pub fn encode_transaction_message(vault: &Pubkey, instructions: &[Instruction]) -> Result<Vec<u8>> {
+    let message = Message::new(instructions, Some(vault));
+    let header = message.header;
+    let keys = &message.account_keys;
+    let num_signers = header.num_required_signatures;
+    let small_len = |len: usize, what: &str| {
+        u8::try_from(len).map_err(|_| anyhow!("Too many {} for a vault transaction: {}", what, len))
+    };
+
+    let mut out = vec![
+        num_signers,
+        num_signers - header.num_readonly_signed_accounts,
+        (keys.len() - num_signers as usize - header.num_readonly_unsigned_accounts as usize) as u8,
+    ];
+    out.push(small_len(keys.len(), "accounts")?);
+    for key in keys {
+        out.extend_from_slice(key.as_ref());
+    }
+    out.push(small_len(message.instructions.len(), "instructions")?);
+    for ix in &message.instructions {
+        out.push(ix.program_id_index);
+        out.push(small_len(ix.accounts.len(), "instruction accounts")?);
+        out.extend_from_slice(&ix.accounts);
+        let data_len = u16::try_from(ix.data.len())
+            .map_err(|_| anyhow!("Instruction data too large: {}", ix.data.len()))?;
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.extend_from_slice(&ix.data);
+    }
+    // 不使用地址查找表
+    out.push(0);
+    Ok(out)
+}
+
+fn discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{}", name));
+    let mut out = [0u8; 8];
+    out.copy_from_slice(&hash[..8]);
+    out
+}
+
+fn instruction_data<T: BorshSerialize>(name: &str, args: &T) -> Result<Vec<u8>> {
+    let mut data = discriminator(name).to_vec();
+    args.serialize(&mut data)?;
+    Ok(data)
+}
+
+#[derive(BorshSerialize)]
+struct VaultTransactionCreateArgs {
+    vault_index: u8,
+    ephemeral_signers: u8,
+    transaction_message: Vec<u8>,
+    memo: Option<String>,
+}
+
+#[derive(BorshSerialize)]
+struct ProposalCreateArgs {
+    transaction_index: u64,
+    draft: bool,
+}
+
+#[derive(BorshSerialize)]
+struct ProposalVoteArgs {
+    memo: Option<String>,
+}
+
+/// 把内层指令包装为 Squads 提案指令（`member` 为多签成员，同时支付租金）
+///
+/// `transaction_index` 为新提案的序号，即 multisig 当前 `transaction_index + 1`。
+pub fn build_proposal_instructions(
+    config: &SquadsConfig,
+    member: &Pubkey,
+    transaction_index: u64,
+    inner_instructions: &[Instruction],
+) -> Result<Vec<Instruction>> {
+    let multisig = config.multisig;
+    let transaction = get_transaction_pda(&multisig, transaction_index);
+    let proposal = get_proposal_pda(&multisig, transaction_index);
+    let system_program = SYSTEM_PROGRAM;
+
+    let mut instructions = vec![
+        Instruction::new_with_bytes(
+            SQUADS_V4_PROGRAM,
+            &instruction_data(
+                "vault_transaction_create",
+                &VaultTransactionCreateArgs {
+                    vault_index: config.vault_index,
+                    ephemeral_signers: 0,
+                    transaction_message: encode_transaction_message(
+                        &config.vault(),
+                        inner_instructions,
+                    )?,
+                    memo: config.memo.clone(),
+                },
+            )?,
+            vec![
+                AccountMeta::new(multisig, false),
+                AccountMeta::new(transaction, false),
+                AccountMeta::new_readonly(*member, true),
+                AccountMeta::new(*member, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        ),
+        Instruction::new_with_bytes(
+            SQUADS_V4_PROGRAM,
+            &instruction_data(
+                "proposal_create",
+                &ProposalCreateArgs { transaction_index, draft: false },
+            )?,
+            vec![
+                AccountMeta::new_readonly(multisig, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new_readonly(*member, true),
+                AccountMeta::new(*member, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        ),
+    ];
+    if config.approve {
+        instructions.push(Instruction::new_with_bytes(
+            SQUADS_V4_PROGRAM,
+            &instruction_data("proposal_approve", &ProposalVoteArgs { memo: None })?,
+            vec![
+                AccountMeta::new_readonly(multisig, false),
+                AccountMeta::new(*member, true),
+                AccountMeta::new(proposal, false),
+            ],
+        ));
+    }
+    Ok(instructions)
+}
+
+/// 多签模式下执行前的指令转换：替换交易主体并包装为提案
+///
+/// `mints` 为交易的输入 / 输出 mint，WSOL 会自动加入。
+pub async fn wrap_in_proposal<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    config: &SquadsConfig,
+    payer: &Pubkey,
+    mints: &[Pubkey],
+    use_seed: bool,
+    mut instructions: Vec<Instruction>,
+) -> Result<Vec<Instruction>> {
+    let transaction_index = fetch_transaction_index(rpc, &config.multisig).await? + 1;
+    let mut mints = mints.to_vec();
+    mints.push(WSOL_TOKEN_ACCOUNT);
+    remap_to_vault(&mut instructions, payer, &config.vault(), &mints, use_seed);
+    build_proposal_instructions(config, payer, transaction_index, &instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_swap_into_proposal() {
+        let (payer, multisig, mint, pool) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let config = SquadsConfig::new(multisig);
+        let vault = config.vault();
+        let payer_ata = get_associated_token_address_with_program_id_fast_use_seed(
+            &payer,
+            &mint,
+            &TOKEN_PROGRAM,
+            false,
+        );
+        let mut swap = vec![Instruction::new_with_bytes(
+            pool,
+            &[9; 4],
+            vec![AccountMeta::new(payer, true), AccountMeta::new(payer_ata, false)],
+        )];
+        remap_to_vault(&mut swap, &payer, &vault, &[mint], false);
+        assert_eq!(swap[0].accounts[0].pubkey, vault);
+        assert_eq!(
+            swap[0].accounts[1].pubkey,
+            get_associated_token_address_with_program_id_fast_use_seed(
+                &vault,
+                &mint,
+                &TOKEN_PROGRAM,
+                false
+            )
+        );
+
+        let message = encode_transaction_message(&vault, &swap).unwrap();
+        // 1 个签名者（金库，可写）、1 个可写非签名者（ATA）、3 个账户
+        assert_eq!(&message[..4], &[1, 1, 1, 3]);
+        assert_eq!(&message[4..36], vault.as_ref());
+
+        let instructions = build_proposal_instructions(&config, &payer, 5, &swap).unwrap();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].data[..8], discriminator("vault_transaction_create"));
+        assert_eq!(instructions[1].accounts[1].pubkey, get_proposal_pda(&multisig, 5));
+        let without_vote = config.with_approve(false);
+        assert_eq!(build_proposal_instructions(&without_vote, &payer, 5, &swap).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,105 @@
+//! Durable nonce 账户的创建与关闭
+//!
+//! [`TradingClient::create_nonce_accounts`] 一次调用完成创建、初始化（authority 为 payer）并登记到
+//! [`nonce_cache`]，之后即可用于多 SWQOS 并发买入等需要 durable nonce 的场景；
+//! [`TradingClient::close_nonce_account`] 取回全部余额并从缓存中移除。
+
+use crate::TradingClient;
+use crate::common::nonce_cache::{DurableNonceInfo, nonce_cache};
+use crate::common::rent_cache;
+use anyhow::{Result, anyhow};
+use solana_nonce::state::State;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction::{create_nonce_account, withdraw_nonce_account};
+
+impl TradingClient {
+    /// Create `count` nonce accounts funded with `lamports` each, authorized to the payer
+    ///
+    /// 每个账户一笔交易（创建 + 初始化），确认后读取初始 nonce 并登记到 [`nonce_cache`]。
+    /// `lamports` 不能低于 nonce 账户的免租金额。
+    ///
+    /// # Returns
+    /// Returns the nonce info of the created accounts, in creation order
+    pub async fn create_nonce_accounts(
+        &self,
+        count: usize,
+        lamports: u64,
+    ) -> Result<Vec<DurableNonceInfo>> {
+        let rent = match rent_cache::get(State::size()) {
+            Some(rent) => rent,
+            None => self.rpc.get_minimum_balance_for_rent_exemption(State::size()).await?,
+        };
+        if lamports < rent {
+            return Err(anyhow!(
+                "Nonce account funding {} is below rent exemption {}",
+                lamports,
+                rent
+            ));
+        }
+        let payer = self.payer.pubkey();
+        let balance = self.rpc.get_balance(&payer).await?;
+        let required = lamports.saturating_mul(count as u64);
+        if balance < required {
+            return Err(anyhow!("Insufficient SOL balance: {} < {}", balance, required));
+        }
+
+        let mut created = Vec::with_capacity(count);
+        for _ in 0..count {
+            let nonce_keypair = Keypair::new();
+            let nonce_account = nonce_keypair.pubkey();
+            let instructions = create_nonce_account(&payer, &nonce_account, &payer, lamports);
+            let blockhash = self.rpc.get_latest_blockhash().await?;
+            let tx = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer),
+                &[self.payer.as_ref(), &nonce_keypair],
+                blockhash,
+            );
+            self.rpc.send_and_confirm_transaction(&tx).await?;
+
+            let info = match nonce_cache().refresh(&self.rpc, nonce_account).await {
+                Some(info) => info,
+                None => {
+                    // 账户已创建但 RPC 尚未返回初始化状态：先登记账户，使用前再刷新
+                    let info = DurableNonceInfo {
+                        nonce_account: Some(nonce_account),
+                        current_nonce: None,
+                    };
+                    nonce_cache().insert(info.clone());
+                    info
+                },
+            };
+            created.push(info);
+        }
+        Ok(created)
+    }
+
+    /// Withdraw the full balance of a payer-authorized nonce account back to the payer, closing it
+    ///
+    /// 账户同时从 [`nonce_cache`] 中移除。
+    ///
+    /// # Returns
+    /// Returns the signature of the withdraw transaction
+    pub async fn close_nonce_account(&self, nonce_account: &Pubkey) -> Result<Signature> {
+        let payer = self.payer.pubkey();
+        let balance = self.rpc.get_balance(nonce_account).await?;
+        if balance == 0 {
+            nonce_cache().remove(nonce_account);
+            return Err(anyhow!("Nonce account {} does not exist", nonce_account));
+        }
+        let ix = withdraw_nonce_account(nonce_account, &payer, &payer, balance);
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer),
+            &[self.payer.as_ref()],
+            blockhash,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&tx).await?;
+        nonce_cache().remove(nonce_account);
+        Ok(signature)
+    }
+}
@@ -0,0 +1,368 @@
+//! Webhook 通知
+//!
+//! 将交易生命周期事件（签名、执行结果、错误）以 JSON 形式 POST 到用户配置的 URL，
+//! 便于接入 Discord / Telegram / Slack 等桥接服务。
+//!
+//! - 事件进入有界队列，由后台任务发送，不阻塞交易热路径；队列满时丢弃并记录日志；
+//! - 发送失败（网络错误或非 2xx）按指数退避重试；
+//! - 配置 `secret` 后，请求头 `X-Signature-256: sha256=<hex>` 为
+//!   `HMAC-SHA256(secret, "<timestamp>.<body>")`，`X-Timestamp` 为毫秒时间戳，接收方可据此验签并防重放。
+
+use crate::swqos::TradeType;
+use crate::swqos::common::TradeError;
+use crate::trading::factory::DexType;
+use crate::trading::fill::{DuplicateExecution, SlippageViolation};
+use crate::trading::{CallbackContext, TradeMetadata, TransactionLifecycleCallback};
+use anyhow::{Result, anyhow};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Webhook 配置
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// 接收事件的 URL
+    pub url: String,
+    /// HMAC 签名密钥（可选）
+    pub secret: Option<String>,
+    /// 最大重试次数（不含首次发送）
+    pub max_retries: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 重试等待时间上限
+    pub max_backoff: Duration,
+    /// 单次请求超时
+    pub timeout: Duration,
+    /// 待发送事件队列容量
+    pub queue_capacity: usize,
+}
+
+impl WebhookConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            timeout: Duration::from_secs(5),
+            queue_capacity: 1024,
+        }
+    }
+
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    pub fn with_retry(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity;
+        self
+    }
+
+    /// 第 `attempt` 次重试前的等待时间（attempt 从 1 开始）
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// 交易事件
+#[derive(Debug, Clone)]
+pub enum TradeEvent {
+    /// 交易已签名（发送前）
+    Signed {
+        signature: String,
+        swqos_type: String,
+        trade_type: TradeType,
+        tip_amount: f64,
+        timestamp_ns: u64,
+        metadata: Option<Arc<TradeMetadata>>,
+    },
+    /// 买入/卖出执行完成（可能成功也可能失败）
+    Executed {
+        trade_type: TradeType,
+        dex_type: DexType,
+        mint: Pubkey,
+        success: bool,
+        signatures: Vec<Signature>,
+        error: Option<TradeError>,
+        metadata: Option<Arc<TradeMetadata>>,
+    },
+    /// 买入/卖出在发送前即失败（参数错误、构建失败等）
+    Error {
+        trade_type: TradeType,
+        dex_type: DexType,
+        mint: Pubkey,
+        message: String,
+        metadata: Option<Arc<TradeMetadata>>,
+    },
+    /// 成交回查发现实际输出低于报价的最小输出
+    SlippageViolation(SlippageViolation),
+    /// 成交回查发现同一笔交易重复上链
+    DuplicateExecution(DuplicateExecution),
+}
+
+impl TradeEvent {
+    /// 事件名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            TradeEvent::Signed { .. } => "signed",
+            TradeEvent::Executed { success: true, .. } => "executed",
+            TradeEvent::Executed { success: false, .. } => "failed",
+            TradeEvent::Error { .. } => "error",
+            TradeEvent::SlippageViolation(_) => "slippage_violation",
+            TradeEvent::DuplicateExecution(_) => "duplicate_execution",
+        }
+    }
+
+    /// 事件的 JSON 表示
+    pub fn to_json(&self) -> serde_json::Value {
+        let data = match self {
+            TradeEvent::Signed {
+                signature,
+                swqos_type,
+                trade_type,
+                tip_amount,
+                timestamp_ns,
+                metadata,
+            } => {
+                serde_json::json!({
+                    "signature": signature,
+                    "swqos_type": swqos_type,
+                    "trade_type": trade_type.to_string(),
+                    "tip_amount": tip_amount,
+                    "timestamp_ns": timestamp_ns,
+                    "metadata": metadata.as_ref().map(|m| m.labels_json()),
+                })
+            },
+            TradeEvent::Executed {
+                trade_type,
+                dex_type,
+                mint,
+                success,
+                signatures,
+                error,
+                metadata,
+            } => {
+                serde_json::json!({
+                    "trade_type": trade_type.to_string(),
+                    "dex_type": format!("{:?}", dex_type),
+                    "mint": mint.to_string(),
+                    "success": success,
+                    "signatures": signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                    "error": error.as_ref().map(|e| serde_json::json!({
+                        "code": e.code,
+                        "message": e.message,
+                        "instruction": e.instruction,
+                        "program_error": e.program_error.as_ref().map(|p| p.name),
+                        "logs": e.logs,
+                    })),
+                    "metadata": metadata.as_ref().map(|m| m.labels_json()),
+                })
+            },
+            TradeEvent::Error { trade_type, dex_type, mint, message, metadata } => {
+                serde_json::json!({
+                    "trade_type": trade_type.to_string(),
+                    "dex_type": format!("{:?}", dex_type),
+                    "mint": mint.to_string(),
+                    "message": message,
+                    "metadata": metadata.as_ref().map(|m| m.labels_json()),
+                })
+            },
+            TradeEvent::SlippageViolation(violation) => violation.to_json(),
+            TradeEvent::DuplicateExecution(duplicate) => duplicate.to_json(),
+        };
+        serde_json::json!({ "event": self.name(), "data": data })
+    }
+}
+
+/// Webhook 通知器
+///
+/// 同时实现了 [`TransactionLifecycleCallback`]，可直接作为 `on_transaction_signed` 使用。
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    tx: mpsc::Sender<TradeEvent>,
+}
+
+impl WebhookNotifier {
+    /// 创建通知器并启动后台发送任务（需在 tokio 运行时中调用）
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        if config.url.is_empty() {
+            return Err(anyhow!("Webhook url is empty"));
+        }
+        let client = reqwest::Client::builder().timeout(config.timeout).build()?;
+        let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+        tokio::spawn(run_worker(client, config, rx));
+        Ok(Self { tx })
+    }
+
+    /// 投递事件（非阻塞，队列满时丢弃）
+    pub fn notify(&self, event: TradeEvent) {
+        if let Err(e) = self.tx.try_send(event) {
+            log::warn!("webhook queue full or closed, dropping event: {}", e);
+        }
+    }
+
+    /// 投递一次买入/卖出的执行结果
+    pub(crate) fn notify_trade(
+        &self,
+        trade_type: TradeType,
+        dex_type: DexType,
+        mint: Pubkey,
+        metadata: Option<Arc<TradeMetadata>>,
+        result: &Result<(bool, Vec<Signature>, Option<TradeError>)>,
+    ) {
+        let event = match result {
+            Ok((success, signatures, error)) => TradeEvent::Executed {
+                trade_type,
+                dex_type,
+                mint,
+                success: *success,
+                signatures: signatures.clone(),
+                error: error.clone(),
+                metadata,
+            },
+            Err(e) => {
+                TradeEvent::Error { trade_type, dex_type, mint, message: e.to_string(), metadata }
+            },
+        };
+        self.notify(event);
+    }
+}
+
+impl TransactionLifecycleCallback for WebhookNotifier {
+    fn on_transaction_signed(
+        &self,
+        context: CallbackContext,
+    ) -> futures::future::BoxFuture<'static, Result<()>> {
+        self.notify(TradeEvent::Signed {
+            signature: context.signature,
+            swqos_type: context.swqos_type.to_string(),
+            trade_type: context.trade_type,
+            tip_amount: context.tip_amount,
+            timestamp_ns: context.timestamp_ns,
+            metadata: context.details.as_ref().and_then(|d| d.metadata.clone()),
+        });
+        Box::pin(async { Ok(()) })
+    }
+}
+
+async fn run_worker(
+    client: reqwest::Client,
+    config: WebhookConfig,
+    mut rx: mpsc::Receiver<TradeEvent>,
+) {
+    while let Some(event) = rx.recv().await {
+        let body = event.to_json().to_string();
+        if let Err(e) = deliver(&client, &config, &body).await {
+            log::warn!("webhook delivery of '{}' event failed: {}", event.name(), e);
+        }
+    }
+}
+
+async fn deliver(client: &reqwest::Client, config: &WebhookConfig, body: &str) -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        match send_once(client, config, body).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= config.max_retries => return Err(e),
+            Err(e) => {
+                attempt += 1;
+                log::debug!("webhook attempt {} failed: {}, retrying", attempt, e);
+                tokio::time::sleep(config.backoff(attempt)).await;
+            },
+        }
+    }
+}
+
+async fn send_once(client: &reqwest::Client, config: &WebhookConfig, body: &str) -> Result<()> {
+    let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+    let mut request = client
+        .post(&config.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Timestamp", &timestamp);
+    if let Some(secret) = &config.secret {
+        request = request.header("X-Signature-256", sign_payload(secret, &timestamp, body));
+    }
+    let response = request.body(body.to_string()).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("webhook returned status {}", response.status()));
+    }
+    Ok(())
+}
+
+/// `sha256=<hex(HMAC-SHA256(secret, "<timestamp>.<body>"))>`
+pub fn sign_payload(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let config = WebhookConfig::new("http://localhost")
+            .with_retry(5, Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(350));
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(350));
+        assert_eq!(config.backoff(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let sig = sign_payload("secret", "1700000000000", r#"{"event":"signed"}"#);
+        assert_eq!(sig, "sha256=5525af8ab583cd6de004602e07a47fab6191722dd73c0721773d6aabfe37cfd6");
+    }
+
+    #[test]
+    fn test_event_json() {
+        let event = TradeEvent::Executed {
+            trade_type: TradeType::Sell,
+            dex_type: DexType::PumpSwap,
+            mint: Pubkey::default(),
+            success: false,
+            signatures: vec![Signature::default()],
+            error: Some(TradeError {
+                code: 6004,
+                message: "slippage".into(),
+                instruction: None,
+                program_error: None,
+                logs: Vec::new(),
+            }),
+            metadata: Some(Arc::new(TradeMetadata::new().with_label("strategy", "dca"))),
+        };
+        let json = event.to_json();
+        assert_eq!(json["event"], "failed");
+        assert_eq!(json["data"]["error"]["code"], 6004);
+        assert_eq!(json["data"]["metadata"]["strategy"], "dca");
+        assert_eq!(json["data"]["signatures"].as_array().unwrap().len(), 1);
+    }
+}
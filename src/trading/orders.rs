@@ -0,0 +1,222 @@
+//! 在途订单登记
+//!
+//! 基于 durable nonce 的交易在上链前可以被「替换」或「取消」：
+//! - 替换：用同一个 nonce 重新构建并发送新交易，任意一笔上链后另一笔自动失效；
+//! - 取消：发送一笔仅推进 nonce 的交易，使原交易失效。
+//!
+//! 本模块只负责记录订单与 nonce 的对应关系，具体的发送逻辑见 `TradingClient::place_order`
+//! / `TradingClient::replace_trade` / `TradingClient::cancel_order`。
+//!
+//! 登记表不会无限增长：每次登记新订单时清理已替换 / 已取消的订单，以及登记超过 TTL
+//! （默认 [`DEFAULT_ORDER_TTL`]）的订单；长时间未登记新订单时可调用 [`OrderRegistry::prune`]。
+
+use crate::common::nonce_cache::DurableNonceInfo;
+use crate::swqos::TradeType;
+use crate::{TradeBuyParams, TradeSellParams};
+use dashmap::DashMap;
+use solana_sdk::signature::Signature;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 订单在登记表中保留的默认时长
+pub const DEFAULT_ORDER_TTL: Duration = Duration::from_secs(600);
+
+/// 订单 ID（进程内唯一）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OrderId(pub u64);
+
+impl std::fmt::Display for OrderId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "order-{}", self.0)
+    }
+}
+
+/// 可替换的交易订单
+#[derive(Clone)]
+pub enum TradeOrder {
+    Buy(TradeBuyParams),
+    Sell(TradeSellParams),
+}
+
+impl TradeOrder {
+    pub fn trade_type(&self) -> TradeType {
+        match self {
+            TradeOrder::Buy(_) => TradeType::Buy,
+            TradeOrder::Sell(_) => TradeType::Sell,
+        }
+    }
+
+    pub fn durable_nonce(&self) -> Option<&DurableNonceInfo> {
+        match self {
+            TradeOrder::Buy(params) => params.durable_nonce.as_ref(),
+            TradeOrder::Sell(params) => params.durable_nonce.as_ref(),
+        }
+    }
+
+    pub(crate) fn set_durable_nonce(&mut self, durable_nonce: DurableNonceInfo) {
+        match self {
+            TradeOrder::Buy(params) => params.durable_nonce = Some(durable_nonce),
+            TradeOrder::Sell(params) => params.durable_nonce = Some(durable_nonce),
+        }
+    }
+}
+
+/// 订单状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    /// 已发送，尚未确认上链
+    Pending,
+    /// 已被新订单替换
+    Replaced(OrderId),
+    /// 已取消（nonce 已推进）
+    Cancelled,
+}
+
+/// 在途订单记录
+#[derive(Clone)]
+pub struct InFlightOrder {
+    pub id: OrderId,
+    pub trade_type: TradeType,
+    pub durable_nonce: DurableNonceInfo,
+    pub signatures: Vec<Signature>,
+    pub status: OrderStatus,
+    pub created_at: Instant,
+}
+
+/// 在途订单登记表
+pub struct OrderRegistry {
+    next_id: AtomicU64,
+    orders: DashMap<OrderId, InFlightOrder>,
+    ttl: Duration,
+}
+
+impl Default for OrderRegistry {
+    fn default() -> Self {
+        Self::with_ttl(DEFAULT_ORDER_TTL)
+    }
+}
+
+impl OrderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定订单保留时长：超过后即使仍为 Pending 也不再跟踪，无法再替换或取消
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self { next_id: AtomicU64::new(0), orders: DashMap::new(), ttl }
+    }
+
+    /// 清理已替换 / 已取消以及超过 TTL 的订单，返回清理数量
+    pub fn prune(&self) -> usize {
+        let before = self.orders.len();
+        self.orders.retain(|_, order| {
+            order.status == OrderStatus::Pending && order.created_at.elapsed() < self.ttl
+        });
+        before.saturating_sub(self.orders.len())
+    }
+
+    /// 登记新订单并返回其 ID（登记前先清理终态与过期订单）
+    pub fn insert(
+        &self,
+        trade_type: TradeType,
+        durable_nonce: DurableNonceInfo,
+        signatures: Vec<Signature>,
+    ) -> OrderId {
+        self.prune();
+        let id = OrderId(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        self.orders.insert(
+            id,
+            InFlightOrder {
+                id,
+                trade_type,
+                durable_nonce,
+                signatures,
+                status: OrderStatus::Pending,
+                created_at: Instant::now(),
+            },
+        );
+        id
+    }
+
+    pub fn get(&self, id: OrderId) -> Option<InFlightOrder> {
+        self.orders.get(&id).map(|o| o.clone())
+    }
+
+    pub fn set_status(&self, id: OrderId, status: OrderStatus) {
+        if let Some(mut order) = self.orders.get_mut(&id) {
+            order.status = status;
+        }
+    }
+
+    /// 移除订单（上链或不再需要跟踪时调用）
+    pub fn remove(&self, id: OrderId) -> Option<InFlightOrder> {
+        self.orders.remove(&id).map(|(_, o)| o)
+    }
+
+    /// 所有仍处于 Pending 的订单
+    pub fn pending(&self) -> Vec<InFlightOrder> {
+        self.orders
+            .iter()
+            .filter(|o| o.status == OrderStatus::Pending)
+            .map(|o| o.clone())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{hash::Hash, pubkey::Pubkey};
+
+    fn nonce() -> DurableNonceInfo {
+        DurableNonceInfo {
+            nonce_account: Some(Pubkey::new_unique()),
+            current_nonce: Some(Hash::default()),
+        }
+    }
+
+    #[test]
+    fn test_registry_lifecycle() {
+        let registry = OrderRegistry::new();
+        let a = registry.insert(TradeType::Buy, nonce(), vec![Signature::default()]);
+        let b = registry.insert(TradeType::Buy, nonce(), vec![]);
+        assert_ne!(a, b);
+        assert_eq!(registry.pending().len(), 2);
+
+        registry.set_status(a, OrderStatus::Replaced(b));
+        assert_eq!(registry.get(a).unwrap().status, OrderStatus::Replaced(b));
+        assert_eq!(registry.pending().len(), 1);
+
+        assert!(registry.remove(b).is_some());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_prune_terminal_and_expired_orders() {
+        let registry = OrderRegistry::new();
+        let a = registry.insert(TradeType::Buy, nonce(), vec![]);
+        let b = registry.insert(TradeType::Sell, nonce(), vec![]);
+        registry.set_status(a, OrderStatus::Cancelled);
+        registry.set_status(b, OrderStatus::Replaced(a));
+        let c = registry.insert(TradeType::Buy, nonce(), vec![]);
+        assert!(registry.get(a).is_none() && registry.get(b).is_none());
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.prune(), 0);
+        assert!(registry.get(c).is_some());
+
+        let registry = OrderRegistry::with_ttl(Duration::ZERO);
+        registry.insert(TradeType::Buy, nonce(), vec![]);
+        registry.insert(TradeType::Buy, nonce(), vec![]);
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.prune(), 1);
+        assert!(registry.is_empty());
+    }
+}
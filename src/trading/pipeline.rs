@@ -0,0 +1,575 @@
+//! 吞吐模式：构建 → 签名 → 发送流水线
+//!
+//! 普通的 `buy` / `sell` 在同一个 future 中依次完成参数补全与检查、指令构建、签名和发送，
+//! 交易频率很高时，下一笔交易要等上一笔发送（或确认）完成才能开始构建。
+//! [`TradePipeline`] 把这三步拆成三组后台任务，以有界通道相连，构建下一笔与发送上一笔重叠进行：
+//!
+//! 1. 构建任务：参数补全、发送前检查（与 `buy` / `sell` 相同）、构建协议指令；
+//! 2. 签名任务：按 gas 策略为每个 provider 组装交易并签名；
+//! 3. 发送任务：并行提交到各 provider（与 [`TradingClient::send_transaction`] 使用相同的提交逻辑，
+//!    包括任一上链后取消其余确认轮询、调用方取消、交易日志、手续费账本与耗时记录）。
+//!
+//! 下游通道满时上游任务等待（背压），[`TradePipeline::metrics`] 返回各阶段的排队数、
+//! 因通道已满而等待的次数与累计时长，可据此调整各阶段的任务数。
+//!
+//! 等待确认的交易会占用发送任务直到确认完成，此时应相应增加 `senders`。
+//! 发送前模拟（`preflight`）在签名任务中执行，策略与 `buy` / `sell` 相同，但模拟失败时不重新加载参数。
+//! 吞吐模式不执行 `on_transaction_signed` 回调、不经过提交队列；不支持模拟交易与多签模式。
+
+use crate::common::cancellation::{cancellable, cancelled_error};
+use crate::swqos::TradeType;
+use crate::swqos::common::TradeError;
+use crate::trading::TradeFactory;
+use crate::trading::common::build_transaction;
+use crate::trading::core::async_executor::{
+    LandingMode, SignedSubmission, plan_providers, send_parallel,
+};
+use crate::trading::core::executor::simulate_preflight;
+use crate::trading::core::params::SwapParams;
+use crate::trading::factory::DexType;
+use crate::trading::metadata::TradeMetadata;
+use crate::trading::store::Journal;
+use crate::{TradeBuyParams, TradeSellParams, TradingClient};
+use anyhow::{Result, anyhow};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// 流水线返回的结果（与 [`TradingClient::buy`] 相同）
+pub type PipelineResult = Result<(bool, Vec<Signature>, Option<TradeError>)>;
+
+/// 流水线配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineConfig {
+    /// 构建任务数（默认：2）
+    pub builders: usize,
+    /// 签名任务数（默认：2）
+    pub signers: usize,
+    /// 发送任务数（默认：4）
+    pub senders: usize,
+    /// 各阶段之间通道的容量（默认：64）
+    pub channel_capacity: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self { builders: 2, signers: 2, senders: 4, channel_capacity: 64 }
+    }
+}
+
+impl PipelineConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_workers(mut self, builders: usize, signers: usize, senders: usize) -> Self {
+        self.builders = builders;
+        self.signers = signers;
+        self.senders = senders;
+        self
+    }
+
+    pub fn with_channel_capacity(mut self, channel_capacity: usize) -> Self {
+        self.channel_capacity = channel_capacity;
+        self
+    }
+}
+
+/// 提交到流水线的交易
+#[derive(Clone)]
+pub enum PipelineTrade {
+    Buy(TradeBuyParams),
+    Sell(TradeSellParams),
+}
+
+/// 单个阶段的背压统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageMetrics {
+    /// 等待该阶段处理的交易数（含正在等待进入通道的）
+    pub queued: usize,
+    /// 该阶段已处理的交易数
+    pub processed: u64,
+    /// 提交到该阶段时因通道已满而等待的次数
+    pub blocked: u64,
+    /// 因通道已满累计等待的时长
+    pub blocked_time: Duration,
+}
+
+/// 各阶段的背压统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineMetrics {
+    pub build: StageMetrics,
+    pub sign: StageMetrics,
+    pub send: StageMetrics,
+}
+
+#[derive(Default)]
+struct StageCounters {
+    queued: AtomicUsize,
+    processed: AtomicU64,
+    blocked: AtomicU64,
+    blocked_nanos: AtomicU64,
+}
+
+impl StageCounters {
+    fn snapshot(&self) -> StageMetrics {
+        StageMetrics {
+            queued: self.queued.load(Ordering::Relaxed),
+            processed: self.processed.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+            blocked_time: Duration::from_nanos(self.blocked_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct PipelineCounters {
+    build: StageCounters,
+    sign: StageCounters,
+    send: StageCounters,
+}
+
+/// 随交易在各阶段间传递的上下文，任一阶段结束交易时通过它回复结果
+struct JobContext {
+    trade_type: TradeType,
+    dex_type: DexType,
+    mint: Pubkey,
+    metadata: Option<Arc<TradeMetadata>>,
+    reply: oneshot::Sender<PipelineResult>,
+}
+
+impl JobContext {
+    fn finish(self, client: &TradingClient, result: PipelineResult) {
+        if let Some(notifier) = &client.notifier {
+            notifier.notify_trade(
+                self.trade_type,
+                self.dex_type,
+                self.mint,
+                self.metadata,
+                &result,
+            );
+        }
+        let _ = self.reply.send(result);
+    }
+}
+
+type Item<T> = (JobContext, T);
+type SharedReceiver<T> = Arc<Mutex<mpsc::Receiver<Item<T>>>>;
+
+/// 构建完成、待签名的交易
+struct BuiltTrade {
+    params: SwapParams,
+    instructions: Vec<Instruction>,
+    protocol_name: &'static str,
+    is_buy: bool,
+}
+
+/// 签名完成、待发送的交易
+struct SignedTrade {
+    params: SwapParams,
+    submissions: Vec<SignedSubmission>,
+    trade_type: TradeType,
+}
+
+/// 提交到下一阶段；通道满时等待并记录背压，通道已关闭时交还条目
+async fn push<T>(
+    output: &mpsc::Sender<Item<T>>,
+    counters: &StageCounters,
+    item: Item<T>,
+) -> Result<(), Item<T>> {
+    counters.queued.fetch_add(1, Ordering::Relaxed);
+    let item = match output.try_send(item) {
+        Ok(()) => return Ok(()),
+        Err(TrySendError::Full(item)) => item,
+        Err(TrySendError::Closed(item)) => {
+            counters.queued.fetch_sub(1, Ordering::Relaxed);
+            return Err(item);
+        },
+    };
+    counters.blocked.fetch_add(1, Ordering::Relaxed);
+    let start = Instant::now();
+    let result = output.send(item).await.map_err(|e| e.0);
+    counters
+        .blocked_nanos
+        .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    if result.is_err() {
+        counters.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+    result
+}
+
+/// 从本阶段的共享通道取出下一笔交易；通道关闭且为空时返回 `None`
+async fn pull<T>(input: &SharedReceiver<T>, counters: &StageCounters) -> Option<Item<T>> {
+    let item = input.lock().await.recv().await;
+    if item.is_some() {
+        counters.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+    item
+}
+
+/// 交给下一阶段；流水线已关闭时直接结束该交易
+async fn forward<T>(
+    client: &TradingClient,
+    output: &mpsc::Sender<Item<T>>,
+    counters: &StageCounters,
+    item: Item<T>,
+) {
+    if let Err((context, _)) = push(output, counters, item).await {
+        context.finish(client, Err(anyhow!("Trade pipeline is shut down")));
+    }
+}
+
+/// 吞吐模式流水线（见模块文档），由 [`TradingClient::start_pipeline`] 创建
+pub struct TradePipeline {
+    client: TradingClient,
+    input: mpsc::Sender<Item<PipelineTrade>>,
+    counters: Arc<PipelineCounters>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl TradePipeline {
+    /// 提交一笔交易，构建通道满时等待；返回的接收端在交易完成或被拒绝时得到结果
+    pub async fn submit(&self, trade: PipelineTrade) -> Result<oneshot::Receiver<PipelineResult>> {
+        let (simulate, trade_type, dex_type, mint, metadata) = match &trade {
+            PipelineTrade::Buy(params) => (
+                params.simulate,
+                TradeType::Buy,
+                params.dex_type.clone(),
+                params.mint,
+                params.metadata.clone(),
+            ),
+            PipelineTrade::Sell(params) => (
+                params.simulate,
+                TradeType::Sell,
+                params.dex_type.clone(),
+                params.mint,
+                params.metadata.clone(),
+            ),
+        };
+        if simulate {
+            return Err(anyhow!("Simulated trades are not supported in throughput mode"));
+        }
+        let (reply, receiver) = oneshot::channel();
+        let metadata = self.client.notifier.as_ref().and(metadata).map(Arc::new);
+        let context = JobContext { trade_type, dex_type, mint, metadata, reply };
+        push(&self.input, &self.counters.build, (context, trade))
+            .await
+            .map_err(|_| anyhow!("Trade pipeline is shut down"))?;
+        Ok(receiver)
+    }
+
+    /// 通过流水线买入并等待结果
+    pub async fn buy(&self, params: TradeBuyParams) -> PipelineResult {
+        let receiver = self.submit(PipelineTrade::Buy(params)).await?;
+        receiver.await.map_err(|_| anyhow!("Trade pipeline dropped the trade"))?
+    }
+
+    /// 通过流水线卖出并等待结果
+    pub async fn sell(&self, params: TradeSellParams) -> PipelineResult {
+        let receiver = self.submit(PipelineTrade::Sell(params)).await?;
+        receiver.await.map_err(|_| anyhow!("Trade pipeline dropped the trade"))?
+    }
+
+    /// 各阶段的背压统计
+    pub fn metrics(&self) -> PipelineMetrics {
+        PipelineMetrics {
+            build: self.counters.build.snapshot(),
+            sign: self.counters.sign.snapshot(),
+            send: self.counters.send.snapshot(),
+        }
+    }
+
+    /// 停止接收新交易，等待已提交的交易全部完成
+    pub async fn shutdown(self) {
+        drop(self.input);
+        for task in self.tasks {
+            let _ = task.await;
+        }
+    }
+}
+
+impl TradingClient {
+    /// 启动吞吐模式流水线（见 [`crate::trading::pipeline`]）
+    pub fn start_pipeline(&self, config: PipelineConfig) -> Result<TradePipeline> {
+        if self.multisig.is_some() {
+            return Err(anyhow!("Multisig mode is not supported in throughput mode"));
+        }
+        let counters = Arc::new(PipelineCounters::default());
+        let capacity = config.channel_capacity.max(1);
+        let (input, build_rx) = mpsc::channel(capacity);
+        let (sign_tx, sign_rx) = mpsc::channel(capacity);
+        let (send_tx, send_rx) = mpsc::channel(capacity);
+        let build_rx: SharedReceiver<PipelineTrade> = Arc::new(Mutex::new(build_rx));
+        let sign_rx: SharedReceiver<BuiltTrade> = Arc::new(Mutex::new(sign_rx));
+        let send_rx: SharedReceiver<SignedTrade> = Arc::new(Mutex::new(send_rx));
+
+        let mut tasks = Vec::new();
+        for _ in 0..config.builders.max(1) {
+            let (client, input, output, counters) =
+                (self.clone(), build_rx.clone(), sign_tx.clone(), counters.clone());
+            tasks.push(tokio::spawn(async move {
+                while let Some((context, trade)) = pull(&input, &counters.build).await {
+                    match build_stage(&client, trade).await {
+                        ControlFlow::Continue(built) => {
+                            forward(&client, &output, &counters.sign, (context, built)).await
+                        },
+                        ControlFlow::Break(result) => context.finish(&client, result),
+                    }
+                    counters.build.processed.fetch_add(1, Ordering::Relaxed);
+                }
+            }));
+        }
+        for _ in 0..config.signers.max(1) {
+            let (client, input, output, counters) =
+                (self.clone(), sign_rx.clone(), send_tx.clone(), counters.clone());
+            tasks.push(tokio::spawn(async move {
+                while let Some((context, built)) = pull(&input, &counters.sign).await {
+                    match sign_stage(built).await {
+                        ControlFlow::Continue(signed) => {
+                            forward(&client, &output, &counters.send, (context, signed)).await
+                        },
+                        ControlFlow::Break(result) => context.finish(&client, result),
+                    }
+                    counters.sign.processed.fetch_add(1, Ordering::Relaxed);
+                }
+            }));
+        }
+        drop((sign_tx, send_tx));
+        for _ in 0..config.senders.max(1) {
+            let (client, input, counters) = (self.clone(), send_rx.clone(), counters.clone());
+            tasks.push(tokio::spawn(async move {
+                while let Some((context, signed)) = pull(&input, &counters.send).await {
+                    let result = send_stage(signed).await;
+                    context.finish(&client, result);
+                    counters.send.processed.fetch_add(1, Ordering::Relaxed);
+                }
+            }));
+        }
+
+        Ok(TradePipeline { client: self.clone(), input, counters, tasks })
+    }
+}
+
+/// 构建阶段：参数补全与发送前检查，构建协议指令
+async fn build_stage(
+    client: &TradingClient,
+    trade: PipelineTrade,
+) -> ControlFlow<PipelineResult, BuiltTrade> {
+    let params = match swap_params(client, trade).await {
+        Ok(Ok(params)) => params,
+        Ok(Err(rejected)) => return ControlFlow::Break(Ok((false, vec![], Some(rejected)))),
+        Err(e) => return ControlFlow::Break(Err(e)),
+    };
+    let is_buy =
+        params.trade_type == TradeType::Buy || params.trade_type == TradeType::CreateAndBuy;
+    let dex_type = params.protocol_params.dex_type();
+    let executor = TradeFactory::create_executor(dex_type.clone());
+    let instructions = match executor.build_instructions(&params).await.and_then(|mut ix| {
+        crate::constants::registry::remap_instructions(&dex_type, &mut ix)?;
+        Ok(ix)
+    }) {
+        Ok(instructions) => instructions,
+        Err(e) => return ControlFlow::Break(Err(e)),
+    };
+    ControlFlow::Continue(BuiltTrade {
+        params,
+        instructions,
+        protocol_name: executor.protocol_name(),
+        is_buy,
+    })
+}
+
+/// 与 `buy` / `sell` 相同的发送前处理；`Ok(Err(_))` 表示交易被拒绝或已取消
+async fn swap_params(
+    client: &TradingClient,
+    trade: PipelineTrade,
+) -> Result<Result<SwapParams, TradeError>> {
+    match trade {
+        PipelineTrade::Buy(mut params) => {
+            let token = params.cancellation_token.clone();
+            let Some(rejected) =
+                cancellable(token.as_ref(), client.buy_preflight(&mut params)).await
+            else {
+                return Ok(Err(TradeError::cancelled("buy cancelled before submission")));
+            };
+            if let Some(error) = rejected? {
+                return Ok(Err(error));
+            }
+            Ok(Ok(client.build_buy_swap_params(params)?))
+        },
+        PipelineTrade::Sell(mut params) => {
+            let token = params.cancellation_token.clone();
+            let Some(rejected) =
+                cancellable(token.as_ref(), client.sell_preflight(&mut params)).await
+            else {
+                return Ok(Err(TradeError::cancelled("sell cancelled before submission")));
+            };
+            if let Some(error) = rejected? {
+                return Ok(Err(error));
+            }
+            Ok(Ok(client.build_sell_swap_params(params)?))
+        },
+    }
+}
+
+/// 签名阶段：按 gas 策略为每个 provider 组装交易并签名
+async fn sign_stage(built: BuiltTrade) -> ControlFlow<PipelineResult, SignedTrade> {
+    if let Some(error) = cancelled_error(built.params.cancellation_token.as_ref(), "before signing")
+    {
+        return ControlFlow::Break(Ok((false, vec![], Some(error))));
+    }
+    // 发送前模拟，避免为必然失败的交易支付小费
+    if let Err(e) = simulate_preflight(
+        &built.params,
+        built.instructions.clone(),
+        built.protocol_name,
+        built.is_buy,
+    )
+    .await
+    {
+        return ControlFlow::Break(Err(e));
+    }
+    let trade_type = if built.is_buy { TradeType::Buy } else { TradeType::Sell };
+    match sign_transactions(&built, trade_type).await {
+        Ok(submissions) => {
+            ControlFlow::Continue(SignedTrade { params: built.params, submissions, trade_type })
+        },
+        Err(e) => ControlFlow::Break(Err(e)),
+    }
+}
+
+/// 选出参与发送的 provider 与 gas 配置（与并行执行器共用 [`plan_providers`]）并逐一签名
+async fn sign_transactions(
+    built: &BuiltTrade,
+    trade_type: TradeType,
+) -> Result<Vec<SignedSubmission>> {
+    let params = &built.params;
+    let plans = plan_providers(
+        &params.swqos_clients,
+        &params.gas_fee_strategy,
+        trade_type,
+        built.is_buy || params.with_tip,
+        LandingMode::for_swap(built.is_buy, params.durable_nonce.is_some()),
+    )?;
+
+    let mut submissions = Vec::with_capacity(plans.len());
+    for plan in plans {
+        let gas = plan.config.2;
+        let tip = plan.tip();
+        let transaction = build_transaction(
+            params.payer.clone(),
+            params.fee_payer.clone(),
+            params.rpc.clone(),
+            gas.cu_limit,
+            gas.cu_price,
+            built.instructions.clone(),
+            params.address_lookup_table_account.clone(),
+            params.recent_blockhash,
+            params.middleware_manager.clone(),
+            built.protocol_name,
+            built.is_buy,
+            !plan.tip_accounts.is_empty(),
+            &plan.tip_accounts,
+            tip,
+            params.durable_nonce.clone(),
+            params.enable_jito_sandwich_protection.unwrap_or(false),
+            params.instruction_layout,
+            params.metadata.as_deref(),
+        )
+        .await?;
+        submissions.push(SignedSubmission {
+            swqos_client: plan.swqos_client,
+            transaction,
+            cu_limit: gas.cu_limit,
+            cu_price: gas.cu_price,
+            tip,
+        });
+    }
+    Ok(submissions)
+}
+
+/// 发送阶段：并行提交到各 provider
+async fn send_stage(signed: SignedTrade) -> PipelineResult {
+    let SignedTrade { params, submissions, trade_type } = signed;
+    if let Some(error) = cancelled_error(params.cancellation_token.as_ref(), "before submission") {
+        return Ok((false, vec![], Some(error)));
+    }
+    let (success, signatures, error) = send_parallel(
+        submissions,
+        trade_type,
+        params.wait_transaction_confirmed,
+        params.fee_ledger,
+        params
+            .trade_store
+            .map(|store| Journal::new(store, params.last_valid_block_height)),
+        params.confirm,
+        params.cancellation_token,
+        params.fanout.as_deref(),
+    )
+    .await?;
+    Ok((success, signatures, error.map(TradeError::from)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> (JobContext, oneshot::Receiver<PipelineResult>) {
+        let (reply, receiver) = oneshot::channel();
+        let context = JobContext {
+            trade_type: TradeType::Buy,
+            dex_type: DexType::PumpFun,
+            mint: Pubkey::new_unique(),
+            metadata: None,
+            reply,
+        };
+        (context, receiver)
+    }
+
+    #[tokio::test]
+    async fn test_push_records_back_pressure() {
+        let counters = Arc::new(StageCounters::default());
+        let (tx, rx) = mpsc::channel::<Item<u32>>(1);
+        let rx: SharedReceiver<u32> = Arc::new(Mutex::new(rx));
+
+        assert!(push(&tx, &counters, (context().0, 1)).await.is_ok());
+        assert_eq!(counters.snapshot().queued, 1);
+        assert_eq!(counters.snapshot().blocked, 0);
+
+        let blocked = tokio::spawn({
+            let (tx, counters) = (tx.clone(), counters.clone());
+            async move { push(&tx, &counters, (context().0, 2)).await.is_ok() }
+        });
+        while counters.snapshot().blocked == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(pull(&rx, &counters).await.map(|(_, n)| n), Some(1));
+        assert!(blocked.await.unwrap());
+        assert_eq!(pull(&rx, &counters).await.map(|(_, n)| n), Some(2));
+
+        let metrics = counters.snapshot();
+        assert_eq!(metrics.queued, 0);
+        assert_eq!(metrics.blocked, 1);
+
+        drop(tx);
+        assert!(pull(&rx, &counters).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_push_to_closed_stage_returns_item() {
+        let counters = StageCounters::default();
+        let (tx, rx) = mpsc::channel::<Item<u32>>(1);
+        drop(rx);
+        let (context, _receiver) = context();
+        let (_, value) = push(&tx, &counters, (context, 7)).await.unwrap_err();
+        assert_eq!(value, 7);
+        assert_eq!(counters.snapshot().queued, 0);
+    }
+}
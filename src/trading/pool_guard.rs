@@ -0,0 +1,310 @@
+//! 交易前池子状态检查
+//!
+//! 协议参数通常在交易前一段时间获取，期间池子可能已迁移、暂停交易或被抽干流动性，
+//! 按旧状态构建的交易注定在链上失败，优先费与小费却已支出。
+//! 配置 [`PoolStateCheckConfig`] 后，构建交易前通过一次 `getMultipleAccounts` 读取池子账户与储备 vault：
+//!
+//! - 参数中的储备一侧为 0、另一侧非 0（储备不一致）；两侧均为 0 视为未提供报价储备，不报错；
+//! - 池子账户或 vault 不存在，vault 余额为 0；
+//! - 池子不可交易：PumpFun bonding curve 已完成、AMM V4 非活跃、CPMM / CLMM 禁用了兑换、
+//!   CPMM 尚未开放、DAMM v2 已禁用；
+//! - CLMM / DAMM v2 的 sqrt price 超出价格区间；
+//! - 开启 `max_slot_lag` 时，RPC 返回的账户状态落后集群最新 slot 超过限制。
+//!
+//! 发现问题时返回 [`TradeError::POOL_STATE_INVALID`]，不发送交易。
+//! 默认值（`Pubkey::default()`）的池子 / vault 地址由指令构建器推导，此处跳过。
+
+use crate::TradingClient;
+use crate::common::SolanaRpcClient;
+use crate::instruction::utils::raydium_amm_v4::pool_status;
+use crate::instruction::utils::{
+    meteora_damm_v2_types, pumpfun, raydium_amm_v4_types, raydium_clmm_types, raydium_cpmm_types,
+};
+use crate::swqos::common::TradeError;
+use crate::trading::core::params::{DexParamEnum, SwapDirection};
+use anyhow::Result;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+/// CPMM `status` 中禁用兑换的位（官方 `PoolStatusBitIndex::Swap`）
+const CPMM_SWAP_DISABLED_BIT: u8 = 1 << 2;
+/// CLMM `status` 中禁用兑换的位（官方 `PoolStatusBitIndex::Swap`）
+const CLMM_SWAP_DISABLED_BIT: u8 = 1 << 4;
+/// SPL Token / Token-2022 账户中 `amount` 字段的偏移量
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// 交易前池子状态检查配置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStateCheckConfig {
+    /// RPC 返回的池子状态最多落后集群最新 slot 的数量（默认：None，不检查，省去一次 `getSlot`）
+    pub max_slot_lag: Option<u64>,
+}
+
+impl PoolStateCheckConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_slot_lag(mut self, max_slot_lag: u64) -> Self {
+        self.max_slot_lag = Some(max_slot_lag);
+        self
+    }
+}
+
+/// 交易 `mint` 时需要读取的池子账户与两个储备 vault（地址为默认值时为 None）
+fn pool_accounts(params: &DexParamEnum, mint: &Pubkey) -> (Option<Pubkey>, [Option<Pubkey>; 2]) {
+    let known = |address: Pubkey| (address != Pubkey::default()).then_some(address);
+    match params {
+        DexParamEnum::PumpFun(p) => {
+            let curve =
+                known(p.bonding_curve.account).or_else(|| pumpfun::get_bonding_curve_pda(mint));
+            (curve, [None, None])
+        },
+        DexParamEnum::PumpSwap(p) => {
+            (known(p.pool), [known(p.pool_base_token_account), known(p.pool_quote_token_account)])
+        },
+        DexParamEnum::Bonk(p) => (known(p.pool_state), [known(p.base_vault), known(p.quote_vault)]),
+        DexParamEnum::RaydiumCpmm(p) => {
+            (known(p.pool_state), [known(p.base_vault), known(p.quote_vault)])
+        },
+        DexParamEnum::RaydiumAmmV4(p) => (known(p.amm), [known(p.token_coin), known(p.token_pc)]),
+        DexParamEnum::RaydiumClmm(p) => {
+            (known(p.pool_state), [known(p.token0_vault), known(p.token1_vault)])
+        },
+        DexParamEnum::MeteoraDammV2(p) => {
+            (known(p.pool), [known(p.token_a_vault), known(p.token_b_vault)])
+        },
+    }
+}
+
+/// 检查参数中的储备是否一致（不发起 RPC）
+pub fn inspect_params(params: &DexParamEnum) -> Option<TradeError> {
+    let (a, b) = match params {
+        DexParamEnum::PumpFun(p) => (
+            p.bonding_curve.virtual_token_reserves as u128,
+            p.bonding_curve.virtual_sol_reserves as u128,
+        ),
+        DexParamEnum::PumpSwap(p) => {
+            (p.pool_base_token_reserves as u128, p.pool_quote_token_reserves as u128)
+        },
+        DexParamEnum::Bonk(p) => (p.virtual_base, p.virtual_quote),
+        DexParamEnum::RaydiumCpmm(p) => (p.base_reserve as u128, p.quote_reserve as u128),
+        DexParamEnum::RaydiumAmmV4(p) => (p.coin_reserve as u128, p.pc_reserve as u128),
+        DexParamEnum::RaydiumClmm(_) | DexParamEnum::MeteoraDammV2(_) => return None,
+    };
+    if (a == 0) != (b == 0) {
+        return Some(TradeError::pool_state_invalid(format!(
+            "{:?} params have inconsistent reserves ({} / {}): one side of the pool is empty",
+            params.dex_type(),
+            a,
+            b
+        )));
+    }
+    None
+}
+
+/// 检查池子账户数据中的交易状态与价格
+///
+/// `now` 为当前 Unix 时间（秒），用于判断 CPMM 池是否已开放。
+pub fn inspect_pool_account(params: &DexParamEnum, data: &[u8], now: u64) -> Option<TradeError> {
+    let dex_type = params.dex_type();
+    let invalid = |reason: String| {
+        Some(TradeError::pool_state_invalid(format!("{:?} pool {}", dex_type, reason)))
+    };
+    let undecodable = || invalid("account data could not be decoded".to_string());
+    match params {
+        DexParamEnum::PumpFun(_) => {
+            let Ok(curve) = pumpfun::decode_bonding_curve_account(data) else {
+                return undecodable();
+            };
+            if curve.complete {
+                return invalid("bonding curve is complete (migrated to PumpSwap)".to_string());
+            }
+            if curve.virtual_token_reserves == 0 || curve.virtual_sol_reserves == 0 {
+                return invalid("bonding curve has zero reserves".to_string());
+            }
+        },
+        DexParamEnum::RaydiumAmmV4(_) => {
+            let Some(amm) = raydium_amm_v4_types::amm_info_decode(data) else {
+                return undecodable();
+            };
+            if amm.status != pool_status::ACTIVE && amm.status != pool_status::SWAP_ONLY {
+                return invalid(format!("is not tradeable (status {})", amm.status));
+            }
+        },
+        DexParamEnum::RaydiumCpmm(_) => {
+            let Some(pool) = data.get(8..).and_then(raydium_cpmm_types::pool_state_decode) else {
+                return undecodable();
+            };
+            if pool.status & CPMM_SWAP_DISABLED_BIT != 0 {
+                return invalid(format!("has swaps disabled (status {:#b})", pool.status));
+            }
+            if pool.open_time > now {
+                return invalid(format!("does not open until {}", pool.open_time));
+            }
+        },
+        DexParamEnum::RaydiumClmm(_) => {
+            let Some(pool) = data.get(8..).and_then(raydium_clmm_types::pool_state_decode) else {
+                return undecodable();
+            };
+            if pool.status & CLMM_SWAP_DISABLED_BIT != 0 {
+                return invalid(format!("has swaps disabled (status {:#b})", pool.status));
+            }
+            if pool.sqrt_price_x64 < SwapDirection::MIN_SQRT_PRICE_X64
+                || pool.sqrt_price_x64 >= SwapDirection::MAX_SQRT_PRICE_X64
+            {
+                return invalid(format!("sqrt price {} is out of bounds", pool.sqrt_price_x64));
+            }
+            if pool.liquidity == 0 {
+                return invalid("has no active liquidity".to_string());
+            }
+        },
+        DexParamEnum::MeteoraDammV2(_) => {
+            let Some(pool) = meteora_damm_v2_types::pool_state_decode(data) else {
+                return undecodable();
+            };
+            if pool.pool_status != 0 {
+                return invalid(format!("is disabled (status {})", pool.pool_status));
+            }
+            if pool.sqrt_price < pool.sqrt_min_price || pool.sqrt_price > pool.sqrt_max_price {
+                return invalid(format!(
+                    "sqrt price {} is outside [{}, {}]",
+                    pool.sqrt_price, pool.sqrt_min_price, pool.sqrt_max_price
+                ));
+            }
+            if pool.liquidity == 0 {
+                return invalid("has no active liquidity".to_string());
+            }
+        },
+        // PumpSwap / Bonk 池子账户没有可靠的交易状态字段，只检查 vault
+        DexParamEnum::PumpSwap(_) | DexParamEnum::Bonk(_) => {},
+    }
+    None
+}
+
+/// 读取池子账户与 vault 并检查；参数本身不一致时不发起 RPC
+pub async fn check_pool_state(
+    rpc: &SolanaRpcClient,
+    config: &PoolStateCheckConfig,
+    params: &DexParamEnum,
+    mint: &Pubkey,
+) -> Result<Option<TradeError>> {
+    if let Some(error) = inspect_params(params) {
+        return Ok(Some(error));
+    }
+    let (pool, vaults) = pool_accounts(params, mint);
+    let addresses: Vec<Pubkey> = pool.into_iter().chain(vaults.into_iter().flatten()).collect();
+    if addresses.is_empty() {
+        return Ok(None);
+    }
+    let response = rpc.get_multiple_accounts_with_commitment(&addresses, rpc.commitment()).await?;
+    if let Some(max_slot_lag) = config.max_slot_lag {
+        let latest = rpc.get_slot_with_commitment(CommitmentConfig::processed()).await?;
+        let lag = latest.saturating_sub(response.context.slot);
+        if lag > max_slot_lag {
+            return Ok(Some(TradeError::pool_state_invalid(format!(
+                "{:?} pool state is {} slots behind the cluster (max {})",
+                params.dex_type(),
+                lag,
+                max_slot_lag
+            ))));
+        }
+    }
+
+    let dex_type = params.dex_type();
+    for (address, account) in addresses.iter().zip(&response.value) {
+        let Some(account) = account else {
+            return Ok(Some(TradeError::pool_state_invalid(format!(
+                "{:?} pool account {} does not exist",
+                dex_type, address
+            ))));
+        };
+        if Some(*address) == pool {
+            let now = chrono::Utc::now().timestamp().max(0) as u64;
+            if let Some(error) = inspect_pool_account(params, &account.data, now) {
+                return Ok(Some(error));
+            }
+            continue;
+        }
+        let amount = account
+            .data
+            .get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("slice length is 8")));
+        if amount.unwrap_or(0) == 0 {
+            return Ok(Some(TradeError::pool_state_invalid(format!(
+                "{:?} pool vault {} is empty",
+                dex_type, address
+            ))));
+        }
+    }
+    Ok(None)
+}
+
+impl TradingClient {
+    /// 按配置检查池子状态，未配置时不检查
+    pub(crate) async fn check_pool_state(
+        &self,
+        params: &DexParamEnum,
+        mint: &Pubkey,
+    ) -> Result<Option<TradeError>> {
+        match &self.pool_state_check {
+            Some(config) => check_pool_state(&self.rpc, config, params, mint).await,
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::bonding_curve::BondingCurveAccount;
+    use crate::constants::TOKEN_PROGRAM;
+    use crate::trading::core::params::{PumpFunParams, RaydiumAmmV4Params};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_inspect_params_reserves() {
+        let amm = |coin_reserve, pc_reserve| {
+            DexParamEnum::RaydiumAmmV4(RaydiumAmmV4Params::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                coin_reserve,
+                pc_reserve,
+            ))
+        };
+        assert!(inspect_params(&amm(1_000, 2_000)).is_none());
+        // 未提供储备（如立即卖出）不报错
+        assert!(inspect_params(&amm(0, 0)).is_none());
+        let error = inspect_params(&amm(1_000, 0)).unwrap();
+        assert!(error.is_pool_state_invalid());
+        assert!(error.message.contains("RaydiumAmmV4"));
+    }
+
+    #[test]
+    fn test_inspect_pumpfun_curve() {
+        let params = DexParamEnum::PumpFun(PumpFunParams::immediate_sell(
+            Pubkey::new_unique(),
+            TOKEN_PROGRAM,
+            false,
+        ));
+        let mut data = vec![0u8; 150];
+        data[8..16].copy_from_slice(&1_000u64.to_le_bytes());
+        data[16..24].copy_from_slice(&1_000u64.to_le_bytes());
+        assert!(inspect_pool_account(&params, &data, 0).is_none());
+
+        data[48] = 1;
+        let error = inspect_pool_account(&params, &data, 0).unwrap();
+        assert!(error.is_pool_state_invalid());
+        assert!(error.message.contains("complete"));
+
+        let curve = BondingCurveAccount { virtual_token_reserves: 1, ..Default::default() };
+        let params = DexParamEnum::PumpFun(PumpFunParams {
+            bonding_curve: Arc::new(curve),
+            ..PumpFunParams::immediate_sell(Pubkey::new_unique(), TOKEN_PROGRAM, false)
+        });
+        assert!(inspect_params(&params).is_some());
+    }
+}
@@ -0,0 +1,198 @@
+//! 钱包交易频率限制
+//!
+//! 中继与验证者会对短时间内大量发送交易的钱包降权，导致后续交易上链率下降。
+//! [`WalletRateLimiter`] 在客户端侧限制同一钱包的交易频率：
+//!
+//! - 任意 `window` 时间内最多 `max_trades` 笔；
+//! - 同一 mint 的两笔交易至少间隔 `min_same_mint_gap`。
+//!
+//! 超限时按 [`RateLimitMode`] 拒绝（[`TradeError::RATE_LIMITED`]）或等待到允许发送为止。
+//! 模拟交易不计入。`TradingClient` 的克隆共享同一个限制器。
+
+use crate::TradingClient;
+use crate::swqos::common::TradeError;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// 超限时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// 直接拒绝，返回 [`TradeError::RATE_LIMITED`]（默认）
+    #[default]
+    Reject,
+    /// 等待到允许发送，最多等待 `max_wait`，超过时拒绝
+    Wait { max_wait: Duration },
+}
+
+/// 钱包交易频率限制配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalletRateLimitConfig {
+    /// 窗口内最多交易笔数
+    pub max_trades: usize,
+    /// 统计窗口
+    pub window: Duration,
+    /// 同一 mint 两笔交易的最小间隔（None 表示不限制）
+    pub min_same_mint_gap: Option<Duration>,
+    pub mode: RateLimitMode,
+}
+
+impl Default for WalletRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_trades: 10,
+            window: Duration::from_secs(10),
+            min_same_mint_gap: None,
+            mode: RateLimitMode::Reject,
+        }
+    }
+}
+
+impl WalletRateLimitConfig {
+    pub fn new(max_trades: usize, window: Duration) -> Self {
+        Self { max_trades, window, ..Default::default() }
+    }
+
+    pub fn with_min_same_mint_gap(mut self, min_same_mint_gap: Duration) -> Self {
+        self.min_same_mint_gap = Some(min_same_mint_gap);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: RateLimitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[derive(Default)]
+struct RateLimitState {
+    recent: VecDeque<Instant>,
+    last_by_mint: HashMap<Pubkey, Instant>,
+}
+
+/// 单个钱包的交易频率限制器
+pub struct WalletRateLimiter {
+    config: WalletRateLimitConfig,
+    state: Mutex<RateLimitState>,
+}
+
+impl WalletRateLimiter {
+    pub fn new(config: WalletRateLimitConfig) -> Self {
+        Self { config, state: Mutex::new(RateLimitState::default()) }
+    }
+
+    pub fn config(&self) -> &WalletRateLimitConfig {
+        &self.config
+    }
+
+    /// 在 `now` 尝试记录一笔 `mint` 的交易；超限时不记录，返回需要等待的时间
+    pub fn try_acquire_at(&self, mint: &Pubkey, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+        let window = self.config.window;
+        while state
+            .recent
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= window)
+        {
+            state.recent.pop_front();
+        }
+        let mut wait = Duration::ZERO;
+        if state.recent.len() >= self.config.max_trades.max(1)
+            && let Some(oldest) = state.recent.front()
+        {
+            wait = wait.max((*oldest + window).saturating_duration_since(now));
+        }
+        if let (Some(gap), Some(last)) =
+            (self.config.min_same_mint_gap, state.last_by_mint.get(mint))
+        {
+            wait = wait.max((*last + gap).saturating_duration_since(now));
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        state.recent.push_back(now);
+        state.last_by_mint.insert(*mint, now);
+        if let Some(gap) = self.config.min_same_mint_gap {
+            state.last_by_mint.retain(|_, last| now.saturating_duration_since(*last) < gap);
+        }
+        Ok(())
+    }
+
+    /// 按配置的模式获取发送许可；拒绝时返回 [`TradeError::RATE_LIMITED`]
+    pub async fn acquire(&self, mint: &Pubkey) -> Result<(), TradeError> {
+        let deadline = match self.config.mode {
+            RateLimitMode::Reject => None,
+            RateLimitMode::Wait { max_wait } => Some(Instant::now() + max_wait),
+        };
+        loop {
+            let now = Instant::now();
+            let wait = match self.try_acquire_at(mint, now) {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+            match deadline {
+                Some(deadline) if now + wait <= deadline => tokio::time::sleep(wait).await,
+                _ => {
+                    return Err(TradeError::rate_limited(format!(
+                        "wallet rate limit exceeded for {}: retry in {:?}",
+                        mint, wait
+                    )));
+                },
+            }
+        }
+    }
+}
+
+impl TradingClient {
+    /// 按 `TradeConfig::wallet_rate_limit` 获取发送许可；超限时返回错误
+    pub(crate) async fn check_rate_limit(
+        &self,
+        mint: &Pubkey,
+        simulate: bool,
+    ) -> Option<TradeError> {
+        let limiter = self.rate_limiter.as_ref()?;
+        if simulate {
+            return None;
+        }
+        limiter.acquire(mint).await.err()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_window_and_same_mint_gap() {
+        let limiter = WalletRateLimiter::new(
+            WalletRateLimitConfig::new(2, Duration::from_secs(10))
+                .with_min_same_mint_gap(Duration::from_secs(3)),
+        );
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let start = Instant::now();
+        assert!(limiter.try_acquire_at(&a, start).is_ok());
+        // 同一 mint 间隔不足
+        assert_eq!(
+            limiter.try_acquire_at(&a, start + Duration::from_secs(1)),
+            Err(Duration::from_secs(2))
+        );
+        assert!(limiter.try_acquire_at(&b, start + Duration::from_secs(1)).is_ok());
+        // 窗口内已满 2 笔
+        assert_eq!(
+            limiter.try_acquire_at(&c, start + Duration::from_secs(4)),
+            Err(Duration::from_secs(6))
+        );
+        assert!(limiter.try_acquire_at(&c, start + Duration::from_secs(10)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_reject_mode() {
+        let limiter =
+            WalletRateLimiter::new(WalletRateLimitConfig::new(1, Duration::from_secs(60)));
+        let mint = Pubkey::new_unique();
+        assert!(limiter.acquire(&mint).await.is_ok());
+        let err = limiter.acquire(&Pubkey::new_unique()).await.unwrap_err();
+        assert!(err.is_rate_limited());
+    }
+}
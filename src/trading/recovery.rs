@@ -0,0 +1,327 @@
+//! 启动时从交易日志恢复未完结的交易
+//!
+//! 进程在交易发送后、确认前退出时，日志中会残留 `Signed` / `Submitted` 状态的记录。
+//! [`TradingClient::recover_pending`] 逐笔查询链上状态：
+//! - 已上链：补写 `record_landed`；
+//! - 未上链且 blockhash 已过期 / nonce 已推进：交易不可能再上链，记为发送失败；
+//! - 未上链且使用 durable nonce、nonce 仍未推进：按 [`NonceRecovery`] 重新广播或推进 nonce 取消；
+//! - 未上链且 blockhash 仍有效：保持原状态，留待下次恢复。
+//!
+//! 单笔交易处理失败（如重新广播出错）不会中断恢复，错误记录在 [`RecoveryReport::errors`] 中。
+
+use crate::TradingClient;
+use crate::common::nonce_cache::fetch_nonce_info;
+use crate::swqos::TradeType;
+use crate::trading::resubmitter::advance_nonce;
+use crate::trading::sender::uses_durable_nonce;
+use crate::trading::store::{TradeRecord, TradeStore};
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::TransactionStatus;
+use std::str::FromStr;
+
+/// `getSignatureStatuses` 单次最多查询的签名数
+const MAX_SIGNATURES_PER_QUERY: usize = 256;
+
+/// 使用 durable nonce、尚未上链且 nonce 未推进的交易如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonceRecovery {
+    /// 推进 nonce，使交易永久失效（默认：重启后行情可能已变化）
+    #[default]
+    Cancel,
+    /// 按原 SWQOS 重新广播已签名的交易
+    Resubmit,
+}
+
+/// 一次恢复的结果（均为交易签名）
+#[derive(Debug, Clone, Default)]
+pub struct RecoveryReport {
+    /// 已上链且执行成功
+    pub landed: Vec<String>,
+    /// 已上链但执行失败
+    pub failed: Vec<String>,
+    /// 未上链且已无法上链（blockhash 过期或 nonce 已推进）
+    pub dropped: Vec<String>,
+    /// 已推进 nonce 取消
+    pub cancelled: Vec<String>,
+    /// 已重新广播，仍待确认
+    pub resubmitted: Vec<String>,
+    /// blockhash 仍有效，仍可能上链，未做处理
+    pub in_flight: Vec<String>,
+    /// 处理失败的交易：(签名, 错误信息)，留待下次恢复
+    pub errors: Vec<(String, String)>,
+}
+
+impl RecoveryReport {
+    /// 仍未得到最终结果的交易数（重新广播 + 在途 + 处理失败）
+    pub fn unresolved(&self) -> usize {
+        self.resubmitted.len() + self.in_flight.len() + self.errors.len()
+    }
+}
+
+impl TradingClient {
+    /// Resolve trades the journal recorded as signed/submitted but never confirmed
+    ///
+    /// 需先通过 [`TradingClient::with_trade_store`] 挂载持久化的交易日志，建议在启动时调用一次。
+    /// 记录的状态按链上结果更新；`nonce_recovery` 决定仍可上链的 durable nonce 交易如何处理。
+    /// 仅读取日志或批量查询状态失败时返回错误，单笔交易的错误汇总到 [`RecoveryReport::errors`]。
+    pub async fn recover_pending(&self, nonce_recovery: NonceRecovery) -> Result<RecoveryReport> {
+        let store = self
+            .trade_store
+            .as_ref()
+            .ok_or_else(|| anyhow!("No trade store attached"))?
+            .clone();
+        let mut report = RecoveryReport::default();
+        let records = store.pending().await?;
+        if records.is_empty() {
+            return Ok(report);
+        }
+
+        let mut unresolved = Vec::new();
+        let statuses = self.signature_statuses(&records).await?;
+        for (record, status) in records.into_iter().zip(statuses) {
+            match status {
+                Some(status) => {
+                    if let Err(e) = finalize(store.as_ref(), &record, &status, &mut report).await {
+                        report.errors.push((record.signature, e.to_string()));
+                    }
+                },
+                None => unresolved.push(record),
+            }
+        }
+
+        // 单笔处理失败不影响其余交易，错误汇总到报告中
+        let mut expired = Vec::new();
+        let mut block_height = None;
+        for record in unresolved {
+            let signature = record.signature.clone();
+            match self
+                .recover_unresolved(
+                    store.as_ref(),
+                    record,
+                    nonce_recovery,
+                    &mut block_height,
+                    &mut report,
+                )
+                .await
+            {
+                Ok(Some(record)) => expired.push(record),
+                Ok(None) => {},
+                Err(e) => report.errors.push((signature, e.to_string())),
+            }
+        }
+
+        // 过期前的最后一刻仍可能上链：再查一次状态后才记为丢弃
+        let statuses = self.signature_statuses(&expired).await?;
+        for (record, status) in expired.into_iter().zip(statuses) {
+            match status {
+                Some(status) => {
+                    if let Err(e) = finalize(store.as_ref(), &record, &status, &mut report).await {
+                        report.errors.push((record.signature, e.to_string()));
+                    }
+                },
+                None => match store
+                    .record_submitted(&record.signature, Some("dropped: transaction expired"))
+                    .await
+                {
+                    Ok(()) => report.dropped.push(record.signature),
+                    Err(e) => report.errors.push((record.signature, e.to_string())),
+                },
+            }
+        }
+        Ok(report)
+    }
+
+    /// 尚未上链的一笔交易：仍可上链的按策略处理，已过期的返回给调用方复核
+    async fn recover_unresolved(
+        &self,
+        store: &dyn TradeStore,
+        record: TradeRecord,
+        nonce_recovery: NonceRecovery,
+        block_height: &mut Option<u64>,
+        report: &mut RecoveryReport,
+    ) -> Result<Option<TradeRecord>> {
+        let tx = decode_transaction(&record)?;
+        let message = &tx.message;
+        if !uses_durable_nonce(message) {
+            // 日志中记录了最后有效区块高度时直接比较，否则向 RPC 校验 blockhash
+            let valid = match record.last_valid_block_height {
+                Some(last_valid_block_height) => {
+                    let height = match *block_height {
+                        Some(height) => height,
+                        None => *block_height.insert(self.rpc.get_block_height().await?),
+                    };
+                    height <= last_valid_block_height
+                },
+                None => {
+                    let blockhash = message.recent_blockhash();
+                    self.rpc.is_blockhash_valid(blockhash, CommitmentConfig::processed()).await?
+                },
+            };
+            if valid {
+                report.in_flight.push(record.signature);
+                return Ok(None);
+            }
+            return Ok(Some(record));
+        }
+
+        let nonce_account = nonce_account_of(message)
+            .ok_or_else(|| anyhow!("Trade {} has no nonce account", record.signature))?;
+        let current_nonce =
+            fetch_nonce_info(&self.rpc, nonce_account).await.and_then(|n| n.current_nonce);
+        if current_nonce != Some(*message.recent_blockhash()) {
+            return Ok(Some(record));
+        }
+        match nonce_recovery {
+            NonceRecovery::Cancel => {
+                match advance_nonce(
+                    &self.rpc,
+                    &self.payer,
+                    &nonce_account,
+                    *message.recent_blockhash(),
+                )
+                .await
+                {
+                    Ok(_) => {
+                        store
+                            .record_submitted(
+                                &record.signature,
+                                Some("cancelled by recovery: nonce advanced"),
+                            )
+                            .await?;
+                        report.cancelled.push(record.signature);
+                    },
+                    // 推进失败通常意味着交易恰好在此期间上链，留待下次恢复
+                    Err(e) => {
+                        log::warn!("failed to cancel trade {}: {}", record.signature, e);
+                        report.in_flight.push(record.signature);
+                    },
+                }
+            },
+            NonceRecovery::Resubmit => {
+                self.resubmit(&record, &tx).await?;
+                report.resubmitted.push(record.signature);
+            },
+        }
+        Ok(None)
+    }
+
+    async fn signature_statuses(
+        &self,
+        records: &[TradeRecord],
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        let signatures = records
+            .iter()
+            .map(|r| Signature::from_str(&r.signature))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut statuses = Vec::with_capacity(signatures.len());
+        for chunk in signatures.chunks(MAX_SIGNATURES_PER_QUERY) {
+            statuses.extend(self.rpc.get_signature_statuses_with_history(chunk).await?.value);
+        }
+        Ok(statuses)
+    }
+
+    /// 优先通过记录中的 SWQOS 重新广播，未配置该服务时走 RPC
+    async fn resubmit(&self, record: &TradeRecord, tx: &VersionedTransaction) -> Result<()> {
+        let swqos_client = self
+            .swqos_clients()
+            .iter()
+            .find(|client| client.get_swqos_type().to_string() == record.swqos_type)
+            .cloned();
+        match swqos_client {
+            Some(client) => client.send_transaction(trade_type_of(record), tx, false).await?,
+            None => {
+                let config =
+                    RpcSendTransactionConfig { skip_preflight: true, ..Default::default() };
+                self.rpc.send_transaction_with_config(tx, config).await?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// 按链上状态补写上链结果
+async fn finalize(
+    store: &dyn TradeStore,
+    record: &TradeRecord,
+    status: &TransactionStatus,
+    report: &mut RecoveryReport,
+) -> Result<()> {
+    match &status.err {
+        None => {
+            store.record_landed(&record.signature, true, None).await?;
+            report.landed.push(record.signature.clone());
+        },
+        Some(err) => {
+            store.record_landed(&record.signature, false, Some(&err.to_string())).await?;
+            report.failed.push(record.signature.clone());
+        },
+    }
+    Ok(())
+}
+
+fn decode_transaction(record: &TradeRecord) -> Result<VersionedTransaction> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(&record.transaction_base64)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// `AdvanceNonceAccount` 指令的第一个账户即 nonce 账户
+fn nonce_account_of(message: &VersionedMessage) -> Option<Pubkey> {
+    let instruction = message.instructions().first()?;
+    let index = *instruction.accounts.first()?;
+    message.static_account_keys().get(index as usize).copied()
+}
+
+fn trade_type_of(record: &TradeRecord) -> TradeType {
+    [TradeType::Create, TradeType::CreateAndBuy, TradeType::Buy, TradeType::Sell]
+        .into_iter()
+        .find(|t| t.to_string() == record.trade_type)
+        .unwrap_or(TradeType::Buy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::Message;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+    use solana_system_interface::instruction::{advance_nonce_account, transfer};
+
+    #[test]
+    fn test_nonce_account_of() {
+        let payer = Keypair::new().pubkey();
+        let nonce_account = Pubkey::new_unique();
+        let ixs = [advance_nonce_account(&nonce_account, &payer), transfer(&payer, &payer, 1)];
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &ixs,
+            Some(&payer),
+            &Hash::default(),
+        ));
+        assert!(uses_durable_nonce(&message));
+        assert_eq!(nonce_account_of(&message), Some(nonce_account));
+
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &ixs[1..],
+            Some(&payer),
+            &Hash::default(),
+        ));
+        assert!(!uses_durable_nonce(&message));
+    }
+
+    #[test]
+    fn test_unresolved_counts_errors() {
+        let report = RecoveryReport {
+            in_flight: vec!["a".to_string()],
+            errors: vec![("b".to_string(), "resubmit failed".to_string())],
+            ..Default::default()
+        };
+        assert_eq!(report.unresolved(), 2);
+    }
+}
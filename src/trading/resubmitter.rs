@@ -0,0 +1,711 @@
+//! 重复广播引擎
+//!
+//! 对于使用 durable nonce 的交易，同一笔已签名交易不会因为 blockhash 过期而失效，
+//! 因此可以按固定节奏持续向所有 SWQOS 重复广播，直到：
+//! - 任意一笔交易上链且执行成功（Landed）或执行失败（Failed）；
+//! - 调用方显式取消：发送一笔仅包含 `advance_nonce_account` 的交易推进 nonce，
+//!   使所有已广播的交易失效（Cancelled）；
+//! - 超过最长广播时间（Expired）。
+//!
+//! 使用普通 blockhash 的交易（[`NonceResubmitter::start_with_blockhash`]）在区块高度超过
+//! `last_valid_block_height` 且仍未上链时，旧交易已不可能再上链，此时换用最新 blockhash
+//! 重新签名后继续广播；这类交易无法主动作废，取消只会停止广播（Stopped）。
+//! 区块高度与 blockhash 均按 confirmed 级别读取；任一交易已被处理（Processed，尚未确认）
+//! 或签名状态查询失败时本轮不重新签名，避免新旧交易同时上链。
+
+use crate::common::SolanaRpcClient;
+use crate::common::nonce_cache::DurableNonceInfo;
+use crate::swqos::{SwqosClient, TradeType};
+use anyhow::{Result, anyhow};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::{Transaction, VersionedTransaction},
+};
+use solana_system_interface::instruction::advance_nonce_account;
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// 重复广播配置
+#[derive(Debug, Clone, Copy)]
+pub struct ResubmitConfig {
+    /// 两轮广播之间的间隔
+    pub interval: Duration,
+    /// 最长广播时长，超过后停止（不会自动推进 nonce）
+    pub max_duration: Duration,
+}
+
+impl Default for ResubmitConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(400),
+            max_duration: Duration::from_secs(60),
+        }
+    }
+}
+
+/// 重复广播的最终结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResubmitOutcome {
+    /// 某一笔交易已上链（Confirmed 或 Finalized）且执行成功
+    Landed { signature: Signature, rounds: u32 },
+    /// 某一笔交易已上链但执行失败（nonce / 手续费已消耗，不再广播）
+    Failed { signature: Signature, error: String, rounds: u32 },
+    /// 已取消，nonce 已被推进，返回推进 nonce 的交易签名
+    Cancelled { cancel_signature: Signature, rounds: u32 },
+    /// 已停止广播（基于 blockhash 的交易无法作废，过期前仍可能上链）
+    Stopped { rounds: u32 },
+    /// 超过最长广播时长仍未上链
+    Expired { rounds: u32 },
+}
+
+/// 一笔待广播的交易及其目标 SWQOS
+#[derive(Clone)]
+pub struct ResubmitEntry {
+    pub swqos_client: Arc<SwqosClient>,
+    pub transaction: VersionedTransaction,
+}
+
+/// 重复广播任务句柄
+pub struct ResubmitHandle {
+    signatures: Vec<Signature>,
+    cancel_tx: Option<oneshot::Sender<()>>,
+    task: JoinHandle<Result<ResubmitOutcome>>,
+}
+
+impl ResubmitHandle {
+    /// 开始广播时的所有交易签名（重新签名后的签名见最终结果）
+    pub fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    /// 广播是否已经结束
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// 取消广播；durable nonce 交易同时推进 nonce，使所有已广播交易失效
+    ///
+    /// 若交易在取消前已上链，返回 `Landed` / `Failed`；推进 nonce 失败时返回错误。
+    pub async fn cancel(mut self) -> Result<ResubmitOutcome> {
+        if let Some(cancel_tx) = self.cancel_tx.take() {
+            // 任务已结束时发送失败，直接取任务结果即可
+            let _ = cancel_tx.send(());
+        }
+        self.wait().await
+    }
+
+    /// 等待广播结束
+    pub async fn wait(self) -> Result<ResubmitOutcome> {
+        self.task.await.map_err(|e| anyhow!("resubmit task failed: {}", e))?
+    }
+}
+
+/// 重复广播循环用到的 RPC 方法
+#[async_trait::async_trait]
+trait ResubmitRpc: Send + Sync {
+    async fn signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<TransactionStatus>>>;
+
+    async fn block_height(&self) -> Result<u64>;
+
+    /// 最新 blockhash 及其 `last_valid_block_height`
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)>;
+
+    async fn advance_nonce(
+        &self,
+        payer: &Keypair,
+        nonce_account: &Pubkey,
+        current_nonce: Hash,
+    ) -> Result<Signature>;
+}
+
+#[async_trait::async_trait]
+impl ResubmitRpc for SolanaRpcClient {
+    async fn signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        Ok(self.get_signature_statuses(signatures).await?.value)
+    }
+
+    async fn block_height(&self) -> Result<u64> {
+        Ok(self.get_block_height_with_commitment(CommitmentConfig::confirmed()).await?)
+    }
+
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+        Ok(self.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed()).await?)
+    }
+
+    async fn advance_nonce(
+        &self,
+        payer: &Keypair,
+        nonce_account: &Pubkey,
+        current_nonce: Hash,
+    ) -> Result<Signature> {
+        advance_nonce(self, payer, nonce_account, current_nonce).await
+    }
+}
+
+/// 交易有效期的来源
+#[derive(Debug, Clone, Copy)]
+enum Lifetime {
+    /// durable nonce：取消时推进 nonce
+    Nonce { nonce_account: Pubkey, current_nonce: Hash },
+    /// 普通 blockhash：过期后换用新 blockhash 重新签名
+    Blockhash { last_valid_block_height: u64 },
+}
+
+/// 重复广播器（durable nonce 或普通 blockhash）
+pub struct NonceResubmitter {
+    rpc: Arc<SolanaRpcClient>,
+    payer: Arc<Keypair>,
+}
+
+impl NonceResubmitter {
+    pub fn new(rpc: Arc<SolanaRpcClient>, payer: Arc<Keypair>) -> Self {
+        Self { rpc, payer }
+    }
+
+    /// 开始重复广播
+    ///
+    /// 所有 `entries` 必须使用同一个 nonce（`durable_nonce.current_nonce`）签名，
+    /// 这样任意一笔上链或被取消后，其余交易都会自动失效。
+    pub fn start(
+        &self,
+        trade_type: TradeType,
+        entries: Vec<ResubmitEntry>,
+        durable_nonce: DurableNonceInfo,
+        config: ResubmitConfig,
+    ) -> Result<ResubmitHandle> {
+        if entries.is_empty() {
+            return Err(anyhow!("No transactions to resubmit"));
+        }
+        let nonce_account = durable_nonce
+            .nonce_account
+            .ok_or_else(|| anyhow!("Nonce account not set in DurableNonceInfo"))?;
+        let current_nonce = durable_nonce
+            .current_nonce
+            .ok_or_else(|| anyhow!("Current nonce not set in DurableNonceInfo"))?;
+        if let Some(entry) = entries
+            .iter()
+            .find(|e| *e.transaction.message.recent_blockhash() != current_nonce)
+        {
+            return Err(anyhow!(
+                "Transaction {:?} was not signed with the current nonce",
+                entry.transaction.signatures.first()
+            ));
+        }
+
+        Ok(spawn(
+            self.rpc.clone(),
+            self.payer.clone(),
+            trade_type,
+            entries,
+            Lifetime::Nonce { nonce_account, current_nonce },
+            config,
+        ))
+    }
+
+    /// 开始重复广播使用普通 blockhash 签名的交易
+    ///
+    /// 区块高度超过 `last_valid_block_height` 且没有交易上链时，用最新 blockhash 重新签名
+    /// 所有交易后继续广播（旧交易此时已不可能上链，不会重复成交）。
+    /// `last_valid_block_height` 应取自 confirmed 级别的 blockhash；交易必须只有 payer 一个签名者。
+    pub fn start_with_blockhash(
+        &self,
+        trade_type: TradeType,
+        entries: Vec<ResubmitEntry>,
+        last_valid_block_height: u64,
+        config: ResubmitConfig,
+    ) -> Result<ResubmitHandle> {
+        if entries.is_empty() {
+            return Err(anyhow!("No transactions to resubmit"));
+        }
+        let payer = self.payer.pubkey();
+        if let Some(entry) = entries.iter().find(|e| {
+            e.transaction.message.header().num_required_signatures != 1
+                || e.transaction.message.static_account_keys().first() != Some(&payer)
+        }) {
+            return Err(anyhow!(
+                "Transaction {:?} cannot be re-signed: payer must be the only signer",
+                entry.transaction.signatures.first()
+            ));
+        }
+
+        Ok(spawn(
+            self.rpc.clone(),
+            self.payer.clone(),
+            trade_type,
+            entries,
+            Lifetime::Blockhash { last_valid_block_height },
+            config,
+        ))
+    }
+}
+
+fn spawn(
+    rpc: Arc<dyn ResubmitRpc>,
+    payer: Arc<Keypair>,
+    trade_type: TradeType,
+    entries: Vec<ResubmitEntry>,
+    lifetime: Lifetime,
+    config: ResubmitConfig,
+) -> ResubmitHandle {
+    let signatures: Vec<Signature> = entries
+        .iter()
+        .filter_map(|e| e.transaction.signatures.first().copied())
+        .collect();
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    let task = tokio::spawn(run_loop(
+        rpc,
+        payer,
+        trade_type,
+        entries,
+        signatures.clone(),
+        lifetime,
+        config,
+        cancel_rx,
+    ));
+    ResubmitHandle { signatures, cancel_tx: Some(cancel_tx), task }
+}
+
+async fn run_loop(
+    rpc: Arc<dyn ResubmitRpc>,
+    payer: Arc<Keypair>,
+    trade_type: TradeType,
+    mut entries: Vec<ResubmitEntry>,
+    mut signatures: Vec<Signature>,
+    mut lifetime: Lifetime,
+    config: ResubmitConfig,
+    mut cancel_rx: oneshot::Receiver<()>,
+) -> Result<ResubmitOutcome> {
+    let deadline = tokio::time::Instant::now() + config.max_duration;
+    // 句柄被丢弃后不再监听取消信号，继续广播直到上链或超时
+    let mut cancel_open = true;
+    let mut rounds = 0u32;
+
+    loop {
+        // 1. 先读区块高度再查状态：状态为空且高度已超过有效期时，旧交易不可能再上链
+        let expired = match lifetime {
+            Lifetime::Blockhash { last_valid_block_height } => match rpc.block_height().await {
+                Ok(height) => height > last_valid_block_height,
+                Err(e) => {
+                    log::warn!("resubmit failed to fetch the block height: {}", e);
+                    false
+                },
+            },
+            Lifetime::Nonce { .. } => false,
+        };
+        let landing = find_landed(rpc.as_ref(), &signatures, rounds).await;
+        match landing {
+            Ok(Landing::Settled(outcome)) => return Ok(outcome),
+            Err(ref e) => log::warn!("resubmit failed to fetch signature statuses: {}", e),
+            Ok(_) => {},
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Ok(ResubmitOutcome::Expired { rounds });
+        }
+        // 状态未知或已有交易被处理时不重新签名：旧交易仍可能确认，新交易会重复成交
+        if expired && matches!(landing, Ok(Landing::NotSeen)) {
+            // 获取失败时本轮照常广播旧交易，下一轮重试
+            match rpc.latest_blockhash().await {
+                Ok((blockhash, last_valid_block_height)) => {
+                    for entry in &mut entries {
+                        entry.transaction = resign(&entry.transaction, &payer, blockhash)?;
+                        signatures.extend(entry.transaction.signatures.first().copied());
+                    }
+                    lifetime = Lifetime::Blockhash { last_valid_block_height };
+                    log::debug!(
+                        "resubmit re-signed {} transactions on {}",
+                        entries.len(),
+                        blockhash
+                    );
+                },
+                Err(e) => log::warn!("resubmit failed to fetch a new blockhash: {}", e),
+            }
+        }
+
+        // 2. 向所有 SWQOS 广播（不等待确认）
+        rounds += 1;
+        for entry in &entries {
+            let client = entry.swqos_client.clone();
+            let tx = entry.transaction.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.send_transaction(trade_type, &tx, false).await {
+                    log::debug!("resubmit to {:?} failed: {}", client.get_swqos_type(), e);
+                }
+            });
+        }
+
+        // 3. 等待下一轮或取消
+        let next_round = (tokio::time::Instant::now() + config.interval).min(deadline);
+        tokio::select! {
+            _ = tokio::time::sleep_until(next_round) => {},
+            cancelled = &mut cancel_rx, if cancel_open => {
+                if cancelled.is_err() {
+                    cancel_open = false;
+                    continue;
+                }
+                if let Ok(Landing::Settled(outcome)) =
+                    find_landed(rpc.as_ref(), &signatures, rounds).await
+                {
+                    return Ok(outcome);
+                }
+                return match lifetime {
+                    Lifetime::Nonce { nonce_account, current_nonce } => {
+                        let cancel_signature =
+                            rpc.advance_nonce(&payer, &nonce_account, current_nonce).await?;
+                        Ok(ResubmitOutcome::Cancelled { cancel_signature, rounds })
+                    },
+                    Lifetime::Blockhash { .. } => Ok(ResubmitOutcome::Stopped { rounds }),
+                };
+            },
+        }
+    }
+}
+
+/// 签名状态的查询结果
+enum Landing {
+    /// 已确认（Confirmed 或 Finalized），按执行结果为 `Landed` / `Failed`
+    Settled(ResubmitOutcome),
+    /// 已被处理但尚未确认（Processed），之后可能确认也可能回滚
+    Processed,
+    /// 所有交易均未被处理
+    NotSeen,
+}
+
+/// 检查是否有交易已被处理；已确认的交易优先于仅被处理的交易
+async fn find_landed(
+    rpc: &dyn ResubmitRpc,
+    signatures: &[Signature],
+    rounds: u32,
+) -> Result<Landing> {
+    let statuses = rpc.signature_statuses(signatures).await?;
+    let mut landing = Landing::NotSeen;
+    for (status, signature) in statuses.iter().zip(signatures) {
+        let Some(status) = status else {
+            continue;
+        };
+        if !matches!(
+            status.confirmation_status,
+            Some(TransactionConfirmationStatus::Confirmed)
+                | Some(TransactionConfirmationStatus::Finalized)
+        ) {
+            landing = Landing::Processed;
+            continue;
+        }
+        return Ok(Landing::Settled(match &status.err {
+            None => ResubmitOutcome::Landed { signature: *signature, rounds },
+            Some(err) => {
+                ResubmitOutcome::Failed { signature: *signature, error: err.to_string(), rounds }
+            },
+        }));
+    }
+    Ok(landing)
+}
+
+/// 换用新的 blockhash 重新签名（仅 payer 一个签名者）
+fn resign(
+    transaction: &VersionedTransaction,
+    payer: &Keypair,
+    blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let mut message = transaction.message.clone();
+    message.set_recent_blockhash(blockhash);
+    Ok(VersionedTransaction::try_new(message, &[payer])?)
+}
+
+/// 发送一笔仅推进 nonce 的交易，使用同一个 nonce 签名的所有交易随之失效
+pub async fn advance_nonce(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    nonce_account: &Pubkey,
+    current_nonce: Hash,
+) -> Result<Signature> {
+    let ix = advance_nonce_account(nonce_account, &payer.pubkey());
+    let tx =
+        Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], current_nonce);
+    Ok(rpc.send_and_confirm_transaction(&tx).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swqos::SwqosType;
+    use solana_sdk::message::{Message, VersionedMessage};
+    use solana_sdk::transaction::TransactionError;
+    use solana_system_interface::instruction::transfer;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+    type Sent = Arc<Mutex<Vec<VersionedTransaction>>>;
+
+    /// 记录广播的交易
+    struct RecordingSwqos {
+        sent: Sent,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::swqos::SwqosClientTrait for RecordingSwqos {
+        async fn send_transaction(
+            &self,
+            _trade_type: TradeType,
+            transaction: &VersionedTransaction,
+            _wait_confirmation: bool,
+        ) -> Result<()> {
+            self.sent.lock().unwrap().push(transaction.clone());
+            Ok(())
+        }
+
+        async fn send_transactions(
+            &self,
+            trade_type: TradeType,
+            transactions: &[VersionedTransaction],
+            wait_confirmation: bool,
+        ) -> Result<()> {
+            for transaction in transactions {
+                self.send_transaction(trade_type, transaction, wait_confirmation).await?;
+            }
+            Ok(())
+        }
+
+        fn get_tip_account(&self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn get_swqos_type(&self) -> SwqosType {
+            SwqosType::Default
+        }
+    }
+
+    /// 已广播且 blockhash 为 `landing_blockhash` 的交易按 `err` 上链
+    struct FakeRpc {
+        sent: Sent,
+        landing_blockhash: Option<Hash>,
+        err: Option<TransactionError>,
+        confirmation_status: TransactionConfirmationStatus,
+        /// 为 true 时签名状态查询失败
+        statuses_unavailable: AtomicBool,
+        block_height: AtomicU64,
+        new_blockhash: Hash,
+    }
+
+    #[async_trait::async_trait]
+    impl ResubmitRpc for FakeRpc {
+        async fn signature_statuses(
+            &self,
+            signatures: &[Signature],
+        ) -> Result<Vec<Option<TransactionStatus>>> {
+            if self.statuses_unavailable.load(Ordering::Relaxed) {
+                return Err(anyhow!("rpc unavailable"));
+            }
+            let sent = self.sent.lock().unwrap();
+            Ok(signatures
+                .iter()
+                .map(|signature| {
+                    let tx = sent.iter().find(|tx| tx.signatures[0] == *signature)?;
+                    (Some(*tx.message.recent_blockhash()) == self.landing_blockhash).then(|| {
+                        TransactionStatus {
+                            slot: 1,
+                            confirmations: None,
+                            status: self.err.clone().map_or(Ok(()), Err),
+                            err: self.err.clone(),
+                            confirmation_status: Some(self.confirmation_status.clone()),
+                        }
+                    })
+                })
+                .collect())
+        }
+
+        async fn block_height(&self) -> Result<u64> {
+            Ok(self.block_height.load(Ordering::Relaxed))
+        }
+
+        async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+            Ok((self.new_blockhash, 1_000))
+        }
+
+        async fn advance_nonce(
+            &self,
+            _payer: &Keypair,
+            _nonce_account: &Pubkey,
+            _current_nonce: Hash,
+        ) -> Result<Signature> {
+            Ok(Signature::new_unique())
+        }
+    }
+
+    struct Harness {
+        rpc: Arc<FakeRpc>,
+        payer: Arc<Keypair>,
+        entry: ResubmitEntry,
+        blockhash: Hash,
+    }
+
+    fn harness(landing: Option<bool>, err: Option<TransactionError>) -> Harness {
+        let payer = Arc::new(Keypair::new());
+        let blockhash = Hash::new_unique();
+        let new_blockhash = Hash::new_unique();
+        let message = Message::new_with_blockhash(
+            &[transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)],
+            Some(&payer.pubkey()),
+            &blockhash,
+        );
+        let transaction =
+            VersionedTransaction::try_new(VersionedMessage::Legacy(message), &[&payer]).unwrap();
+        let sent: Sent = Arc::default();
+        let rpc = Arc::new(FakeRpc {
+            sent: sent.clone(),
+            // Some(true)：原交易上链；Some(false)：重新签名后的交易上链
+            landing_blockhash: landing
+                .map(|original| if original { blockhash } else { new_blockhash }),
+            err,
+            confirmation_status: TransactionConfirmationStatus::Confirmed,
+            statuses_unavailable: AtomicBool::new(false),
+            block_height: AtomicU64::new(0),
+            new_blockhash,
+        });
+        let entry = ResubmitEntry { swqos_client: Arc::new(RecordingSwqos { sent }), transaction };
+        Harness { rpc, payer, entry, blockhash }
+    }
+
+    fn config(max_duration: Duration) -> ResubmitConfig {
+        ResubmitConfig { interval: Duration::from_millis(5), max_duration }
+    }
+
+    fn nonce_lifetime(h: &Harness) -> Lifetime {
+        Lifetime::Nonce {
+            nonce_account: Pubkey::new_unique(),
+            current_nonce: h.blockhash,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resubmit_until_landed() {
+        let h = harness(Some(true), None);
+        let handle = spawn(
+            h.rpc.clone(),
+            h.payer.clone(),
+            TradeType::Buy,
+            vec![h.entry.clone()],
+            nonce_lifetime(&h),
+            config(Duration::from_secs(5)),
+        );
+        let outcome = handle.wait().await.unwrap();
+        assert_eq!(
+            outcome,
+            ResubmitOutcome::Landed { signature: h.entry.transaction.signatures[0], rounds: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_landing_stops_resubmission() {
+        let h = harness(Some(true), Some(TransactionError::InsufficientFundsForFee));
+        let handle = spawn(
+            h.rpc.clone(),
+            h.payer.clone(),
+            TradeType::Buy,
+            vec![h.entry.clone()],
+            nonce_lifetime(&h),
+            config(Duration::from_secs(5)),
+        );
+        match handle.wait().await.unwrap() {
+            ResubmitOutcome::Failed { signature, rounds, .. } => {
+                assert_eq!(signature, h.entry.transaction.signatures[0]);
+                assert_eq!(rounds, 1);
+            },
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expires_without_landing() {
+        let h = harness(None, None);
+        let handle = spawn(
+            h.rpc.clone(),
+            h.payer.clone(),
+            TradeType::Buy,
+            vec![h.entry.clone()],
+            nonce_lifetime(&h),
+            config(Duration::from_millis(30)),
+        );
+        match handle.wait().await.unwrap() {
+            ResubmitOutcome::Expired { rounds } => assert!(rounds >= 1),
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+        assert!(h.rpc.sent.lock().unwrap().iter().all(|tx| tx == &h.entry.transaction));
+    }
+
+    #[tokio::test]
+    async fn test_resigns_on_new_blockhash_after_expiry() {
+        let h = harness(Some(false), None);
+        let handle = spawn(
+            h.rpc.clone(),
+            h.payer.clone(),
+            TradeType::Buy,
+            vec![h.entry.clone()],
+            Lifetime::Blockhash { last_valid_block_height: 100 },
+            config(Duration::from_secs(5)),
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!handle.is_finished());
+        h.rpc.block_height.store(101, Ordering::Relaxed);
+
+        let ResubmitOutcome::Landed { signature, .. } = handle.wait().await.unwrap() else {
+            panic!("re-signed transaction should land");
+        };
+        let sent = h.rpc.sent.lock().unwrap();
+        let resigned = sent.iter().find(|tx| tx.signatures[0] == signature).unwrap();
+        assert_ne!(signature, h.entry.transaction.signatures[0]);
+        assert_eq!(*resigned.message.recent_blockhash(), h.rpc.new_blockhash);
+        assert!(resigned.verify_with_results().iter().all(|ok| *ok));
+        assert!(sent.iter().any(|tx| tx == &h.entry.transaction));
+    }
+
+    #[tokio::test]
+    async fn test_processed_or_unknown_status_skips_resign() {
+        // 原交易已被处理但未确认 / 状态查询失败：区块高度已过期也不得重新签名
+        for processed in [true, false] {
+            let mut h = harness(Some(true), None);
+            let rpc = Arc::get_mut(&mut h.rpc).unwrap();
+            rpc.confirmation_status = TransactionConfirmationStatus::Processed;
+            rpc.statuses_unavailable = AtomicBool::new(!processed);
+            h.rpc.sent.lock().unwrap().push(h.entry.transaction.clone());
+            h.rpc.block_height.store(101, Ordering::Relaxed);
+            let handle = spawn(
+                h.rpc.clone(),
+                h.payer.clone(),
+                TradeType::Buy,
+                vec![h.entry.clone()],
+                Lifetime::Blockhash { last_valid_block_height: 100 },
+                config(Duration::from_millis(30)),
+            );
+            assert!(matches!(handle.wait().await.unwrap(), ResubmitOutcome::Expired { .. }));
+            assert!(h.rpc.sent.lock().unwrap().iter().all(|tx| tx == &h.entry.transaction));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_blockhash_resubmission_stops() {
+        let h = harness(None, None);
+        let handle = spawn(
+            h.rpc.clone(),
+            h.payer.clone(),
+            TradeType::Buy,
+            vec![h.entry.clone()],
+            Lifetime::Blockhash { last_valid_block_height: 100 },
+            config(Duration::from_secs(5)),
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(matches!(handle.cancel().await.unwrap(), ResubmitOutcome::Stopped { .. }));
+    }
+}
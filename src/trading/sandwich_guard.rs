@@ -0,0 +1,242 @@
+//! 发送前的三明治攻击风险检查
+//!
+//! 滑点越宽、池子越浅，抢跑者能从一笔交易中榨取的价值越多：抢跑者先在同方向买入，
+//! 最多把价格推到受害交易恰好满足最小输出的位置，再在受害交易之后反向卖出。
+//! 按常数乘积模型求出抢跑者的最优抢跑量与可榨取价值（抢跑者两次兑换都要支付池子手续费，
+//! 池子越深，推动同样价格所需的抢跑量越大，手续费越高），超过
+//! [`SandwichGuardConfig`] 的阈值时按模式打印警告或直接拒绝发送（[`TradeError::SANDWICH_RISK`]）。
+//!
+//! 只在经过公开中继发送时检查：所有 SWQOS 均为 Jito 且启用了三明治防护（`jitodontfront`）时跳过。
+//! 无法按储备估算的协议（CLMM、DAMM v2）不检查。
+
+use crate::TradingClient;
+use crate::common::Bps;
+use crate::constants::trade_consts::DEFAULT_SLIPPAGE;
+use crate::swqos::SwqosType;
+use crate::swqos::common::TradeError;
+use crate::trading::core::params::DexParamEnum;
+use solana_sdk::pubkey::Pubkey;
+
+/// 超过阈值时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SandwichGuardMode {
+    /// 打印警告后照常发送（默认）
+    #[default]
+    Warn,
+    /// 拒绝发送，返回 [`TradeError::SANDWICH_RISK`]
+    Block,
+}
+
+/// 三明治风险阈值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SandwichGuardConfig {
+    /// 可榨取价值占投入的最大比例
+    pub max_extractable_bps: Bps,
+    /// 可榨取价值的绝对上限（输入代币原始单位）
+    pub max_extractable_amount: Option<u64>,
+    /// 估算使用的单次兑换手续费
+    pub pool_fee_bps: Bps,
+    pub mode: SandwichGuardMode,
+}
+
+impl Default for SandwichGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_extractable_bps: Bps::new(100),
+            max_extractable_amount: None,
+            pool_fee_bps: Bps::new(25),
+            mode: SandwichGuardMode::Warn,
+        }
+    }
+}
+
+impl SandwichGuardConfig {
+    pub fn new(max_extractable_bps: Bps, mode: SandwichGuardMode) -> Self {
+        Self { max_extractable_bps, mode, ..Default::default() }
+    }
+
+    pub fn with_max_extractable_amount(mut self, max_extractable_amount: u64) -> Self {
+        self.max_extractable_amount = Some(max_extractable_amount);
+        self
+    }
+
+    pub fn with_pool_fee_bps(mut self, pool_fee_bps: Bps) -> Self {
+        self.pool_fee_bps = pool_fee_bps;
+        self
+    }
+}
+
+/// 三明治攻击估算结果（单位均为输入代币原始单位）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SandwichEstimate {
+    /// 收益最高的抢跑量（不超过使受害交易恰好达到最小输出的抢跑量）
+    pub front_run_amount: u64,
+    /// 抢跑者的往返收益
+    pub extractable: u64,
+    /// 可榨取价值占投入的比例（基点）
+    pub extractable_bps: Bps,
+}
+
+fn swap_out(reserve_in: u128, reserve_out: u128, amount_in: u128, fee_bps: u128) -> u128 {
+    let amount_in = amount_in * (10_000 - fee_bps) / 10_000;
+    if reserve_in + amount_in == 0 {
+        return 0;
+    }
+    reserve_out.saturating_mul(amount_in) / (reserve_in + amount_in)
+}
+
+/// 常数乘积池上抢跑者的最优收益（每次兑换扣除 `fee_bps` 手续费）
+pub fn estimate_sandwich(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    slippage_bps: Bps,
+    fee_bps: Bps,
+) -> SandwichEstimate {
+    if reserve_in == 0 || reserve_out == 0 || amount_in == 0 {
+        return SandwichEstimate::default();
+    }
+    let (x, y, a) = (reserve_in as u128, reserve_out as u128, amount_in as u128);
+    let fee = fee_bps.as_u128();
+    let expected = swap_out(x, y, a, fee);
+    let min_out = expected * (10_000 - slippage_bps.capped().as_u128()) / 10_000;
+    // 抢跑 f 之后受害交易的输出
+    let victim_out = |f: u128| {
+        let front_out = swap_out(x, y, f, fee);
+        swap_out(x + f, y - front_out, a, fee)
+    };
+    // 抢跑 f、受害交易、反向卖出后的收益
+    let profit = |f: u128| -> i128 {
+        let front_out = swap_out(x, y, f, fee);
+        let (x1, y1) = (x + f, y - front_out);
+        let victim = swap_out(x1, y1, a, fee);
+        let back_out = swap_out(y1 - victim, x1 + a, front_out, fee);
+        back_out as i128 - f as i128
+    };
+
+    // 二分查找满足 victim_out(f) >= min_out 的最大 f
+    let (mut low, mut high) = (0u128, x.saturating_mul(1_000));
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if victim_out(mid) >= min_out {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    // 在 [0, f_max] 上三分查找收益最高的 f
+    let (mut low, mut high) = (0u128, low);
+    while high - low > 2 {
+        let m1 = low + (high - low) / 3;
+        let m2 = high - (high - low) / 3;
+        if profit(m1) < profit(m2) {
+            low = m1 + 1;
+        } else {
+            high = m2 - 1;
+        }
+    }
+    let front_run = (low..=high).max_by_key(|f| profit(*f)).unwrap_or(0);
+    let extractable = profit(front_run).max(0) as u128;
+    if extractable == 0 {
+        return SandwichEstimate::default();
+    }
+    SandwichEstimate {
+        front_run_amount: u64::try_from(front_run).unwrap_or(u64::MAX),
+        extractable: u64::try_from(extractable).unwrap_or(u64::MAX),
+        extractable_bps: Bps::new(
+            u64::try_from(extractable * Bps::DENOMINATOR as u128 / a).unwrap_or(u64::MAX),
+        ),
+    }
+}
+
+/// 发送路径是否受保护：全部为 Jito 且启用了三明治防护
+pub fn is_protected_route(swqos_types: &[SwqosType], jito_sandwich_protection: bool) -> bool {
+    jito_sandwich_protection
+        && !swqos_types.is_empty()
+        && swqos_types.iter().all(|swqos_type| *swqos_type == SwqosType::Jito)
+}
+
+/// 按协议参数中的储备检查一笔交易，超过阈值时返回估算值与是否拒绝
+pub fn check_sandwich_risk(
+    config: &SandwichGuardConfig,
+    extension_params: &DexParamEnum,
+    mint: &Pubkey,
+    is_buy: bool,
+    amount_in: u64,
+    slippage_bps: Bps,
+) -> Option<(SandwichEstimate, Option<TradeError>)> {
+    let reserve_in = extension_params.input_reserve(mint, is_buy)?;
+    let reserve_out = extension_params.input_reserve(mint, !is_buy)?;
+    let estimate =
+        estimate_sandwich(reserve_in, reserve_out, amount_in, slippage_bps, config.pool_fee_bps);
+    let exceeds = estimate.extractable_bps > config.max_extractable_bps
+        || config.max_extractable_amount.is_some_and(|max| estimate.extractable > max);
+    if !exceeds {
+        return None;
+    }
+    let message = format!(
+        "sandwich risk on {}: {} ({}) extractable with {} slippage",
+        mint, estimate.extractable, estimate.extractable_bps, slippage_bps
+    );
+    match config.mode {
+        SandwichGuardMode::Warn => {
+            log::warn!("{}", message);
+            Some((estimate, None))
+        },
+        SandwichGuardMode::Block => Some((estimate, Some(TradeError::sandwich_risk(message)))),
+    }
+}
+
+impl TradingClient {
+    /// 按 `TradeConfig::sandwich_guard` 检查一笔交易；需要拒绝时返回错误
+    pub(crate) fn check_sandwich_guard(
+        &self,
+        extension_params: &DexParamEnum,
+        mint: &Pubkey,
+        is_buy: bool,
+        amount_in: u64,
+        slippage_basis_points: Option<Bps>,
+        jito_sandwich_protection: Option<bool>,
+    ) -> Option<TradeError> {
+        let config = self.sandwich_guard.as_ref()?;
+        let swqos_types: Vec<SwqosType> =
+            self.swqos_clients().iter().map(|client| client.get_swqos_type()).collect();
+        let protection = jito_sandwich_protection.unwrap_or(self.enable_jito_sandwich_protection);
+        if is_protected_route(&swqos_types, protection) {
+            return None;
+        }
+        let slippage = slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE);
+        check_sandwich_risk(config, extension_params, mint, is_buy, amount_in, slippage)?.1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_sandwich_grows_with_slippage() {
+        let fee = Bps::new(25);
+        let tight = estimate_sandwich(1_000_000_000, 1_000_000_000, 10_000_000, Bps::new(10), fee);
+        let wide =
+            estimate_sandwich(1_000_000_000, 1_000_000_000, 10_000_000, Bps::new(1_000), fee);
+        assert!(wide.extractable > tight.extractable);
+        assert!(wide.front_run_amount > tight.front_run_amount);
+        // 不计手续费时约等于滑点比例
+        let no_fee =
+            estimate_sandwich(1_000_000_000, 1_000_000_000, 10_000_000, Bps::new(1_000), Bps::ZERO);
+        assert!((Bps::new(990)..=Bps::new(1_010)).contains(&no_fee.extractable_bps));
+        // 深池中抢跑的手续费超过收益
+        let deep =
+            estimate_sandwich(100_000_000_000, 100_000_000_000, 10_000_000, Bps::new(1_000), fee);
+        assert_eq!(deep.extractable, 0);
+
+        let config = SandwichGuardConfig::default();
+        assert!(wide.extractable_bps > config.max_extractable_bps);
+        assert!(tight.extractable_bps <= config.max_extractable_bps);
+
+        assert!(is_protected_route(&[SwqosType::Jito], true));
+        assert!(!is_protected_route(&[SwqosType::Jito, SwqosType::Default], true));
+        assert!(!is_protected_route(&[SwqosType::Jito], false));
+    }
+}
@@ -0,0 +1,400 @@
+//! 配置校验与启动自检
+//!
+//! 大部分“第一笔交易就失败”的问题来自配置：SWQOS 缺少 token、RPC 不可达、
+//! 钱包余额不够支付小费、WSOL ATA 不存在等。
+//!
+//! - [`TradeConfig::validate`]：不访问网络的静态检查，存在失败项时返回错误；
+//! - [`TradingClient::startup_report`]：在静态检查之外实际访问 RPC，检查可达性、
+//!   commitment、各 SWQOS 客户端、付款钱包余额与 WSOL ATA，返回 [`StartupReport`]。
+
+use crate::TradingClient;
+use crate::common::cluster::current_cluster;
+use crate::common::fee_ledger::{priority_fee_lamports, sol_to_lamports};
+use crate::common::{GasFeeStrategy, TradeConfig};
+use crate::constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use crate::swqos::{SwqosConfig, SwqosType, TradeType};
+use anyhow::{Result, anyhow};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::signer::Signer;
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Instant;
+
+/// 单笔交易的基础签名费（lamports）
+const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+/// 检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Pass,
+    /// 可以交易，但可能影响上链率或成本
+    Warn,
+    /// 交易大概率失败
+    Fail,
+}
+
+impl CheckStatus {
+    fn icon(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        }
+    }
+}
+
+/// 单个检查项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckItem {
+    /// 检查项名称，例如 `rpc`、`swqos.Jito`、`payer_balance`
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckItem {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status, detail: detail.into() }
+    }
+
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, detail)
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, detail)
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, detail)
+    }
+}
+
+/// 启动自检报告
+#[derive(Debug, Clone, Default)]
+pub struct StartupReport {
+    pub items: Vec<CheckItem>,
+}
+
+impl StartupReport {
+    /// 没有失败项
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckItem> {
+        self.items.iter().filter(|item| item.status == CheckStatus::Fail)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &CheckItem> {
+        self.items.iter().filter(|item| item.status == CheckStatus::Warn)
+    }
+
+    /// 打印到标准输出
+    pub fn print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🔍 sol-trade-sdk 启动自检")?;
+        let width = self.items.iter().map(|item| item.name.len()).max().unwrap_or(0);
+        for item in &self.items {
+            writeln!(
+                f,
+                "  {} {:<width$}  {}",
+                item.status.icon(),
+                item.name,
+                item.detail,
+                width = width
+            )?;
+        }
+        write!(
+            f,
+            "  结果: {} 项通过, {} 项警告, {} 项失败",
+            self.items.iter().filter(|item| item.status == CheckStatus::Pass).count(),
+            self.warnings().count(),
+            self.failures().count()
+        )
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn check_swqos(config: &SwqosConfig, is_mainnet: bool) -> CheckItem {
+    let swqos_type = config.swqos_type();
+    let name = format!("swqos.{}", swqos_type);
+    if config.is_blacklisted() {
+        return CheckItem::warn(name, "已在黑名单中，启动时会被跳过");
+    }
+    let (token, url) = match config {
+        SwqosConfig::Default(endpoint) => {
+            if !is_http_url(endpoint) {
+                return CheckItem::fail(name, format!("RPC 地址无效: {:?}", endpoint));
+            }
+            return CheckItem::pass(name, "通过 RPC 直接发送");
+        },
+        SwqosConfig::Jito(token, _, url)
+        | SwqosConfig::NextBlock(token, _, url)
+        | SwqosConfig::Bloxroute(token, _, url)
+        | SwqosConfig::Temporal(token, _, url)
+        | SwqosConfig::ZeroSlot(token, _, url)
+        | SwqosConfig::Node1(token, _, url)
+        | SwqosConfig::FlashBlock(token, _, url)
+        | SwqosConfig::BlockRazor(token, _, url)
+        | SwqosConfig::Astralane(token, _, url)
+        | SwqosConfig::Stellium(token, _, url)
+        | SwqosConfig::Lightspeed(token, _, url)
+        | SwqosConfig::Soyas(token, _, url)
+        | SwqosConfig::Speedlanding(token, _, url) => (token, url),
+    };
+    // Jito 的 uuid 可选，其余服务必须提供 token
+    if token.trim().is_empty() && swqos_type != SwqosType::Jito {
+        return CheckItem::fail(name, "缺少 API token");
+    }
+    if swqos_type == SwqosType::Lightspeed && url.is_none() {
+        return CheckItem::fail(name, "Lightspeed 需要包含 api_key 的自定义 URL");
+    }
+    if !is_mainnet {
+        return CheckItem::warn(name, "非主网集群不附加小费，该服务可能拒绝交易");
+    }
+    CheckItem::pass(
+        name,
+        url.as_ref()
+            .map_or("使用内置区域地址".to_string(), |url| format!("自定义地址 {}", url)),
+    )
+}
+
+impl TradeConfig {
+    /// 静态检查配置（不访问网络），返回全部检查项
+    pub fn check(&self) -> Vec<CheckItem> {
+        let mut items = vec![];
+        if self.rpc_url.trim().is_empty() {
+            items.push(CheckItem::fail("rpc_url", "RPC 地址为空"));
+        } else if !is_http_url(&self.rpc_url) {
+            items.push(CheckItem::fail("rpc_url", format!("RPC 地址无效: {:?}", self.rpc_url)));
+        } else {
+            items.push(CheckItem::pass("rpc_url", self.rpc_url.clone()));
+        }
+
+        if self.commitment == CommitmentConfig::processed() {
+            items.push(CheckItem::warn(
+                "commitment",
+                "processed 级别的状态可能被回滚，建议使用 confirmed",
+            ));
+        } else {
+            items.push(CheckItem::pass("commitment", format!("{:?}", self.commitment.commitment)));
+        }
+
+        if self.swqos_configs.is_empty() {
+            items.push(CheckItem::fail("swqos", "未配置任何 SWQOS，交易无法发送"));
+        }
+        let mut seen = HashSet::new();
+        for config in &self.swqos_configs {
+            if !seen.insert(config.swqos_type()) {
+                items.push(CheckItem::warn(
+                    format!("swqos.{}", config.swqos_type()),
+                    "重复配置，同一笔交易会被发送多次",
+                ));
+                continue;
+            }
+            items.push(check_swqos(config, self.cluster.is_mainnet()));
+        }
+
+        if let Some(limit) = &self.wallet_rate_limit
+            && (limit.max_trades == 0 || limit.window.is_zero())
+        {
+            items.push(CheckItem::warn(
+                "wallet_rate_limit",
+                format!("max_trades={} window={:?} 配置无效", limit.max_trades, limit.window),
+            ));
+        }
+        items
+    }
+
+    /// 校验配置；存在失败项时返回包含全部失败原因的错误
+    pub fn validate(&self) -> Result<()> {
+        let failures: Vec<String> = self
+            .check()
+            .into_iter()
+            .filter(|item| item.status == CheckStatus::Fail)
+            .map(|item| format!("{}: {}", item.name, item.detail))
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("invalid trade config: {}", failures.join("; ")))
+        }
+    }
+}
+
+impl TradingClient {
+    /// 启动自检：配置检查 + RPC / SWQOS / 余额 / WSOL ATA 实际检查
+    ///
+    /// 小费按各 SWQOS 的最低小费估算，使用 [`Self::startup_report_with_gas_fee_strategy`]
+    /// 按实际的小费配置检查余额。
+    pub async fn startup_report(&self) -> StartupReport {
+        self.build_startup_report(None).await
+    }
+
+    /// 启动自检，按 `gas_fee_strategy` 中的买入小费与优先费检查付款钱包余额
+    pub async fn startup_report_with_gas_fee_strategy(
+        &self,
+        gas_fee_strategy: &GasFeeStrategy,
+    ) -> StartupReport {
+        self.build_startup_report(Some(gas_fee_strategy)).await
+    }
+
+    async fn build_startup_report(
+        &self,
+        gas_fee_strategy: Option<&GasFeeStrategy>,
+    ) -> StartupReport {
+        let mut items = self.config_checks.as_ref().clone();
+
+        let start = Instant::now();
+        match self.rpc.get_version().await {
+            Ok(version) => items.push(CheckItem::pass(
+                "rpc",
+                format!("solana-core {} ({}ms)", version.solana_core, start.elapsed().as_millis()),
+            )),
+            Err(e) => items.push(CheckItem::fail("rpc", format!("RPC 不可达: {}", e))),
+        }
+
+        let commitment = self.rpc.commitment();
+        match self.rpc.get_slot_with_commitment(commitment).await {
+            Ok(slot) => items.push(CheckItem::pass(
+                "rpc.commitment",
+                format!("{:?} slot {}", commitment.commitment, slot),
+            )),
+            Err(e) => items.push(CheckItem::fail(
+                "rpc.commitment",
+                format!("不支持 {:?}: {}", commitment.commitment, e),
+            )),
+        }
+
+        let swqos_clients = self.swqos_clients();
+        if swqos_clients.is_empty() {
+            items.push(CheckItem::fail("swqos.clients", "没有可用的 SWQOS 客户端"));
+        }
+        let is_mainnet = current_cluster().is_mainnet();
+        // 每个 provider 单笔交易的费用（小费 + 优先费 + 签名费）
+        let mut costs = vec![];
+        for client in swqos_clients.iter() {
+            let swqos_type = client.get_swqos_type();
+            if swqos_type != SwqosType::Default && is_mainnet {
+                match client.get_tip_account() {
+                    Ok(account) => items.push(CheckItem::pass(
+                        format!("swqos.{}.tip_account", swqos_type),
+                        account,
+                    )),
+                    Err(e) => items.push(CheckItem::fail(
+                        format!("swqos.{}.tip_account", swqos_type),
+                        e.to_string(),
+                    )),
+                }
+            }
+            let with_tip = swqos_type != SwqosType::Default && is_mainnet;
+            let cost = match gas_fee_strategy {
+                Some(strategy) => strategy
+                    .get_strategies(TradeType::Buy)
+                    .into_iter()
+                    .filter(|(t, _, _)| *t == swqos_type)
+                    .map(|(_, _, value)| {
+                        priority_fee_lamports(value.cu_limit, value.cu_price)
+                            + if with_tip { sol_to_lamports(value.tip) } else { 0 }
+                    })
+                    .max(),
+                None => Some(if with_tip { sol_to_lamports(swqos_type.min_tip()) } else { 0 }),
+            };
+            if let Some(cost) = cost {
+                costs.push(cost + BASE_FEE_LAMPORTS);
+            }
+        }
+
+        let payer = self.payer.pubkey();
+        match self.rpc.get_balance(&payer).await {
+            Ok(balance) => {
+                // 配置了最低保留额时，余额需在保留额之外支付手续费
+                let reserve = self.sol_reserve.map_or(0, |reserve| reserve.reserve_lamports);
+                let max_single = costs.iter().copied().max().unwrap_or(BASE_FEE_LAMPORTS) + reserve;
+                let total = costs.iter().sum::<u64>() + reserve;
+                let detail = format!(
+                    "{} 余额 {} lamports，保留 {}，单笔至少需要 {}，全部通道同时上链需要 {}",
+                    payer, balance, reserve, max_single, total
+                );
+                let status = if balance < max_single {
+                    CheckStatus::Fail
+                } else if balance < total {
+                    CheckStatus::Warn
+                } else {
+                    CheckStatus::Pass
+                };
+                items.push(CheckItem::new("payer_balance", status, detail));
+            },
+            Err(e) => items.push(CheckItem::fail("payer_balance", format!("查询余额失败: {}", e))),
+        }
+
+        let wsol_ata = crate::common::fast_fn::get_associated_token_address_with_program_id_fast(
+            &payer,
+            &WSOL_TOKEN_ACCOUNT,
+            &TOKEN_PROGRAM,
+        );
+        match self.rpc.get_token_account_balance(&wsol_ata).await {
+            Ok(balance) => items.push(CheckItem::pass(
+                "wsol_ata",
+                format!("{} 余额 {} WSOL", wsol_ata, balance.ui_amount_string),
+            )),
+            Err(_) => items.push(CheckItem::warn(
+                "wsol_ata",
+                format!("{} 不存在，使用 WSOL 的交易需先调用 create_wsol_ata", wsol_ata),
+            )),
+        }
+
+        StartupReport { items }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::cluster::Cluster;
+    use crate::swqos::SwqosRegion;
+
+    #[test]
+    fn test_validate_reports_config_failures() {
+        let config = TradeConfig::new(
+            "https://api.mainnet-beta.solana.com".to_string(),
+            vec![
+                SwqosConfig::Default("https://api.mainnet-beta.solana.com".to_string()),
+                SwqosConfig::Jito(String::new(), SwqosRegion::Frankfurt, None),
+            ],
+            CommitmentConfig::confirmed(),
+        );
+        assert!(config.validate().is_ok());
+
+        let mut config = config;
+        config.swqos_configs.push(SwqosConfig::Temporal(
+            String::new(),
+            SwqosRegion::Frankfurt,
+            None,
+        ));
+        config.swqos_configs.push(SwqosConfig::Lightspeed(
+            "key".to_string(),
+            SwqosRegion::Frankfurt,
+            None,
+        ));
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("swqos.Temporal") && err.contains("swqos.Lightspeed"));
+
+        let config = TradeConfig::new(String::new(), vec![], CommitmentConfig::processed())
+            .with_cluster(Cluster::Devnet);
+        let items = config.check();
+        let status = |name: &str| items.iter().find(|item| item.name == name).unwrap().status;
+        assert_eq!(status("rpc_url"), CheckStatus::Fail);
+        assert_eq!(status("swqos"), CheckStatus::Fail);
+        assert_eq!(status("commitment"), CheckStatus::Warn);
+    }
+}
@@ -0,0 +1,95 @@
+//! 卖出前账户状态检查
+//!
+//! 代币账户被冻结（黑名单）或 mint 设置了永久委托时，卖出交易会在链上失败，
+//! 优先费与小费却已支出。开启 `TradeConfig::check_sell_accounts` 后，
+//! 卖出前先读取 mint 与持仓账户，发现问题时直接返回 [`TradeError::ACCOUNT_FROZEN`]，不发送交易。
+
+use crate::common::SolanaRpcClient;
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
+use crate::swqos::common::TradeError;
+use anyhow::Result;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, AccountState, Mint};
+
+/// 检查已读取的 mint 与持仓账户数据
+///
+/// - 持仓账户状态为 Frozen；
+/// - mint 的永久委托不是 `owner` 本人（委托方可随时转走或销毁持仓）。
+pub fn inspect_sell_accounts(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    mint_data: &[u8],
+    token_account_data: Option<&[u8]>,
+) -> Option<TradeError> {
+    if let Some(data) = token_account_data
+        && let Ok(account) = StateWithExtensions::<TokenAccount>::unpack(data)
+        && account.base.state == AccountState::Frozen
+    {
+        return Some(TradeError::account_frozen(format!(
+            "token account of {} for mint {} is frozen",
+            owner, mint
+        )));
+    }
+    if let Ok(mint_state) = StateWithExtensions::<Mint>::unpack(mint_data)
+        && let Ok(extension) = mint_state.get_extension::<PermanentDelegate>()
+        && let Some(delegate) = Option::<Pubkey>::from(extension.delegate)
+        && delegate != *owner
+    {
+        return Some(TradeError::account_frozen(format!(
+            "mint {} has permanent delegate {}",
+            mint, delegate
+        )));
+    }
+    None
+}
+
+/// 通过 RPC 读取 mint 与 `owner` 的持仓账户并检查（持仓账户不存在时只检查 mint）
+pub async fn check_sell_accounts(
+    rpc: &SolanaRpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    use_seed_optimize: bool,
+) -> Result<Option<TradeError>> {
+    let mint_account: Account = rpc.get_account(mint).await?;
+    crate::utils::token::cache_decimals_from_mint_account(mint, &mint_account);
+    let token_account = get_associated_token_address_with_program_id_fast_use_seed(
+        owner,
+        mint,
+        &mint_account.owner,
+        use_seed_optimize,
+    );
+    let token_account_data = rpc.get_account(&token_account).await.ok().map(|a| a.data);
+    Ok(inspect_sell_accounts(owner, mint, &mint_account.data, token_account_data.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token::solana_program::program_pack::Pack;
+
+    fn token_account_data(state: spl_token::state::AccountState) -> Vec<u8> {
+        let account = spl_token::state::Account { amount: 1, state, ..Default::default() };
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        account.pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn test_inspect_sell_accounts() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut mint_data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint { is_initialized: true, decimals: 6, ..Default::default() }
+            .pack_into_slice(&mut mint_data);
+
+        let frozen = token_account_data(spl_token::state::AccountState::Frozen);
+        let error = inspect_sell_accounts(&owner, &mint, &mint_data, Some(&frozen)).unwrap();
+        assert!(error.is_account_frozen());
+
+        let active = token_account_data(spl_token::state::AccountState::Initialized);
+        assert!(inspect_sell_accounts(&owner, &mint, &mint_data, Some(&active)).is_none());
+        assert!(inspect_sell_accounts(&owner, &mint, &mint_data, None).is_none());
+    }
+}
@@ -0,0 +1,434 @@
+//! 外部构建交易的多通道提交
+//!
+//! [`TradingClient::send_transaction`] 让调用方自行构建的 `VersionedTransaction`（NFT mint、
+//! 任意合约调用等）复用 swap 的提交管线：多个 SWQOS 并行发送、任一上链后取消其余确认轮询，
+//! 并写入手续费账本、耗时分解与原始响应记录。
+//!
+//! - 未设置 [`SendOptions::gas_fee_strategy`] 时交易原样发送，只使用 `SwqosType::Default`（RPC）通道；
+//! - 设置后按 gas 策略为每个 provider 在交易末尾追加小费转账（由 account[0] 支付），
+//!   修改消息后需要重新签名，因此交易的所有签名者必须是 payer 或在 [`SendOptions::signers`] 中。
+//!   各 provider 的交易签名不同，发送给多个 provider 时交易必须使用 durable nonce。
+//!
+//! 与 swap 相同，按客户端的 `preflight` 策略在发送前模拟原始交易，挂载交易日志时写入待确认记录，
+//! 并响应 [`SendOptions::cancellation_token`]。
+
+use crate::TradingClient;
+use crate::common::cancellation::CancellationToken;
+use crate::common::{GasFeeStrategy, PreflightPolicy, RpcProviderRef};
+use crate::constants::SYSTEM_PROGRAM;
+use crate::swqos::common::TradeError;
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::common::split_tip;
+use crate::trading::core::async_executor::{
+    LandingMode, SignedSubmission, plan_providers, send_parallel,
+};
+use crate::trading::core::executor::check_preflight;
+use crate::trading::store::Journal;
+use anyhow::{Result, anyhow};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_system_interface::instruction::transfer;
+use solana_transaction_status::UiTransactionEncoding;
+use std::sync::Arc;
+
+/// 未设置 compute unit limit 时按单条指令的默认上限统计优先费
+const DEFAULT_INSTRUCTION_CU_LIMIT: u32 = 200_000;
+
+/// 外部交易的提交选项
+#[derive(Clone)]
+pub struct SendOptions {
+    /// 使用的 SWQOS（`None` 表示全部已配置的 provider）
+    pub swqos_types: Option<Vec<SwqosType>>,
+    /// 小费配置：按 `trade_type` 方向读取各 provider 的小费与拆分数（cu 配置不使用，保留交易自带的 compute budget）
+    pub gas_fee_strategy: Option<GasFeeStrategy>,
+    /// 读取 gas 策略与提交时使用的交易方向
+    pub trade_type: TradeType,
+    /// 追加小费后重新签名所需的其他签名者（payer 自动包含）
+    pub signers: Vec<Arc<Keypair>>,
+    /// 是否等待交易确认
+    pub wait_transaction_confirmed: bool,
+    /// 取消令牌（可选）：发送前取消则不发送，等待确认时取消则停止确认轮询
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl Default for SendOptions {
+    /// 原样通过 RPC（`SwqosType::Default`）发送，等待确认
+    fn default() -> Self {
+        Self {
+            swqos_types: None,
+            gas_fee_strategy: None,
+            trade_type: TradeType::Buy,
+            signers: Vec::new(),
+            wait_transaction_confirmed: true,
+            cancellation_token: None,
+        }
+    }
+}
+
+impl SendOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_swqos_types(mut self, swqos_types: Vec<SwqosType>) -> Self {
+        self.swqos_types = Some(swqos_types);
+        self
+    }
+
+    /// 按 gas 策略为每个 provider 追加小费
+    pub fn with_tips(mut self, gas_fee_strategy: GasFeeStrategy) -> Self {
+        self.gas_fee_strategy = Some(gas_fee_strategy);
+        self
+    }
+
+    pub fn with_trade_type(mut self, trade_type: TradeType) -> Self {
+        self.trade_type = trade_type;
+        self
+    }
+
+    pub fn with_signers(mut self, signers: Vec<Arc<Keypair>>) -> Self {
+        self.signers = signers;
+        self
+    }
+
+    pub fn with_wait_transaction_confirmed(mut self, wait: bool) -> Self {
+        self.wait_transaction_confirmed = wait;
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+}
+
+/// 在已编译的消息末尾追加小费转账（由 account[0] 支付）
+///
+/// 新的小费账户作为可写非签名账户、system program（缺失时）作为只读非签名账户插入静态账户表，
+/// 其后的静态账户与地址查找表账户的索引随之后移。
+pub fn append_tip_transfers(
+    message: &VersionedMessage,
+    tips: &[(Pubkey, u64)],
+) -> Result<VersionedMessage> {
+    let mut message = message.clone();
+    let (header, keys, instructions, loaded_count) = match &mut message {
+        VersionedMessage::Legacy(m) => (&mut m.header, &mut m.account_keys, &mut m.instructions, 0),
+        VersionedMessage::V0(m) => {
+            let loaded = m
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum::<usize>();
+            (&mut m.header, &mut m.account_keys, &mut m.instructions, loaded)
+        },
+    };
+    if keys.is_empty() {
+        return Err(anyhow!("Message has no fee payer"));
+    }
+    let readonly_start = keys.len() - header.num_readonly_unsigned_accounts as usize;
+    let signed_count = header.num_required_signatures as usize;
+    let writable_signed = signed_count - header.num_readonly_signed_accounts as usize;
+
+    let mut new_writable = Vec::new();
+    for (tip_account, _) in tips {
+        match keys.iter().position(|key| key == tip_account) {
+            Some(index)
+                if index < writable_signed || (signed_count..readonly_start).contains(&index) => {},
+            Some(_) => {
+                return Err(anyhow!("Tip account {} is read-only in the message", tip_account));
+            },
+            None if !new_writable.contains(tip_account) => new_writable.push(*tip_account),
+            None => {},
+        }
+    }
+    let add_system_program = !keys.contains(&SYSTEM_PROGRAM);
+    let added = new_writable.len() + add_system_program as usize;
+    if keys.len() + added + loaded_count > u8::MAX as usize + 1 {
+        return Err(anyhow!("Too many accounts to append tip transfers"));
+    }
+
+    // 旧索引 -> 新索引：只读非签名账户后移 new_writable 个，查找表账户后移 added 个
+    let static_count = keys.len();
+    let remap = |index: u8| -> u8 {
+        let index = index as usize;
+        let shifted = if index >= static_count {
+            index + added
+        } else if index >= readonly_start {
+            index + new_writable.len()
+        } else {
+            index
+        };
+        shifted as u8
+    };
+    for instruction in instructions.iter_mut() {
+        instruction.program_id_index = remap(instruction.program_id_index);
+        for account in instruction.accounts.iter_mut() {
+            *account = remap(*account);
+        }
+    }
+    keys.splice(readonly_start..readonly_start, new_writable.iter().copied());
+    if add_system_program {
+        keys.push(SYSTEM_PROGRAM);
+        header.num_readonly_unsigned_accounts += 1;
+    }
+
+    let index_of = |key: &Pubkey| keys.iter().position(|k| k == key).map(|i| i as u8);
+    let system_index = index_of(&SYSTEM_PROGRAM).unwrap_or_default();
+    let payer = keys[0];
+    for (tip_account, lamports) in tips {
+        let tip_index = index_of(tip_account).unwrap_or_default();
+        instructions.push(CompiledInstruction {
+            program_id_index: system_index,
+            accounts: vec![0, tip_index],
+            data: transfer(&payer, tip_account, *lamports).data,
+        });
+    }
+    Ok(message)
+}
+
+/// 交易的第一条指令是否为 system program 的 `AdvanceNonceAccount`
+pub fn uses_durable_nonce(message: &VersionedMessage) -> bool {
+    message.instructions().first().is_some_and(|instruction| {
+        message.static_account_keys().get(instruction.program_id_index as usize)
+            == Some(&SYSTEM_PROGRAM)
+            && instruction.data.starts_with(&[4, 0, 0, 0])
+    })
+}
+
+/// 交易自带的 compute unit limit 与 price（用于手续费统计）
+fn compute_budget(message: &VersionedMessage) -> (u32, u64) {
+    let keys = message.static_account_keys();
+    let (mut cu_limit, mut cu_price) = (None, 0);
+    for instruction in message.instructions() {
+        let Some(program_id) = keys.get(instruction.program_id_index as usize) else {
+            continue;
+        };
+        if !solana_compute_budget_interface::check_id(program_id) {
+            continue;
+        }
+        match instruction.data.split_first() {
+            Some((2, rest)) if rest.len() >= 4 => {
+                cu_limit = Some(u32::from_le_bytes(rest[..4].try_into().unwrap()));
+            },
+            Some((3, rest)) if rest.len() >= 8 => {
+                cu_price = u64::from_le_bytes(rest[..8].try_into().unwrap());
+            },
+            _ => {},
+        }
+    }
+    let default_limit = DEFAULT_INSTRUCTION_CU_LIMIT * message.instructions().len() as u32;
+    (cu_limit.unwrap_or(default_limit), cu_price)
+}
+
+impl TradingClient {
+    /// Submit an externally built transaction through the configured SWQOS providers
+    ///
+    /// 未追加小费时交易原样发送（必须已签名）；追加小费时按 [`SendOptions::signers`] 重新签名。
+    ///
+    /// # Returns
+    /// Returns `(success, signatures, error)` in the same shape as [`TradingClient::buy`]
+    pub async fn send_transaction(
+        &self,
+        transaction: VersionedTransaction,
+        options: SendOptions,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        let clients: Vec<_> = self
+            .swqos_clients()
+            .iter()
+            .filter(|client| {
+                options
+                    .swqos_types
+                    .as_ref()
+                    .is_none_or(|types| types.contains(&client.get_swqos_type()))
+            })
+            .cloned()
+            .collect();
+        let (cu_limit, cu_price) = compute_budget(&transaction.message);
+        if self.preflight != PreflightPolicy::Skip {
+            check_preflight(self.preflight, self.simulate_external(&transaction).await)?;
+        }
+
+        let Some(gas_fee_strategy) = &options.gas_fee_strategy else {
+            let submissions: Vec<_> = clients
+                .into_iter()
+                .filter(|client| client.get_swqos_type() == SwqosType::Default)
+                .map(|swqos_client| SignedSubmission {
+                    swqos_client,
+                    transaction: transaction.clone(),
+                    cu_limit,
+                    cu_price,
+                    tip: 0.0,
+                })
+                .collect();
+            if submissions.is_empty() {
+                return Err(anyhow!("No Rpc Default Swqos configured."));
+            }
+            return self.submit_signed(submissions, &options).await;
+        };
+
+        // provider、小费账户与费用上限的选取规则与 swap 交易一致；
+        // 追加小费的交易签名各不相同，多于一个 provider 时必须使用 durable nonce（最多一笔上链）
+        let landing = if uses_durable_nonce(&transaction.message) {
+            LandingMode::Exclusive
+        } else {
+            LandingMode::Single
+        };
+        let plans = plan_providers(&clients, gas_fee_strategy, options.trade_type, true, landing)?;
+        let mut submissions = Vec::with_capacity(plans.len());
+        for plan in plans {
+            let tip = plan.tip();
+            let transaction = if plan.tip_accounts.is_empty() {
+                transaction.clone()
+            } else {
+                let message = append_tip_transfers(
+                    &transaction.message,
+                    &split_tip(&plan.tip_accounts, tip)?,
+                )?;
+                self.resign(message, &options.signers)?
+            };
+            submissions.push(SignedSubmission {
+                swqos_client: plan.swqos_client,
+                transaction,
+                cu_limit,
+                cu_price,
+                tip,
+            });
+        }
+        self.submit_signed(submissions, &options).await
+    }
+
+    /// 使用 payer 与 `signers` 重新签名；消息要求的签名者必须全部可用
+    fn resign(
+        &self,
+        message: VersionedMessage,
+        signers: &[Arc<Keypair>],
+    ) -> Result<VersionedTransaction> {
+        let required =
+            &message.static_account_keys()[..message.header().num_required_signatures as usize];
+        let keypairs = required
+            .iter()
+            .map(|key| {
+                std::iter::once(&self.payer)
+                    .chain(signers)
+                    .find(|keypair| keypair.pubkey() == *key)
+                    .map(|keypair| keypair.as_ref())
+                    .ok_or_else(|| anyhow!("Missing signer {} required to append tips", key))
+            })
+            .collect::<Result<Vec<&Keypair>>>()?;
+        Ok(VersionedTransaction::try_new(message, &keypairs)?)
+    }
+
+    /// 发送前模拟原始交易（不校验签名）
+    pub(crate) async fn simulate_external(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>)> {
+        let rpc = self.rpc_provider.clone().unwrap_or_else(|| self.rpc.clone() as RpcProviderRef);
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: false,
+            commitment: Some(CommitmentConfig::processed()),
+            encoding: Some(UiTransactionEncoding::Base64),
+            ..Default::default()
+        };
+        let result = rpc.simulate_transaction(transaction, config).await?;
+        let signatures = transaction.signatures.first().copied().into_iter().collect();
+        match result.err {
+            Some(err) => Ok((false, signatures, Some(anyhow!("{:?}", err)))),
+            None => Ok((true, signatures, None)),
+        }
+    }
+
+    async fn submit_signed(
+        &self,
+        submissions: Vec<SignedSubmission>,
+        options: &SendOptions,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>)> {
+        // 外部交易的最后有效区块高度未知，恢复时改为校验 blockhash
+        let (success, signatures, error) = send_parallel(
+            submissions,
+            options.trade_type,
+            options.wait_transaction_confirmed,
+            Some(self.fee_ledger.clone()),
+            self.trade_store.clone().map(|store| Journal::new(store, None)),
+            self.confirm,
+            options.cancellation_token.clone(),
+            self.swqos_fanout.as_deref(),
+        )
+        .await?;
+        Ok((success, signatures, error.map(TradeError::from)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::{AddressLookupTableAccount, v0};
+
+    #[test]
+    fn test_append_tip_transfers_remaps_indexes() {
+        let payer = Keypair::new();
+        let program = Pubkey::new_unique();
+        let (writable, readonly, looked_up) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = Instruction::new_with_bytes(
+            program,
+            &[7],
+            vec![
+                AccountMeta::new(writable, false),
+                AccountMeta::new_readonly(readonly, false),
+                AccountMeta::new_readonly(looked_up, false),
+            ],
+        );
+        let table =
+            AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: vec![looked_up] };
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&payer.pubkey(), &[instruction], &[table], Hash::default())
+                .unwrap(),
+        );
+        let tip = Pubkey::new_unique();
+        let tipped = append_tip_transfers(&message, &[(tip, 1_000)]).unwrap();
+
+        let keys = tipped.static_account_keys();
+        let header = tipped.header();
+        // 小费账户位于可写非签名区末尾，system program 位于只读区末尾
+        assert_eq!(keys[2], tip);
+        assert_eq!(*keys.last().unwrap(), SYSTEM_PROGRAM);
+        assert_eq!(
+            header.num_readonly_unsigned_accounts,
+            message.header().num_readonly_unsigned_accounts + 1
+        );
+        assert!(2 < keys.len() - header.num_readonly_unsigned_accounts as usize);
+
+        // 原指令的账户（含查找表账户）仍指向相同地址
+        let resolve = |message: &VersionedMessage, index: u8| {
+            let keys = message.static_account_keys();
+            keys.get(index as usize).copied().unwrap_or_else(|| {
+                assert_eq!(index as usize, keys.len());
+                looked_up
+            })
+        };
+        let (before, after) = (&message.instructions()[0], &tipped.instructions()[0]);
+        assert_eq!(
+            resolve(&message, before.program_id_index),
+            resolve(&tipped, after.program_id_index)
+        );
+        for (old, new) in before.accounts.iter().zip(&after.accounts) {
+            assert_eq!(resolve(&message, *old), resolve(&tipped, *new));
+        }
+
+        let tip_ix = tipped.instructions().last().unwrap();
+        assert_eq!(keys[tip_ix.program_id_index as usize], SYSTEM_PROGRAM);
+        assert_eq!(tip_ix.accounts, vec![0, 2]);
+        assert!(!uses_durable_nonce(&tipped));
+        assert_eq!(compute_budget(&message), (DEFAULT_INSTRUCTION_CU_LIMIT, 0));
+    }
+}
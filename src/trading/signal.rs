@@ -0,0 +1,364 @@
+//! 外部信号执行器
+//!
+//! 将任意外部 alpha 来源（策略服务、Telegram 机器人、跟单信号等）产生的买卖指令
+//! 转换为 [`TradeSignal`] 并以 `Stream` 形式交给 [`SignalExecutor::run`]，即可自动执行。
+//!
+//! - 每个信号可覆盖滑点、协议参数；按 [`SignalUrgency`] 选择不同的 gas 策略；
+//! - 通过信号量限制并发执行数；所有许可都被占用时暂停拉取信号流（背压），
+//!   上游可据此降速或丢弃；
+//! - 可选地丢弃排队过久的信号，避免在行情已变化后才执行。
+
+use crate::common::{Bps, GasFeeStrategy};
+use crate::swqos::common::TradeError;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use crate::{TradeBuyParams, TradeSellParams, TradingClient};
+use anyhow::Result;
+use futures::{Stream, StreamExt};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// 信号方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignalSide {
+    Buy,
+    Sell,
+}
+
+/// 信号紧急程度，用于选择 gas 策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SignalUrgency {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// 外部交易信号
+#[derive(Clone)]
+pub struct TradeSignal {
+    /// 信号标识（由上游定义，用于关联执行结果）
+    pub id: String,
+    pub side: SignalSide,
+    pub dex_type: DexType,
+    pub mint: Pubkey,
+    /// 买入为 quote 代币数量（SOL 为 lamports），卖出为代币数量（最小单位）
+    pub amount: u64,
+    pub urgency: SignalUrgency,
+    /// 覆盖模板中的滑点
+    pub slippage_basis_points: Option<Bps>,
+    /// 覆盖模板中的协议参数
+    pub extension_params: Option<DexParamEnum>,
+    /// 信号产生时间；配合 `SignalExecutorConfig::max_signal_age` 丢弃过期信号
+    pub issued_at: Instant,
+}
+
+impl TradeSignal {
+    pub fn new(
+        id: impl Into<String>,
+        side: SignalSide,
+        dex_type: DexType,
+        mint: Pubkey,
+        amount: u64,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            side,
+            dex_type,
+            mint,
+            amount,
+            urgency: SignalUrgency::Normal,
+            slippage_basis_points: None,
+            extension_params: None,
+            issued_at: Instant::now(),
+        }
+    }
+
+    pub fn with_urgency(mut self, urgency: SignalUrgency) -> Self {
+        self.urgency = urgency;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage_basis_points: Bps) -> Self {
+        self.slippage_basis_points = Some(slippage_basis_points);
+        self
+    }
+
+    pub fn with_extension_params(mut self, extension_params: DexParamEnum) -> Self {
+        self.extension_params = Some(extension_params);
+        self
+    }
+}
+
+impl std::fmt::Debug for TradeSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TradeSignal")
+            .field("id", &self.id)
+            .field("side", &self.side)
+            .field("dex_type", &self.dex_type)
+            .field("mint", &self.mint)
+            .field("amount", &self.amount)
+            .field("urgency", &self.urgency)
+            .finish()
+    }
+}
+
+/// 买入模板：根据信号生成基础买入参数（返回 `None` 表示跳过该信号）
+pub type BuySignalTemplate = Arc<dyn Fn(&TradeSignal) -> Option<TradeBuyParams> + Send + Sync>;
+/// 卖出模板：根据信号生成基础卖出参数（返回 `None` 表示跳过该信号）
+pub type SellSignalTemplate = Arc<dyn Fn(&TradeSignal) -> Option<TradeSellParams> + Send + Sync>;
+
+/// 单个信号的执行结果
+#[derive(Debug)]
+pub struct SignalResult {
+    pub signal: TradeSignal,
+    /// 执行结果：(是否成功, 签名, 最后一个错误)；发送前失败时为 Err
+    pub outcome: Result<(bool, Vec<Signature>, Option<TradeError>), String>,
+    /// 从收到信号到执行完成的耗时
+    pub latency: Duration,
+}
+
+/// 结果回调
+pub type SignalResultHandler = Arc<dyn Fn(&SignalResult) + Send + Sync>;
+
+/// 信号执行器配置
+#[derive(Clone)]
+pub struct SignalExecutorConfig {
+    pub buy_template: Option<BuySignalTemplate>,
+    pub sell_template: Option<SellSignalTemplate>,
+    /// 最大并发执行数
+    pub max_concurrency: usize,
+    /// 信号从产生到开始执行的最长等待；超时丢弃
+    pub max_signal_age: Option<Duration>,
+    /// 按紧急程度覆盖 gas 策略（未配置的紧急程度使用模板中的策略）
+    pub urgency_gas_strategies: HashMap<SignalUrgency, GasFeeStrategy>,
+    /// 每个信号执行完成后的回调
+    pub on_result: Option<SignalResultHandler>,
+}
+
+impl Default for SignalExecutorConfig {
+    fn default() -> Self {
+        Self {
+            buy_template: None,
+            sell_template: None,
+            max_concurrency: 4,
+            max_signal_age: None,
+            urgency_gas_strategies: HashMap::new(),
+            on_result: None,
+        }
+    }
+}
+
+impl SignalExecutorConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_buy_template(mut self, template: BuySignalTemplate) -> Self {
+        self.buy_template = Some(template);
+        self
+    }
+
+    pub fn with_sell_template(mut self, template: SellSignalTemplate) -> Self {
+        self.sell_template = Some(template);
+        self
+    }
+
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    pub fn with_max_signal_age(mut self, max_signal_age: Duration) -> Self {
+        self.max_signal_age = Some(max_signal_age);
+        self
+    }
+
+    pub fn with_urgency_gas_strategy(
+        mut self,
+        urgency: SignalUrgency,
+        gas_fee_strategy: GasFeeStrategy,
+    ) -> Self {
+        self.urgency_gas_strategies.insert(urgency, gas_fee_strategy);
+        self
+    }
+
+    pub fn with_result_handler(mut self, handler: SignalResultHandler) -> Self {
+        self.on_result = Some(handler);
+        self
+    }
+}
+
+/// 一次 `run` 的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SignalRunStats {
+    /// 收到的信号数
+    pub received: u64,
+    /// 因缺少模板、模板返回 None 或 DEX 不匹配而跳过
+    pub skipped: u64,
+    /// 因排队过久而丢弃
+    pub expired: u64,
+    /// 执行成功（至少一笔交易成功）
+    pub succeeded: u64,
+    /// 执行失败（含发送前失败）
+    pub failed: u64,
+}
+
+enum PreparedTrade {
+    Buy(TradeBuyParams),
+    Sell(TradeSellParams),
+}
+
+/// 外部信号执行器
+pub struct SignalExecutor {
+    client: Arc<TradingClient>,
+    config: SignalExecutorConfig,
+}
+
+impl SignalExecutor {
+    pub fn new(client: Arc<TradingClient>, config: SignalExecutorConfig) -> Self {
+        Self { client, config }
+    }
+
+    pub fn config(&self) -> &SignalExecutorConfig {
+        &self.config
+    }
+
+    /// 由模板生成交易参数并应用信号级覆盖（不发送）
+    fn prepare(&self, signal: &TradeSignal) -> Option<PreparedTrade> {
+        let gas_fee_strategy = self.config.urgency_gas_strategies.get(&signal.urgency);
+        let prepared = match signal.side {
+            SignalSide::Buy => {
+                let mut params = (self.config.buy_template.as_ref()?)(signal)?;
+                params.mint = signal.mint;
+                params.input_token_amount = signal.amount;
+                if let Some(slippage) = signal.slippage_basis_points {
+                    params.slippage_basis_points = Some(slippage);
+                }
+                if let Some(extension_params) = &signal.extension_params {
+                    params.extension_params = extension_params.clone();
+                }
+                if let Some(strategy) = gas_fee_strategy {
+                    params.gas_fee_strategy = strategy.clone();
+                }
+                (params.dex_type == signal.dex_type).then_some(PreparedTrade::Buy(params))
+            },
+            SignalSide::Sell => {
+                let mut params = (self.config.sell_template.as_ref()?)(signal)?;
+                params.mint = signal.mint;
+                params.input_token_amount = signal.amount;
+                if let Some(slippage) = signal.slippage_basis_points {
+                    params.slippage_basis_points = Some(slippage);
+                }
+                if let Some(extension_params) = &signal.extension_params {
+                    params.extension_params = extension_params.clone();
+                }
+                if let Some(strategy) = gas_fee_strategy {
+                    params.gas_fee_strategy = strategy.clone();
+                }
+                (params.dex_type == signal.dex_type).then_some(PreparedTrade::Sell(params))
+            },
+        };
+        if prepared.is_none() {
+            log::warn!("signal {} skipped: template returned a different dex_type", signal.id);
+        }
+        prepared
+    }
+
+    fn is_expired(&self, signal: &TradeSignal) -> bool {
+        self.config
+            .max_signal_age
+            .is_some_and(|max_age| signal.issued_at.elapsed() > max_age)
+    }
+
+    /// 消费信号流直到其结束，等待所有在途信号执行完毕后返回统计
+    pub async fn run<S>(&self, signals: S) -> Result<SignalRunStats>
+    where
+        S: Stream<Item = TradeSignal> + Send,
+    {
+        let semaphore = Arc::new(Semaphore::new(self.config.max_concurrency.max(1)));
+        let mut signals = std::pin::pin!(signals);
+        let mut in_flight: JoinSet<SignalResult> = JoinSet::new();
+        let mut stats = SignalRunStats::default();
+
+        loop {
+            // 先拿到执行许可再拉取下一个信号：并发已满时不再消费信号流
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit?,
+                Some(done) = in_flight.join_next(), if !in_flight.is_empty() => {
+                    self.finish(done?, &mut stats);
+                    continue;
+                },
+            };
+            let Some(signal) = signals.next().await else {
+                break;
+            };
+            stats.received += 1;
+
+            if self.is_expired(&signal) {
+                log::warn!("signal {} expired before execution, dropping", signal.id);
+                stats.expired += 1;
+                continue;
+            }
+            let Some(prepared) = self.prepare(&signal) else {
+                stats.skipped += 1;
+                continue;
+            };
+
+            let client = self.client.clone();
+            in_flight.spawn(async move {
+                let _permit = permit;
+                let outcome = match prepared {
+                    PreparedTrade::Buy(params) => client.buy(params).await,
+                    PreparedTrade::Sell(params) => client.sell(params).await,
+                };
+                SignalResult {
+                    latency: signal.issued_at.elapsed(),
+                    outcome: outcome.map_err(|e| e.to_string()),
+                    signal,
+                }
+            });
+        }
+
+        while let Some(done) = in_flight.join_next().await {
+            self.finish(done?, &mut stats);
+        }
+        Ok(stats)
+    }
+
+    fn finish(&self, result: SignalResult, stats: &mut SignalRunStats) {
+        match &result.outcome {
+            Ok((true, _, _)) => stats.succeeded += 1,
+            _ => stats.failed += 1,
+        }
+        if let Some(handler) = &self.config.on_result {
+            handler(&result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_builders_and_config_defaults() {
+        let signal =
+            TradeSignal::new("s1", SignalSide::Buy, DexType::PumpFun, Pubkey::new_unique(), 10)
+                .with_urgency(SignalUrgency::High)
+                .with_slippage(Bps::new(300));
+        assert_eq!(signal.urgency, SignalUrgency::High);
+        assert_eq!(signal.slippage_basis_points, Some(Bps::new(300)));
+        assert!(signal.extension_params.is_none());
+
+        let config = SignalExecutorConfig::new().with_max_concurrency(0);
+        assert_eq!(config.max_concurrency, 1);
+        assert!(config.buy_template.is_none());
+        assert_eq!(SignalUrgency::default(), SignalUrgency::Normal);
+    }
+}
@@ -0,0 +1,358 @@
+//! 狙击（Sniper）编排模块
+//!
+//! 将「新池事件流 → 过滤 → 构建买入参数 → 一次性触发」串成一个可配置的流水线。
+//!
+//! 新池的 mint 与协议参数要到事件到达时才确定，因此这里按事件构建 [`TradeBuyParams`]；
+//! 账户与指令可提前确定的场景请使用预签名的 [`crate::trading::common::TransactionTemplate`]。
+//!
+//! SDK 本身不绑定任何数据源（Yellowstone gRPC、ShredStream、WebSocket 等），
+//! 调用方只需把自己的新池事件转换为 [`NewPoolEvent`] 并以 `Stream` 形式交给 [`Sniper::run`]。
+
+use crate::TradeBuyParams;
+use crate::TradingClient;
+use crate::common::cancellation::{CancellationToken, cancellable};
+use crate::swqos::common::TradeError;
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use futures::{Stream, StreamExt};
+use regex::Regex;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 新池事件
+///
+/// 由调用方从任意数据源解析得到，是 Sniper 过滤与构建买入参数的唯一输入。
+#[derive(Debug, Clone)]
+pub struct NewPoolEvent {
+    /// 池所属 DEX
+    pub dex_type: DexType,
+    /// 池地址（PumpFun 为 bonding curve 地址）
+    pub pool: Pubkey,
+    /// 目标代币 mint
+    pub mint: Pubkey,
+    /// 代币创建者
+    pub creator: Pubkey,
+    /// 代币名称（若数据源提供）
+    pub name: Option<String>,
+    /// 代币符号（若数据源提供）
+    pub symbol: Option<String>,
+    /// 初始流动性（以 quote 代币最小单位计，SOL 池为 lamports）
+    pub initial_liquidity: u64,
+    /// 事件所在 slot
+    pub slot: u64,
+}
+
+/// 过滤拒绝原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterRejection {
+    /// DEX 不在允许列表中
+    DexNotAllowed,
+    /// 创建者不在白名单中
+    CreatorNotAllowed,
+    /// 创建者在黑名单中
+    CreatorDenied,
+    /// 初始流动性低于下限
+    LiquidityTooLow,
+    /// 初始流动性高于上限
+    LiquidityTooHigh,
+    /// 名称不匹配正则
+    NameMismatch,
+    /// 符号不匹配正则
+    SymbolMismatch,
+}
+
+/// 新池过滤条件
+///
+/// 所有条件为「与」关系；未设置的条件视为通过。
+#[derive(Debug, Clone, Default)]
+pub struct SniperFilter {
+    /// 允许的 DEX（为空表示不限制）
+    pub dex_types: Vec<DexType>,
+    /// 创建者白名单（为空表示不限制）
+    pub creator_allow_list: HashSet<Pubkey>,
+    /// 创建者黑名单
+    pub creator_deny_list: HashSet<Pubkey>,
+    /// 初始流动性下限（含）
+    pub min_initial_liquidity: Option<u64>,
+    /// 初始流动性上限（含）
+    pub max_initial_liquidity: Option<u64>,
+    /// 名称正则；事件缺少名称时视为不匹配
+    pub name_regex: Option<Regex>,
+    /// 符号正则；事件缺少符号时视为不匹配
+    pub symbol_regex: Option<Regex>,
+}
+
+impl SniperFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dex_types(mut self, dex_types: Vec<DexType>) -> Self {
+        self.dex_types = dex_types;
+        self
+    }
+
+    pub fn with_creator_allow_list(mut self, creators: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.creator_allow_list = creators.into_iter().collect();
+        self
+    }
+
+    pub fn with_creator_deny_list(mut self, creators: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.creator_deny_list = creators.into_iter().collect();
+        self
+    }
+
+    pub fn with_liquidity_bounds(mut self, min: Option<u64>, max: Option<u64>) -> Self {
+        self.min_initial_liquidity = min;
+        self.max_initial_liquidity = max;
+        self
+    }
+
+    /// 设置名称正则（编译失败时返回错误）
+    pub fn with_name_regex(mut self, pattern: &str) -> Result<Self> {
+        self.name_regex = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// 设置符号正则（编译失败时返回错误）
+    pub fn with_symbol_regex(mut self, pattern: &str) -> Result<Self> {
+        self.symbol_regex = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// 检查事件是否通过全部过滤条件
+    pub fn check(&self, event: &NewPoolEvent) -> Result<(), FilterRejection> {
+        if !self.dex_types.is_empty() && !self.dex_types.contains(&event.dex_type) {
+            return Err(FilterRejection::DexNotAllowed);
+        }
+        if self.creator_deny_list.contains(&event.creator) {
+            return Err(FilterRejection::CreatorDenied);
+        }
+        if !self.creator_allow_list.is_empty() && !self.creator_allow_list.contains(&event.creator)
+        {
+            return Err(FilterRejection::CreatorNotAllowed);
+        }
+        if let Some(min) = self.min_initial_liquidity
+            && event.initial_liquidity < min
+        {
+            return Err(FilterRejection::LiquidityTooLow);
+        }
+        if let Some(max) = self.max_initial_liquidity
+            && event.initial_liquidity > max
+        {
+            return Err(FilterRejection::LiquidityTooHigh);
+        }
+        if let Some(re) = &self.name_regex
+            && !event.name.as_deref().is_some_and(|n| re.is_match(n))
+        {
+            return Err(FilterRejection::NameMismatch);
+        }
+        if let Some(re) = &self.symbol_regex
+            && !event.symbol.as_deref().is_some_and(|s| re.is_match(s))
+        {
+            return Err(FilterRejection::SymbolMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// 买入参数构建器：根据新池事件生成买入参数
+///
+/// 构建器在 Sniper 创建时预先准备好（gas 策略、滑点、ATA 选项等都已就绪），
+/// 触发时只需补全与事件相关的字段（mint、协议参数），尽量缩短热路径。
+/// 返回 `None` 表示该事件无法构建交易（例如缺少协议参数），将被跳过。
+pub type SnipeParamsBuilder = Arc<dyn Fn(&NewPoolEvent) -> Option<TradeBuyParams> + Send + Sync>;
+
+/// 狙击配置
+#[derive(Clone)]
+pub struct SniperConfig {
+    /// 过滤条件
+    pub filter: SniperFilter,
+    /// 买入参数构建器
+    pub params_builder: SnipeParamsBuilder,
+    /// 截止时长：从 `run` 开始计时，超时未触发则返回 [`SnipeOutcome::DeadlineExceeded`]
+    pub deadline: Option<Duration>,
+    /// 取消令牌：取消后 `run` 返回 [`SnipeOutcome::Cancelled`]，并传给构建器未设置令牌的买入
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+impl SniperConfig {
+    pub fn new(filter: SniperFilter, params_builder: SnipeParamsBuilder) -> Self {
+        Self {
+            filter,
+            params_builder,
+            deadline: None,
+            cancellation_token: None,
+        }
+    }
+
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+}
+
+/// 狙击结果
+#[derive(Debug)]
+pub enum SnipeOutcome {
+    /// 已触发买入
+    Fired {
+        /// 触发的事件
+        event: NewPoolEvent,
+        /// 是否至少有一笔交易成功
+        success: bool,
+        /// 所有提交的交易签名
+        signatures: Vec<Signature>,
+        /// 最后一个错误（如果全部失败）
+        error: Option<TradeError>,
+    },
+    /// 截止时间已到，未触发
+    DeadlineExceeded,
+    /// 事件流结束，未触发
+    StreamEnded,
+    /// 调用方取消，未触发
+    Cancelled,
+}
+
+/// 狙击编排器
+///
+/// 一次性（one-shot）：第一次通过过滤并成功构建参数的事件会触发买入，随后 `run` 返回。
+pub struct Sniper {
+    client: Arc<TradingClient>,
+    config: SniperConfig,
+}
+
+impl Sniper {
+    pub fn new(client: Arc<TradingClient>, config: SniperConfig) -> Self {
+        Self { client, config }
+    }
+
+    pub fn config(&self) -> &SniperConfig {
+        &self.config
+    }
+
+    /// 对单个事件执行过滤并构建买入参数（不发送）
+    pub fn prepare(&self, event: &NewPoolEvent) -> Option<TradeBuyParams> {
+        if let Err(reason) = self.config.filter.check(event) {
+            log::debug!("sniper skip {} ({:?}): {:?}", event.mint, event.dex_type, reason);
+            return None;
+        }
+        let mut params = (self.config.params_builder)(event)?;
+        if params.dex_type != event.dex_type {
+            log::warn!(
+                "sniper params builder returned dex_type {:?} for {:?} event, skipping",
+                params.dex_type,
+                event.dex_type
+            );
+            return None;
+        }
+        if params.cancellation_token.is_none() {
+            params.cancellation_token = self.config.cancellation_token.clone();
+        }
+        Some(params)
+    }
+
+    /// 消费新池事件流，直到触发一次买入、截止时间到达或事件流结束
+    pub async fn run<S>(&self, events: S) -> Result<SnipeOutcome>
+    where
+        S: Stream<Item = NewPoolEvent> + Send,
+    {
+        let deadline = self.config.deadline.map(|d| Instant::now() + d);
+        let mut events = std::pin::pin!(events);
+        let token = self.config.cancellation_token.as_ref();
+
+        loop {
+            let next = match deadline {
+                Some(at) => tokio::time::timeout_at(at, cancellable(token, events.next())).await,
+                None => Ok(cancellable(token, events.next()).await),
+            };
+            let next = match next {
+                Ok(Some(next)) => next,
+                Ok(None) => return Ok(SnipeOutcome::Cancelled),
+                Err(_) => return Ok(SnipeOutcome::DeadlineExceeded),
+            };
+            let Some(event) = next else {
+                return Ok(SnipeOutcome::StreamEnded);
+            };
+            let Some(params) = self.prepare(&event) else {
+                continue;
+            };
+
+            let (success, signatures, error) = self
+                .client
+                .buy(params)
+                .await
+                .map_err(|e| anyhow!("sniper buy failed for {}: {}", event.mint, e))?;
+            return Ok(SnipeOutcome::Fired { event, success, signatures, error });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> NewPoolEvent {
+        NewPoolEvent {
+            dex_type: DexType::PumpFun,
+            pool: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            name: Some("Doge Moon".to_string()),
+            symbol: Some("DMOON".to_string()),
+            initial_liquidity: 5_000_000_000,
+            slot: 1,
+        }
+    }
+
+    #[test]
+    fn test_empty_filter_accepts_everything() {
+        assert_eq!(SniperFilter::new().check(&event()), Ok(()));
+    }
+
+    #[test]
+    fn test_creator_lists() {
+        let ev = event();
+        let deny = SniperFilter::new().with_creator_deny_list([ev.creator]);
+        assert_eq!(deny.check(&ev), Err(FilterRejection::CreatorDenied));
+
+        let allow = SniperFilter::new().with_creator_allow_list([Pubkey::new_unique()]);
+        assert_eq!(allow.check(&ev), Err(FilterRejection::CreatorNotAllowed));
+
+        let allow = SniperFilter::new().with_creator_allow_list([ev.creator]);
+        assert_eq!(allow.check(&ev), Ok(()));
+    }
+
+    #[test]
+    fn test_liquidity_bounds_and_regex() {
+        let ev = event();
+        let f = SniperFilter::new().with_liquidity_bounds(Some(10_000_000_000), None);
+        assert_eq!(f.check(&ev), Err(FilterRejection::LiquidityTooLow));
+        let f = SniperFilter::new().with_liquidity_bounds(None, Some(1_000_000_000));
+        assert_eq!(f.check(&ev), Err(FilterRejection::LiquidityTooHigh));
+
+        let f = SniperFilter::new().with_name_regex("(?i)moon").unwrap();
+        assert_eq!(f.check(&ev), Ok(()));
+        let f = SniperFilter::new().with_symbol_regex("^PEPE").unwrap();
+        assert_eq!(f.check(&ev), Err(FilterRejection::SymbolMismatch));
+
+        let mut no_name = ev.clone();
+        no_name.name = None;
+        let f = SniperFilter::new().with_name_regex(".*").unwrap();
+        assert_eq!(f.check(&no_name), Err(FilterRejection::NameMismatch));
+    }
+
+    #[test]
+    fn test_dex_filter() {
+        let f = SniperFilter::new().with_dex_types(vec![DexType::PumpSwap]);
+        assert_eq!(f.check(&event()), Err(FilterRejection::DexNotAllowed));
+    }
+}
@@ -0,0 +1,145 @@
+//! 保留最低 SOL 余额
+//!
+//! 钱包的 SOL 全部用于买入后，将没有余额支付卖出的手续费与小费，持仓无法退出。
+//! 配置 [`SolReserveConfig`] 后，买入发送前查询付款钱包余额，
+//! 扣除本次买入投入的 SOL（仅 SOL 输入）与预估手续费后必须不低于保留额：
+//!
+//! - 默认拒绝发送，返回 [`TradeError::BELOW_SOL_RESERVE`]；
+//! - 开启 `clamp_buys` 时，SOL 输入的买入金额缩减为可用余额，可用余额为 0 时仍拒绝。
+//!
+//! 卖出不受限制（保留额正是为了卖出），模拟交易不检查。
+//! 预估手续费 = 买入策略中单个 SWQOS 的最高（优先费 + 小费）+ 基础签名费，不含新建账户的租金。
+
+use crate::common::GasFeeStrategy;
+use crate::common::fee_ledger::{priority_fee_lamports, sol_to_lamports};
+use crate::swqos::common::TradeError;
+use crate::swqos::{SwqosType, TradeType};
+use crate::{TradeTokenType, TradingClient};
+use anyhow::Result;
+use solana_sdk::signer::Signer;
+
+/// 单笔交易的基础签名费（lamports）
+const BASE_FEE_LAMPORTS: u64 = 5_000;
+
+/// 最低 SOL 保留配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolReserveConfig {
+    /// 始终保留的 SOL（lamports）
+    pub reserve_lamports: u64,
+    /// SOL 输入的买入超过可用余额时，缩减为可用余额而不是拒绝（默认：false）
+    pub clamp_buys: bool,
+}
+
+impl SolReserveConfig {
+    pub fn new(reserve_lamports: u64) -> Self {
+        Self { reserve_lamports, clamp_buys: false }
+    }
+
+    pub fn with_clamp_buys(mut self, clamp_buys: bool) -> Self {
+        self.clamp_buys = clamp_buys;
+        self
+    }
+
+    /// 扣除保留额与手续费后可用于买入的 SOL
+    pub fn spendable(&self, balance: u64, fee_lamports: u64) -> u64 {
+        balance.saturating_sub(self.reserve_lamports).saturating_sub(fee_lamports)
+    }
+
+    /// 按余额检查一笔买入：返回允许投入的 SOL（可能被缩减），不满足保留额时返回错误
+    ///
+    /// `sol_in` 为本次买入投入的 SOL（非 SOL 输入时为 0）。
+    pub fn check_buy(
+        &self,
+        balance: u64,
+        sol_in: u64,
+        fee_lamports: u64,
+    ) -> Result<u64, TradeError> {
+        let spendable = self.spendable(balance, fee_lamports);
+        if sol_in <= spendable && (sol_in > 0 || balance >= self.reserve_lamports + fee_lamports) {
+            return Ok(sol_in);
+        }
+        if self.clamp_buys && sol_in > 0 && spendable > 0 {
+            return Ok(spendable);
+        }
+        Err(TradeError::below_sol_reserve(format!(
+            "buy of {} lamports would leave less than the {} lamport SOL reserve \
+             (balance {}, estimated fees {})",
+            sol_in, self.reserve_lamports, balance, fee_lamports
+        )))
+    }
+}
+
+/// 按买入策略预估单笔交易的手续费（lamports）
+pub fn estimate_buy_fee(gas_fee_strategy: &GasFeeStrategy) -> u64 {
+    gas_fee_strategy
+        .get_strategies(TradeType::Buy)
+        .into_iter()
+        .map(|(swqos_type, _, value)| {
+            let tip = if swqos_type == SwqosType::Default { 0 } else { sol_to_lamports(value.tip) };
+            priority_fee_lamports(value.cu_limit, value.cu_price) + tip
+        })
+        .max()
+        .unwrap_or(0)
+        + BASE_FEE_LAMPORTS
+}
+
+impl TradingClient {
+    /// 扣除 `TradeConfig::sol_reserve` 保留额后，付款钱包可用的 SOL（未配置时为全部余额）
+    pub async fn spendable_sol(&self) -> Result<u64> {
+        let balance = self.rpc.get_balance(&self.payer.pubkey()).await?;
+        Ok(self.sol_reserve.map_or(balance, |reserve| reserve.spendable(balance, 0)))
+    }
+
+    /// 买入发送前检查保留额；允许缩减时直接修改 `input_token_amount`
+    pub(crate) async fn check_sol_reserve(
+        &self,
+        input_token_type: &TradeTokenType,
+        input_token_amount: &mut u64,
+        gas_fee_strategy: &GasFeeStrategy,
+        simulate: bool,
+    ) -> Result<Option<TradeError>> {
+        let Some(reserve) = self.sol_reserve else {
+            return Ok(None);
+        };
+        if simulate {
+            return Ok(None);
+        }
+        let balance = self.rpc.get_balance(&self.payer.pubkey()).await?;
+        let sol_in = if *input_token_type == TradeTokenType::SOL { *input_token_amount } else { 0 };
+        match reserve.check_buy(balance, sol_in, estimate_buy_fee(gas_fee_strategy)) {
+            Ok(allowed) => {
+                if allowed < sol_in {
+                    log::warn!(
+                        "buy reduced from {} to {} lamports to keep the SOL reserve",
+                        sol_in,
+                        allowed
+                    );
+                    *input_token_amount = allowed;
+                }
+                Ok(None)
+            },
+            Err(error) => Ok(Some(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_buy_keeps_reserve() {
+        let reserve = SolReserveConfig::new(50_000_000);
+        let fee = 10_000;
+        // 余额 1 SOL：最多投入 1 - 0.05 SOL - 手续费
+        assert_eq!(reserve.check_buy(1_000_000_000, 500_000_000, fee).ok(), Some(500_000_000));
+        let err = reserve.check_buy(1_000_000_000, 990_000_000, fee).unwrap_err();
+        assert!(err.is_below_sol_reserve());
+        let clamped = reserve.with_clamp_buys(true);
+        assert_eq!(clamped.check_buy(1_000_000_000, 990_000_000, fee).ok(), Some(949_990_000));
+        // 非 SOL 输入只需付得起手续费
+        assert_eq!(reserve.check_buy(50_010_000, 0, fee).ok(), Some(0));
+        assert!(clamped.check_buy(50_000_000, 0, fee).is_err());
+        assert!(clamped.check_buy(40_000_000, 1_000, fee).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+//! 交易日志持久化
+//!
+//! [`TradeStore`] 在交易生命周期的各个节点被自动调用：
+//! - 签名后、发送前：记录交易实体、blockhash 与最后有效区块高度（`record_signed`）；
+//! - 发送后：记录 SWQOS 返回结果（`record_submitted`）；
+//! - 确认后：记录上链结果（`record_landed`，仅在 `wait_transaction_confirmed = true` 时）；
+//! - 解析后：记录成交明细（`record_fill`，由 `TradingClient::record_fill` 触发）。
+//!
+//! `record_signed` 在发送前等待写入完成（预写日志），确保进程在发送后崩溃时仍留有待确认记录；
+//! 其余事件在后台任务中按顺序写入，不阻塞交易发送。写入失败仅记录日志。
+//! 进程重启后可调用 `TradingClient::recover_pending` 补全未完结（`Signed` / `Submitted`）的记录。
+//! 启用 `sqlite` feature 后可使用 [`SqliteTradeStore`] 作为开箱即用的实现。
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteTradeStore;
+
+use crate::parser::types::ParsedTradeInfo;
+use crate::trading::CallbackContext;
+use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use std::sync::Arc;
+
+/// 交易记录状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeRecordStatus {
+    /// 已签名，尚未发送
+    Signed,
+    /// 已发送至 SWQOS
+    Submitted,
+    /// 发送失败（未上链）
+    SubmitFailed,
+    /// 已上链且执行成功
+    Landed,
+    /// 已上链但执行失败（例如滑点超限）
+    Failed,
+}
+
+impl TradeRecordStatus {
+    /// 是否仍待确认（已签名或已发送，但尚无最终结果）
+    pub fn is_pending(&self) -> bool {
+        matches!(self, TradeRecordStatus::Signed | TradeRecordStatus::Submitted)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeRecordStatus::Signed => "signed",
+            TradeRecordStatus::Submitted => "submitted",
+            TradeRecordStatus::SubmitFailed => "submit_failed",
+            TradeRecordStatus::Landed => "landed",
+            TradeRecordStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "signed" => TradeRecordStatus::Signed,
+            "submitted" => TradeRecordStatus::Submitted,
+            "submit_failed" => TradeRecordStatus::SubmitFailed,
+            "landed" => TradeRecordStatus::Landed,
+            "failed" => TradeRecordStatus::Failed,
+            _ => return None,
+        })
+    }
+}
+
+/// 一笔交易的完整记录
+#[derive(Debug, Clone)]
+pub struct TradeRecord {
+    pub signature: String,
+    pub swqos_type: String,
+    pub trade_type: String,
+    pub status: TradeRecordStatus,
+    pub with_tip: bool,
+    /// 小费金额（SOL）
+    pub tip_amount: f64,
+    /// 签名后交易的 Base64 编码
+    pub transaction_base64: String,
+    /// 交易使用的 blockhash（使用 durable nonce 时为 nonce 值）
+    pub recent_blockhash: String,
+    /// blockhash 的最后有效区块高度；未知或使用 durable nonce 时为 None
+    pub last_valid_block_height: Option<u64>,
+    pub signed_at_ns: u64,
+    pub submitted_at_ns: Option<u64>,
+    pub landed_at_ns: Option<u64>,
+    pub error: Option<String>,
+    /// 解析出的成交明细
+    pub fills: Vec<ParsedTradeInfo>,
+}
+
+impl TradeRecord {
+    pub fn from_context(context: &CallbackContext, last_valid_block_height: Option<u64>) -> Self {
+        Self {
+            signature: context.signature.clone(),
+            swqos_type: context.swqos_type.to_string(),
+            trade_type: context.trade_type.to_string(),
+            status: TradeRecordStatus::Signed,
+            with_tip: context.with_tip,
+            tip_amount: context.tip_amount,
+            transaction_base64: context.to_base64(),
+            recent_blockhash: context.transaction.message.recent_blockhash().to_string(),
+            last_valid_block_height,
+            signed_at_ns: context.timestamp_ns,
+            submitted_at_ns: None,
+            landed_at_ns: None,
+            error: None,
+            fills: Vec::new(),
+        }
+    }
+}
+
+/// 交易日志存储 Trait
+#[async_trait::async_trait]
+pub trait TradeStore: Send + Sync {
+    /// 交易签名后（发送前）；`last_valid_block_height` 用于重启后判断交易是否已过期
+    async fn record_signed(
+        &self,
+        context: &CallbackContext,
+        last_valid_block_height: Option<u64>,
+    ) -> Result<()>;
+
+    /// 交易发送后；`error` 为 None 表示 SWQOS 已接收
+    async fn record_submitted(&self, signature: &str, error: Option<&str>) -> Result<()>;
+
+    /// 交易确认上链后；`success` 表示链上执行是否成功
+    async fn record_landed(
+        &self,
+        signature: &str,
+        success: bool,
+        error: Option<&str>,
+    ) -> Result<()>;
+
+    /// 解析出的成交明细
+    async fn record_fill(&self, fill: &ParsedTradeInfo) -> Result<()>;
+
+    /// 查询单笔交易记录
+    async fn get(&self, signature: &str) -> Result<Option<TradeRecord>>;
+
+    /// 所有尚未得到最终结果的交易（`Signed` / `Submitted`），按签名时间排序
+    async fn pending(&self) -> Result<Vec<TradeRecord>>;
+}
+
+/// Arc 包装的存储（便于共享）
+pub type TradeStoreRef = Arc<dyn TradeStore>;
+
+/// 一笔交易发送后产生的日志事件
+pub(crate) struct JournalEntry {
+    pub context: CallbackContext,
+    /// 发送前的待确认记录是否已写入；未写入时由后台任务补写
+    pub pending_recorded: bool,
+    pub submit_error: Option<String>,
+    /// 确认结果：(是否执行成功, 错误信息)；未等待确认时为 None
+    pub landed: Option<(bool, Option<String>)>,
+}
+
+/// 发送流程使用的交易日志句柄
+#[derive(Clone)]
+pub(crate) struct Journal {
+    store: TradeStoreRef,
+    last_valid_block_height: Option<u64>,
+}
+
+impl Journal {
+    pub fn new(store: TradeStoreRef, last_valid_block_height: Option<u64>) -> Self {
+        Self { store, last_valid_block_height }
+    }
+
+    /// 发送前写入待确认记录并等待完成；写入失败返回 false，由 [`Self::finish`] 补写
+    pub async fn record_pending(&self, context: &CallbackContext) -> bool {
+        match self.store.record_signed(context, self.last_valid_block_height).await {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("trade store write-ahead failed for {}: {}", context.signature, e);
+                false
+            },
+        }
+    }
+
+    /// 在后台按顺序写入发送与确认结果，不阻塞调用方
+    pub fn finish(&self, entry: JournalEntry) {
+        let journal = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = journal.write(&entry).await {
+                log::warn!("trade store write failed for {}: {}", entry.context.signature, e);
+            }
+        });
+    }
+
+    async fn write(&self, entry: &JournalEntry) -> Result<()> {
+        write_journal(self.store.as_ref(), entry, self.last_valid_block_height).await
+    }
+}
+
+async fn write_journal(
+    store: &dyn TradeStore,
+    entry: &JournalEntry,
+    last_valid_block_height: Option<u64>,
+) -> Result<()> {
+    let signature = entry.context.signature.as_str();
+    if !entry.pending_recorded {
+        store.record_signed(&entry.context, last_valid_block_height).await?;
+    }
+    store.record_submitted(signature, entry.submit_error.as_deref()).await?;
+    if let Some((success, error)) = &entry.landed {
+        store.record_landed(signature, *success, error.as_deref()).await?;
+    }
+    Ok(())
+}
+
+fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// 内存存储（进程退出后丢失，适用于测试与调试）
+#[derive(Default)]
+pub struct MemoryTradeStore {
+    records: DashMap<String, TradeRecord>,
+}
+
+impl MemoryTradeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn update(&self, signature: &str, f: impl FnOnce(&mut TradeRecord)) -> Result<()> {
+        let mut record = self
+            .records
+            .get_mut(signature)
+            .ok_or_else(|| anyhow!("Trade {} has not been recorded", signature))?;
+        f(&mut record);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TradeStore for MemoryTradeStore {
+    async fn record_signed(
+        &self,
+        context: &CallbackContext,
+        last_valid_block_height: Option<u64>,
+    ) -> Result<()> {
+        self.records.insert(
+            context.signature.clone(),
+            TradeRecord::from_context(context, last_valid_block_height),
+        );
+        Ok(())
+    }
+
+    async fn record_submitted(&self, signature: &str, error: Option<&str>) -> Result<()> {
+        self.update(signature, |record| {
+            record.submitted_at_ns = Some(now_ns());
+            record.status = match error {
+                Some(_) => TradeRecordStatus::SubmitFailed,
+                None => TradeRecordStatus::Submitted,
+            };
+            record.error = error.map(str::to_string);
+        })
+    }
+
+    async fn record_landed(
+        &self,
+        signature: &str,
+        success: bool,
+        error: Option<&str>,
+    ) -> Result<()> {
+        self.update(signature, |record| {
+            record.landed_at_ns = Some(now_ns());
+            record.status =
+                if success { TradeRecordStatus::Landed } else { TradeRecordStatus::Failed };
+            record.error = error.map(str::to_string);
+        })
+    }
+
+    async fn record_fill(&self, fill: &ParsedTradeInfo) -> Result<()> {
+        self.update(&fill.signature, |record| record.fills.push(fill.clone()))
+    }
+
+    async fn get(&self, signature: &str) -> Result<Option<TradeRecord>> {
+        Ok(self.records.get(signature).map(|r| r.clone()))
+    }
+
+    async fn pending(&self) -> Result<Vec<TradeRecord>> {
+        let mut records: Vec<TradeRecord> = self
+            .records
+            .iter()
+            .filter(|r| r.status.is_pending())
+            .map(|r| r.clone())
+            .collect();
+        records.sort_by_key(|r| r.signed_at_ns);
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swqos::{SwqosType, TradeType};
+    use solana_sdk::{
+        hash::Hash, message::Message, signature::Keypair, signer::Signer,
+        transaction::VersionedTransaction,
+    };
+
+    fn context() -> CallbackContext {
+        let payer = Keypair::new();
+        let message = Message::new_with_blockhash(&[], Some(&payer.pubkey()), &Hash::default());
+        let tx = VersionedTransaction::try_new(
+            solana_sdk::message::VersionedMessage::Legacy(message),
+            &[&payer],
+        )
+        .unwrap();
+        CallbackContext::new(tx, SwqosType::Jito, TradeType::Buy, true, 0.001)
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_journal_order() {
+        let store = MemoryTradeStore::new();
+        let entry = JournalEntry {
+            context: context(),
+            pending_recorded: false,
+            submit_error: None,
+            landed: Some((false, Some("ExceededSlippage".to_string()))),
+        };
+        write_journal(&store, &entry, Some(100)).await.unwrap();
+
+        let record = store.get(&entry.context.signature).await.unwrap().unwrap();
+        assert_eq!(record.status, TradeRecordStatus::Failed);
+        assert_eq!(record.swqos_type, "Jito");
+        assert!(record.submitted_at_ns.is_some());
+        assert_eq!(record.error.as_deref(), Some("ExceededSlippage"));
+        assert!(store.record_submitted("missing", None).await.is_err());
+        assert!(store.pending().await.unwrap().is_empty());
+
+        let pending = context();
+        store.record_signed(&pending, None).await.unwrap();
+        store.record_submitted(&pending.signature, None).await.unwrap();
+        let records = store.pending().await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].signature, pending.signature);
+    }
+
+    #[tokio::test]
+    async fn test_journal_writes_pending_record_before_send() {
+        let store = Arc::new(MemoryTradeStore::new());
+        let journal = Journal::new(store.clone(), Some(1_000));
+        let context = context();
+
+        // 发送前：已落盘为待确认记录，携带 blockhash 与最后有效区块高度
+        assert!(journal.record_pending(&context).await);
+        let pending = store.pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].status, TradeRecordStatus::Signed);
+        assert_eq!(pending[0].recent_blockhash, Hash::default().to_string());
+        assert_eq!(pending[0].last_valid_block_height, Some(1_000));
+
+        // 确认后：更新为最终状态，不重复写入签名记录
+        let signature = context.signature.clone();
+        let entry = JournalEntry {
+            context,
+            pending_recorded: true,
+            submit_error: None,
+            landed: Some((true, None)),
+        };
+        journal.write(&entry).await.unwrap();
+        let record = store.get(&signature).await.unwrap().unwrap();
+        assert_eq!(record.status, TradeRecordStatus::Landed);
+        assert_eq!(record.last_valid_block_height, Some(1_000));
+        assert!(store.pending().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            TradeRecordStatus::Signed,
+            TradeRecordStatus::Submitted,
+            TradeRecordStatus::SubmitFailed,
+            TradeRecordStatus::Landed,
+            TradeRecordStatus::Failed,
+        ] {
+            assert_eq!(TradeRecordStatus::parse(status.as_str()), Some(status));
+        }
+    }
+}
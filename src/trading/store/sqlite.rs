@@ -0,0 +1,274 @@
+//! SQLite 交易日志存储（`sqlite` feature）
+//!
+//! 表结构：
+//! - `trades`：每笔已签名交易一行，随生命周期更新状态；
+//! - `trade_fills`：解析出的成交明细，按签名关联，原始数据以 JSON 保存。
+
+use super::{TradeRecord, TradeRecordStatus, TradeStore, now_ns};
+use crate::parser::types::ParsedTradeInfo;
+use crate::trading::CallbackContext;
+use anyhow::{Result, anyhow};
+use parking_lot::Mutex;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::sync::Arc;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS trades (
+    signature TEXT PRIMARY KEY,
+    swqos_type TEXT NOT NULL,
+    trade_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    with_tip INTEGER NOT NULL,
+    tip_amount REAL NOT NULL,
+    transaction_base64 TEXT NOT NULL,
+    recent_blockhash TEXT NOT NULL DEFAULT '',
+    last_valid_block_height INTEGER,
+    signed_at_ns INTEGER NOT NULL,
+    submitted_at_ns INTEGER,
+    landed_at_ns INTEGER,
+    error TEXT
+);
+CREATE TABLE IF NOT EXISTS trade_fills (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    dex TEXT NOT NULL,
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    input_mint TEXT NOT NULL,
+    input_amount_raw TEXT NOT NULL,
+    output_mint TEXT NOT NULL,
+    output_amount_raw TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    raw_json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_trade_fills_signature ON trade_fills(signature);
+";
+
+/// 旧版本数据库缺少的列：(列名, 列定义)
+const TRADE_MIGRATIONS: &[(&str, &str)] =
+    &[("recent_blockhash", "TEXT NOT NULL DEFAULT ''"), ("last_valid_block_height", "INTEGER")];
+
+/// SQLite 交易日志存储
+///
+/// rusqlite 为同步接口，所有写入都在 `spawn_blocking` 中执行。
+#[derive(Clone)]
+pub struct SqliteTradeStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteTradeStore {
+    /// 打开（或创建）数据库文件并初始化表结构
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// 内存数据库（测试用）
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&conn)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || f(&conn.lock()))
+            .await
+            .map_err(|e| anyhow!("sqlite task failed: {}", e))?
+    }
+}
+
+#[async_trait::async_trait]
+impl TradeStore for SqliteTradeStore {
+    async fn record_signed(
+        &self,
+        context: &CallbackContext,
+        last_valid_block_height: Option<u64>,
+    ) -> Result<()> {
+        let record = TradeRecord::from_context(context, last_valid_block_height);
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO trades (signature, swqos_type, trade_type, status, \
+                 with_tip, tip_amount, transaction_base64, recent_blockhash, \
+                 last_valid_block_height, signed_at_ns) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    record.signature,
+                    record.swqos_type,
+                    record.trade_type,
+                    record.status.as_str(),
+                    record.with_tip,
+                    record.tip_amount,
+                    record.transaction_base64,
+                    record.recent_blockhash,
+                    record.last_valid_block_height.map(|h| h as i64),
+                    record.signed_at_ns as i64,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn record_submitted(&self, signature: &str, error: Option<&str>) -> Result<()> {
+        let signature = signature.to_string();
+        let error = error.map(str::to_string);
+        let status = match error {
+            Some(_) => TradeRecordStatus::SubmitFailed,
+            None => TradeRecordStatus::Submitted,
+        };
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE trades SET status = ?2, submitted_at_ns = ?3, error = ?4 \
+                 WHERE signature = ?1",
+                params![signature, status.as_str(), now_ns() as i64, error],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn record_landed(
+        &self,
+        signature: &str,
+        success: bool,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let signature = signature.to_string();
+        let error = error.map(str::to_string);
+        let status = if success { TradeRecordStatus::Landed } else { TradeRecordStatus::Failed };
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE trades SET status = ?2, landed_at_ns = ?3, error = ?4 \
+                 WHERE signature = ?1",
+                params![signature, status.as_str(), now_ns() as i64, error],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn record_fill(&self, fill: &ParsedTradeInfo) -> Result<()> {
+        let fill = fill.clone();
+        let raw_json = serde_json::to_string(&fill)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO trade_fills (signature, dex, pool, user, input_mint, \
+                 input_amount_raw, output_mint, output_amount_raw, slot, timestamp, raw_json) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    fill.signature,
+                    fill.dex,
+                    fill.pool.to_string(),
+                    fill.user.to_string(),
+                    fill.input_token.mint.to_string(),
+                    fill.input_token.amount_raw,
+                    fill.output_token.mint.to_string(),
+                    fill.output_token.amount_raw,
+                    fill.slot as i64,
+                    fill.timestamp,
+                    raw_json,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get(&self, signature: &str) -> Result<Option<TradeRecord>> {
+        let signature = signature.to_string();
+        self.with_conn(move |conn| {
+            let record = conn
+                .query_row(
+                    &format!("SELECT {} FROM trades WHERE signature = ?1", TRADE_COLUMNS),
+                    params![signature],
+                    read_record,
+                )
+                .optional()?;
+            let Some(mut record) = record else {
+                return Ok(None);
+            };
+            load_fills(conn, &mut record)?;
+            Ok(Some(record))
+        })
+        .await
+    }
+
+    async fn pending(&self) -> Result<Vec<TradeRecord>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM trades WHERE status IN (?1, ?2) ORDER BY signed_at_ns",
+                TRADE_COLUMNS
+            ))?;
+            let rows = stmt.query_map(
+                params![TradeRecordStatus::Signed.as_str(), TradeRecordStatus::Submitted.as_str()],
+                read_record,
+            )?;
+            let mut records = Vec::new();
+            for record in rows {
+                let mut record = record?;
+                load_fills(conn, &mut record)?;
+                records.push(record);
+            }
+            Ok(records)
+        })
+        .await
+    }
+}
+
+const TRADE_COLUMNS: &str = "signature, swqos_type, trade_type, status, with_tip, tip_amount, \
+                             transaction_base64, signed_at_ns, submitted_at_ns, landed_at_ns, \
+                             error, recent_blockhash, last_valid_block_height";
+
+fn migrate(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('trades')")?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (name, definition) in TRADE_MIGRATIONS {
+        if !columns.iter().any(|c| c == name) {
+            conn.execute_batch(&format!("ALTER TABLE trades ADD COLUMN {} {};", name, definition))?;
+        }
+    }
+    Ok(())
+}
+
+fn read_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<TradeRecord> {
+    Ok(TradeRecord {
+        signature: row.get(0)?,
+        swqos_type: row.get(1)?,
+        trade_type: row.get(2)?,
+        status: TradeRecordStatus::parse(&row.get::<_, String>(3)?)
+            .unwrap_or(TradeRecordStatus::Signed),
+        with_tip: row.get(4)?,
+        tip_amount: row.get(5)?,
+        transaction_base64: row.get(6)?,
+        signed_at_ns: row.get::<_, i64>(7)? as u64,
+        submitted_at_ns: row.get::<_, Option<i64>>(8)?.map(|v| v as u64),
+        landed_at_ns: row.get::<_, Option<i64>>(9)?.map(|v| v as u64),
+        error: row.get(10)?,
+        recent_blockhash: row.get(11)?,
+        last_valid_block_height: row.get::<_, Option<i64>>(12)?.map(|v| v as u64),
+        fills: Vec::new(),
+    })
+}
+
+fn load_fills(conn: &Connection, record: &mut TradeRecord) -> Result<()> {
+    let mut stmt =
+        conn.prepare("SELECT raw_json FROM trade_fills WHERE signature = ?1 ORDER BY id")?;
+    let rows = stmt.query_map(params![record.signature], |row| row.get::<_, String>(0))?;
+    for raw in rows {
+        record.fills.push(serde_json::from_str(&raw?)?);
+    }
+    Ok(())
+}
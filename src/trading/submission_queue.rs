@@ -0,0 +1,357 @@
+//! 按紧急程度排队的交易提交队列
+//!
+//! 多笔交易同时触发时（例如狙击与止损同时发生），构建、签名与提交会互相争抢 CPU、RPC
+//! 与 SWQOS 的频率额度。配置 [`SubmissionQueueConfig`] 后：
+//!
+//! - 同时处于构建 / 签名阶段的交易不超过 `max_in_flight` 笔，排队时按
+//!   [`Urgency`]（Snipe > Exit > Normal）优先、同级先到先得；所有 provider 的交易签名完成后释放名额；
+//! - 为有频率限制的 SWQOS 配置 [`ProviderRateLimit`] 后，该 provider 的发送额度优先分配给
+//!   紧急程度更高的交易；等待超过 `max_wait` 的交易放弃该 provider（其余 provider 照常发送）。
+//!
+//! 交易的紧急程度由 `TradeBuyParams::urgency` / `TradeSellParams::urgency` 指定，
+//! 未指定时买入为 [`Urgency::Normal`]，卖出为 [`Urgency::Exit`]。模拟交易不排队。
+
+use crate::swqos::SwqosType;
+use anyhow::{Result, anyhow};
+use parking_lot::Mutex;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, VecDeque};
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{Notify, oneshot};
+use tokio::time::Instant;
+
+/// 交易紧急程度（按声明顺序递增）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Urgency {
+    /// 普通交易（买入默认）
+    #[default]
+    Normal,
+    /// 退出持仓（卖出默认）
+    Exit,
+    /// 狙击：最高优先级
+    Snipe,
+}
+
+/// 单个 SWQOS 的发送频率限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderRateLimit {
+    /// `per` 时间内最多发送次数
+    pub max_requests: usize,
+    pub per: Duration,
+    /// 排队等待额度的最长时间，超过后本笔交易放弃该 provider
+    pub max_wait: Duration,
+}
+
+impl ProviderRateLimit {
+    pub fn new(max_requests: usize, per: Duration) -> Self {
+        Self { max_requests, per, max_wait: Duration::from_millis(500) }
+    }
+
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+}
+
+/// 提交队列配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionQueueConfig {
+    /// 同时构建 / 签名的最大交易数
+    pub max_in_flight: usize,
+    /// 有频率限制的 provider
+    pub provider_limits: HashMap<SwqosType, ProviderRateLimit>,
+}
+
+impl SubmissionQueueConfig {
+    pub fn new(max_in_flight: usize) -> Self {
+        Self { max_in_flight, provider_limits: HashMap::new() }
+    }
+
+    pub fn with_provider_limit(mut self, swqos_type: SwqosType, limit: ProviderRateLimit) -> Self {
+        self.provider_limits.insert(swqos_type, limit);
+        self
+    }
+}
+
+/// 排队序号：同一紧急程度内先到先得
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+fn next_seq() -> u64 {
+    NEXT_SEQ.fetch_add(1, Ordering::Relaxed)
+}
+
+struct Waiter {
+    key: (Urgency, Reverse<u64>),
+    tx: oneshot::Sender<GatePermit>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+struct GateState {
+    available: usize,
+    waiters: BinaryHeap<Waiter>,
+}
+
+/// 按紧急程度分配名额的并发闸门
+pub struct PriorityGate {
+    state: Mutex<GateState>,
+}
+
+/// 闸门名额，drop 时交给排队中紧急程度最高的交易
+pub struct GatePermit {
+    gate: Arc<PriorityGate>,
+}
+
+impl PriorityGate {
+    pub fn new(capacity: usize) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(GateState { available: capacity.max(1), waiters: BinaryHeap::new() }),
+        })
+    }
+
+    /// 获取名额；无空闲名额时按紧急程度排队
+    pub async fn acquire(self: &Arc<Self>, urgency: Urgency) -> GatePermit {
+        let rx = {
+            let mut state = self.state.lock();
+            if state.available > 0 && state.waiters.is_empty() {
+                state.available -= 1;
+                return GatePermit { gate: self.clone() };
+            }
+            let (tx, rx) = oneshot::channel();
+            state.waiters.push(Waiter { key: (urgency, Reverse(next_seq())), tx });
+            rx
+        };
+        // 名额随消息转交；等待方被取消时消息中的名额随通道一起 drop 并再次转交
+        rx.await.expect("priority gate dropped while waiting")
+    }
+
+    /// 当前排队数
+    pub fn queued(&self) -> usize {
+        self.state.lock().waiters.len()
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock();
+        while let Some(waiter) = state.waiters.pop() {
+            if waiter.tx.is_closed() {
+                continue;
+            }
+            let permit = GatePermit { gate: self.clone() };
+            match waiter.tx.send(permit) {
+                Ok(()) => return,
+                // 接收方刚好被取消：名额退回后 forget，避免递归释放
+                Err(permit) => std::mem::forget(permit),
+            }
+        }
+        state.available += 1;
+    }
+}
+
+impl Drop for GatePermit {
+    fn drop(&mut self) {
+        self.gate.release();
+    }
+}
+
+struct RateState {
+    sent: VecDeque<Instant>,
+    waiters: BTreeSet<(Reverse<Urgency>, u64)>,
+}
+
+/// 按紧急程度分配发送额度的滑动窗口限频器
+pub struct PriorityRateLimiter {
+    limit: ProviderRateLimit,
+    state: Mutex<RateState>,
+    notify: Notify,
+}
+
+impl PriorityRateLimiter {
+    pub fn new(limit: ProviderRateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(RateState { sent: VecDeque::new(), waiters: BTreeSet::new() }),
+            notify: Notify::new(),
+        }
+    }
+
+    /// 获取一次发送额度；排在更紧急的交易之后，超过 `max_wait` 时返回错误
+    pub async fn acquire(&self, urgency: Urgency) -> Result<()> {
+        let key = (Reverse(urgency), next_seq());
+        let deadline = Instant::now() + self.limit.max_wait;
+        self.state.lock().waiters.insert(key);
+        let _waiter = RateWaiter { limiter: self, key };
+        loop {
+            let mut notified = pin!(self.notify.notified());
+            notified.as_mut().enable();
+            let wait = {
+                let mut state = self.state.lock();
+                let now = Instant::now();
+                while state
+                    .sent
+                    .front()
+                    .is_some_and(|t| now.saturating_duration_since(*t) >= self.limit.per)
+                {
+                    state.sent.pop_front();
+                }
+                let is_first = state.waiters.first() == Some(&key);
+                if is_first && state.sent.len() < self.limit.max_requests.max(1) {
+                    state.sent.push_back(now);
+                    return Ok(());
+                }
+                if now >= deadline {
+                    return Err(anyhow!(
+                        "provider rate limit: no send slot within {:?}",
+                        self.limit.max_wait
+                    ));
+                }
+                // 轮到自己时等到最早一次发送移出窗口，否则等待前面的交易取得额度或放弃
+                let refill = match state.sent.front() {
+                    Some(t) if is_first => (*t + self.limit.per).saturating_duration_since(now),
+                    _ => deadline - now,
+                };
+                refill.min(deadline - now)
+            };
+            tokio::select! {
+                _ = notified => {},
+                _ = tokio::time::sleep(wait) => {},
+            }
+        }
+    }
+}
+
+/// 排队中的限频请求；完成、超时或被取消时移出队列并唤醒后面的请求
+struct RateWaiter<'a> {
+    limiter: &'a PriorityRateLimiter,
+    key: (Reverse<Urgency>, u64),
+}
+
+impl Drop for RateWaiter<'_> {
+    fn drop(&mut self) {
+        self.limiter.state.lock().waiters.remove(&self.key);
+        self.limiter.notify.notify_waiters();
+    }
+}
+
+/// 交易提交队列，`TradingClient` 的克隆之间共享
+pub struct SubmissionQueue {
+    trades: Arc<PriorityGate>,
+    providers: HashMap<SwqosType, PriorityRateLimiter>,
+}
+
+impl SubmissionQueue {
+    pub fn new(config: SubmissionQueueConfig) -> Self {
+        Self {
+            trades: PriorityGate::new(config.max_in_flight),
+            providers: config
+                .provider_limits
+                .into_iter()
+                .map(|(swqos_type, limit)| (swqos_type, PriorityRateLimiter::new(limit)))
+                .collect(),
+        }
+    }
+
+    /// 获取构建 / 签名名额
+    pub async fn acquire_trade(&self, urgency: Urgency) -> GatePermit {
+        self.trades.acquire(urgency).await
+    }
+
+    /// 获取 provider 的发送额度（未配置频率限制的 provider 直接通过）
+    pub async fn acquire_provider(&self, swqos_type: SwqosType, urgency: Urgency) -> Result<()> {
+        match self.providers.get(&swqos_type) {
+            Some(limiter) => limiter.acquire(urgency).await,
+            None => Ok(()),
+        }
+    }
+
+    /// 排队等待构建名额的交易数
+    pub fn queued(&self) -> usize {
+        self.trades.queued()
+    }
+}
+
+/// 一笔交易在执行器中持有的排队凭证
+#[derive(Clone)]
+pub(crate) struct SubmissionTicket {
+    pub queue: Arc<SubmissionQueue>,
+    pub urgency: Urgency,
+    /// 构建 / 签名名额：执行器与各 provider 任务各持有一份，全部签名完成后释放
+    pub permit: Arc<GatePermit>,
+}
+
+impl SubmissionTicket {
+    pub async fn acquire(queue: Arc<SubmissionQueue>, urgency: Urgency) -> Self {
+        let permit = Arc::new(queue.acquire_trade(urgency).await);
+        Self { queue, urgency, permit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gate_serves_highest_urgency_first() {
+        let gate = PriorityGate::new(1);
+        let held = gate.acquire(Urgency::Normal).await;
+        let order = Arc::new(Mutex::new(vec![]));
+        let mut tasks = vec![];
+        for urgency in [Urgency::Normal, Urgency::Exit, Urgency::Snipe] {
+            let (gate, order) = (gate.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                let _permit = gate.acquire(urgency).await;
+                order.lock().push(urgency);
+            }));
+            tokio::task::yield_now().await;
+        }
+        while gate.queued() < 3 {
+            tokio::task::yield_now().await;
+        }
+        drop(held);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*order.lock(), vec![Urgency::Snipe, Urgency::Exit, Urgency::Normal]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_prefers_urgent_and_times_out() {
+        let limiter = Arc::new(PriorityRateLimiter::new(
+            ProviderRateLimit::new(1, Duration::from_millis(200))
+                .with_max_wait(Duration::from_millis(300)),
+        ));
+        limiter.acquire(Urgency::Normal).await.unwrap();
+        let normal = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(Urgency::Normal).await }
+        });
+        tokio::task::yield_now().await;
+        let snipe = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(Urgency::Snipe).await }
+        });
+        // 下一个额度在 200ms 后可用，先分配给 Snipe；Normal 要到 400ms 才有额度，超过等待上限
+        assert!(snipe.await.unwrap().is_ok());
+        assert!(normal.await.unwrap().is_err());
+    }
+}
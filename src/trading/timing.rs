@@ -0,0 +1,225 @@
+//! 交易延迟分解（结构化数据）
+//!
+//! `perf-trace` 只把各阶段耗时写入日志；这里把同样的数据记录为 [`TradeTiming`]，
+//! 通过 [`crate::TradeResult::timing`] 与回调上下文 [`crate::trading::CallbackContext::timing`]
+//! 获取，便于做延迟回归统计而无需解析日志。
+//!
+//! - 交易级：指令构建、发送前总耗时、发送前模拟、并发发送、总耗时；
+//! - provider 级：交易组装、签名、提交、确认（仅等待确认时）；
+//! - slot：发送时的 slot 与上链 slot（仅等待确认时），两者之差即 [`TradeTiming::slots_waited`]。
+//!
+//! 记录按签名保存在进程内的环形缓冲区中（最近 [`TIMING_CAPACITY`] 条）。
+
+use crate::swqos::SwqosType;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use solana_sdk::signature::Signature;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// 保留的最近记录条数
+pub const TIMING_CAPACITY: usize = 1024;
+
+/// 单个 SWQOS provider 的耗时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderTiming {
+    pub swqos_type: SwqosType,
+    pub signature: Signature,
+    /// 组装交易（不含签名）
+    pub build: Duration,
+    /// 签名
+    pub sign: Duration,
+    /// 提交到 provider（回调中尚未发送时为 `None`）
+    pub submit: Option<Duration>,
+    /// 提交后等待确认（未等待确认时为 `None`）
+    pub confirm: Option<Duration>,
+    /// 交易上链的 slot（未等待确认或未上链时为 `None`）
+    pub landed_slot: Option<u64>,
+}
+
+/// 一次交易的耗时分解
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TradeTiming {
+    /// 构建协议指令
+    pub instruction_build: Duration,
+    /// 从开始到发送前（含指令构建与中间件处理）
+    pub before_submit: Duration,
+    /// 发送前模拟（未开启时为 `None`）
+    pub preflight: Option<Duration>,
+    /// 并发发送阶段（等待确认时包含确认）
+    pub send: Duration,
+    /// 总耗时
+    pub total: Duration,
+    /// 各 provider 的耗时；未等待确认时，返回时部分 provider 可能尚未完成
+    pub providers: Vec<ProviderTiming>,
+    /// 开始发送时的 slot（仅等待确认时查询）
+    pub submit_slot: Option<u64>,
+}
+
+impl TradeTiming {
+    /// 提交最快的 provider
+    pub fn fastest_submit(&self) -> Option<&ProviderTiming> {
+        self.providers.iter().filter(|p| p.submit.is_some()).min_by_key(|p| p.submit)
+    }
+
+    /// 最早上链的 slot
+    pub fn landed_slot(&self) -> Option<u64> {
+        self.providers.iter().filter_map(|p| p.landed_slot).min()
+    }
+
+    /// 从发送到上链经过的 slot 数
+    pub fn slots_waited(&self) -> Option<u64> {
+        Some(self.landed_slot()?.saturating_sub(self.submit_slot?))
+    }
+}
+
+/// 交易级阶段耗时
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TradeStages {
+    pub instruction_build: Duration,
+    pub before_submit: Duration,
+    pub preflight: Option<Duration>,
+    pub send: Duration,
+    pub total: Duration,
+    pub submit_slot: Option<u64>,
+}
+
+static PROVIDER_TIMINGS: Lazy<Mutex<VecDeque<ProviderTiming>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(TIMING_CAPACITY)));
+static TRADE_TIMINGS: Lazy<Mutex<VecDeque<(Signature, TradeStages)>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(TIMING_CAPACITY)));
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, item: T) {
+    if queue.len() >= TIMING_CAPACITY {
+        queue.pop_front();
+    }
+    queue.push_back(item);
+}
+
+pub(crate) fn record_provider(timing: ProviderTiming) {
+    push_bounded(&mut PROVIDER_TIMINGS.lock(), timing);
+}
+
+pub(crate) fn record_trade(signatures: &[Signature], stages: TradeStages) {
+    let mut trades = TRADE_TIMINGS.lock();
+    for signature in signatures {
+        push_bounded(&mut trades, (*signature, stages));
+    }
+}
+
+/// 按交易签名查询耗时分解（签名来自 `buy` / `sell` 的返回值）
+pub fn trade_timing(signatures: &[Signature]) -> Option<TradeTiming> {
+    let stages = TRADE_TIMINGS
+        .lock()
+        .iter()
+        .rev()
+        .find(|(signature, _)| signatures.contains(signature))
+        .map(|(_, stages)| *stages)?;
+    let providers = PROVIDER_TIMINGS
+        .lock()
+        .iter()
+        .filter(|p| signatures.contains(&p.signature))
+        .copied()
+        .collect();
+    Some(TradeTiming {
+        instruction_build: stages.instruction_build,
+        before_submit: stages.before_submit,
+        preflight: stages.preflight,
+        send: stages.send,
+        total: stages.total,
+        providers,
+        submit_slot: stages.submit_slot,
+    })
+}
+
+/// provider 任务内由底层函数标记的阶段点
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct StageMarks {
+    pub sign: Option<Duration>,
+    pub confirm_started_at: Option<Instant>,
+    pub landed_slot: Option<u64>,
+}
+
+tokio::task_local! {
+    static STAGE_MARKS: Cell<StageMarks>;
+}
+
+/// 在独立的阶段标记作用域中运行 provider 任务
+pub(crate) fn scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    STAGE_MARKS.scope(Cell::new(StageMarks::default()), future)
+}
+
+fn update_marks(f: impl FnOnce(&mut StageMarks)) {
+    let _ = STAGE_MARKS.try_with(|cell| {
+        let mut marks = cell.get();
+        f(&mut marks);
+        cell.set(marks);
+    });
+}
+
+/// 记录签名耗时（作用域外调用时忽略）
+pub(crate) fn record_sign(duration: Duration) {
+    update_marks(|marks| marks.sign = Some(duration));
+}
+
+/// 标记开始等待确认（作用域外调用时忽略）
+pub(crate) fn mark_confirm_started() {
+    update_marks(|marks| marks.confirm_started_at = Some(Instant::now()));
+}
+
+/// 记录交易上链的 slot（作用域外调用时忽略）
+pub(crate) fn record_landed_slot(slot: u64) {
+    update_marks(|marks| marks.landed_slot = Some(slot));
+}
+
+pub(crate) fn stage_marks() -> StageMarks {
+    STAGE_MARKS.try_with(|cell| cell.get()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stage_marks_and_lookup() {
+        let marks = scope(async {
+            record_sign(Duration::from_micros(40));
+            mark_confirm_started();
+            record_landed_slot(105);
+            stage_marks()
+        })
+        .await;
+        assert_eq!(marks.sign, Some(Duration::from_micros(40)));
+        assert!(marks.confirm_started_at.is_some());
+        assert_eq!(marks.landed_slot, Some(105));
+        // 作用域外不记录
+        record_sign(Duration::from_micros(1));
+        assert!(stage_marks().sign.is_none());
+
+        let signature = Signature::new_unique();
+        record_provider(ProviderTiming {
+            swqos_type: SwqosType::Jito,
+            signature,
+            build: Duration::from_micros(100),
+            sign: Duration::from_micros(40),
+            submit: Some(Duration::from_millis(5)),
+            confirm: None,
+            landed_slot: Some(105),
+        });
+        assert!(trade_timing(&[signature]).is_none());
+        record_trade(
+            &[signature],
+            TradeStages {
+                total: Duration::from_millis(6),
+                submit_slot: Some(100),
+                ..Default::default()
+            },
+        );
+        let timing = trade_timing(&[signature]).unwrap();
+        assert_eq!(timing.total, Duration::from_millis(6));
+        assert_eq!(timing.fastest_submit().unwrap().swqos_type, SwqosType::Jito);
+        assert_eq!(timing.slots_waited(), Some(5));
+    }
+}
@@ -0,0 +1,301 @@
+//! 分批（tranche）执行大额订单
+//!
+//! 按池子深度把一笔大额买入/卖出拆成多笔：每笔的价格冲击不超过
+//! [`TrancheConfig::max_price_impact_bps`]（常数乘积池按输入侧储备估算，见
+//! [`constant_product_impact_bps`]），笔数受 `max_tranches` 限制。
+//!
+//! - 各笔依次提交，可选地在两笔之间等待若干 slot，让套利者把价格拉回；
+//! - 协议参数中的储备来自模板，不会在笔与笔之间刷新。因此第 i 笔的滑点取前 i 笔的
+//!   累计冲击加上 `slippage_buffer_bps`，保证即使价格没有恢复也不会因最小输出过高而失败；
+//! - 完成后返回每笔结果与汇总统计（成交量、失败笔数、手续费、耗时）。
+
+use crate::common::Bps;
+use crate::utils::quote::{constant_product_impact_bps, max_amount_in_for_impact};
+use crate::{TradeBuyParams, TradeResult, TradeSellParams, TradingClient};
+use anyhow::{Result, anyhow};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 两笔之间的间隔
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrancheSpacing {
+    /// 上一笔完成后立即提交下一笔
+    #[default]
+    Sequential,
+    /// 上一笔完成后至少等待 N 个 slot
+    Slots(u64),
+}
+
+/// 分批执行配置
+#[derive(Debug, Clone)]
+pub struct TrancheConfig {
+    /// 单笔最大价格冲击（基点）
+    pub max_price_impact_bps: Bps,
+    /// 最大笔数；深度不足时最后一笔的冲击可能超过上限
+    pub max_tranches: usize,
+    /// 单笔最小数量，避免拆得过碎（0 表示不限制）
+    pub min_tranche_amount: u64,
+    pub spacing: TrancheSpacing,
+    /// 在累计冲击之外额外容忍的滑点；None 时使用模板中的滑点
+    pub slippage_buffer_bps: Option<Bps>,
+    /// 某笔失败后是否停止剩余批次
+    pub stop_on_failure: bool,
+}
+
+impl Default for TrancheConfig {
+    fn default() -> Self {
+        Self {
+            max_price_impact_bps: Bps::new(100),
+            max_tranches: 10,
+            min_tranche_amount: 0,
+            spacing: TrancheSpacing::Sequential,
+            slippage_buffer_bps: Some(Bps::new(50)),
+            stop_on_failure: true,
+        }
+    }
+}
+
+impl TrancheConfig {
+    pub fn new(max_price_impact_bps: Bps, max_tranches: usize) -> Self {
+        Self { max_price_impact_bps, max_tranches, ..Default::default() }
+    }
+
+    pub fn with_spacing(mut self, spacing: TrancheSpacing) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_min_tranche_amount(mut self, min_tranche_amount: u64) -> Self {
+        self.min_tranche_amount = min_tranche_amount;
+        self
+    }
+
+    pub fn with_slippage_buffer(mut self, slippage_buffer_bps: Option<Bps>) -> Self {
+        self.slippage_buffer_bps = slippage_buffer_bps;
+        self
+    }
+
+    pub fn with_stop_on_failure(mut self, stop_on_failure: bool) -> Self {
+        self.stop_on_failure = stop_on_failure;
+        self
+    }
+}
+
+/// 计划中的一笔
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedTranche {
+    pub amount: u64,
+    /// 按模板储备估算的本笔价格冲击（基点）
+    pub price_impact_bps: Bps,
+    /// 本笔使用的滑点；None 表示沿用模板
+    pub slippage_basis_points: Option<Bps>,
+}
+
+/// 按输入侧储备拆分数量
+///
+/// 每笔数量尽量相等，余数计入最后一笔。
+pub fn plan_tranches(total: u64, reserve_in: u64, config: &TrancheConfig) -> Vec<PlannedTranche> {
+    if total == 0 {
+        return vec![];
+    }
+    let max_tranches = config.max_tranches.max(1) as u64;
+    let per_tranche_cap = max_amount_in_for_impact(reserve_in, config.max_price_impact_bps).max(1);
+    let mut count = total.div_ceil(per_tranche_cap).clamp(1, max_tranches);
+    if config.min_tranche_amount > 0 {
+        count = count.min((total / config.min_tranche_amount).max(1));
+    }
+    let base = total / count;
+    let mut planned = Vec::with_capacity(count as usize);
+    let mut cumulative_impact = 0u64;
+    for index in 0..count {
+        let amount = if index + 1 == count { total - base * (count - 1) } else { base };
+        let price_impact_bps = Bps::new(constant_product_impact_bps(reserve_in, amount));
+        cumulative_impact = cumulative_impact.saturating_add(price_impact_bps.as_u64());
+        planned.push(PlannedTranche {
+            amount,
+            price_impact_bps,
+            slippage_basis_points: config
+                .slippage_buffer_bps
+                .map(|buffer| Bps::saturating(cumulative_impact.saturating_add(buffer.as_u64()))),
+        });
+    }
+    planned
+}
+
+/// 单笔执行结果
+#[derive(Debug)]
+pub struct TrancheFill {
+    pub index: usize,
+    pub planned: PlannedTranche,
+    /// 发送前失败时为 Err
+    pub result: Result<TradeResult, String>,
+}
+
+impl TrancheFill {
+    pub fn is_success(&self) -> bool {
+        self.result.as_ref().is_ok_and(|result| result.success)
+    }
+}
+
+/// 分批执行汇总
+#[derive(Debug, Default)]
+pub struct TrancheReport {
+    pub fills: Vec<TrancheFill>,
+    /// 计划总量
+    pub total_amount: u64,
+    /// 成功批次的输入总量
+    pub filled_amount: u64,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 未执行（因失败提前停止）的批次数
+    pub skipped: usize,
+    /// 成功批次支付的优先费与小费合计（lamports）
+    pub fee_lamports: u64,
+    pub elapsed: Duration,
+}
+
+impl TrancheReport {
+    /// 成交比例（0.0 - 1.0）
+    pub fn fill_ratio(&self) -> f64 {
+        if self.total_amount == 0 {
+            return 0.0;
+        }
+        self.filled_amount as f64 / self.total_amount as f64
+    }
+
+    pub(crate) fn record(&mut self, fill: TrancheFill) {
+        if fill.is_success() {
+            self.succeeded += 1;
+            self.filled_amount += fill.planned.amount;
+            if let Ok(result) = &fill.result {
+                self.fee_lamports += result.fee_lamports();
+            }
+        } else {
+            self.failed += 1;
+        }
+        self.fills.push(fill);
+    }
+}
+
+impl TradingClient {
+    /// 分批买入：按 `params.extension_params` 中的池子储备拆分 `params.input_token_amount`
+    ///
+    /// 不支持按储备估算冲击的协议（CLMM、DAMM v2）返回错误。
+    pub async fn buy_in_tranches(
+        &self,
+        params: TradeBuyParams,
+        config: &TrancheConfig,
+    ) -> Result<TrancheReport> {
+        let reserve_in =
+            params.extension_params.input_reserve(&params.mint, true).ok_or_else(|| {
+                anyhow!("{:?} pool depth is not available for tranching", params.dex_type)
+            })?;
+        let planned = plan_tranches(params.input_token_amount, reserve_in, config);
+        self.run_tranches(params.input_token_amount, planned, config, |tranche| {
+            let mut leg = params.clone();
+            leg.input_token_amount = tranche.amount;
+            leg.fixed_output_token_amount = None;
+            if let Some(slippage) = tranche.slippage_basis_points {
+                leg.slippage_basis_points = Some(slippage);
+            }
+            async move { self.buy_with_result(leg).await }
+        })
+        .await
+    }
+
+    /// 分批卖出：按 `params.extension_params` 中的池子储备拆分 `params.input_token_amount`
+    pub async fn sell_in_tranches(
+        &self,
+        params: TradeSellParams,
+        config: &TrancheConfig,
+    ) -> Result<TrancheReport> {
+        let reserve_in =
+            params.extension_params.input_reserve(&params.mint, false).ok_or_else(|| {
+                anyhow!("{:?} pool depth is not available for tranching", params.dex_type)
+            })?;
+        let planned = plan_tranches(params.input_token_amount, reserve_in, config);
+        self.run_tranches(params.input_token_amount, planned, config, |tranche| {
+            let mut leg = params.clone();
+            leg.input_token_amount = tranche.amount;
+            leg.fixed_output_token_amount = None;
+            if let Some(slippage) = tranche.slippage_basis_points {
+                leg.slippage_basis_points = Some(slippage);
+            }
+            async move { self.sell_with_result(leg).await }
+        })
+        .await
+    }
+
+    async fn run_tranches<F, Fut>(
+        &self,
+        total_amount: u64,
+        planned: Vec<PlannedTranche>,
+        config: &TrancheConfig,
+        execute: F,
+    ) -> Result<TrancheReport>
+    where
+        F: Fn(PlannedTranche) -> Fut,
+        Fut: std::future::Future<Output = Result<TradeResult>>,
+    {
+        let start = Instant::now();
+        let mut report = TrancheReport { total_amount, ..Default::default() };
+        let count = planned.len();
+        for (index, tranche) in planned.into_iter().enumerate() {
+            if index > 0
+                && let TrancheSpacing::Slots(slots) = config.spacing
+            {
+                self.wait_slots(slots).await?;
+            }
+            let result = execute(tranche).await.map_err(|e| e.to_string());
+            let fill = TrancheFill { index, planned: tranche, result };
+            let failed = !fill.is_success();
+            report.record(fill);
+            if failed && config.stop_on_failure {
+                report.skipped = count - index - 1;
+                break;
+            }
+        }
+        report.elapsed = start.elapsed();
+        Ok(report)
+    }
+
+    /// 等待链上 slot 前进 `slots` 个
+    async fn wait_slots(&self, slots: u64) -> Result<()> {
+        if slots == 0 {
+            return Ok(());
+        }
+        let target = self.rpc.get_slot().await? + slots;
+        while self.rpc.get_slot().await? < target {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_tranches_respects_impact_and_caps() {
+        let config = TrancheConfig::new(Bps::new(100), 10).with_slippage_buffer(Some(Bps::new(50)));
+        // 单笔上限约为储备的 1.01%
+        let planned = plan_tranches(50_000, 1_000_000, &config);
+        assert_eq!(planned.len(), 5);
+        assert_eq!(planned.iter().map(|t| t.amount).sum::<u64>(), 50_000);
+        assert!(planned.iter().all(|t| t.price_impact_bps <= Bps::new(100)));
+        let slippages: Vec<Bps> = planned.iter().filter_map(|t| t.slippage_basis_points).collect();
+        assert!(slippages.windows(2).all(|w| w[0] < w[1]));
+
+        // 深度不足时受 max_tranches 限制
+        let planned = plan_tranches(1_000_000, 1_000_000, &config);
+        assert_eq!(planned.len(), 10);
+
+        let config = config.with_min_tranche_amount(20_000).with_slippage_buffer(None);
+        let planned = plan_tranches(50_000, 1_000_000, &config);
+        assert_eq!(planned.len(), 2);
+        assert_eq!(planned[1].amount, 25_000);
+        assert!(planned.iter().all(|t| t.slippage_basis_points.is_none()));
+        assert!(plan_tranches(0, 1_000_000, &config).is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! 资金划转（SOL / SPL 代币）
+//!
+//! [`TradingClient::transfer_sol_to`] / [`TradingClient::transfer_token`] 为资金归集、提现等
+//! 场景提供与交易相同的发送通道：按 [`TransferOptions`] 选择 SWQOS，使用 gas 策略中
+//! 卖出方向（`TradeType::Sell`）的 cu_limit / cu_price / 小费配置，并受手续费上限约束。
+//!
+//! 安全检查：
+//! - 收款地址必须在 `TradeConfig::with_transfer_allow_list` 设置的白名单内（未设置时不限制）；
+//! - 不允许转给 payer 自己、不允许 0 数量；
+//! - 发送前检查余额是否充足。
+//!
+//! 代币转账使用 `transfer_checked`，收款方 ATA 不存在时在同一笔交易中幂等创建（由 payer 支付租金），
+//! 带 Transfer Hook 的 Token-2022 mint 会自动附加 hook 账户。
+
+use crate::TradingClient;
+use crate::common::GasFeeStrategy;
+use crate::common::fast_fn::{
+    create_associated_token_account_idempotent_fast,
+    get_associated_token_address_with_program_id_fast,
+    get_associated_token_address_with_program_id_fast_use_seed,
+};
+use crate::common::transfer_hook::{append_transfer_hook_accounts, fetch_transfer_hook_info};
+use crate::common::units::{ComputeUnits, FeeSpec, Lamports, MicroLamports};
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::common::{InstructionLayout, TransactionTemplate, assemble_instructions};
+use anyhow::{Result, anyhow};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_system_interface::instruction::transfer;
+use std::str::FromStr;
+
+/// 默认转账 cu_limit（含收款方 ATA 幂等创建）
+pub const DEFAULT_TRANSFER_CU_LIMIT: u32 = 60_000;
+/// 默认转账 cu_price（micro-lamports）
+pub const DEFAULT_TRANSFER_CU_PRICE: u64 = 100_000;
+
+/// 转账发送选项
+#[derive(Clone)]
+pub struct TransferOptions {
+    /// 发送使用的 SWQOS（必须已在 `TradeConfig` 中配置）
+    pub swqos_type: SwqosType,
+    /// gas 策略，读取 `swqos_type` 在卖出方向的配置
+    pub gas_fee_strategy: GasFeeStrategy,
+    /// 是否等待交易确认
+    pub wait_transaction_confirmed: bool,
+}
+
+impl Default for TransferOptions {
+    /// 通过 RPC（`SwqosType::Default`）发送，无小费，等待确认
+    fn default() -> Self {
+        let gas_fee_strategy = GasFeeStrategy::new();
+        let fees = FeeSpec::new(
+            ComputeUnits(DEFAULT_TRANSFER_CU_LIMIT),
+            MicroLamports(DEFAULT_TRANSFER_CU_PRICE),
+            Lamports::ZERO,
+        );
+        gas_fee_strategy.set_normal_fees(&[SwqosType::Default], fees, fees);
+        Self {
+            swqos_type: SwqosType::Default,
+            gas_fee_strategy,
+            wait_transaction_confirmed: true,
+        }
+    }
+}
+
+impl TransferOptions {
+    pub fn new(swqos_type: SwqosType, gas_fee_strategy: GasFeeStrategy) -> Self {
+        Self {
+            swqos_type,
+            gas_fee_strategy,
+            wait_transaction_confirmed: true,
+        }
+    }
+
+    pub fn with_wait_transaction_confirmed(mut self, wait: bool) -> Self {
+        self.wait_transaction_confirmed = wait;
+        self
+    }
+}
+
+/// 校验收款地址与数量
+pub fn check_transfer_recipient(
+    allow_list: Option<&[Pubkey]>,
+    payer: &Pubkey,
+    to: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Err(anyhow!("Transfer amount cannot be zero"));
+    }
+    if to == payer {
+        return Err(anyhow!("Refusing to transfer to the payer itself"));
+    }
+    if let Some(allow_list) = allow_list
+        && !allow_list.contains(to)
+    {
+        return Err(anyhow!("Recipient {} is not in the transfer allow-list", to));
+    }
+    Ok(())
+}
+
+impl TradingClient {
+    /// Transfer native SOL to `to` (lamports) using the default transfer options
+    ///
+    /// 与 [`TradingClient::transfer_sol`]（由指定 payer 直接经 RPC 转出）不同，这里由 payer 转出并经过白名单与余额检查。
+    pub async fn transfer_sol_to(&self, to: &Pubkey, lamports: u64) -> Result<Signature> {
+        self.transfer_sol_with_options(to, lamports, &TransferOptions::default()).await
+    }
+
+    /// Transfer native SOL to `to` (lamports) through the given SWQOS / gas strategy
+    pub async fn transfer_sol_with_options(
+        &self,
+        to: &Pubkey,
+        lamports: u64,
+        options: &TransferOptions,
+    ) -> Result<Signature> {
+        let payer = self.payer.pubkey();
+        check_transfer_recipient(self.transfer_allow_list.as_deref(), &payer, to, lamports)?;
+        let balance = self.rpc.get_balance(&payer).await?;
+        if balance < lamports {
+            return Err(anyhow!("Insufficient SOL balance: {} < {}", balance, lamports));
+        }
+        self.send_transfer(vec![transfer(&payer, to, lamports)], options).await
+    }
+
+    /// Transfer SPL tokens (raw amount) of `mint` to the wallet `to` using the default transfer options
+    ///
+    /// `to` 为收款钱包地址，收款 ATA 不存在时自动创建。
+    pub async fn transfer_token(
+        &self,
+        mint: &Pubkey,
+        to: &Pubkey,
+        amount: u64,
+    ) -> Result<Signature> {
+        self.transfer_token_with_options(mint, to, amount, &TransferOptions::default())
+            .await
+    }
+
+    /// Transfer SPL tokens (raw amount) of `mint` to the wallet `to` through the given SWQOS / gas strategy
+    pub async fn transfer_token_with_options(
+        &self,
+        mint: &Pubkey,
+        to: &Pubkey,
+        amount: u64,
+        options: &TransferOptions,
+    ) -> Result<Signature> {
+        let payer = self.payer.pubkey();
+        check_transfer_recipient(self.transfer_allow_list.as_deref(), &payer, to, amount)?;
+
+        let mint_account = self.rpc.get_account(mint).await?;
+        let token_program = mint_account.owner;
+        if token_program != TOKEN_PROGRAM && token_program != TOKEN_PROGRAM_2022 {
+            return Err(anyhow!("{} is not a token mint (owner {})", mint, token_program));
+        }
+        let decimals = crate::utils::token::cache_decimals_from_mint_account(mint, &mint_account)
+            .ok_or_else(|| anyhow!("Invalid mint account data for {}", mint))?;
+
+        let source = get_associated_token_address_with_program_id_fast_use_seed(
+            &payer,
+            mint,
+            &token_program,
+            self.use_seed_optimize,
+        );
+        let balance = self
+            .rpc
+            .get_token_account_balance(&source)
+            .await?
+            .amount
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Failed to parse token balance"))?;
+        if balance < amount {
+            return Err(anyhow!("Insufficient token balance: {} < {}", balance, amount));
+        }
+
+        let destination =
+            get_associated_token_address_with_program_id_fast(to, mint, &token_program);
+        let mut instructions =
+            create_associated_token_account_idempotent_fast(&payer, to, mint, &token_program);
+        let mut transfer_ix = crate::common::spl_token::transfer_checked(
+            &token_program,
+            &source,
+            mint,
+            &destination,
+            &payer,
+            amount,
+            decimals,
+        )?;
+        let hook = fetch_transfer_hook_info(self.rpc.as_ref(), mint).await?;
+        append_transfer_hook_accounts(
+            &mut transfer_ix.accounts,
+            hook.as_deref(),
+            &source,
+            mint,
+            &destination,
+            &payer,
+            amount,
+        )?;
+        instructions.push(transfer_ix);
+        self.send_transfer(instructions, options).await
+    }
+
+    async fn send_transfer(
+        &self,
+        business_instructions: Vec<Instruction>,
+        options: &TransferOptions,
+    ) -> Result<Signature> {
+        let swqos_type = options.swqos_type;
+        let swqos_client = self
+            .swqos_registry
+            .get(swqos_type)
+            .ok_or_else(|| anyhow!("SWQOS {:?} is not configured", swqos_type))?;
+        let config = options
+            .gas_fee_strategy
+            .get_strategies(TradeType::Sell)
+            .into_iter()
+            .find(|config| config.0 == swqos_type)
+            .ok_or_else(|| anyhow!("No gas fee strategy for {:?}", swqos_type))?;
+        options.gas_fee_strategy.enforce_caps(&[config], true, true)?;
+        let gas = config.2;
+
+        let tip_account_str = swqos_client.resolve_tip_account()?;
+        let use_tip = swqos_type != SwqosType::Default
+            && !tip_account_str.is_empty()
+            && gas.tip > 0.0
+            && crate::common::cluster::current_cluster().supports_tips();
+        let tip_accounts = if use_tip {
+            let tip_account = Pubkey::from_str(&tip_account_str)
+                .map_err(|e| anyhow!("无效的小费接收地址 '{}': {}", tip_account_str, e))?;
+            swqos_type.pick_tip_accounts(
+                tip_account,
+                options.gas_fee_strategy.tip_split_for(swqos_type, gas.tip),
+            )
+        } else {
+            vec![]
+        };
+
+        let instructions = assemble_instructions(
+            self.payer.as_ref(),
+            gas.cu_limit,
+            gas.cu_price,
+            business_instructions,
+            use_tip,
+            &tip_accounts,
+            if use_tip { gas.tip } else { 0.0 },
+            None,
+            false,
+            InstructionLayout::default(),
+        )?;
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let transaction = TransactionTemplate::new(
+            self.payer.clone(),
+            &instructions,
+            None,
+            blockhash,
+            swqos_type,
+            TradeType::Sell,
+            false,
+        )?
+        .sign_with_blockhash(&blockhash)?;
+        let signature = transaction.signatures[0];
+        swqos_client
+            .send_transaction(TradeType::Sell, &transaction, options.wait_transaction_confirmed)
+            .await?;
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_transfer_recipient() {
+        let payer = Pubkey::new_unique();
+        let allowed = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        assert!(check_transfer_recipient(None, &payer, &other, 1).is_ok());
+        assert!(check_transfer_recipient(None, &payer, &other, 0).is_err());
+        assert!(check_transfer_recipient(None, &payer, &payer, 1).is_err());
+
+        let allow_list = [allowed];
+        assert!(check_transfer_recipient(Some(&allow_list), &payer, &allowed, 1).is_ok());
+        let err = check_transfer_recipient(Some(&allow_list), &payer, &other, 1).unwrap_err();
+        assert!(err.to_string().contains("allow-list"));
+        assert!(check_transfer_recipient(Some(&[]), &payer, &allowed, 1).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Token-2022 转账手续费感知的卖出
+//!
+//! 带转账手续费（TransferFeeConfig）的代币卖出时，池子实际收到的是扣费后的数量，
+//! 按原始余额计算的最小输出会偏高，导致滑点检查失败。这里按当前 epoch 的费率
+//! 计算手续费，据此放宽滑点（或缩放固定输出），使最小输出对应扣费后的数量。
+
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
+use crate::common::{Bps, SolanaRpcClient};
+use crate::swqos::common::TradeError;
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+
+/// 根据 mint 账户数据计算转账 `amount` 时的手续费（无 TransferFeeConfig 扩展时为 0）
+pub fn transfer_fee_from_mint_data(mint_data: &[u8], epoch: u64, amount: u64) -> u64 {
+    StateWithExtensions::<Mint>::unpack(mint_data)
+        .ok()
+        .and_then(|mint| {
+            mint.get_extension::<TransferFeeConfig>()
+                .ok()
+                .and_then(|config| config.calculate_epoch_fee(epoch, amount))
+        })
+        .unwrap_or(0)
+}
+
+/// 通过 RPC 读取 mint 与当前 epoch，计算转账 `amount` 时的手续费
+pub async fn fetch_transfer_fee(rpc: &SolanaRpcClient, mint: &Pubkey, amount: u64) -> Result<u64> {
+    let (mint_account, epoch_info) = tokio::try_join!(rpc.get_account(mint), rpc.get_epoch_info())?;
+    crate::utils::token::cache_decimals_from_mint_account(mint, &mint_account);
+    Ok(transfer_fee_from_mint_data(&mint_account.data, epoch_info.epoch, amount))
+}
+
+/// 读取 `owner` 持有 `mint` 的可用余额（不含被扣留的手续费；账户不存在时为 0）
+pub async fn fetch_token_balance(
+    rpc: &SolanaRpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    use_seed_optimize: bool,
+) -> Result<u64> {
+    let mint_account = rpc.get_account(mint).await?;
+    crate::utils::token::cache_decimals_from_mint_account(mint, &mint_account);
+    let token_account = get_associated_token_address_with_program_id_fast_use_seed(
+        owner,
+        mint,
+        &mint_account.owner,
+        use_seed_optimize,
+    );
+    let Ok(account) = rpc.get_account(&token_account).await else {
+        return Ok(0);
+    };
+    Ok(StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .map(|account| account.base.amount)
+        .unwrap_or(0))
+}
+
+/// 扣除手续费后的滑点：`(1 - s') = (1 - s) * (amount - fee) / amount`
+pub fn slippage_after_fee(slippage_basis_points: Bps, amount: u64, fee: u64) -> Bps {
+    if amount == 0 || fee == 0 {
+        return slippage_basis_points;
+    }
+    let kept = 10_000u128 - slippage_basis_points.capped().as_u128();
+    let net = amount.saturating_sub(fee) as u128;
+    Bps::saturating((10_000 - kept * net / amount as u128) as u64)
+}
+
+/// 固定输出按扣费后的数量等比缩放
+pub fn output_after_fee(output: u64, amount: u64, fee: u64) -> u64 {
+    if amount == 0 {
+        return output;
+    }
+    (output as u128 * amount.saturating_sub(fee) as u128 / amount as u128) as u64
+}
+
+/// 代币程序是否报告余额不足（SPL Token `InsufficientFunds`，自定义错误码 1）
+pub fn is_insufficient_funds(error: &TradeError) -> bool {
+    error.message.to_ascii_lowercase().contains("insufficient funds")
+        || (error.code == 1 && error.program_error.is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_adjustments() {
+        // 10% 滑点、5% 手续费：保留 0.9 * 0.95 = 0.855
+        let slippage = Bps::new(1_000);
+        assert_eq!(slippage_after_fee(slippage, 1_000_000, 50_000), Bps::new(1_450));
+        assert_eq!(slippage_after_fee(slippage, 1_000_000, 0), slippage);
+        assert_eq!(slippage_after_fee(slippage, 100, 100), Bps::HUNDRED_PERCENT);
+        assert_eq!(output_after_fee(2_000, 1_000_000, 50_000), 1_900);
+        assert_eq!(output_after_fee(2_000, 0, 0), 2_000);
+        assert_eq!(transfer_fee_from_mint_data(&[], 0, 1_000), 0);
+    }
+
+    #[test]
+    fn test_is_insufficient_funds() {
+        let error = |code, message: &str| TradeError {
+            code,
+            message: message.to_string(),
+            instruction: Some(3),
+            program_error: None,
+            logs: Vec::new(),
+        };
+        assert!(is_insufficient_funds(&error(1, "custom program error: 0x1")));
+        assert!(is_insufficient_funds(&error(500, "Program log: Error: insufficient funds")));
+        assert!(!is_insufficient_funds(&error(6004, "slippage")));
+    }
+}
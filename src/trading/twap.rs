@@ -0,0 +1,434 @@
+//! TWAP / 冰山（iceberg）执行
+//!
+//! 在 [`TwapConfig::duration`] 内把目标数量拆成多片，由后台任务按时间均匀提交：
+//!
+//! - 每片的提交时间与数量可随机抖动，降低被识别的概率；
+//! - `max_visible_amount` 限制单片数量（冰山模式），必要时自动增加片数；
+//! - 设置 `pool` 后每片提交前刷新协议参数，配合 `price_limit` 在价格越界时推迟该片，
+//!   推迟的数量并入后续分片，最后一片仍越界时停止并保留未成交部分；
+//! - 通过 [`TwapHandle`] 暂停、恢复、取消，并随时查看进度。暂停期间计划整体顺延。
+//!
+//! 单片的执行与统计沿用 [`tranche`](crate::trading::tranche) 的 [`TrancheFill`] / [`TrancheReport`]。
+
+use crate::common::Bps;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::tranche::{PlannedTranche, TrancheFill, TrancheReport};
+use crate::{TradeBuyParams, TradeSellParams, TradingClient};
+use anyhow::{Result, anyhow};
+use parking_lot::Mutex;
+use rand::Rng;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// TWAP 订单：目标数量为模板的 `input_token_amount`
+#[derive(Clone)]
+pub enum TwapOrder {
+    Buy(TradeBuyParams),
+    Sell(TradeSellParams),
+}
+
+impl TwapOrder {
+    fn total_amount(&self) -> u64 {
+        match self {
+            TwapOrder::Buy(params) => params.input_token_amount,
+            TwapOrder::Sell(params) => params.input_token_amount,
+        }
+    }
+
+    fn is_buy(&self) -> bool {
+        matches!(self, TwapOrder::Buy(_))
+    }
+
+    fn mint(&self) -> Pubkey {
+        match self {
+            TwapOrder::Buy(params) => params.mint,
+            TwapOrder::Sell(params) => params.mint,
+        }
+    }
+
+    fn extension_params(&self) -> &DexParamEnum {
+        match self {
+            TwapOrder::Buy(params) => &params.extension_params,
+            TwapOrder::Sell(params) => &params.extension_params,
+        }
+    }
+
+    fn set_extension_params(&mut self, extension_params: DexParamEnum) {
+        match self {
+            TwapOrder::Buy(params) => params.extension_params = extension_params,
+            TwapOrder::Sell(params) => params.extension_params = extension_params,
+        }
+    }
+
+    /// 按协议参数中的储备计算现价（quote 原始单位 / base 原始单位）
+    fn spot_price(&self) -> Option<f64> {
+        let params = self.extension_params();
+        let mint = self.mint();
+        let quote_reserve = params.input_reserve(&mint, true)?;
+        let base_reserve = params.input_reserve(&mint, false)?;
+        (base_reserve > 0).then(|| quote_reserve as f64 / base_reserve as f64)
+    }
+}
+
+/// TWAP 配置
+#[derive(Debug, Clone)]
+pub struct TwapConfig {
+    /// 执行总时长
+    pub duration: Duration,
+    /// 计划片数
+    pub slices: usize,
+    /// 单片数量的随机抖动比例（0.0 - 1.0）
+    pub size_jitter: f64,
+    /// 提交时间的随机抖动比例（相对分片间隔，0.0 - 1.0）
+    pub time_jitter: f64,
+    /// 单片最大数量（冰山模式）
+    pub max_visible_amount: Option<u64>,
+    /// 池地址（PumpFun 为 mint）；设置后每片提交前刷新协议参数
+    pub pool: Option<Pubkey>,
+    /// 价格限制（quote 原始单位 / base 原始单位）：买入时现价高于该值、卖出时低于该值则推迟
+    pub price_limit: Option<f64>,
+    /// 单片滑点；None 时沿用模板
+    pub slice_slippage_bps: Option<Bps>,
+}
+
+impl TwapConfig {
+    pub fn new(duration: Duration, slices: usize) -> Self {
+        Self {
+            duration,
+            slices,
+            size_jitter: 0.0,
+            time_jitter: 0.0,
+            max_visible_amount: None,
+            pool: None,
+            price_limit: None,
+            slice_slippage_bps: None,
+        }
+    }
+
+    pub fn with_jitter(mut self, size_jitter: f64, time_jitter: f64) -> Self {
+        self.size_jitter = size_jitter.clamp(0.0, 1.0);
+        self.time_jitter = time_jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_max_visible_amount(mut self, max_visible_amount: u64) -> Self {
+        self.max_visible_amount = Some(max_visible_amount);
+        self
+    }
+
+    pub fn with_pool(mut self, pool: Pubkey) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    pub fn with_price_limit(mut self, price_limit: f64) -> Self {
+        self.price_limit = Some(price_limit);
+        self
+    }
+
+    pub fn with_slice_slippage(mut self, slice_slippage_bps: Bps) -> Self {
+        self.slice_slippage_bps = Some(slice_slippage_bps);
+        self
+    }
+
+    /// 实际片数：考虑冰山上限后的片数
+    pub fn effective_slices(&self, total: u64) -> usize {
+        let slices = self.slices.max(1) as u64;
+        let slices = match self.max_visible_amount {
+            Some(max) if max > 0 => slices.max(total.div_ceil(max)),
+            _ => slices,
+        };
+        slices as usize
+    }
+}
+
+/// 计算下一片数量：剩余数量按剩余片数均分后抖动，并受冰山上限约束
+fn next_slice_amount(
+    remaining: u64,
+    remaining_slices: usize,
+    config: &TwapConfig,
+    rng: &mut impl Rng,
+) -> u64 {
+    if remaining_slices <= 1 {
+        return config.max_visible_amount.map_or(remaining, |max| remaining.min(max));
+    }
+    let even = remaining as f64 / remaining_slices as f64;
+    let factor = if config.size_jitter > 0.0 {
+        1.0 + rng.random_range(-config.size_jitter..=config.size_jitter)
+    } else {
+        1.0
+    };
+    let mut amount = (even * factor).round() as u64;
+    if let Some(max) = config.max_visible_amount {
+        amount = amount.min(max);
+    }
+    amount.clamp(1, remaining)
+}
+
+/// 第 `index` 片相对开始时间的计划提交时刻
+fn slice_offset(index: usize, slices: usize, config: &TwapConfig, rng: &mut impl Rng) -> Duration {
+    let interval = config.duration.as_secs_f64() / slices.max(1) as f64;
+    let jitter = if config.time_jitter > 0.0 && index > 0 {
+        rng.random_range(-config.time_jitter..=config.time_jitter) * interval / 2.0
+    } else {
+        0.0
+    };
+    Duration::from_secs_f64((interval * index as f64 + jitter).max(0.0))
+}
+
+/// 运行控制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TwapControl {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// 执行进度
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TwapProgress {
+    pub total_amount: u64,
+    pub filled_amount: u64,
+    pub slices_done: usize,
+    pub slices_planned: usize,
+    /// 因价格越界被推迟的次数
+    pub price_deferrals: usize,
+    pub paused: bool,
+}
+
+/// TWAP 执行结果
+#[derive(Debug)]
+pub struct TwapReport {
+    pub execution: TrancheReport,
+    pub price_deferrals: usize,
+    pub cancelled: bool,
+}
+
+impl TwapReport {
+    /// 未成交数量
+    pub fn unfilled_amount(&self) -> u64 {
+        self.execution.total_amount.saturating_sub(self.execution.filled_amount)
+    }
+}
+
+/// 后台 TWAP 任务句柄
+pub struct TwapHandle {
+    control: watch::Sender<TwapControl>,
+    progress: Arc<Mutex<TwapProgress>>,
+    task: JoinHandle<TwapReport>,
+}
+
+impl TwapHandle {
+    /// 暂停（正在提交的分片会执行完）
+    pub fn pause(&self) {
+        self.control.send_if_modified(|state| {
+            let changed = *state == TwapControl::Running;
+            if changed {
+                *state = TwapControl::Paused;
+            }
+            changed
+        });
+    }
+
+    pub fn resume(&self) {
+        self.control.send_if_modified(|state| {
+            let changed = *state == TwapControl::Paused;
+            if changed {
+                *state = TwapControl::Running;
+            }
+            changed
+        });
+    }
+
+    pub fn progress(&self) -> TwapProgress {
+        *self.progress.lock()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// 取消剩余分片并等待任务结束
+    pub async fn cancel(self) -> Result<TwapReport> {
+        let _ = self.control.send(TwapControl::Cancelled);
+        self.wait().await
+    }
+
+    /// 等待执行结束
+    pub async fn wait(self) -> Result<TwapReport> {
+        self.task.await.map_err(|e| anyhow!("twap task failed: {}", e))
+    }
+}
+
+/// 等待到 `deadline`；暂停期间 deadline 顺延。返回 false 表示已取消
+async fn wait_until(
+    deadline: &mut Instant,
+    control: &mut watch::Receiver<TwapControl>,
+    progress: &Mutex<TwapProgress>,
+) -> bool {
+    loop {
+        let state = *control.borrow_and_update();
+        match state {
+            TwapControl::Cancelled => return false,
+            TwapControl::Paused => {
+                progress.lock().paused = true;
+                let paused_at = Instant::now();
+                if control.changed().await.is_err() {
+                    return false;
+                }
+                *deadline += paused_at.elapsed();
+                progress.lock().paused = false;
+            },
+            TwapControl::Running => {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(*deadline) => return true,
+                    changed = control.changed() => {
+                        if changed.is_err() {
+                            return false;
+                        }
+                    },
+                }
+            },
+        }
+    }
+}
+
+impl TradingClient {
+    /// 启动后台 TWAP / 冰山执行
+    ///
+    /// 设置了 `price_limit` 时协议必须能按储备计算现价（CLMM、DAMM v2 不支持）。
+    pub fn start_twap(&self, order: TwapOrder, config: TwapConfig) -> Result<TwapHandle> {
+        if config.price_limit.is_some() && order.spot_price().is_none() {
+            return Err(anyhow!("price_limit requires a constant-product pool"));
+        }
+        let total = order.total_amount();
+        let slices = config.effective_slices(total);
+        let (control_tx, control_rx) = watch::channel(TwapControl::Running);
+        let progress = Arc::new(Mutex::new(TwapProgress {
+            total_amount: total,
+            slices_planned: slices,
+            ..Default::default()
+        }));
+        let client = self.clone();
+        let task_progress = progress.clone();
+        let task = tokio::spawn(async move {
+            client.run_twap(order, config, slices, control_rx, task_progress).await
+        });
+        Ok(TwapHandle { control: control_tx, progress, task })
+    }
+
+    async fn run_twap(
+        &self,
+        mut order: TwapOrder,
+        config: TwapConfig,
+        slices: usize,
+        mut control: watch::Receiver<TwapControl>,
+        progress: Arc<Mutex<TwapProgress>>,
+    ) -> TwapReport {
+        let start = Instant::now();
+        let total = order.total_amount();
+        let mut execution = TrancheReport { total_amount: total, ..Default::default() };
+        let mut remaining = total;
+        let mut price_deferrals = 0;
+        let mut cancelled = false;
+
+        for index in 0..slices {
+            if remaining == 0 {
+                break;
+            }
+            let offset = slice_offset(index, slices, &config, &mut rand::rng());
+            let mut deadline = start + offset;
+            if !wait_until(&mut deadline, &mut control, &progress).await {
+                cancelled = true;
+                break;
+            }
+
+            if let Some(pool) = config.pool {
+                let dex_type = order.extension_params().dex_type();
+                match DexParamEnum::from_pool_force(&self.rpc, dex_type, &pool).await {
+                    Ok(params) => order.set_extension_params(params),
+                    Err(e) => log::warn!("twap: failed to refresh params: {}", e),
+                }
+            }
+            if let (Some(limit), Some(spot)) = (config.price_limit, order.spot_price()) {
+                let out_of_range = if order.is_buy() { spot > limit } else { spot < limit };
+                if out_of_range {
+                    price_deferrals += 1;
+                    progress.lock().price_deferrals = price_deferrals;
+                    continue;
+                }
+            }
+
+            let amount = next_slice_amount(remaining, slices - index, &config, &mut rand::rng());
+            let planned = PlannedTranche {
+                amount,
+                price_impact_bps: Bps::ZERO,
+                slippage_basis_points: config.slice_slippage_bps,
+            };
+            let result = match &order {
+                TwapOrder::Buy(params) => {
+                    let mut leg = params.clone();
+                    leg.input_token_amount = amount;
+                    leg.fixed_output_token_amount = None;
+                    if let Some(slippage) = config.slice_slippage_bps {
+                        leg.slippage_basis_points = Some(slippage);
+                    }
+                    self.buy_with_result(leg).await
+                },
+                TwapOrder::Sell(params) => {
+                    let mut leg = params.clone();
+                    leg.input_token_amount = amount;
+                    leg.fixed_output_token_amount = None;
+                    if let Some(slippage) = config.slice_slippage_bps {
+                        leg.slippage_basis_points = Some(slippage);
+                    }
+                    self.sell_with_result(leg).await
+                },
+            };
+            let fill = TrancheFill { index, planned, result: result.map_err(|e| e.to_string()) };
+            if fill.is_success() {
+                remaining -= amount;
+            }
+            execution.record(fill);
+            let mut current = progress.lock();
+            current.filled_amount = execution.filled_amount;
+            current.slices_done = index + 1;
+        }
+
+        execution.skipped = slices.saturating_sub(execution.fills.len() + price_deferrals);
+        execution.elapsed = start.elapsed();
+        TwapReport { execution, price_deferrals, cancelled }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_twap_slicing() {
+        let config = TwapConfig::new(Duration::from_secs(60), 4).with_max_visible_amount(100);
+        assert_eq!(config.effective_slices(1_000), 10);
+        assert_eq!(config.effective_slices(200), 4);
+
+        let mut rng = rand::rng();
+        let config = TwapConfig::new(Duration::from_secs(60), 4).with_jitter(0.2, 0.5);
+        let mut remaining = 1_000u64;
+        for index in 0..4 {
+            let amount = next_slice_amount(remaining, 4 - index, &config, &mut rng);
+            assert!(amount >= 1 && amount <= remaining);
+            remaining -= amount;
+        }
+        assert_eq!(remaining, 0);
+
+        for index in 0..4 {
+            let offset = slice_offset(index, 4, &config, &mut rng).as_secs_f64();
+            let planned = 15.0 * index as f64;
+            assert!((offset - planned).abs() <= 3.75 + 1e-9);
+        }
+    }
+}
@@ -1,4 +1,28 @@
+//! Bonk（LetsBonk / Raydium LaunchLab）bonding curve 计算
+//!
+//! 与 [`crate::instruction::bonk`] 构建交易时使用的是同一套公式，策略代码可直接复用。
+//!
+//! 单位约定：
+//! - `amount_in` / 返回的数量均为最小单位：quote 为 lamports（USD1 池为 USD1 最小单位，6 位精度），
+//!   base 为代币最小单位（LaunchLab 代币默认 6 位精度）；
+//! - `virtual_base` / `virtual_quote` / `real_base` / `real_quote` 直接取自池状态（[`crate::trading::core::params::BonkParams`]），
+//!   `real_base` 为已售出的 base 数量，`real_quote` 为已募集的 quote 数量；
+//! - `slippage_basis_points` 为 [`Bps`]，传 [`Bps::ZERO`] 得到不含滑点的精确报价；
+//! - 价格为按精度换算后的 quote / base（例如 1 个代币值多少 SOL）。
+
+use crate::common::Bps;
+use crate::constants::decimals::{DEFAULT_TOKEN_DECIMALS, SOL_DECIMALS};
 use crate::instruction::utils::bonk::accounts;
+use crate::utils::calc::common::{DepthLadder, constant_product_depth};
+
+/// LaunchLab 默认发行参数下 bonding curve 可售出的 base 总量（793,100,000 个代币，6 位精度）
+///
+/// 已售出量 `real_base` 达到该值时池子迁移到 AMM。
+pub const DEFAULT_TOTAL_BASE_SELL: u128 = 793_100_000_000_000;
+
+/// 交易总费率（基点）：协议费 + 平台费 + 分享费；买入从输入扣除，卖出从输出扣除
+pub const TOTAL_FEE_BASIS_POINTS: u128 =
+    accounts::PROTOCOL_FEE_RATE + accounts::PLATFORM_FEE_RATE + accounts::SHARE_FEE_RATE;
 
 /// Calculates the amount of tokens to receive when buying with SOL
 ///
@@ -23,7 +47,7 @@ pub fn get_buy_token_amount_from_sol_amount(
     virtual_quote: u128,
     real_base: u128,
     real_quote: u128,
-    slippage_basis_points: u128,
+    slippage_basis_points: Bps,
 ) -> u64 {
     let amount_in_u128 = amount_in as u128;
 
@@ -51,7 +75,7 @@ pub fn get_buy_token_amount_from_sol_amount(
     let mut amount_out = numerator.checked_div(denominator).unwrap();
 
     // Apply slippage protection
-    amount_out = amount_out - (amount_out * slippage_basis_points) / 10000;
+    amount_out = amount_out - (amount_out * slippage_basis_points.capped().as_u128()) / 10000;
     amount_out as u64
 }
 
@@ -78,7 +102,7 @@ pub fn get_sell_sol_amount_from_token_amount(
     virtual_quote: u128,
     real_base: u128,
     real_quote: u128,
-    slippage_basis_points: u128,
+    slippage_basis_points: Bps,
 ) -> u64 {
     let amount_in_u128 = amount_in as u128;
 
@@ -106,7 +130,222 @@ pub fn get_sell_sol_amount_from_token_amount(
         .unwrap();
 
     // Apply slippage protection
-    let final_amount = sol_amount_net - (sol_amount_net * slippage_basis_points) / 10000;
+    let final_amount =
+        sol_amount_net - (sol_amount_net * slippage_basis_points.capped().as_u128()) / 10000;
 
     final_amount as u64
 }
+
+/// 当前 bonding curve 价格：1 个代币值多少 quote（SOL/USD1，按精度换算，不含手续费）
+///
+/// # Arguments
+///
+/// * `virtual_base` - Virtual base token reserves
+/// * `virtual_quote` - Virtual quote token reserves
+/// * `real_base` - Real base token reserves (sold)
+/// * `real_quote` - Real quote token reserves (raised)
+/// * `base_decimals` - Base token decimals
+/// * `quote_decimals` - Quote token decimals
+pub fn get_token_price(
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+    base_decimals: u8,
+    quote_decimals: u8,
+) -> f64 {
+    let base_reserve = virtual_base.saturating_sub(real_base);
+    if base_reserve == 0 {
+        return 0.0;
+    }
+    let quote_reserve = virtual_quote.saturating_add(real_quote);
+    let decimal_factor = 10f64.powi(quote_decimals as i32 - base_decimals as i32);
+    quote_reserve as f64 / base_reserve as f64 / decimal_factor
+}
+
+/// 当前 bonding curve 价格（SOL 计价池，代币 6 位精度）
+pub fn get_token_price_in_sol(
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+) -> f64 {
+    get_token_price(
+        virtual_base,
+        virtual_quote,
+        real_base,
+        real_quote,
+        DEFAULT_TOKEN_DECIMALS,
+        SOL_DECIMALS,
+    )
+}
+
+/// 迁移进度：已售出 base 占可售总量的比例，范围 `[0.0, 1.0]`
+///
+/// # Arguments
+///
+/// * `real_base` - Real base token reserves (sold, in smallest units)
+/// * `total_base_sell` - Total base sellable on the curve (e.g. [`DEFAULT_TOTAL_BASE_SELL`])
+pub fn get_migration_progress(real_base: u128, total_base_sell: u128) -> f64 {
+    if total_base_sell == 0 {
+        return 0.0;
+    }
+    (real_base as f64 / total_base_sell as f64).clamp(0.0, 1.0)
+}
+
+/// 距离迁移还可买入的 base 数量（最小单位）
+pub fn get_remaining_base_to_migration(real_base: u128, total_base_sell: u128) -> u128 {
+    total_base_sell.saturating_sub(real_base)
+}
+
+/// 深度阶梯：对 `levels_bps` 中的每个价格变动（如 `[1000, 2500, 5000]` 即 +10% / +25% / +50%），
+/// 计算把价格推高所需的 quote 与推低时可收回的 quote
+///
+/// 买入档受距离迁移的剩余可售量（`total_base_sell - real_base`）限制，卖出档受已募集的 `real_quote` 限制；
+/// quote 数量已计入 [`TOTAL_FEE_BASIS_POINTS`]。base 按 6 位精度换算价格。
+///
+/// # Arguments
+///
+/// * `total_base_sell` - Total base sellable on the curve (e.g. [`DEFAULT_TOTAL_BASE_SELL`])
+/// * `quote_decimals` - Quote token decimals（SOL 池为 9，USD1 池为 6）
+/// * `levels_bps` - Price moves in basis points
+pub fn get_depth_ladder(
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+    total_base_sell: u128,
+    quote_decimals: u8,
+    levels_bps: &[Bps],
+) -> DepthLadder {
+    let mut ladder = constant_product_depth(
+        virtual_quote.saturating_add(real_quote),
+        virtual_base.saturating_sub(real_base),
+        total_base_sell.saturating_sub(real_base),
+        real_quote,
+        10f64.powi(DEFAULT_TOKEN_DECIMALS as i32 - quote_decimals as i32),
+        levels_bps,
+    );
+    // 买入从输入扣费：gross * (1 - fee) = net；卖出从输出扣费
+    for level in &mut ladder.buys {
+        let net = level.quote_amount as u128;
+        level.quote_amount = (net * 10_000).div_ceil(10_000 - TOTAL_FEE_BASIS_POINTS) as u64;
+    }
+    for level in &mut ladder.sells {
+        let gross = level.quote_amount as u128;
+        level.quote_amount = (gross - gross * TOTAL_FEE_BASIS_POINTS / 10_000) as u64;
+    }
+    ladder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // LaunchLab 初始状态：1,073,025,605 个代币 / 30 SOL 虚拟储备
+    const VIRTUAL_BASE: u128 = 1_073_025_605_596_382;
+    const VIRTUAL_QUOTE: u128 = 30_000_852_951;
+
+    #[test]
+    fn test_buy_then_sell_round_trip_loses_fees() {
+        let amount_in = 1_000_000_000; // 1 SOL
+        let tokens = get_buy_token_amount_from_sol_amount(
+            amount_in,
+            VIRTUAL_BASE,
+            VIRTUAL_QUOTE,
+            0,
+            0,
+            Bps::ZERO,
+        );
+        assert!(tokens > 0);
+        let slipped = get_buy_token_amount_from_sol_amount(
+            amount_in,
+            VIRTUAL_BASE,
+            VIRTUAL_QUOTE,
+            0,
+            0,
+            Bps::new(100),
+        );
+        assert!(slipped < tokens);
+
+        let sol_back = get_sell_sol_amount_from_token_amount(
+            tokens,
+            VIRTUAL_BASE,
+            VIRTUAL_QUOTE,
+            tokens as u128,
+            amount_in as u128 * (10_000 - TOTAL_FEE_BASIS_POINTS) / 10_000,
+            Bps::ZERO,
+        );
+        assert!(sol_back < amount_in);
+    }
+
+    #[test]
+    fn test_price_and_migration_progress() {
+        let price = get_token_price_in_sol(VIRTUAL_BASE, VIRTUAL_QUOTE, 0, 0);
+        assert!((price - 2.796e-8).abs() < 1e-10);
+        assert!(get_token_price_in_sol(VIRTUAL_BASE, VIRTUAL_QUOTE, VIRTUAL_BASE / 2, 0) > price);
+
+        assert_eq!(get_migration_progress(0, DEFAULT_TOTAL_BASE_SELL), 0.0);
+        assert_eq!(
+            get_migration_progress(DEFAULT_TOTAL_BASE_SELL / 2, DEFAULT_TOTAL_BASE_SELL),
+            0.5
+        );
+        assert_eq!(
+            get_migration_progress(DEFAULT_TOTAL_BASE_SELL * 2, DEFAULT_TOTAL_BASE_SELL),
+            1.0
+        );
+        assert_eq!(
+            get_remaining_base_to_migration(DEFAULT_TOTAL_BASE_SELL + 1, DEFAULT_TOTAL_BASE_SELL),
+            0
+        );
+    }
+
+    #[test]
+    fn test_depth_ladder() {
+        let (real_base, real_quote) = (DEFAULT_TOTAL_BASE_SELL / 2, 40_000_000_000);
+        let ladder = get_depth_ladder(
+            VIRTUAL_BASE,
+            VIRTUAL_QUOTE,
+            real_base,
+            real_quote,
+            DEFAULT_TOTAL_BASE_SELL,
+            SOL_DECIMALS,
+            &[1000, 5000, 100_000].map(Bps::new),
+        );
+        let price = get_token_price_in_sol(VIRTUAL_BASE, VIRTUAL_QUOTE, real_base, real_quote);
+        assert!((ladder.price / price - 1.0).abs() < 1e-12);
+
+        let plus_10 = ladder.buys[0];
+        assert!(!plus_10.capped);
+        assert!((plus_10.price / ladder.price - 1.1).abs() < 1e-6);
+        let tokens = get_buy_token_amount_from_sol_amount(
+            plus_10.quote_amount,
+            VIRTUAL_BASE,
+            VIRTUAL_QUOTE,
+            real_base,
+            real_quote,
+            Bps::ZERO,
+        );
+        assert!(tokens.abs_diff(plus_10.base_amount) <= plus_10.base_amount / 1_000_000);
+
+        // +1000% 超过迁移前剩余可售量
+        let capped = ladder.buys[2];
+        assert!(capped.capped);
+        assert_eq!(capped.base_amount as u128, DEFAULT_TOTAL_BASE_SELL - real_base);
+
+        // 10 万基点的卖出档被忽略；-50% 需卖出的代币与收回的 SOL 一致
+        assert_eq!(ladder.sells.len(), 2);
+        let minus_50 = ladder.sells[1];
+        assert!(!minus_50.capped);
+        assert!((minus_50.price / ladder.price - 0.5).abs() < 1e-6);
+        let sol = get_sell_sol_amount_from_token_amount(
+            minus_50.base_amount,
+            VIRTUAL_BASE,
+            VIRTUAL_QUOTE,
+            real_base,
+            real_quote,
+            Bps::ZERO,
+        );
+        assert!(sol.abs_diff(minus_50.quote_amount) <= minus_50.quote_amount / 1_000_000);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::common::Bps;
+
 /// Calculate transaction fee based on amount and fee basis points
 ///
 /// # Parameters
@@ -41,7 +43,8 @@ pub const fn ceil_div(a: u128, b: u128) -> u128 {
 /// * basis_points = 100 -> 1% slippage
 /// * basis_points = 500 -> 5% slippage
 #[inline(always)]
-pub const fn calculate_with_slippage_buy(amount: u64, basis_points: u64) -> u64 {
+pub const fn calculate_with_slippage_buy(amount: u64, basis_points: Bps) -> u64 {
+    let basis_points = basis_points.as_u64();
     amount + (amount * basis_points / 10000)
 }
 
@@ -58,6 +61,96 @@ pub const fn calculate_with_slippage_buy(amount: u64, basis_points: u64) -> u64
 /// * basis_points = 100 -> 1% slippage
 /// * basis_points = 500 -> 5% slippage
 #[inline(always)]
-pub const fn calculate_with_slippage_sell(amount: u64, basis_points: u64) -> u64 {
+pub const fn calculate_with_slippage_sell(amount: u64, basis_points: Bps) -> u64 {
+    let basis_points = basis_points.capped().as_u64();
     if amount <= basis_points / 10000 { 1 } else { amount - (amount * basis_points / 10000) }
 }
+
+/// Bonding curve 深度档位：把价格推动到目标位置所需的成交量
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DepthLevel {
+    /// 相对当前价格的变动（基点）：买入档为上涨幅度，卖出档为下跌幅度
+    pub change_bps: Bps,
+    /// 成交后的价格（quote / base，已按精度换算）
+    pub price: f64,
+    /// 买入档为需支付的 quote（含手续费），卖出档为可收到的 quote（已扣手续费），最小单位
+    pub quote_amount: u64,
+    /// 买入档为可买到的 base，卖出档为需卖出的 base，最小单位
+    pub base_amount: u64,
+    /// 曲线剩余流动性不足以到达该档位（买完可售量 / 卖空已募集量），数量与价格为截断后的值
+    pub capped: bool,
+}
+
+/// Bonding curve 深度阶梯
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepthLadder {
+    /// 当前价格（quote / base，已按精度换算）
+    pub price: f64,
+    /// 买入档（价格上涨），按传入顺序
+    pub buys: Vec<DepthLevel>,
+    /// 卖出档（价格下跌），按传入顺序；不小于 [`Bps::HUNDRED_PERCENT`] 的档位被忽略
+    pub sells: Vec<DepthLevel>,
+}
+
+/// 常数乘积曲线的深度阶梯（不含手续费，quote 数量为进入 / 离开曲线的净额）
+///
+/// 价格 `P = quote_reserve / base_reserve`，`k` 不变时价格变为 `m` 倍需要 quote 储备变为 `sqrt(m)` 倍。
+/// `max_buy_base` 为曲线剩余可买出的 base，`max_sell_quote` 为曲线中可取出的 quote；
+/// `price_scale` 将储备比换算为按精度的价格（`10^(base_decimals - quote_decimals)`）。
+pub(crate) fn constant_product_depth(
+    quote_reserve: u128,
+    base_reserve: u128,
+    max_buy_base: u128,
+    max_sell_quote: u128,
+    price_scale: f64,
+    levels_bps: &[Bps],
+) -> DepthLadder {
+    if quote_reserve == 0 || base_reserve == 0 {
+        return DepthLadder::default();
+    }
+    let (quote, base) = (quote_reserve as f64, base_reserve as f64);
+    let k = quote * base;
+    let price = |quote: f64, base: f64| quote / base * price_scale;
+
+    let buys = levels_bps
+        .iter()
+        .map(|&change_bps| {
+            let target_quote = quote * (1.0 + change_bps.to_fraction()).sqrt();
+            let mut new_base = k / target_quote;
+            let capped = base - new_base > max_buy_base as f64;
+            if capped {
+                new_base = base - max_buy_base as f64;
+            }
+            let new_quote = if capped { k / new_base } else { target_quote };
+            DepthLevel {
+                change_bps,
+                price: price(new_quote, new_base),
+                quote_amount: (new_quote - quote).ceil() as u64,
+                base_amount: (base - new_base).floor() as u64,
+                capped,
+            }
+        })
+        .collect();
+
+    let sells = levels_bps
+        .iter()
+        .filter(|&&change_bps| change_bps < Bps::HUNDRED_PERCENT)
+        .map(|&change_bps| {
+            let mut new_quote = quote * (1.0 - change_bps.to_fraction()).sqrt();
+            let capped = quote - new_quote > max_sell_quote as f64;
+            if capped {
+                new_quote = quote - max_sell_quote as f64;
+            }
+            let new_base = k / new_quote;
+            DepthLevel {
+                change_bps,
+                price: price(new_quote, new_base),
+                quote_amount: (quote - new_quote).floor() as u64,
+                base_amount: (new_base - base).ceil() as u64,
+                capped,
+            }
+        })
+        .collect();
+
+    DepthLadder { price: price(quote, base), buys, sells }
+}
@@ -1,8 +1,10 @@
 use solana_sdk::{native_token::sol_str_to_lamports, pubkey::Pubkey};
 
 use crate::{
+    common::{Bps, bonding_curve::BondingCurveAccount},
+    constants::decimals::{DEFAULT_TOKEN_DECIMALS, SOL_DECIMALS},
     instruction::utils::pumpfun::global_constants::{CREATOR_FEE, FEE_BASIS_POINTS},
-    utils::calc::common::compute_fee,
+    utils::calc::common::{DepthLadder, compute_fee, constant_product_depth},
 };
 
 /// Calculates the amount of tokens that can be purchased with a given SOL amount
@@ -111,3 +113,81 @@ pub fn get_sell_sol_amount_from_token_amount(
 
     sol_cost.saturating_sub(fee) as u64
 }
+
+/// Builds a depth ladder for a bonding curve: for each price move in `levels_bps`
+/// (e.g. `[1000, 2500, 5000]` for +10% / +25% / +50%), the SOL required to push the price
+/// up by that much and the SOL received when selling it down by that much.
+///
+/// Buy levels are capped by `real_token_reserves`, sell levels by `real_sol_reserves`.
+/// Quote amounts include the protocol fee (plus creator fee when the curve has a creator).
+///
+/// # Returns
+/// An empty ladder when the bonding curve is complete (migrated)
+pub fn get_depth_ladder(bonding_curve: &BondingCurveAccount, levels_bps: &[Bps]) -> DepthLadder {
+    if bonding_curve.complete {
+        return DepthLadder::default();
+    }
+    let total_fee_basis_points = (FEE_BASIS_POINTS
+        + if bonding_curve.creator != Pubkey::default() { CREATOR_FEE } else { 0 })
+        as u128;
+    let mut ladder = constant_product_depth(
+        bonding_curve.virtual_sol_reserves as u128,
+        bonding_curve.virtual_token_reserves as u128,
+        bonding_curve.real_token_reserves as u128,
+        bonding_curve.real_sol_reserves as u128,
+        10f64.powi(DEFAULT_TOKEN_DECIMALS as i32 - SOL_DECIMALS as i32),
+        levels_bps,
+    );
+    for level in &mut ladder.buys {
+        let net = level.quote_amount as u128;
+        level.quote_amount = (net + compute_fee(net, total_fee_basis_points)) as u64;
+    }
+    for level in &mut ladder.sells {
+        let gross = level.quote_amount as u128;
+        level.quote_amount =
+            gross.saturating_sub(compute_fee(gross, total_fee_basis_points)) as u64;
+    }
+    ladder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::utils::pumpfun::global_constants::{
+        INITIAL_REAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_VIRTUAL_TOKEN_RESERVES,
+    };
+
+    #[test]
+    fn test_depth_ladder() {
+        let curve = BondingCurveAccount {
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            real_token_reserves: INITIAL_REAL_TOKEN_RESERVES,
+            creator: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let ladder = get_depth_ladder(&curve, &[1000, 2500, 5000, 1_000_000].map(Bps::new));
+        assert_eq!(ladder.buys.len(), 4);
+        // 新曲线没有已募集的 SOL，卖出档全部截断为 0
+        assert!(ladder.sells.iter().all(|level| level.capped && level.quote_amount == 0));
+
+        let plus_10 = ladder.buys[0];
+        assert!(!plus_10.capped);
+        assert!((plus_10.price / ladder.price - 1.1).abs() < 1e-6);
+        // 支付该数量的 SOL 买到的代币与档位一致（误差来自取整）
+        let tokens = get_buy_token_amount_from_sol_amount(
+            curve.virtual_token_reserves as u128,
+            curve.virtual_sol_reserves as u128,
+            curve.real_token_reserves as u128,
+            curve.creator,
+            plus_10.quote_amount,
+        );
+        assert!(tokens.abs_diff(plus_10.base_amount) <= plus_10.base_amount / 1_000_000);
+        assert!(ladder.buys.windows(2).all(|w| w[0].quote_amount < w[1].quote_amount));
+
+        // +10000% 超过曲线剩余可售量
+        let capped = ladder.buys[3];
+        assert!(capped.capped);
+        assert_eq!(capped.base_amount, INITIAL_REAL_TOKEN_RESERVES);
+    }
+}
@@ -1,6 +1,7 @@
 use super::common::{
     calculate_with_slippage_buy, calculate_with_slippage_sell, ceil_div, compute_fee,
 };
+use crate::common::Bps;
 use crate::instruction::utils::pumpswap::accounts::{
     COIN_CREATOR_FEE_BASIS_POINTS, LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS,
 };
@@ -28,6 +29,17 @@ pub struct BuyQuoteInputResult {
     pub max_quote: u64,
 }
 
+/// Result for buying base tokens with an exact quote budget (fees included)
+#[derive(Clone, Debug)]
+pub struct BuyExactQuoteInputResult {
+    /// Largest base amount whose total cost (amount + LP, protocol and creator fees) fits the budget
+    pub base: u64,
+    /// Total quote amount debited for `base` at current reserves (never above the budget)
+    pub total_quote: u64,
+    /// Minimum base amount with slippage protection
+    pub min_base: u64,
+}
+
 /// Result for selling base tokens with base amount input
 #[derive(Clone, Debug)]
 pub struct SellBaseInputResult {
@@ -63,7 +75,7 @@ pub struct SellQuoteInputResult {
 /// * `BuyBaseInputResult` containing quote amounts and slippage calculations
 pub fn buy_base_input_internal(
     base: u64,
-    slippage_basis_points: u64,
+    slippage_basis_points: Bps,
     base_reserve: u64,
     quote_reserve: u64,
     coin_creator: &Pubkey,
@@ -119,7 +131,7 @@ pub fn buy_base_input_internal(
 /// * `BuyQuoteInputResult` containing base amount and slippage calculations
 pub fn buy_quote_input_internal(
     quote: u64,
-    slippage_basis_points: u64,
+    slippage_basis_points: Bps,
     base_reserve: u64,
     quote_reserve: u64,
     coin_creator: &Pubkey,
@@ -157,6 +169,56 @@ pub fn buy_quote_input_internal(
     })
 }
 
+/// Calculate the base tokens to buy so that the total debit, fees included, spends the quote budget
+///
+/// Searches for the largest base amount whose cost per [`buy_base_input_internal`] (with the same fee
+/// rounding as the program) does not exceed `quote`, so a budget-capped buy never overdraws.
+///
+/// # Arguments
+/// * `quote` - Quote budget, including all fees
+/// * `slippage_basis_points` - Slippage tolerance in basis points (100 = 1%)
+/// * `base_reserve` - Base token reserves in the pool
+/// * `quote_reserve` - Quote token reserves in the pool
+/// * `coin_creator` - Token creator address
+///
+/// # Returns
+/// * `BuyExactQuoteInputResult` containing base amounts and the exact debit
+pub fn buy_exact_quote_input_internal(
+    quote: u64,
+    slippage_basis_points: Bps,
+    base_reserve: u64,
+    quote_reserve: u64,
+    coin_creator: &Pubkey,
+) -> Result<BuyExactQuoteInputResult, String> {
+    if base_reserve == 0 || quote_reserve == 0 {
+        return Err("Invalid input: 'baseReserve' or 'quoteReserve' cannot be zero.".to_string());
+    }
+    let total_cost = |base: u64| {
+        buy_base_input_internal(base, Bps::ZERO, base_reserve, quote_reserve, coin_creator)
+            .map(|result| result.ui_quote)
+    };
+
+    // 成本随 base 单调递增：二分查找预算内可买到的最大 base
+    let (mut low, mut high) = (0u64, base_reserve - 1);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if total_cost(mid)? <= quote {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    if low == 0 {
+        return Err("Quote budget is too small to buy any base tokens.".to_string());
+    }
+
+    Ok(BuyExactQuoteInputResult {
+        base: low,
+        total_quote: total_cost(low)?,
+        min_base: calculate_with_slippage_sell(low, slippage_basis_points),
+    })
+}
+
 /// Calculate quote tokens received for selling a specific amount of base tokens
 ///
 /// # Arguments
@@ -170,7 +232,7 @@ pub fn buy_quote_input_internal(
 /// * `SellBaseInputResult` containing quote amounts and slippage calculations
 pub fn sell_base_input_internal(
     base: u64,
-    slippage_basis_points: u64,
+    slippage_basis_points: Bps,
     base_reserve: u64,
     quote_reserve: u64,
     coin_creator: &Pubkey,
@@ -239,7 +301,7 @@ fn calculate_quote_amount_out(
 /// * `SellQuoteInputResult` containing base amount and slippage calculations
 pub fn sell_quote_input_internal(
     quote: u64,
-    slippage_basis_points: u64,
+    slippage_basis_points: Bps,
     base_reserve: u64,
     quote_reserve: u64,
     coin_creator: &Pubkey,
@@ -277,3 +339,41 @@ pub fn sell_quote_input_internal(
         min_quote,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buy_exact_quote_input_spends_budget() {
+        let (base_reserve, quote_reserve) = (200_000_000_000_000, 80_000_000_000);
+        let creator = Pubkey::new_unique();
+        let budget = 1_000_000_000;
+        let result = buy_exact_quote_input_internal(
+            budget,
+            Bps::new(100),
+            base_reserve,
+            quote_reserve,
+            &creator,
+        )
+        .unwrap();
+
+        // 总扣款不超过预算，且多买 1 个 base 就会超出
+        assert!(result.total_quote <= budget);
+        let next = buy_base_input_internal(
+            result.base + 1,
+            Bps::ZERO,
+            base_reserve,
+            quote_reserve,
+            &creator,
+        )
+        .unwrap();
+        assert!(next.ui_quote > budget);
+        assert_eq!(result.min_base, calculate_with_slippage_sell(result.base, Bps::new(100)));
+
+        assert!(
+            buy_exact_quote_input_internal(0, Bps::ZERO, base_reserve, quote_reserve, &creator)
+                .is_err()
+        );
+    }
+}
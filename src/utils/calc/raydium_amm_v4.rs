@@ -16,10 +16,13 @@
 //! - 使用恒定乘积公式计算输入/输出数量
 //! - 扣除交易费和 Swap 费
 //! - 支持滑点计算
+//! - 支持固定输出（SwapBaseOut）：按目标输出反推所需输入与最大输入
 
+use crate::common::Bps;
 use crate::instruction::utils::raydium_amm_v4::accounts::{
     SWAP_FEE_DENOMINATOR, SWAP_FEE_NUMERATOR, TRADE_FEE_DENOMINATOR, TRADE_FEE_NUMERATOR,
 };
+use anyhow::{Result, anyhow};
 
 /// Computes trading fee using ceiling division.
 ///
@@ -141,7 +144,7 @@ pub fn compute_swap_amount(
     quote_reserve: u64,
     is_base_in: bool,
     amount_in: u64,
-    slippage_basis_points: u64,
+    slippage_basis_points: Bps,
 ) -> ComputeSwapParams {
     let (input_reserve, output_reserve) =
         if is_base_in { (base_reserve, quote_reserve) } else { (quote_reserve, base_reserve) };
@@ -154,8 +157,8 @@ pub fn compute_swap_amount(
         SWAP_FEE_NUMERATOR,
     );
 
-    let min_amount_out = ((swap_result.output_amount as f64)
-        * (1.0 - (slippage_basis_points as f64) / 10000.0)) as u64;
+    let min_amount_out =
+        ((swap_result.output_amount as f64) * (1.0 - slippage_basis_points.to_fraction())) as u64;
 
     let all_trade = swap_result.input_amount == amount_in;
 
@@ -167,3 +170,73 @@ pub fn compute_swap_amount(
         fee: swap_result.trade_fee,
     }
 }
+
+/// Parameters for an exact-output (SwapBaseOut) swap.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapOutParams {
+    /// The exact output amount requested
+    pub amount_out: u64,
+    /// The input amount required at current reserves, including the swap fee
+    pub amount_in: u64,
+    /// The maximum acceptable input amount (considering slippage_basis_points)
+    pub max_amount_in: u64,
+    /// The swap fee included in `amount_in`
+    pub fee: u64,
+}
+
+/// Computes the input required to receive exactly `amount_out`, mirroring the program's
+/// SwapBaseOut math: `ceil(in_reserve * out / (out_reserve - out))`, grossed up by the swap fee.
+///
+/// # Returns
+/// An error when `amount_out` is zero or not below the output reserve
+pub fn compute_swap_amount_base_out(
+    base_reserve: u64,
+    quote_reserve: u64,
+    is_base_in: bool,
+    amount_out: u64,
+    slippage_basis_points: Bps,
+) -> Result<ComputeSwapOutParams> {
+    let (input_reserve, output_reserve) =
+        if is_base_in { (base_reserve, quote_reserve) } else { (quote_reserve, base_reserve) };
+    if amount_out == 0 || amount_out >= output_reserve {
+        return Err(anyhow!(
+            "Output amount {} is not within the pool reserve {}",
+            amount_out,
+            output_reserve
+        ));
+    }
+
+    let amount_in_before_fee = ((input_reserve as u128) * (amount_out as u128))
+        .div_ceil((output_reserve - amount_out) as u128);
+    let amount_in = (amount_in_before_fee * SWAP_FEE_DENOMINATOR as u128)
+        .div_ceil((SWAP_FEE_DENOMINATOR - SWAP_FEE_NUMERATOR) as u128);
+    let amount_in = u64::try_from(amount_in).map_err(|_| anyhow!("Input amount overflows u64"))?;
+
+    Ok(ComputeSwapOutParams {
+        amount_out,
+        amount_in,
+        max_amount_in: slippage_basis_points.apply_up(amount_in),
+        fee: amount_in - amount_in_before_fee as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_out_round_trips_base_in() {
+        let (base, quote) = (1_000_000_000_000, 50_000_000_000);
+        let quote_out =
+            compute_swap_amount_base_out(base, quote, false, 1_000_000_000, Bps::new(100)).unwrap();
+        assert!(quote_out.fee > 0);
+        assert_eq!(quote_out.max_amount_in, Bps::new(100).apply_up(quote_out.amount_in));
+
+        // 按反推的输入做固定输入报价，输出与目标输出一致（误差来自取整）
+        let quote_in = compute_swap_amount(base, quote, false, quote_out.amount_in, Bps::ZERO);
+        assert!(quote_in.amount_out.abs_diff(quote_out.amount_out) < quote_out.amount_out / 10_000);
+
+        assert!(compute_swap_amount_base_out(base, quote, true, quote, Bps::ZERO).is_err());
+        assert!(compute_swap_amount_base_out(base, quote, true, 0, Bps::ZERO).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::common::Bps;
 use crate::instruction::utils::raydium_cpmm::accounts::{
     CREATOR_FEE_RATE, FEE_RATE_DENOMINATOR_VALUE, FUND_FEE_RATE, PROTOCOL_FEE_RATE, TRADE_FEE_RATE,
 };
@@ -165,7 +166,7 @@ pub fn compute_swap_amount(
     quote_reserve: u64,
     is_base_in: bool,
     amount_in: u64,
-    slippage_basis_points: u64,
+    slippage_basis_points: Bps,
 ) -> ComputeSwapParams {
     let (input_reserve, output_reserve) =
         if is_base_in { (base_reserve, quote_reserve) } else { (quote_reserve, base_reserve) };
@@ -181,8 +182,8 @@ pub fn compute_swap_amount(
         true,
     );
 
-    let min_amount_out = ((swap_result.output_amount as f64)
-        * (1.0 - (slippage_basis_points as f64) / 10000.0)) as u64;
+    let min_amount_out =
+        ((swap_result.output_amount as f64) * (1.0 - slippage_basis_points.to_fraction())) as u64;
 
     let all_trade = swap_result.input_amount == amount_in;
 
@@ -0,0 +1,89 @@
+//! 协议自动识别
+//!
+//! 从区块浏览器复制的池地址无需事先知道所属 AMM：[`detect_protocol`] 根据账户所属程序
+//! 判断协议，并解码出可直接用于交易的 [`DexParamEnum`]。
+
+use crate::common::SolanaRpcClient;
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use crate::instruction::utils::{
+    bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_clmm, raydium_cpmm,
+};
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use anyhow::{Result, anyhow};
+use solana_sdk::pubkey::Pubkey;
+
+/// 根据账户所属程序返回协议类型（PumpFun 除外：bonding curve 无法反推 mint）
+pub fn dex_type_for_program(program_id: &Pubkey) -> Option<DexType> {
+    Some(match *program_id {
+        id if id == pumpswap::accounts::AMM_PROGRAM => DexType::PumpSwap,
+        id if id == bonk::accounts::BONK => DexType::Bonk,
+        id if id == raydium_cpmm::accounts::RAYDIUM_CPMM => DexType::RaydiumCpmm,
+        id if id == raydium_amm_v4::accounts::RAYDIUM_AMM_V4 => DexType::RaydiumAmmV4,
+        id if id == raydium_clmm::accounts::RAYDIUM_CLMM => DexType::RaydiumClmm,
+        id if id == meteora_damm_v2::accounts::METEORA_DAMM_V2 => DexType::MeteoraDammV2,
+        _ => return None,
+    })
+}
+
+/// 识别池地址所属协议并解码参数
+///
+/// - 池账户：按所属程序识别（PumpSwap、Bonk、Raydium CPMM / AMM V4 / CLMM、Meteora DAMM V2）；
+/// - 代币 mint：若存在未完成的 PumpFun bonding curve，识别为 PumpFun
+///   （PumpFun 的 bonding curve 账户不记录 mint，因此需要传 mint 而不是 bonding curve 地址）。
+pub async fn detect_protocol(
+    rpc: &SolanaRpcClient,
+    pool: &Pubkey,
+) -> Result<(DexType, DexParamEnum)> {
+    let account = rpc.get_account(pool).await.map_err(|e| anyhow!("RPC 调用失败: {}", e))?;
+
+    if let Some(dex_type) = dex_type_for_program(&account.owner) {
+        let params = DexParamEnum::from_pool(rpc, dex_type.clone(), pool).await?;
+        return Ok((dex_type, params));
+    }
+
+    if account.owner == TOKEN_PROGRAM || account.owner == TOKEN_PROGRAM_2022 {
+        let bonding_curve = pumpfun::get_bonding_curve_pda(pool)
+            .ok_or_else(|| anyhow!("Bonding curve PDA not found for mint {}", pool))?;
+        if rpc
+            .get_account(&bonding_curve)
+            .await
+            .is_ok_and(|a| a.owner == pumpfun::accounts::PUMPFUN)
+        {
+            let params = DexParamEnum::from_pool(rpc, DexType::PumpFun, pool).await?;
+            return Ok((DexType::PumpFun, params));
+        }
+        return Err(anyhow!(
+            "{} is a token mint without a PumpFun bonding curve; pass a pool address instead",
+            pool
+        ));
+    }
+
+    if account.owner == pumpfun::accounts::PUMPFUN {
+        return Err(anyhow!(
+            "{} is a PumpFun account; pass the token mint instead of the bonding curve",
+            pool
+        ));
+    }
+
+    Err(anyhow!("{} is owned by unsupported program {}", pool, account.owner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dex_type_for_program() {
+        assert_eq!(
+            dex_type_for_program(&raydium_cpmm::accounts::RAYDIUM_CPMM),
+            Some(DexType::RaydiumCpmm)
+        );
+        assert_eq!(
+            dex_type_for_program(&meteora_damm_v2::accounts::METEORA_DAMM_V2),
+            Some(DexType::MeteoraDammV2)
+        );
+        assert_eq!(dex_type_for_program(&pumpfun::accounts::PUMPFUN), None);
+        assert_eq!(dex_type_for_program(&TOKEN_PROGRAM), None);
+    }
+}
@@ -1,12 +1,16 @@
 pub mod calc;
+pub mod discovery;
 pub mod price;
 pub mod quote;
 pub mod token;
+pub mod wallet;
 use crate::TradingClient;
+use crate::common::{BalanceTracker, BalanceTrackerConfig};
 use crate::trading;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
+use std::sync::Arc;
 
 impl TradingClient {
     #[inline]
@@ -57,4 +61,15 @@ impl TradingClient {
     pub async fn close_token_account(&self, mint: &Pubkey) -> Result<(), anyhow::Error> {
         trading::common::utils::close_token_account(&self.rpc, self.payer.as_ref(), mint).await
     }
+
+    /// 创建并启动 payer 的余额跟踪器（ATA 推导沿用客户端的 seed 优化配置）
+    pub async fn start_balance_tracker(
+        &self,
+        config: BalanceTrackerConfig,
+    ) -> Result<Arc<BalanceTracker>, anyhow::Error> {
+        let config = config.with_seed_optimize(self.use_seed_optimize);
+        let tracker = Arc::new(BalanceTracker::new(self.rpc.clone(), self.payer.pubkey(), config));
+        tracker.start().await?;
+        Ok(tracker)
+    }
 }
@@ -0,0 +1,29 @@
+//! Meteora DAMM V2 价格计算
+//!
+//! DAMM V2 池的 `sqrt_price` 为 Q64.64 定点数，表示 token B / token A（最小单位）的平方根。
+
+/// Calculate the price of token A in token B
+///
+/// # Arguments
+/// * `sqrt_price` - The sqrt price of the pool (Q64.64)
+/// * `decimals_a` - The decimals of token A
+/// * `decimals_b` - The decimals of token B
+///
+/// # Returns
+/// The price of token A in token B
+pub fn price_token_a_in_token_b(sqrt_price: u128, decimals_a: u8, decimals_b: u8) -> f64 {
+    crate::utils::price::raydium_clmm::price_token0_in_token1(sqrt_price, decimals_a, decimals_b)
+}
+
+/// Calculate the price of token B in token A
+///
+/// # Arguments
+/// * `sqrt_price` - The sqrt price of the pool (Q64.64)
+/// * `decimals_a` - The decimals of token A
+/// * `decimals_b` - The decimals of token B
+///
+/// # Returns
+/// The price of token B in token A
+pub fn price_token_b_in_token_a(sqrt_price: u128, decimals_a: u8, decimals_b: u8) -> f64 {
+    crate::utils::price::raydium_clmm::price_token1_in_token0(sqrt_price, decimals_a, decimals_b)
+}
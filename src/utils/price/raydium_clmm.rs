@@ -1,3 +1,5 @@
+use crate::instruction::utils::raydium_clmm_types::ObservationState;
+
 /// Calculate the price of token0 in token1
 ///
 /// # Arguments
@@ -34,3 +36,128 @@ pub fn price_token1_in_token0(
 ) -> f64 {
     1.0 / price_token0_in_token1(sqrt_price_x64, decimals_token0, decimals_token1)
 }
+
+/// Calculate the price of token0 in token1 at a (possibly fractional) tick
+///
+/// `price = 1.0001^tick`, adjusted by token decimals
+pub fn price_from_tick(tick: f64, decimals_token0: u8, decimals_token1: u8) -> f64 {
+    let scale = 10f64.powi((decimals_token0 as i32) - (decimals_token1 as i32));
+    1.0001f64.powf(tick) * scale
+}
+
+/// 基于链上观测点的时间加权平均价（TWAP）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClmmTwap {
+    /// 区间内的平均 tick
+    pub tick: f64,
+    /// 区间起止时间（unix 秒，对应两个观测点）
+    pub start_timestamp: u32,
+    pub end_timestamp: u32,
+    /// token0 以 token1 计的 TWAP 价格（已按精度调整）
+    pub price_token0_in_token1: f64,
+}
+
+impl ClmmTwap {
+    /// 实际覆盖的时间窗口（秒）
+    pub fn window_secs(&self) -> u32 {
+        self.end_timestamp - self.start_timestamp
+    }
+
+    /// token1 以 token0 计的 TWAP 价格
+    pub fn price_token1_in_token0(&self) -> f64 {
+        1.0 / self.price_token0_in_token1
+    }
+
+    /// 现价相对 TWAP 的偏离（bps，正数表示现价高于 TWAP），可用于识别被操纵的现价
+    pub fn deviation_bps(&self, spot_price_token0_in_token1: f64) -> f64 {
+        (spot_price_token0_in_token1 / self.price_token0_in_token1 - 1.0) * 10_000.0
+    }
+}
+
+/// 从观测点环形缓冲区计算最近 `window_secs` 秒的 TWAP
+///
+/// 从最新观测点向前回溯到第一个不晚于 `最新时间 - window_secs` 的观测点；
+/// 历史不足时使用最早的有效观测点（见 [`ClmmTwap::window_secs`]）。
+/// 账户未初始化或有效观测点少于两个时返回 `None`。
+pub fn twap_from_observations(
+    state: &ObservationState,
+    window_secs: u32,
+    decimals_token0: u8,
+    decimals_token1: u8,
+) -> Option<ClmmTwap> {
+    let len = state.observations.len();
+    let latest_index = state.observation_index as usize % len;
+    let latest = state.observations[latest_index];
+    if !state.initialized || latest.block_timestamp == 0 {
+        return None;
+    }
+
+    let target = latest.block_timestamp.saturating_sub(window_secs);
+    let mut start = None;
+    let mut prev_timestamp = latest.block_timestamp;
+    for step in 1..len {
+        let obs = state.observations[(latest_index + len - step) % len];
+        // 未写入的槽位，或绕回到比后一个观测点还新的数据，说明已经到头
+        if obs.block_timestamp == 0 || obs.block_timestamp >= prev_timestamp {
+            break;
+        }
+        start = Some(obs);
+        prev_timestamp = obs.block_timestamp;
+        if obs.block_timestamp <= target {
+            break;
+        }
+    }
+
+    let start = start?;
+    let elapsed = latest.block_timestamp - start.block_timestamp;
+    let tick = latest.tick_cumulative.wrapping_sub(start.tick_cumulative) as f64 / elapsed as f64;
+    Some(ClmmTwap {
+        tick,
+        start_timestamp: start.block_timestamp,
+        end_timestamp: latest.block_timestamp,
+        price_token0_in_token1: price_from_tick(tick, decimals_token0, decimals_token1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::utils::raydium_clmm_types::{OBSERVATION_NUM, Observation};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn state(points: &[(usize, u32, i64)], observation_index: u16) -> ObservationState {
+        let mut observations = [Observation::default(); OBSERVATION_NUM];
+        for &(index, block_timestamp, tick_cumulative) in points {
+            observations[index] =
+                Observation { block_timestamp, tick_cumulative, ..Default::default() };
+        }
+        ObservationState {
+            initialized: true,
+            recent_epoch: 0,
+            observation_index,
+            pool_id: Pubkey::new_unique(),
+            observations,
+            padding: [0; 4],
+        }
+    }
+
+    #[test]
+    fn test_twap_from_observations() {
+        // tick 先为 100（0..60 秒）再为 200（60..120 秒），环形缓冲区在下标 0 处绕回
+        let ring = state(&[(98, 1_000, 0), (99, 1_060, 6_000), (0, 1_120, 18_000)], 0);
+        let twap = twap_from_observations(&ring, 60, 9, 9).unwrap();
+        assert_eq!((twap.start_timestamp, twap.window_secs()), (1_060, 60));
+        assert!((twap.tick - 200.0).abs() < 1e-9);
+
+        let twap = twap_from_observations(&ring, 600, 9, 6).unwrap();
+        assert_eq!(twap.window_secs(), 120);
+        assert!((twap.tick - 150.0).abs() < 1e-9);
+        assert!((twap.price_token0_in_token1 - 1.0001f64.powf(150.0) * 1_000.0).abs() < 1e-6);
+        assert!(twap.deviation_bps(twap.price_token0_in_token1 * 1.01).round() == 100.0);
+
+        assert_eq!(twap_from_observations(&state(&[(5, 1_000, 0)], 5), 60, 9, 9), None);
+        let mut uninitialized = ring.clone();
+        uninitialized.initialized = false;
+        assert_eq!(twap_from_observations(&uninitialized, 60, 9, 9), None);
+    }
+}
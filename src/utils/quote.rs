@@ -2,6 +2,7 @@
 ///
 /// This module provides a common result type so higher layers can compare pools
 /// using a consistent shape.
+use crate::common::Bps;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct QuoteExactInResult {
@@ -14,3 +15,39 @@ pub struct QuoteExactInResult {
     /// Number of extra on-chain accounts read to produce this quote.
     pub extra_accounts_read: usize,
 }
+
+/// Price impact (bps) of swapping `amount_in` against a constant-product pool whose input-side
+/// reserve is `reserve_in`: `amount_in / (reserve_in + amount_in)`, fees excluded.
+pub fn constant_product_impact_bps(reserve_in: u64, amount_in: u64) -> u64 {
+    let denominator = reserve_in as u128 + amount_in as u128;
+    if denominator == 0 {
+        return 0;
+    }
+    (amount_in as u128 * 10_000 / denominator) as u64
+}
+
+/// Largest `amount_in` whose constant-product price impact stays within `max_impact_bps`.
+pub fn max_amount_in_for_impact(reserve_in: u64, max_impact_bps: Bps) -> u64 {
+    if max_impact_bps >= Bps::HUNDRED_PERCENT {
+        return u64::MAX;
+    }
+    let amount = reserve_in as u128 * max_impact_bps.as_u128()
+        / (Bps::DENOMINATOR - max_impact_bps.as_u64()) as u128;
+    u64::try_from(amount).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_product_impact_round_trip() {
+        assert_eq!(constant_product_impact_bps(1_000_000, 0), 0);
+        assert_eq!(constant_product_impact_bps(0, 0), 0);
+        assert_eq!(constant_product_impact_bps(9_000, 1_000), 1_000);
+        let max_in = max_amount_in_for_impact(1_000_000, Bps::new(100));
+        assert!(constant_product_impact_bps(1_000_000, max_in) <= 100);
+        assert!(constant_product_impact_bps(1_000_000, max_in + 1_000) > 100);
+        assert_eq!(max_amount_in_for_impact(1_000_000, Bps::HUNDRED_PERCENT), u64::MAX);
+    }
+}
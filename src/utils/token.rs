@@ -3,7 +3,12 @@
 //! 提供跨项目使用的 Token 相关工具函数
 
 use crate::common::auto_mock_rpc::PoolRpcClient;
-use crate::constants::{RAY_MINT, SOL_MINT, USDC_MINT, USDT_MINT};
+use crate::common::shared_pool_cache::shared_pool_cache;
+use crate::constants::decimals::{DEFAULT_TOKEN_DECIMALS, SOL_DECIMALS};
+use crate::constants::{
+    RAY_MINT, SOL_MINT, SOL_TOKEN_ACCOUNT, TOKEN_PROGRAM, TOKEN_PROGRAM_2022, USDC_MINT, USDT_MINT,
+    WSOL_TOKEN_ACCOUNT,
+};
 use anyhow::Result;
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
@@ -34,6 +39,96 @@ pub fn get_cached_mint_info(mint: &Pubkey) -> Option<MintInfo> {
     MINT_INFO_CACHE.get(mint).map(|info| info.clone())
 }
 
+/// 全局代币精度缓存
+///
+/// 计算、计价、解析与执行各层共用：解析交易时从 token balance 中写入，加载池状态时从池账户中写入，
+/// 均未命中时才通过 RPC 读取 mint 账户。精度在 mint 创建后不可变，条目不会过期。
+static DECIMALS_CACHE: Lazy<DashMap<Pubkey, u8>> = Lazy::new(|| {
+    let cache = DashMap::with_capacity(MAX_TOKEN_METADATA_CACHE_SIZE);
+    cache.insert(WSOL_TOKEN_ACCOUNT, SOL_DECIMALS);
+    cache.insert(SOL_TOKEN_ACCOUNT, SOL_DECIMALS);
+    cache.insert(USDC_MINT, DEFAULT_TOKEN_DECIMALS);
+    cache.insert(USDT_MINT, DEFAULT_TOKEN_DECIMALS);
+    cache
+});
+
+/// mint account 中 `decimals` 字段的偏移（Token 与 Token2022 相同）
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// 单次 `getMultipleAccounts` 的最大账户数
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// 读取缓存的代币精度，不存在则返回 None（不访问 RPC）
+///
+/// 本地未命中时读取已安装的跨进程共享缓存（见 [`crate::common::shared_pool_cache`]）。
+pub fn cached_token_decimals(mint: &Pubkey) -> Option<u8> {
+    if let Some(decimals) = DECIMALS_CACHE.get(mint) {
+        return Some(*decimals);
+    }
+    let decimals = shared_pool_cache()?.get_decimals(mint)?;
+    DECIMALS_CACHE.insert(*mint, decimals);
+    Some(decimals)
+}
+
+/// 写入已知的代币精度（例如从交易 token balance 或池状态中得到）
+///
+/// 本进程是共享缓存的写入方时同时发布给其他进程。
+pub fn cache_token_decimals(mint: Pubkey, decimals: u8) {
+    if DECIMALS_CACHE.insert(mint, decimals) == Some(decimals) {
+        return;
+    }
+    if let Some(shared) = shared_pool_cache().filter(|cache| cache.is_writer())
+        && let Err(e) = shared.put_decimals(&mint, decimals)
+    {
+        log::warn!("failed to publish decimals of {}: {}", mint, e);
+    }
+}
+
+/// 从已读取的 mint 账户中解析精度并写入缓存；不是 Token / Token2022 mint 时返回 None
+pub fn cache_decimals_from_mint_account(
+    mint: &Pubkey,
+    account: &solana_sdk::account::Account,
+) -> Option<u8> {
+    if account.owner != TOKEN_PROGRAM && account.owner != TOKEN_PROGRAM_2022 {
+        return None;
+    }
+    if account.data.len() < Mint::LEN {
+        return None;
+    }
+    let decimals = account.data[MINT_DECIMALS_OFFSET];
+    cache_token_decimals(*mint, decimals);
+    Some(decimals)
+}
+
+/// 批量预热代币精度：跳过已缓存的 mint，其余按每批 100 个通过 `getMultipleAccounts` 读取
+///
+/// 不存在或不是 mint 账户的地址会被忽略；返回本次新写入缓存的数量。
+pub async fn warm_token_decimals(
+    rpc: &crate::common::SolanaRpcClient,
+    mints: &[Pubkey],
+) -> Result<usize> {
+    let mut missing: Vec<Pubkey> = mints
+        .iter()
+        .filter(|mint| !DECIMALS_CACHE.contains_key(*mint))
+        .copied()
+        .collect();
+    missing.sort_unstable();
+    missing.dedup();
+
+    let mut warmed = 0;
+    for chunk in missing.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc.get_multiple_accounts(chunk).await?;
+        for (mint, account) in chunk.iter().zip(accounts) {
+            if account
+                .is_some_and(|account| cache_decimals_from_mint_account(mint, &account).is_some())
+            {
+                warmed += 1;
+            }
+        }
+    }
+    Ok(warmed)
+}
+
 /// 获取 Mint 账户的完整信息（统一实现，支持 Token 和 Token2022）
 ///
 /// 使用全局缓存减少 RPC 调用
@@ -52,6 +147,7 @@ pub async fn get_mint_info(
             is_token2022: false,
         };
         MINT_INFO_CACHE.insert(*mint, info.clone());
+        cache_token_decimals(*mint, info.decimals);
         return Ok(info);
     }
 
@@ -74,6 +170,7 @@ pub async fn get_mint_info(
 
             let info = MintInfo { decimals, symbol, is_token2022: true };
             MINT_INFO_CACHE.insert(*mint, info.clone());
+            cache_token_decimals(*mint, decimals);
             return Ok(info);
         }
     }
@@ -107,6 +204,7 @@ pub async fn get_mint_info_with_client<T: PoolRpcClient + ?Sized>(
             is_token2022: false,
         };
         MINT_INFO_CACHE.insert(*mint, info.clone());
+        cache_token_decimals(*mint, info.decimals);
         return Ok(info);
     }
 
@@ -129,6 +227,7 @@ pub async fn get_mint_info_with_client<T: PoolRpcClient + ?Sized>(
 
             let info = MintInfo { decimals, symbol, is_token2022: true };
             MINT_INFO_CACHE.insert(*mint, info.clone());
+            cache_token_decimals(*mint, decimals);
             return Ok(info);
         }
     }
@@ -156,8 +255,8 @@ pub async fn get_token_decimals_with_client<T: PoolRpcClient + ?Sized>(
     mint: &Pubkey,
 ) -> Result<u8> {
     // Fast path: 检查缓存
-    if let Some(info) = get_cached_mint_info(mint) {
-        return Ok(info.decimals);
+    if let Some(decimals) = cached_token_decimals(mint) {
+        return Ok(decimals);
     }
 
     // 缓存未命中，获取完整 MintInfo
@@ -418,4 +517,29 @@ mod tests {
         assert_eq!(info1.symbol, info2.symbol);
         assert_eq!(info1.is_token2022, info2.is_token2022);
     }
+
+    #[test]
+    fn test_decimals_cache_seeded() {
+        assert_eq!(cached_token_decimals(&WSOL_TOKEN_ACCOUNT), Some(9));
+        assert_eq!(cached_token_decimals(&USDC_MINT), Some(6));
+        assert_eq!(cached_token_decimals(&USDT_MINT), Some(6));
+
+        let mint = Pubkey::new_unique();
+        assert_eq!(cached_token_decimals(&mint), None);
+        cache_token_decimals(mint, 5);
+        assert_eq!(cached_token_decimals(&mint), Some(5));
+
+        let other = Pubkey::new_unique();
+        let mut data = vec![0u8; Mint::LEN];
+        Mint { decimals: 8, is_initialized: true, ..Default::default() }.pack_into_slice(&mut data);
+        let account = solana_sdk::account::Account {
+            lamports: 1,
+            data,
+            owner: TOKEN_PROGRAM,
+            executable: false,
+            rent_epoch: 0,
+        };
+        assert_eq!(cache_decimals_from_mint_account(&other, &account), Some(8));
+        assert_eq!(cached_token_decimals(&other), Some(8));
+    }
 }
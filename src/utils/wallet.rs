@@ -0,0 +1,714 @@
+//! 钱包代币账户管理
+//!
+//! 长期运行的交易机器人会积累大量余额为 0 的 ATA，每个占用约 0.002 SOL 租金。
+//! [`TradingClient::sweep_empty_atas`] 扫描 payer 名下所有代币账户（SPL Token 与 Token-2022），
+//! 按交易大小限制分批关闭空账户并汇总回收的租金。
+//!
+//! [`TradingClient::rent_harvest_report`] 只读扫描可回收的价值（空账户租金、残留的 WSOL 临时账户、
+//! WSOL ATA 中的闲置余额），[`TradingClient::execute_cleanup`] 一次性执行全部回收。
+//!
+//! 切换 `use_seed_optimize` 后同一 mint 的余额可能分散在标准 ATA 与 seed 账户中，
+//! [`TradingClient::reconcile_token_accounts`] 将 seed 账户余额合并到标准 ATA 并关闭 seed 账户。
+
+use crate::TradingClient;
+use crate::common::fast_fn::{
+    create_associated_token_account_idempotent_fast,
+    get_associated_token_address_with_program_id_fast,
+};
+use crate::common::seed::get_associated_token_address_with_program_id_use_seed;
+use crate::common::transfer_hook::{
+    TransferHookInfo, append_transfer_hook_accounts, fetch_transfer_hook_info,
+};
+use crate::common::{SolanaRpcClient, auto_mock_rpc::PoolRpcClient, spl_token::close_account};
+use crate::constants::{
+    TOKEN_PROGRAM, TOKEN_PROGRAM_2022, WSOL_TOKEN_ACCOUNT, trade_consts::MAX_TRANSACTION_SIZE,
+};
+use anyhow::{Result, anyhow};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use solana_account_decoder::{UiAccountData, UiAccountEncoding};
+use solana_client::rpc_filter::Memcmp;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::RpcFilterType,
+};
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use spl_token::solana_program::program_pack::Pack;
+use spl_token::state::Mint as SplMint;
+use spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, AccountState};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// 代币账户中 owner 字段的偏移（mint 32 字节之后）
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+
+/// 钱包中的一个代币账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletTokenAccount {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    /// 所属代币程序（SPL Token 或 Token-2022）
+    pub token_program: Pubkey,
+    /// 代币余额（最小单位）
+    pub amount: u64,
+    /// 账户 lamports（关闭后回收的租金）
+    pub lamports: u64,
+    pub is_frozen: bool,
+    pub is_native: bool,
+    pub close_authority: Option<Pubkey>,
+    /// Token-2022 TransferFeeAmount 扩展中被扣留的转账手续费，需先提取才能关闭账户
+    pub withheld_amount: u64,
+}
+
+impl WalletTokenAccount {
+    /// 是否可由 `owner` 直接关闭：余额为 0、无扣留的手续费、未冻结、关闭权限属于 owner
+    ///
+    /// WSOL 账户不在此列：SDK 启动时会创建并复用 WSOL ATA，由 `close_wsol` 单独管理。
+    pub fn is_closable_by(&self, owner: &Pubkey) -> bool {
+        self.amount == 0
+            && self.withheld_amount == 0
+            && !self.is_frozen
+            && !self.is_native
+            && self.mint != WSOL_TOKEN_ACCOUNT
+            && self.close_authority.is_none_or(|authority| authority == *owner)
+    }
+}
+
+/// 空账户清理结果
+#[derive(Debug, Clone, Default)]
+pub struct SweepReport {
+    /// 扫描到的代币账户数
+    pub scanned: usize,
+    /// 成功关闭的账户
+    pub closed: Vec<Pubkey>,
+    /// 回收的租金（lamports）
+    pub rent_reclaimed: u64,
+    /// 每批关闭交易的签名
+    pub signatures: Vec<Signature>,
+    /// 发送失败的批次：(批次中的账户, 错误信息)
+    pub failed: Vec<(Vec<Pubkey>, String)>,
+}
+
+/// 查询 `owner` 名下所有代币账户（SPL Token 与 Token-2022）
+pub async fn get_token_accounts_by_owner<T: PoolRpcClient + ?Sized>(
+    rpc: &T,
+    owner: &Pubkey,
+) -> Result<Vec<WalletTokenAccount>> {
+    let mut token_accounts = Vec::new();
+    for token_program in [TOKEN_PROGRAM, TOKEN_PROGRAM_2022] {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                TOKEN_ACCOUNT_OWNER_OFFSET,
+                &owner.to_bytes(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+            sort_results: None,
+        };
+        let accounts = rpc
+            .get_program_ui_accounts_with_config(&token_program, config)
+            .await
+            .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+        token_accounts.extend(accounts.into_iter().filter_map(|(address, account)| {
+            let data = match &account.data {
+                UiAccountData::Binary(base64_str, _) => STANDARD.decode(base64_str).ok()?,
+                _ => return None,
+            };
+            let mut token_account =
+                decode_token_account(&Pubkey::from_str(&address).ok()?, &data, token_program)?;
+            token_account.lamports = account.lamports;
+            Some(token_account)
+        }));
+    }
+    Ok(token_accounts)
+}
+
+/// 解析代币账户数据（SPL Token 与 Token-2022，含扩展；未初始化的账户返回 None）
+fn decode_token_account(
+    address: &Pubkey,
+    data: &[u8],
+    token_program: Pubkey,
+) -> Option<WalletTokenAccount> {
+    let state = StateWithExtensions::<TokenAccount>::unpack(data).ok()?;
+    let account = state.base;
+    if account.state == AccountState::Uninitialized {
+        return None;
+    }
+    let withheld_amount = state
+        .get_extension::<TransferFeeAmount>()
+        .map(|extension| u64::from(extension.withheld_amount))
+        .unwrap_or(0);
+    Some(WalletTokenAccount {
+        address: *address,
+        mint: account.mint,
+        token_program,
+        amount: account.amount,
+        lamports: 0,
+        is_frozen: account.state == AccountState::Frozen,
+        is_native: account.is_native.is_some(),
+        close_authority: account.close_authority.into(),
+        withheld_amount,
+    })
+}
+
+/// 将关闭指令按交易大小限制分批（每批一笔交易）
+fn batch_close_instructions(
+    payer: &Keypair,
+    instructions: Vec<(Pubkey, Instruction)>,
+) -> Result<Vec<Vec<(Pubkey, Instruction)>>> {
+    let mut batches: Vec<Vec<(Pubkey, Instruction)>> = Vec::new();
+    let mut current: Vec<(Pubkey, Instruction)> = Vec::new();
+    for item in instructions {
+        current.push(item);
+        if current.len() > 1 && transaction_size(payer, &current)? > MAX_TRANSACTION_SIZE {
+            let item = current.pop().expect("batch is non-empty");
+            batches.push(std::mem::take(&mut current));
+            current.push(item);
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    Ok(batches)
+}
+
+fn transaction_size(payer: &Keypair, batch: &[(Pubkey, Instruction)]) -> Result<usize> {
+    let instructions: Vec<Instruction> = batch.iter().map(|(_, ix)| ix.clone()).collect();
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        Hash::default(),
+    );
+    Ok(bincode::serialized_size(&tx).map_err(|e| anyhow!("交易序列化失败: {}", e))? as usize)
+}
+
+/// 关闭 `payer` 名下所有余额为 0 的代币账户，租金退回 payer
+pub async fn sweep_empty_atas(rpc: &SolanaRpcClient, payer: &Keypair) -> Result<SweepReport> {
+    let owner = payer.pubkey();
+    let token_accounts = get_token_accounts_by_owner(rpc, &owner).await?;
+    let mut report = SweepReport { scanned: token_accounts.len(), ..Default::default() };
+
+    let mut lamports_by_account = HashMap::new();
+    let mut instructions = Vec::new();
+    for account in token_accounts.iter().filter(|account| account.is_closable_by(&owner)) {
+        let ix = close_account(&account.token_program, &account.address, &owner, &owner, &[])?;
+        lamports_by_account.insert(account.address, account.lamports);
+        instructions.push((account.address, ix));
+    }
+    if instructions.is_empty() {
+        return Ok(report);
+    }
+
+    close_in_batches(rpc, payer, instructions, &lamports_by_account, &mut report).await?;
+    Ok(report)
+}
+
+/// 分批发送关闭指令并把结果累计到 `report`
+async fn close_in_batches(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    instructions: Vec<(Pubkey, Instruction)>,
+    lamports_by_account: &HashMap<Pubkey, u64>,
+    report: &mut SweepReport,
+) -> Result<()> {
+    let owner = payer.pubkey();
+    for batch in batch_close_instructions(payer, instructions)? {
+        let (addresses, batch_instructions): (Vec<Pubkey>, Vec<Instruction>) =
+            batch.into_iter().unzip();
+        match send_instructions(rpc, payer, &batch_instructions).await {
+            Ok(signature) => {
+                report.signatures.push(signature);
+                report.rent_reclaimed +=
+                    addresses.iter().map(|address| lamports_by_account[address]).sum::<u64>();
+                report.closed.extend(addresses);
+            },
+            Err(e) => {
+                log::warn!("failed to close {} accounts for {}: {}", addresses.len(), owner, e);
+                report.failed.push((addresses, e.to_string()));
+            },
+        }
+    }
+    Ok(())
+}
+
+async fn send_instructions(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+) -> Result<Signature> {
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    Ok(rpc.send_and_confirm_transaction(&transaction).await?)
+}
+
+/// 可回收价值的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReclaimKind {
+    /// 余额为 0 的代币账户，关闭后回收租金
+    EmptyTokenAccount,
+    /// WSOL ATA 以外的 WSOL 账户（如 `wrap_wsol_to_sol` 未关闭的 seed 账户），关闭后回收余额与租金
+    StaleWsolAccount,
+    /// WSOL ATA 中的闲置余额，解包为 SOL（保留 ATA 供后续交易使用）
+    ResidualWsol,
+}
+
+/// 一项可回收的价值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReclaimableItem {
+    pub kind: ReclaimKind,
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    /// 可回收的 lamports
+    pub lamports: u64,
+}
+
+/// 可回收租金 / 余额报告
+#[derive(Debug, Clone, Default)]
+pub struct RentHarvestReport {
+    /// 扫描到的代币账户数
+    pub scanned: usize,
+    pub items: Vec<ReclaimableItem>,
+}
+
+impl RentHarvestReport {
+    /// 可回收的 lamports 合计
+    pub fn total_lamports(&self) -> u64 {
+        self.items.iter().map(|item| item.lamports).sum()
+    }
+
+    /// 某一类来源的 lamports 合计
+    pub fn lamports_of(&self, kind: ReclaimKind) -> u64 {
+        self.items
+            .iter()
+            .filter(|item| item.kind == kind)
+            .map(|item| item.lamports)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl std::fmt::Display for RentHarvestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "scanned {} accounts, reclaimable {:.6} SOL (empty accounts {}, stale WSOL {}, residual WSOL {})",
+            self.scanned,
+            self.total_lamports() as f64 / 1e9,
+            self.lamports_of(ReclaimKind::EmptyTokenAccount),
+            self.lamports_of(ReclaimKind::StaleWsolAccount),
+            self.lamports_of(ReclaimKind::ResidualWsol),
+        )
+    }
+}
+
+/// 根据代币账户列表生成回收报告
+pub fn build_harvest_report(owner: &Pubkey, accounts: &[WalletTokenAccount]) -> RentHarvestReport {
+    let wsol_ata = get_associated_token_address_with_program_id_fast(
+        owner,
+        &WSOL_TOKEN_ACCOUNT,
+        &TOKEN_PROGRAM,
+    );
+    let items = accounts
+        .iter()
+        .filter_map(|account| {
+            let closable = !account.is_frozen
+                && account.close_authority.is_none_or(|authority| authority == *owner);
+            let (kind, lamports) = if account.is_closable_by(owner) {
+                (ReclaimKind::EmptyTokenAccount, account.lamports)
+            } else if account.mint == WSOL_TOKEN_ACCOUNT && account.address != wsol_ata {
+                if !closable {
+                    return None;
+                }
+                (ReclaimKind::StaleWsolAccount, account.lamports)
+            } else if account.mint == WSOL_TOKEN_ACCOUNT && account.amount > 0 && !account.is_frozen
+            {
+                (ReclaimKind::ResidualWsol, account.amount)
+            } else {
+                return None;
+            };
+            Some(ReclaimableItem {
+                kind,
+                account: account.address,
+                mint: account.mint,
+                token_program: account.token_program,
+                lamports,
+            })
+        })
+        .collect();
+    RentHarvestReport { scanned: accounts.len(), items }
+}
+
+/// 标准 ATA 与 seed 账户合并结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconcileReport {
+    pub mint: Pubkey,
+    /// 合并后保留的标准 ATA
+    pub canonical_account: Pubkey,
+    /// 被合并的 seed 账户
+    pub seed_account: Pubkey,
+    /// 从 seed 账户转入 ATA 的代币数量（最小单位）
+    pub moved_amount: u64,
+    /// seed 账户是否已关闭（关闭权限不属于 owner 时只转移余额）
+    pub seed_account_closed: bool,
+    /// 合并交易签名（seed 账户不存在时为 None）
+    pub signature: Option<Signature>,
+}
+
+/// 构建合并指令：幂等创建 ATA → 转移 seed 账户全部余额 → 关闭 seed 账户
+///
+/// 返回指令与 seed 账户是否会被关闭。
+pub fn build_reconcile_instructions(
+    owner: &Pubkey,
+    seed_account: &WalletTokenAccount,
+    canonical_account: &Pubkey,
+    decimals: u8,
+    hook: Option<&TransferHookInfo>,
+) -> Result<(Vec<Instruction>, bool)> {
+    if seed_account.is_frozen {
+        return Err(anyhow!("Seed account {} is frozen", seed_account.address));
+    }
+    let mint = &seed_account.mint;
+    let token_program = &seed_account.token_program;
+    let mut instructions =
+        create_associated_token_account_idempotent_fast(owner, owner, mint, token_program);
+    if seed_account.amount > 0 {
+        let mut transfer_ix = crate::common::spl_token::transfer_checked(
+            token_program,
+            &seed_account.address,
+            mint,
+            canonical_account,
+            owner,
+            seed_account.amount,
+            decimals,
+        )?;
+        append_transfer_hook_accounts(
+            &mut transfer_ix.accounts,
+            hook,
+            &seed_account.address,
+            mint,
+            canonical_account,
+            owner,
+            seed_account.amount,
+        )?;
+        instructions.push(transfer_ix);
+    }
+    // 扣留的转账手续费需由 mint 的提取权限取走，之前无法关闭
+    let closable = seed_account.withheld_amount == 0
+        && seed_account.close_authority.is_none_or(|authority| authority == *owner);
+    if closable {
+        instructions.push(close_account(token_program, &seed_account.address, owner, owner, &[])?);
+    }
+    Ok((instructions, closable))
+}
+
+impl TradingClient {
+    /// 查询 payer 名下所有代币账户
+    pub async fn get_payer_token_accounts(&self) -> Result<Vec<WalletTokenAccount>> {
+        get_token_accounts_by_owner(self.rpc.as_ref(), &self.payer.pubkey()).await
+    }
+
+    /// 关闭 payer 名下所有余额为 0 的代币账户（WSOL 账户与仍有扣留转账手续费的 Token-2022 账户除外），
+    /// 返回回收的租金等统计
+    ///
+    /// 关闭指令按交易大小限制分批发送并等待确认；单批失败不影响其余批次，记录在 `failed` 中。
+    pub async fn sweep_empty_atas(&self) -> Result<SweepReport> {
+        sweep_empty_atas(&self.rpc, &self.payer).await
+    }
+
+    /// 扫描 payer 名下可回收的租金与闲置 WSOL（只读，不发送交易）
+    pub async fn rent_harvest_report(&self) -> Result<RentHarvestReport> {
+        let accounts = self.get_payer_token_accounts().await?;
+        Ok(build_harvest_report(&self.payer.pubkey(), &accounts))
+    }
+
+    /// 执行回收报告中的全部项目，返回实际回收的统计
+    ///
+    /// 空账户与残留 WSOL 账户按交易大小分批关闭；WSOL ATA 中的闲置余额通过临时 seed 账户
+    /// 解包为 SOL，ATA 本身保留。`rent_reclaimed` 包含解包的 WSOL 余额。
+    pub async fn execute_cleanup(&self) -> Result<SweepReport> {
+        let report = self.rent_harvest_report().await?;
+        let owner = self.payer.pubkey();
+        let mut result = SweepReport { scanned: report.scanned, ..Default::default() };
+
+        let mut lamports_by_account = HashMap::new();
+        let mut instructions = Vec::new();
+        for item in report.items.iter().filter(|item| item.kind != ReclaimKind::ResidualWsol) {
+            let ix = close_account(&item.token_program, &item.account, &owner, &owner, &[])?;
+            lamports_by_account.insert(item.account, item.lamports);
+            instructions.push((item.account, ix));
+        }
+        close_in_batches(&self.rpc, &self.payer, instructions, &lamports_by_account, &mut result)
+            .await?;
+
+        // 残留的 seed 账户关闭后才能重新创建，用于解包闲置 WSOL
+        for item in report.items.iter().filter(|item| item.kind == ReclaimKind::ResidualWsol) {
+            let unwrap_instructions =
+                crate::trading::common::wsol_manager::wrap_wsol_to_sol(&owner, item.lamports)?;
+            match send_instructions(&self.rpc, &self.payer, &unwrap_instructions).await {
+                Ok(signature) => {
+                    result.signatures.push(signature);
+                    result.rent_reclaimed += item.lamports;
+                },
+                Err(e) => {
+                    log::warn!("execute_cleanup: failed to unwrap residual WSOL: {}", e);
+                    result.failed.push((vec![item.account], e.to_string()));
+                },
+            }
+        }
+        Ok(result)
+    }
+
+    /// 将 `mint` 在 seed 账户中的余额合并到标准 ATA，并关闭 seed 账户回收租金
+    ///
+    /// 用于开关 `use_seed_optimize` 之后整理分散的余额。seed 账户不存在时不发送交易；
+    /// 合并完成后应关闭 seed 优化（全局或按笔 `use_seed_optimize: Some(false)`）使用标准 ATA 交易。
+    pub async fn reconcile_token_accounts(&self, mint: &Pubkey) -> Result<ReconcileReport> {
+        if *mint == WSOL_TOKEN_ACCOUNT {
+            return Err(anyhow!("WSOL accounts are managed by wrap/close_wsol, not reconciled"));
+        }
+        let owner = self.payer.pubkey();
+        let mint_account = self.rpc.get_account(mint).await?;
+        let token_program = mint_account.owner;
+        if token_program != TOKEN_PROGRAM && token_program != TOKEN_PROGRAM_2022 {
+            return Err(anyhow!("{} is not a token mint (owner {})", mint, token_program));
+        }
+        if mint_account.data.len() < SplMint::LEN {
+            return Err(anyhow!("Invalid mint account data for {}", mint));
+        }
+        let decimals = SplMint::unpack_from_slice(&mint_account.data[..SplMint::LEN])?.decimals;
+        crate::utils::token::cache_token_decimals(*mint, decimals);
+
+        let canonical_account =
+            get_associated_token_address_with_program_id_fast(&owner, mint, &token_program);
+        let seed_address =
+            get_associated_token_address_with_program_id_use_seed(&owner, mint, &token_program)?;
+        let mut report = ReconcileReport {
+            mint: *mint,
+            canonical_account,
+            seed_account: seed_address,
+            moved_amount: 0,
+            seed_account_closed: false,
+            signature: None,
+        };
+
+        let Some(seed_account) = self
+            .rpc
+            .get_account_with_commitment(&seed_address, self.rpc.commitment())
+            .await?
+            .value
+            .and_then(|account| decode_token_account(&seed_address, &account.data, account.owner))
+        else {
+            return Ok(report);
+        };
+        if seed_account.mint != *mint {
+            return Err(anyhow!("Seed account {} holds mint {}", seed_address, seed_account.mint));
+        }
+
+        let hook = fetch_transfer_hook_info(self.rpc.as_ref(), mint).await?;
+        let (instructions, closable) = build_reconcile_instructions(
+            &owner,
+            &seed_account,
+            &canonical_account,
+            decimals,
+            hook.as_deref(),
+        )?;
+        report.signature = Some(send_instructions(&self.rpc, &self.payer, &instructions).await?);
+        report.moved_amount = seed_account.amount;
+        report.seed_account_closed = closable;
+        Ok(report)
+    }
+
+    /// 按固定间隔生成回收报告并交给 `on_report`（不自动执行回收）
+    pub fn spawn_rent_harvest_reports<F>(
+        &self,
+        interval: std::time::Duration,
+        on_report: F,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn(RentHarvestReport) + Send + Sync + 'static,
+    {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match client.rent_harvest_report().await {
+                    Ok(report) => on_report(report),
+                    Err(e) => log::warn!("rent harvest report failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_account(owner: &Pubkey) -> WalletTokenAccount {
+        WalletTokenAccount {
+            address: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM,
+            amount: 0,
+            lamports: 2_039_280,
+            is_frozen: false,
+            is_native: false,
+            close_authority: Some(*owner),
+            withheld_amount: 0,
+        }
+    }
+
+    #[test]
+    fn test_is_closable_by() {
+        let owner = Pubkey::new_unique();
+        let account = empty_account(&owner);
+        assert!(account.is_closable_by(&owner));
+        assert!(!WalletTokenAccount { amount: 1, ..account.clone() }.is_closable_by(&owner));
+        assert!(!WalletTokenAccount { is_frozen: true, ..account.clone() }.is_closable_by(&owner));
+        assert!(
+            !WalletTokenAccount { withheld_amount: 1, ..account.clone() }.is_closable_by(&owner)
+        );
+        assert!(
+            !WalletTokenAccount { mint: WSOL_TOKEN_ACCOUNT, ..account.clone() }
+                .is_closable_by(&owner)
+        );
+        assert!(
+            !WalletTokenAccount { close_authority: Some(Pubkey::new_unique()), ..account }
+                .is_closable_by(&owner)
+        );
+    }
+
+    #[test]
+    fn test_decode_token_2022_withheld_fee() {
+        use spl_token_2022::extension::{
+            BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+        };
+
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let len = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+            ExtensionType::TransferFeeAmount,
+        ])
+        .unwrap();
+        let mut data = vec![0u8; len];
+        let mut state =
+            StateWithExtensionsMut::<TokenAccount>::unpack_uninitialized(&mut data).unwrap();
+        state.init_extension::<TransferFeeAmount>(true).unwrap().withheld_amount = 42.into();
+        state.base = TokenAccount {
+            mint,
+            owner,
+            state: AccountState::Initialized,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        let address = Pubkey::new_unique();
+        let account = decode_token_account(&address, &data, TOKEN_PROGRAM_2022).unwrap();
+        assert_eq!((account.mint, account.amount, account.withheld_amount), (mint, 0, 42));
+        assert!(!account.is_closable_by(&owner));
+        assert!(decode_token_account(&address, &vec![0u8; len], TOKEN_PROGRAM_2022).is_none());
+    }
+
+    #[test]
+    fn test_build_harvest_report() {
+        let owner = Pubkey::new_unique();
+        let empty = empty_account(&owner);
+        let wsol_ata = WalletTokenAccount {
+            address: get_associated_token_address_with_program_id_fast(
+                &owner,
+                &WSOL_TOKEN_ACCOUNT,
+                &TOKEN_PROGRAM,
+            ),
+            mint: WSOL_TOKEN_ACCOUNT,
+            amount: 5_000_000,
+            is_native: true,
+            ..empty.clone()
+        };
+        let stale_wsol = WalletTokenAccount { address: Pubkey::new_unique(), ..wsol_ata.clone() };
+        let held = WalletTokenAccount { amount: 10, ..empty_account(&owner) };
+
+        let report = build_harvest_report(&owner, &[empty.clone(), wsol_ata, stale_wsol, held]);
+        assert_eq!(report.scanned, 4);
+        assert_eq!(report.items.len(), 3);
+        assert_eq!(report.lamports_of(ReclaimKind::EmptyTokenAccount), empty.lamports);
+        assert_eq!(report.lamports_of(ReclaimKind::StaleWsolAccount), empty.lamports);
+        assert_eq!(report.lamports_of(ReclaimKind::ResidualWsol), 5_000_000);
+        assert_eq!(report.total_lamports(), 2 * empty.lamports + 5_000_000);
+    }
+
+    #[test]
+    fn test_build_reconcile_instructions() {
+        let owner = Pubkey::new_unique();
+        let mut seed_account = WalletTokenAccount {
+            amount: 1_000,
+            mint: Pubkey::new_unique(),
+            ..empty_account(&owner)
+        };
+        let canonical = get_associated_token_address_with_program_id_fast(
+            &owner,
+            &seed_account.mint,
+            &seed_account.token_program,
+        );
+        let (instructions, closable) =
+            build_reconcile_instructions(&owner, &seed_account, &canonical, 6, None).unwrap();
+        assert!(closable);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[1].data[0], 12);
+        assert_eq!(instructions[1].accounts[2].pubkey, canonical);
+        assert_eq!(instructions[2].data, vec![9]);
+
+        seed_account.amount = 0;
+        seed_account.close_authority = Some(Pubkey::new_unique());
+        let (instructions, closable) =
+            build_reconcile_instructions(&owner, &seed_account, &canonical, 6, None).unwrap();
+        assert!(!closable);
+        assert_eq!(instructions.len(), 1);
+
+        seed_account.is_frozen = true;
+        assert!(build_reconcile_instructions(&owner, &seed_account, &canonical, 6, None).is_err());
+    }
+
+    #[test]
+    fn test_batch_close_instructions_respects_size() {
+        let payer = Keypair::new();
+        let owner = payer.pubkey();
+        let instructions: Vec<_> = (0..60)
+            .map(|_| {
+                let account = Pubkey::new_unique();
+                (account, close_account(&TOKEN_PROGRAM, &account, &owner, &owner, &[]).unwrap())
+            })
+            .collect();
+        let batches = batch_close_instructions(&payer, instructions).unwrap();
+        assert!(batches.len() > 1);
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 60);
+        for batch in &batches {
+            assert!(transaction_size(&payer, batch).unwrap() <= MAX_TRANSACTION_SIZE);
+        }
+    }
+}
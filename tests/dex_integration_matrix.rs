@@ -0,0 +1,264 @@
+//! 协议集成测试矩阵：每个 DexType 一个测试，在本地验证器上执行一次买入 -> 全部卖出
+//!
+//! 共享代码（参数构建、ATA 处理、指令组装、发送）改动后，用于发现某个协议构建器的回归。
+//! 新增协议时在此文件中补充一个 [`MatrixCase`] 与对应测试。
+//!
+//! 测试假设：
+//! - 本地 RPC `http://127.0.0.1:8899` 已接入主网状态（例如使用 surfpool 按需克隆账户）
+//! - 各协议程序及下列池子在该 RPC 上可用
+//!
+//! 池子与代币可通过环境变量覆盖（`MATRIX_<DEX>_POOL` / `MATRIX_<DEX>_MINT`，如
+//! `MATRIX_BONK_POOL`）。没有固定主网池的协议（PumpFun、Bonk）未设置环境变量时跳过。
+//! PumpFun 的池地址即代币 mint。
+//!
+//! 运行测试:
+//!     cargo test --features integration-matrix --test dex_integration_matrix -- --nocapture
+//!     make test-matrix
+
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
+use sol_trade_sdk::{
+    DexType, TradeBuyParams, TradeSellParams, TradeTokenType, common::GasFeeStrategy,
+    trading::core::params::DexParamEnum,
+};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::str::FromStr;
+
+mod test_helpers;
+use test_helpers::{create_test_client, print_token_balance};
+
+const RPC_URL: &str = "http://127.0.0.1:8899";
+
+/// 每个协议买入 0.01 SOL
+const BUY_AMOUNT_LAMPORTS: u64 = 10_000_000;
+
+/// 矩阵中的一个协议用例
+struct MatrixCase {
+    dex_type: DexType,
+    /// 环境变量前缀，如 `PUMPSWAP` 对应 `MATRIX_PUMPSWAP_POOL` / `MATRIX_PUMPSWAP_MINT`
+    env_name: &'static str,
+    pool: Option<&'static str>,
+    mint: Option<&'static str>,
+}
+
+impl MatrixCase {
+    /// 解析池与代币地址；未配置时返回 None（跳过）
+    fn resolve(&self) -> Option<(Pubkey, Pubkey)> {
+        let lookup = |suffix: &str, default: Option<&'static str>| {
+            std::env::var(format!("MATRIX_{}_{}", self.env_name, suffix))
+                .ok()
+                .or_else(|| default.map(str::to_string))
+        };
+        let mint = lookup("MINT", self.mint)?;
+        // PumpFun 以 mint 作为池地址
+        let pool = lookup("POOL", self.pool).unwrap_or_else(|| mint.clone());
+        let parse = |value: &str| {
+            Pubkey::from_str(value).unwrap_or_else(|_| panic!("Invalid address: {}", value))
+        };
+        Some((parse(&pool), parse(&mint)))
+    }
+}
+
+/// 买入 `BUY_AMOUNT_LAMPORTS` 的目标代币，再卖出全部余额换回 SOL
+async fn run_buy_sell(case: MatrixCase) {
+    println!("\n=== 集成矩阵：{:?} ===", case.dex_type);
+    let Some((pool, mint)) = case.resolve() else {
+        println!(
+            "⏭️  跳过 {:?}：未设置 MATRIX_{}_MINT / MATRIX_{}_POOL",
+            case.dex_type, case.env_name, case.env_name
+        );
+        return;
+    };
+    println!("Pool: {}  Mint: {}", pool, mint);
+
+    let client = create_test_client().await;
+    let payer = client.payer.as_ref().pubkey();
+    let gas_fee_strategy = GasFeeStrategy::new();
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
+
+    let initial_balance = print_token_balance(RPC_URL, &payer, &mint, "Target")
+        .await
+        .expect("Failed to fetch initial token balance");
+
+    // ===== 买入 =====
+    let buy_params = TradeBuyParams {
+        dex_type: case.dex_type.clone(),
+        input_token_type: TradeTokenType::SOL,
+        mint,
+        input_token_amount: BUY_AMOUNT_LAMPORTS,
+        slippage_basis_points: Some(Bps::new(10000)), // 只验证构建与执行，不关心价格
+        recent_blockhash: Some(
+            client.rpc.get_latest_blockhash().await.expect("Failed to get latest blockhash"),
+        ),
+        last_valid_block_height: None,
+        extension_params: DexParamEnum::from_pool(&client.rpc, case.dex_type.clone(), &pool)
+            .await
+            .expect("Failed to load params for buy"),
+        address_lookup_table_account: None,
+        wait_transaction_confirmed: true,
+        create_input_token_ata: true,
+        close_input_token_ata: false,
+        create_mint_ata: true,
+        durable_nonce: None,
+        enable_jito_sandwich_protection: Some(false),
+        fixed_output_token_amount: None,
+        gas_fee_strategy: gas_fee_strategy.clone(),
+        simulate: false,
+        on_transaction_signed: None,
+        callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
+    };
+    let (success, signatures, error) = client
+        .buy(buy_params)
+        .await
+        .unwrap_or_else(|e| panic!("{:?} 买入失败: {}", case.dex_type, e));
+    assert!(success, "{:?} 买入交易应成功: {:?}", case.dex_type, error);
+    println!("✅ 买入成功，签名: {:?}", signatures.first());
+
+    let after_buy = print_token_balance(RPC_URL, &payer, &mint, "Target")
+        .await
+        .expect("Failed to fetch token balance after buy");
+    assert!(after_buy > initial_balance, "{:?} 买入后目标代币余额应增加", case.dex_type);
+
+    // ===== 卖出全部 =====
+    let sell_params = TradeSellParams {
+        dex_type: case.dex_type.clone(),
+        output_token_type: TradeTokenType::SOL,
+        mint,
+        input_token_amount: after_buy,
+        slippage_basis_points: Some(Bps::new(10000)),
+        recent_blockhash: Some(
+            client.rpc.get_latest_blockhash().await.expect("Failed to get latest blockhash"),
+        ),
+        last_valid_block_height: None,
+        with_tip: false,
+        // 买入改变了储备，重新读取参数
+        extension_params: DexParamEnum::from_pool_force(&client.rpc, case.dex_type.clone(), &pool)
+            .await
+            .expect("Failed to load params for sell"),
+        address_lookup_table_account: None,
+        wait_transaction_confirmed: true,
+        create_output_token_ata: true,
+        close_output_token_ata: false,
+        close_mint_token_ata: false,
+        durable_nonce: None,
+        enable_jito_sandwich_protection: Some(false),
+        fixed_output_token_amount: None,
+        gas_fee_strategy,
+        simulate: false,
+        on_transaction_signed: None,
+        callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
+    };
+    let (success, signatures, error) = client
+        .sell(sell_params)
+        .await
+        .unwrap_or_else(|e| panic!("{:?} 卖出失败: {}", case.dex_type, e));
+    assert!(success, "{:?} 卖出交易应成功: {:?}", case.dex_type, error);
+    println!("✅ 卖出成功，签名: {:?}", signatures.first());
+
+    let after_sell = print_token_balance(RPC_URL, &payer, &mint, "Target")
+        .await
+        .expect("Failed to fetch token balance after sell");
+    assert_eq!(after_sell, 0, "{:?} 卖出后目标代币余额应为 0", case.dex_type);
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_pumpfun() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::PumpFun,
+        env_name: "PUMPFUN",
+        pool: None,
+        mint: None,
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_pumpswap() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::PumpSwap,
+        env_name: "PUMPSWAP",
+        pool: Some("539m4mVWt6iduB6W8rDGPMarzNCMesuqY5eUTiiYHAgR"),
+        mint: Some("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn"),
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_bonk() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::Bonk,
+        env_name: "BONK",
+        pool: None,
+        mint: None,
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_raydium_cpmm() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::RaydiumCpmm,
+        env_name: "RAYDIUM_CPMM",
+        pool: Some("BnYsRpYvJpz6biY3hV6U9smChVePCJ6YyupVDfcnXpTp"),
+        mint: Some("8ycz3kctoRb4LFrtoYG2r8tRyUYUeGf5Q16M2TEMp7A"),
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_raydium_amm_v4() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::RaydiumAmmV4,
+        env_name: "RAYDIUM_AMM_V4",
+        pool: Some("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"),
+        mint: Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_raydium_clmm() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::RaydiumClmm,
+        env_name: "RAYDIUM_CLMM",
+        pool: Some("EZVkeboWeXygtq8LMyENHyXdF5wpYrtExRNH9UwB1qYw"),
+        mint: Some("JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN"),
+    })
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(global_dex_cache)]
+async fn test_matrix_meteora_damm_v2() {
+    run_buy_sell(MatrixCase {
+        dex_type: DexType::MeteoraDammV2,
+        env_name: "METEORA_DAMM_V2",
+        pool: Some("7dVri3qjYD3uobSZL3Zth8vSCgU6r6R2nvFsh7uVfDte"),
+        mint: Some("PRVT6TB7uss3FrUd2D9xs2zqDBsa3GbMJMwCQsgmeta"),
+    })
+    .await;
+}
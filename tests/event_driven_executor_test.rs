@@ -6,6 +6,7 @@
 //! - 超时机制正常工作
 //! - 性能相比固定等待有提升
 
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     DexType, TradeBuyParams, TradeTokenType,
     common::GasFeeStrategy,
@@ -85,7 +86,8 @@ async fn test_event_driven_fast_return() {
 
     // 配置 Gas 费策略
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 500_000, 500_000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let recent_blockhash = match client.rpc.get_latest_blockhash().await {
         Ok(hash) => hash,
@@ -103,8 +105,9 @@ async fn test_event_driven_fast_return() {
         input_token_type: TradeTokenType::SOL,
         mint: target_mint,
         input_token_amount: input_amount,
-        slippage_basis_points: Some(1000), // 10% 滑点
+        slippage_basis_points: Some(Bps::new(1000)), // 10% 滑点
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::RaydiumCpmm(cpmm_params),
         address_lookup_table_account: None,
         wait_transaction_confirmed: false, // 🔧 关键：不等待确认
@@ -118,6 +121,15 @@ async fn test_event_driven_fast_return() {
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     // 执行交易并计时
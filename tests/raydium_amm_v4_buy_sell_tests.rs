@@ -1,3 +1,4 @@
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     DexType, TradeBuyParams, TradeSellParams, TradeTokenType,
     common::{GasFeeStrategy, auto_mock_rpc::AutoMockRpcClient},
@@ -80,7 +81,8 @@ async fn test_raydium_amm_v4_buy_sell_complete() {
 
     let input_amount = 20_000_000; // 0.02 SOL
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 500_000, 500_000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let buy_params =
         TradeBuyParams {
@@ -88,12 +90,13 @@ async fn test_raydium_amm_v4_buy_sell_complete() {
             input_token_type: TradeTokenType::SOL,
             mint: usdc_mint,
             input_token_amount: input_amount,
-            slippage_basis_points: Some(1000),
+            slippage_basis_points: Some(Bps::new(1000)),
             recent_blockhash: Some(
                 client.rpc.get_latest_blockhash().await.unwrap_or_else(|e| {
                     panic!("获取最新 blockhash 失败: {}\n  RPC: {}", e, rpc_url)
                 }),
             ),
+            last_valid_block_height: None,
             extension_params: DexParamEnum::RaydiumAmmV4(params),
             address_lookup_table_account: None,
             wait_transaction_confirmed: true,
@@ -107,6 +110,15 @@ async fn test_raydium_amm_v4_buy_sell_complete() {
             simulate: false,
             on_transaction_signed: None,
             callback_execution_mode: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
         };
 
     let (success, signatures, _) = client.buy(buy_params).await.unwrap_or_else(|e| {
@@ -136,12 +148,13 @@ async fn test_raydium_amm_v4_buy_sell_complete() {
             output_token_type: TradeTokenType::SOL,
             mint: usdc_mint,
             input_token_amount: usdc_after_buy,
-            slippage_basis_points: Some(1000),
+            slippage_basis_points: Some(Bps::new(1000)),
             recent_blockhash: Some(
                 client.rpc.get_latest_blockhash().await.unwrap_or_else(|e| {
                     panic!("获取最新 blockhash 失败: {}\n  RPC: {}", e, rpc_url)
                 }),
             ),
+            last_valid_block_height: None,
             with_tip: false,
             extension_params: DexParamEnum::RaydiumAmmV4(params),
             address_lookup_table_account: None,
@@ -156,6 +169,15 @@ async fn test_raydium_amm_v4_buy_sell_complete() {
             simulate: false,
             on_transaction_signed: None,
             callback_execution_mode: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
         };
 
     let (success, signatures, _) = client.sell(sell_params).await.unwrap_or_else(|e| {
@@ -201,7 +223,8 @@ async fn test_raydium_amm_v4_slippage_protection() {
     let rpc_url = "http://127.0.0.1:8899";
 
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 500_000, 500_000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let params = RaydiumAmmV4Params::from_amm_address_by_rpc(&client.rpc, amm_address)
         .await
@@ -214,12 +237,13 @@ async fn test_raydium_amm_v4_slippage_protection() {
             input_token_type: TradeTokenType::SOL,
             mint: usdc_mint,
             input_token_amount: 10_000_000,
-            slippage_basis_points: Some(1), // 0.01% 极小滑点，应该失败
+            slippage_basis_points: Some(Bps::new(1)), // 0.01% 极小滑点，应该失败
             recent_blockhash: Some(
                 client.rpc.get_latest_blockhash().await.unwrap_or_else(|e| {
                     panic!("获取最新 blockhash 失败: {}\n  RPC: {}", e, rpc_url)
                 }),
             ),
+            last_valid_block_height: None,
             extension_params: DexParamEnum::RaydiumAmmV4(params),
             address_lookup_table_account: None,
             wait_transaction_confirmed: true,
@@ -233,6 +257,15 @@ async fn test_raydium_amm_v4_slippage_protection() {
             simulate: false,
             on_transaction_signed: None,
             callback_execution_mode: None,
+            pre_swap_instructions: vec![],
+            post_swap_instructions: vec![],
+            metadata: None,
+            fee_payer: None,
+            use_seed_optimize: None,
+            urgency: None,
+            confirm_timeout: None,
+            confirm_poll_interval: None,
+            cancellation_token: None,
         };
 
     println!("🚀 尝试使用 0.01% 的极小滑点进行交易（预期失败）...");
@@ -1,3 +1,4 @@
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, Lamports, MicroLamports};
 use sol_trade_sdk::{
     DexType, TradeBuyParams, TradeSellParams, TradeTokenType,
     common::GasFeeStrategy,
@@ -76,7 +77,8 @@ async fn test_raydium_clmm_buy_and_sell_jup() {
 
     let gas_fee_strategy_buy = GasFeeStrategy::new();
     // cu_price 设置为 0，只添加 SetComputeUnitLimit 指令
-    gas_fee_strategy_buy.set_global_fee_strategy(1_400_000, 1_400_000, 0, 0, 0.0, 0.0);
+    let fees = FeeSpec::new(ComputeUnits(1_400_000), MicroLamports(0), Lamports::ZERO);
+    gas_fee_strategy_buy.set_global_fees(fees, fees);
 
     let recent_blockhash_buy =
         client.rpc.get_latest_blockhash().await.unwrap_or_else(|e| {
@@ -88,8 +90,9 @@ async fn test_raydium_clmm_buy_and_sell_jup() {
         input_token_type: TradeTokenType::SOL,
         mint: jup_mint,
         input_token_amount: buy_amount_sol,
-        slippage_basis_points: Some(100), // 1% 滑点
+        slippage_basis_points: Some(Bps::new(100)), // 1% 滑点
         recent_blockhash: Some(recent_blockhash_buy),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::RaydiumClmm(clmm_params.clone()),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -103,6 +106,15 @@ async fn test_raydium_clmm_buy_and_sell_jup() {
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success_buy, buy_sigs, error_buy) =
@@ -204,7 +216,8 @@ async fn test_raydium_clmm_buy_and_sell_jup() {
 
     let gas_fee_strategy_sell = GasFeeStrategy::new();
     // 使用较大的 Compute Unit 限制，确保 CLMM swap 有足够的计算资源
-    gas_fee_strategy_sell.set_global_fee_strategy(1_400_000, 1_400_000, 0, 0, 0.0, 0.0);
+    let fees = FeeSpec::new(ComputeUnits(1_400_000), MicroLamports(0), Lamports::ZERO);
+    gas_fee_strategy_sell.set_global_fees(fees, fees);
 
     let recent_blockhash_sell =
         client.rpc.get_latest_blockhash().await.unwrap_or_else(|e| {
@@ -216,8 +229,9 @@ async fn test_raydium_clmm_buy_and_sell_jup() {
         output_token_type: TradeTokenType::SOL,
         mint: jup_mint,
         input_token_amount: sell_amount,
-        slippage_basis_points: Some(1000), // 10% slippage
+        slippage_basis_points: Some(Bps::new(1000)), // 10% slippage
         recent_blockhash: Some(recent_blockhash_sell),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::RaydiumClmm(clmm_params),
         address_lookup_table_account: None,
@@ -232,6 +246,15 @@ async fn test_raydium_clmm_buy_and_sell_jup() {
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success_sell, sell_sigs, error_sell) =
@@ -13,6 +13,7 @@
 //! 运行测试:
 //!     cargo test --test raydium_cpmm_buy_sell_tests -- --nocapture
 
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     DexType, TradeBuyParams, TradeSellParams, TradeTokenType,
     common::{GasFeeStrategy, auto_mock_rpc::AutoMockRpcClient},
@@ -112,7 +113,8 @@ async fn test_raydium_cpmm_buy_sell_complete() {
 
     let input_amount = 20_000_000u64; // 0.02 SOL
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 500_000, 500_000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     let recent_blockhash =
         client.rpc.get_latest_blockhash().await.expect("Failed to get latest blockhash");
@@ -122,8 +124,9 @@ async fn test_raydium_cpmm_buy_sell_complete() {
         input_token_type: TradeTokenType::SOL,
         mint: target_mint,
         input_token_amount: input_amount,
-        slippage_basis_points: Some(10000), // 10% 容忍度，避免因滑点导致测试偶发失败
+        slippage_basis_points: Some(Bps::new(10000)), // 10% 容忍度，避免因滑点导致测试偶发失败
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::RaydiumCpmm(cpmm_params.clone()),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -137,6 +140,15 @@ async fn test_raydium_cpmm_buy_sell_complete() {
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success_buy, buy_sigs, _error_buy) =
@@ -199,8 +211,9 @@ async fn test_raydium_cpmm_buy_sell_complete() {
         output_token_type: TradeTokenType::SOL,
         mint: target_mint,
         input_token_amount: token_after_buy,
-        slippage_basis_points: Some(10000),
+        slippage_basis_points: Some(Bps::new(10000)),
         recent_blockhash: Some(recent_blockhash_sell),
+        last_valid_block_height: None,
         with_tip: false,
         extension_params: DexParamEnum::RaydiumCpmm(cpmm_params_sell),
         address_lookup_table_account: None,
@@ -215,6 +228,15 @@ async fn test_raydium_cpmm_buy_sell_complete() {
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     let (success_sell, sell_sigs, _error_sell) =
@@ -2,6 +2,7 @@
 //!
 //! 提供测试用的辅助函数，包括 SOL 空投和测试客户端创建
 
+use sol_trade_sdk::common::{Bps, ComputeUnits, FeeSpec, MicroLamports, Sol};
 use sol_trade_sdk::{
     SolanaTrade, TradeBuyParams, TradeTokenType,
     common::fast_fn::{
@@ -337,7 +338,7 @@ pub async fn buy_pump_with_sol(
     pool: Pubkey,
     mint: Pubkey,
     sol_amount: u64,
-    slippage_basis_points: Option<u64>,
+    slippage_basis_points: Option<Bps>,
 ) -> Result<
     (bool, Vec<solana_sdk::signature::Signature>, Option<sol_trade_sdk::swqos::common::TradeError>),
     anyhow::Error,
@@ -347,7 +348,7 @@ pub async fn buy_pump_with_sol(
     println!("  - Token Mint: {}", mint);
     println!("  - 购买金额: {} lamports ({:.4} SOL)", sol_amount, sol_amount as f64 / 1e9);
     if let Some(slippage) = slippage_basis_points {
-        println!("  - 滑点容忍: {} bps ({:.1}%)", slippage, slippage as f64 / 100.0);
+        println!("  - 滑点容忍: {} ({:.1}%)", slippage, slippage.to_percent());
     }
 
     // 1. 从 RPC 获取池信息
@@ -370,7 +371,8 @@ pub async fn buy_pump_with_sol(
 
     // 3. 设置 Gas 策略
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 500_000, 500_000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // 4. 构建买入参数
     let buy_params = TradeBuyParams {
@@ -380,6 +382,7 @@ pub async fn buy_pump_with_sol(
         input_token_amount: sol_amount,
         slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(pump_swap_params),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -393,6 +396,15 @@ pub async fn buy_pump_with_sol(
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     // 5. 执行买入交易
@@ -436,7 +448,7 @@ pub async fn buy_pump_with_fixed_output(
     pool: Pubkey,
     mint: Pubkey,
     token_amount: u64,
-    slippage_basis_points: Option<u64>,
+    slippage_basis_points: Option<Bps>,
 ) -> Result<
     (bool, Vec<solana_sdk::signature::Signature>, Option<sol_trade_sdk::swqos::common::TradeError>),
     anyhow::Error,
@@ -446,7 +458,7 @@ pub async fn buy_pump_with_fixed_output(
     println!("  - Token Mint: {}", mint);
     println!("  - 目标代币数量: {}", token_amount);
     if let Some(slippage) = slippage_basis_points {
-        println!("  - 滑点容忍: {} bps ({:.1}%)", slippage, slippage as f64 / 100.0);
+        println!("  - 滑点容忍: {} ({:.1}%)", slippage, slippage.to_percent());
     }
 
     // 1. 从 RPC 获取池信息
@@ -469,7 +481,8 @@ pub async fn buy_pump_with_fixed_output(
 
     // 3. 设置 Gas 策略
     let gas_fee_strategy = GasFeeStrategy::new();
-    gas_fee_strategy.set_global_fee_strategy(150_000, 150_000, 500_000, 500_000, 0.001, 0.001);
+    let fees = FeeSpec::new(ComputeUnits(150_000), MicroLamports(500_000), Sol(0.001));
+    gas_fee_strategy.set_global_fees(fees, fees);
 
     // 4. 构建买入参数（使用 fixed_output_token_amount）
     let buy_params = TradeBuyParams {
@@ -479,6 +492,7 @@ pub async fn buy_pump_with_fixed_output(
         input_token_amount: 0, // 使用 fixed_output_token_amount 时不需要
         slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
+        last_valid_block_height: None,
         extension_params: DexParamEnum::PumpSwap(pump_swap_params),
         address_lookup_table_account: None,
         wait_transaction_confirmed: true,
@@ -492,6 +506,15 @@ pub async fn buy_pump_with_fixed_output(
         simulate: false,
         on_transaction_signed: None,
         callback_execution_mode: None,
+        pre_swap_instructions: vec![],
+        post_swap_instructions: vec![],
+        metadata: None,
+        fee_payer: None,
+        use_seed_optimize: None,
+        urgency: None,
+        confirm_timeout: None,
+        confirm_poll_interval: None,
+        cancellation_token: None,
     };
 
     // 5. 执行买入交易
@@ -7,6 +7,7 @@
 //!
 //! 注意：需要确保 surfpool 正在运行
 
+use sol_trade_sdk::common::Bps;
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
 use std::str::FromStr;
 
@@ -213,7 +214,7 @@ async fn test_trade_with_wsol() {
         .expect("Failed to parse Pump mint address: pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn");
 
     // 使用工具函数购买 0.01 SOL 的 Pump 代币
-    let (success, signatures, error) = buy_pump_with_sol(&client, pool, mint, 10_000_000, Some(500))
+    let (success, signatures, error) = buy_pump_with_sol(&client, pool, mint, 10_000_000, Some(Bps::new(500)))
         .await
         .unwrap_or_else(|e| panic!(
             "Pump 交易执行失败: {}\n  Pool: {}\n  Mint: {}\n  购买金额: 10_000_000 lamports (0.01 SOL)",
@@ -275,7 +276,7 @@ async fn test_trade_with_fixed_output_token_amount() {
 
     // 使用工具函数购买指定数量的代币
     let result =
-        buy_pump_with_fixed_output(&client, pool, mint, target_token_amount, Some(500)).await;
+        buy_pump_with_fixed_output(&client, pool, mint, target_token_amount, Some(Bps::new(500))).await;
 
     // 验证交易结果
     match result {